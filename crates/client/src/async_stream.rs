@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use pod::buf::ArrayVec;
+use protocol::Poll;
+use protocol::buf::RecvBuf;
+use protocol::poll::PollEvent;
+use tokio::io::Interest;
+use tokio::io::unix::AsyncFd;
+
+use crate::Stream;
+use crate::events::StreamEvent;
+
+/// A [`Stream`] which is driven by the tokio runtime.
+///
+/// The stream registers its file descriptors with a [`Poll`] as usual, and the
+/// poll itself is registered with the runtime, which wakes the stream up once
+/// any of them are ready. This lets [`AsyncStream::run`] be awaited alongside
+/// other futures instead of the stream driving its own poll loop.
+///
+/// Processing of client nodes happens on the task which awaits
+/// [`AsyncStream::run`], so this is best suited for clients which don't
+/// process audio with tight deadlines, like control surfaces and monitors.
+///
+/// This requires the `tokio` feature and that the stream is constructed
+/// inside of a runtime with IO enabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{AsyncStream, Stream};
+/// use protocol::{Connection, Properties};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let stream = Stream::new(Connection::open()?, Properties::new())?;
+/// let mut stream = AsyncStream::new(stream)?;
+///
+/// loop {
+///     let event = stream.run().await?;
+///     println!("{event:?}");
+/// }
+/// # }
+/// ```
+pub struct AsyncStream {
+    stream: Stream,
+    poll: AsyncFd<Poll>,
+    recv: RecvBuf,
+}
+
+impl AsyncStream {
+    /// Register a stream with the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// This panics if called outside of a tokio runtime.
+    pub fn new(stream: Stream) -> Result<Self> {
+        let poll = Poll::new().context("Creating poll")?;
+
+        let poll = AsyncFd::with_interest(poll, Interest::READABLE)
+            .context("Registering poll with the runtime")?;
+
+        Ok(Self {
+            stream,
+            poll,
+            recv: RecvBuf::new(),
+        })
+    }
+
+    /// Access the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &Stream {
+        &self.stream
+    }
+
+    /// Access the underlying stream mutably, like to create nodes.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Stream {
+        &mut self.stream
+    }
+
+    /// Deregister the stream from the runtime and return it.
+    ///
+    /// File descriptors of the stream are registered with a poll which is
+    /// dropped, so they have to be added again through
+    /// [`Stream::add_interest`] before the stream is driven by a poll of its
+    /// own.
+    #[inline]
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+
+    /// Run the stream until it produces the next event, see [`Stream::run`].
+    ///
+    /// This is cancel safe, so it can be used in `tokio::select!` together
+    /// with timers which drive deadlines like [`Stream::idle_deadline`].
+    pub async fn run(&mut self) -> Result<StreamEvent> {
+        let mut events = ArrayVec::<PollEvent, 4>::new();
+
+        loop {
+            if let Some(ev) = self.stream.run(self.poll.get_mut(), &mut self.recv)? {
+                return Ok(ev);
+            }
+
+            let mut guard = self.poll.readable_mut().await.context("Waiting for poll")?;
+
+            guard.get_inner_mut().try_poll(&mut events)?;
+
+            // NB: The runtime only reports the poll as ready again once new
+            // events arrive, so readiness is only cleared once it is drained.
+            if events.is_empty() {
+                guard.clear_ready();
+                continue;
+            }
+
+            while let Some(e) = events.pop() {
+                self.stream.drive(&mut self.recv, e)?;
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use pod::{AsSlice, Builder, Error, Id, Object, Slice, Type, Value, Writer};
+use protocol::id;
+
+/// A Bluetooth audio codec advertised by a device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BluetoothCodec {
+    /// The identifier of the codec.
+    pub id: id::BluetoothAudioCodec,
+    /// The user readable label of the codec as advertised by the device, like
+    /// `"AAC"` or `"LDAC"`.
+    pub name: String,
+}
+
+/// The Bluetooth audio codecs supported by a device.
+///
+/// This is populated from the [`id::Param::PROP_INFO`] and
+/// [`id::Param::PROPS`] parameters of a bound device.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BluetoothCodecs {
+    /// The currently active codec, if known.
+    pub current: Option<id::BluetoothAudioCodec>,
+    /// The codecs available for selection.
+    pub available: Vec<BluetoothCodec>,
+}
+
+impl BluetoothCodecs {
+    /// Construct an empty set of codecs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update codec information from a [`id::Param::PROP_INFO`] parameter.
+    ///
+    /// Parameters which do not describe
+    /// [`id::Prop::BLUETOOTH_AUDIO_CODEC`] are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::BluetoothCodecs;
+    /// use pod::{ChoiceType, Id, Type};
+    /// use protocol::id;
+    ///
+    /// let mut pod = pod::array();
+    ///
+    /// // Properties which are not codecs have values of other types.
+    /// pod.as_mut().write_object(id::ObjectType::PROP_INFO, id::Param::PROP_INFO, |obj| {
+    ///     obj.property(id::PropInfo::ID).write(Id(id::Prop::VOLUME))?;
+    ///     obj.property(id::PropInfo::NAME).write("Volume")?;
+    ///     obj.property(id::PropInfo::TYPE).write_choice(ChoiceType::RANGE, Type::FLOAT, |choice| {
+    ///         choice.child().write_sized(1.0f32)?;
+    ///         choice.child().write_sized(0.0f32)?;
+    ///         choice.child().write_sized(10.0f32)?;
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let mut codecs = BluetoothCodecs::new();
+    /// codecs.read_prop_info(pod.as_ref().read_object()?)?;
+    /// assert!(codecs.available.is_empty());
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn read_prop_info(&mut self, obj: Object<impl AsSlice>) -> Result<(), Error> {
+        // NB: Check the identifier of the property first, since the type and
+        // labels of other properties are not codec identifiers.
+        let mut is_codec = false;
+        let mut st = obj.as_ref();
+
+        while !st.is_empty() {
+            let p = st.property()?;
+
+            if p.key::<id::PropInfo>() == id::PropInfo::ID {
+                let Id(prop) = p.value().read_sized::<Id<id::Prop>>()?;
+                is_codec = prop == id::Prop::BLUETOOTH_AUDIO_CODEC;
+                break;
+            }
+        }
+
+        if !is_codec {
+            return Ok(());
+        }
+
+        let mut obj = obj.as_ref();
+        let mut ids = Vec::new();
+        let mut labels = Vec::new();
+
+        while !obj.is_empty() {
+            let p = obj.property()?;
+
+            match p.key::<id::PropInfo>() {
+                id::PropInfo::TYPE => {
+                    read_type(p.value(), &mut ids)?;
+                }
+                id::PropInfo::LABELS => {
+                    let mut st = p.value().read_struct()?;
+
+                    while !st.is_empty() {
+                        let Id(codec) = st.field()?.read_sized::<Id<id::BluetoothAudioCodec>>()?;
+                        let label = st.field()?.read_unsized::<str>()?;
+                        labels.push((codec, label));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.available.clear();
+
+        for codec in ids {
+            let name = match labels.iter().find(|(id, _)| *id == codec) {
+                Some((_, label)) => String::from(*label),
+                None => alloc::format!("{codec:?}"),
+            };
+
+            self.available.push(BluetoothCodec { id: codec, name });
+        }
+
+        Ok(())
+    }
+
+    /// Update the current codec from a [`id::Param::PROPS`] parameter.
+    pub fn read_props(&mut self, obj: Object<impl AsSlice>) -> Result<(), Error> {
+        let mut obj = obj.as_ref();
+
+        while !obj.is_empty() {
+            let p = obj.property()?;
+
+            if p.key::<id::Prop>() == id::Prop::BLUETOOTH_AUDIO_CODEC {
+                let Id(codec) = p.value().read_sized::<Id<id::BluetoothAudioCodec>>()?;
+                self.current = Some(codec);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find an available codec by name, matched case insensitively.
+    pub fn by_name(&self, name: &str) -> Option<&BluetoothCodec> {
+        self.available
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Find an available codec by its identifier.
+    pub fn get(&self, id: id::BluetoothAudioCodec) -> Option<&BluetoothCodec> {
+        self.available.iter().find(|c| c.id == id)
+    }
+
+    /// Write a [`id::Param::PROPS`] parameter selecting the given codec.
+    pub fn write_select(
+        pod: Builder<impl Writer>,
+        codec: id::BluetoothAudioCodec,
+    ) -> Result<(), Error> {
+        pod.write_object(id::ObjectType::PROPS, id::Param::PROPS, |obj| {
+            obj.property(id::Prop::BLUETOOTH_AUDIO_CODEC)
+                .write(Id(codec))?;
+            Ok(())
+        })
+    }
+}
+
+/// Read the values of a property type, which is either a plain identifier or
+/// a choice of identifiers.
+///
+/// The first value of an enum choice is the default which is repeated among
+/// the alternatives, so duplicates are skipped.
+fn read_type(value: Value<Slice<'_>>, ids: &mut Vec<id::BluetoothAudioCodec>) -> Result<(), Error> {
+    if value.ty() == Type::ID {
+        let Id(codec) = value.read_sized::<Id<id::BluetoothAudioCodec>>()?;
+        ids.push(codec);
+        return Ok(());
+    }
+
+    let mut choice = value.read_choice()?;
+
+    while let Some(value) = choice.next() {
+        let Id(codec) = value.read_sized::<Id<id::BluetoothAudioCodec>>()?;
+
+        if !ids.contains(&codec) {
+            ids.push(codec);
+        }
+    }
+
+    Ok(())
+}
@@ -1,18 +1,30 @@
+//! Buffers shared with the server and views of their contents.
+
+use core::iter;
 use core::mem::MaybeUninit;
+use core::slice;
 
 use alloc::vec::Vec;
 
+use anyhow::{Result, bail, ensure};
 use bittle::BitsMut;
+use pod::buf::ArrayBuf;
+use pod::{Builder, ControlType, Pod};
 use protocol::consts;
 use protocol::consts::Direction;
 use protocol::ffi;
 use protocol::flags;
 use protocol::id;
+use protocol::object::AudioFormat;
 
 use crate::MixId;
 use crate::PortId;
+use crate::events::{MidiEvent, MidiMessage};
 use crate::memory::Region;
 
+/// The capacity in bytes of sequences written by [`Data::write_midi`].
+const MIDI_CAPACITY: usize = 8192;
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Meta {
@@ -30,20 +42,158 @@ pub struct Data {
 }
 
 impl Data {
+    /// Construct a new data block over the given data and chunk regions.
+    ///
+    /// # Safety
+    ///
+    /// Safe methods of the data block read and write through its regions, so
+    /// the caller must ensure that both `region` and `chunk` point to memory
+    /// which is valid for reads and writes of their size for as long as the
+    /// data block is alive, and which is not accessed through any other
+    /// reference while the data block is in use.
+    pub unsafe fn new(
+        ty: id::DataType,
+        region: Region<[MaybeUninit<u8>]>,
+        flags: flags::DataFlag,
+        chunk: Region<ffi::Chunk>,
+    ) -> Self {
+        Self {
+            ty,
+            region,
+            flags,
+            chunk,
+        }
+    }
+
     /// Read the valid region of the data according to the associated chunk.
     ///
+    /// The offset of the chunk is taken modulo the size of the data region,
+    /// and its size is clamped to the remainder of the region. Returns `None`
+    /// if the data region is empty.
+    ///
     /// # Safety
     ///
     /// The caller must ensure that the region is valid.
     pub unsafe fn valid_region(&self) -> Option<Region<[u8]>> {
         unsafe {
-            let chunk = self.chunk.as_ref();
-            let offset = chunk.offset as usize % self.region.len();
-            let size = (chunk.size as usize - offset).min(self.region.len());
+            let chunk = self.chunk.read();
+            let len = self.region.len();
+            let offset = (chunk.offset as usize).checked_rem(len)?;
+            let size = (chunk.size as usize).min(len.checked_sub(offset)?);
             Some(self.region.slice(offset, size)?.cast_array_unchecked())
         }
     }
 
+    /// Iterate over the MIDI events in the control sequence of the data.
+    ///
+    /// Controls which are not MIDI, and MIDI messages which are not supported
+    /// by [`MidiMessage`] are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use core::ptr::NonNull;
+    ///
+    /// use client::buffer::Data;
+    /// use client::events::{MidiEvent, MidiMessage};
+    /// use client::memory::Region;
+    /// use protocol::{ffi, flags, id};
+    ///
+    /// let events = [
+    ///     MidiEvent::new(0, MidiMessage::NoteOn { channel: 0, note: 60, velocity: 127 }),
+    ///     MidiEvent::new(128, MidiMessage::ControlChange { channel: 2, controller: 7, value: 64 }),
+    /// ];
+    ///
+    /// let mut memory = [0u64; 128];
+    /// let mut chunk = ffi::Chunk { offset: 0, size: 0, stride: 0, flags: flags::ChunkFlags::NONE };
+    ///
+    /// let region = Region::from_slice(0, &mut memory[..]).cast_array::<MaybeUninit<u8>>()?;
+    /// let chunk = Region::new(0, size_of::<ffi::Chunk>(), NonNull::from(&mut chunk));
+    /// // SAFETY: Both regions point to locals which outlive the data block.
+    /// let mut data = unsafe {
+    ///     Data::new(id::DataType::MEM_PTR, region, flags::DataFlag::READWRITE, chunk)
+    /// };
+    ///
+    /// data.write_midi(&events)?;
+    /// assert!(data.midi_events().eq(events));
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn midi_events(&self) -> impl Iterator<Item = MidiEvent> + '_ {
+        let mut seq = Pod::new(pod::buf::slice(self.valid_bytes()))
+            .read_sequence()
+            .ok();
+
+        iter::from_fn(move || {
+            let seq = seq.as_mut()?;
+
+            while !seq.is_empty() {
+                let control = seq.control().ok()?;
+
+                if control.control_type() != ControlType::MIDI {
+                    continue;
+                }
+
+                let offset = control.offset();
+
+                let Ok(bytes) = control.value().read_unsized::<[u8]>() else {
+                    continue;
+                };
+
+                if let Some(message) = MidiMessage::parse(bytes) {
+                    return Some(MidiEvent { offset, message });
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Write MIDI events as a control sequence to the data and write a chunk
+    /// covering it.
+    ///
+    /// Events should be ordered by their offset.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the sequence doesn't fit in the data region.
+    pub fn write_midi(&mut self, events: &[MidiEvent]) -> Result<()> {
+        let mut pod = Builder::new(ArrayBuf::<MIDI_CAPACITY>::new());
+
+        pod.as_mut().write_sequence(|seq| {
+            for event in events {
+                seq.control_at(event.offset)
+                    .midi(&event.message.to_bytes())?;
+            }
+
+            Ok(())
+        })?;
+
+        let bytes = pod.as_buf().as_bytes();
+
+        ensure!(
+            bytes.len() <= self.region.len(),
+            "MIDI sequence of {} bytes does not fit in data region of {} bytes",
+            bytes.len(),
+            self.region.len()
+        );
+
+        self.write_bytes(bytes, 1);
+        Ok(())
+    }
+
+    /// The valid bytes of the data according to the associated chunk.
+    fn valid_bytes(&self) -> &[u8] {
+        // SAFETY: The regions of a data block are valid per the contract of
+        // `Data::new`.
+        let Some(region) = (unsafe { self.valid_region() }) else {
+            return &[];
+        };
+
+        // SAFETY: The region is owned by the data block, which is borrowed.
+        unsafe { slice::from_raw_parts(region.as_ptr(), region.len()) }
+    }
+
     /// Return the uninitialized region of the data.
     pub fn uninit_region(&self) -> Region<[MaybeUninit<u8>]> {
         self.region.clone()
@@ -51,11 +201,76 @@ impl Data {
 
     /// Write a complete chunk to the data region.
     pub fn write_chunk(&mut self, chunk: ffi::Chunk) {
-        /// SAFETY: We assume the chunk region is valid through construction.
+        // SAFETY: The chunk region is valid per the contract of `Data::new`.
         unsafe {
             self.chunk.write(chunk);
         }
     }
+
+    /// Copy the given bytes to the start of the data region and write a chunk
+    /// covering them with the given `stride`.
+    ///
+    /// Returns the number of bytes copied, which is limited by the size of the
+    /// data region.
+    pub fn write_bytes(&mut self, bytes: &[u8], stride: i32) -> usize {
+        let len = bytes.len().min(self.region.len());
+
+        // SAFETY: The region is valid per the contract of `Data::new`, and `len` is
+        // bounded by both the source and the destination.
+        unsafe {
+            self.region
+                .as_mut_ptr()
+                .cast::<u8>()
+                .copy_from_nonoverlapping(bytes.as_ptr(), len);
+        }
+
+        self.write_chunk(ffi::Chunk {
+            offset: 0,
+            size: u32::try_from(len).unwrap_or(u32::MAX),
+            stride,
+            flags: flags::ChunkFlags::NONE,
+        });
+
+        len
+    }
+
+    /// Copy the valid region of another data block into this one, including
+    /// the stride and flags of its chunk.
+    ///
+    /// This is used to fan out data which has already been produced for one
+    /// output port into other ports carrying the same data, like a port and
+    /// its monitor, so that the processing only has to be done once.
+    ///
+    /// Returns the number of bytes copied.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the region of `source` is valid, see
+    /// [`Data::valid_region`].
+    pub unsafe fn copy_from(&mut self, source: &Data) -> usize {
+        unsafe {
+            let Some(region) = source.valid_region() else {
+                return 0;
+            };
+
+            let chunk = source.chunk.read();
+            let len = region.len().min(self.region.len());
+
+            self.region
+                .as_mut_ptr()
+                .cast::<u8>()
+                .copy_from_nonoverlapping(region.as_ptr(), len);
+
+            self.write_chunk(ffi::Chunk {
+                offset: 0,
+                size: u32::try_from(len).unwrap_or(u32::MAX),
+                stride: chunk.stride,
+                flags: chunk.flags,
+            });
+
+            len
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -68,6 +283,15 @@ pub struct Buffer {
     pub datas: Vec<Data>,
 }
 
+impl Buffer {
+    /// Access the samples of the buffer according to the negotiated audio
+    /// format, see [`AudioView`].
+    #[inline]
+    pub fn audio(&self, format: &AudioFormat) -> AudioView<'_> {
+        AudioView::new(format, &self.datas)
+    }
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Buffers {
@@ -79,3 +303,199 @@ pub struct Buffers {
     /// The buffers which are available in this set.
     pub available: u128,
 }
+
+/// A view of the samples in the data blocks of a buffer according to the
+/// negotiated audio format.
+///
+/// Planar formats like [`F32P`] store each channel in a separate data block,
+/// while interleaved formats like [`S16`] store all channels in the first data
+/// block. Samples are accessed through the valid region of each data block as
+/// described by its chunk, and accessing them with a sample type that doesn't
+/// match the format, or a channel which doesn't exist, is an error.
+///
+/// [`F32P`]: id::AudioFormat::F32P
+/// [`S16`]: id::AudioFormat::S16
+///
+/// # Examples
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use core::ptr::NonNull;
+///
+/// use client::buffer::{AudioView, Data};
+/// use client::memory::Region;
+/// use protocol::{ffi, flags, id};
+/// use protocol::object::AudioFormat;
+///
+/// let mut samples = [0.0f32, 0.5, 1.0, -0.5, -1.0, 0.25];
+/// let bytes = size_of_val(&samples);
+///
+/// let mut chunk = ffi::Chunk {
+///     offset: 0,
+///     size: bytes as u32,
+///     stride: 8,
+///     flags: flags::ChunkFlags::NONE,
+/// };
+///
+/// let region = Region::from_slice(0, &mut samples[..]).cast_array::<MaybeUninit<u8>>()?;
+/// let chunk = Region::new(0, size_of::<ffi::Chunk>(), NonNull::from(&mut chunk));
+///
+/// // SAFETY: Both regions point to locals which outlive the data block.
+/// let datas = [unsafe { Data::new(id::DataType::MEM_PTR, region, flags::DataFlag::NONE, chunk) }];
+///
+/// let format = AudioFormat {
+///     media_type: id::MediaType::AUDIO,
+///     media_sub_type: id::MediaSubType::RAW,
+///     format: id::AudioFormat::F32,
+///     channels: 2,
+///     rate: 48000,
+/// };
+///
+/// let view = AudioView::new(&format, &datas);
+/// assert_eq!(view.frames(), 3);
+/// assert_eq!(view.as_interleaved_f32()?, &[0.0, 0.5, 1.0, -0.5, -1.0, 0.25]);
+/// assert!(view.as_interleaved_i16().is_err());
+/// assert!(view.as_planar_f32(0).is_err());
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AudioView<'a> {
+    format: id::AudioFormat,
+    channels: usize,
+    datas: &'a [Data],
+}
+
+impl<'a> AudioView<'a> {
+    /// Construct a view of the given data blocks in the given format.
+    #[inline]
+    pub fn new(format: &AudioFormat, datas: &'a [Data]) -> Self {
+        Self {
+            format: format.format,
+            channels: format.channels as usize,
+            datas,
+        }
+    }
+
+    /// The sample format of the view.
+    #[inline]
+    pub fn format(&self) -> id::AudioFormat {
+        self.format
+    }
+
+    /// The number of channels of the view.
+    #[inline]
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Test if the view is of a planar format.
+    #[inline]
+    pub fn is_planar(&self) -> bool {
+        self.format.is_planar()
+    }
+
+    /// The number of frames in the view, which is the number of samples per
+    /// channel.
+    ///
+    /// This is zero if the format is not supported by any of the accessors of
+    /// the view.
+    pub fn frames(&self) -> usize {
+        let size = match self.format {
+            id::AudioFormat::F32 | id::AudioFormat::F32P => size_of::<f32>(),
+            id::AudioFormat::S32 | id::AudioFormat::S32P => size_of::<i32>(),
+            id::AudioFormat::S16 | id::AudioFormat::S16P => size_of::<i16>(),
+            _ => return 0,
+        };
+
+        let Some(data) = self.datas.first() else {
+            return 0;
+        };
+
+        // SAFETY: The regions of a data block are valid per the contract of
+        // `Data::new`.
+        let Some(region) = (unsafe { data.valid_region() }) else {
+            return 0;
+        };
+
+        let samples = region.len() / size;
+
+        if self.is_planar() {
+            samples
+        } else {
+            samples.checked_div(self.channels).unwrap_or_default()
+        }
+    }
+
+    /// Access the samples of a channel of a planar 32-bit float view.
+    pub fn as_planar_f32(&self, channel: usize) -> Result<&'a [f32]> {
+        self.planar(id::AudioFormat::F32P, channel)
+    }
+
+    /// Access the samples of a channel of a planar 32-bit signed integer view.
+    pub fn as_planar_i32(&self, channel: usize) -> Result<&'a [i32]> {
+        self.planar(id::AudioFormat::S32P, channel)
+    }
+
+    /// Access the samples of a channel of a planar 16-bit signed integer view.
+    pub fn as_planar_i16(&self, channel: usize) -> Result<&'a [i16]> {
+        self.planar(id::AudioFormat::S16P, channel)
+    }
+
+    /// Access the frames of an interleaved 32-bit float view.
+    pub fn as_interleaved_f32(&self) -> Result<&'a [f32]> {
+        self.interleaved(id::AudioFormat::F32)
+    }
+
+    /// Access the frames of an interleaved 32-bit signed integer view.
+    pub fn as_interleaved_i32(&self) -> Result<&'a [i32]> {
+        self.interleaved(id::AudioFormat::S32)
+    }
+
+    /// Access the frames of an interleaved 16-bit signed integer view.
+    pub fn as_interleaved_i16(&self) -> Result<&'a [i16]> {
+        self.interleaved(id::AudioFormat::S16)
+    }
+
+    fn planar<T>(&self, format: id::AudioFormat, channel: usize) -> Result<&'a [T]> {
+        ensure!(
+            channel < self.channels,
+            "Channel {channel} out of bounds for {} channels",
+            self.channels
+        );
+
+        self.samples(format, channel)
+    }
+
+    fn interleaved<T>(&self, format: id::AudioFormat) -> Result<&'a [T]> {
+        let samples = self.samples::<T>(format, 0)?;
+
+        // NB: Partial frames at the end of the data block are ignored.
+        let len = samples.len() - samples.len().checked_rem(self.channels).unwrap_or_default();
+        Ok(&samples[..len])
+    }
+
+    fn samples<T>(&self, format: id::AudioFormat, index: usize) -> Result<&'a [T]> {
+        ensure!(
+            self.format == format,
+            "Cannot access samples of format {:?} as {:?}",
+            self.format,
+            format
+        );
+
+        let Some(data) = self.datas.get(index) else {
+            bail!("Missing data block {index} in buffer");
+        };
+
+        // SAFETY: The regions of a data block are valid per the contract of
+        // `Data::new`.
+        let Some(region) = (unsafe { data.valid_region() }) else {
+            return Ok(&[]);
+        };
+
+        let region = region.cast_array::<T>()?;
+
+        // SAFETY: The region is owned by the data block, which is borrowed
+        // for `'a`.
+        unsafe { Ok(slice::from_raw_parts(region.as_ptr(), region.len())) }
+    }
+}
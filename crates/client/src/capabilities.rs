@@ -0,0 +1,80 @@
+use alloc::string::String;
+
+use protocol::{Properties, prop};
+
+/// The kind of access a client has been granted by the server.
+///
+/// This corresponds to the `pipewire.access` property which the server sets in
+/// the client info.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Access {
+    /// Access has not been reported by the server yet.
+    Unknown,
+    /// The client has unrestricted access to the graph.
+    Unrestricted,
+    /// The client has restricted access and only sees the objects it has been
+    /// granted permissions to.
+    Restricted,
+    /// The client is running in a flatpak sandbox.
+    Flatpak,
+    /// The client is connected through the portal.
+    Portal,
+    /// Some other access mode.
+    Other(String),
+}
+
+impl Access {
+    /// Parse access from client properties.
+    pub fn from_props(props: &Properties) -> Self {
+        let Some(access) = props
+            .get(prop::ACCESS)
+            .or_else(|| props.get(prop::CLIENT_ACCESS))
+        else {
+            return Self::Unknown;
+        };
+
+        match access {
+            "unrestricted" | "allowed" => Self::Unrestricted,
+            "restricted" => Self::Restricted,
+            "flatpak" => Self::Flatpak,
+            "portal" => Self::Portal,
+            other => Self::Other(String::from(other)),
+        }
+    }
+
+    /// Test if access is restricted, in which case objects might be hidden
+    /// from the client or only be partially accessible.
+    pub fn is_restricted(&self) -> bool {
+        !matches!(self, Self::Unknown | Self::Unrestricted)
+    }
+}
+
+/// Describes which operations are permitted for a stream.
+///
+/// This is intended for UI layers to hide actions which are not available, like
+/// when running inside of a sandbox. Operations on individual objects are
+/// further governed by the permissions of the object, see
+/// [`Stream::permissions`].
+///
+/// [`Stream::permissions`]: crate::Stream::permissions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// The access granted to the client.
+    pub access: Access,
+    /// The application id reported by the portal, if any.
+    pub app_id: Option<String>,
+    /// Whether global objects can be bound, which is required to subscribe to
+    /// parameters and to control devices.
+    ///
+    /// This is derived from the permissions reported by the server, and is
+    /// only set if the client can read at least one node, device or metadata
+    /// object.
+    pub bind: bool,
+    /// Whether client nodes can be created.
+    pub create_client_node: bool,
+    /// Whether the whole graph can be observed. Restricted clients only see
+    /// the objects they have been granted access to.
+    pub observe: bool,
+}
@@ -0,0 +1,1200 @@
+use core::fmt;
+use core::mem;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use anyhow::{Result, bail, ensure};
+use pod::builder::StructBuilder;
+use pod::{AsSlice, BuildPod, Builder, Error, Object, Slice, Struct, Type, Writer};
+use protocol::id;
+
+use crate::{BlockId, Stats};
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the preset format written by
+/// [`ChannelStrip::write_preset`].
+const PRESET_VERSION: i32 = 1;
+
+/// The default length of a bypass crossfade in seconds.
+const DEFAULT_CROSSFADE: f32 = 0.01;
+
+/// The number of samples a strip reserves space for processing at a time,
+/// which is the default maximum quantum of the server.
+const DEFAULT_MAX_BLOCK: usize = 8192;
+
+/// The floor of the level detector of the dynamics block.
+const MIN_LEVEL: f32 = 1e-9;
+
+/// A processing block in a [`ChannelStrip`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StripBlock {
+    /// Gain staging.
+    Gain,
+    /// A three band equalizer, with a low shelf, a peaking mid band and a
+    /// high shelf.
+    Eq,
+    /// A downward compressor.
+    Dynamics,
+    /// Post processing sends.
+    Sends,
+}
+
+impl StripBlock {
+    /// All blocks in their default processing order.
+    pub const ALL: [StripBlock; 4] = [
+        StripBlock::Gain,
+        StripBlock::Eq,
+        StripBlock::Dynamics,
+        StripBlock::Sends,
+    ];
+
+    /// The name of the block, which is used as its parameter namespace.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            StripBlock::Gain => "gain",
+            StripBlock::Eq => "eq",
+            StripBlock::Dynamics => "dynamics",
+            StripBlock::Sends => "sends",
+        }
+    }
+
+    /// Look up a block by its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.name() == name)
+    }
+
+    #[inline]
+    const fn label(&self) -> &'static str {
+        match self {
+            StripBlock::Gain => "strip.gain",
+            StripBlock::Eq => "strip.eq",
+            StripBlock::Dynamics => "strip.dynamics",
+            StripBlock::Sends => "strip.sends",
+        }
+    }
+
+    #[inline]
+    const fn index(&self) -> usize {
+        match self {
+            StripBlock::Gain => 0,
+            StripBlock::Eq => 1,
+            StripBlock::Dynamics => 2,
+            StripBlock::Sends => 3,
+        }
+    }
+}
+
+/// The processing blocks of a [`ChannelStrip`] registered with [`Stats`],
+/// see [`ChannelStrip::process_timed`].
+#[derive(Debug, Clone, Copy)]
+pub struct StripTimers {
+    blocks: [BlockId; 4],
+}
+
+impl StripTimers {
+    /// Register the processing blocks of a channel strip, labelled like
+    /// `strip.eq`.
+    ///
+    /// Every strip timed with the same statistics shares these blocks.
+    pub fn register(stats: &mut Stats) -> Self {
+        Self {
+            blocks: StripBlock::ALL.map(|block| stats.register_block(block.label())),
+        }
+    }
+}
+
+impl fmt::Display for StripBlock {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+/// Parameters of a strip, the value range they accept and their default.
+///
+/// Send levels are not included, since they are indexed by the send.
+const PARAMS: &[(&str, f32, f32, f32)] = &[
+    ("gain.level", -96.0, 24.0, 0.0),
+    ("eq.low.freq", 10.0, 20000.0, 100.0),
+    ("eq.low.gain", -24.0, 24.0, 0.0),
+    ("eq.mid.freq", 10.0, 20000.0, 1000.0),
+    ("eq.mid.gain", -24.0, 24.0, 0.0),
+    ("eq.mid.q", 0.1, 18.0, 0.707),
+    ("eq.high.freq", 10.0, 20000.0, 8000.0),
+    ("eq.high.gain", -24.0, 24.0, 0.0),
+    ("dynamics.threshold", -96.0, 0.0, -20.0),
+    ("dynamics.ratio", 1.0, 100.0, 4.0),
+    ("dynamics.attack", 0.01, 1000.0, 10.0),
+    ("dynamics.release", 1.0, 5000.0, 100.0),
+    ("dynamics.makeup", -24.0, 24.0, 0.0),
+];
+
+/// The range and default of the level of a send.
+const SEND_LEVEL: (f32, f32, f32) = (-96.0, 24.0, 0.0);
+
+#[derive(Debug, Clone, Copy)]
+struct Bypass {
+    bypassed: bool,
+    /// How much of the processed signal is currently mixed in, where `0.0`
+    /// is fully bypassed.
+    mix: f32,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+/// The state of a [`Biquad`], which is kept separate so that coefficients
+/// can be replaced without resetting the filter.
+#[derive(Debug, Default, Clone, Copy)]
+struct BiquadState {
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// Process a single sample, where `offset` is added to the input to keep
+    /// the state of the filter from decaying into denormal numbers.
+    #[inline]
+    fn process(&self, state: &mut BiquadState, x: f32, offset: f32) -> f32 {
+        let x = x + offset;
+        let y = self.b0 * x + state.z1;
+        state.z1 = self.b1 * x - self.a1 * y + state.z2;
+        state.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    fn set(&mut self, b: [f32; 3], a: [f32; 3]) {
+        self.b0 = b[0] / a[0];
+        self.b1 = b[1] / a[0];
+        self.b2 = b[2] / a[0];
+        self.a1 = a[1] / a[0];
+        self.a2 = a[2] / a[0];
+    }
+
+    fn low_shelf(&mut self, w0: f32, gain: f32) {
+        let a = 10f32.powf(gain / 40.0);
+        let (sin, cos) = w0.sin_cos();
+        let s = a.sqrt() * sin * core::f32::consts::SQRT_2;
+
+        self.set(
+            [
+                a * ((a + 1.0) - (a - 1.0) * cos + s),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - s),
+            ],
+            [
+                (a + 1.0) + (a - 1.0) * cos + s,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - s,
+            ],
+        );
+    }
+
+    fn high_shelf(&mut self, w0: f32, gain: f32) {
+        let a = 10f32.powf(gain / 40.0);
+        let (sin, cos) = w0.sin_cos();
+        let s = a.sqrt() * sin * core::f32::consts::SQRT_2;
+
+        self.set(
+            [
+                a * ((a + 1.0) + (a - 1.0) * cos + s),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - s),
+            ],
+            [
+                (a + 1.0) - (a - 1.0) * cos + s,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - s,
+            ],
+        );
+    }
+
+    fn peaking(&mut self, w0: f32, gain: f32, q: f32) {
+        let a = 10f32.powf(gain / 40.0);
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+
+        self.set(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        );
+    }
+}
+
+/// Coefficients of a [`ChannelStrip`] derived from its parameters and sample
+/// rate.
+///
+/// These are calculated with [`ChannelStrip::coefficients`], which can be
+/// done ahead of time when the sample rate changes, and installed with
+/// [`ChannelStrip::swap_coefficients`].
+#[derive(Debug, Clone)]
+pub struct StripCoefficients {
+    rate: u32,
+    crossfade: u32,
+    eq: [Biquad; 3],
+    attack: f32,
+    release: f32,
+    generation: u64,
+}
+
+impl StripCoefficients {
+    fn compute(values: &[f32; PARAMS.len()], crossfade: f32, rate: u32, generation: u64) -> Self {
+        let rate = rate.max(1);
+        let value = |path| value(values, path);
+
+        let r = rate as f32;
+        let w0 = |freq: f32| core::f32::consts::TAU * freq.min(r * 0.49) / r;
+        let coeff = |ms: f32| (-1000.0 / (ms * r)).exp();
+
+        let mut eq = [Biquad::default(); 3];
+        eq[0].low_shelf(w0(value("eq.low.freq")), value("eq.low.gain"));
+        eq[1].peaking(
+            w0(value("eq.mid.freq")),
+            value("eq.mid.gain"),
+            value("eq.mid.q"),
+        );
+        eq[2].high_shelf(w0(value("eq.high.freq")), value("eq.high.gain"));
+
+        Self {
+            rate,
+            crossfade: ((crossfade.max(0.0) * r) as u32).max(1),
+            eq,
+            attack: coeff(value("dynamics.attack")),
+            release: coeff(value("dynamics.release")),
+            generation,
+        }
+    }
+
+    /// The sample rate the coefficients were calculated for.
+    #[inline]
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+}
+
+/// A fixed delay line.
+#[derive(Debug, Default, Clone)]
+struct Delay {
+    line: Vec<f32>,
+    pos: usize,
+}
+
+impl Delay {
+    fn set(&mut self, len: usize) {
+        self.line.clear();
+        self.line.resize(len, 0.0);
+        self.pos = 0;
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        if self.line.is_empty() {
+            return;
+        }
+
+        for sample in samples {
+            mem::swap(sample, &mut self.line[self.pos]);
+            self.pos = (self.pos + 1) % self.line.len();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Send {
+    level: f32,
+    buf: Vec<f32>,
+}
+
+/// A channel strip composing gain, EQ, dynamics and sends in a reorderable
+/// chain of blocks.
+///
+/// Each block can be bypassed, in which case it is crossfaded out over a short
+/// period to avoid clicks. Reordering blocks takes effect immediately, so to
+/// avoid discontinuities the affected blocks should be bypassed first.
+///
+/// The dynamics block can be keyed by an external sidechain through
+/// [`ChannelStrip::process_with_sidechain`].
+///
+/// Parameters are addressed through nested namespaces, like `gain.level`,
+/// `eq.mid.q` or `sends.0.level`. Levels and gains are in decibels,
+/// frequencies in hertz and times in milliseconds:
+///
+/// * `gain.level` - The gain applied by the gain block.
+/// * `eq.low.freq`, `eq.low.gain` - The low shelf of the equalizer.
+/// * `eq.mid.freq`, `eq.mid.gain`, `eq.mid.q` - The peaking mid band of the
+///   equalizer.
+/// * `eq.high.freq`, `eq.high.gain` - The high shelf of the equalizer.
+/// * `dynamics.threshold`, `dynamics.ratio`, `dynamics.attack`,
+///   `dynamics.release`, `dynamics.makeup` - The compressor.
+/// * `sends.<n>.level` - The level of send `n`.
+///
+/// # Examples
+///
+/// ```
+/// use client::{ChannelStrip, StripBlock};
+///
+/// let mut strip = ChannelStrip::new(48000);
+/// strip.set_sends(1);
+/// strip.set_param("gain.level", -6.0)?;
+/// strip.set_param("eq.mid.gain", 3.0)?;
+///
+/// // Run the compressor before the equalizer.
+/// strip.move_block(StripBlock::Dynamics, 1);
+/// assert!(strip.order().eq([
+///     StripBlock::Gain,
+///     StripBlock::Dynamics,
+///     StripBlock::Eq,
+///     StripBlock::Sends,
+/// ]));
+///
+/// let mut samples = [0.5f32; 128];
+/// strip.process(&mut samples);
+/// assert_eq!(strip.send(0).len(), 128);
+///
+/// // Save the strip as a preset and restore it somewhere else.
+/// let mut pod = pod::dynamic();
+/// strip.write_preset(pod.as_mut())?;
+///
+/// let mut other = ChannelStrip::new(48000);
+/// other.read_preset(pod.as_ref().read_struct()?)?;
+/// assert_eq!(other.param("gain.level"), Some(-6.0));
+/// assert!(other.order().eq(strip.order()));
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+pub struct ChannelStrip {
+    coefficients: StripCoefficients,
+    crossfade: f32,
+    generation: u64,
+    order: [StripBlock; 4],
+    bypass: [Bypass; 4],
+    values: [f32; PARAMS.len()],
+    gain: f32,
+    eq: [BiquadState; 3],
+    dither: f32,
+    envelope: f32,
+    sends: Vec<Send>,
+    dry: Vec<f32>,
+    ramp: Vec<f32>,
+    alignment: i64,
+    program_delay: Delay,
+    key_delay: Delay,
+    key: Vec<f32>,
+    keyed: bool,
+    overloaded: Option<StripBlock>,
+    max_block: usize,
+}
+
+impl ChannelStrip {
+    /// Construct a new channel strip processing audio at the given sample
+    /// rate, with all blocks in their default order and every parameter at its
+    /// default.
+    ///
+    /// Space is reserved to process up to 8192 samples at a time without
+    /// allocating, see [`ChannelStrip::reserve`].
+    pub fn new(rate: u32) -> Self {
+        let mut values = [0.0; PARAMS.len()];
+
+        for (value, &(_, _, _, default)) in values.iter_mut().zip(PARAMS) {
+            *value = default;
+        }
+
+        let mut strip = Self {
+            coefficients: StripCoefficients::compute(&values, DEFAULT_CROSSFADE, rate, 0),
+            crossfade: DEFAULT_CROSSFADE,
+            generation: 0,
+            order: StripBlock::ALL,
+            bypass: [Bypass {
+                bypassed: false,
+                mix: 1.0,
+            }; 4],
+            gain: db_to_linear(value(&values, "gain.level")),
+            values,
+            eq: [BiquadState::default(); 3],
+            dither: 0.0,
+            envelope: 0.0,
+            sends: Vec::new(),
+            dry: Vec::new(),
+            ramp: Vec::new(),
+            alignment: 0,
+            program_delay: Delay::default(),
+            key_delay: Delay::default(),
+            key: Vec::new(),
+            keyed: false,
+            overloaded: None,
+            max_block: 0,
+        };
+
+        strip.reserve(DEFAULT_MAX_BLOCK);
+        strip
+    }
+
+    /// Get the sample rate the strip is processing at.
+    #[inline]
+    pub fn rate(&self) -> u32 {
+        self.coefficients.rate
+    }
+
+    /// Set the sample rate of the strip, recalculating all coefficients.
+    ///
+    /// To avoid calculating coefficients while processing, see
+    /// [`ChannelStrip::coefficients`].
+    pub fn set_rate(&mut self, rate: u32) {
+        self.update(rate);
+    }
+
+    /// Calculate coefficients for the current parameters at the given sample
+    /// rate.
+    ///
+    /// The coefficients can be installed with
+    /// [`ChannelStrip::swap_coefficients`], which allows them to be
+    /// calculated ahead of time when the sample rate of the graph changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::ChannelStrip;
+    ///
+    /// let mut strip = ChannelStrip::new(48000);
+    ///
+    /// let coefficients = strip.coefficients(44100);
+    /// assert!(strip.swap_coefficients(coefficients).is_ok());
+    /// assert_eq!(strip.rate(), 44100);
+    ///
+    /// // Coefficients are stale if parameters changed in the meantime.
+    /// let coefficients = strip.coefficients(48000);
+    /// strip.set_param("eq.mid.gain", 6.0)?;
+    /// assert!(strip.swap_coefficients(coefficients).is_err());
+    /// assert_eq!(strip.rate(), 44100);
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn coefficients(&self, rate: u32) -> StripCoefficients {
+        StripCoefficients::compute(&self.values, self.crossfade, rate, self.generation)
+    }
+
+    /// Install coefficients calculated with [`ChannelStrip::coefficients`],
+    /// returning the coefficients which were replaced.
+    ///
+    /// This doesn't allocate or reset the state of any filters, so it's
+    /// suitable to call between two processing cycles. If the parameters of
+    /// the strip have changed since the coefficients were calculated they are
+    /// stale and returned as an error.
+    pub fn swap_coefficients(
+        &mut self,
+        coefficients: StripCoefficients,
+    ) -> Result<StripCoefficients, StripCoefficients> {
+        if coefficients.generation != self.generation {
+            return Err(coefficients);
+        }
+
+        Ok(mem::replace(&mut self.coefficients, coefficients))
+    }
+
+    /// Set the length of the crossfade used when bypassing blocks, in
+    /// seconds.
+    pub fn set_crossfade(&mut self, seconds: f32) {
+        self.crossfade = seconds;
+        self.update(self.rate());
+    }
+
+    /// Set the amplitude of the offset added to the input of the equalizer
+    /// filters, which is disabled with `0.0` by default.
+    ///
+    /// On long stretches of near-silence the state of the filters decays into
+    /// denormal numbers, which are very slow to process on some CPUs unless
+    /// they are flushed to zero, see [`utils::set_flush_denormals`]. The offset
+    /// alternates in sign every sample so that it adds no DC, and an amplitude
+    /// like `1e-20` is far below anything audible while keeping the state
+    /// normal.
+    ///
+    /// [`utils::set_flush_denormals`]: crate::utils::set_flush_denormals
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::ChannelStrip;
+    ///
+    /// let mut strip = ChannelStrip::new(48000);
+    /// strip.set_param("eq.low.gain", 6.0)?;
+    /// strip.set_dither(1e-20);
+    ///
+    /// let mut samples = [0.0f32; 128];
+    /// strip.process(&mut samples);
+    /// assert!(samples.iter().all(|s| s.abs() < 1e-15));
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn set_dither(&mut self, amplitude: f32) {
+        self.dither = amplitude.abs();
+    }
+
+    /// Reserve space so that processing up to `samples` samples at a time
+    /// does not allocate.
+    ///
+    /// Processing more samples than has been reserved panics when debug
+    /// assertions are enabled, since it would allocate.
+    pub fn reserve(&mut self, samples: usize) {
+        self.max_block = self.max_block.max(samples);
+
+        for buf in [&mut self.dry, &mut self.ramp, &mut self.key]
+            .into_iter()
+            .chain(self.sends.iter_mut().map(|s| &mut s.buf))
+        {
+            buf.reserve(self.max_block.saturating_sub(buf.len()));
+        }
+    }
+
+    /// Get the number of samples which can be processed at a time without
+    /// allocating, see [`ChannelStrip::reserve`].
+    #[inline]
+    pub fn max_block(&self) -> usize {
+        self.max_block
+    }
+
+    /// Get the processing order of the blocks.
+    pub fn order(&self) -> impl ExactSizeIterator<Item = StripBlock> + '_ {
+        self.order.iter().copied()
+    }
+
+    /// Set the processing order of the blocks, which must contain every block
+    /// exactly once.
+    pub fn set_order(&mut self, order: [StripBlock; 4]) -> Result<()> {
+        for block in StripBlock::ALL {
+            ensure!(
+                order.contains(&block),
+                "Block `{block}` is missing from the order"
+            );
+        }
+
+        self.order = order;
+        Ok(())
+    }
+
+    /// Move a block so that it's processed at the given position in the
+    /// chain.
+    ///
+    /// Positions past the end move the block last.
+    pub fn move_block(&mut self, block: StripBlock, index: usize) {
+        let from = self.position(block);
+        let to = index.min(self.order.len() - 1);
+
+        if from < to {
+            self.order[from..=to].rotate_left(1);
+        } else {
+            self.order[to..=from].rotate_right(1);
+        }
+    }
+
+    /// Get the position of a block in the chain.
+    pub fn position(&self, block: StripBlock) -> usize {
+        self.order
+            .iter()
+            .position(|b| *b == block)
+            .unwrap_or_default()
+    }
+
+    /// Bypass a block or bring it back into the chain.
+    ///
+    /// The change is crossfaded over the length configured with
+    /// [`ChannelStrip::set_crossfade`].
+    pub fn set_bypass(&mut self, block: StripBlock, bypassed: bool) {
+        self.bypass[block.index()].bypassed = bypassed;
+    }
+
+    /// Test if a block is bypassed.
+    pub fn is_bypassed(&self, block: StripBlock) -> bool {
+        self.bypass[block.index()].bypassed
+    }
+
+    /// Get the number of sends.
+    pub fn sends(&self) -> usize {
+        self.sends.len()
+    }
+
+    /// Set the number of sends, new sends start at their default level.
+    pub fn set_sends(&mut self, count: usize) {
+        let max_block = self.max_block;
+
+        self.sends.resize_with(count, || Send {
+            level: SEND_LEVEL.2,
+            buf: Vec::with_capacity(max_block),
+        });
+    }
+
+    /// Get the output of a send from the last call to
+    /// [`ChannelStrip::process`].
+    ///
+    /// Returns an empty slice if the send does not exist.
+    pub fn send(&self, index: usize) -> &[f32] {
+        match self.sends.get(index) {
+            Some(send) => &send.buf,
+            None => &[],
+        }
+    }
+
+    /// Get the value of a parameter.
+    pub fn param(&self, path: &str) -> Option<f32> {
+        if let Some(index) = param_index(path) {
+            return Some(self.values[index]);
+        }
+
+        let index = send_index(path)?;
+        Some(self.sends.get(index)?.level)
+    }
+
+    /// Set the value of a parameter.
+    ///
+    /// Errors if the parameter doesn't exist or the value is out of range.
+    pub fn set_param(&mut self, path: &str, value: f32) -> Result<()> {
+        if let Some(index) = param_index(path) {
+            let (_, min, max, _) = PARAMS[index];
+            ensure!(
+                (min..=max).contains(&value),
+                "Parameter `{path}` value {value} is not in range {min}..={max}"
+            );
+
+            self.values[index] = value;
+            self.update(self.rate());
+            return Ok(());
+        }
+
+        let Some(index) = send_index(path) else {
+            bail!("Unknown parameter `{path}`");
+        };
+
+        let Some(send) = self.sends.get_mut(index) else {
+            bail!("Send {index} does not exist");
+        };
+
+        let (min, max, _) = SEND_LEVEL;
+        ensure!(
+            (min..=max).contains(&value),
+            "Parameter `{path}` value {value} is not in range {min}..={max}"
+        );
+
+        send.level = value;
+        Ok(())
+    }
+
+    /// Iterate over the path and value of every parameter.
+    pub fn params(&self) -> impl Iterator<Item = (String, f32)> + '_ {
+        let params = PARAMS
+            .iter()
+            .zip(self.values)
+            .map(|(&(path, ..), value)| (String::from(path), value));
+
+        let sends = self
+            .sends
+            .iter()
+            .enumerate()
+            .map(|(n, send)| (format!("sends.{n}.level"), send.level));
+
+        params.chain(sends)
+    }
+
+    /// Write the parameters of the strip as key-value pairs into a struct,
+    /// each key prefixed with the given namespace.
+    ///
+    /// This is the format of [`id::Prop::PARAMS`], and can be used to combine
+    /// several strips into one [`id::Param::PROPS`] parameter by giving each
+    /// of them a distinct namespace. Bypass states are written as booleans
+    /// under `<block>.bypass`.
+    pub fn write_params<W, P>(
+        &self,
+        namespace: &str,
+        st: &mut StructBuilder<W, P>,
+    ) -> Result<(), Error>
+    where
+        W: Writer,
+        P: BuildPod,
+    {
+        for (path, value) in self.params() {
+            st.field()
+                .write_unsized(namespaced(namespace, &path).as_str())?;
+            st.field().write_sized(value)?;
+        }
+
+        for block in StripBlock::ALL {
+            let path = format!("{block}.bypass");
+            st.field()
+                .write_unsized(namespaced(namespace, &path).as_str())?;
+            st.field().write_sized(self.is_bypassed(block))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a [`id::Param::PROPS`] parameter with the parameters of the strip
+    /// under the given namespace.
+    ///
+    /// See [`ChannelStrip::write_params`].
+    pub fn write_props(&self, namespace: &str, pod: Builder<impl Writer>) -> Result<(), Error> {
+        pod.write_object(id::ObjectType::PROPS, id::Param::PROPS, |obj| {
+            obj.property(id::Prop::PARAMS)
+                .write_struct(|st| self.write_params(namespace, st))
+        })
+    }
+
+    /// Update the strip from a [`id::Param::PROPS`] parameter, applying the
+    /// parameters under the given namespace.
+    ///
+    /// Parameters outside of the namespace are ignored, which allows several
+    /// strips to be controlled through the same parameter.
+    pub fn read_props(&mut self, namespace: &str, obj: Object<impl AsSlice>) -> Result<()> {
+        let mut obj = obj.as_ref();
+
+        while !obj.is_empty() {
+            let p = obj.property()?;
+
+            if p.key::<id::Prop>() == id::Prop::PARAMS {
+                self.apply_params(namespace, p.value().read_struct()?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply key-value pairs written by [`ChannelStrip::write_params`].
+    fn apply_params(&mut self, namespace: &str, mut st: Struct<Slice<'_>>) -> Result<()> {
+        while !st.is_empty() {
+            let key = st.field()?.read_unsized::<str>()?;
+            let value = st.field()?;
+
+            let Some(path) = strip_namespace(namespace, key) else {
+                continue;
+            };
+
+            if let Some(block) = path.strip_suffix(".bypass") {
+                let Some(block) = StripBlock::from_name(block) else {
+                    bail!("Unknown block in `{key}`");
+                };
+
+                self.set_bypass(block, value.read_sized::<bool>()?);
+                continue;
+            }
+
+            let value = match value.ty() {
+                Type::DOUBLE => value.read_sized::<f64>()? as f32,
+                _ => value.read_sized::<f32>()?,
+            };
+
+            self.set_param(path, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the strip as a preset.
+    ///
+    /// A preset captures the order of blocks, the number of sends, bypass
+    /// states and every parameter, and can be restored with
+    /// [`ChannelStrip::read_preset`].
+    pub fn write_preset(&self, pod: Builder<impl Writer>) -> Result<(), Error> {
+        pod.write_struct(|st| {
+            st.field().write_sized(PRESET_VERSION)?;
+
+            st.field().write_struct(|order| {
+                for block in self.order() {
+                    order.field().write_unsized(block.name())?;
+                }
+
+                Ok(())
+            })?;
+
+            st.field().write_sized(self.sends.len() as i32)?;
+            st.field()
+                .write_struct(|params| self.write_params("", params))
+        })
+    }
+
+    /// Restore the strip from a preset written by
+    /// [`ChannelStrip::write_preset`].
+    ///
+    /// The strip is left untouched if the preset is malformed.
+    pub fn read_preset(&mut self, st: Struct<impl AsSlice>) -> Result<()> {
+        let mut st = st.as_ref();
+
+        let version = st.field()?.read_sized::<i32>()?;
+        ensure!(
+            version == PRESET_VERSION,
+            "Unsupported preset version {version}"
+        );
+
+        let mut order = self.order;
+        let mut names = st.field()?.read_struct()?;
+
+        for slot in &mut order {
+            let name = names.field()?.read_unsized::<str>()?;
+
+            let Some(block) = StripBlock::from_name(name) else {
+                bail!("Unknown block `{name}` in preset");
+            };
+
+            *slot = block;
+        }
+
+        let Ok(sends) = usize::try_from(st.field()?.read_sized::<i32>()?) else {
+            bail!("Negative number of sends in preset");
+        };
+
+        let params = st.field()?.read_struct()?;
+
+        let mut strip = self.clone();
+        strip.set_order(order)?;
+        strip.set_sends(sends);
+        strip.apply_params("", params)?;
+        *self = strip;
+        Ok(())
+    }
+
+    /// Align the sidechain with the signal processed by the strip, in samples.
+    ///
+    /// A positive offset delays the sidechain, which is used when it arrives
+    /// ahead of the processed signal. A negative offset delays the processed
+    /// signal instead, which adds latency to the strip.
+    ///
+    /// This only applies to [`ChannelStrip::process_with_sidechain`].
+    pub fn set_sidechain_alignment(&mut self, offset: i64) {
+        if self.alignment == offset {
+            return;
+        }
+
+        self.alignment = offset;
+        self.key_delay
+            .set(usize::try_from(offset).unwrap_or_default());
+        self.program_delay
+            .set(usize::try_from(offset.saturating_neg()).unwrap_or_default());
+    }
+
+    /// Get the sidechain alignment, see
+    /// [`ChannelStrip::set_sidechain_alignment`].
+    pub fn sidechain_alignment(&self) -> i64 {
+        self.alignment
+    }
+
+    /// Process a block of mono samples in place.
+    ///
+    /// The output of each send is available through [`ChannelStrip::send`]
+    /// until the next call.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        self.keyed = false;
+        self.process_chain(samples, None);
+    }
+
+    /// Process a block of mono samples in place like
+    /// [`ChannelStrip::process`], accounting the CPU time of each block to
+    /// `stats`.
+    ///
+    /// Bypassed blocks are only timed while they are crossfaded. This reads
+    /// the thread CPU time once per block and does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::{ChannelStrip, Stats, StripBlock, StripTimers};
+    ///
+    /// let mut stats = Stats::default();
+    /// let timers = StripTimers::register(&mut stats);
+    ///
+    /// let mut strip = ChannelStrip::new(48000);
+    /// strip.set_crossfade(0.001);
+    /// strip.set_bypass(StripBlock::Dynamics, true);
+    ///
+    /// let mut samples = [0.5f32; 128];
+    /// strip.process_timed(&mut samples, &mut stats, &timers);
+    /// strip.process_timed(&mut samples, &mut stats, &timers);
+    ///
+    /// assert_eq!(stats.block("strip.eq").map(|b| b.count), Some(2));
+    /// // The first call crossfades the bypassed block out.
+    /// assert_eq!(stats.block("strip.dynamics").map(|b| b.count), Some(1));
+    /// ```
+    pub fn process_timed(&mut self, samples: &mut [f32], stats: &mut Stats, timers: &StripTimers) {
+        self.keyed = false;
+        self.process_chain(samples, Some((stats, timers)));
+    }
+
+    /// Process a block of mono samples in place, where the dynamics block is
+    /// keyed by the given sidechain instead of by the processed signal.
+    ///
+    /// If the sidechain is shorter than the processed samples, it's padded
+    /// with silence.
+    pub fn process_with_sidechain(&mut self, samples: &mut [f32], sidechain: &[f32]) {
+        let len = samples.len();
+        self.debug_assert_reserved(len);
+
+        self.key.clear();
+        self.key
+            .extend_from_slice(&sidechain[..sidechain.len().min(len)]);
+        self.key.resize(len, 0.0);
+
+        self.key_delay.process(&mut self.key);
+        self.program_delay.process(samples);
+
+        self.keyed = true;
+        self.process_chain(samples, None);
+    }
+
+    /// Get the first block whose output exceeded full scale during the last
+    /// call to [`ChannelStrip::process`], if any.
+    ///
+    /// Samples beyond full scale are not clipped until they leave the graph,
+    /// but indicate that a stage is overloaded and the signal is likely to
+    /// clip downstream.
+    #[inline]
+    pub fn overloaded(&self) -> Option<StripBlock> {
+        self.overloaded
+    }
+
+    fn process_chain(
+        &mut self,
+        samples: &mut [f32],
+        mut timing: Option<(&mut Stats, &StripTimers)>,
+    ) {
+        let len = samples.len();
+        self.debug_assert_reserved(len);
+        self.overloaded = None;
+
+        for send in &mut self.sends {
+            send.buf.clear();
+            send.buf.resize(len, 0.0);
+        }
+
+        if let Some((stats, _)) = &mut timing {
+            stats.start_blocks();
+        }
+
+        for block in self.order {
+            let bypass = self.bypass[block.index()];
+            let target = if bypass.bypassed { 0.0 } else { 1.0 };
+
+            if bypass.mix == target {
+                if !bypass.bypassed {
+                    self.process_block(block, samples);
+                    self.check_overload(block, samples);
+
+                    if let Some((stats, timers)) = &mut timing {
+                        stats.lap(timers.blocks[block.index()]);
+                    }
+                }
+
+                continue;
+            }
+
+            // Crossfade between the unprocessed and the processed signal.
+            let step = 1.0 / self.coefficients.crossfade as f32;
+
+            self.dry.clear();
+            self.dry.extend_from_slice(samples);
+            self.ramp.clear();
+
+            let mut mix = bypass.mix;
+
+            for _ in 0..len {
+                mix = if mix < target {
+                    (mix + step).min(target)
+                } else {
+                    (mix - step).max(target)
+                };
+
+                self.ramp.push(mix);
+            }
+
+            self.bypass[block.index()].mix = mix;
+            self.process_block(block, samples);
+
+            for ((out, dry), mix) in samples.iter_mut().zip(&self.dry).zip(&self.ramp) {
+                *out = *dry + (*out - *dry) * *mix;
+            }
+
+            if block == StripBlock::Sends {
+                for send in &mut self.sends {
+                    for (out, mix) in send.buf.iter_mut().zip(&self.ramp) {
+                        *out *= *mix;
+                    }
+                }
+            }
+
+            self.check_overload(block, samples);
+
+            if let Some((stats, timers)) = &mut timing {
+                stats.lap(timers.blocks[block.index()]);
+            }
+        }
+    }
+
+    #[inline]
+    fn debug_assert_reserved(&self, len: usize) {
+        debug_assert!(
+            len <= self.max_block,
+            "Processing {len} samples would grow buffers reserved for {} samples",
+            self.max_block
+        );
+    }
+
+    #[inline]
+    fn check_overload(&mut self, block: StripBlock, samples: &[f32]) {
+        if self.overloaded.is_none() && samples.iter().any(|s| s.abs() > 1.0) {
+            self.overloaded = Some(block);
+        }
+    }
+
+    fn process_block(&mut self, block: StripBlock, samples: &mut [f32]) {
+        match block {
+            StripBlock::Gain => {
+                let target = db_to_linear(self.value("gain.level"));
+                let step = (target - self.gain) / samples.len().max(1) as f32;
+
+                for sample in samples.iter_mut() {
+                    self.gain += step;
+                    *sample *= self.gain;
+                }
+
+                self.gain = target;
+            }
+            StripBlock::Eq => {
+                for sample in samples.iter_mut() {
+                    for (band, state) in self.coefficients.eq.iter().zip(&mut self.eq) {
+                        *sample = band.process(state, *sample, self.dither);
+                    }
+
+                    self.dither = -self.dither;
+                }
+            }
+            StripBlock::Dynamics => {
+                let threshold = self.value("dynamics.threshold");
+                let slope = 1.0 - 1.0 / self.value("dynamics.ratio");
+                let makeup = self.value("dynamics.makeup");
+
+                for (n, sample) in samples.iter_mut().enumerate() {
+                    let level = if self.keyed {
+                        self.key[n].abs()
+                    } else {
+                        sample.abs()
+                    };
+
+                    let coeff = if level > self.envelope {
+                        self.coefficients.attack
+                    } else {
+                        self.coefficients.release
+                    };
+
+                    self.envelope = level + coeff * (self.envelope - level);
+
+                    let over = 20.0 * self.envelope.max(MIN_LEVEL).log10() - threshold;
+                    let reduction = if over > 0.0 { over * slope } else { 0.0 };
+                    *sample *= db_to_linear(makeup - reduction);
+                }
+            }
+            StripBlock::Sends => {
+                for send in &mut self.sends {
+                    let level = db_to_linear(send.level);
+
+                    for (out, sample) in send.buf.iter_mut().zip(samples.iter()) {
+                        *out = *sample * level;
+                    }
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn value(&self, path: &str) -> f32 {
+        value(&self.values, path)
+    }
+
+    /// Recalculate coefficients after parameters have changed.
+    fn update(&mut self, rate: u32) {
+        self.generation = self.generation.wrapping_add(1);
+        self.coefficients = self.coefficients(rate);
+    }
+}
+
+impl Clone for ChannelStrip {
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut strip = Self {
+            coefficients: self.coefficients.clone(),
+            crossfade: self.crossfade,
+            generation: self.generation,
+            order: self.order,
+            bypass: self.bypass,
+            values: self.values,
+            gain: self.gain,
+            eq: self.eq,
+            dither: self.dither,
+            envelope: self.envelope,
+            sends: self.sends.clone(),
+            dry: Vec::new(),
+            ramp: Vec::new(),
+            alignment: self.alignment,
+            program_delay: self.program_delay.clone(),
+            key_delay: self.key_delay.clone(),
+            key: Vec::new(),
+            keyed: false,
+            overloaded: None,
+            max_block: 0,
+        };
+
+        strip.reserve(self.max_block);
+        strip
+    }
+}
+
+impl fmt::Debug for ChannelStrip {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelStrip")
+            .field("rate", &self.rate())
+            .field("order", &self.order)
+            .field("bypass", &self.bypass)
+            .field("values", &self.values)
+            .field("sends", &self.sends.len())
+            .field("alignment", &self.alignment)
+            .finish_non_exhaustive()
+    }
+}
+
+#[inline]
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[inline]
+fn value(values: &[f32; PARAMS.len()], path: &str) -> f32 {
+    param_index(path).map(|i| values[i]).unwrap_or_default()
+}
+
+#[inline]
+fn param_index(path: &str) -> Option<usize> {
+    PARAMS.iter().position(|&(p, ..)| p == path)
+}
+
+/// Parse the index out of a `sends.<n>.level` path.
+fn send_index(path: &str) -> Option<usize> {
+    let rest = path.strip_prefix("sends.")?;
+    let index = rest.strip_suffix(".level")?;
+    index.parse().ok()
+}
+
+fn namespaced(namespace: &str, path: &str) -> String {
+    if namespace.is_empty() {
+        return String::from(path);
+    }
+
+    format!("{namespace}.{path}")
+}
+
+fn strip_namespace<'a>(namespace: &str, key: &'a str) -> Option<&'a str> {
+    if namespace.is_empty() {
+        return Some(key);
+    }
+
+    key.strip_prefix(namespace)?.strip_prefix('.')
+}
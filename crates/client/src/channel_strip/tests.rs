@@ -0,0 +1,97 @@
+use super::{ChannelStrip, StripBlock};
+
+/// Ten seconds of audio at 48 kHz.
+const SAMPLES: usize = 480_000;
+const BLOCK: usize = 128;
+
+fn strip(dither: f32) -> ChannelStrip {
+    let mut strip = ChannelStrip::new(48000);
+    strip.set_param("eq.low.gain", 6.0).unwrap();
+    strip.set_param("eq.mid.gain", 3.0).unwrap();
+    strip.set_param("eq.high.gain", -3.0).unwrap();
+    strip.set_dither(dither);
+    strip
+}
+
+/// Feed an impulse followed by a long stretch of silence through the strip,
+/// returning whether the state of any filter became denormal.
+fn feed_silence(strip: &mut ChannelStrip, block: &mut [f32; BLOCK]) -> bool {
+    let mut denormal = false;
+
+    block.fill(0.0);
+    block[0] = 1.0;
+    strip.process(block);
+
+    for _ in 0..SAMPLES / BLOCK {
+        block.fill(0.0);
+        strip.process(block);
+
+        denormal |= strip
+            .eq
+            .iter()
+            .any(|s| s.z1.is_subnormal() || s.z2.is_subnormal());
+    }
+
+    denormal
+}
+
+#[test]
+fn silence_decays_into_denormals() {
+    let mut strip = strip(0.0);
+    let mut block = [0.0; BLOCK];
+
+    assert!(feed_silence(&mut strip, &mut block));
+}
+
+#[test]
+fn dither_avoids_denormals() {
+    let mut strip = strip(1e-20);
+    let mut block = [0.0; BLOCK];
+
+    assert!(!feed_silence(&mut strip, &mut block));
+
+    // The dither itself stays far below anything audible.
+    assert!(block.iter().all(|s| s.abs() < 1e-15), "{block:?}");
+    assert!(block.iter().any(|s| *s != 0.0));
+}
+
+/// The capacities of every buffer used while processing.
+fn capacities(strip: &ChannelStrip) -> [usize; 4] {
+    [
+        strip.dry.capacity(),
+        strip.ramp.capacity(),
+        strip.key.capacity(),
+        strip.sends[0].buf.capacity(),
+    ]
+}
+
+#[test]
+fn processing_does_not_grow_buffers() {
+    let mut strip = strip(0.0);
+    strip.set_sends(1);
+
+    let mut block = [0.5; BLOCK];
+    let sidechain = [0.25; BLOCK * 2];
+
+    for mut strip in [strip.clone(), strip] {
+        let before = capacities(&strip);
+        assert!(before.iter().all(|&c| c >= BLOCK), "{before:?}");
+
+        // Crossfade a bypassed block, which uses every buffer.
+        strip.set_bypass(StripBlock::Eq, true);
+        strip.process(&mut block);
+        strip.process_with_sidechain(&mut block, &sidechain);
+
+        assert_eq!(capacities(&strip), before);
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "would grow buffers"]
+fn processing_beyond_reserved_panics() {
+    let mut strip = strip(0.0);
+    let mut block = [0.0; BLOCK];
+    strip.max_block = BLOCK - 1;
+    strip.process(&mut block);
+}
@@ -1,22 +1,28 @@
+use core::fmt;
+
 use std::collections::BTreeMap;
-use std::os::fd::{AsRawFd, RawFd};
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
 
+use alloc::vec;
 use alloc::vec::Vec;
 
 use anyhow::Result;
-use pod::{AsSlice, Object};
+use pod::{AsSlice, Fd, IntoRaw, Object, Type};
 use protocol::buf::RecvBuf;
 use protocol::buf::SendBuf;
 use protocol::consts;
 use protocol::flags;
 use protocol::id;
+use protocol::message::{
+    CoreGetRegistry, CoreHello, CorePong, CoreSync, MetadataClear, MetadataSetProperty,
+};
 use protocol::op;
 use protocol::poll::{ChangeInterest, Interest};
-use protocol::{Connection, Properties};
+use protocol::{Connection, Properties, Symbol};
 use tracing::Level;
 
 use crate::ports::PortParam;
-use crate::{LocalId, Parameters, PortId};
+use crate::{GlobalId, LocalId, Parameters, PortId};
 
 #[derive(Debug)]
 pub struct Client {
@@ -58,11 +64,18 @@ impl Client {
         Ok(())
     }
 
+    /// Send data to the server until the socket would block.
+    pub fn send_all(&mut self) -> Result<()> {
+        self.connection.send_all(&mut self.outgoing)?;
+        Ok(())
+    }
+
     /// Send client hello.
     pub fn core_hello(&mut self) -> Result<()> {
         let mut pod = pod::array();
-        pod.as_mut()
-            .write_struct(|st| st.field().write_sized(consts::VERSION))?;
+        pod.as_mut().write(CoreHello {
+            version: consts::VERSION,
+        })?;
 
         self.connection.request(
             &mut self.outgoing,
@@ -77,10 +90,9 @@ impl Client {
     pub fn core_get_registry(&mut self, new_id: LocalId) -> Result<()> {
         let mut pod = pod::array();
 
-        pod.as_mut().write_struct(|st| {
-            st.field().write(consts::REGISTRY_VERSION as i32)?;
-            st.field().write(new_id.into_u32())?;
-            Ok(())
+        pod.as_mut().write(CoreGetRegistry {
+            version: consts::REGISTRY_VERSION as i32,
+            new_id: new_id.into_u32(),
         })?;
 
         self.connection.request(
@@ -99,10 +111,9 @@ impl Client {
 
         let mut pod = pod::array();
 
-        pod.as_mut().write_struct(|st| {
-            st.field().write_sized(id)?;
-            st.field().write_sized(sync_sequence)?;
-            Ok(())
+        pod.as_mut().write(CoreSync {
+            id,
+            seq: sync_sequence,
         })?;
 
         self.connection.request(
@@ -118,16 +129,49 @@ impl Client {
     pub fn core_pong(&mut self, id: u32, seq: u32) -> Result<()> {
         let mut pod = pod::array();
 
+        pod.as_mut().write(CorePong { id, seq })?;
+
+        self.connection.request(
+            &mut self.outgoing,
+            consts::CORE_ID,
+            op::Core::PONG,
+            pod.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    /// Report an error on the object with the given id to the server, where
+    /// `seq` is the sequence number of the message which caused it and `res`
+    /// is a negative errno.
+    pub fn core_error(&mut self, id: LocalId, seq: u32, res: i32, message: &str) -> Result<()> {
+        let mut pod = pod::array();
+
         pod.as_mut().write_struct(|st| {
-            st.field().write_sized(id)?;
-            st.field().write_sized(seq)?;
+            st.field().write(id)?;
+            st.field().write(seq as i32)?;
+            st.field().write(res)?;
+            st.field().write(message)?;
             Ok(())
         })?;
 
         self.connection.request(
             &mut self.outgoing,
             consts::CORE_ID,
-            op::Core::PONG,
+            op::Core::ERROR,
+            pod.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    /// Destroy a resource.
+    pub fn core_destroy(&mut self, id: LocalId) -> Result<()> {
+        let mut pod = pod::array();
+        pod.as_mut().write_struct(|st| st.field().write(id))?;
+
+        self.connection.request(
+            &mut self.outgoing,
+            consts::CORE_ID,
+            op::Core::DESTROY,
             pod.as_ref(),
         )?;
         Ok(())
@@ -226,6 +270,7 @@ impl Client {
         max_input_ports: u32,
         max_output_ports: u32,
         props: &mut Properties,
+        removed: &[Symbol],
         params: &Parameters,
     ) -> Result<()> {
         let mut pod = pod::dynamic();
@@ -268,11 +313,17 @@ impl Client {
                     st.field().write_sized(node_flags)?;
 
                     if props_modified {
-                        st.field().write_sized(props.len() as u32)?;
+                        st.field()
+                            .write_sized((props.len() + removed.len()) as u32)?;
 
                         for (key, value) in props.iter() {
                             st.write((key, value))?;
                         }
+
+                        // NB: A property without a value is removed.
+                        for key in removed {
+                            st.write((key.as_str(), None::<&str>))?;
+                        }
                     } else {
                         st.field().write(0u32)?;
                     }
@@ -399,6 +450,226 @@ impl Client {
         Ok(())
     }
 
+    /// Bind to a global object through the registry.
+    pub fn registry_bind(
+        &mut self,
+        registry: LocalId,
+        id: GlobalId,
+        ty: &str,
+        version: u32,
+        new_id: LocalId,
+    ) -> Result<()> {
+        let mut pod = pod::array();
+
+        pod.as_mut().write_struct(|st| {
+            st.field().write_sized(id.into_u32())?;
+            st.field().write_unsized(ty)?;
+            st.field().write_sized(version)?;
+            st.field().write_sized(new_id.into_u32())?;
+            Ok(())
+        })?;
+
+        self.connection.request(
+            &mut self.outgoing,
+            registry.into_u32(),
+            op::Registry::BIND,
+            pod.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    /// Create a new security context on a bound security context proxy.
+    ///
+    /// Clients connecting to `listen_fd` are assigned the given properties.
+    /// The context is removed by the server once the peer of `close_fd` is
+    /// closed.
+    pub fn security_context_create(
+        &mut self,
+        id: LocalId,
+        listen_fd: OwnedFd,
+        close_fd: OwnedFd,
+        props: &Properties,
+    ) -> Result<()> {
+        let mut pod = pod::array();
+
+        pod.as_mut().write_struct(|st| {
+            st.field().write_sized(Fd::new(0))?;
+            st.field().write_sized(Fd::new(1))?;
+
+            st.field().write_struct(|st| {
+                st.field().write_sized(props.len() as u32)?;
+
+                for (key, value) in props.iter() {
+                    st.write((key, value))?;
+                }
+
+                Ok(())
+            })?;
+
+            Ok(())
+        })?;
+
+        self.connection.request_with_fds(
+            &mut self.outgoing,
+            id.into_u32(),
+            op::SecurityContext::CREATE,
+            pod.as_ref(),
+            vec![listen_fd, close_fd],
+        )?;
+        Ok(())
+    }
+
+    /// Subscribe to parameter changes on a bound node.
+    pub fn node_subscribe_params(&mut self, id: LocalId, ids: &[id::Param]) -> Result<()> {
+        self.subscribe_params(id, op::Node::SUBSCRIBE_PARAMS, ids)
+    }
+
+    /// Subscribe to parameter changes on a bound device.
+    pub fn device_subscribe_params(&mut self, id: LocalId, ids: &[id::Param]) -> Result<()> {
+        self.subscribe_params(id, op::Device::SUBSCRIBE_PARAMS, ids)
+    }
+
+    /// Enumerate parameters on a bound node.
+    pub fn node_enum_params(
+        &mut self,
+        id: LocalId,
+        seq: i32,
+        param: id::Param,
+        index: u32,
+        num: u32,
+    ) -> Result<()> {
+        self.enum_params(id, op::Node::ENUM_PARAMS, seq, param, index, num)
+    }
+
+    /// Enumerate parameters on a bound device.
+    pub fn device_enum_params(
+        &mut self,
+        id: LocalId,
+        seq: i32,
+        param: id::Param,
+        index: u32,
+        num: u32,
+    ) -> Result<()> {
+        self.enum_params(id, op::Device::ENUM_PARAMS, seq, param, index, num)
+    }
+
+    /// Set a parameter on a bound device.
+    pub fn device_set_param(
+        &mut self,
+        id: LocalId,
+        param: id::Param,
+        flags: u32,
+        value: Object<impl AsSlice>,
+    ) -> Result<()> {
+        let mut pod = pod::dynamic();
+
+        pod.as_mut().write_struct(|st| {
+            st.field().write(param)?;
+            st.field().write_sized(flags)?;
+            st.field().write(value.as_ref())?;
+            Ok(())
+        })?;
+
+        self.connection.request(
+            &mut self.outgoing,
+            id.into_u32(),
+            op::Device::SET_PARAM,
+            pod.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    /// Set a property on a subject of a bound metadata object, or remove it
+    /// if `value` is `None`.
+    pub fn metadata_set_property(
+        &mut self,
+        id: LocalId,
+        subject: GlobalId,
+        key: &str,
+        ty: Option<&str>,
+        value: Option<&str>,
+    ) -> Result<()> {
+        let mut pod = pod::dynamic();
+
+        pod.as_mut().write(MetadataSetProperty {
+            subject: subject.into_u32(),
+            key,
+            ty,
+            value,
+        })?;
+
+        self.connection.request(
+            &mut self.outgoing,
+            id.into_u32(),
+            op::Metadata::SET_PROPERTY,
+            pod.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    /// Clear all properties of a bound metadata object.
+    pub fn metadata_clear(&mut self, id: LocalId) -> Result<()> {
+        let mut pod = pod::array();
+
+        pod.as_mut().write(MetadataClear {})?;
+
+        self.connection.request(
+            &mut self.outgoing,
+            id.into_u32(),
+            op::Metadata::CLEAR,
+            pod.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    fn subscribe_params(
+        &mut self,
+        id: LocalId,
+        op: impl IntoRaw<u8> + fmt::Display + fmt::Debug,
+        ids: &[id::Param],
+    ) -> Result<()> {
+        let mut pod = pod::array();
+
+        pod.as_mut().write_struct(|st| {
+            st.field().write_array(Type::ID, |array| {
+                for id in ids {
+                    array.child().write(*id)?;
+                }
+
+                Ok(())
+            })
+        })?;
+
+        self.connection
+            .request(&mut self.outgoing, id.into_u32(), op, pod.as_ref())?;
+        Ok(())
+    }
+
+    fn enum_params(
+        &mut self,
+        id: LocalId,
+        op: impl IntoRaw<u8> + fmt::Display + fmt::Debug,
+        seq: i32,
+        param: id::Param,
+        index: u32,
+        num: u32,
+    ) -> Result<()> {
+        let mut pod = pod::array();
+
+        pod.as_mut().write_struct(|st| {
+            st.field().write_sized(seq)?;
+            st.field().write(param)?;
+            st.field().write_sized(index)?;
+            st.field().write_sized(num)?;
+            st.field().write_none()?;
+            Ok(())
+        })?;
+
+        self.connection
+            .request(&mut self.outgoing, id.into_u32(), op, pod.as_ref())?;
+        Ok(())
+    }
+
     /// Update the client.
     pub fn client_node_set_active(&mut self, id: LocalId, active: bool) -> Result<()> {
         let mut pod = pod::array();
@@ -1,77 +1,172 @@
 use core::fmt;
 use core::mem::{self, MaybeUninit};
+use core::time::Duration;
 
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::format;
+use std::string::ToString;
 use std::vec::Vec;
 
-use anyhow::{Result, bail};
-use pod::{AsSlice, DynamicBuf, Object};
+use anyhow::{Context, Result, bail, ensure};
+use pod::{AsSlice, Builder, DynamicBuf, Error, Object, Slice, Struct, Writer};
 use protocol::consts::Activation;
 use protocol::ffi;
 use protocol::flags::{self, Status};
 use protocol::id::{self, Param};
 use protocol::poll::Token;
-use protocol::{EventFd, Properties};
-use slab::Slab;
+use protocol::{EventFd, Prop, Properties, Symbol, prop};
 
 use crate::activation;
+use crate::grace::Reader;
 use crate::memory::Region;
+use crate::ports::PortMixInfo;
 use crate::ptr::{atomic, volatile};
 use crate::utils;
-use crate::{LocalId, Parameters, PeerActivation, Ports, Stats};
+use crate::{
+    ChannelStrip, ClipDetector, ClipLatch, ClockTime, Cycle, GlobalId, IdlePolicy, LocalId,
+    OverloadAction, OverloadDecision, OverloadPolicy, OverloadState, Parameters, PeerActivation,
+    Ports, ProcessChunks, Stats, StripTimers,
+};
+
+/// The version of the preset format written by [`ClientNode::write_preset`].
+const PRESET_VERSION: i32 = 1;
 
 /// Collection of data related to client nodes.
+///
+/// Nodes are stored in a generational slot map. Slots are reused once a node
+/// has been removed, but each reuse bumps the generation of the slot so that
+/// stale [`ClientNodeId`]s never address a newer node.
+///
+/// The nodes themselves are stored in the order they were created, and each
+/// slot refers to the position of its node.
 pub struct ClientNodes {
-    data: Slab<ClientNode>,
+    nodes: Vec<Entry>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+struct Entry {
+    id: ClientNodeId,
+    node: ClientNode,
+}
+
+struct Slot {
+    generation: u32,
+    position: Option<usize>,
 }
 
 impl ClientNodes {
     /// Create a new `ClientNodes` instance.
     #[inline]
     pub fn new() -> Self {
-        Self { data: Slab::new() }
+        Self {
+            nodes: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
     }
 
     /// Insert a new client node into the collection.
-    pub(crate) fn insert(&mut self, node: ClientNode) -> ClientNodeId {
-        let id = self.data.insert(node);
-        ClientNodeId::new(id as u32)
+    pub(crate) fn insert(&mut self, node: ClientNode) -> Result<ClientNodeId> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let Ok(index) = u32::try_from(self.slots.len()) else {
+                    bail!("Ran out of client node identifiers");
+                };
+
+                self.slots.push(Slot {
+                    generation: 0,
+                    position: None,
+                });
+
+                index
+            }
+        };
+
+        let slot = &mut self.slots[index as usize];
+        slot.position = Some(self.nodes.len());
+
+        let id = ClientNodeId::new(index, slot.generation);
+        self.nodes.push(Entry { id, node });
+        Ok(id)
     }
 
     /// Remove a client node from the collection by its identifier.
     pub(crate) fn remove(&mut self, id: ClientNodeId) -> Option<ClientNode> {
-        self.data.try_remove(id.index())
+        let position = self.position(id)?;
+        let entry = self.nodes.remove(position);
+
+        let slot = &mut self.slots[id.index()];
+        slot.position = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+
+        // NB: Nodes after the removed one have moved back one position.
+        for entry in &self.nodes[position..] {
+            if let Some(position) = &mut self.slots[entry.id.index()].position {
+                *position -= 1;
+            }
+        }
+
+        Some(entry.node)
+    }
+
+    /// Get the current identifier of the node stored at the given index.
+    pub(crate) fn id_at(&self, index: u32) -> Option<ClientNodeId> {
+        let slot = self.slots.get(index as usize)?;
+        slot.position?;
+        Some(ClientNodeId::new(index, slot.generation))
     }
 
-    /// Iterate over all client nodes.
-    pub(crate) fn iter(&mut self) -> impl Iterator<Item = &ClientNode> {
-        self.data.iter().map(|(_, node)| node)
+    /// Iterate over all client nodes in the order they were created.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &ClientNode> {
+        self.nodes.iter().map(|entry| &entry.node)
     }
 
-    /// Iterate over all client nodes mutably.
+    /// Iterate over all client nodes mutably in the order they were created.
     pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut ClientNode> {
-        self.data.iter_mut().map(|(_, node)| node)
+        self.nodes.iter_mut().map(|entry| &mut entry.node)
+    }
+
+    /// Iterate over all client nodes mutably together with their identifiers
+    /// in the order they were created.
+    pub(crate) fn iter_mut_with_id(
+        &mut self,
+    ) -> impl Iterator<Item = (ClientNodeId, &mut ClientNode)> {
+        self.nodes
+            .iter_mut()
+            .map(|entry| (entry.id, &mut entry.node))
+    }
+
+    /// Get the position of the node with the given ID.
+    #[inline]
+    fn position(&self, id: ClientNodeId) -> Option<usize> {
+        self.slots
+            .get(id.index())
+            .filter(|slot| slot.generation == id.generation)?
+            .position
     }
 
     /// Get a reference to the client node with the given ID.
     #[inline]
     pub fn get(&self, id: ClientNodeId) -> Result<&ClientNode> {
-        let Some(node) = self.data.get(id.index()) else {
+        let Some(position) = self.position(id) else {
             bail!("No client node found for id {}", id);
         };
 
-        Ok(node)
+        Ok(&self.nodes[position].node)
     }
 
     /// Get a mutable reference to the client node with the given ID.
     #[inline]
     pub fn get_mut(&mut self, id: ClientNodeId) -> Result<&mut ClientNode> {
-        let Some(node) = self.data.get_mut(id.index()) else {
+        let Some(position) = self.position(id) else {
             bail!("No client node found for id {}", id);
         };
 
-        Ok(node)
+        Ok(&mut self.nodes[position].node)
     }
 }
 
@@ -83,43 +178,56 @@ impl Default for ClientNodes {
 }
 
 /// A client node identifier.
+///
+/// This consists of an index and a generation. The generation is bumped every
+/// time a node is removed, so an identifier which refers to a removed node
+/// will never address a node which is later created in its place.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(transparent)]
-pub struct ClientNodeId(u32);
+pub struct ClientNodeId {
+    index: u32,
+    generation: u32,
+}
 
 impl ClientNodeId {
-    /// Create a new `ClientNodeId` from a `u32`.
+    /// Create a new `ClientNodeId` from an index and a generation.
     #[inline]
-    pub fn new(id: u32) -> Self {
-        Self(id)
-    }
-
-    /// Convert the `ClientNodeId` into a `u32`.
-    #[inline]
-    pub(crate) fn into_u32(self) -> u32 {
-        self.0
+    pub(crate) fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
     }
 
     /// Get the index of the client node.
     ///
-    /// Since it was constructed from a `u32`, it can always be safely coerced
-    /// into one.
+    /// Indexes are reused once a node has been removed, so this is only
+    /// unique among nodes which are alive at the same time.
+    #[inline]
     pub fn index(self) -> usize {
-        self.0 as usize
+        self.index as usize
+    }
+
+    /// Get the index of the client node as a `u32`.
+    #[inline]
+    pub(crate) fn index_u32(self) -> u32 {
+        self.index
+    }
+
+    /// Get the generation of the client node.
+    #[inline]
+    pub fn generation(self) -> u32 {
+        self.generation
     }
 }
 
 impl fmt::Display for ClientNodeId {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        write!(f, "{}v{}", self.index, self.generation)
     }
 }
 
 impl fmt::Debug for ClientNodeId {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
+        fmt::Display::fmt(self, f)
     }
 }
 
@@ -128,6 +236,9 @@ impl fmt::Debug for ClientNodeId {
 pub struct ClientNode {
     /// The unique identifier for this node.
     pub id: LocalId,
+    /// The global identifier for this node, once it has been bound by the
+    /// server.
+    pub global_id: Option<GlobalId>,
     /// Activation record for this node.
     pub activation: Option<Region<ffi::NodeActivation>>,
     /// Activation records for dependent nodes.
@@ -136,6 +247,9 @@ pub struct ClientNode {
     pub ports: Ports,
     pub params: Parameters,
     pub props: Properties,
+    /// Default properties of the ports of this node, which are overridden by
+    /// the properties of each port.
+    pub port_props: Properties,
     pub(super) read_fd: Option<EventFd>,
     pub(super) read_token: Token,
     pub(super) write_fd: Option<EventFd>,
@@ -145,8 +259,29 @@ pub struct ClientNode {
     pub(super) io_position: Option<Region<ffi::IoPosition>>,
     pub(super) max_input_ports: u32,
     pub(super) max_output_ports: u32,
+    pub(super) active: bool,
+    pub(super) idle: IdlePolicy,
+    /// The properties the node was created with through the factory, which
+    /// are used to create it again when the stream reconnects.
+    pub(super) factory_props: Properties,
+    /// Properties which have been removed since the last update, which are
+    /// cleared on the server with the next update.
+    pub(super) removed_props: Vec<Symbol>,
+    reader: Reader,
+    chunk_size: Option<usize>,
+    overload: Option<OverloadState>,
+    clip: Option<ClipDetector>,
+    strips: Vec<ChannelStrip>,
+    strip_timers: Option<StripTimers>,
+    rate: Option<u32>,
+    rate_change: Option<(Option<u32>, u32)>,
+    quantum: Option<u64>,
+    quantum_change: Option<(Option<u64>, u64)>,
     modified: bool,
+    batch: usize,
+    committed: bool,
     then: u64,
+    then_cpu: u64,
     stats: Stats,
 }
 
@@ -156,15 +291,19 @@ impl ClientNode {
         ports: Ports,
         write_token: Token,
         read_token: Token,
+        reader: Reader,
+        factory_props: Properties,
     ) -> Result<Self> {
         Ok(Self {
             id,
+            global_id: None,
             ports,
             write_fd: None,
             read_fd: None,
             write_token,
             read_token,
             props: Properties::new(),
+            port_props: Properties::new(),
             params: Parameters::new(),
             activation: None,
             peer_activations: Vec::new(),
@@ -173,8 +312,25 @@ impl ClientNode {
             io_position: None,
             max_input_ports: 0,
             max_output_ports: 0,
+            active: false,
+            idle: IdlePolicy::new(),
+            factory_props,
+            removed_props: Vec::new(),
+            reader,
+            chunk_size: None,
+            overload: None,
+            clip: None,
+            strips: Vec::new(),
+            strip_timers: None,
+            rate: None,
+            rate_change: None,
+            quantum: None,
+            quantum_change: None,
             modified: true,
+            batch: 0,
+            committed: false,
             then: 0,
+            then_cpu: 0,
             stats: Stats::default(),
         })
     }
@@ -191,14 +347,568 @@ impl ClientNode {
         self.modified = true;
     }
 
+    /// Set the user-facing description of the node, like `Livemix Channel 1`.
+    ///
+    /// Like other changes to the properties of the node this is sent to the
+    /// server with the next update, so the node can be renamed while running.
+    pub fn set_description(&mut self, description: impl AsRef<str>) {
+        self.props.insert(prop::NODE_DESCRIPTION, description);
+    }
+
+    /// Set the short nickname of the node, which is used when there is
+    /// limited space to display it.
+    pub fn set_nick(&mut self, nick: impl AsRef<str>) {
+        self.props.insert(prop::NODE_NICK, nick);
+    }
+
+    /// Set the name of the icon which represents the node, following the
+    /// freedesktop icon naming specification.
+    pub fn set_icon_name(&mut self, icon_name: impl AsRef<str>) {
+        self.props.insert(prop::MEDIA_ICON_NAME, icon_name);
+    }
+
+    /// Set the media role of the node, like `Music` or `Communication`.
+    pub fn set_role(&mut self, role: impl AsRef<str>) {
+        self.props.insert(prop::MEDIA_ROLE, role);
+    }
+
+    /// Ask the graph to run at the given sample rate while this node is
+    /// running, or clear the request with `None`.
+    ///
+    /// The server decides whether the request is honored, the resulting rate
+    /// is reported through [`StreamEvent::RateChanged`]. Clearing the request
+    /// removes the `node.force-rate` property.
+    ///
+    /// [`StreamEvent::RateChanged`]: crate::events::StreamEvent::RateChanged
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::sim::Simulation;
+    /// use protocol::prop;
+    ///
+    /// let mut sim = Simulation::new(48_000, 1024)?;
+    /// let node = sim.node_mut();
+    ///
+    /// node.set_force_rate(Some(96_000));
+    /// assert_eq!(node.force_rate(), Some(96_000));
+    ///
+    /// node.set_force_rate(None);
+    /// assert_eq!(node.force_rate(), None);
+    /// assert_eq!(node.props.get(prop::NODE_FORCE_RATE.as_str()), None);
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn set_force_rate(&mut self, rate: Option<u32>) {
+        match rate {
+            Some(rate) => {
+                self.props.insert(prop::NODE_FORCE_RATE, rate.to_string());
+            }
+            None => {
+                self.remove_prop(prop::NODE_FORCE_RATE);
+            }
+        }
+    }
+
+    /// Get the sample rate requested with [`ClientNode::set_force_rate`].
+    pub fn force_rate(&self) -> Option<u32> {
+        parse_nonzero(self.props.get(prop::NODE_FORCE_RATE)?)
+    }
+
+    /// Ask the graph to run with the given quantum while this node is running,
+    /// or clear the request with `None`.
+    ///
+    /// This is the same property which is used by
+    /// [`OverloadAction::RequestQuantum`], so an overload policy with that
+    /// action overrides it. The resulting quantum is reported through
+    /// [`StreamEvent::QuantumChanged`]. Clearing the request removes the
+    /// `node.force-quantum` property.
+    ///
+    /// [`StreamEvent::QuantumChanged`]: crate::events::StreamEvent::QuantumChanged
+    pub fn set_force_quantum(&mut self, quantum: Option<u32>) {
+        match quantum {
+            Some(quantum) => {
+                self.props
+                    .insert(prop::NODE_FORCE_QUANTUM, quantum.to_string());
+            }
+            None => {
+                self.remove_prop(prop::NODE_FORCE_QUANTUM);
+            }
+        }
+    }
+
+    /// Get the quantum requested with [`ClientNode::set_force_quantum`].
+    pub fn force_quantum(&self) -> Option<u32> {
+        parse_nonzero(self.props.get(prop::NODE_FORCE_QUANTUM)?)
+    }
+
+    /// Prevent the quantum of the graph from changing while this node is
+    /// running, even if other nodes ask for a different one.
+    pub fn set_lock_quantum(&mut self, lock: bool) {
+        self.props
+            .insert(prop::NODE_LOCK_QUANTUM, if lock { "true" } else { "false" });
+    }
+
+    /// Test if the quantum is locked, see [`ClientNode::set_lock_quantum`].
+    pub fn is_quantum_locked(&self) -> bool {
+        self.props.get(prop::NODE_LOCK_QUANTUM) == Some("true")
+    }
+
+    /// Begin a batch of parameter changes.
+    ///
+    /// While a batch is open, changes to node and port parameters are
+    /// collected instead of being sent to the server one by one. Once the
+    /// batch is closed with [`ClientNode::commit`] all modified parameters are
+    /// sent as a single set of update messages, which avoids renegotiating
+    /// the node for every individual change.
+    ///
+    /// Batches can be nested, in which case changes are only sent once the
+    /// outermost batch is committed.
+    ///
+    /// The server bracketing parameter changes with the
+    /// [`id::NodeCommand::PARAM_BEGIN`] and [`id::NodeCommand::PARAM_END`]
+    /// commands automatically opens and commits a batch. Changes to bound
+    /// devices are batched separately, see [`DeviceProxy::begin_params`].
+    ///
+    /// [`DeviceProxy::begin_params`]: crate::DeviceProxy::begin_params
+    /// [`id::NodeCommand::PARAM_BEGIN`]: protocol::id::NodeCommand::PARAM_BEGIN
+    /// [`id::NodeCommand::PARAM_END`]: protocol::id::NodeCommand::PARAM_END
+    pub fn begin_params(&mut self) {
+        self.batch += 1;
+    }
+
+    /// Commit a batch of parameter changes started with
+    /// [`ClientNode::begin_params`].
+    ///
+    /// The collected changes are sent the next time the stream is run.
+    /// Committing without an open batch schedules an update of any pending
+    /// changes.
+    pub fn commit(&mut self) {
+        self.batch = self.batch.saturating_sub(1);
+
+        if self.batch == 0 {
+            self.committed = true;
+        }
+    }
+
+    /// Test if the node has an open batch of parameter changes.
+    #[inline]
+    pub fn is_batching(&self) -> bool {
+        self.batch > 0
+    }
+
+    /// Set the amount of time the node is allowed to stay active without any
+    /// links before it is automatically deactivated.
+    ///
+    /// The node is re-activated as soon as a link to it appears. Setting this
+    /// to `None` disables the policy.
+    ///
+    /// Idle nodes are checked each time the stream is run, see
+    /// [`IdlePolicy`].
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle.set_timeout(timeout);
+    }
+
+    /// Get the configured idle timeout.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle.timeout()
+    }
+
+    /// Test if the node has been deactivated due to inactivity.
+    pub fn is_suspended(&self) -> bool {
+        self.idle.is_suspended()
+    }
+
+    /// Get the number of links connected to this node.
+    pub fn links(&self) -> usize {
+        self.idle.links()
+    }
+
     pub fn duration(&self) -> Option<u64> {
         let io_position = &mut self.io_position.as_ref()?;
         Some(unsafe { volatile!(io_position, clock.duration).read() })
     }
 
+    /// Set the size in samples of the chunks produced by
+    /// [`ClientNode::process_chunks`].
+    ///
+    /// Setting this to `None` processes the whole quantum as a single chunk.
+    pub fn set_chunk_size(&mut self, size: Option<usize>) {
+        self.chunk_size = size;
+    }
+
+    /// Get the configured chunk size.
+    #[inline]
+    pub fn chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
+
+    /// Iterate over the chunks of the current cycle, as configured through
+    /// [`ClientNode::set_chunk_size`].
+    ///
+    /// This is empty if the clock duration of the node is not configured.
+    pub fn process_chunks(&self) -> ProcessChunks {
+        let duration = self.duration().unwrap_or_default();
+        let duration = usize::try_from(duration).unwrap_or(usize::MAX);
+        ProcessChunks::new(duration, self.chunk_size)
+    }
+
+    /// Set the policy used to shed load when processing repeatedly overruns
+    /// its budget.
+    ///
+    /// Setting this to `None` disables the policy. Any action which was
+    /// engaged by a previous policy is not released.
+    pub fn set_overload_policy(&mut self, policy: Option<OverloadPolicy>) {
+        self.overload = policy.map(OverloadState::new);
+    }
+
+    /// Test if processing should be skipped and silence emitted since the
+    /// node is overloaded.
+    ///
+    /// This is only the case if an [`OverloadAction::Silence`] policy is
+    /// engaged.
+    #[inline]
+    pub fn is_shedding(&self) -> bool {
+        self.overload
+            .as_ref()
+            .and_then(OverloadState::engaged)
+            .is_some_and(|action| action == OverloadAction::Silence)
+    }
+
+    /// Set the detector used to latch clip and overload indicators of the
+    /// channels processed by this node.
+    ///
+    /// Every latch is reported once through [`StreamEvent::Clip`], and latched
+    /// indicators are reflected in the [`Stats`] of the node.
+    ///
+    /// [`StreamEvent::Clip`]: crate::events::StreamEvent::Clip
+    pub fn set_clip_detector(&mut self, detector: Option<ClipDetector>) {
+        if let Some(old) = &mut self.clip {
+            old.reset_all(&mut self.stats);
+        }
+
+        self.clip = detector;
+    }
+
+    /// Access the clip detector of the node.
+    #[inline]
+    pub fn clip_detector(&self) -> Option<&ClipDetector> {
+        self.clip.as_ref()
+    }
+
+    /// Detect clipping in the samples of a channel processed in the current
+    /// cycle, returning `true` if this latched its clip indicator.
+    ///
+    /// This does nothing if no detector is set, see
+    /// [`ClientNode::set_clip_detector`].
+    pub fn detect_clip(&mut self, channel: usize, samples: &[f32]) -> bool {
+        let nsec = self.clip_nsec();
+
+        let Some(clip) = &mut self.clip else {
+            return false;
+        };
+
+        clip.process(channel, samples, nsec, &mut self.stats)
+    }
+
+    /// Detect whether a stage of the channel strip of a channel overloaded
+    /// in the current cycle, returning `true` if this latched its overload
+    /// indicator.
+    ///
+    /// This does nothing if no detector is set, see
+    /// [`ClientNode::set_clip_detector`].
+    pub fn detect_overload(&mut self, channel: usize, strip: &ChannelStrip) -> bool {
+        let nsec = self.clip_nsec();
+
+        let Some(clip) = &mut self.clip else {
+            return false;
+        };
+
+        clip.observe_strip(channel, strip, nsec, &mut self.stats)
+    }
+
+    /// Reset the latched indicators of a channel.
+    pub fn reset_clip(&mut self, channel: usize) {
+        if let Some(clip) = &mut self.clip {
+            clip.reset(channel, &mut self.stats);
+        }
+    }
+
+    /// Add a channel strip which is owned by the node, returning its index.
+    ///
+    /// When the sample rate of the graph changes, the stream recalculates the
+    /// coefficients of every strip owned by the node and swaps them in before
+    /// [`StreamEvent::RateChanged`] is emitted. Strips which are not owned by
+    /// the node have to be updated manually, see
+    /// [`ChannelStrip::coefficients`].
+    ///
+    /// The strip is set to the current rate of the graph if it is known, and
+    /// space is reserved to process the current quantum of the graph.
+    ///
+    /// [`StreamEvent::RateChanged`]: crate::events::StreamEvent::RateChanged
+    pub fn add_strip(&mut self, mut strip: ChannelStrip) -> usize {
+        if let Some(rate) = self.rate
+            && strip.rate() != rate
+        {
+            strip.set_rate(rate);
+        }
+
+        if let Some(quantum) = self.quantum {
+            strip.reserve(usize::try_from(quantum).unwrap_or(usize::MAX));
+        }
+
+        // NB: Blocks are registered here so that timing strips while
+        // processing doesn't allocate.
+        if self.strip_timers.is_none() {
+            self.strip_timers = Some(StripTimers::register(&mut self.stats));
+        }
+
+        self.strips.push(strip);
+        self.strips.len() - 1
+    }
+
+    /// Access a channel strip owned by the node.
+    #[inline]
+    pub fn strip(&self, index: usize) -> Option<&ChannelStrip> {
+        self.strips.get(index)
+    }
+
+    /// Access a channel strip owned by the node mutably, like to process
+    /// samples through it.
+    #[inline]
+    pub fn strip_mut(&mut self, index: usize) -> Option<&mut ChannelStrip> {
+        self.strips.get_mut(index)
+    }
+
+    /// Remove all channel strips owned by the node.
+    pub fn clear_strips(&mut self) {
+        self.strips.clear();
+    }
+
+    /// Process a block of mono samples in place through a channel strip
+    /// owned by the node, accounting the CPU time of each of its blocks to
+    /// the statistics of the node.
+    ///
+    /// Returns `false` if there is no strip with the given index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::ChannelStrip;
+    /// use client::sim::Simulation;
+    ///
+    /// let mut sim = Simulation::new(48_000, 1024)?;
+    /// let node = sim.node_mut();
+    /// let strip = node.add_strip(ChannelStrip::new(48_000));
+    ///
+    /// let mut samples = [0.5f32; 128];
+    /// assert!(node.process_strip(strip, &mut samples));
+    /// assert!(!node.process_strip(strip + 1, &mut samples));
+    ///
+    /// let eq = node.stats().block("strip.eq").map(|b| b.count);
+    /// assert_eq!(eq, Some(1));
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn process_strip(&mut self, index: usize, samples: &mut [f32]) -> bool {
+        let (Some(strip), Some(timers)) = (self.strips.get_mut(index), &self.strip_timers) else {
+            return false;
+        };
+
+        strip.process_timed(samples, &mut self.stats, timers);
+        true
+    }
+
+    /// Write the node as a preset.
+    ///
+    /// A preset captures the properties of the node, the defaults of its
+    /// ports and a preset of every channel strip owned by the node, see
+    /// [`ChannelStrip::write_preset`], and can be restored with
+    /// [`ClientNode::read_preset`].
+    pub fn write_preset(&self, pod: Builder<impl Writer>) -> Result<(), Error> {
+        pod.write_struct(|st| {
+            st.field().write_sized(PRESET_VERSION)?;
+
+            for props in [&self.props, &self.port_props] {
+                st.field().write_struct(|st| {
+                    st.field().write_sized(props.len() as u32)?;
+
+                    for (key, value) in props.iter() {
+                        st.write((key, value))?;
+                    }
+
+                    Ok(())
+                })?;
+            }
+
+            st.field().write_struct(|strips| {
+                for strip in &self.strips {
+                    strip.write_preset(strips.field())?;
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Restore the node from a preset written by
+    /// [`ClientNode::write_preset`].
+    ///
+    /// The whole preset is validated before anything is applied, so the node
+    /// is left untouched if it is malformed or doesn't have one strip preset
+    /// for every strip owned by the node. The changes are applied as a single
+    /// batch, see [`ClientNode::begin_params`].
+    ///
+    /// Properties of the preset are set on the node and as defaults of its
+    /// ports, where they override the defaults of the stream when properties
+    /// are resolved, see [`Stream::resolve_node_props`]. Strips keep their processing state, so
+    /// changes to gains are ramped and changes to bypass states are
+    /// crossfaded.
+    ///
+    /// [`Stream::resolve_node_props`]: crate::Stream::resolve_node_props
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::ChannelStrip;
+    /// use client::sim::Simulation;
+    ///
+    /// let mut sim = Simulation::new(48_000, 1024)?;
+    /// let node = sim.node_mut();
+    /// let strip = node.add_strip(ChannelStrip::new(48_000));
+    ///
+    /// node.props.insert("node.description", "Saved");
+    ///
+    /// if let Some(strip) = node.strip_mut(strip) {
+    ///     strip.set_param("gain.level", -6.0)?;
+    /// }
+    ///
+    /// let mut pod = pod::dynamic();
+    /// node.write_preset(pod.as_mut())?;
+    ///
+    /// node.props.insert("node.description", "Changed");
+    /// node.clear_strips();
+    ///
+    /// // The preset has a strip which the node no longer has.
+    /// assert!(node.read_preset(pod.as_ref().read_struct()?).is_err());
+    /// assert_eq!(node.props.get("node.description"), Some("Changed"));
+    ///
+    /// let strip = node.add_strip(ChannelStrip::new(48_000));
+    /// node.read_preset(pod.as_ref().read_struct()?)?;
+    ///
+    /// assert_eq!(node.props.get("node.description"), Some("Saved"));
+    /// assert_eq!(node.strip(strip).and_then(|s| s.param("gain.level")), Some(-6.0));
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn read_preset(&mut self, st: Struct<impl AsSlice>) -> Result<()> {
+        let mut st = st.as_ref();
+
+        let version = st.field()?.read_sized::<i32>()?;
+        ensure!(
+            version == PRESET_VERSION,
+            "Unsupported preset version {version}"
+        );
+
+        let props = read_preset_props(st.field()?.read_struct()?)?;
+        let port_props = read_preset_props(st.field()?.read_struct()?)?;
+
+        let mut presets = st.field()?.read_struct()?;
+        let mut strips = Vec::with_capacity(self.strips.len());
+
+        for (index, strip) in self.strips.iter().enumerate() {
+            ensure!(!presets.is_empty(), "Preset is missing strip {index}");
+
+            let mut strip = strip.clone();
+            strip
+                .read_preset(presets.field()?.read_struct()?)
+                .with_context(|| format!("Strip {index}"))?;
+            strips.push(strip);
+        }
+
+        ensure!(
+            presets.is_empty(),
+            "Preset has more strips than the {} owned by the node",
+            self.strips.len()
+        );
+
+        self.begin_params();
+
+        for (key, value) in props {
+            self.props.insert(key, value);
+        }
+
+        for (key, value) in port_props {
+            self.port_props.insert(key, value);
+        }
+
+        for (strip, preset) in self.strips.iter_mut().zip(strips) {
+            *strip = preset;
+        }
+
+        self.commit();
+        Ok(())
+    }
+
+    /// Detect whether a stage of a channel strip owned by the node overloaded
+    /// in the current cycle, using the index of the strip as the channel.
+    ///
+    /// See [`ClientNode::detect_overload`].
+    pub fn detect_strip_overload(&mut self, index: usize) -> bool {
+        let nsec = self.clip_nsec();
+
+        let (Some(clip), Some(strip)) = (&mut self.clip, self.strips.get(index)) else {
+            return false;
+        };
+
+        clip.observe_strip(index, strip, nsec, &mut self.stats)
+    }
+
+    /// The time indicators latch at, which is the time of the current cycle
+    /// or the monotonic time if the clock is not available.
+    fn clip_nsec(&self) -> u64 {
+        match self.clock_time() {
+            Some(clock) => clock.nsec(),
+            None => utils::get_monotonic_nsec().unwrap_or_default(),
+        }
+    }
+
+    /// Get a snapshot of the graph clock for the current cycle.
+    ///
+    /// This can be used to convert between sample positions and monotonic
+    /// timestamps, see [`ClockTime`].
+    pub fn clock_time(&self) -> Option<ClockTime> {
+        let io_position = self.io_position.as_ref()?;
+        let clock = unsafe { volatile!(io_position, clock).read() };
+        ClockTime::from_io_clock(&clock)
+    }
+
+    /// Get the sample rate of the graph as observed during the last processing
+    /// cycle.
+    ///
+    /// Changes to the rate are reported through
+    /// [`StreamEvent::RateChanged`].
+    ///
+    /// [`StreamEvent::RateChanged`]: crate::events::StreamEvent::RateChanged
+    #[inline]
+    pub fn rate(&self) -> Option<u32> {
+        self.rate
+    }
+
+    /// Get the quantum of the graph in samples as observed during the last
+    /// processing cycle.
+    ///
+    /// Changes to the quantum are reported through
+    /// [`StreamEvent::QuantumChanged`].
+    ///
+    /// [`StreamEvent::QuantumChanged`]: crate::events::StreamEvent::QuantumChanged
+    #[inline]
+    pub fn quantum(&self) -> Option<u64> {
+        self.quantum
+    }
+
     /// Start processing for this node.
     pub fn start_process(&mut self) -> Result<()> {
+        self.reader.enter();
         self.then = utils::get_monotonic_nsec()?;
+        self.then_cpu = utils::get_thread_cpu_nsec()?;
 
         let Some(na) = &mut self.activation else {
             bail!("Missing activation area for node {}", self.id);
@@ -219,11 +929,16 @@ impl ClientNode {
 
     /// End processing for this node.
     pub fn end_process(&mut self) -> Result<()> {
+        self.reader.leave();
+
+        let driving = self.is_driving();
+
         let Some(na) = &mut self.activation else {
             bail!("Missing activation area for node {}", self.id);
         };
 
         let now = utils::get_monotonic_nsec()?;
+        let now_cpu = utils::get_thread_cpu_nsec()?;
 
         unsafe {
             let was_awake = unsafe {
@@ -231,6 +946,10 @@ impl ClientNode {
             };
 
             if was_awake {
+                if driving {
+                    signal_ready(self.write_fd.as_ref(), &mut self.stats);
+                }
+
                 for a in &mut self.peer_activations {
                     unsafe {
                         let signaled = a.trigger(now)?;
@@ -246,16 +965,115 @@ impl ClientNode {
                 }
             }
 
-            self.stats.timing_sum += now.saturating_sub(self.then);
+            let elapsed = now.saturating_sub(self.then);
+            self.stats.timing_sum += elapsed;
             self.stats.timing_count += 1;
+            self.stats.cpu_sum += now_cpu.saturating_sub(self.then_cpu);
+
+            if let Some(overload) = &mut self.overload
+                && let Some(budget) = cycle_budget(self.io_position.as_ref())
+            {
+                overload.observe(elapsed as f64 / budget as f64);
+            }
 
             let prev_finish_time = volatile!(na, finish_time).replace(self.then);
             volatile!(na, prev_finish_time).write(prev_finish_time);
         }
 
+        if let Some(clock) = self.clock_time()
+            && self.rate != Some(clock.rate())
+        {
+            let previous = self.rate.replace(clock.rate());
+            self.rate_change = Some((previous, clock.rate()));
+        }
+
+        if let Some(quantum) = self.duration()
+            && self.quantum != Some(quantum)
+        {
+            let previous = self.quantum.replace(quantum);
+            self.quantum_change = Some((previous, quantum));
+        }
+
         Ok(())
     }
 
+    /// Test if this node is the driver of the graph it is part of.
+    pub fn is_driving(&self) -> bool {
+        let (Some(global_id), Some(io_position)) = (self.global_id, &self.io_position) else {
+            return false;
+        };
+
+        let id = unsafe { volatile!(io_position, clock.id).read() };
+        id == global_id.into_u32()
+    }
+
+    /// Start a cycle scheduled by a [`CycleScheduler`] if this node is the
+    /// driver of the graph it is part of.
+    ///
+    /// This publishes the clock of the cycle in the position area of the graph
+    /// and signals the node, which causes the server to start processing the
+    /// graph. Returns `false` if the node is not driving.
+    ///
+    /// [`CycleScheduler`]: crate::CycleScheduler
+    pub fn drive(&mut self, cycle: &Cycle) -> Result<bool> {
+        if !self.is_driving() {
+            return Ok(false);
+        }
+
+        let Some(io_position) = &mut self.io_position else {
+            return Ok(false);
+        };
+
+        unsafe {
+            volatile!(io_position, clock.nsec).write(cycle.nsec);
+            volatile!(io_position, clock.rate).write(ffi::Fraction {
+                num: 1,
+                denom: cycle.rate,
+            });
+            volatile!(io_position, clock.position).write(cycle.position);
+            volatile!(io_position, clock.duration).write(cycle.duration);
+            volatile!(io_position, clock.rate_diff).write(cycle.rate_diff);
+            volatile!(io_position, clock.read_nsec).write(cycle.next_nsec);
+            volatile!(io_position, clock.cycle).write(cycle.cycle);
+            volatile!(io_position, clock.xrun).write(cycle.xrun);
+        }
+
+        signal_ready(self.write_fd.as_ref(), &mut self.stats);
+        Ok(true)
+    }
+
+    /// Signal that the node has drained, which means that it has no more data
+    /// to produce.
+    ///
+    /// This updates the status of the activation area and notifies the server
+    /// through the write file descriptor of the node.
+    pub fn signal_drained(&mut self) -> Result<()> {
+        let Some(na) = &mut self.activation else {
+            bail!("Missing activation area for node {}", self.id);
+        };
+
+        unsafe {
+            volatile!(na, state[0].status).write(Status::DRAINED);
+        }
+
+        signal_ready(self.write_fd.as_ref(), &mut self.stats);
+        Ok(())
+    }
+
+    /// Get the oldest epoch which might still be observed by the processing
+    /// cycle of this node, which is entered through
+    /// [`ClientNode::start_process`] and left through
+    /// [`ClientNode::end_process`].
+    #[inline]
+    pub(crate) fn observed_epoch(&self) -> u64 {
+        self.reader.observed()
+    }
+
+    /// Access statistics for this node.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
     /// Access statistics mutably for this node.
     pub fn stats_mut(&mut self) -> &mut Stats {
         &mut self.stats
@@ -317,9 +1135,168 @@ impl ClientNode {
         active_driver_id.write(id);
     }
 
+    /// Reset the state which is tied to the connection to the server, so
+    /// that the node and its ports are sent again in full once it has been
+    /// re-created.
+    ///
+    /// Memory regions must already have been freed.
+    pub(super) fn reset_connection(&mut self) {
+        self.global_id = None;
+        self.read_fd = None;
+        self.write_fd = None;
+        self.idle.reset();
+        self.modified = true;
+        self.props.mark_modified();
+        self.params.mark_modified();
+
+        for port in self.ports.iter_mut() {
+            port.mix_info = PortMixInfo::default();
+            port.props.mark_modified();
+            port.params.mark_modified();
+        }
+    }
+
+    /// Remove a property of the node, which is also cleared on the server
+    /// with the next update.
+    ///
+    /// Returns `true` if the property was removed.
+    pub(super) fn remove_prop(&mut self, key: &Prop) -> bool {
+        if self.props.remove(key.as_str()).is_none() {
+            return false;
+        }
+
+        if !self
+            .removed_props
+            .iter()
+            .any(|k| k.as_str() == key.as_str())
+        {
+            self.removed_props.push(Symbol::new(key.as_str()));
+        }
+
+        true
+    }
+
     /// Take and return the modified state of the node.
     #[inline]
     pub(super) fn take_modified(&mut self) -> bool {
-        mem::take(&mut self.modified)
+        let params = self.params.take_modified();
+        mem::take(&mut self.modified) || params || self.props.is_modified()
     }
+
+    /// Test if the properties of the node or any of its ports have been
+    /// modified.
+    pub(super) fn has_modified_props(&self) -> bool {
+        self.props.is_modified()
+            || self.ports.inputs().iter().any(|p| p.props.is_modified())
+            || self.ports.outputs().iter().any(|p| p.props.is_modified())
+    }
+
+    /// Take the oldest decision of the overload policy which has not been
+    /// reported, and the number of decisions which were dropped before it.
+    #[inline]
+    pub(super) fn take_overload_decision(&mut self) -> Option<(OverloadDecision, u32)> {
+        self.overload.as_mut()?.take_pending()
+    }
+
+    /// Take the next latched clip indicator which has not been reported.
+    #[inline]
+    pub(super) fn take_clip_latch(&mut self) -> Option<ClipLatch> {
+        self.clip.as_mut()?.take_latched()
+    }
+
+    /// Take the last change in sample rate which has not been reported, as the
+    /// previous and the new rate.
+    ///
+    /// This rebuilds the coefficients of the strips owned by the node for the
+    /// new rate.
+    pub(super) fn take_rate_change(&mut self) -> Option<(Option<u32>, u32)> {
+        let (previous, rate) = self.rate_change.take()?;
+
+        for strip in &mut self.strips {
+            let coefficients = strip.coefficients(rate);
+
+            // NB: Parameters can't change between calculating and swapping in
+            // the coefficients since the strip is exclusively borrowed, so
+            // they are never stale.
+            _ = strip.swap_coefficients(coefficients);
+        }
+
+        Some((previous, rate))
+    }
+
+    /// Take the last change in quantum which has not been reported, as the
+    /// previous and the new quantum.
+    ///
+    /// This reserves space in the strips owned by the node to process the
+    /// new quantum without allocating.
+    pub(super) fn take_quantum_change(&mut self) -> Option<(Option<u64>, u64)> {
+        let (previous, quantum) = self.quantum_change.take()?;
+        let samples = usize::try_from(quantum).unwrap_or(usize::MAX);
+
+        for strip in &mut self.strips {
+            strip.reserve(samples);
+        }
+
+        Some((previous, quantum))
+    }
+
+    /// Take and return whether a batch of parameter changes has been
+    /// committed.
+    #[inline]
+    pub(super) fn take_committed(&mut self) -> bool {
+        mem::take(&mut self.committed)
+    }
+}
+
+/// Calculate the budget of the current cycle in nanoseconds.
+fn cycle_budget(io_position: Option<&Region<ffi::IoPosition>>) -> Option<u64> {
+    let io_position = io_position?;
+
+    let (duration, rate) = unsafe {
+        (
+            volatile!(io_position, clock.duration).read(),
+            volatile!(io_position, clock.rate).read(),
+        )
+    };
+
+    if rate.denom == 0 {
+        return None;
+    }
+
+    let budget =
+        u128::from(duration) * u128::from(rate.num) * 1_000_000_000 / u128::from(rate.denom);
+    u64::try_from(budget).ok().filter(|&budget| budget > 0)
+}
+
+/// Read the properties of a preset written by [`ClientNode::write_preset`].
+fn read_preset_props(mut st: Struct<Slice<'_>>) -> Result<Vec<(&str, &str)>> {
+    let n_items = st.read::<u32>()?;
+    let mut props = Vec::new();
+
+    for _ in 0..n_items {
+        props.push(st.read::<(&str, &str)>()?);
+    }
+
+    Ok(props)
+}
+
+/// Notify the server through the write file descriptor of a node.
+///
+/// This is what tells the server that a driving node has finished its cycle,
+/// without which the graph would not make progress.
+fn signal_ready(write_fd: Option<&EventFd>, stats: &mut Stats) {
+    let Some(write_fd) = write_fd else {
+        return;
+    };
+
+    if matches!(write_fd.write(1), Ok(true)) {
+        stats.ready_ok += 1;
+    } else {
+        stats.ready_error += 1;
+    }
+}
+
+/// Parse a numerical property, where zero means that it's unset.
+fn parse_nonzero(value: &str) -> Option<u32> {
+    value.parse().ok().filter(|value| *value != 0)
 }
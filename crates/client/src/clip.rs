@@ -0,0 +1,260 @@
+use alloc::vec::Vec;
+
+use crate::stats::earliest;
+use crate::{ChannelStrip, Stats, StripBlock};
+
+/// The kind of a latched clip indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClipKind {
+    /// Consecutive full-scale samples were detected in the signal.
+    Clip,
+    /// The output of a block of a channel strip exceeded full scale.
+    Overload(StripBlock),
+}
+
+/// A clip indicator which has latched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ClipLatch {
+    /// The channel the indicator belongs to.
+    pub channel: usize,
+    /// What caused the indicator to latch.
+    pub kind: ClipKind,
+    /// The monotonic time in nanoseconds at which it latched.
+    pub nsec: u64,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Channel {
+    run: usize,
+    clip: Option<u64>,
+    overload: Option<(StripBlock, u64)>,
+}
+
+/// Detects digital clipping and overloaded processing stages per channel.
+///
+/// Clipping is detected as a number of consecutive samples at or above full
+/// scale, which is how it's distinguished from a legitimate full-scale peak.
+///
+/// Indicators latch the first time they trigger and stay latched with the
+/// time of that first occurrence until they are reset, so every latch is
+/// reported exactly once through [`ClipDetector::take_latched`].
+///
+/// Latched indicators of the first 64 channels are reflected in the
+/// `clip_*` and `overload_*` fields of [`Stats`].
+///
+/// # Examples
+///
+/// ```
+/// use client::{ClipDetector, ClipKind, Stats};
+///
+/// let mut stats = Stats::default();
+/// let mut clip = ClipDetector::new(2).with_run(3);
+///
+/// clip.process(0, &[0.5, 1.0, -1.0, 0.2], 1000, &mut stats);
+/// assert_eq!(clip.clip(0), None);
+///
+/// clip.process(1, &[1.0, 1.0], 1000, &mut stats);
+/// clip.process(1, &[1.0, 0.5], 2000, &mut stats);
+/// assert_eq!(clip.clip(1), Some(2000));
+/// assert_eq!(stats.clip_latched, 0b10);
+///
+/// let latch = clip.take_latched().unwrap();
+/// assert_eq!((latch.channel, latch.kind, latch.nsec), (1, ClipKind::Clip, 2000));
+/// assert!(clip.take_latched().is_none());
+///
+/// // Latched indicators are only reported once.
+/// clip.process(1, &[1.0; 8], 3000, &mut stats);
+/// assert!(clip.take_latched().is_none());
+///
+/// clip.reset(1, &mut stats);
+/// assert_eq!(stats.clip_latched, 0);
+///
+/// // Channels which do not exist are ignored.
+/// assert!(!clip.process(2, &[1.0; 8], 4000, &mut stats));
+/// ```
+#[derive(Clone)]
+pub struct ClipDetector {
+    threshold: f32,
+    run: usize,
+    channels: Vec<Channel>,
+    pending: Vec<ClipLatch>,
+}
+
+impl ClipDetector {
+    /// Construct a detector for the given number of channels.
+    ///
+    /// By default a clip is detected once 3 consecutive samples are at or
+    /// above full scale.
+    ///
+    /// Any number of channels is supported, but since [`Stats`] holds latched
+    /// indicators as 64-bit masks, indicators of channels beyond the first 64
+    /// are only reported through [`ClipDetector::take_latched`] and accessors
+    /// like [`ClipDetector::clip`].
+    pub fn new(channels: usize) -> Self {
+        Self {
+            threshold: 1.0,
+            run: 3,
+            channels: alloc::vec![Channel::default(); channels],
+            // NB: Each channel can latch at most twice before it's reset.
+            pending: Vec::with_capacity(channels * 2),
+        }
+    }
+
+    /// Set the absolute level at or above which a sample is considered to be
+    /// at full scale.
+    pub fn with_threshold(self, threshold: f32) -> Self {
+        Self { threshold, ..self }
+    }
+
+    /// Set the number of consecutive full-scale samples which are considered
+    /// a clip.
+    pub fn with_run(self, run: usize) -> Self {
+        Self {
+            run: run.max(1),
+            ..self
+        }
+    }
+
+    /// The number of channels of the detector.
+    #[inline]
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Process the samples of a channel at the monotonic time `nsec`,
+    /// returning `true` if this latched its clip indicator.
+    ///
+    /// Runs of full-scale samples are tracked across calls. Channels which do
+    /// not exist are ignored.
+    pub fn process(
+        &mut self,
+        channel: usize,
+        samples: &[f32],
+        nsec: u64,
+        stats: &mut Stats,
+    ) -> bool {
+        let Some(state) = self.channels.get_mut(channel) else {
+            return false;
+        };
+
+        for sample in samples {
+            if sample.abs() >= self.threshold {
+                state.run += 1;
+            } else {
+                state.run = 0;
+            }
+
+            if state.run >= self.run && state.clip.is_none() {
+                state.clip = Some(nsec);
+
+                self.pending.push(ClipLatch {
+                    channel,
+                    kind: ClipKind::Clip,
+                    nsec,
+                });
+
+                Self::sync(&self.channels, stats);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Observe the channel strip of a channel after it has processed samples
+    /// at the monotonic time `nsec`, returning `true` if this latched its
+    /// overload indicator.
+    ///
+    /// Channels which do not exist are ignored.
+    pub fn observe_strip(
+        &mut self,
+        channel: usize,
+        strip: &ChannelStrip,
+        nsec: u64,
+        stats: &mut Stats,
+    ) -> bool {
+        let Some(state) = self.channels.get_mut(channel) else {
+            return false;
+        };
+
+        let Some(block) = strip.overloaded() else {
+            return false;
+        };
+
+        if state.overload.is_some() {
+            return false;
+        }
+
+        state.overload = Some((block, nsec));
+
+        self.pending.push(ClipLatch {
+            channel,
+            kind: ClipKind::Overload(block),
+            nsec,
+        });
+
+        Self::sync(&self.channels, stats);
+        true
+    }
+
+    /// The time at which the clip indicator of a channel latched.
+    pub fn clip(&self, channel: usize) -> Option<u64> {
+        self.channels.get(channel)?.clip
+    }
+
+    /// The block and time at which the overload indicator of a channel
+    /// latched.
+    pub fn overload(&self, channel: usize) -> Option<(StripBlock, u64)> {
+        self.channels.get(channel)?.overload
+    }
+
+    /// Reset the indicators of a channel.
+    pub fn reset(&mut self, channel: usize, stats: &mut Stats) {
+        if let Some(state) = self.channels.get_mut(channel) {
+            *state = Channel::default();
+        }
+
+        self.pending.retain(|latch| latch.channel != channel);
+        Self::sync(&self.channels, stats);
+    }
+
+    /// Reset the indicators of all channels.
+    pub fn reset_all(&mut self, stats: &mut Stats) {
+        self.channels.fill(Channel::default());
+        self.pending.clear();
+        Self::sync(&self.channels, stats);
+    }
+
+    /// Take the next latch which has not been reported.
+    pub fn take_latched(&mut self) -> Option<ClipLatch> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        Some(self.pending.remove(0))
+    }
+
+    /// Reflect latched indicators in the statistics.
+    fn sync(channels: &[Channel], stats: &mut Stats) {
+        stats.clip_latched = 0;
+        stats.clip_nsec = 0;
+        stats.overload_latched = 0;
+        stats.overload_nsec = 0;
+
+        // NB: Channels which don't fit in the masks are only reported through
+        // their latches.
+        for (n, state) in channels.iter().enumerate().take(64) {
+            if let Some(nsec) = state.clip {
+                stats.clip_latched |= 1 << n;
+                stats.clip_nsec = earliest(stats.clip_nsec, nsec);
+            }
+
+            if let Some((_, nsec)) = state.overload {
+                stats.overload_latched |= 1 << n;
+                stats.overload_nsec = earliest(stats.overload_nsec, nsec);
+            }
+        }
+    }
+}
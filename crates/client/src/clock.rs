@@ -0,0 +1,155 @@
+use protocol::ffi;
+
+const NSEC_PER_SEC: u128 = 1_000_000_000;
+
+/// A snapshot of a graph clock, used to convert between clock domains.
+///
+/// The graph clock relates a sample position at its own rate to a
+/// `CLOCK_MONOTONIC` timestamp in nanoseconds, which is the time domain used
+/// by [`utils::get_monotonic_nsec`]. Since the clock of the driver might drift
+/// compared to the monotonic clock, conversions take the rate difference
+/// reported by the driver into account, which makes them agree with the
+/// accounting of PipeWire itself.
+///
+/// This is constructed for the current cycle through
+/// [`ClientNode::clock_time`].
+///
+/// [`utils::get_monotonic_nsec`]: crate::utils::get_monotonic_nsec
+/// [`ClientNode::clock_time`]: crate::ClientNode::clock_time
+///
+/// # Examples
+///
+/// ```
+/// use client::ClockTime;
+///
+/// // A 48kHz clock at position 48000 at 10 seconds of monotonic time.
+/// let clock = ClockTime::new(10_000_000_000, 48_000, 48_000);
+///
+/// assert_eq!(clock.position_to_nsec(96_000), 11_000_000_000);
+/// assert_eq!(clock.nsec_to_position(10_500_000_000), 72_000);
+/// assert_eq!(clock.position_at_rate(96_000, 44_100), 88_200);
+///
+/// // The driver clock is running 1% fast compared to monotonic time.
+/// let clock = clock.with_rate_diff(1.01);
+/// assert_eq!(clock.nsec_to_position(11_000_000_000), 96_480);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockTime {
+    nsec: u64,
+    position: u64,
+    rate: u32,
+    rate_diff: f64,
+}
+
+impl ClockTime {
+    /// Construct a new clock snapshot where `position` at the sample `rate`
+    /// happened at the monotonic timestamp `nsec`.
+    pub fn new(nsec: u64, position: u64, rate: u32) -> Self {
+        Self {
+            nsec,
+            position,
+            rate,
+            rate_diff: 1.0,
+        }
+    }
+
+    /// Construct a clock snapshot from a shared clock area.
+    ///
+    /// Returns `None` if the rate of the clock is not configured.
+    pub fn from_io_clock(clock: &ffi::IoClock) -> Option<Self> {
+        if clock.rate.num == 0 || clock.rate.denom == 0 {
+            return None;
+        }
+
+        let rate = clock.rate.denom / clock.rate.num;
+
+        Some(Self {
+            nsec: clock.nsec,
+            position: clock.position,
+            rate,
+            rate_diff: clock.rate_diff,
+        })
+    }
+
+    /// Set the rate difference between the clock and monotonic time, as a
+    /// ratio of clock speeds.
+    ///
+    /// A value above `1.0` means that the clock advances faster than monotonic
+    /// time.
+    pub fn with_rate_diff(self, rate_diff: f64) -> Self {
+        Self { rate_diff, ..self }
+    }
+
+    /// The monotonic timestamp in nanoseconds of the snapshot.
+    #[inline]
+    pub fn nsec(&self) -> u64 {
+        self.nsec
+    }
+
+    /// The sample position of the snapshot.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The sample rate of the clock.
+    #[inline]
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// The rate difference between the clock and monotonic time.
+    #[inline]
+    pub fn rate_diff(&self) -> f64 {
+        self.rate_diff
+    }
+
+    /// Convert a sample position of the clock into a monotonic timestamp in
+    /// nanoseconds.
+    pub fn position_to_nsec(&self, position: u64) -> u64 {
+        let samples = position as i128 - self.position as i128;
+        let nsec = samples_to_nsec(samples, self.rate) as f64 / self.rate_diff;
+        add_signed(self.nsec, nsec as i128)
+    }
+
+    /// Convert a monotonic timestamp in nanoseconds into a sample position of
+    /// the clock.
+    pub fn nsec_to_position(&self, nsec: u64) -> u64 {
+        let elapsed = nsec as i128 - self.nsec as i128;
+        let elapsed = (elapsed as f64 * self.rate_diff) as i128;
+        add_signed(self.position, nsec_to_samples(elapsed, self.rate))
+    }
+
+    /// Convert a sample position of the clock into a position at another
+    /// sample rate.
+    pub fn position_at_rate(&self, position: u64, rate: u32) -> u64 {
+        if self.rate == 0 {
+            return 0;
+        }
+
+        let position = u128::from(position) * u128::from(rate) / u128::from(self.rate);
+        u64::try_from(position).unwrap_or(u64::MAX)
+    }
+}
+
+/// Convert a number of samples at the given rate into nanoseconds.
+#[inline]
+fn samples_to_nsec(samples: i128, rate: u32) -> i128 {
+    if rate == 0 {
+        return 0;
+    }
+
+    samples * NSEC_PER_SEC as i128 / i128::from(rate)
+}
+
+/// Convert a number of nanoseconds into samples at the given rate.
+#[inline]
+fn nsec_to_samples(nsec: i128, rate: u32) -> i128 {
+    nsec * i128::from(rate) / NSEC_PER_SEC as i128
+}
+
+#[inline]
+fn add_signed(base: u64, offset: i128) -> u64 {
+    let value = i128::from(base).saturating_add(offset);
+    u64::try_from(value.max(0)).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,487 @@
+use core::fmt;
+use core::time::Duration;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
+
+use anyhow::{Result, ensure};
+use protocol::TimerFd;
+
+use crate::ClockTime;
+use crate::utils;
+
+const NSEC_PER_SEC: f64 = 1_000_000_000.0;
+
+/// How quickly the rate difference estimated by an [`FdClock`] follows the
+/// measured one.
+const RATE_DIFF_SMOOTHING: f64 = 0.05;
+
+/// A wakeup of a [`ClockSource`], which starts a new cycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct ClockTick {
+    /// The monotonic time of the wakeup in nanoseconds.
+    pub nsec: u64,
+    /// The estimated monotonic time of the next wakeup in nanoseconds.
+    pub next_nsec: u64,
+    /// The rate difference between the source and monotonic time, as a ratio
+    /// of clock speeds.
+    pub rate_diff: f64,
+    /// The number of cycles which were missed since the previous wakeup.
+    pub missed: u64,
+}
+
+/// A source of timing for the cycles started when driving the graph.
+///
+/// A clock source is configured with the quantum and rate of the cycles it
+/// should produce, and is woken up either when its file descriptor becomes
+/// readable or, for sources without one, whenever the application sees fit.
+pub trait ClockSource {
+    /// The name of the clock source, like `timerfd` or the name of a device.
+    fn name(&self) -> &str;
+
+    /// The file descriptor to poll for wakeups, if the source has one.
+    fn as_raw_fd(&self) -> Option<RawFd>;
+
+    /// Configure the quantum in samples and the rate of the cycles produced
+    /// by the source.
+    fn configure(&mut self, quantum: u64, rate: u32) -> Result<()>;
+
+    /// Handle a wakeup of the source, returning a tick if a new cycle should
+    /// be started.
+    fn wakeup(&mut self) -> Result<Option<ClockTick>>;
+
+    /// Feed a position update of another driver, which is ignored by sources
+    /// which don't follow one.
+    #[inline]
+    fn follow(&mut self, clock: ClockTime) {
+        _ = clock;
+    }
+}
+
+/// The duration of a quantum at the given rate in nanoseconds.
+fn quantum_nsec(quantum: u64, rate: u32) -> u64 {
+    if rate == 0 {
+        return 0;
+    }
+
+    (quantum as u128 * 1_000_000_000 / rate as u128) as u64
+}
+
+/// A clock source driven by a `CLOCK_MONOTONIC` timer.
+///
+/// Since the timer is the reference clock, its rate difference is always
+/// `1.0`.
+pub struct TimerClock {
+    timer: TimerFd,
+    period: u64,
+}
+
+impl TimerClock {
+    /// Construct a new timer clock source.
+    pub fn new() -> io::Result<Self> {
+        let timer = TimerFd::new()?;
+        timer.set_nonblocking(true)?;
+        Ok(Self { timer, period: 0 })
+    }
+}
+
+impl ClockSource for TimerClock {
+    #[inline]
+    fn name(&self) -> &str {
+        "timerfd"
+    }
+
+    #[inline]
+    fn as_raw_fd(&self) -> Option<RawFd> {
+        Some(self.timer.as_raw_fd())
+    }
+
+    fn configure(&mut self, quantum: u64, rate: u32) -> Result<()> {
+        let period = quantum_nsec(quantum, rate);
+        ensure!(period > 0, "Invalid quantum {quantum} at rate {rate}");
+        self.timer.set_interval(Duration::from_nanos(period))?;
+        self.period = period;
+        Ok(())
+    }
+
+    fn wakeup(&mut self) -> Result<Option<ClockTick>> {
+        let Some(expirations) = self.timer.read()? else {
+            return Ok(None);
+        };
+
+        let nsec = utils::get_monotonic_nsec()?;
+
+        Ok(Some(ClockTick {
+            nsec,
+            next_nsec: nsec + self.period,
+            rate_diff: 1.0,
+            missed: expirations.saturating_sub(1),
+        }))
+    }
+}
+
+impl fmt::Debug for TimerClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerClock")
+            .field("period", &self.period)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A clock source driven by the wakeups of a device, like an ALSA PCM.
+///
+/// The file descriptor is provided by the user, and becomes readable whenever
+/// the device has processed a period. When it does, the `ack` callback is
+/// called to acknowledge the wakeup and report the position of the device in
+/// frames, or `None` if the wakeup was spurious. The rate difference between
+/// the device and monotonic time is estimated from the reported positions.
+pub struct FdClock<F> {
+    name: String,
+    fd: OwnedFd,
+    ack: F,
+    quantum: u64,
+    rate: u32,
+    last: Option<(u64, u64)>,
+    rate_diff: f64,
+}
+
+impl<F> FdClock<F>
+where
+    F: FnMut(BorrowedFd<'_>) -> io::Result<Option<u64>>,
+{
+    /// Construct a new clock source from a device file descriptor.
+    pub fn new(name: &str, fd: OwnedFd, ack: F) -> Self {
+        Self {
+            name: String::from(name),
+            fd,
+            ack,
+            quantum: 0,
+            rate: 0,
+            last: None,
+            rate_diff: 1.0,
+        }
+    }
+}
+
+impl<F> ClockSource for FdClock<F>
+where
+    F: FnMut(BorrowedFd<'_>) -> io::Result<Option<u64>>,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    fn as_raw_fd(&self) -> Option<RawFd> {
+        Some(self.fd.as_raw_fd())
+    }
+
+    fn configure(&mut self, quantum: u64, rate: u32) -> Result<()> {
+        ensure!(
+            quantum > 0 && rate > 0,
+            "Invalid quantum {quantum} at rate {rate}"
+        );
+
+        self.quantum = quantum;
+        self.rate = rate;
+        self.last = None;
+        self.rate_diff = 1.0;
+        Ok(())
+    }
+
+    fn wakeup(&mut self) -> Result<Option<ClockTick>> {
+        let Some(frames) = (self.ack)(self.fd.as_fd())? else {
+            return Ok(None);
+        };
+
+        let nsec = utils::get_monotonic_nsec()?;
+        let mut missed = 0;
+
+        if let Some((last_nsec, last_frames)) = self.last.replace((nsec, frames))
+            && nsec > last_nsec
+            && frames > last_frames
+        {
+            let elapsed = frames - last_frames;
+            let measured =
+                (elapsed as f64 / self.rate as f64) / ((nsec - last_nsec) as f64 / NSEC_PER_SEC);
+            self.rate_diff += (measured - self.rate_diff) * RATE_DIFF_SMOOTHING;
+            missed = (elapsed / self.quantum).saturating_sub(1);
+        }
+
+        let period = quantum_nsec(self.quantum, self.rate) as f64 / self.rate_diff;
+
+        Ok(Some(ClockTick {
+            nsec,
+            next_nsec: nsec + period as u64,
+            rate_diff: self.rate_diff,
+            missed,
+        }))
+    }
+}
+
+impl<F> fmt::Debug for FdClock<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FdClock")
+            .field("name", &self.name)
+            .field("fd", &self.fd)
+            .field("rate_diff", &self.rate_diff)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A clock source slaved to the position updates of another driver.
+///
+/// Positions are fed through [`ClockSource::follow`], typically from
+/// [`ClientNode::clock_time`] of a node which follows the other driver, and
+/// every update which advances the position results in a tick. Since the
+/// source has no file descriptor, it should be woken up after it has been
+/// fed.
+///
+/// [`ClientNode::clock_time`]: crate::ClientNode::clock_time
+#[derive(Debug, Default)]
+pub struct FollowerClock {
+    pending: Option<ClockTime>,
+    last: Option<u64>,
+    quantum: u64,
+}
+
+impl FollowerClock {
+    /// Construct a new follower clock source.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ClockSource for FollowerClock {
+    #[inline]
+    fn name(&self) -> &str {
+        "follower"
+    }
+
+    #[inline]
+    fn as_raw_fd(&self) -> Option<RawFd> {
+        None
+    }
+
+    fn configure(&mut self, quantum: u64, rate: u32) -> Result<()> {
+        ensure!(
+            quantum > 0 && rate > 0,
+            "Invalid quantum {quantum} at rate {rate}"
+        );
+
+        self.quantum = quantum;
+        Ok(())
+    }
+
+    #[inline]
+    fn follow(&mut self, clock: ClockTime) {
+        self.pending = Some(clock);
+    }
+
+    fn wakeup(&mut self) -> Result<Option<ClockTick>> {
+        let Some(clock) = self.pending.take() else {
+            return Ok(None);
+        };
+
+        let position = clock.position();
+
+        let missed = match self.last.replace(position) {
+            Some(last) if position <= last => return Ok(None),
+            Some(last) => ((position - last) / self.quantum.max(1)).saturating_sub(1),
+            None => 0,
+        };
+
+        Ok(Some(ClockTick {
+            nsec: clock.nsec(),
+            next_nsec: clock.position_to_nsec(position + self.quantum),
+            rate_diff: clock.rate_diff(),
+            missed,
+        }))
+    }
+}
+
+/// A cycle started by a [`CycleScheduler`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct Cycle {
+    /// The monotonic time the cycle started at in nanoseconds.
+    pub nsec: u64,
+    /// The estimated monotonic time of the next cycle in nanoseconds.
+    pub next_nsec: u64,
+    /// The position of the cycle in samples.
+    pub position: u64,
+    /// The duration of the cycle in samples.
+    pub duration: u64,
+    /// The rate of the cycle.
+    pub rate: u32,
+    /// The rate difference between the clock source and monotonic time.
+    pub rate_diff: f64,
+    /// The number of cycles started so far, which wraps around.
+    pub cycle: u32,
+    /// The accumulated duration of missed cycles in samples.
+    pub xrun: u64,
+}
+
+/// Schedules the cycles of a driving node from a [`ClockSource`].
+///
+/// The scheduler keeps the position of the graph continuous, also when the
+/// clock source is replaced or cycles are missed. Cycles are applied to a
+/// driving node through [`ClientNode::drive`].
+///
+/// [`ClientNode::drive`]: crate::ClientNode::drive
+///
+/// # Examples
+///
+/// ```
+/// use client::{ClockSource, ClockTime, CycleScheduler, FollowerClock};
+///
+/// let mut scheduler = CycleScheduler::new(Box::new(FollowerClock::new()), 256, 48_000)?;
+///
+/// scheduler.source_mut().follow(ClockTime::new(1_000_000, 0, 48_000));
+/// let cycle = scheduler.wakeup()?.expect("a cycle");
+/// assert_eq!(cycle.position, 0);
+/// assert_eq!(cycle.duration, 256);
+/// assert_eq!(cycle.nsec, 1_000_000);
+///
+/// // No new position has been observed.
+/// assert!(scheduler.wakeup()?.is_none());
+///
+/// // The followed driver skipped a cycle.
+/// scheduler.source_mut().follow(ClockTime::new(1_000_000, 512, 48_000));
+/// let cycle = scheduler.wakeup()?.expect("a cycle");
+/// assert_eq!(cycle.position, 512);
+/// assert_eq!(cycle.xrun, 256);
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+pub struct CycleScheduler {
+    source: Box<dyn ClockSource>,
+    quantum: u64,
+    rate: u32,
+    position: Option<u64>,
+    cycle: u32,
+    xrun: u64,
+}
+
+impl CycleScheduler {
+    /// Construct a new scheduler producing cycles of `quantum` samples at the
+    /// given rate from a clock source.
+    pub fn new(mut source: Box<dyn ClockSource>, quantum: u64, rate: u32) -> Result<Self> {
+        source.configure(quantum, rate)?;
+
+        Ok(Self {
+            source,
+            quantum,
+            rate,
+            position: None,
+            cycle: 0,
+            xrun: 0,
+        })
+    }
+
+    /// Access the clock source.
+    #[inline]
+    pub fn source(&self) -> &dyn ClockSource {
+        &*self.source
+    }
+
+    /// Access the clock source mutably, like to feed a [`FollowerClock`].
+    #[inline]
+    pub fn source_mut(&mut self) -> &mut dyn ClockSource {
+        &mut *self.source
+    }
+
+    /// Replace the clock source, returning the previous one.
+    ///
+    /// The position of the graph continues from where the previous source
+    /// left off.
+    pub fn set_source(&mut self, mut source: Box<dyn ClockSource>) -> Result<Box<dyn ClockSource>> {
+        source.configure(self.quantum, self.rate)?;
+        tracing::debug!(
+            from = self.source.name(),
+            to = source.name(),
+            "Switching clock source"
+        );
+        Ok(core::mem::replace(&mut self.source, source))
+    }
+
+    /// Change the quantum and rate of cycles.
+    pub fn configure(&mut self, quantum: u64, rate: u32) -> Result<()> {
+        self.source.configure(quantum, rate)?;
+        self.quantum = quantum;
+        self.rate = rate;
+        Ok(())
+    }
+
+    /// The quantum of cycles in samples.
+    #[inline]
+    pub fn quantum(&self) -> u64 {
+        self.quantum
+    }
+
+    /// The rate of cycles.
+    #[inline]
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// The file descriptor to poll for wakeups of the clock source, if it has
+    /// one.
+    #[inline]
+    pub fn as_raw_fd(&self) -> Option<RawFd> {
+        self.source.as_raw_fd()
+    }
+
+    /// Handle a wakeup of the clock source, returning the cycle to start if
+    /// any.
+    pub fn wakeup(&mut self) -> Result<Option<Cycle>> {
+        let Some(tick) = self.source.wakeup()? else {
+            return Ok(None);
+        };
+
+        let position = match self.position {
+            Some(position) => position + self.quantum * (tick.missed + 1),
+            None => 0,
+        };
+
+        if tick.missed > 0 {
+            self.xrun += self.quantum * tick.missed;
+            tracing::debug!(
+                missed = tick.missed,
+                source = self.source.name(),
+                "Missed cycles"
+            );
+        }
+
+        self.position = Some(position);
+        self.cycle = self.cycle.wrapping_add(1);
+
+        Ok(Some(Cycle {
+            nsec: tick.nsec,
+            next_nsec: tick.next_nsec,
+            position,
+            duration: self.quantum,
+            rate: self.rate,
+            rate_diff: tick.rate_diff,
+            cycle: self.cycle,
+            xrun: self.xrun,
+        }))
+    }
+}
+
+impl fmt::Debug for CycleScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CycleScheduler")
+            .field("source", &self.source.name())
+            .field("quantum", &self.quantum)
+            .field("rate", &self.rate)
+            .field("position", &self.position)
+            .field("cycle", &self.cycle)
+            .field("xrun", &self.xrun)
+            .finish()
+    }
+}
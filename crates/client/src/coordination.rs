@@ -0,0 +1,427 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::events::CoordinationEvent;
+use crate::{GlobalId, ProxyId};
+
+/// The key an instance announces its name under.
+pub(crate) const INSTANCE_KEY: &str = "livemix.instance";
+/// The prefix of keys an instance claims bus names under.
+pub(crate) const BUS_PREFIX: &str = "livemix.bus.";
+/// The key an instance announces its driver priority under.
+pub(crate) const DRIVER_KEY: &str = "livemix.driver";
+/// The type of values published to the metadata.
+pub(crate) const STRING_TYPE: &str = "Spa:String";
+
+/// A peer instance discovered through the shared metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Peer {
+    /// The name the peer announced, if any.
+    pub instance: Option<String>,
+    /// Bus names claimed by the peer.
+    pub buses: BTreeSet<String>,
+    /// The priority the peer is a driver candidate with, if it is one.
+    pub driver: Option<i32>,
+}
+
+/// Coordination between several instances sharing a PipeWire server.
+///
+/// Every instance publishes its name, the bus names it claims and its driver
+/// priority in the `default` metadata object, using its client id as the
+/// subject. Since entries are removed along with the client, instances which
+/// go away release their claims automatically.
+///
+/// All instances resolve conflicts the same way, so they agree without
+/// further negotiation:
+/// * A bus is owned by the claimant whose client connected first.
+/// * The driver is the candidate with the highest priority, where ties are
+///   broken in favor of the client which connected first.
+///
+/// Which client connected first is decided by the `object.serial` of the
+/// clients, see [`Coordination::set_serial`], since unlike client ids serials
+/// are never reused. Clients whose serial isn't known are ordered after the
+/// ones which are, and by client id among each other.
+///
+/// This is set up through [`Stream::coordinate`], and changes are reported
+/// through [`StreamEvent::Coordination`].
+///
+/// [`Stream::coordinate`]: crate::Stream::coordinate
+/// [`StreamEvent::Coordination`]: crate::events::StreamEvent::Coordination
+///
+/// # Examples
+///
+/// ```
+/// use client::{Coordination, GlobalId};
+/// use client::events::CoordinationEvent;
+///
+/// let mut events = Vec::new();
+///
+/// let mut c = Coordination::new("studio", GlobalId::new(32));
+/// c.set_serial(GlobalId::new(32), Some(100), &mut events);
+/// c.claim_bus("monitor", &mut events);
+/// c.set_driver_priority(Some(10), &mut events);
+/// assert!(c.owns_bus("monitor"));
+/// assert!(c.is_driver());
+///
+/// // A peer which connected earlier claims the same bus with a higher
+/// // driver priority. Its client id is higher, since ids are reused.
+/// let peer = GlobalId::new(40);
+/// events.clear();
+/// c.set_serial(peer, Some(90), &mut events);
+/// c.property(peer, Some("livemix.instance"), Some("stage"), &mut events);
+/// c.property(peer, Some("livemix.bus.monitor"), Some("stage"), &mut events);
+/// c.property(peer, Some("livemix.driver"), Some("20"), &mut events);
+///
+/// assert_eq!(c.bus_owner("monitor"), Some(peer));
+/// assert!(!c.owns_bus("monitor"));
+/// assert_eq!(c.driver(), Some(peer));
+/// assert!(events.contains(&CoordinationEvent::PeerJoined {
+///     peer,
+///     instance: String::from("stage"),
+/// }));
+///
+/// // Once the peer goes away its claims are released.
+/// events.clear();
+/// c.remove_peer(peer, &mut events);
+/// assert!(c.owns_bus("monitor"));
+/// assert!(c.is_driver());
+/// assert!(events.contains(&CoordinationEvent::PeerLeft { peer }));
+/// ```
+#[derive(Debug)]
+pub struct Coordination {
+    instance: String,
+    local: GlobalId,
+    pub(crate) proxy: Option<ProxyId>,
+    buses: BTreeSet<String>,
+    driver: Option<i32>,
+    peers: BTreeMap<GlobalId, Peer>,
+    serials: BTreeMap<GlobalId, u64>,
+    owners: BTreeMap<String, GlobalId>,
+    elected: Option<GlobalId>,
+}
+
+impl Coordination {
+    /// Construct coordination state for the instance with the given name,
+    /// where `local` is the client id of the instance.
+    pub fn new(instance: &str, local: GlobalId) -> Self {
+        Self {
+            instance: String::from(instance),
+            local,
+            proxy: None,
+            buses: BTreeSet::new(),
+            driver: None,
+            peers: BTreeMap::new(),
+            serials: BTreeMap::new(),
+            owners: BTreeMap::new(),
+            elected: None,
+        }
+    }
+
+    /// The name of this instance.
+    #[inline]
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+
+    /// Rename this instance.
+    pub(crate) fn set_instance(&mut self, instance: &str) {
+        self.instance = String::from(instance);
+    }
+
+    /// Forget everything learned from the server, keeping the claims of this
+    /// instance so that they are published again under the new client id.
+    pub(crate) fn reset(&mut self, local: GlobalId) {
+        self.local = local;
+        self.proxy = None;
+        self.peers.clear();
+        self.serials.clear();
+        self.owners.clear();
+        self.elected = None;
+    }
+
+    /// The client id of this instance, which is used as the subject of its
+    /// metadata entries.
+    #[inline]
+    pub fn local(&self) -> GlobalId {
+        self.local
+    }
+
+    /// Test if the shared metadata object is bound.
+    ///
+    /// Until it is, peers are unknown and every claim of this instance is
+    /// considered to be owned by it.
+    #[inline]
+    pub fn is_bound(&self) -> bool {
+        self.proxy.is_some()
+    }
+
+    /// Iterate over the bus names claimed by this instance.
+    pub fn buses(&self) -> impl Iterator<Item = &str> + '_ {
+        self.buses.iter().map(String::as_str)
+    }
+
+    /// The driver priority of this instance, if it is a candidate.
+    #[inline]
+    pub fn driver_priority(&self) -> Option<i32> {
+        self.driver
+    }
+
+    /// Iterate over known peer instances.
+    pub fn peers(&self) -> impl Iterator<Item = (GlobalId, &Peer)> + '_ {
+        self.peers.iter().map(|(id, peer)| (*id, peer))
+    }
+
+    /// Get a peer instance by its client id.
+    pub fn peer(&self, id: GlobalId) -> Option<&Peer> {
+        self.peers.get(&id)
+    }
+
+    /// Get the client id of the instance owning a bus name, if it is claimed.
+    pub fn bus_owner(&self, bus: &str) -> Option<GlobalId> {
+        self.owners.get(bus).copied()
+    }
+
+    /// Test if this instance owns the given bus name.
+    pub fn owns_bus(&self, bus: &str) -> bool {
+        self.bus_owner(bus) == Some(self.local)
+    }
+
+    /// Get the client id of the elected driver, if there are any candidates.
+    #[inline]
+    pub fn driver(&self) -> Option<GlobalId> {
+        self.elected
+    }
+
+    /// Test if this instance is the elected driver.
+    #[inline]
+    pub fn is_driver(&self) -> bool {
+        self.elected == Some(self.local)
+    }
+
+    /// Claim a bus name for this instance, returning `true` if it was not
+    /// already claimed.
+    pub fn claim_bus(&mut self, bus: &str, events: &mut Vec<CoordinationEvent>) -> bool {
+        if !self.buses.insert(String::from(bus)) {
+            return false;
+        }
+
+        self.recompute(events);
+        true
+    }
+
+    /// Release a bus name claimed by this instance, returning `true` if it
+    /// was claimed.
+    pub fn release_bus(&mut self, bus: &str, events: &mut Vec<CoordinationEvent>) -> bool {
+        if !self.buses.remove(bus) {
+            return false;
+        }
+
+        self.recompute(events);
+        true
+    }
+
+    /// Set the driver priority of this instance, or `None` to withdraw its
+    /// candidacy.
+    pub fn set_driver_priority(
+        &mut self,
+        priority: Option<i32>,
+        events: &mut Vec<CoordinationEvent>,
+    ) {
+        self.driver = priority;
+        self.recompute(events);
+    }
+
+    /// Set the `object.serial` of a client, which decides which of two
+    /// instances connected first.
+    ///
+    /// This is set by the stream for every client in the registry, including
+    /// the client of this instance.
+    pub fn set_serial(
+        &mut self,
+        id: GlobalId,
+        serial: Option<u64>,
+        events: &mut Vec<CoordinationEvent>,
+    ) {
+        let changed = match serial {
+            Some(serial) => self.serials.insert(id, serial) != Some(serial),
+            None => self.serials.remove(&id).is_some(),
+        };
+
+        if changed {
+            self.recompute(events);
+        }
+    }
+
+    /// Apply a property change of the shared metadata.
+    ///
+    /// A `key` of `None` removes all properties of the subject, and a `value`
+    /// of `None` removes the property. Properties of this instance and keys
+    /// which are not used for coordination are ignored.
+    pub fn property(
+        &mut self,
+        subject: GlobalId,
+        key: Option<&str>,
+        value: Option<&str>,
+        events: &mut Vec<CoordinationEvent>,
+    ) {
+        if subject == self.local {
+            return;
+        }
+
+        let Some(key) = key else {
+            self.remove_peer(subject, events);
+            return;
+        };
+
+        if key == INSTANCE_KEY {
+            let Some(value) = value else {
+                self.remove_peer(subject, events);
+                return;
+            };
+
+            let peer = self.peers.entry(subject).or_default();
+
+            if peer.instance.is_none() {
+                events.push(CoordinationEvent::PeerJoined {
+                    peer: subject,
+                    instance: String::from(value),
+                });
+            }
+
+            peer.instance = Some(String::from(value));
+        } else if let Some(bus) = key.strip_prefix(BUS_PREFIX) {
+            if value.is_some() {
+                let peer = self.peers.entry(subject).or_default();
+                peer.buses.insert(String::from(bus));
+            } else if let Some(peer) = self.peers.get_mut(&subject) {
+                peer.buses.remove(bus);
+            }
+        } else if key == DRIVER_KEY {
+            match value.and_then(|value| value.parse().ok()) {
+                Some(priority) => {
+                    self.peers.entry(subject).or_default().driver = Some(priority);
+                }
+                None => {
+                    if let Some(peer) = self.peers.get_mut(&subject) {
+                        peer.driver = None;
+                    }
+                }
+            }
+        } else {
+            return;
+        }
+
+        self.recompute(events);
+    }
+
+    /// Remove a peer instance and release its claims, like when its client
+    /// has gone away.
+    pub fn remove_peer(&mut self, id: GlobalId, events: &mut Vec<CoordinationEvent>) {
+        if id != self.local {
+            self.serials.remove(&id);
+        }
+
+        let Some(peer) = self.peers.remove(&id) else {
+            return;
+        };
+
+        if peer.instance.is_some() {
+            events.push(CoordinationEvent::PeerLeft { peer: id });
+        }
+
+        self.recompute(events);
+    }
+
+    /// Forget about the shared metadata object, since it has gone away.
+    pub(crate) fn unbind(&mut self, events: &mut Vec<CoordinationEvent>) {
+        self.proxy = None;
+
+        let peers = core::mem::take(&mut self.peers);
+
+        for (id, peer) in peers {
+            if peer.instance.is_some() {
+                events.push(CoordinationEvent::PeerLeft { peer: id });
+            }
+        }
+
+        self.recompute(events);
+    }
+
+    /// The rank of a client when resolving conflicts, where lower ranks
+    /// connected earlier.
+    fn rank(&self, id: GlobalId) -> (u64, GlobalId) {
+        let serial = self.serials.get(&id).copied().unwrap_or(u64::MAX);
+        (serial, id)
+    }
+
+    /// Recompute bus owners and the elected driver, reporting changes.
+    fn recompute(&mut self, events: &mut Vec<CoordinationEvent>) {
+        let mut owners = BTreeMap::new();
+
+        for bus in &self.buses {
+            owners.insert(bus.clone(), self.local);
+        }
+
+        for (&id, peer) in &self.peers {
+            for bus in &peer.buses {
+                owners
+                    .entry(bus.clone())
+                    .and_modify(|owner: &mut GlobalId| {
+                        if self.rank(id) < self.rank(*owner) {
+                            *owner = id;
+                        }
+                    })
+                    .or_insert(id);
+            }
+        }
+
+        for (bus, &owner) in &owners {
+            if self.owners.get(bus) != Some(&owner) {
+                events.push(CoordinationEvent::BusOwner {
+                    bus: bus.clone(),
+                    owner: Some(owner),
+                    owned: owner == self.local,
+                });
+            }
+        }
+
+        for bus in self.owners.keys() {
+            if !owners.contains_key(bus) {
+                events.push(CoordinationEvent::BusOwner {
+                    bus: bus.clone(),
+                    owner: None,
+                    owned: false,
+                });
+            }
+        }
+
+        self.owners = owners;
+
+        let local = self.driver.map(|priority| (priority, self.local));
+
+        let peers = self
+            .peers
+            .iter()
+            .filter_map(|(&id, peer)| Some((peer.driver?, id)));
+
+        let elected = local
+            .into_iter()
+            .chain(peers)
+            .max_by(|(a, a_id), (b, b_id)| {
+                a.cmp(b)
+                    .then_with(|| self.rank(*b_id).cmp(&self.rank(*a_id)))
+            })
+            .map(|(_, id)| id);
+
+        if elected != self.elected {
+            self.elected = elected;
+
+            events.push(CoordinationEvent::Driver {
+                driver: elected,
+                elected: elected == Some(self.local),
+            });
+        }
+    }
+}
@@ -0,0 +1,1112 @@
+//! Differential checking of the state of a stream against `pw-dump`.
+//!
+//! The output of a concurrent `pw-dump` run is parsed into a [`Dump`], which
+//! is compared with the registry and the parameters cached for bound proxies
+//! through [`diff`]. Every difference is reported as a [`Divergence`].
+//!
+//! Since the two snapshots are not taken atomically, objects which are added
+//! or removed while they are taken are expected to diverge.
+//!
+//! The state of a stream can also be rendered in the format of `pw-dump`
+//! through [`to_json`].
+//!
+//! # Examples
+//!
+//! ```
+//! use client::dump::Dump;
+//! use client::GlobalId;
+//! use protocol::id;
+//!
+//! let dump = Dump::parse(r#"[
+//!     {
+//!         "id": 31,
+//!         "type": "PipeWire:Interface:Node",
+//!         "info": {
+//!             "props": { "node.name": "speakers", "object.serial": 42, "node.virtual": false },
+//!             "params": { "EnumFormat": [ {}, {} ], "Props": [ {} ] }
+//!         }
+//!     }
+//! ]"#)?;
+//!
+//! let node = dump.get(GlobalId::new(31)).unwrap();
+//! assert_eq!(node.ty(), "PipeWire:Interface:Node");
+//! assert_eq!(node.prop("node.name"), Some("speakers"));
+//! assert_eq!(node.prop("object.serial"), Some("42"));
+//! assert_eq!(node.prop("node.virtual"), Some("false"));
+//! assert_eq!(node.param_count(id::Param::ENUM_FORMAT), Some(2));
+//! assert_eq!(node.param_count(id::Param::FORMAT), None);
+//! # Ok::<_, anyhow::Error>(())
+//! ```
+
+use core::ffi::CStr;
+use core::fmt;
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail, ensure};
+use pod::{Choice, ChoiceType, Fraction, Id, Object, Rectangle, Slice, Type, Value};
+use protocol::type_info::{self, TypeInfo};
+use protocol::{flags, id};
+
+use crate::utils::JsonString;
+use crate::{GlobalId, Proxies, Registry};
+
+#[cfg(test)]
+mod tests;
+
+/// The deepest nesting of arrays and objects which is parsed.
+const MAX_DEPTH: usize = 128;
+
+/// An object in the output of `pw-dump`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpObject {
+    id: GlobalId,
+    ty: String,
+    props: Option<BTreeMap<String, String>>,
+    params: BTreeMap<id::Param, usize>,
+}
+
+impl DumpObject {
+    /// The identifier of the global object.
+    #[inline]
+    pub fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    /// The interface type of the object, like `PipeWire:Interface:Node`.
+    #[inline]
+    pub fn ty(&self) -> &str {
+        &self.ty
+    }
+
+    /// Get a property of the object.
+    ///
+    /// Properties which are not strings are converted into their JSON
+    /// representation, so `42` becomes `"42"`.
+    pub fn prop(&self, key: &str) -> Option<&str> {
+        Some(self.props.as_ref()?.get(key)?.as_str())
+    }
+
+    /// Iterate over the properties of the object.
+    pub fn props(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.props
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// The number of values of a parameter, or `None` if the parameter was
+    /// not dumped.
+    pub fn param_count(&self, id: id::Param) -> Option<usize> {
+        self.params.get(&id).copied()
+    }
+}
+
+/// The parsed output of `pw-dump`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Dump {
+    objects: BTreeMap<GlobalId, DumpObject>,
+}
+
+impl Dump {
+    /// Parse the JSON output of `pw-dump`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let json = Json::parse(input)?;
+
+        let Json::Array(values) = json else {
+            bail!("Expected an array of objects");
+        };
+
+        let mut objects = BTreeMap::new();
+
+        for value in &values {
+            let object = parse_object(value)?;
+            objects.insert(object.id, object);
+        }
+
+        Ok(Self { objects })
+    }
+
+    /// The number of dumped objects.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Test if there are no dumped objects.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Get the dumped object with the given identifier.
+    pub fn get(&self, id: GlobalId) -> Option<&DumpObject> {
+        self.objects.get(&id)
+    }
+
+    /// Iterate over all dumped objects in the order of their identifiers.
+    pub fn iter(&self) -> impl Iterator<Item = &DumpObject> {
+        self.objects.values()
+    }
+}
+
+/// A difference between the state of a stream and the output of `pw-dump`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Divergence {
+    /// A global object in the dump is not in the registry.
+    MissingInRegistry { id: GlobalId, ty: String },
+    /// A global object in the registry is not in the dump.
+    MissingInDump { id: GlobalId, ty: String },
+    /// The type of a global object differs.
+    Type {
+        id: GlobalId,
+        registry: String,
+        dump: String,
+    },
+    /// The value of a property of a global object differs, where `dump` is
+    /// `None` if the property is missing from the dump.
+    Property {
+        id: GlobalId,
+        key: String,
+        registry: String,
+        dump: Option<String>,
+    },
+    /// The number of cached values of a parameter of a bound proxy differs,
+    /// where `dump` is `None` if the parameter is missing from the dump.
+    Param {
+        id: GlobalId,
+        param: id::Param,
+        cached: usize,
+        dump: Option<usize>,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingInRegistry { id, ty } => {
+                write!(f, "{id}: {ty} is missing from the registry")
+            }
+            Self::MissingInDump { id, ty } => {
+                write!(f, "{id}: {ty} is missing from the dump")
+            }
+            Self::Type { id, registry, dump } => {
+                write!(f, "{id}: type {registry} differs from {dump} in the dump")
+            }
+            Self::Property {
+                id,
+                key,
+                registry,
+                dump: Some(dump),
+            } => {
+                write!(
+                    f,
+                    "{id}: property {key}={registry:?} differs from {dump:?} in the dump"
+                )
+            }
+            Self::Property {
+                id,
+                key,
+                registry,
+                dump: None,
+            } => {
+                write!(
+                    f,
+                    "{id}: property {key}={registry:?} is missing from the dump"
+                )
+            }
+            Self::Param {
+                id,
+                param,
+                cached,
+                dump: Some(dump),
+            } => {
+                write!(
+                    f,
+                    "{id}: {cached} cached {param:?} values differ from {dump} in the dump"
+                )
+            }
+            Self::Param {
+                id,
+                param,
+                cached,
+                dump: None,
+            } => {
+                write!(
+                    f,
+                    "{id}: {cached} cached {param:?} values are missing from the dump"
+                )
+            }
+        }
+    }
+}
+
+/// Compare the registry and the parameters cached for bound proxies with the
+/// output of `pw-dump`.
+///
+/// Properties of the registry are compared with the properties of the info of
+/// dumped objects, which are a superset of them. Only the parameters which
+/// have been cached for a proxy are compared.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::process::Command;
+///
+/// use client::Stream;
+/// use client::dump::{self, Dump};
+/// use protocol::{Connection, Properties};
+///
+/// let stream = Stream::new(Connection::open()?, Properties::new())?;
+/// // Drive the stream until the registry has been populated.
+///
+/// let output = Command::new("pw-dump").output()?;
+/// let dump = Dump::parse(&String::from_utf8(output.stdout)?)?;
+///
+/// for divergence in dump::diff(stream.registry(), stream.proxies(), &dump) {
+///     println!("{divergence}");
+/// }
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+pub fn diff(registry: Registry<'_>, proxies: &Proxies, dump: &Dump) -> Vec<Divergence> {
+    let mut out = Vec::new();
+
+    for object in registry.iter() {
+        let Some(dumped) = dump.get(object.id()) else {
+            out.push(Divergence::MissingInDump {
+                id: object.id(),
+                ty: object.ty().to_owned(),
+            });
+
+            continue;
+        };
+
+        if dumped.ty() != object.ty() {
+            out.push(Divergence::Type {
+                id: object.id(),
+                registry: object.ty().to_owned(),
+                dump: dumped.ty().to_owned(),
+            });
+
+            continue;
+        }
+
+        if dumped.props.is_none() {
+            continue;
+        }
+
+        for (key, value) in object.props().iter() {
+            let other = dumped.prop(key.as_str());
+
+            if other != Some(value) {
+                out.push(Divergence::Property {
+                    id: object.id(),
+                    key: key.as_str().to_owned(),
+                    registry: value.to_owned(),
+                    dump: other.map(str::to_owned),
+                });
+            }
+        }
+    }
+
+    for dumped in dump.iter() {
+        if registry.get(dumped.id()).is_none() {
+            out.push(Divergence::MissingInRegistry {
+                id: dumped.id(),
+                ty: dumped.ty().to_owned(),
+            });
+        }
+    }
+
+    for proxy in proxies.iter() {
+        let Some(dumped) = dump.get(proxy.global_id) else {
+            continue;
+        };
+
+        for param in proxy.params.ids() {
+            let cached = proxy.params.get(param).len();
+            let other = dumped.param_count(param);
+
+            if other != Some(cached) {
+                out.push(Divergence::Param {
+                    id: proxy.global_id,
+                    param,
+                    cached,
+                    dump: other,
+                });
+            }
+        }
+    }
+
+    out
+}
+
+/// Render the registry and the parameters cached for bound proxies in the
+/// format of `pw-dump`.
+///
+/// Every global object is rendered with its properties, and with the
+/// parameters cached for it if it has been bound. Since the registry only
+/// holds the properties announced with a global object, these are a subset
+/// of the ones printed by `pw-dump`.
+///
+/// The output can be parsed back through [`Dump::parse`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::Stream;
+/// use client::dump;
+/// use protocol::{Connection, Properties};
+///
+/// let stream = Stream::new(Connection::open()?, Properties::new())?;
+/// // Drive the stream until the registry has been populated.
+///
+/// println!("{}", dump::to_json(stream.registry(), stream.proxies()));
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+pub fn to_json(registry: Registry<'_>, proxies: &Proxies) -> Json {
+    let proxies = proxies
+        .iter()
+        .map(|proxy| (proxy.global_id, proxy))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut objects = Vec::new();
+
+    for object in registry.iter() {
+        let permissions = [
+            (flags::Permission::R, "r"),
+            (flags::Permission::W, "w"),
+            (flags::Permission::X, "x"),
+            (flags::Permission::M, "m"),
+        ]
+        .into_iter()
+        .filter(|&(perm, _)| object.permissions().contains(perm))
+        .map(|(_, name)| Json::String(name.to_owned()))
+        .collect();
+
+        let props = object
+            .props()
+            .iter()
+            .map(|(key, value)| (key.as_str().to_owned(), Json::String(value.to_owned())))
+            .collect();
+
+        let mut info = vec![(String::from("props"), Json::Object(props))];
+
+        if let Some(proxy) = proxies.get(&object.id()) {
+            let params = proxy
+                .params
+                .ids()
+                .map(|param| {
+                    let name = match type_info::PARAM.short_name(param.into_id()) {
+                        Some(name) => name.to_owned(),
+                        None => format!("{}", param.into_id()),
+                    };
+
+                    let values = proxy
+                        .params
+                        .get(param)
+                        .iter()
+                        .map(|value| from_object(value.as_ref()))
+                        .collect();
+
+                    (name, Json::Array(values))
+                })
+                .collect();
+
+            info.push((String::from("params"), Json::Object(params)));
+        }
+
+        objects.push(Json::Object(vec![
+            (String::from("id"), Json::Number(object.id().to_string())),
+            (String::from("type"), Json::String(object.ty().to_owned())),
+            (
+                String::from("version"),
+                Json::Number(object.version().to_string()),
+            ),
+            (String::from("permissions"), Json::Array(permissions)),
+            (String::from("info"), Json::Object(info)),
+        ]));
+    }
+
+    Json::Array(objects)
+}
+
+fn from_value(value: Value<Slice<'_>>, names: Option<&TypeInfo>) -> Json {
+    match value.ty() {
+        Type::BOOL => value
+            .read_sized::<bool>()
+            .map(Json::Bool)
+            .unwrap_or(Json::Null),
+        Type::ID => match value.read_sized::<Id<u32>>() {
+            Ok(Id(id)) => match names.and_then(|names| names.short_name(id)) {
+                Some(name) => Json::String(last_segment(name).to_owned()),
+                None => Json::Number(id.to_string()),
+            },
+            Err(..) => Json::Null,
+        },
+        Type::INT => number(value.read_sized::<i32>()),
+        Type::LONG => number(value.read_sized::<i64>()),
+        Type::FLOAT => float(value.read_sized::<f32>().map(f64::from)),
+        Type::DOUBLE => float(value.read_sized::<f64>()),
+        Type::STRING => match value.read_unsized::<CStr>() {
+            Ok(string) => Json::String(string.to_string_lossy().into_owned()),
+            Err(..) => Json::Null,
+        },
+        Type::RECTANGLE => match value.read_sized::<Rectangle>() {
+            Ok(rect) => Json::Object(vec![
+                (String::from("width"), Json::Number(rect.width.to_string())),
+                (
+                    String::from("height"),
+                    Json::Number(rect.height.to_string()),
+                ),
+            ]),
+            Err(..) => Json::Null,
+        },
+        Type::FRACTION => match value.read_sized::<Fraction>() {
+            Ok(fraction) => Json::Object(vec![
+                (String::from("num"), Json::Number(fraction.num.to_string())),
+                (
+                    String::from("denom"),
+                    Json::Number(fraction.denom.to_string()),
+                ),
+            ]),
+            Err(..) => Json::Null,
+        },
+        Type::ARRAY => {
+            let Ok(mut array) = value.read_array() else {
+                return Json::Null;
+            };
+
+            let mut values = Vec::new();
+
+            while let Ok(Some(value)) = array.next() {
+                values.push(from_value(value, names));
+            }
+
+            Json::Array(values)
+        }
+        Type::STRUCT => {
+            let Ok(mut st) = value.read_struct() else {
+                return Json::Null;
+            };
+
+            let mut values = Vec::new();
+
+            while !st.is_empty() {
+                let Ok(value) = st.field() else {
+                    break;
+                };
+
+                values.push(from_value(value, None));
+            }
+
+            Json::Array(values)
+        }
+        Type::OBJECT => match value.read_object() {
+            Ok(object) => from_object(object),
+            Err(..) => Json::Null,
+        },
+        Type::CHOICE => match value.read_choice() {
+            Ok(choice) => from_choice(choice, names),
+            Err(..) => Json::Null,
+        },
+        _ => Json::Null,
+    }
+}
+
+fn from_object(mut object: Object<Slice<'_>>) -> Json {
+    let object_type = object.object_type::<id::ObjectType>();
+    let keys = type_info::keys(object_type);
+
+    let mut entries = Vec::new();
+
+    while !object.is_empty() {
+        let Ok(prop) = object.property() else {
+            break;
+        };
+
+        let key = prop.key::<u32>();
+
+        let name = match keys.and_then(|keys| keys.short_name(key)) {
+            Some(name) => last_segment(name).to_owned(),
+            None => key.to_string(),
+        };
+
+        let names = type_info::values(object_type, key);
+        entries.push((name, from_value(prop.value(), names)));
+    }
+
+    Json::Object(entries)
+}
+
+fn from_choice(mut choice: Choice<Slice<'_>>, names: Option<&TypeInfo>) -> Json {
+    let mut values = Vec::new();
+
+    while let Some(value) = choice.next() {
+        values.push(from_value(value, names));
+    }
+
+    let mut values = values.into_iter();
+
+    let Some(default) = values.next() else {
+        return Json::Null;
+    };
+
+    let labels: &[&str] = match choice.choice_type() {
+        ChoiceType::RANGE => &["min", "max"],
+        ChoiceType::STEP => &["min", "max", "step"],
+        ChoiceType::FLAGS => {
+            return Json::Object(vec![
+                (String::from("default"), default),
+                (String::from("flags"), Json::Array(values.collect())),
+            ]);
+        }
+        ChoiceType::ENUM => &[],
+        _ => return default,
+    };
+
+    let mut entries = vec![(String::from("default"), default)];
+
+    for (n, value) in values.enumerate() {
+        let label = match labels.get(n) {
+            Some(label) => String::from(*label),
+            None => format!("alt{}", n + 1),
+        };
+
+        entries.push((label, value));
+    }
+
+    Json::Object(entries)
+}
+
+fn number<T>(value: Result<T, pod::Error>) -> Json
+where
+    T: fmt::Display,
+{
+    match value {
+        Ok(value) => Json::Number(value.to_string()),
+        Err(..) => Json::Null,
+    }
+}
+
+fn float(value: Result<f64, pod::Error>) -> Json {
+    match value {
+        Ok(value) if value.is_finite() => Json::Number(value.to_string()),
+        _ => Json::Null,
+    }
+}
+
+/// Get the last segment of a type name, like `rate` for `Audio:rate`.
+fn last_segment(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn parse_object(value: &Json) -> Result<DumpObject> {
+    let id = value
+        .get("id")
+        .and_then(Json::as_u32)
+        .context("Object is missing a numeric id")?;
+
+    let ty = value
+        .get("type")
+        .and_then(Json::as_str)
+        .with_context(|| format!("Object {id} is missing a type"))?;
+
+    let info = value.get("info");
+
+    let props = match info
+        .and_then(|info| info.get("props"))
+        .or(value.get("props"))
+    {
+        Some(Json::Object(entries)) => Some(
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_prop_string()))
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    let mut params = BTreeMap::new();
+
+    if let Some(Json::Object(entries)) = info.and_then(|info| info.get("params")) {
+        for (name, values) in entries {
+            let Some(param) = type_info::PARAM.id(name) else {
+                continue;
+            };
+
+            let Json::Array(values) = values else {
+                continue;
+            };
+
+            params.insert(id::Param::from_id(param), values.len());
+        }
+    }
+
+    Ok(DumpObject {
+        id: GlobalId::new(id),
+        ty: ty.to_owned(),
+        props,
+        params,
+    })
+}
+
+/// A JSON value.
+///
+/// Values are printed with indentation through their [`Display`]
+/// implementation, like the output of `pw-dump`.
+///
+/// [`Display`]: core::fmt::Display
+///
+/// # Examples
+///
+/// ```
+/// use client::dump::Json;
+///
+/// let json = Json::parse(r#"{ "id": 31, "name": "speakers\n" }"#)?;
+/// assert_eq!(json.get("id").and_then(Json::as_u32), Some(31));
+/// assert_eq!(json.to_string(), "{\n  \"id\": 31,\n  \"name\": \"speakers\\n\"\n}");
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number in its textual representation.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse a JSON value.
+    ///
+    /// Arrays and objects nested deeper than 128 levels are rejected.
+    pub fn parse(input: &str) -> Result<Self> {
+        Parser::new(input).parse()
+    }
+
+    /// Convert a value into its JSON representation as printed by
+    /// `pw-dump`.
+    ///
+    /// Values which have no JSON representation, like file descriptors, are
+    /// converted into [`Json::Null`].
+    pub fn from_value(value: Value<Slice<'_>>) -> Self {
+        from_value(value, None)
+    }
+
+    /// Convert an object into its JSON representation as printed by
+    /// `pw-dump`.
+    ///
+    /// Properties are keyed by the last segment of their names, and
+    /// identifiers which have a known name are converted into it. Choices are
+    /// converted into an object holding their default value and alternatives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::dump::Json;
+    /// use protocol::id::AudioFormat;
+    /// use protocol::param::EnumFormatBuilder;
+    ///
+    /// let format = EnumFormatBuilder::audio()
+    ///     .format_any(&[AudioFormat::F32P, AudioFormat::S16])
+    ///     .rate_range(8000, 192000, 48000);
+    ///
+    /// let mut pod = pod::array();
+    /// let obj = pod.as_mut().embed(&format)?;
+    ///
+    /// let json = Json::from_object(obj.as_ref());
+    /// assert_eq!(json.get("mediaType").and_then(Json::as_str), Some("audio"));
+    /// assert_eq!(json.get("mediaSubtype").and_then(Json::as_str), Some("raw"));
+    ///
+    /// let format = json.get("format").unwrap();
+    /// assert_eq!(format.get("default").and_then(Json::as_str), Some("F32P"));
+    ///
+    /// let rate = json.get("rate").unwrap();
+    /// assert_eq!(rate.get("default").and_then(Json::as_u32), Some(48000));
+    /// assert_eq!(rate.get("max").and_then(Json::as_u32), Some(192000));
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn from_object(object: Object<Slice<'_>>) -> Self {
+        from_object(object)
+    }
+
+    /// Get the value of a key if this is an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        let Json::Object(entries) = self else {
+            return None;
+        };
+
+        entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Get the value as a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Get the value as an unsigned 32-bit number.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Json::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Convert the value into the string used to compare it with a property.
+    fn to_prop_string(&self) -> String {
+        match self {
+            Json::Null => String::from("null"),
+            Json::Bool(value) => format!("{value}"),
+            Json::Number(number) => number.clone(),
+            Json::String(string) => string.clone(),
+            Json::Array(..) | Json::Object(..) => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_json(f, self, 0)
+    }
+}
+
+fn write_json(f: &mut fmt::Formatter<'_>, value: &Json, depth: usize) -> fmt::Result {
+    match value {
+        Json::Null => f.write_str("null"),
+        Json::Bool(value) => write!(f, "{value}"),
+        Json::Number(number) => f.write_str(number),
+        Json::String(string) => write!(f, "{}", JsonString(string)),
+        Json::Array(values) if values.is_empty() => f.write_str("[]"),
+        Json::Object(entries) if entries.is_empty() => f.write_str("{}"),
+        Json::Array(values) => {
+            f.write_str("[")?;
+
+            for (n, value) in values.iter().enumerate() {
+                f.write_str(if n == 0 { "\n" } else { ",\n" })?;
+                indent(f, depth + 1)?;
+                write_json(f, value, depth + 1)?;
+            }
+
+            f.write_str("\n")?;
+            indent(f, depth)?;
+            f.write_str("]")
+        }
+        Json::Object(entries) => {
+            f.write_str("{")?;
+
+            for (n, (key, value)) in entries.iter().enumerate() {
+                f.write_str(if n == 0 { "\n" } else { ",\n" })?;
+                indent(f, depth + 1)?;
+                write!(f, "{}", JsonString(key))?;
+                f.write_str(": ")?;
+                write_json(f, value, depth + 1)?;
+            }
+
+            f.write_str("\n")?;
+            indent(f, depth)?;
+            f.write_str("}")
+        }
+    }
+}
+
+fn indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        f.write_str("  ")?;
+    }
+
+    Ok(())
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    at: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            at: 0,
+            depth: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<Json> {
+        let value = self.value()?;
+        self.ws();
+        ensure!(
+            self.at == self.input.len(),
+            "Trailing data at byte {}",
+            self.at
+        );
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.at).copied()
+    }
+
+    fn ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.at += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<()> {
+        self.ws();
+
+        ensure!(
+            self.peek() == Some(b),
+            "Expected `{}` at byte {}",
+            b as char,
+            self.at
+        );
+
+        self.at += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json> {
+        ensure!(
+            self.input[self.at..].starts_with(keyword),
+            "Expected `{keyword}` at byte {}",
+            self.at
+        );
+
+        self.at += keyword.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json> {
+        self.ws();
+
+        match self.peek() {
+            Some(b @ (b'{' | b'[')) => {
+                ensure!(
+                    self.depth < MAX_DEPTH,
+                    "Nesting deeper than {MAX_DEPTH} at byte {}",
+                    self.at
+                );
+
+                self.depth += 1;
+
+                let value = if b == b'{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+
+                self.depth -= 1;
+                value
+            }
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b) => bail!("Unexpected `{}` at byte {}", b as char, self.at),
+            None => bail!("Unexpected end of input"),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json> {
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+
+        self.ws();
+
+        if self.peek() == Some(b'}') {
+            self.at += 1;
+            return Ok(Json::Object(entries));
+        }
+
+        loop {
+            self.ws();
+            let key = self.string()?;
+            self.expect(b':')?;
+            entries.push((key, self.value()?));
+            self.ws();
+
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b'}') => {
+                    self.at += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => bail!("Expected `,` or `}}` at byte {}", self.at),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+
+        self.ws();
+
+        if self.peek() == Some(b']') {
+            self.at += 1;
+            return Ok(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.ws();
+
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b']') => {
+                    self.at += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => bail!("Expected `,` or `]` at byte {}", self.at),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let start = self.at;
+
+        if self.peek() == Some(b'-') {
+            self.at += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => self.at += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => bail!("Invalid number at byte {start}"),
+        }
+
+        if self.peek() == Some(b'.') {
+            self.at += 1;
+            ensure!(
+                matches!(self.peek(), Some(b'0'..=b'9')),
+                "Invalid number at byte {start}"
+            );
+            self.digits();
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            self.at += 1;
+
+            if let Some(b'-' | b'+') = self.peek() {
+                self.at += 1;
+            }
+
+            ensure!(
+                matches!(self.peek(), Some(b'0'..=b'9')),
+                "Invalid number at byte {start}"
+            );
+            self.digits();
+        }
+
+        Ok(Json::Number(self.input[start..self.at].to_owned()))
+    }
+
+    fn digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.at += 1;
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+
+        loop {
+            let rest = &self.input[self.at..];
+
+            let Some(n) = rest.find(|c| matches!(c, '"' | '\\' | '\0'..='\x1f')) else {
+                bail!("Unterminated string");
+            };
+
+            out.push_str(&rest[..n]);
+            self.at += n + 1;
+
+            match rest.as_bytes()[n] {
+                b'"' => return Ok(out),
+                b'\\' => {}
+                _ => bail!("Control character in string at byte {}", self.at - 1),
+            }
+
+            let Some(escape) = self.peek() else {
+                bail!("Unterminated string");
+            };
+
+            self.at += 1;
+
+            let c = match escape {
+                b'"' => '"',
+                b'\\' => '\\',
+                b'/' => '/',
+                b'b' => '\u{8}',
+                b'f' => '\u{c}',
+                b'n' => '\n',
+                b'r' => '\r',
+                b't' => '\t',
+                b'u' => self.unicode()?,
+                b => bail!("Invalid escape `\\{}` at byte {}", b as char, self.at),
+            };
+
+            out.push(c);
+        }
+    }
+
+    fn unicode(&mut self) -> Result<char> {
+        let high = self.hex()?;
+
+        // NB: Characters outside of the basic multilingual plane are encoded
+        // as a surrogate pair. Unpaired surrogates are replaced, like when
+        // decoding lossily.
+        if (0xd800..0xdc00).contains(&high) && self.input[self.at..].starts_with("\\u") {
+            let at = self.at;
+            self.at += 2;
+            let low = self.hex()?;
+
+            if (0xdc00..0xe000).contains(&low) {
+                let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return Ok(char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+
+            // The second escape is not a low surrogate, so it is decoded on
+            // its own.
+            self.at = at;
+        }
+
+        Ok(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn hex(&mut self) -> Result<u32> {
+        let Some(digits) = self.input.get(self.at..self.at + 4) else {
+            bail!("Truncated unicode escape at byte {}", self.at);
+        };
+
+        ensure!(
+            digits.bytes().all(|b| b.is_ascii_hexdigit()),
+            "Invalid unicode escape at byte {}",
+            self.at
+        );
+
+        let Ok(value) = u32::from_str_radix(digits, 16) else {
+            bail!("Invalid unicode escape at byte {}", self.at);
+        };
+
+        self.at += 4;
+        Ok(value)
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use super::{Dump, Json, MAX_DEPTH};
+
+#[test]
+fn malformed() {
+    let inputs = [
+        "",
+        "[",
+        "[1,",
+        "[1 2]",
+        "{\"a\"}",
+        "{\"a\": 1,}",
+        "{1: 2}",
+        "\"unterminated",
+        "\"bad \\q escape\"",
+        "\"raw\ncontrol\"",
+        "\"\\u12\"",
+        "\"\\u+123\"",
+        "tru",
+        "nul",
+        "-",
+        "01x",
+        "1.",
+        "1e",
+        "1e+",
+        "--1",
+        ".5",
+        "[] []",
+    ];
+
+    for input in inputs {
+        assert!(Json::parse(input).is_err(), "{input:?} should not parse");
+    }
+}
+
+#[test]
+fn numbers() {
+    for input in ["0", "-0", "42", "-1.5", "1e10", "2.5E-3", "1e+2"] {
+        assert_eq!(
+            Json::parse(input).unwrap(),
+            Json::Number(String::from(input))
+        );
+    }
+}
+
+#[test]
+fn surrogates() {
+    let json = Json::parse(r#""\ud83c\udfb5""#).unwrap();
+    assert_eq!(json, Json::String(String::from("\u{1f3b5}")));
+
+    // Unpaired surrogates are replaced.
+    let json = Json::parse(r#""\ud83c""#).unwrap();
+    assert_eq!(json, Json::String(String::from("\u{fffd}")));
+
+    let json = Json::parse(r#""\udfb5\ud83c""#).unwrap();
+    assert_eq!(json, Json::String(String::from("\u{fffd}\u{fffd}")));
+
+    // A high surrogate followed by an escape which is not a low surrogate.
+    let json = Json::parse(r#""\ud83c\u0041""#).unwrap();
+    assert_eq!(json, Json::String(String::from("\u{fffd}A")));
+
+    assert!(Json::parse(r#""\ud83c\u""#).is_err());
+}
+
+#[test]
+fn nesting() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+    assert!(Json::parse(&nested(MAX_DEPTH + 1)).is_err());
+
+    let objects = format!(
+        "{}1{}",
+        "{\"a\":".repeat(MAX_DEPTH + 1),
+        "}".repeat(MAX_DEPTH + 1)
+    );
+
+    assert!(Json::parse(&objects).is_err());
+
+    // Deep nesting is rejected without exhausting the stack.
+    assert!(Json::parse(&"[".repeat(1 << 20)).is_err());
+}
+
+#[test]
+fn roundtrip() {
+    let input = r#"{"a": [1, -2.5e3, true, false, null], "b": "tab\t\"quote\" \u0001 \ud83c\udfb5", "c": {}}"#;
+
+    let json = Json::parse(input).unwrap();
+    let output = json.to_string();
+    assert_eq!(Json::parse(&output).unwrap(), json);
+}
+
+#[test]
+fn dump_rejects_malformed_objects() {
+    assert!(Dump::parse("{}").is_err());
+    assert!(Dump::parse(r#"[{"type": "PipeWire:Interface:Node"}]"#).is_err());
+    assert!(Dump::parse("[]").unwrap().is_empty());
+}
@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+
+use protocol::consts::Direction;
+use protocol::id::Param;
+
+use crate::events::StreamEvent;
+use crate::{ClientNodeId, MixId, PortId, ProxyId, RouteId};
+
+#[cfg(test)]
+mod tests;
+
+/// The default number of events which can be queued.
+const DEFAULT_CAPACITY: usize = 256;
+
+/// A bounded queue of events produced by a stream.
+///
+/// Events which supersede an already queued event, like a parameter being set
+/// multiple times between polls, replace the queued event instead of being
+/// queued again.
+///
+/// Once the queue is full, the oldest informational event is dropped, like a
+/// parameter of a bound proxy being received. Events which affect the state
+/// the client has to track, like lifecycle and registry events, are never
+/// dropped and are queued even if that exceeds the capacity.
+pub(crate) struct EventQueue {
+    events: VecDeque<StreamEvent>,
+    capacity: usize,
+    dropped: usize,
+}
+
+impl EventQueue {
+    /// Construct a new empty event queue.
+    pub(crate) fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            dropped: 0,
+        }
+    }
+
+    /// Set the capacity of the queue.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+
+        while self.events.len() > self.capacity && self.drop_oldest() {}
+    }
+
+    /// Take the number of events which have been dropped because the queue
+    /// was full since this was last called.
+    #[inline]
+    pub(crate) fn take_dropped(&mut self) -> usize {
+        core::mem::take(&mut self.dropped)
+    }
+
+    /// The number of queued events.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Push an event onto the queue.
+    pub(crate) fn push(&mut self, event: StreamEvent) {
+        if let Some(key) = Key::of(&event)
+            && let Some(existing) = self.events.iter_mut().find(|e| Key::of(e) == Some(key))
+        {
+            *existing = event;
+            return;
+        }
+
+        if self.events.len() >= self.capacity && !self.drop_oldest() && is_droppable(&event) {
+            self.dropped += 1;
+            return;
+        }
+
+        self.events.push_back(event);
+    }
+
+    /// Pop the next event from the queue.
+    #[inline]
+    pub(crate) fn pop(&mut self) -> Option<StreamEvent> {
+        self.events.pop_front()
+    }
+
+    /// Drop the oldest informational event, returning `false` if there are
+    /// none.
+    fn drop_oldest(&mut self) -> bool {
+        let Some(index) = self.events.iter().position(is_droppable) else {
+            return false;
+        };
+
+        self.events.remove(index);
+        self.dropped += 1;
+        true
+    }
+}
+
+/// Test if an event is informational, in that the client can recover the
+/// state it describes without it.
+fn is_droppable(event: &StreamEvent) -> bool {
+    matches!(
+        event,
+        StreamEvent::ProxyParam(..)
+            | StreamEvent::RouteVolume(..)
+            | StreamEvent::MetadataProperty(..)
+            | StreamEvent::Clip(..)
+            | StreamEvent::RateChanged(..)
+            | StreamEvent::QuantumChanged(..)
+            | StreamEvent::UnknownMessage(..)
+            | StreamEvent::Message(..)
+    )
+}
+
+/// The key used to coalesce events, where a later event with the same key
+/// supersedes an earlier one.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Key {
+    NodeParam(ClientNodeId, Param),
+    PortParam(ClientNodeId, Direction, PortId, Param),
+    UseBuffers(ClientNodeId, Direction, PortId, MixId),
+    ProxyParam(ProxyId, Param),
+    RouteVolume(ProxyId, RouteId),
+}
+
+impl Key {
+    fn of(event: &StreamEvent) -> Option<Self> {
+        let key = match event {
+            StreamEvent::SetNodeParam(e) => Key::NodeParam(e.node_id, e.param),
+            StreamEvent::RemoveNodeParam(e) => Key::NodeParam(e.node_id, e.param),
+            StreamEvent::SetPortParam(e) => {
+                Key::PortParam(e.node_id, e.direction, e.port_id, e.param)
+            }
+            StreamEvent::RemovePortParam(e) => {
+                Key::PortParam(e.node_id, e.direction, e.port_id, e.param)
+            }
+            StreamEvent::UseBuffers(e) => {
+                Key::UseBuffers(e.node_id, e.direction, e.port_id, e.mix_id)
+            }
+            StreamEvent::ProxyParam(e) => Key::ProxyParam(e.proxy_id, e.param),
+            StreamEvent::RouteVolume(e) => Key::RouteVolume(e.proxy_id, e.route),
+            _ => return None,
+        };
+
+        Some(key)
+    }
+}
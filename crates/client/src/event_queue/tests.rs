@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use protocol::id::Param;
+
+use super::EventQueue;
+use crate::events::{ProxyParamEvent, SetNodeParamEvent, StreamEvent};
+use crate::{ClientNodeId, ProxyId};
+
+fn proxy_param(proxy_id: u32, param: Param) -> StreamEvent {
+    StreamEvent::ProxyParam(ProxyParamEvent {
+        proxy_id: ProxyId::new(proxy_id),
+        param,
+    })
+}
+
+fn node_param(node_id: u32, param: Param) -> StreamEvent {
+    StreamEvent::SetNodeParam(SetNodeParamEvent {
+        node_id: ClientNodeId::new(node_id, 0),
+        param,
+    })
+}
+
+fn drain(queue: &mut EventQueue) -> Vec<StreamEvent> {
+    let mut events = Vec::new();
+
+    while let Some(event) = queue.pop() {
+        events.push(event);
+    }
+
+    events
+}
+
+#[test]
+fn coalesce() {
+    let mut queue = EventQueue::new();
+
+    queue.push(node_param(0, Param::PROPS));
+    queue.push(proxy_param(1, Param::ROUTE));
+    queue.push(node_param(0, Param::FORMAT));
+    queue.push(node_param(0, Param::PROPS));
+    queue.push(proxy_param(1, Param::ROUTE));
+    queue.push(proxy_param(2, Param::ROUTE));
+
+    // NB: Coalesced events keep the position of the first event.
+    assert_eq!(
+        drain(&mut queue),
+        [
+            node_param(0, Param::PROPS),
+            proxy_param(1, Param::ROUTE),
+            node_param(0, Param::FORMAT),
+            proxy_param(2, Param::ROUTE),
+        ]
+    );
+
+    assert_eq!(queue.take_dropped(), 0);
+}
+
+#[test]
+fn overflow_drops_oldest_informational() {
+    let mut queue = EventQueue::new();
+    queue.set_capacity(3);
+
+    queue.push(StreamEvent::Started);
+    queue.push(proxy_param(0, Param::ROUTE));
+    queue.push(proxy_param(1, Param::ROUTE));
+    queue.push(proxy_param(2, Param::ROUTE));
+    queue.push(StreamEvent::Synced(1));
+
+    assert_eq!(queue.take_dropped(), 2);
+    assert_eq!(queue.take_dropped(), 0);
+
+    assert_eq!(
+        drain(&mut queue),
+        [
+            StreamEvent::Started,
+            proxy_param(2, Param::ROUTE),
+            StreamEvent::Synced(1),
+        ]
+    );
+}
+
+#[test]
+fn overflow_keeps_lifecycle() {
+    let mut queue = EventQueue::new();
+    queue.set_capacity(2);
+
+    queue.push(StreamEvent::Started);
+    queue.push(StreamEvent::Disconnected);
+    queue.push(StreamEvent::Reconnected);
+
+    // NB: Informational events are dropped as they arrive once the queue is
+    // filled with events which can't be dropped.
+    queue.push(proxy_param(0, Param::ROUTE));
+    queue.push(node_param(0, Param::PROPS));
+    queue.push(StreamEvent::Synced(1));
+
+    assert_eq!(queue.len(), 5);
+    assert_eq!(queue.take_dropped(), 1);
+
+    assert_eq!(
+        drain(&mut queue),
+        [
+            StreamEvent::Started,
+            StreamEvent::Disconnected,
+            StreamEvent::Reconnected,
+            node_param(0, Param::PROPS),
+            StreamEvent::Synced(1),
+        ]
+    );
+}
+
+#[test]
+fn shrink_capacity() {
+    let mut queue = EventQueue::new();
+
+    queue.push(proxy_param(0, Param::ROUTE));
+    queue.push(StreamEvent::Started);
+    queue.push(proxy_param(1, Param::ROUTE));
+    queue.push(proxy_param(2, Param::ROUTE));
+
+    queue.set_capacity(1);
+    assert_eq!(queue.take_dropped(), 3);
+    assert_eq!(drain(&mut queue), [StreamEvent::Started]);
+}
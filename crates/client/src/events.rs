@@ -1,6 +1,16 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use std::path::PathBuf;
+
+use protocol::object::{AudioFormat, Format};
 use protocol::{consts::Direction, id::Param};
 
-use crate::{ClientNodeId, PortId};
+use crate::{
+    ClientNodeId, ClipKind, ClipLatch, GlobalId, GlobalObject, MixId, Notification,
+    NotificationKind, OverloadDecision, PortId, ProxyId, RouteId,
+};
 
 /// A parameter for a client node has been set.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +36,54 @@ pub struct SetPortParamEvent {
     pub direction: Direction,
     pub port_id: PortId,
     pub param: Param,
+    /// The new format of the port, if the parameter set is [`Param::FORMAT`].
+    pub format: Option<Format>,
+}
+
+/// The negotiated audio format of the port of a client node has changed.
+///
+/// See [`StreamBuilder`].
+///
+/// [`StreamBuilder`]: crate::StreamBuilder
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormatChangedEvent {
+    pub node_id: ClientNodeId,
+    pub direction: Direction,
+    pub port_id: PortId,
+    /// The new format, or `None` if the format was cleared.
+    pub format: Option<AudioFormat>,
+}
+
+/// A format set on the port of a client node by the server has been rejected,
+/// since it is not accepted by the port.
+///
+/// The rejection is reported to the server as an error on the node, and the
+/// port keeps its previous format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormatRejectedEvent {
+    pub node_id: ClientNodeId,
+    pub direction: Direction,
+    pub port_id: PortId,
+    /// The rejected format, or `None` if it is not an audio format.
+    pub format: Option<AudioFormat>,
+}
+
+/// A preset could not be loaded into a client node, see
+/// [`Stream::load_preset`].
+///
+/// The node is left untouched.
+///
+/// [`Stream::load_preset`]: crate::Stream::load_preset
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PresetRejectedEvent {
+    pub node_id: ClientNodeId,
+    /// The path of the preset file.
+    pub path: PathBuf,
+    /// A description of why the preset was rejected.
+    pub error: String,
 }
 
 /// A parameter for the port of a client node has been removed.
@@ -38,6 +96,337 @@ pub struct RemovePortParamEvent {
     pub param: Param,
 }
 
+/// A new set of buffers is used by the port of a client node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UseBuffersEvent {
+    pub node_id: ClientNodeId,
+    pub direction: Direction,
+    pub port_id: PortId,
+    pub mix_id: MixId,
+    /// The number of buffers in use, which is zero if buffers were cleared.
+    pub n_buffers: usize,
+}
+
+/// A parameter of a bound proxy has been received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProxyParamEvent {
+    pub proxy_id: ProxyId,
+    pub param: Param,
+}
+
+/// A device has reported the state of a route after its volume was changed
+/// through [`Stream::set_route_volume`].
+///
+/// [`Stream::set_route_volume`]: crate::Stream::set_route_volume
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RouteVolumeEvent {
+    pub proxy_id: ProxyId,
+    pub route: RouteId,
+    /// Whether the reported route state matches the requested one.
+    pub applied: bool,
+}
+
+/// The overload policy of a client node has taken a decision.
+///
+/// See [`ClientNode::set_overload_policy`].
+///
+/// [`ClientNode::set_overload_policy`]: crate::ClientNode::set_overload_policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OverloadEvent {
+    pub node_id: ClientNodeId,
+    pub decision: OverloadDecision,
+    /// The number of decisions taken before this one which were dropped
+    /// because the stream did not report them in time.
+    ///
+    /// Decisions are always reported in the order they were taken, so the
+    /// latest decision reflects the current state of the policy.
+    pub dropped: u32,
+}
+
+/// A clip or overload indicator of a client node has latched.
+///
+/// See [`ClientNode::set_clip_detector`].
+///
+/// [`ClientNode::set_clip_detector`]: crate::ClientNode::set_clip_detector
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ClipEvent {
+    pub node_id: ClientNodeId,
+    pub latch: ClipLatch,
+}
+
+impl ClipEvent {
+    /// Describe the event as a notification which can be delivered through
+    /// a [`Notifier`].
+    ///
+    /// [`Notifier`]: crate::Notifier
+    pub fn notification(&self) -> Notification {
+        let message = match self.latch.kind {
+            ClipKind::Clip => format!("Channel {} clipped", self.latch.channel),
+            ClipKind::Overload(block) => {
+                format!("Channel {} overloaded in {block}", self.latch.channel)
+            }
+        };
+
+        Notification::new(NotificationKind::Clip, &message)
+    }
+}
+
+/// The sample rate of the graph a client node is part of has changed.
+///
+/// The coefficients of channel strips owned by the node, see
+/// [`ClientNode::add_strip`], have already been recalculated when this is
+/// emitted. Anything else derived from the sample rate, like filter
+/// coefficients or delays in samples, has to be recalculated. See
+/// [`ChannelStrip::coefficients`] for a way to do this ahead of time.
+///
+/// [`ClientNode::add_strip`]: crate::ClientNode::add_strip
+/// [`ChannelStrip::coefficients`]: crate::ChannelStrip::coefficients
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RateChangedEvent {
+    pub node_id: ClientNodeId,
+    /// The previous rate, if one had been observed.
+    pub previous: Option<u32>,
+    /// The new rate.
+    pub rate: u32,
+}
+
+/// The quantum of the graph a client node is part of has changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct QuantumChangedEvent {
+    pub node_id: ClientNodeId,
+    /// The previous quantum, if one had been observed.
+    pub previous: Option<u64>,
+    /// The new quantum in samples.
+    pub quantum: u64,
+}
+
+/// A property of a bound metadata object has been set or removed.
+///
+/// See [`Stream::subscribe_metadata`].
+///
+/// [`Stream::subscribe_metadata`]: crate::Stream::subscribe_metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MetadataPropertyEvent {
+    pub proxy_id: ProxyId,
+    pub subject: GlobalId,
+    /// The key of the property, or `None` if all properties of the subject
+    /// have been removed.
+    pub key: Option<String>,
+    /// The type of the value.
+    pub ty: Option<String>,
+    /// The new value of the property, or `None` if it has been removed.
+    pub value: Option<String>,
+}
+
+/// A change in the coordination with other instances, see [`Coordination`].
+///
+/// [`Coordination`]: crate::Coordination
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CoordinationEvent {
+    /// A peer instance has announced itself.
+    PeerJoined { peer: GlobalId, instance: String },
+    /// A peer instance has gone away, releasing its claims.
+    PeerLeft { peer: GlobalId },
+    /// The owner of a bus name has changed.
+    BusOwner {
+        bus: String,
+        /// The client id of the new owner, or `None` if the bus name is no
+        /// longer claimed.
+        owner: Option<GlobalId>,
+        /// Whether the bus name is owned by this instance.
+        owned: bool,
+    },
+    /// A different driver has been elected.
+    Driver {
+        /// The client id of the elected driver, or `None` if there are no
+        /// candidates.
+        driver: Option<GlobalId>,
+        /// Whether this instance is the elected driver.
+        elected: bool,
+    },
+}
+
+/// A message which was not understood by the stream.
+///
+/// This is only emitted if enabled through [`Stream::set_capture_unknown`].
+///
+/// [`Stream::set_capture_unknown`]: crate::Stream::set_capture_unknown
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UnknownMessageEvent {
+    /// The interface the message was addressed to, like `client-node`, or
+    /// `unknown` if the receiver is not known.
+    pub interface: &'static str,
+    /// The identifier of the receiver of the message.
+    pub id: u32,
+    /// The opcode of the message.
+    pub op: u8,
+    /// The raw payload of the message.
+    pub payload: Vec<u8>,
+}
+
+/// A message received from the server.
+///
+/// This is only emitted if enabled through [`Stream::set_capture_messages`],
+/// and is emitted before any events which result from processing it.
+///
+/// [`Stream::set_capture_messages`]: crate::Stream::set_capture_messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MessageEvent {
+    /// The interface the message was addressed to, like `client-node`, or
+    /// `unknown` if the receiver is not known.
+    pub interface: &'static str,
+    /// The identifier of the receiver of the message.
+    pub id: u32,
+    /// The opcode of the message.
+    pub op: u8,
+    /// The number of file descriptors which were passed with the message.
+    ///
+    /// File descriptors are referenced from the payload by their index.
+    pub fds: u32,
+    /// The raw payload of the message.
+    pub payload: Vec<u8>,
+}
+
+/// A MIDI message carried by the control sequence of a MIDI port.
+///
+/// Channels are numbered from 0 to 15.
+///
+/// # Examples
+///
+/// ```
+/// use client::events::MidiMessage;
+///
+/// let message = MidiMessage::parse(&[0x91, 60, 100]).unwrap();
+/// assert_eq!(message, MidiMessage::NoteOn { channel: 1, note: 60, velocity: 100 });
+/// assert_eq!(message.to_bytes(), [0x91, 60, 100]);
+///
+/// // A note on with a velocity of zero is a note off.
+/// let message = MidiMessage::parse(&[0x90, 60, 0]).unwrap();
+/// assert_eq!(message, MidiMessage::NoteOff { channel: 0, note: 60, velocity: 0 });
+///
+/// let message = MidiMessage::parse(&[0xb0, 7, 127]).unwrap();
+/// assert_eq!(message, MidiMessage::ControlChange { channel: 0, controller: 7, value: 127 });
+///
+/// // Other messages are not supported.
+/// assert!(MidiMessage::parse(&[0xf8]).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MidiMessage {
+    /// A note was released.
+    NoteOff { channel: u8, note: u8, velocity: u8 },
+    /// A note was pressed.
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    /// The value of a controller changed.
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+}
+
+impl MidiMessage {
+    /// Parse a message from raw MIDI bytes.
+    ///
+    /// Returns `None` if the bytes are not a note or control change message.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let &[status, a, b] = bytes else {
+            return None;
+        };
+
+        if a > 0x7f || b > 0x7f {
+            return None;
+        }
+
+        let channel = status & 0x0f;
+
+        let message = match status & 0xf0 {
+            0x80 => Self::NoteOff {
+                channel,
+                note: a,
+                velocity: b,
+            },
+            0x90 if b == 0 => Self::NoteOff {
+                channel,
+                note: a,
+                velocity: 0,
+            },
+            0x90 => Self::NoteOn {
+                channel,
+                note: a,
+                velocity: b,
+            },
+            0xb0 => Self::ControlChange {
+                channel,
+                controller: a,
+                value: b,
+            },
+            _ => return None,
+        };
+
+        Some(message)
+    }
+
+    /// Convert the message into raw MIDI bytes.
+    ///
+    /// Out of range values are masked to fit.
+    pub fn to_bytes(&self) -> [u8; 3] {
+        let (status, channel, a, b) = match *self {
+            Self::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => (0x80, channel, note, velocity),
+            Self::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => (0x90, channel, note, velocity),
+            Self::ControlChange {
+                channel,
+                controller,
+                value,
+            } => (0xb0, channel, controller, value),
+        };
+
+        [status | (channel & 0x0f), a & 0x7f, b & 0x7f]
+    }
+}
+
+/// A MIDI message at an offset in frames from the start of the cycle.
+///
+/// These are read from and written to the buffers of MIDI ports, see
+/// [`Ports::insert_midi`].
+///
+/// [`Ports::insert_midi`]: crate::Ports::insert_midi
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MidiEvent {
+    /// The offset in frames from the start of the cycle.
+    pub offset: u32,
+    /// The message.
+    pub message: MidiMessage,
+}
+
+impl MidiEvent {
+    /// Construct a new event at the given offset.
+    #[inline]
+    pub fn new(offset: u32, message: MidiMessage) -> Self {
+        Self { offset, message }
+    }
+}
+
 /// A kind of object.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -52,10 +441,75 @@ pub enum ObjectKind {
 pub enum StreamEvent {
     /// The stream has been configured.
     Started,
+    /// The connection to the server has been lost, and the stream will be
+    /// resumed once it reconnects.
+    ///
+    /// This implies that every global object has been removed from the
+    /// registry, and no [`StreamEvent::GlobalRemoved`] is emitted for them.
+    /// Bound proxies are dropped.
+    ///
+    /// See [`Stream::set_reconnect`].
+    ///
+    /// [`Stream::set_reconnect`]: crate::Stream::set_reconnect
+    Disconnected,
+    /// The stream has reconnected to the server and re-created every client
+    /// node with its ports and parameters.
+    ///
+    /// Objects which were bound through the previous connection, like
+    /// proxies, have to be bound again.
+    Reconnected,
     Process(ClientNodeId),
     ObjectCreated(ObjectKind),
+    /// A global object has been announced by the registry.
+    GlobalAdded(GlobalObject),
+    /// A global object has been removed from the registry.
+    GlobalRemoved(GlobalObject),
     SetNodeParam(SetNodeParamEvent),
     RemoveNodeParam(RemoveNodeParamEvent),
     SetPortParam(SetPortParamEvent),
     RemovePortParam(RemovePortParamEvent),
+    /// The negotiated audio format of a port has changed.
+    FormatChanged(FormatChangedEvent),
+    /// A format set on a port has been rejected.
+    FormatRejected(FormatRejectedEvent),
+    /// A preset could not be loaded into a node.
+    PresetRejected(PresetRejectedEvent),
+    /// Buffers have been assigned to a port.
+    UseBuffers(UseBuffersEvent),
+    /// A subscribed or enumerated parameter of a bound proxy has been received.
+    ProxyParam(ProxyParamEvent),
+    /// The volume change of a device route has been acknowledged.
+    RouteVolume(RouteVolumeEvent),
+    /// A property of a bound metadata object has changed.
+    MetadataProperty(MetadataPropertyEvent),
+    /// A node has been deactivated because it was idle for longer than its
+    /// configured idle timeout.
+    NodeSuspended(ClientNodeId),
+    /// A previously suspended node has been re-activated since a link to it
+    /// appeared.
+    NodeResumed(ClientNodeId),
+    /// The overload policy of a node has engaged or released its action.
+    Overload(OverloadEvent),
+    /// A clip or overload indicator of a node has latched.
+    Clip(ClipEvent),
+    /// The sample rate of the graph a node is part of has changed.
+    RateChanged(RateChangedEvent),
+    /// The quantum of the graph a node is part of has changed.
+    QuantumChanged(QuantumChangedEvent),
+    /// The coordination with other instances has changed.
+    Coordination(CoordinationEvent),
+    /// A shutdown initiated through [`Stream::shutdown`] has been
+    /// acknowledged by the server, after which the stream can be dropped.
+    ///
+    /// [`Stream::shutdown`]: crate::Stream::shutdown
+    Shutdown,
+    /// A synchronization requested through [`Stream::sync`] has completed,
+    /// with the sequence number it returned.
+    ///
+    /// [`Stream::sync`]: crate::Stream::sync
+    Synced(u32),
+    /// A message which was not understood has been captured.
+    UnknownMessage(UnknownMessageEvent),
+    /// A message received from the server has been captured.
+    Message(MessageEvent),
 }
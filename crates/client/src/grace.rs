@@ -0,0 +1,103 @@
+//! Grace periods for deferred reclamation.
+//!
+//! This implements quiescent-state based reclamation. The control thread
+//! advances a global epoch whenever it retires something which might still be
+//! observed by a reader, like a memory mapping. Readers publish the epoch they
+//! observed when entering a processing cycle, and clear it again once they
+//! leave it, which is their quiescent point.
+//!
+//! Anything retired at epoch `E` can be reclaimed once no reader is inside of
+//! a cycle which was entered before `E`.
+
+#[cfg(test)]
+mod tests;
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// The value published by a reader which is outside of a processing cycle.
+const QUIESCENT: u64 = u64::MAX;
+
+/// The global epoch.
+#[derive(Debug, Clone)]
+pub(crate) struct Epoch {
+    current: Arc<AtomicU64>,
+}
+
+impl Epoch {
+    /// Construct a new global epoch.
+    pub(crate) fn new() -> Self {
+        Self {
+            current: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Advance the epoch, returning the epoch something retired at this point
+    /// is associated with.
+    ///
+    /// This must be called after the retired value has been made unreachable
+    /// to readers.
+    #[inline]
+    pub(crate) fn advance(&self) -> u64 {
+        self.current.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Construct a new reader associated with this epoch.
+    pub(crate) fn reader(&self) -> Reader {
+        Reader {
+            current: self.current.clone(),
+            observed: Arc::new(AtomicU64::new(QUIESCENT)),
+        }
+    }
+}
+
+/// A reader which is tracked for the purpose of grace periods.
+#[derive(Debug)]
+pub(crate) struct Reader {
+    current: Arc<AtomicU64>,
+    observed: Arc<AtomicU64>,
+}
+
+impl Reader {
+    /// Enter a critical section, like a processing cycle.
+    #[inline]
+    pub(crate) fn enter(&self) {
+        let mut epoch = self.current.load(Ordering::SeqCst);
+
+        // NB: The epoch might advance between loading and publishing it, in
+        // which case the control thread might already have decided that
+        // something retired at the new epoch is reclaimable while we still
+        // hold on to it. So we publish the epoch until it is stable, after
+        // which any advance of the epoch is followed by a check that sees the
+        // published epoch.
+        loop {
+            self.observed.store(epoch, Ordering::SeqCst);
+            let current = self.current.load(Ordering::SeqCst);
+
+            if current == epoch {
+                break;
+            }
+
+            epoch = current;
+        }
+    }
+
+    /// Leave a critical section, which marks a quiescent point.
+    #[inline]
+    pub(crate) fn leave(&self) {
+        self.observed.store(QUIESCENT, Ordering::Release);
+    }
+
+    /// Get the oldest epoch which might still be observed by this reader.
+    #[inline]
+    pub(crate) fn observed(&self) -> u64 {
+        self.observed.load(Ordering::Acquire)
+    }
+}
+
+/// Test if something retired at the given epoch can be reclaimed, given the
+/// oldest epoch observed by any reader.
+#[inline]
+pub(crate) fn is_reclaimable(retired: u64, oldest: u64) -> bool {
+    oldest >= retired
+}
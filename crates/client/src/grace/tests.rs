@@ -0,0 +1,121 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use std::thread;
+
+use super::{Epoch, QUIESCENT, is_reclaimable};
+
+#[test]
+fn quiescent_reader() {
+    let epoch = Epoch::new();
+    let reader = epoch.reader();
+
+    assert_eq!(reader.observed(), QUIESCENT);
+
+    let retired = epoch.advance();
+    assert!(is_reclaimable(retired, reader.observed()));
+}
+
+#[test]
+fn reader_in_cycle() {
+    let epoch = Epoch::new();
+    let reader = epoch.reader();
+
+    reader.enter();
+    let retired = epoch.advance();
+    assert!(!is_reclaimable(retired, reader.observed()));
+
+    reader.leave();
+    assert!(is_reclaimable(retired, reader.observed()));
+}
+
+#[test]
+fn reader_entered_after_retire() {
+    let epoch = Epoch::new();
+    let reader = epoch.reader();
+
+    let retired = epoch.advance();
+    reader.enter();
+    assert!(is_reclaimable(retired, reader.observed()));
+
+    // Anything retired later has to wait for the reader.
+    let later = epoch.advance();
+    assert!(!is_reclaimable(later, reader.observed()));
+
+    reader.leave();
+    assert!(is_reclaimable(later, reader.observed()));
+}
+
+#[test]
+fn oldest_reader_holds_back_reclamation() {
+    let epoch = Epoch::new();
+    let a = epoch.reader();
+    let b = epoch.reader();
+
+    a.enter();
+    let first = epoch.advance();
+    b.enter();
+    let second = epoch.advance();
+
+    let oldest = a.observed().min(b.observed());
+    assert!(!is_reclaimable(first, oldest));
+    assert!(!is_reclaimable(second, oldest));
+
+    a.leave();
+    let oldest = a.observed().min(b.observed());
+    assert!(is_reclaimable(first, oldest));
+    assert!(!is_reclaimable(second, oldest));
+
+    b.leave();
+    let oldest = a.observed().min(b.observed());
+    assert!(is_reclaimable(second, oldest));
+}
+
+/// A reader concurrently entering cycles must never observe a generation of
+/// a value which has been reclaimed.
+#[test]
+fn concurrent_reclamation() {
+    const GENERATIONS: u64 = 10_000;
+
+    let epoch = Epoch::new();
+    let reader = Arc::new(epoch.reader());
+    let published = Arc::new(AtomicU64::new(1));
+    let reclaimed = Arc::new(AtomicU64::new(0));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let handle = thread::spawn({
+        let reader = reader.clone();
+        let published = published.clone();
+        let reclaimed = reclaimed.clone();
+        let done = done.clone();
+
+        move || {
+            while !done.load(Ordering::SeqCst) {
+                reader.enter();
+                let generation = published.load(Ordering::SeqCst);
+
+                for _ in 0..16 {
+                    core::hint::spin_loop();
+                }
+
+                assert!(reclaimed.load(Ordering::SeqCst) < generation);
+                reader.leave();
+            }
+        }
+    });
+
+    for generation in 2..=GENERATIONS {
+        // Make the previous generation unreachable, then retire it.
+        published.store(generation, Ordering::SeqCst);
+        let retired = epoch.advance();
+
+        while !is_reclaimable(retired, reader.observed()) {
+            core::hint::spin_loop();
+        }
+
+        reclaimed.store(generation - 1, Ordering::SeqCst);
+    }
+
+    done.store(true, Ordering::SeqCst);
+    handle.join().unwrap();
+}
@@ -67,9 +67,42 @@ macro_rules! id {
 id! {
     pub struct LocalId;
     pub struct GlobalId;
+    pub struct ProxyId;
 }
 
 impl GlobalId {
     /// An invalid global ID.
     pub const INVALID: Self = Self(u32::MAX);
 }
+
+/// A reference to a global object which includes its serial.
+///
+/// Global identifiers are reused by the server once an object is removed,
+/// while serials are never reused. So a reference can be held on to, and
+/// resolving it through [`Stream::resolve`] fails if the object it refers to
+/// has since been replaced.
+///
+/// [`Stream::resolve`]: crate::Stream::resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GlobalRef {
+    /// The identifier of the global object.
+    pub id: GlobalId,
+    /// The serial of the global object, as specified by the `object.serial`
+    /// property.
+    pub serial: u64,
+}
+
+impl GlobalRef {
+    /// Construct a new global reference.
+    #[inline]
+    pub fn new(id: GlobalId, serial: u64) -> Self {
+        Self { id, serial }
+    }
+}
+
+impl fmt::Display for GlobalRef {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.id, self.serial)
+    }
+}
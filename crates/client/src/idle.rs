@@ -0,0 +1,166 @@
+use core::time::Duration;
+
+/// A transition of a node decided by an [`IdlePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdleTransition {
+    /// The node has been idle for too long and should be deactivated.
+    Suspend,
+    /// A link to a suspended node has appeared and it should be activated
+    /// again.
+    Resume,
+}
+
+/// The policy which deactivates a node once it has been active without any
+/// links for longer than a timeout, and activates it again once a link to it
+/// appears.
+///
+/// Times are monotonic timestamps in nanoseconds, like the ones returned by
+/// [`utils::get_monotonic_nsec`].
+///
+/// Every client node has a policy which is driven by the [`Stream`] each time
+/// it is run, and whose timeout is set through
+/// [`ClientNode::set_idle_timeout`]. Since idle nodes are only suspended when
+/// the stream is run, callers which want them to be suspended promptly should
+/// make sure the stream is run by [`Stream::idle_deadline`].
+///
+/// [`utils::get_monotonic_nsec`]: crate::utils::get_monotonic_nsec
+/// [`Stream`]: crate::Stream
+/// [`ClientNode::set_idle_timeout`]: crate::ClientNode::set_idle_timeout
+/// [`Stream::idle_deadline`]: crate::Stream::idle_deadline
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use client::{IdlePolicy, IdleTransition};
+///
+/// const S: u64 = 1_000_000_000;
+///
+/// let mut idle = IdlePolicy::new();
+/// idle.set_timeout(Some(Duration::from_secs(5)));
+///
+/// // An active node without links is idle.
+/// idle.set_active(true, 0);
+/// assert_eq!(idle.deadline(), Some(5 * S));
+/// assert_eq!(idle.poll(4 * S), None);
+///
+/// // Once the timeout has passed, it is deactivated.
+/// assert_eq!(idle.poll(5 * S), Some(IdleTransition::Suspend));
+/// assert!(idle.is_suspended());
+/// assert_eq!(idle.deadline(), None);
+///
+/// // A link to the node activates it again.
+/// assert_eq!(idle.link_added(), Some(IdleTransition::Resume));
+/// assert!(!idle.is_suspended());
+/// assert_eq!(idle.poll(100 * S), None);
+///
+/// // Once the link is removed it is idle again.
+/// idle.link_removed(100 * S);
+/// assert_eq!(idle.deadline(), Some(105 * S));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct IdlePolicy {
+    timeout: Option<Duration>,
+    active: bool,
+    suspended: bool,
+    links: usize,
+    since: Option<u64>,
+}
+
+impl IdlePolicy {
+    /// Construct a new policy for an inactive node without a timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how long the node is allowed to stay active without any links.
+    ///
+    /// Setting this to `None` disables the policy.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Get the configured timeout.
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Test if the node has been deactivated due to inactivity.
+    #[inline]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Get the number of links connected to the node.
+    #[inline]
+    pub fn links(&self) -> usize {
+        self.links
+    }
+
+    /// Mark the node as explicitly activated or deactivated, which clears any
+    /// suspension.
+    pub fn set_active(&mut self, active: bool, now: u64) {
+        self.active = active;
+        self.suspended = false;
+        self.since = (active && self.links == 0).then_some(now);
+    }
+
+    /// Register a link to the node.
+    ///
+    /// Returns [`IdleTransition::Resume`] if the node was suspended.
+    pub fn link_added(&mut self) -> Option<IdleTransition> {
+        self.links += 1;
+        self.since = None;
+
+        if !self.suspended {
+            return None;
+        }
+
+        self.suspended = false;
+        Some(IdleTransition::Resume)
+    }
+
+    /// Register that a link to the node has been removed.
+    pub fn link_removed(&mut self, now: u64) {
+        self.links = self.links.saturating_sub(1);
+
+        if self.links == 0 {
+            self.since = Some(now);
+        }
+    }
+
+    /// The time at which the node is suspended, if it is idle.
+    pub fn deadline(&self) -> Option<u64> {
+        if !self.active || self.suspended || self.links > 0 {
+            return None;
+        }
+
+        let timeout = u64::try_from(self.timeout?.as_nanos()).unwrap_or(u64::MAX);
+        Some(self.since?.saturating_add(timeout))
+    }
+
+    /// Test if the node should be suspended at the given time.
+    ///
+    /// Returns [`IdleTransition::Suspend`] once, after which the node is
+    /// considered suspended.
+    pub fn poll(&mut self, now: u64) -> Option<IdleTransition> {
+        if self.deadline()? > now {
+            return None;
+        }
+
+        self.suspended = true;
+        Some(IdleTransition::Suspend)
+    }
+
+    /// Reset the links and suspension of the node, like when the connection
+    /// to the server has been lost.
+    pub fn reset(&mut self) {
+        self.suspended = false;
+        self.links = 0;
+        self.since = None;
+    }
+}
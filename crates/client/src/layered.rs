@@ -0,0 +1,104 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+use protocol::Properties;
+
+/// The layer a resolved property was taken from.
+///
+/// Layers are ordered from least to most specific, where more specific layers
+/// override less specific ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum PropertyLayer {
+    /// Defaults for all nodes or ports of the client, see
+    /// [`Stream::node_defaults_mut`] and [`Stream::port_defaults_mut`].
+    ///
+    /// [`Stream::node_defaults_mut`]: crate::Stream::node_defaults_mut
+    /// [`Stream::port_defaults_mut`]: crate::Stream::port_defaults_mut
+    Client,
+    /// Properties of a node, or defaults for the ports of a node, see
+    /// [`ClientNode::port_props`].
+    ///
+    /// [`ClientNode::port_props`]: crate::ClientNode::port_props
+    Node,
+    /// Properties of a port.
+    Port,
+}
+
+/// Properties resolved from a stack of layers, which remembers which layer
+/// each property was taken from.
+///
+/// # Examples
+///
+/// ```
+/// use client::{PropertyLayer, ResolvedProperties};
+/// use protocol::Properties;
+///
+/// let mut defaults = Properties::new();
+/// defaults.insert("media.role", "Music");
+/// defaults.insert("node.description", "Default");
+///
+/// let mut node = Properties::new();
+/// node.insert("node.description", "Channel 1");
+///
+/// let resolved = ResolvedProperties::resolve([
+///     (PropertyLayer::Client, &defaults),
+///     (PropertyLayer::Node, &node),
+/// ]);
+///
+/// assert_eq!(resolved.get("node.description"), Some("Channel 1"));
+/// assert_eq!(resolved.layer("node.description"), Some(PropertyLayer::Node));
+/// assert_eq!(resolved.get("media.role"), Some("Music"));
+/// assert_eq!(resolved.layer("media.role"), Some(PropertyLayer::Client));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ResolvedProperties {
+    entries: BTreeMap<String, (String, PropertyLayer)>,
+}
+
+impl ResolvedProperties {
+    /// Resolve properties from the given layers, where later layers override
+    /// earlier ones.
+    pub fn resolve<'a>(layers: impl IntoIterator<Item = (PropertyLayer, &'a Properties)>) -> Self {
+        let mut entries = BTreeMap::new();
+
+        for (layer, props) in layers {
+            for (key, value) in props {
+                entries.insert(String::from(key.as_str()), (String::from(value), layer));
+            }
+        }
+
+        Self { entries }
+    }
+
+    /// Get the resolved value of a property.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let (value, _) = self.entries.get(key)?;
+        Some(value.as_str())
+    }
+
+    /// Get the layer a property was resolved from.
+    pub fn layer(&self, key: &str) -> Option<PropertyLayer> {
+        let (_, layer) = self.entries.get(key)?;
+        Some(*layer)
+    }
+
+    /// Iterate over the resolved properties and the layer they were resolved
+    /// from.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, PropertyLayer)> + '_ {
+        self.entries
+            .iter()
+            .map(|(key, (value, layer))| (key.as_str(), value.as_str(), *layer))
+    }
+
+    /// Convert into a flat collection of properties.
+    pub fn to_properties(&self) -> Properties {
+        let mut props = Properties::new();
+
+        for (key, value, _) in self.iter() {
+            props.insert(key, value);
+        }
+
+        props
+    }
+}
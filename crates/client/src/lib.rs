@@ -15,30 +15,121 @@ use self::client::Client;
 mod stream;
 pub use self::stream::Stream;
 
+mod stream_builder;
+pub use self::stream_builder::{FormatSpec, StreamBuilder};
+
+#[cfg(feature = "tokio")]
+mod async_stream;
+#[cfg(feature = "tokio")]
+pub use self::async_stream::AsyncStream;
+
+mod grace;
+
+mod event_queue;
+mod warnings;
+
+mod subscription;
+pub use self::subscription::{EventClasses, Subscription};
+
 pub mod memory;
 use self::memory::{Memory, Region};
 
-mod buffer;
+pub mod buffer;
 use self::buffer::Buffers;
 
 mod client_node;
 pub use self::client_node::{ClientNode, ClientNodeId, ClientNodes};
 
 mod ports;
-pub use self::ports::{MixId, Port, PortId, PortParam, Ports};
+pub use self::ports::{MixId, Port, PortId, PortKey, PortParam, Ports};
 
 mod activation;
 pub use self::activation::PeerActivation;
 
+#[cfg(feature = "dump")]
+pub mod dump;
 pub mod events;
+pub mod offline;
 pub mod ptr;
+pub mod sim;
 pub mod utils;
 
 mod stats;
-pub use self::stats::Stats;
+pub use self::stats::{
+    BlockId, BlockStats, ControlStats, STATS_OBJECT_TYPE, STATS_PARAM, Stats, StatsSnapshot,
+};
+
+mod clock;
+pub use self::clock::ClockTime;
+
+mod clock_source;
+pub use self::clock_source::{
+    ClockSource, ClockTick, Cycle, CycleScheduler, FdClock, FollowerClock, TimerClock,
+};
+
+mod process_chunks;
+pub use self::process_chunks::{ProcessChunk, ProcessChunks};
+
+mod clip;
+pub use self::clip::{ClipDetector, ClipKind, ClipLatch};
+
+mod overload;
+use self::overload::OverloadState;
+pub use self::overload::{OverloadAction, OverloadDecision, OverloadPolicy};
 
 mod parameters;
 pub use self::parameters::Parameters;
 
 mod id;
-pub use self::id::{GlobalId, LocalId};
+pub use self::id::{GlobalId, GlobalRef, LocalId, ProxyId};
+
+mod param_cache;
+pub use self::param_cache::ParamCache;
+
+mod param_refresh;
+pub use self::param_refresh::{ParamRefresh, Refresh};
+
+mod idle;
+pub use self::idle::{IdlePolicy, IdleTransition};
+
+mod metadata;
+pub use self::metadata::{Metadata, MetadataEntry};
+
+mod proxy;
+pub use self::proxy::{DeviceProxy, Proxies, Proxy, ProxyKind};
+
+mod route;
+pub use self::route::{RouteId, RouteVolume};
+
+mod bluetooth;
+pub use self::bluetooth::{BluetoothCodec, BluetoothCodecs};
+
+mod capabilities;
+pub use self::capabilities::{Access, Capabilities};
+
+mod security_context;
+pub use self::security_context::SecurityContext;
+
+mod layered;
+pub use self::layered::{PropertyLayer, ResolvedProperties};
+
+mod channel_strip;
+pub use self::channel_strip::{ChannelStrip, StripBlock, StripCoefficients, StripTimers};
+
+mod coordination;
+pub use self::coordination::{Coordination, Peer};
+
+mod session;
+pub use self::session::{NodeRef, Session, SessionEntry, SessionLink};
+
+mod preset_watcher;
+pub use self::preset_watcher::PresetWatcher;
+
+mod registry_filter;
+pub use self::registry_filter::RegistryFilter;
+
+mod registry;
+pub use self::registry::{Device, GlobalObject, Link, Node, Registry};
+
+mod notify;
+pub use self::notify::{Notification, NotificationKind, Notifier, NotifyRule, NotifyTarget};
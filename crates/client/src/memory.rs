@@ -7,7 +7,10 @@ use core::mem::{self, MaybeUninit};
 use core::ptr::NonNull;
 
 use core::slice;
-use std::collections::HashMap;
+
+use alloc::vec::Vec;
+
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::os::fd::AsRawFd;
 use std::os::fd::OwnedFd;
@@ -19,6 +22,8 @@ use protocol::id;
 use slab::Slab;
 use tracing::Level;
 
+use crate::grace::{self, Epoch};
+
 #[derive(Debug)]
 #[allow(unused)]
 pub(crate) struct File {
@@ -27,9 +32,32 @@ pub(crate) struct File {
     fd: OwnedFd,
     flags: flags::MemBlock,
     users: u32,
+    revoked: bool,
     region: Option<Region<[MaybeUninit<u8>]>>,
 }
 
+/// Error raised when trying to map memory which has been revoked by the
+/// server.
+///
+/// Memory is revoked through the `Core::RemoveMem` event. Any regions which
+/// refer to revoked memory are released by the stream, and further attempts to
+/// map it result in this error until the identifier is re-used by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RevokedMemory {
+    /// The identifier of the revoked memory.
+    pub mem_id: u32,
+}
+
+impl fmt::Display for RevokedMemory {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Memory {} has been revoked", self.mem_id)
+    }
+}
+
+impl core::error::Error for RevokedMemory {}
+
 /// A region of memory which is mapped to a file descriptor.
 ///
 /// # Examples
@@ -393,6 +421,11 @@ where
 pub(crate) struct Memory {
     map: HashMap<u32, usize>,
     files: Slab<File>,
+    revoked: HashSet<u32>,
+    epoch: Epoch,
+    /// Files which are no longer used, but which have not yet been unmapped,
+    /// together with the epoch they were retired at.
+    pending: Vec<(u64, File)>,
 }
 
 impl Memory {
@@ -401,6 +434,9 @@ impl Memory {
         Self {
             map: HashMap::new(),
             files: Slab::new(),
+            revoked: HashSet::new(),
+            epoch: Epoch::new(),
+            pending: Vec::new(),
         }
     }
 
@@ -470,9 +506,12 @@ impl Memory {
             fd,
             flags,
             users: 1,
+            revoked: false,
             region: Some(region),
         });
 
+        self.revoked.remove(&mem_id);
+
         if let Some(old) = self.map.insert(mem_id, file) {
             self.free_file(old);
         }
@@ -499,6 +538,90 @@ impl Memory {
         self.free_file(index);
     }
 
+    /// Revoke memory by its identifier.
+    ///
+    /// This prevents the memory from being mapped again, and marks any regions
+    /// referring to it as revoked. The memory is only unmapped once all regions
+    /// referring to it have been freed, see [`Memory::reclaim`].
+    ///
+    /// Returns `true` if the memory was revoked.
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn revoke(&mut self, mem_id: u32) -> bool {
+        let Some(index) = self.map.remove(&mem_id) else {
+            tracing::warn!("Tried to revoke memory with id {mem_id} but it was not found");
+            return false;
+        };
+
+        self.revoked.insert(mem_id);
+
+        if let Some(file) = self.files.get_mut(index) {
+            file.revoked = true;
+        }
+
+        self.free_file(index);
+        true
+    }
+
+    /// Revoke all memory, such as when the connection to the server has been
+    /// lost.
+    pub(crate) fn revoke_all(&mut self) {
+        let mem_ids = self.map.keys().copied().collect::<Vec<_>>();
+
+        for mem_id in mem_ids {
+            self.revoke(mem_id);
+        }
+    }
+
+    /// Test if the given region refers to revoked memory.
+    pub(crate) fn is_revoked<T>(&self, region: &Region<T>) -> bool
+    where
+        T: ?Sized,
+    {
+        self.files.get(region.file).is_none_or(|file| file.revoked)
+    }
+
+    /// Get the epoch used to track grace periods for unmapping memory.
+    ///
+    /// Readers of memory, like processing nodes, must be associated with this
+    /// epoch.
+    #[inline]
+    pub(crate) fn epoch(&self) -> &Epoch {
+        &self.epoch
+    }
+
+    /// Test if there is memory which is waiting to be unmapped.
+    #[inline]
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Unmap memory which is no longer in use and whose grace period has
+    /// passed.
+    ///
+    /// The `oldest` epoch is the oldest epoch observed by any reader, since a
+    /// reader which entered a cycle before memory was retired might still be
+    /// reading from it.
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn reclaim(&mut self, oldest: u64) {
+        for (_, file) in self
+            .pending
+            .extract_if(.., |(epoch, _)| grace::is_reclaimable(*epoch, oldest))
+        {
+            let Some(region) = file.region else {
+                continue;
+            };
+
+            // SAFETY: The file is no longer in use and its grace period has
+            // passed, so nothing refers to the mapped region.
+            unsafe {
+                if libc::munmap(region.ptr.as_ptr().cast(), region.size) == -1 {
+                    let error = io::Error::last_os_error();
+                    tracing::error!(file = file.file, %error, "Failed to unmap memory");
+                }
+            }
+        }
+    }
+
     /// Drop a mapped memory region.
     #[tracing::instrument(skip(self))]
     pub(crate) fn free<T>(&mut self, region: Region<T>)
@@ -530,6 +653,10 @@ impl Memory {
             .get_mut(&mem_id)
             .and_then(|&mut index| self.files.get_mut(index))
         else {
+            if self.revoked.contains(&mem_id) {
+                bail!(RevokedMemory { mem_id });
+            }
+
             bail!("Memory {mem_id} missing");
         };
 
@@ -558,7 +685,11 @@ impl Memory {
             return false;
         }
 
-        self.files.remove(file);
+        // NB: Unmapping is deferred until the grace period of the file has
+        // passed, see `Memory::reclaim`.
+        let file = self.files.remove(file);
+        let epoch = self.epoch.advance();
+        self.pending.push((epoch, file));
         true
     }
 }
@@ -0,0 +1,127 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+use std::collections::BTreeMap;
+
+use crate::GlobalId;
+
+/// A property of a subject in a [`Metadata`] object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataEntry {
+    ty: Option<String>,
+    value: String,
+}
+
+impl MetadataEntry {
+    /// The type of the value, like `Spa:String:JSON`.
+    #[inline]
+    pub fn ty(&self) -> Option<&str> {
+        self.ty.as_deref()
+    }
+
+    /// The value of the property.
+    #[inline]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// The properties of a bound metadata object.
+///
+/// Properties are populated through `Property` events, which are sent for all
+/// existing properties once the metadata object has been bound and as they
+/// change after that.
+///
+/// Properties are set on subjects, which are global objects. Properties which
+/// apply to the server as a whole, like `default.audio.sink`, are set on the
+/// subject `0`.
+///
+/// See [`Stream::subscribe_metadata`].
+///
+/// [`Stream::subscribe_metadata`]: crate::Stream::subscribe_metadata
+#[derive(Debug, Default)]
+pub struct Metadata {
+    subjects: BTreeMap<GlobalId, BTreeMap<String, MetadataEntry>>,
+}
+
+impl Metadata {
+    /// Construct a new empty metadata object.
+    pub fn new() -> Self {
+        Self {
+            subjects: BTreeMap::new(),
+        }
+    }
+
+    /// Test if there are no properties.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.subjects.is_empty()
+    }
+
+    /// Get a property of a subject.
+    pub fn get(&self, subject: GlobalId, key: &str) -> Option<&MetadataEntry> {
+        self.subjects.get(&subject)?.get(key)
+    }
+
+    /// Get the value of a property of a subject.
+    pub fn value(&self, subject: GlobalId, key: &str) -> Option<&str> {
+        Some(self.get(subject, key)?.value())
+    }
+
+    /// Iterate over the properties of a subject.
+    pub fn subject(&self, subject: GlobalId) -> impl Iterator<Item = (&str, &MetadataEntry)> {
+        self.subjects
+            .get(&subject)
+            .into_iter()
+            .flatten()
+            .map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// Iterate over all properties.
+    pub fn iter(&self) -> impl Iterator<Item = (GlobalId, &str, &MetadataEntry)> {
+        self.subjects.iter().flat_map(|(&subject, entries)| {
+            entries
+                .iter()
+                .map(move |(key, entry)| (subject, key.as_str(), entry))
+        })
+    }
+
+    /// Apply a `Property` event.
+    ///
+    /// If `key` is `None`, all properties of the subject are removed. If
+    /// `value` is `None`, the property is removed.
+    pub(crate) fn apply(
+        &mut self,
+        subject: GlobalId,
+        key: Option<&str>,
+        ty: Option<&str>,
+        value: Option<&str>,
+    ) {
+        let Some(key) = key else {
+            self.subjects.remove(&subject);
+            return;
+        };
+
+        let Some(value) = value else {
+            if let Some(entries) = self.subjects.get_mut(&subject) {
+                entries.remove(key);
+
+                if entries.is_empty() {
+                    self.subjects.remove(&subject);
+                }
+            }
+
+            return;
+        };
+
+        let entry = MetadataEntry {
+            ty: ty.map(str::to_owned),
+            value: value.to_owned(),
+        };
+
+        self.subjects
+            .entry(subject)
+            .or_default()
+            .insert(key.to_owned(), entry);
+    }
+}
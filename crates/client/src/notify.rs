@@ -0,0 +1,500 @@
+use core::fmt;
+use core::time::Duration;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+
+use crate::events::StreamEvent;
+use crate::utils::JsonString;
+
+#[cfg(test)]
+mod tests;
+
+/// How long to wait on the network when posting a notification.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The kind of an event which can be notified about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum NotificationKind {
+    /// Processing missed one or more cycles.
+    Xrun,
+    /// A device which was in use has gone away.
+    DeviceLost,
+    /// A recording has started.
+    RecordingStarted,
+    /// A recording has stopped.
+    RecordingStopped,
+    /// A signal has clipped.
+    Clip,
+}
+
+impl NotificationKind {
+    /// Every kind of notification.
+    pub const ALL: [NotificationKind; 5] = [
+        NotificationKind::Xrun,
+        NotificationKind::DeviceLost,
+        NotificationKind::RecordingStarted,
+        NotificationKind::RecordingStopped,
+        NotificationKind::Clip,
+    ];
+
+    /// The name of the kind, which is what external targets receive.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotificationKind::Xrun => "xrun",
+            NotificationKind::DeviceLost => "device-lost",
+            NotificationKind::RecordingStarted => "recording-started",
+            NotificationKind::RecordingStopped => "recording-stopped",
+            NotificationKind::Clip => "clip",
+        }
+    }
+}
+
+impl fmt::Display for NotificationKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A notification about an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Notification {
+    /// The kind of the event.
+    pub kind: NotificationKind,
+    /// A human readable description of the event.
+    pub message: String,
+}
+
+impl Notification {
+    /// Construct a new notification.
+    pub fn new(kind: NotificationKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: String::from(message),
+        }
+    }
+
+    /// Describe a stream event as a notification, if it corresponds to one.
+    ///
+    /// Only [`StreamEvent::Clip`] does, since xruns, lost devices and
+    /// recordings are not reported by the stream.
+    pub fn from_event(event: &StreamEvent) -> Option<Self> {
+        match event {
+            StreamEvent::Clip(event) => Some(event.notification()),
+            _ => None,
+        }
+    }
+}
+
+/// Where a notification is delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NotifyTarget {
+    /// Execute a command without waiting for it to complete.
+    ///
+    /// The notification is passed through the `LIVEMIX_EVENT`,
+    /// `LIVEMIX_MESSAGE` and `LIVEMIX_SUPPRESSED` environment variables, where
+    /// the latter is the number of notifications of the same kind which were
+    /// dropped by rate limiting since the last one was delivered.
+    Exec {
+        /// The program to execute.
+        program: String,
+        /// Arguments to the program.
+        args: Vec<String>,
+    },
+    /// Emit a signal on the session bus through `dbus-send`, with the event
+    /// name, message and suppressed count as arguments.
+    DBus {
+        /// The object path of the signal.
+        path: String,
+        /// The interface of the signal.
+        interface: String,
+        /// The name of the signal.
+        member: String,
+    },
+    /// Post the notification as JSON to an `http://` URL.
+    ///
+    /// The request is made on a separate thread so that delivery doesn't
+    /// block the caller. Failures are logged since they are only known after
+    /// the notification has been handed off.
+    HttpPost {
+        /// The URL to post to.
+        url: String,
+    },
+}
+
+impl NotifyTarget {
+    /// Construct a target which executes a command.
+    pub fn exec<I>(program: &str, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        Self::Exec {
+            program: String::from(program),
+            args: args
+                .into_iter()
+                .map(|arg| String::from(arg.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Construct a target which emits a D-Bus signal.
+    pub fn dbus(path: &str, interface: &str, member: &str) -> Self {
+        Self::DBus {
+            path: String::from(path),
+            interface: String::from(interface),
+            member: String::from(member),
+        }
+    }
+
+    /// Construct a target which posts to an HTTP endpoint.
+    pub fn http_post(url: &str) -> Self {
+        Self::HttpPost {
+            url: String::from(url),
+        }
+    }
+}
+
+/// A rule delivering notifications of selected kinds to a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifyRule {
+    target: NotifyTarget,
+    kinds: Vec<NotificationKind>,
+    interval: Duration,
+}
+
+impl NotifyRule {
+    /// Construct a rule delivering every kind of notification to `target`
+    /// at most once per second per kind.
+    pub fn new(target: NotifyTarget) -> Self {
+        Self {
+            target,
+            kinds: Vec::from(NotificationKind::ALL),
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Only deliver notifications of the given kinds.
+    pub fn with_kinds(mut self, kinds: impl IntoIterator<Item = NotificationKind>) -> Self {
+        self.kinds = kinds.into_iter().collect();
+        self
+    }
+
+    /// Set the minimum interval between two notifications of the same kind.
+    ///
+    /// Notifications arriving sooner are dropped and counted, so that a
+    /// burst of xruns or clips results in a single notification.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// The target of the rule.
+    #[inline]
+    pub fn target(&self) -> &NotifyTarget {
+        &self.target
+    }
+}
+
+struct RuleState {
+    rule: NotifyRule,
+    limit: RateLimit,
+}
+
+/// Limits how often notifications of each kind are delivered.
+#[derive(Default)]
+struct RateLimit {
+    last: Vec<(NotificationKind, Instant, u64)>,
+}
+
+impl RateLimit {
+    /// Test if a notification of the given kind which happens at `now` should
+    /// be delivered, returning the number of notifications of the same kind
+    /// which were suppressed since the last one was delivered.
+    fn check(&mut self, kind: NotificationKind, now: Instant, interval: Duration) -> Option<u64> {
+        let Some((_, last, suppressed)) = self.last.iter_mut().find(|(k, ..)| *k == kind) else {
+            self.last.push((kind, now, 0));
+            return Some(0);
+        };
+
+        if now.saturating_duration_since(*last) < interval {
+            *suppressed += 1;
+            return None;
+        }
+
+        *last = now;
+        Some(core::mem::take(suppressed))
+    }
+}
+
+/// Delivers notifications about events to external targets, so that livemix
+/// can be integrated with studio automation.
+///
+/// The notifier is not connected to a stream by itself. Events from the
+/// stream which correspond to notifications, like clips, are delivered by
+/// passing them to [`Notifier::notify_event`]. Xruns, lost devices and
+/// recordings are not reported by the stream, so they have to be notified by
+/// the caller through [`Notifier::notify`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use client::{Notification, NotificationKind, Notifier, NotifyRule, NotifyTarget};
+///
+/// let rule = NotifyRule::new(NotifyTarget::exec("true", [] as [&str; 0]))
+///     .with_kinds([NotificationKind::Xrun, NotificationKind::Clip])
+///     .with_interval(Duration::from_secs(5));
+///
+/// let mut notifier = Notifier::new().with_rule(rule);
+///
+/// let xrun = Notification::new(NotificationKind::Xrun, "missed 2 cycles");
+/// let now = Instant::now();
+///
+/// assert_eq!(notifier.notify_at(&xrun, now)?, 1);
+/// // Rate limited.
+/// assert_eq!(notifier.notify_at(&xrun, now + Duration::from_secs(1))?, 0);
+/// assert_eq!(notifier.notify_at(&xrun, now + Duration::from_secs(6))?, 1);
+///
+/// // Not selected by the rule.
+/// let lost = Notification::new(NotificationKind::DeviceLost, "usb-headphones");
+/// assert_eq!(notifier.notify_at(&lost, now)?, 0);
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+#[derive(Default)]
+pub struct Notifier {
+    rules: Vec<RuleState>,
+    children: Vec<Child>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Notifier {
+    /// Construct a notifier without any rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule to the notifier.
+    pub fn with_rule(mut self, rule: NotifyRule) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    /// Add a rule to the notifier.
+    pub fn add_rule(&mut self, rule: NotifyRule) {
+        self.rules.push(RuleState {
+            rule,
+            limit: RateLimit::default(),
+        });
+    }
+
+    /// Iterate over the rules of the notifier.
+    pub fn rules(&self) -> impl Iterator<Item = &NotifyRule> + '_ {
+        self.rules.iter().map(|state| &state.rule)
+    }
+
+    /// Deliver the notification corresponding to a stream event, if any,
+    /// returning the number of targets it was delivered to.
+    ///
+    /// See [`Notification::from_event`].
+    pub fn notify_event(&mut self, event: &StreamEvent) -> Result<usize> {
+        match Notification::from_event(event) {
+            Some(notification) => self.notify(&notification),
+            None => Ok(0),
+        }
+    }
+
+    /// Deliver a notification, returning the number of targets it was
+    /// delivered to.
+    pub fn notify(&mut self, notification: &Notification) -> Result<usize> {
+        self.notify_at(notification, Instant::now())
+    }
+
+    /// Deliver a notification as if it happened at `now`, returning the
+    /// number of targets it was delivered to.
+    ///
+    /// Delivery is attempted for every matching rule even if an earlier one
+    /// fails, in which case the first error is returned.
+    pub fn notify_at(&mut self, notification: &Notification, now: Instant) -> Result<usize> {
+        self.reap();
+
+        let mut delivered = 0;
+        let mut error = None;
+
+        for state in &mut self.rules {
+            if !state.rule.kinds.contains(&notification.kind) {
+                continue;
+            }
+
+            let Some(suppressed) = state
+                .limit
+                .check(notification.kind, now, state.rule.interval)
+            else {
+                continue;
+            };
+
+            let result = match &state.rule.target {
+                NotifyTarget::Exec { program, args } => {
+                    spawn(&mut self.children, program, args, notification, suppressed)
+                }
+                NotifyTarget::DBus {
+                    path,
+                    interface,
+                    member,
+                } => {
+                    let args = [
+                        String::from("--session"),
+                        String::from("--type=signal"),
+                        path.clone(),
+                        format!("{interface}.{member}"),
+                        format!("string:{}", notification.kind),
+                        format!("string:{}", notification.message),
+                        format!("uint64:{suppressed}"),
+                    ];
+
+                    spawn(
+                        &mut self.children,
+                        "dbus-send",
+                        &args,
+                        notification,
+                        suppressed,
+                    )
+                }
+                NotifyTarget::HttpPost { url } => {
+                    spawn_http_post(&mut self.threads, url, notification, suppressed)
+                }
+            };
+
+            match result {
+                Ok(()) => delivered += 1,
+                Err(e) => {
+                    tracing::warn!(target = ?state.rule.target, "Failed to deliver notification: {e}");
+                    error.get_or_insert(e);
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(delivered),
+        }
+    }
+
+    /// Reap commands and requests which have completed.
+    fn reap(&mut self) {
+        self.children
+            .retain_mut(|child| !matches!(child.try_wait(), Ok(Some(..)) | Err(..)));
+        self.threads.retain(|thread| !thread.is_finished());
+    }
+}
+
+fn spawn(
+    children: &mut Vec<Child>,
+    program: &str,
+    args: &[String],
+    notification: &Notification,
+    suppressed: u64,
+) -> Result<()> {
+    let child = Command::new(program)
+        .args(args)
+        .env("LIVEMIX_EVENT", notification.kind.name())
+        .env("LIVEMIX_MESSAGE", &notification.message)
+        .env("LIVEMIX_SUPPRESSED", format!("{suppressed}"))
+        .stdin(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Executing {program}"))?;
+
+    children.push(child);
+    Ok(())
+}
+
+fn spawn_http_post(
+    threads: &mut Vec<JoinHandle<()>>,
+    url: &str,
+    notification: &Notification,
+    suppressed: u64,
+) -> Result<()> {
+    let url = String::from(url);
+
+    let body = format!(
+        "{{\"event\":{},\"message\":{},\"suppressed\":{suppressed}}}",
+        JsonString(notification.kind.name()),
+        JsonString(&notification.message),
+    );
+
+    let thread = thread::Builder::new()
+        .name(String::from("livemix-notify"))
+        .spawn(move || {
+            if let Err(e) = http_post(&url, &body) {
+                tracing::warn!(url, "Failed to post notification: {e}");
+            }
+        })
+        .context("Spawning notification thread")?;
+
+    threads.push(thread);
+    Ok(())
+}
+
+fn http_post(url: &str, body: &str) -> Result<()> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        bail!("Unsupported notification URL {url}, only http:// is supported");
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(n) => rest.split_at(n),
+        None => (rest, "/"),
+    };
+
+    let address = if authority.contains(':') {
+        String::from(authority)
+    } else {
+        format!("{authority}:80")
+    };
+
+    let request = format!(
+        "POST {path} HTTP/1.1\r\n\
+         Host: {authority}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        body.len()
+    );
+
+    let address = std::net::ToSocketAddrs::to_socket_addrs(&address)
+        .with_context(|| format!("Resolving {authority}"))?
+        .next()
+        .with_context(|| format!("No address for {authority}"))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, HTTP_TIMEOUT)
+        .with_context(|| format!("Connecting to {authority}"))?;
+    stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    stream.write_all(request.as_bytes())?;
+
+    let mut status = [0u8; 12];
+    stream.read_exact(&mut status)?;
+
+    match &status[9..] {
+        [b'2', _, _] => Ok(()),
+        code => bail!(
+            "Posting to {url} failed with status {}",
+            String::from_utf8_lossy(code)
+        ),
+    }
+}
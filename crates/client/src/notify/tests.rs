@@ -0,0 +1,102 @@
+use core::time::Duration;
+
+use std::time::Instant;
+
+use super::{Notification, NotificationKind, Notifier, NotifyRule, NotifyTarget, RateLimit};
+use crate::events::{ClipEvent, StreamEvent};
+use crate::{ClientNodeId, ClipKind, ClipLatch};
+
+const SECOND: Duration = Duration::from_secs(1);
+
+#[test]
+fn rate_limit_interval() {
+    let mut limit = RateLimit::default();
+    let now = Instant::now();
+
+    assert_eq!(limit.check(NotificationKind::Xrun, now, SECOND), Some(0));
+    assert_eq!(limit.check(NotificationKind::Xrun, now, SECOND), None);
+    assert_eq!(
+        limit.check(NotificationKind::Xrun, now + SECOND / 2, SECOND),
+        None
+    );
+    // The interval is measured from the last delivered notification.
+    assert_eq!(
+        limit.check(NotificationKind::Xrun, now + SECOND, SECOND),
+        Some(2)
+    );
+    assert_eq!(
+        limit.check(NotificationKind::Xrun, now + SECOND * 3 / 2, SECOND),
+        None
+    );
+}
+
+#[test]
+fn rate_limit_suppressed() {
+    let mut limit = RateLimit::default();
+    let now = Instant::now();
+
+    assert_eq!(limit.check(NotificationKind::Clip, now, SECOND), Some(0));
+
+    for n in 1..10 {
+        assert_eq!(
+            limit.check(NotificationKind::Clip, now + SECOND * n / 10, SECOND),
+            None
+        );
+    }
+
+    assert_eq!(
+        limit.check(NotificationKind::Clip, now + SECOND * 2, SECOND),
+        Some(9)
+    );
+    // The suppressed count is reset once delivered.
+    assert_eq!(
+        limit.check(NotificationKind::Clip, now + SECOND * 3, SECOND),
+        Some(0)
+    );
+}
+
+#[test]
+fn rate_limit_per_kind() {
+    let mut limit = RateLimit::default();
+    let now = Instant::now();
+
+    assert_eq!(limit.check(NotificationKind::Xrun, now, SECOND), Some(0));
+    assert_eq!(limit.check(NotificationKind::Clip, now, SECOND), Some(0));
+    assert_eq!(limit.check(NotificationKind::Xrun, now, SECOND), None);
+    assert_eq!(limit.check(NotificationKind::Clip, now, SECOND), None);
+}
+
+#[test]
+fn rules_limit_independently() -> anyhow::Result<()> {
+    let target = NotifyTarget::exec("true", [] as [&str; 0]);
+
+    let mut notifier = Notifier::new()
+        .with_rule(NotifyRule::new(target.clone()).with_interval(SECOND))
+        .with_rule(NotifyRule::new(target).with_interval(SECOND * 10));
+
+    let xrun = Notification::new(NotificationKind::Xrun, "missed a cycle");
+    let now = Instant::now();
+
+    assert_eq!(notifier.notify_at(&xrun, now)?, 2);
+    assert_eq!(notifier.notify_at(&xrun, now + SECOND * 2)?, 1);
+    assert_eq!(notifier.notify_at(&xrun, now + SECOND * 10)?, 2);
+    Ok(())
+}
+
+#[test]
+fn notification_from_event() {
+    let event = StreamEvent::Clip(ClipEvent {
+        node_id: ClientNodeId::new(0, 0),
+        latch: ClipLatch {
+            channel: 1,
+            kind: ClipKind::Clip,
+            nsec: 1000,
+        },
+    });
+
+    let notification = Notification::from_event(&event).expect("clip is notified");
+    assert_eq!(notification.kind, NotificationKind::Clip);
+    assert_eq!(notification.message, "Channel 1 clipped");
+
+    assert!(Notification::from_event(&StreamEvent::Started).is_none());
+}
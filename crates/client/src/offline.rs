@@ -0,0 +1,263 @@
+//! Offline rendering of the processing graph.
+//!
+//! This drives processing as fast as possible from inputs which are read
+//! quantum by quantum, without a running PipeWire server. Inputs negotiate
+//! their format like the ports of a node would, and every cycle is presented
+//! with a graph clock in the same way as a live cycle, which makes it suitable
+//! for batch mixdowns and golden-file tests of processing code.
+//!
+//! # Examples
+//!
+//! ```
+//! use client::offline::{MemorySource, Offline};
+//!
+//! let mut offline = Offline::new(48_000, 256)?;
+//! let a = offline.add_input(MemorySource::new(48_000, vec![0.5; 1000]))?;
+//! let b = offline.add_input(MemorySource::new(48_000, vec![0.25; 600]))?;
+//!
+//! let mut output = Vec::new();
+//! let mut positions = Vec::new();
+//!
+//! let rendered = offline.run(|cycle| {
+//!     positions.push(cycle.clock().position());
+//!
+//!     for (a, b) in cycle.input(a).iter().zip(cycle.input(b)).take(cycle.len()) {
+//!         output.push(a + b);
+//!     }
+//!
+//!     Ok(())
+//! })?;
+//!
+//! assert_eq!(rendered, 1000);
+//! assert_eq!(positions, [0, 256, 512, 768]);
+//! assert_eq!(output.len(), 1000);
+//! assert_eq!(output[599], 0.75);
+//! assert_eq!(output[600], 0.5);
+//! # Ok::<_, anyhow::Error>(())
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{Result, bail, ensure};
+use protocol::id;
+use protocol::object::AudioFormat;
+
+use crate::ClockTime;
+
+/// A source of samples for an input of an offline render.
+///
+/// Inputs are mono DSP ports, so the source must produce a single channel of
+/// 32-bit float samples at the rate of the render.
+pub trait OfflineSource {
+    /// The format of the source, which is negotiated when the input is added.
+    fn format(&self) -> AudioFormat;
+
+    /// Read samples into `out`, returning the number of samples read.
+    ///
+    /// Returning fewer samples than requested is only allowed once the source
+    /// has been exhausted.
+    fn read(&mut self, out: &mut [f32]) -> Result<usize>;
+}
+
+/// A source of samples held in memory.
+#[derive(Debug, Clone)]
+pub struct MemorySource {
+    rate: u32,
+    samples: Vec<f32>,
+    offset: usize,
+}
+
+impl MemorySource {
+    /// Construct a new source of samples at the given rate.
+    pub fn new(rate: u32, samples: Vec<f32>) -> Self {
+        Self {
+            rate,
+            samples,
+            offset: 0,
+        }
+    }
+}
+
+impl OfflineSource for MemorySource {
+    fn format(&self) -> AudioFormat {
+        AudioFormat {
+            media_type: id::MediaType::AUDIO,
+            media_sub_type: id::MediaSubType::RAW,
+            format: id::AudioFormat::F32P,
+            channels: 1,
+            rate: self.rate,
+        }
+    }
+
+    fn read(&mut self, out: &mut [f32]) -> Result<usize> {
+        let remaining = &self.samples[self.offset..];
+        let n = remaining.len().min(out.len());
+        out[..n].copy_from_slice(&remaining[..n]);
+        self.offset += n;
+        Ok(n)
+    }
+}
+
+struct Input {
+    source: Box<dyn OfflineSource>,
+    buf: Vec<f32>,
+    exhausted: bool,
+}
+
+/// An offline render of the processing graph.
+///
+/// See the [module level documentation](self).
+pub struct Offline {
+    rate: u32,
+    quantum: usize,
+    inputs: Vec<Input>,
+}
+
+impl Offline {
+    /// Construct a new offline render running at the given sample `rate`
+    /// where each cycle processes `quantum` samples.
+    pub fn new(rate: u32, quantum: usize) -> Result<Self> {
+        ensure!(
+            rate > 0 && quantum > 0,
+            "Invalid quantum {quantum} at rate {rate}"
+        );
+
+        Ok(Self {
+            rate,
+            quantum,
+            inputs: Vec::new(),
+        })
+    }
+
+    /// The format every input is negotiated to.
+    pub fn format(&self) -> AudioFormat {
+        AudioFormat {
+            media_type: id::MediaType::AUDIO,
+            media_sub_type: id::MediaSubType::RAW,
+            format: id::AudioFormat::F32P,
+            channels: 1,
+            rate: self.rate,
+        }
+    }
+
+    /// Add an input, returning its index.
+    ///
+    /// This errors if the format of the source does not match the format of
+    /// the render, since no conversion is performed.
+    pub fn add_input(&mut self, source: impl OfflineSource + 'static) -> Result<usize> {
+        let format = source.format();
+        let expected = self.format();
+
+        if format != expected {
+            bail!("Unsupported input format {format:?}, expected {expected:?}");
+        }
+
+        self.inputs.push(Input {
+            source: Box::new(source),
+            buf: vec![0.0; self.quantum],
+            exhausted: false,
+        });
+
+        Ok(self.inputs.len() - 1)
+    }
+
+    /// Run cycles until every input is exhausted, returning the number of
+    /// samples rendered.
+    ///
+    /// The last cycle is padded with silence, and [`OfflineCycle::len`] tells
+    /// how many of its samples are part of the render.
+    pub fn run(&mut self, mut process: impl FnMut(&OfflineCycle<'_>) -> Result<()>) -> Result<u64> {
+        ensure!(!self.inputs.is_empty(), "Offline render has no inputs");
+
+        let mut position = 0u64;
+
+        loop {
+            let mut len = 0;
+
+            for input in &mut self.inputs {
+                let mut n = 0;
+
+                if !input.exhausted {
+                    while n < self.quantum {
+                        let read = input.source.read(&mut input.buf[n..])?;
+
+                        if read == 0 {
+                            input.exhausted = true;
+                            break;
+                        }
+
+                        n += read;
+                    }
+                }
+
+                input.buf[n..].fill(0.0);
+                len = len.max(n);
+            }
+
+            if len == 0 {
+                break;
+            }
+
+            let nsec = (position as u128 * 1_000_000_000 / self.rate as u128) as u64;
+
+            process(&OfflineCycle {
+                clock: ClockTime::new(nsec, position, self.rate),
+                duration: self.quantum,
+                len,
+                inputs: &self.inputs,
+            })?;
+
+            position += len as u64;
+
+            if len < self.quantum {
+                break;
+            }
+        }
+
+        Ok(position)
+    }
+}
+
+/// A cycle of an offline render.
+pub struct OfflineCycle<'a> {
+    clock: ClockTime,
+    duration: usize,
+    len: usize,
+    inputs: &'a [Input],
+}
+
+impl OfflineCycle<'_> {
+    /// The graph clock of the cycle.
+    #[inline]
+    pub fn clock(&self) -> ClockTime {
+        self.clock
+    }
+
+    /// The duration of the cycle in samples, which is the quantum of the
+    /// render.
+    #[inline]
+    pub fn duration(&self) -> usize {
+        self.duration
+    }
+
+    /// The number of samples in the cycle which are part of the render.
+    ///
+    /// This is only less than [`OfflineCycle::duration`] for the last cycle.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The samples of the given input, which span the whole duration of the
+    /// cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input does not exist.
+    pub fn input(&self, input: usize) -> &[f32] {
+        &self.inputs[input].buf
+    }
+}
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod tests;
+
+/// The number of decisions which are kept until they are reported.
+const PENDING: usize = 4;
+
+/// The action taken by an [`OverloadPolicy`] once a node is considered
+/// overloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverloadAction {
+    /// Skip processing and emit silence until the node recovers.
+    ///
+    /// This is signalled through [`ClientNode::is_shedding`], which the
+    /// process callback is expected to consult.
+    ///
+    /// [`ClientNode::is_shedding`]: crate::ClientNode::is_shedding
+    Silence,
+    /// Request that the graph runs with a larger quantum of the given number
+    /// of samples by setting the `node.force-quantum` property of the node.
+    RequestQuantum(u32),
+}
+
+/// A decision taken by an [`OverloadPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverloadDecision {
+    /// The node has overrun its budget for too many cycles, and the given
+    /// action has been engaged.
+    Engaged(OverloadAction),
+    /// The node has recovered and the given action has been released.
+    Released(OverloadAction),
+}
+
+/// A policy for shedding load when processing repeatedly overruns its budget.
+///
+/// The load of a cycle is the time spent between [`ClientNode::start_process`]
+/// and [`ClientNode::end_process`] divided by the duration of the cycle. The
+/// policy engages once the load has been above the threshold for a number of
+/// consecutive cycles, and releases once it has stayed below the threshold for
+/// a number of consecutive cycles, which provides hysteresis.
+///
+/// Every decision is reported through [`StreamEvent::Overload`].
+///
+/// [`ClientNode::start_process`]: crate::ClientNode::start_process
+/// [`ClientNode::end_process`]: crate::ClientNode::end_process
+/// [`StreamEvent::Overload`]: crate::events::StreamEvent::Overload
+///
+/// # Examples
+///
+/// ```
+/// use client::{OverloadAction, OverloadPolicy};
+///
+/// let policy = OverloadPolicy::new(OverloadAction::Silence)
+///     .with_threshold(0.9)
+///     .with_trigger(4)
+///     .with_release(64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverloadPolicy {
+    action: OverloadAction,
+    threshold: f64,
+    trigger: u32,
+    release: u32,
+}
+
+impl OverloadPolicy {
+    /// Construct a new policy taking the given action.
+    ///
+    /// By default the policy engages after 8 consecutive cycles using more
+    /// than 95% of their budget, and releases after 128 consecutive cycles
+    /// below it.
+    pub fn new(action: OverloadAction) -> Self {
+        Self {
+            action,
+            threshold: 0.95,
+            trigger: 8,
+            release: 128,
+        }
+    }
+
+    /// Set the fraction of the cycle budget above which a cycle is considered
+    /// overrun.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        Self { threshold, ..self }
+    }
+
+    /// Set the number of consecutive overrun cycles before the policy engages.
+    pub fn with_trigger(self, trigger: u32) -> Self {
+        Self {
+            trigger: trigger.max(1),
+            ..self
+        }
+    }
+
+    /// Set the number of consecutive cycles within budget before the policy
+    /// is released.
+    pub fn with_release(self, release: u32) -> Self {
+        Self {
+            release: release.max(1),
+            ..self
+        }
+    }
+
+    /// Get the action of the policy.
+    #[inline]
+    pub fn action(&self) -> OverloadAction {
+        self.action
+    }
+}
+
+/// The state of an overload policy attached to a node.
+pub(crate) struct OverloadState {
+    policy: OverloadPolicy,
+    count: u32,
+    engaged: bool,
+    /// Decisions which have not been reported, in the order they were taken.
+    pending: [Option<OverloadDecision>; PENDING],
+    head: usize,
+    len: usize,
+    /// The number of decisions which were overwritten before being reported.
+    dropped: u32,
+}
+
+impl OverloadState {
+    pub(crate) fn new(policy: OverloadPolicy) -> Self {
+        Self {
+            policy,
+            count: 0,
+            engaged: false,
+            pending: [None; PENDING],
+            head: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Test if the policy is currently engaged.
+    #[inline]
+    pub(crate) fn engaged(&self) -> Option<OverloadAction> {
+        self.engaged.then_some(self.policy.action)
+    }
+
+    /// Observe the load of a single cycle.
+    pub(crate) fn observe(&mut self, load: f64) {
+        let overrun = load > self.policy.threshold;
+
+        // NB: Count cycles which go against the current state.
+        if overrun != self.engaged {
+            self.count += 1;
+        } else {
+            self.count = 0;
+        }
+
+        let limit = if self.engaged {
+            self.policy.release
+        } else {
+            self.policy.trigger
+        };
+
+        if self.count < limit {
+            return;
+        }
+
+        self.count = 0;
+        self.engaged = !self.engaged;
+
+        let decision = if self.engaged {
+            OverloadDecision::Engaged(self.policy.action)
+        } else {
+            OverloadDecision::Released(self.policy.action)
+        };
+
+        // NB: Overwrite the oldest decision if decisions are not reported in
+        // time, since the latest decision reflects the current state.
+        if self.len == PENDING {
+            self.head = (self.head + 1) % PENDING;
+            self.len -= 1;
+            self.dropped = self.dropped.saturating_add(1);
+        }
+
+        self.pending[(self.head + self.len) % PENDING] = Some(decision);
+        self.len += 1;
+    }
+
+    /// Take the oldest decision which has not been reported, and the number
+    /// of decisions which were dropped since the last one was taken.
+    pub(crate) fn take_pending(&mut self) -> Option<(OverloadDecision, u32)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let decision = self.pending[self.head].take()?;
+        self.head = (self.head + 1) % PENDING;
+        self.len -= 1;
+        Some((decision, core::mem::take(&mut self.dropped)))
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::vec::Vec;
+
+use super::{OverloadAction, OverloadDecision, OverloadPolicy, OverloadState};
+
+const ENGAGED: OverloadDecision = OverloadDecision::Engaged(OverloadAction::Silence);
+const RELEASED: OverloadDecision = OverloadDecision::Released(OverloadAction::Silence);
+
+fn state() -> OverloadState {
+    let policy = OverloadPolicy::new(OverloadAction::Silence)
+        .with_trigger(1)
+        .with_release(1);
+
+    OverloadState::new(policy)
+}
+
+fn drain(state: &mut OverloadState) -> Vec<(OverloadDecision, u32)> {
+    let mut decisions = Vec::new();
+
+    while let Some(decision) = state.take_pending() {
+        decisions.push(decision);
+    }
+
+    decisions
+}
+
+#[test]
+fn engage_and_release_are_both_reported() {
+    let mut state = state();
+
+    state.observe(1.0);
+    state.observe(0.0);
+
+    assert_eq!(drain(&mut state), [(ENGAGED, 0), (RELEASED, 0)]);
+    assert_eq!(state.engaged(), None);
+}
+
+#[test]
+fn overwritten_decisions_are_counted() {
+    let mut state = state();
+
+    for _ in 0..3 {
+        state.observe(1.0);
+        state.observe(0.0);
+    }
+
+    // The two oldest decisions are overwritten.
+    assert_eq!(
+        drain(&mut state),
+        [(ENGAGED, 2), (RELEASED, 0), (ENGAGED, 0), (RELEASED, 0)]
+    );
+
+    state.observe(1.0);
+    assert_eq!(drain(&mut state), [(ENGAGED, 0)]);
+}
@@ -0,0 +1,95 @@
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use std::collections::BTreeMap;
+
+use pod::{DynamicBuf, Object};
+use protocol::id;
+
+#[derive(Debug, Default)]
+struct Entry {
+    seq: i32,
+    index: Option<u32>,
+    values: Vec<Object<DynamicBuf>>,
+}
+
+/// A cache of parameters received from a remote object.
+///
+/// Parameters are populated through `Param` events, either as a response to
+/// enumeration or as a result of a parameter subscription.
+pub struct ParamCache {
+    values: BTreeMap<id::Param, Entry>,
+}
+
+impl ParamCache {
+    /// Construct a new empty parameter cache.
+    pub fn new() -> Self {
+        Self {
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Get the cached values of a parameter.
+    pub fn get(&self, id: id::Param) -> &[Object<DynamicBuf>] {
+        match self.values.get(&id) {
+            Some(entry) => entry.values.as_slice(),
+            None => &[],
+        }
+    }
+
+    /// Iterate over the identifiers of all cached parameters.
+    pub fn ids(&self) -> impl Iterator<Item = id::Param> {
+        self.values.keys().copied()
+    }
+
+    /// Insert a parameter value received from a `Param` event.
+    ///
+    /// A new sequence number, or an index which does not follow the previously
+    /// received one, indicates that the parameter is being enumerated anew in
+    /// which case the previously cached values are replaced.
+    pub(crate) fn insert(
+        &mut self,
+        seq: i32,
+        id: id::Param,
+        index: u32,
+        value: Option<Object<DynamicBuf>>,
+    ) {
+        let e = self.values.entry(id).or_default();
+
+        if e.seq != seq || e.index.is_some_and(|last| index <= last) {
+            e.values.clear();
+        }
+
+        e.seq = seq;
+        e.index = Some(index);
+        e.values.extend(value);
+    }
+
+    /// Get the sequence number the values of a parameter were last received
+    /// with.
+    pub(crate) fn seq(&self, id: id::Param) -> Option<i32> {
+        Some(self.values.get(&id)?.seq)
+    }
+
+    /// Remove all cached values of a parameter.
+    pub(crate) fn remove(&mut self, id: id::Param) -> bool {
+        self.values.remove(&id).is_some()
+    }
+}
+
+impl fmt::Debug for ParamCache {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.values.iter().map(|(id, e)| (id, &e.values)))
+            .finish()
+    }
+}
+
+impl Default for ParamCache {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
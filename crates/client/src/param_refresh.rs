@@ -0,0 +1,262 @@
+use core::time::Duration;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use protocol::id;
+
+use crate::ProxyId;
+
+/// The default number of refreshes which can be outstanding at once.
+const DEFAULT_MAX_OUTSTANDING: usize = 8;
+/// The default shortest delay between refreshes of the same object.
+const DEFAULT_MIN_DELAY: Duration = Duration::from_millis(50);
+/// The default longest delay between refreshes of the same object.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default)]
+struct Pending {
+    /// Parameters waiting to be enumerated.
+    params: Vec<id::Param>,
+    /// The earliest time the parameters can be enumerated.
+    due: u64,
+    /// The current delay between refreshes.
+    delay: u64,
+    /// When the last refresh was issued.
+    last: Option<u64>,
+    /// Whether a refresh is outstanding.
+    in_flight: bool,
+}
+
+/// A refresh of parameters which is to be issued or has completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Refresh {
+    /// The proxy whose parameters are enumerated.
+    pub proxy_id: ProxyId,
+    /// The sequence number the enumeration is issued with.
+    pub seq: i32,
+    /// The parameters to enumerate.
+    pub params: Vec<id::Param>,
+}
+
+/// A scheduler for re-enumerating the parameters of remote objects.
+///
+/// When the server floods the client with changes, like when devices are
+/// hotplugged, naively re-enumerating parameters for every change would queue
+/// up far more requests than the server can answer. Instead:
+/// * Requests for an object are coalesced until its refresh is issued, and
+///   requests which arrive while a refresh is outstanding are issued once it
+///   has completed.
+/// * An object which is refreshed again shortly after its previous refresh has
+///   its delay doubled, up to a maximum. The delay is reset once the object
+///   has been quiet for the maximum delay.
+/// * Only a limited number of refreshes are outstanding at once.
+///
+/// Times are monotonic timestamps in nanoseconds, like the ones returned by
+/// [`utils::get_monotonic_nsec`].
+///
+/// This is used through [`Stream::refresh_params`] and
+/// [`Stream::watch_params`].
+///
+/// [`utils::get_monotonic_nsec`]: crate::utils::get_monotonic_nsec
+/// [`Stream::refresh_params`]: crate::Stream::refresh_params
+/// [`Stream::watch_params`]: crate::Stream::watch_params
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use client::{ParamRefresh, ProxyId};
+/// use protocol::id;
+///
+/// const MS: u64 = 1_000_000;
+///
+/// let mut r = ParamRefresh::new();
+/// r.set_max_outstanding(1);
+/// r.set_backoff(Duration::from_millis(10), Duration::from_millis(100));
+///
+/// let a = ProxyId::new(0);
+/// let b = ProxyId::new(1);
+///
+/// // Requests are coalesced until they are issued.
+/// r.request(a, &[id::Param::PROPS], 0);
+/// r.request(a, &[id::Param::PROPS, id::Param::ROUTE], 0);
+/// r.request(b, &[id::Param::PROPS], 0);
+///
+/// let first = r.next(0).unwrap();
+/// assert_eq!(first.proxy_id, a);
+/// assert_eq!(first.params, [id::Param::PROPS, id::Param::ROUTE]);
+///
+/// // Only one refresh can be outstanding.
+/// assert!(r.next(0).is_none());
+/// assert_eq!(r.complete(first.seq), Some(first));
+///
+/// let second = r.next(0).unwrap();
+/// assert_eq!(second.proxy_id, b);
+/// r.complete(second.seq);
+///
+/// // Refreshing the same object again backs off.
+/// r.request(a, &[id::Param::PROPS], MS);
+/// assert_eq!(r.deadline(), Some(10 * MS));
+/// assert!(r.next(5 * MS).is_none());
+/// let third = r.next(10 * MS).unwrap();
+/// r.complete(third.seq);
+///
+/// r.request(a, &[id::Param::PROPS], 11 * MS);
+/// assert_eq!(r.deadline(), Some(30 * MS));
+/// ```
+#[derive(Debug)]
+pub struct ParamRefresh {
+    max_outstanding: usize,
+    min_delay: u64,
+    max_delay: u64,
+    seq: i32,
+    objects: BTreeMap<ProxyId, Pending>,
+    outstanding: BTreeMap<i32, Refresh>,
+}
+
+impl ParamRefresh {
+    /// Construct a new refresh scheduler with default limits.
+    pub fn new() -> Self {
+        Self {
+            max_outstanding: DEFAULT_MAX_OUTSTANDING,
+            min_delay: DEFAULT_MIN_DELAY.as_nanos() as u64,
+            max_delay: DEFAULT_MAX_DELAY.as_nanos() as u64,
+            seq: 0,
+            objects: BTreeMap::new(),
+            outstanding: BTreeMap::new(),
+        }
+    }
+
+    /// Set the number of refreshes which can be outstanding at once.
+    ///
+    /// At least one refresh is always allowed.
+    pub fn set_max_outstanding(&mut self, max: usize) {
+        self.max_outstanding = max.max(1);
+    }
+
+    /// Set the shortest and the longest delay between refreshes of the same
+    /// object.
+    pub fn set_backoff(&mut self, min: Duration, max: Duration) {
+        self.min_delay = min.as_nanos() as u64;
+        self.max_delay = (max.as_nanos() as u64).max(self.min_delay);
+    }
+
+    /// Request that the given parameters of an object are enumerated.
+    pub fn request(&mut self, proxy_id: ProxyId, params: &[id::Param], now: u64) {
+        if params.is_empty() {
+            return;
+        }
+
+        let p = self.objects.entry(proxy_id).or_default();
+
+        if p.params.is_empty() {
+            match p.last {
+                Some(last) if now.saturating_sub(last) < self.max_delay => {
+                    p.delay = p
+                        .delay
+                        .saturating_mul(2)
+                        .clamp(self.min_delay, self.max_delay);
+                    p.due = now.max(last.saturating_add(p.delay));
+                }
+                _ => {
+                    p.delay = 0;
+                    p.due = now;
+                }
+            }
+        }
+
+        for &param in params {
+            if !p.params.contains(&param) {
+                p.params.push(param);
+            }
+        }
+    }
+
+    /// Test if there are any refreshes waiting to be issued.
+    pub fn is_pending(&self) -> bool {
+        self.objects.values().any(|p| !p.params.is_empty())
+    }
+
+    /// The number of outstanding refreshes.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    /// The earliest time a refresh can be issued, if any are waiting.
+    ///
+    /// Refreshes which are waiting for an outstanding refresh of the same
+    /// object are not considered.
+    pub fn deadline(&self) -> Option<u64> {
+        self.objects
+            .values()
+            .filter(|p| !p.params.is_empty() && !p.in_flight)
+            .map(|p| p.due)
+            .min()
+    }
+
+    /// Take the next refresh which is due, if the number of outstanding
+    /// refreshes permits it.
+    ///
+    /// The returned refresh is considered outstanding until its sequence
+    /// number is passed to [`ParamRefresh::complete`].
+    pub fn next(&mut self, now: u64) -> Option<Refresh> {
+        if self.outstanding.len() >= self.max_outstanding {
+            return None;
+        }
+
+        let (&proxy_id, p) = self
+            .objects
+            .iter_mut()
+            .filter(|(_, p)| !p.params.is_empty() && !p.in_flight && p.due <= now)
+            .min_by_key(|(_, p)| p.due)?;
+
+        p.in_flight = true;
+        p.last = Some(now);
+
+        self.seq = self.seq.wrapping_add(1);
+
+        let refresh = Refresh {
+            proxy_id,
+            seq: self.seq,
+            params: core::mem::take(&mut p.params),
+        };
+
+        self.outstanding.insert(refresh.seq, refresh.clone());
+        Some(refresh)
+    }
+
+    /// Mark the refresh with the given sequence number as completed,
+    /// returning it if it was outstanding.
+    pub fn complete(&mut self, seq: i32) -> Option<Refresh> {
+        let refresh = self.outstanding.remove(&seq)?;
+
+        if let Some(p) = self.objects.get_mut(&refresh.proxy_id) {
+            p.in_flight = false;
+        }
+
+        Some(refresh)
+    }
+
+    /// Forget about an object, like when its proxy has been removed.
+    pub fn remove(&mut self, proxy_id: ProxyId) {
+        self.objects.remove(&proxy_id);
+        self.outstanding.retain(|_, r| r.proxy_id != proxy_id);
+    }
+
+    /// Forget about all objects and outstanding refreshes.
+    pub fn clear(&mut self) {
+        self.objects.clear();
+        self.outstanding.clear();
+    }
+}
+
+impl Default for ParamRefresh {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
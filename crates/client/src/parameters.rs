@@ -47,6 +47,12 @@ impl Parameters {
         self.modified
     }
 
+    /// Mark the parameters as modified, so that they are sent in full with
+    /// the next update.
+    pub(crate) fn mark_modified(&mut self) {
+        self.modified = true;
+    }
+
     /// Take the modified state of the parameters.
     pub(crate) fn take_modified(&mut self) -> bool {
         mem::take(&mut self.modified)
@@ -138,6 +144,36 @@ impl Parameters {
         }
     }
 
+    /// Copy the given parameters and their flags into a new collection.
+    pub(crate) fn subset(&self, ids: &[id::Param]) -> Result<Self> {
+        let mut values = BTreeMap::new();
+
+        for &id in ids {
+            let Some(e) = self.values.get(&id) else {
+                continue;
+            };
+
+            let mut entry = Entry {
+                values: Vec::with_capacity(e.values.len()),
+                flags: e.flags,
+            };
+
+            for param in &e.values {
+                entry.values.push(PortParam::with_flags(
+                    param.value.as_ref().to_owned()?,
+                    param.flags,
+                ));
+            }
+
+            values.insert(id, entry);
+        }
+
+        Ok(Self {
+            values,
+            modified: true,
+        })
+    }
+
     /// Get parameters from the port.
     pub(crate) fn values(&self) -> impl ExactSizeIterator<Item = &[PortParam<DynamicBuf>]> {
         self.values.values().map(|e| e.values.as_slice())
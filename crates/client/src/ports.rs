@@ -8,6 +8,7 @@ use std::collections::BTreeSet;
 use std::collections::VecDeque;
 use std::collections::btree_map::Entry;
 
+use alloc::format;
 use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
@@ -16,12 +17,14 @@ use anyhow::{Result, bail};
 use bittle::Bits;
 use bittle::BitsMut;
 use pod::{
-    AsSlice, ChoiceType, DynamicBuf, Object, PodItem, PodSink, PodStream, Readable, Type, Writable,
+    AsSlice, ChoiceType, ControlType, DynamicBuf, Object, PodItem, PodSink, PodStream, Readable,
+    Type, Writable,
 };
-use protocol::Properties;
 use protocol::consts::{self, Direction};
 use protocol::flags::{ParamFlags, Status};
 use protocol::id;
+use protocol::param::EnumFormatBuilder;
+use protocol::{Properties, Symbol, prop};
 use protocol::{ffi, flags, object};
 use tracing::Level;
 
@@ -72,6 +75,54 @@ impl<'de> Readable<'de> for PortId {
     }
 }
 
+/// A stable key for a port.
+///
+/// Unlike [`PortId`], which is assigned in the order ports are inserted and
+/// might change when ports are recreated across reconnects, a key is derived
+/// from the direction and the `port.name` property of the port. Configuration
+/// which refers to ports by key therefore survives port renumbering.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortKey {
+    direction: Direction,
+    name: Symbol,
+}
+
+impl PortKey {
+    /// Construct a new port key from a direction and a port name.
+    pub fn new(direction: Direction, name: &str) -> Self {
+        Self {
+            direction,
+            name: Symbol::new(name),
+        }
+    }
+
+    /// Get the direction of the port.
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Get the name of the port.
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl fmt::Display for PortKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}:{}", self.direction, self.name)
+    }
+}
+
+impl fmt::Debug for PortKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
 /// The identifier of a mix.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -407,15 +458,49 @@ pub struct Port {
     pub mix_info: PortMixInfo,
     pub props: Properties,
     pub params: Parameters,
+    /// The input port this port is monitoring, if it is a monitor port.
+    ///
+    /// See [`Ports::insert_monitor`].
+    pub monitor: Option<PortId>,
+    /// Whether the port carries MIDI in a control sequence.
+    ///
+    /// See [`Ports::insert_midi`].
+    pub midi: bool,
+    /// The audio format negotiated for the port, if any.
+    ///
+    /// See [`StreamBuilder`].
+    ///
+    /// [`StreamBuilder`]: crate::StreamBuilder
+    pub format: Option<object::AudioFormat>,
 }
 
 impl Port {
+    /// Get the name of the port, as specified by the `port.name` property.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.props.get(prop::PORT_NAME)
+    }
+
+    /// Get the stable key of the port, if it has a name.
+    #[inline]
+    pub fn key(&self) -> Option<PortKey> {
+        Some(PortKey::new(self.direction, self.name()?))
+    }
+
     /// Take the modified state of the port.
     #[inline]
     pub(crate) fn is_modified(&mut self) -> bool {
         self.props.is_modified() || self.params.is_modified()
     }
 
+    /// Extract the sets of buffers matching the given predicate.
+    pub(crate) fn extract_buffers_if(
+        &mut self,
+        mut pred: impl FnMut(&Buffers) -> bool,
+    ) -> impl Iterator<Item = Buffers> {
+        self.port_buffers.buffers.extract_if(.., move |b| pred(b))
+    }
+
     /// Replace the current set of buffers for this port.
     #[inline]
     #[tracing::instrument(skip(self, f, buffers), fields(port_id = ?self.id, mix_id = ?buffers.mix_id), ret(level = Level::TRACE))]
@@ -517,6 +602,24 @@ impl Ports {
         &mut self.output_ports
     }
 
+    /// Mark the properties of every port as modified.
+    pub(crate) fn mark_props_modified(&mut self) {
+        for port in self
+            .input_ports
+            .iter_mut()
+            .chain(self.output_ports.iter_mut())
+        {
+            port.props.mark_modified();
+        }
+    }
+
+    /// Iterate mutably over all ports, inputs first.
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut Port> {
+        self.input_ports
+            .iter_mut()
+            .chain(self.output_ports.iter_mut())
+    }
+
     /// Insert a new port in the specified direction and return the inserted
     /// port for configuration.
     pub fn insert(&mut self, direction: Direction) -> Result<&mut Port> {
@@ -538,12 +641,76 @@ impl Ports {
             props: Properties::new(),
             params: Parameters::new(),
             mix_info: PortMixInfo::default(),
+            monitor: None,
+            midi: false,
+            format: None,
         };
 
         ports.push(port);
         Ok(&mut ports[id.index()])
     }
 
+    /// Insert an output port which monitors the given input port and return
+    /// it for configuration.
+    ///
+    /// The port is marked with the `port.monitor` property, and if the input
+    /// port has a name the monitor port is named after it with a `monitor_`
+    /// prefix. Like with [`Ports::insert`], parameters such as the supported
+    /// formats have to be added by the caller.
+    ///
+    /// Filling the monitor port is up to the processing code of the node, which
+    /// is expected to copy the audio of the monitored port into it. Monitor
+    /// ports can be found through [`Ports::monitors`].
+    pub fn insert_monitor(&mut self, input: PortId) -> Result<&mut Port> {
+        let name = self
+            .get(Direction::INPUT, input)?
+            .name()
+            .map(|name| format!("monitor_{name}"));
+
+        let port = self.insert(Direction::OUTPUT)?;
+        port.monitor = Some(input);
+        port.props.insert(prop::PORT_MONITOR, "true");
+
+        if let Some(name) = name {
+            port.props.insert(prop::PORT_NAME, name);
+        }
+
+        Ok(port)
+    }
+
+    /// Insert a port in the specified direction which carries MIDI and return
+    /// it for configuration.
+    ///
+    /// The port accepts a control sequence of MIDI events, which is written
+    /// as its [`ENUM_FORMAT`] parameter. Formats set on the port which are
+    /// not control sequences are rejected. Events are read from and written
+    /// to the buffers of the port through [`Data::midi_events`] and
+    /// [`Data::write_midi`].
+    ///
+    /// [`ENUM_FORMAT`]: id::Param::ENUM_FORMAT
+    /// [`Data::midi_events`]: crate::buffer::Data::midi_events
+    /// [`Data::write_midi`]: crate::buffer::Data::write_midi
+    pub fn insert_midi(&mut self, direction: Direction) -> Result<&mut Port> {
+        let port = self.insert(direction)?;
+        port.midi = true;
+        port.props.insert(prop::FORMAT_DSP, "8 bit raw midi");
+
+        let format = EnumFormatBuilder::control().control_types(1 << ControlType::MIDI.into_u32());
+
+        let mut pod = pod::array();
+        port.params.push(pod.as_mut().embed(format)?)?;
+        port.params.set_writable(id::Param::FORMAT);
+        Ok(port)
+    }
+
+    /// Iterate over the identifiers of monitor ports, and the input ports
+    /// they are monitoring.
+    pub fn monitors(&self) -> impl Iterator<Item = (PortId, PortId)> + '_ {
+        self.output_ports
+            .iter()
+            .filter_map(|port| Some((port.id, port.monitor?)))
+    }
+
     /// Get a port.
     pub fn get(&self, direction: Direction, id: PortId) -> Result<&Port> {
         let ports = self.get_direction(direction)?;
@@ -566,6 +733,30 @@ impl Ports {
         Ok(port)
     }
 
+    /// Find a port by its `port.name` property.
+    pub fn by_name(&self, direction: Direction, name: &str) -> Option<&Port> {
+        let ports = self.get_direction(direction).ok()?;
+        ports.iter().find(|port| port.name() == Some(name))
+    }
+
+    /// Find a port mutably by its `port.name` property.
+    pub fn by_name_mut(&mut self, direction: Direction, name: &str) -> Option<&mut Port> {
+        let ports = get_direction_mut!(self, direction).ok()?;
+        ports.iter_mut().find(|port| port.name() == Some(name))
+    }
+
+    /// Find a port by its stable key.
+    #[inline]
+    pub fn by_key(&self, key: PortKey) -> Option<&Port> {
+        self.by_name(key.direction, key.name())
+    }
+
+    /// Find a port mutably by its stable key.
+    #[inline]
+    pub fn by_key_mut(&mut self, key: PortKey) -> Option<&mut Port> {
+        self.by_name_mut(key.direction, key.name())
+    }
+
     #[inline]
     fn get_direction(&self, dir: Direction) -> Result<&Vec<Port>> {
         match dir {
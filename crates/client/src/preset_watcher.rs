@@ -0,0 +1,190 @@
+use core::mem;
+use core::time::Duration;
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use std::ffi::OsString;
+use std::io;
+use std::os::fd::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+use protocol::TimerFd;
+use protocol::inotify::{InotifyFd, WatchMask};
+
+#[cfg(test)]
+mod tests;
+
+/// The default time to wait for further changes before reloading.
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The size of the buffer inotify events are read into.
+const BUFFER_SIZE: usize = 4096;
+
+/// Watches a directory of presets for changes through the poll loop.
+///
+/// The watcher has two file descriptors which should be registered with the
+/// poll of the stream, see [`PresetWatcher::inotify_fd`] and
+/// [`PresetWatcher::timer_fd`]. When the inotify file descriptor is readable,
+/// [`PresetWatcher::notify`] collects the files which have been written and
+/// re-arms the debounce timer. Once the timer expires,
+/// [`PresetWatcher::expire`] returns the files which changed.
+///
+/// Each changed file can then be loaded with [`Stream::load_preset`], which
+/// validates the whole preset before applying it and emits
+/// [`StreamEvent::PresetRejected`] without touching the node if it is
+/// invalid.
+///
+/// Files are reported once they have been written and closed, or moved into
+/// the directory, so presets which are saved by renaming a temporary file
+/// over them are only reported once they are complete.
+///
+/// [`Stream::load_preset`]: crate::Stream::load_preset
+/// [`StreamEvent::PresetRejected`]: crate::events::StreamEvent::PresetRejected
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use client::{ClientNodeId, PresetWatcher, Stream};
+/// use protocol::poll::Interest;
+/// use protocol::Poll;
+///
+/// # fn f(stream: &mut Stream, poll: &mut Poll, node_id: ClientNodeId) -> anyhow::Result<()> {
+/// let mut watcher = PresetWatcher::new("presets")?
+///     .with_extension("preset")
+///     .with_debounce(Duration::from_millis(100));
+///
+/// let inotify_token = stream.token()?;
+/// poll.add(watcher.inotify_fd(), inotify_token, Interest::READ)?;
+///
+/// let timer_token = stream.token()?;
+/// poll.add(watcher.timer_fd(), timer_token, Interest::READ)?;
+///
+/// // When the inotify file descriptor is readable.
+/// watcher.notify()?;
+///
+/// // When the timer file descriptor is readable.
+/// for path in watcher.expire()? {
+///     stream.load_preset(node_id, &path)?;
+/// }
+/// # Ok(()) }
+/// ```
+pub struct PresetWatcher {
+    dir: PathBuf,
+    extension: Option<OsString>,
+    debounce: Duration,
+    inotify: InotifyFd,
+    timer: TimerFd,
+    changed: BTreeSet<PathBuf>,
+    buf: Vec<u8>,
+}
+
+impl PresetWatcher {
+    /// Start watching the given directory.
+    ///
+    /// By default every file in the directory is reported, and reloading is
+    /// debounced by 200 milliseconds.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+
+        let inotify = InotifyFd::new()?;
+        inotify.set_nonblocking(true)?;
+        inotify.add_watch(dir, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)?;
+
+        let timer = TimerFd::new()?;
+        timer.set_nonblocking(true)?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            extension: None,
+            debounce: DEFAULT_DEBOUNCE,
+            inotify,
+            timer,
+            changed: BTreeSet::new(),
+            buf: alloc::vec![0; BUFFER_SIZE],
+        })
+    }
+
+    /// Only report files with the given extension, like `preset`.
+    pub fn with_extension(self, extension: &str) -> Self {
+        Self {
+            extension: Some(OsString::from(extension)),
+            ..self
+        }
+    }
+
+    /// Set how long to wait for further changes before changed files are
+    /// reported.
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        Self { debounce, ..self }
+    }
+
+    /// Get the directory being watched.
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The inotify file descriptor, which is readable when files in the
+    /// directory have changed, see [`PresetWatcher::notify`].
+    #[inline]
+    pub fn inotify_fd(&self) -> RawFd {
+        self.inotify.as_raw_fd()
+    }
+
+    /// The debounce timer, which is readable once changed files should be
+    /// reloaded, see [`PresetWatcher::expire`].
+    #[inline]
+    pub fn timer_fd(&self) -> RawFd {
+        self.timer.as_raw_fd()
+    }
+
+    /// Collect changed files, re-arming the debounce timer if any file
+    /// changed.
+    ///
+    /// Returns `true` if the timer was armed.
+    pub fn notify(&mut self) -> io::Result<bool> {
+        let mut armed = false;
+
+        while let Some(events) = self.inotify.read(&mut self.buf)? {
+            for event in events {
+                let name = Path::new(event.name());
+
+                if name.as_os_str().is_empty() {
+                    continue;
+                }
+
+                if let Some(extension) = &self.extension
+                    && name.extension() != Some(extension.as_os_str())
+                {
+                    continue;
+                }
+
+                self.changed.insert(self.dir.join(name));
+                armed = true;
+            }
+        }
+
+        if armed {
+            // NB: A zero timeout disarms the timer.
+            self.timer
+                .set_timeout(self.debounce.max(Duration::from_nanos(1)))?;
+        }
+
+        Ok(armed)
+    }
+
+    /// Take the files which changed once the debounce timer has expired, in
+    /// sorted order.
+    ///
+    /// Returns an empty list if the timer has not expired.
+    pub fn expire(&mut self) -> io::Result<Vec<PathBuf>> {
+        if self.timer.read()?.is_none() {
+            return Ok(Vec::new());
+        }
+
+        Ok(mem::take(&mut self.changed).into_iter().collect())
+    }
+}
@@ -0,0 +1,56 @@
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+use super::PresetWatcher;
+
+/// Construct an empty directory which is unique to the test.
+fn dir(name: &str) -> anyhow::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(std::format!("livemix-{name}-{}", std::process::id()));
+
+    _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+#[test]
+fn changed_presets_are_debounced() -> anyhow::Result<()> {
+    let dir = dir("preset-watcher")?;
+
+    let mut watcher = PresetWatcher::new(&dir)?
+        .with_extension("preset")
+        .with_debounce(Duration::from_millis(1));
+
+    assert!(!watcher.notify()?);
+    assert!(watcher.expire()?.is_empty());
+
+    fs::write(dir.join("a.preset"), b"a")?;
+    fs::write(dir.join("a.preset"), b"b")?;
+    fs::write(dir.join("b.txt"), b"b")?;
+    fs::write(dir.join("c.tmp"), b"c")?;
+    fs::rename(dir.join("c.tmp"), dir.join("c.preset"))?;
+
+    assert!(watcher.notify()?);
+
+    let mut changed = Vec::new();
+
+    for _ in 0..100 {
+        changed = watcher.expire()?;
+
+        if !changed.is_empty() {
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    assert_eq!(changed, [dir.join("a.preset"), dir.join("c.preset")]);
+    assert!(watcher.expire()?.is_empty());
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
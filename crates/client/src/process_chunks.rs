@@ -0,0 +1,157 @@
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+/// An iterator over fixed-size chunks of a processing cycle.
+///
+/// This is used by DSP code which requires processing in fixed block sizes
+/// which are smaller than the graph quantum, like FFT-based effects. Each
+/// [`ProcessChunk`] describes the offset and length of the samples to process
+/// so that the same callback can be called repeatedly with adjusted buffers.
+///
+/// This is constructed through [`ClientNode::process_chunks`].
+///
+/// [`ClientNode::process_chunks`]: crate::ClientNode::process_chunks
+///
+/// # Examples
+///
+/// ```
+/// use client::ProcessChunks;
+///
+/// let input = [1.0f32; 10];
+/// let mut output = [0.0f32; 10];
+///
+/// let mut chunks = Vec::new();
+///
+/// for chunk in ProcessChunks::new(10, Some(4)) {
+///     let input = chunk.slice(&input);
+///     let output = chunk.slice_mut(&mut output);
+///     output.copy_from_slice(input);
+///     chunks.push((chunk.offset(), chunk.len(), chunk.is_partial()));
+/// }
+///
+/// assert_eq!(chunks, [(0, 4, false), (4, 4, false), (8, 2, true)]);
+/// assert_eq!(output, input);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProcessChunks {
+    offset: usize,
+    duration: usize,
+    size: usize,
+}
+
+impl ProcessChunks {
+    /// Construct an iterator over chunks of `duration` samples each at most
+    /// `size` samples large.
+    ///
+    /// If `size` is `None` or zero the whole duration is processed as a single
+    /// chunk.
+    pub fn new(duration: usize, size: Option<usize>) -> Self {
+        let size = match size {
+            Some(size) if size > 0 => size,
+            _ => duration,
+        };
+
+        Self {
+            offset: 0,
+            duration,
+            size,
+        }
+    }
+}
+
+impl Iterator for ProcessChunks {
+    type Item = ProcessChunk;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.duration {
+            return None;
+        }
+
+        let len = self.size.min(self.duration - self.offset);
+
+        let chunk = ProcessChunk {
+            offset: self.offset,
+            len,
+            partial: len < self.size,
+        };
+
+        self.offset += len;
+        Some(chunk)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.duration.saturating_sub(self.offset);
+        let len = remaining.div_ceil(self.size.max(1));
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ProcessChunks {}
+impl FusedIterator for ProcessChunks {}
+
+/// A single chunk in a processing cycle.
+///
+/// See [`ProcessChunks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessChunk {
+    offset: usize,
+    len: usize,
+    partial: bool,
+}
+
+impl ProcessChunk {
+    /// The offset in samples of the chunk from the start of the cycle.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The length of the chunk in samples.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Test if the chunk is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Test if the chunk is smaller than the requested chunk size.
+    ///
+    /// This happens for the last chunk in a cycle where the quantum is not a
+    /// multiple of the chunk size.
+    #[inline]
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// The range of samples covered by the chunk.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+
+    /// Get the part of a buffer covered by this chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is smaller than the end of the chunk.
+    #[inline]
+    pub fn slice<'a, T>(&self, buf: &'a [T]) -> &'a [T] {
+        &buf[self.range()]
+    }
+
+    /// Get the part of a buffer covered by this chunk mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is smaller than the end of the chunk.
+    #[inline]
+    pub fn slice_mut<'a, T>(&self, buf: &'a mut [T]) -> &'a mut [T] {
+        &mut buf[self.range()]
+    }
+}
@@ -0,0 +1,323 @@
+use core::mem;
+
+use alloc::vec::Vec;
+
+use anyhow::{Result, bail, ensure};
+use protocol::{consts, flags, id};
+use slab::Slab;
+
+use crate::client::Client;
+use crate::{GlobalId, LocalId, Metadata, ParamCache, ProxyId, RouteId, RouteVolume};
+
+/// The kind of a bound proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProxyKind {
+    /// A bound `PipeWire:Interface:Node`.
+    Node,
+    /// A bound `PipeWire:Interface:Device`.
+    Device,
+    /// A bound `PipeWire:Interface:Metadata`.
+    Metadata,
+}
+
+impl ProxyKind {
+    /// Get the proxy kind corresponding to an interface type.
+    pub fn from_type(ty: &str) -> Option<Self> {
+        match ty {
+            consts::INTERFACE_NODE => Some(Self::Node),
+            consts::INTERFACE_DEVICE => Some(Self::Device),
+            consts::INTERFACE_METADATA => Some(Self::Metadata),
+            _ => None,
+        }
+    }
+
+    /// Get the interface type of the proxy kind.
+    pub fn as_type(&self) -> &'static str {
+        match self {
+            Self::Node => consts::INTERFACE_NODE,
+            Self::Device => consts::INTERFACE_DEVICE,
+            Self::Metadata => consts::INTERFACE_METADATA,
+        }
+    }
+
+    /// The highest interface version supported for the proxy kind.
+    pub(crate) fn version(&self) -> u32 {
+        match self {
+            Self::Node => 3,
+            Self::Device => 3,
+            Self::Metadata => 3,
+        }
+    }
+}
+
+/// A proxy bound to a remote global object.
+#[non_exhaustive]
+pub struct Proxy {
+    /// The local identifier of the proxy.
+    pub id: LocalId,
+    /// The global identifier the proxy is bound to.
+    pub global_id: GlobalId,
+    /// The serial of the global object the proxy is bound to, if it was
+    /// announced.
+    pub serial: Option<u64>,
+    /// The kind of the proxy.
+    pub kind: ProxyKind,
+    /// Parameters received for the proxy.
+    pub params: ParamCache,
+    /// Properties received for the proxy, if it is bound to a metadata object.
+    pub metadata: Metadata,
+    pub(crate) subscribed: Vec<id::Param>,
+    /// Parameters which are re-enumerated when the server reports them as
+    /// changed.
+    pub(crate) watched: Vec<id::Param>,
+    /// The flags of parameters last reported by the server.
+    pub(crate) param_flags: Vec<(id::Param, flags::ParamFlags)>,
+    pub(crate) pending_routes: Vec<RouteVolume>,
+    /// The depth of the open batch of parameter changes, see
+    /// [`DeviceProxy::begin_params`].
+    pub(crate) batch: usize,
+    /// Route volume changes collected while a batch is open, and whether
+    /// they should be saved.
+    pub(crate) batched_routes: Vec<(RouteVolume, bool)>,
+}
+
+impl Proxy {
+    pub(crate) fn new(
+        id: LocalId,
+        global_id: GlobalId,
+        serial: Option<u64>,
+        kind: ProxyKind,
+    ) -> Self {
+        Self {
+            id,
+            global_id,
+            serial,
+            kind,
+            params: ParamCache::new(),
+            metadata: Metadata::new(),
+            subscribed: Vec::new(),
+            watched: Vec::new(),
+            param_flags: Vec::new(),
+            pending_routes: Vec::new(),
+            batch: 0,
+            batched_routes: Vec::new(),
+        }
+    }
+}
+
+/// A bound device proxy, through which the state of the device can be
+/// changed.
+///
+/// This is accessed through [`Stream::device`].
+///
+/// [`Stream::device`]: crate::Stream::device
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{RouteId, Stream};
+/// # use client::GlobalId;
+///
+/// # fn f(stream: &mut Stream, global_id: GlobalId) -> anyhow::Result<()> {
+/// let proxy_id = stream.bind(global_id)?;
+///
+/// let route = RouteId { index: 1, device: 0 };
+/// stream.device(proxy_id)?.set_route_volume(route, &[0.5, 0.5], false, true)?;
+/// # Ok(()) }
+/// ```
+pub struct DeviceProxy<'a> {
+    pub(crate) c: &'a mut Client,
+    pub(crate) proxy: &'a mut Proxy,
+    pub(crate) permissions: Option<flags::Permission>,
+}
+
+impl DeviceProxy<'_> {
+    /// Access the underlying proxy.
+    #[inline]
+    pub fn proxy(&self) -> &Proxy {
+        self.proxy
+    }
+
+    /// Iterate over route volume changes which the device has not yet
+    /// reported back.
+    pub fn pending_routes(&self) -> impl Iterator<Item = &RouteVolume> {
+        self.proxy.pending_routes.iter()
+    }
+
+    /// Begin a batch of parameter changes on the device.
+    ///
+    /// While a batch is open, route volume changes are collected instead of
+    /// being sent to the device. Once the batch is closed with
+    /// [`DeviceProxy::commit`] only the last change to each route is sent,
+    /// which avoids the device applying every intermediate state.
+    ///
+    /// Batches can be nested, in which case changes are only sent once the
+    /// outermost batch is committed.
+    ///
+    /// Unlike nodes, devices have no `ParamBegin` and `ParamEnd` commands in
+    /// the protocol, so the batch is only bracketed on the client.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use client::{RouteId, Stream};
+    /// # use client::ProxyId;
+    ///
+    /// # fn f(stream: &mut Stream, proxy_id: ProxyId) -> anyhow::Result<()> {
+    /// let mut device = stream.device(proxy_id)?;
+    /// device.begin_params();
+    ///
+    /// for index in 0..4 {
+    ///     let route = RouteId { index, device: index };
+    ///     device.set_route_volume(route, &[0.5, 0.5], false, true)?;
+    /// }
+    ///
+    /// device.commit()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn begin_params(&mut self) {
+        self.proxy.batch += 1;
+    }
+
+    /// Commit a batch of parameter changes started with
+    /// [`DeviceProxy::begin_params`], sending the collected changes once the
+    /// outermost batch is committed.
+    pub fn commit(&mut self) -> Result<()> {
+        self.proxy.batch = self.proxy.batch.saturating_sub(1);
+
+        if self.proxy.batch > 0 {
+            return Ok(());
+        }
+
+        for (volume, save) in mem::take(&mut self.proxy.batched_routes) {
+            self.send_route_volume(volume, save)?;
+        }
+
+        Ok(())
+    }
+
+    /// Test if the device has an open batch of parameter changes.
+    #[inline]
+    pub fn is_batching(&self) -> bool {
+        self.proxy.batch > 0
+    }
+
+    /// Set the volume and mute state of a route on the device.
+    ///
+    /// If `save` is set, the session manager is asked to persist the new route
+    /// settings. The device is subscribed to [`id::Param::ROUTE`] updates if it
+    /// isn't already, and once the device reports the state of the route with
+    /// the same index and device a [`StreamEvent::RouteVolume`] event is
+    /// emitted indicating whether the change was applied.
+    ///
+    /// If a batch is open the change is sent once it is committed, see
+    /// [`DeviceProxy::begin_params`].
+    ///
+    /// [`StreamEvent::RouteVolume`]: crate::events::StreamEvent::RouteVolume
+    pub fn set_route_volume(
+        &mut self,
+        route: RouteId,
+        channel_volumes: &[f32],
+        mute: bool,
+        save: bool,
+    ) -> Result<()> {
+        let perm = flags::Permission::W | flags::Permission::X;
+        let global_id = self.proxy.global_id;
+
+        ensure!(
+            self.permissions.is_some_and(|p| p.contains(perm)),
+            "Missing permissions {perm:?} on global object {global_id}"
+        );
+
+        let volume = RouteVolume::new(route, channel_volumes, mute);
+
+        if self.proxy.batch > 0 {
+            self.proxy.batched_routes.retain(|(p, _)| p.route != route);
+            self.proxy.batched_routes.push((volume, save));
+            return Ok(());
+        }
+
+        self.send_route_volume(volume, save)
+    }
+
+    fn send_route_volume(&mut self, volume: RouteVolume, save: bool) -> Result<()> {
+        let mut pod = pod::dynamic();
+        volume.write(pod.as_mut(), save)?;
+
+        self.c.device_set_param(
+            self.proxy.id,
+            id::Param::ROUTE,
+            0,
+            pod.as_ref().read_object()?,
+        )?;
+
+        if !self.proxy.subscribed.contains(&id::Param::ROUTE) {
+            self.proxy.subscribed.push(id::Param::ROUTE);
+            self.c
+                .device_subscribe_params(self.proxy.id, &self.proxy.subscribed)?;
+        }
+
+        self.proxy
+            .pending_routes
+            .retain(|p| p.route != volume.route);
+        self.proxy.pending_routes.push(volume);
+        Ok(())
+    }
+}
+
+/// Collection of bound proxies.
+pub struct Proxies {
+    data: Slab<Proxy>,
+}
+
+impl Proxies {
+    /// Create a new empty collection of proxies.
+    #[inline]
+    pub fn new() -> Self {
+        Self { data: Slab::new() }
+    }
+
+    /// Insert a new proxy into the collection.
+    pub(crate) fn insert(&mut self, proxy: Proxy) -> ProxyId {
+        let id = self.data.insert(proxy);
+        ProxyId::new(id as u32)
+    }
+
+    /// Remove a proxy from the collection by its identifier.
+    pub(crate) fn remove(&mut self, id: ProxyId) -> Option<Proxy> {
+        self.data.try_remove(id.index())
+    }
+
+    /// Get a reference to the proxy with the given ID.
+    #[inline]
+    pub fn get(&self, id: ProxyId) -> Result<&Proxy> {
+        let Some(proxy) = self.data.get(id.index()) else {
+            bail!("No proxy found for id {id}");
+        };
+
+        Ok(proxy)
+    }
+
+    /// Iterate over all bound proxies.
+    pub fn iter(&self) -> impl Iterator<Item = &Proxy> {
+        self.data.iter().map(|(_, proxy)| proxy)
+    }
+
+    /// Get a mutable reference to the proxy with the given ID.
+    #[inline]
+    pub fn get_mut(&mut self, id: ProxyId) -> Result<&mut Proxy> {
+        let Some(proxy) = self.data.get_mut(id.index()) else {
+            bail!("No proxy found for id {id}");
+        };
+
+        Ok(proxy)
+    }
+}
+
+impl Default for Proxies {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use protocol::flags;
+use protocol::{Prop, Properties, Symbol, consts, prop};
+use slab::Slab;
+
+use crate::GlobalId;
+
+/// A global object announced by the registry.
+///
+/// Global objects are accessed through [`Stream::registry`], and are emitted
+/// as they are added and removed through [`StreamEvent::GlobalAdded`] and
+/// [`StreamEvent::GlobalRemoved`]. When the connection is lost, every global
+/// object is removed at once, which is only signalled through
+/// [`StreamEvent::Disconnected`].
+///
+/// [`Stream::registry`]: crate::Stream::registry
+/// [`StreamEvent::GlobalAdded`]: crate::events::StreamEvent::GlobalAdded
+/// [`StreamEvent::GlobalRemoved`]: crate::events::StreamEvent::GlobalRemoved
+/// [`StreamEvent::Disconnected`]: crate::events::StreamEvent::Disconnected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalObject {
+    pub(crate) id: GlobalId,
+    pub(crate) serial: Option<u64>,
+    pub(crate) permissions: i32,
+    pub(crate) ty: Symbol,
+    pub(crate) version: u32,
+    pub(crate) props: Properties,
+}
+
+impl GlobalObject {
+    /// The identifier of the global object.
+    #[inline]
+    pub fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    /// The serial of the global object, as announced through the
+    /// `object.serial` property.
+    #[inline]
+    pub fn serial(&self) -> Option<u64> {
+        self.serial
+    }
+
+    /// The permissions the client has on the global object.
+    #[inline]
+    pub fn permissions(&self) -> flags::Permission {
+        flags::Permission::from_raw(self.permissions as u32)
+    }
+
+    /// The interface type of the global object, like
+    /// [`consts::INTERFACE_NODE`].
+    ///
+    /// [`consts::INTERFACE_NODE`]: protocol::consts::INTERFACE_NODE
+    #[inline]
+    pub fn ty(&self) -> &str {
+        self.ty.as_str()
+    }
+
+    /// The version of the interface of the global object.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The properties of the global object.
+    #[inline]
+    pub fn props(&self) -> &Properties {
+        &self.props
+    }
+
+    /// The `media.class` property of the global object.
+    #[inline]
+    pub fn media_class(&self) -> Option<&str> {
+        self.props.get(prop::MEDIA_CLASS)
+    }
+
+    /// Access the global object as a node, if it is one.
+    pub fn as_node(&self) -> Option<Node<'_>> {
+        (self.ty == consts::INTERFACE_NODE).then_some(Node { object: self })
+    }
+
+    /// Access the global object as a device, if it is one.
+    pub fn as_device(&self) -> Option<Device<'_>> {
+        (self.ty == consts::INTERFACE_DEVICE).then_some(Device { object: self })
+    }
+
+    /// Access the global object as a link, if it is one.
+    pub fn as_link(&self) -> Option<Link<'_>> {
+        (self.ty == consts::INTERFACE_LINK).then_some(Link { object: self })
+    }
+
+    /// Get a property which holds a global identifier.
+    fn global_id(&self, key: &Prop) -> Option<GlobalId> {
+        let id = self.props.get(key)?.parse().ok()?;
+        Some(GlobalId::new(id))
+    }
+}
+
+/// A global node announced by the registry.
+#[derive(Debug, Clone, Copy)]
+pub struct Node<'a> {
+    object: &'a GlobalObject,
+}
+
+impl<'a> Node<'a> {
+    /// The global object of the node.
+    #[inline]
+    pub fn object(&self) -> &'a GlobalObject {
+        self.object
+    }
+
+    /// The identifier of the node.
+    #[inline]
+    pub fn id(&self) -> GlobalId {
+        self.object.id
+    }
+
+    /// The `node.name` property of the node.
+    #[inline]
+    pub fn name(&self) -> Option<&'a str> {
+        self.object.props.get(prop::NODE_NAME)
+    }
+
+    /// The `node.description` property of the node.
+    #[inline]
+    pub fn description(&self) -> Option<&'a str> {
+        self.object.props.get(prop::NODE_DESCRIPTION)
+    }
+
+    /// The `media.class` property of the node, like `Audio/Sink`.
+    #[inline]
+    pub fn media_class(&self) -> Option<&'a str> {
+        self.object.props.get(prop::MEDIA_CLASS)
+    }
+}
+
+/// A global device announced by the registry.
+#[derive(Debug, Clone, Copy)]
+pub struct Device<'a> {
+    object: &'a GlobalObject,
+}
+
+impl<'a> Device<'a> {
+    /// The global object of the device.
+    #[inline]
+    pub fn object(&self) -> &'a GlobalObject {
+        self.object
+    }
+
+    /// The identifier of the device.
+    #[inline]
+    pub fn id(&self) -> GlobalId {
+        self.object.id
+    }
+
+    /// The `device.name` property of the device.
+    #[inline]
+    pub fn name(&self) -> Option<&'a str> {
+        self.object.props.get(prop::DEVICE_NAME)
+    }
+
+    /// The `device.description` property of the device.
+    #[inline]
+    pub fn description(&self) -> Option<&'a str> {
+        self.object.props.get(prop::DEVICE_DESCRIPTION)
+    }
+
+    /// The `media.class` property of the device, like `Audio/Device`.
+    #[inline]
+    pub fn media_class(&self) -> Option<&'a str> {
+        self.object.props.get(prop::MEDIA_CLASS)
+    }
+}
+
+/// A global link announced by the registry.
+#[derive(Debug, Clone, Copy)]
+pub struct Link<'a> {
+    object: &'a GlobalObject,
+}
+
+impl<'a> Link<'a> {
+    /// The global object of the link.
+    #[inline]
+    pub fn object(&self) -> &'a GlobalObject {
+        self.object
+    }
+
+    /// The identifier of the link.
+    #[inline]
+    pub fn id(&self) -> GlobalId {
+        self.object.id
+    }
+
+    /// The node the link is fed from.
+    #[inline]
+    pub fn output_node(&self) -> Option<GlobalId> {
+        self.object.global_id(prop::LINK_OUTPUT_NODE)
+    }
+
+    /// The port the link is fed from.
+    #[inline]
+    pub fn output_port(&self) -> Option<GlobalId> {
+        self.object.global_id(prop::LINK_OUTPUT_PORT)
+    }
+
+    /// The node the link feeds into.
+    #[inline]
+    pub fn input_node(&self) -> Option<GlobalId> {
+        self.object.global_id(prop::LINK_INPUT_NODE)
+    }
+
+    /// The port the link feeds into.
+    #[inline]
+    pub fn input_port(&self) -> Option<GlobalId> {
+        self.object.global_id(prop::LINK_INPUT_PORT)
+    }
+}
+
+/// A view of the global objects announced by the registry.
+///
+/// Objects are iterated over in the order of their identifiers. Globals which
+/// have been dropped by a [`RegistryFilter`] are not visible.
+///
+/// See [`Stream::registry`].
+///
+/// [`RegistryFilter`]: crate::RegistryFilter
+/// [`Stream::registry`]: crate::Stream::registry
+#[derive(Clone, Copy)]
+pub struct Registry<'a> {
+    objects: &'a Slab<GlobalObject>,
+    index: &'a BTreeMap<GlobalId, usize>,
+}
+
+impl<'a> Registry<'a> {
+    #[inline]
+    pub(crate) fn new(
+        objects: &'a Slab<GlobalObject>,
+        index: &'a BTreeMap<GlobalId, usize>,
+    ) -> Self {
+        Self { objects, index }
+    }
+
+    /// The number of global objects.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Test if there are no global objects.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Get the global object with the given identifier.
+    pub fn get(&self, id: GlobalId) -> Option<&'a GlobalObject> {
+        self.objects.get(*self.index.get(&id)?)
+    }
+
+    /// Iterate over all global objects.
+    pub fn iter(&self) -> impl Iterator<Item = &'a GlobalObject> + 'a {
+        let objects = self.objects;
+        self.index
+            .values()
+            .filter_map(move |&index| objects.get(index))
+    }
+
+    /// Iterate over all nodes.
+    pub fn nodes(&self) -> impl Iterator<Item = Node<'a>> + 'a {
+        self.iter().filter_map(GlobalObject::as_node)
+    }
+
+    /// Iterate over all devices.
+    pub fn devices(&self) -> impl Iterator<Item = Device<'a>> + 'a {
+        self.iter().filter_map(GlobalObject::as_device)
+    }
+
+    /// Iterate over all links.
+    pub fn links(&self) -> impl Iterator<Item = Link<'a>> + 'a {
+        self.iter().filter_map(GlobalObject::as_link)
+    }
+}
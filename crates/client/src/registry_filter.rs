@@ -0,0 +1,101 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use pod::{Slice, Struct};
+use protocol::{Prop, Symbol};
+
+/// A filter for global objects announced by the registry.
+///
+/// Filters are installed through [`Stream::add_registry_filter`]. Once any
+/// filter has been installed, only globals which match at least one of them
+/// are tracked by the stream. Other globals are dropped as they are announced,
+/// before any of their properties are copied.
+///
+/// A filter matches if the global has one of the specified interface types,
+/// and all of the specified properties. A filter without any types matches
+/// all interface types.
+///
+/// [`Stream::add_registry_filter`]: crate::Stream::add_registry_filter
+///
+/// # Examples
+///
+/// ```
+/// use client::RegistryFilter;
+/// use protocol::{consts, prop};
+///
+/// let filter = RegistryFilter::new()
+///     .interface(consts::INTERFACE_NODE)
+///     .prop(prop::MEDIA_CLASS, "Audio/Sink");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RegistryFilter {
+    types: Vec<Symbol>,
+    props: Vec<(Symbol, Option<String>)>,
+}
+
+impl RegistryFilter {
+    /// Construct a filter which matches all globals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match globals with the given interface type, like
+    /// [`consts::INTERFACE_NODE`].
+    ///
+    /// If called multiple times, globals matching any of the types match.
+    ///
+    /// [`consts::INTERFACE_NODE`]: protocol::consts::INTERFACE_NODE
+    pub fn interface(mut self, ty: &str) -> Self {
+        self.types.push(Symbol::new(ty));
+        self
+    }
+
+    /// Require that the global has a property with the given value.
+    pub fn prop(mut self, key: impl AsRef<Prop>, value: impl AsRef<str>) -> Self {
+        let key = Symbol::new(key.as_ref().as_str());
+        self.props.push((key, Some(String::from(value.as_ref()))));
+        self
+    }
+
+    /// Require that the global has the given property, regardless of its
+    /// value.
+    pub fn has_prop(mut self, key: impl AsRef<Prop>) -> Self {
+        let key = Symbol::new(key.as_ref().as_str());
+        self.props.push((key, None));
+        self
+    }
+
+    /// Test if the filter matches a global with the given type and
+    /// properties.
+    ///
+    /// The properties are the key-value pairs following the number of items
+    /// in the announced global.
+    pub(crate) fn matches(&self, ty: &str, props: Struct<Slice<'_>>) -> Result<bool> {
+        if !self.types.is_empty() && !self.types.iter().any(|t| *t == ty) {
+            return Ok(false);
+        }
+
+        'outer: for (key, expected) in &self.props {
+            let mut props = props.as_ref();
+
+            while !props.is_empty() {
+                let (k, v) = props.read::<(&str, &str)>()?;
+
+                if *key != k {
+                    continue;
+                }
+
+                if expected.as_deref().is_none_or(|expected| expected == v) {
+                    continue 'outer;
+                }
+
+                return Ok(false);
+            }
+
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+}
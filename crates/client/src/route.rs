@@ -0,0 +1,162 @@
+use alloc::vec::Vec;
+
+use pod::{AsSlice, Builder, Error, Object, Type, Writer};
+use protocol::id;
+
+#[cfg(test)]
+mod tests;
+
+/// The maximum difference between a requested and a reported channel volume
+/// for them to be considered equal.
+const VOLUME_EPSILON: f32 = 1e-4;
+
+/// Identifies a route on a device.
+///
+/// These correspond to the `index` and `device` properties of the
+/// [`id::Param::ROUTE`] parameters advertised by a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RouteId {
+    /// The index of the route.
+    pub index: i32,
+    /// The device the route belongs to.
+    pub device: i32,
+}
+
+/// The volume state of a device route.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct RouteVolume {
+    /// The route the volume applies to.
+    pub route: RouteId,
+    /// Linear volume per channel.
+    pub channel_volumes: Vec<f32>,
+    /// Whether the route is muted.
+    pub mute: bool,
+}
+
+impl RouteVolume {
+    /// Construct a new route volume.
+    pub fn new(route: RouteId, channel_volumes: &[f32], mute: bool) -> Self {
+        Self {
+            route,
+            channel_volumes: channel_volumes.to_vec(),
+            mute,
+        }
+    }
+
+    /// Read the volume state out of a [`id::Param::ROUTE`] parameter.
+    ///
+    /// This errors if the parameter is missing the index or the device of the
+    /// route, since those identify it.
+    pub fn read(obj: Object<impl AsSlice>) -> Result<Self, Error> {
+        let mut obj = obj.as_ref();
+
+        let mut index = None;
+        let mut device = None;
+
+        let mut this = Self {
+            route: RouteId {
+                index: 0,
+                device: 0,
+            },
+            channel_volumes: Vec::new(),
+            mute: false,
+        };
+
+        while !obj.is_empty() {
+            let p = obj.property()?;
+
+            match p.key::<id::ParamRoute>() {
+                id::ParamRoute::INDEX => {
+                    index = Some(p.value().read_sized()?);
+                }
+                id::ParamRoute::DEVICE => {
+                    device = Some(p.value().read_sized()?);
+                }
+                id::ParamRoute::PROPS => {
+                    let mut props = p.value().read_object()?;
+
+                    while !props.is_empty() {
+                        let p = props.property()?;
+
+                        match p.key::<id::Prop>() {
+                            id::Prop::MUTE => {
+                                this.mute = p.value().read_sized()?;
+                            }
+                            id::Prop::CHANNEL_VOLUMES => {
+                                let mut array = p.value().read_array()?;
+                                this.channel_volumes.clear();
+
+                                while let Some(value) = array.next()? {
+                                    this.channel_volumes.push(value.read_sized()?);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let Some(index) = index else {
+            return Err(Error::__missing_object_field("index"));
+        };
+
+        let Some(device) = device else {
+            return Err(Error::__missing_object_field("device"));
+        };
+
+        this.route = RouteId { index, device };
+        Ok(this)
+    }
+
+    /// Write the volume state as a [`id::Param::ROUTE`] parameter.
+    ///
+    /// If `save` is set, the session manager is asked to persist the route
+    /// settings.
+    pub fn write(&self, pod: Builder<impl Writer>, save: bool) -> Result<(), Error> {
+        pod.write_object(id::ObjectType::PARAM_ROUTE, id::Param::ROUTE, |obj| {
+            obj.property(id::ParamRoute::INDEX)
+                .write_sized(self.route.index)?;
+            obj.property(id::ParamRoute::DEVICE)
+                .write_sized(self.route.device)?;
+
+            obj.property(id::ParamRoute::PROPS).write_object(
+                id::ObjectType::PROPS,
+                id::Param::ROUTE,
+                |props| {
+                    props.property(id::Prop::MUTE).write_sized(self.mute)?;
+
+                    props.property(id::Prop::CHANNEL_VOLUMES).write_array(
+                        Type::FLOAT,
+                        |array| {
+                            for volume in &self.channel_volumes {
+                                array.child().write_sized(*volume)?;
+                            }
+
+                            Ok(())
+                        },
+                    )?;
+
+                    Ok(())
+                },
+            )?;
+
+            obj.property(id::ParamRoute::SAVE).write_sized(save)?;
+            Ok(())
+        })
+    }
+
+    /// Test if the reported volume state matches this one.
+    pub(crate) fn matches(&self, other: &RouteVolume) -> bool {
+        self.route == other.route
+            && self.mute == other.mute
+            && self.channel_volumes.len() == other.channel_volumes.len()
+            && self
+                .channel_volumes
+                .iter()
+                .zip(&other.channel_volumes)
+                .all(|(a, b)| (a - b).abs() <= VOLUME_EPSILON)
+    }
+}
@@ -0,0 +1,35 @@
+use protocol::id;
+
+use super::{RouteId, RouteVolume};
+
+#[test]
+fn roundtrip() -> Result<(), pod::Error> {
+    let route = RouteId {
+        index: 3,
+        device: 7,
+    };
+
+    let volume = RouteVolume::new(route, &[0.25, 0.5], true);
+
+    let mut pod = pod::dynamic();
+    volume.write(pod.as_mut(), true)?;
+
+    let read = RouteVolume::read(pod.as_ref().read_object()?)?;
+    assert_eq!(read, volume);
+    assert!(read.matches(&volume));
+    Ok(())
+}
+
+#[test]
+fn missing_device() -> Result<(), pod::Error> {
+    let mut pod = pod::dynamic();
+
+    pod.as_mut()
+        .write_object(id::ObjectType::PARAM_ROUTE, id::Param::ROUTE, |obj| {
+            obj.property(id::ParamRoute::INDEX).write_sized(3i32)?;
+            Ok(())
+        })?;
+
+    assert!(RouteVolume::read(pod.as_ref().read_object()?).is_err());
+    Ok(())
+}
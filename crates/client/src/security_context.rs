@@ -0,0 +1,58 @@
+use std::fs;
+use std::io;
+use std::os::fd::{FromRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+
+/// A restricted socket created through the security context extension.
+///
+/// Clients connecting to the socket at [`SecurityContext::path`] have the
+/// properties which were specified when creating the context, which the
+/// session manager uses to restrict their access to the graph. The path can
+/// be handed to a child process through the `PIPEWIRE_REMOTE` environment
+/// variable.
+///
+/// Dropping the security context closes it, which causes the server to stop
+/// accepting connections on the socket.
+#[derive(Debug)]
+pub struct SecurityContext {
+    path: PathBuf,
+    // Held to keep the security context open, the server closes the context
+    // once this is closed.
+    _close: OwnedFd,
+}
+
+impl SecurityContext {
+    pub(crate) fn new(path: PathBuf, close: OwnedFd) -> Self {
+        Self {
+            path,
+            _close: close,
+        }
+    }
+
+    /// The path of the restricted socket.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SecurityContext {
+    #[inline]
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Create a pipe returning the read and write ends.
+pub(crate) fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+
+    // SAFETY: The buffer is correctly sized for the two returned descriptors.
+    unsafe {
+        if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])))
+    }
+}
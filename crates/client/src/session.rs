@@ -0,0 +1,312 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail, ensure};
+use pod::builder::StructBuilder;
+use pod::{AsSlice, BuildPod, Builder, DynamicBuf, Error, Pod, Slice, Struct, Writer};
+use protocol::consts::Direction;
+
+/// The version of the session format written by [`Session::write`].
+const SESSION_VERSION: i32 = 1;
+
+/// A reference to a node which can be resolved again after a restart.
+///
+/// Global ids are reassigned when objects are recreated, so nodes are
+/// remembered by their `node.name` together with the serial they last had.
+/// The serial disambiguates nodes with the same name as long as the original
+/// node is still around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct NodeRef {
+    /// The `node.name` of the node.
+    pub name: String,
+    /// The `object.serial` the node last had, if known.
+    pub serial: Option<u64>,
+}
+
+impl NodeRef {
+    /// Construct a new node reference.
+    pub fn new(name: &str, serial: Option<u64>) -> Self {
+        Self {
+            name: String::from(name),
+            serial,
+        }
+    }
+
+    fn write<W, P>(&self, st: &mut StructBuilder<W, P>) -> Result<(), Error>
+    where
+        W: Writer,
+        P: BuildPod,
+    {
+        st.field().write_struct(|st| {
+            st.field().write_unsized(self.name.as_str())?;
+
+            match self.serial {
+                Some(serial) => st.field().write_sized(serial),
+                None => st.field().write_none(),
+            }
+        })
+    }
+
+    fn read(mut st: Struct<Slice<'_>>) -> Result<Self> {
+        let name = st.field()?.read_unsized::<str>()?;
+        let serial = st.read::<Option<u64>>()?;
+        Ok(Self::new(name, serial))
+    }
+}
+
+/// A link between a port of a channel or bus and a port elsewhere in the
+/// graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionLink {
+    /// The direction of the local port.
+    pub direction: Direction,
+    /// The `port.name` of the local port.
+    pub port: String,
+    /// The node the local port is linked to.
+    pub peer: NodeRef,
+    /// The `port.name` of the port the local port is linked to.
+    pub peer_port: String,
+}
+
+impl SessionLink {
+    /// Construct a new session link.
+    pub fn new(direction: Direction, port: &str, peer: NodeRef, peer_port: &str) -> Self {
+        Self {
+            direction,
+            port: String::from(port),
+            peer,
+            peer_port: String::from(peer_port),
+        }
+    }
+}
+
+/// The persisted state of a channel or bus.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionEntry {
+    /// The node the channel or bus was last realized as.
+    pub node: Option<NodeRef>,
+    /// The node the channel or bus targets, like an output device.
+    pub target: Option<NodeRef>,
+    /// Links of the channel or bus.
+    pub links: Vec<SessionLink>,
+}
+
+/// A session mapping channel and bus names to the objects they were last
+/// associated with, so that they can be restored after a restart.
+///
+/// Entries are recorded with [`Stream::record_session`] and restored with
+/// [`Stream::restore_session`], which resolves them to equivalent objects
+/// even though their global ids have changed.
+///
+/// [`Stream::record_session`]: crate::Stream::record_session
+/// [`Stream::restore_session`]: crate::Stream::restore_session
+///
+/// # Examples
+///
+/// ```
+/// use client::{NodeRef, Session, SessionLink};
+/// use protocol::consts::Direction;
+///
+/// let mut session = Session::new();
+///
+/// let entry = session.entry("monitor");
+/// entry.target = Some(NodeRef::new("alsa_output.usb-headphones", Some(61)));
+/// entry.links.push(SessionLink::new(
+///     Direction::OUTPUT,
+///     "output_FL",
+///     NodeRef::new("recorder", None),
+///     "input_FL",
+/// ));
+///
+/// let mut pod = pod::dynamic();
+/// session.write(pod.as_mut())?;
+///
+/// let restored = Session::read(pod.as_ref().read_struct()?)?;
+/// assert_eq!(restored, session);
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Session {
+    entries: BTreeMap<String, SessionEntry>,
+}
+
+impl Session {
+    /// Construct a new empty session.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a session from a file.
+    ///
+    /// A missing file results in an empty session.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(e).with_context(|| format!("Reading {}", path.display())),
+        };
+
+        let pod = Pod::new(DynamicBuf::from_slice(&bytes)?);
+
+        Self::read(pod.as_ref().read_struct()?)
+            .with_context(|| format!("Parsing {}", path.display()))
+    }
+
+    /// Save the session to a file.
+    ///
+    /// The session is written to a temporary file next to `path` which is
+    /// synced to disk and then renamed over it, so that a crash never leaves a
+    /// partial session behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        let mut pod = pod::dynamic();
+        self.write(pod.as_mut())?;
+
+        let tmp = path.with_extension("tmp");
+
+        let mut file =
+            fs::File::create(&tmp).with_context(|| format!("Creating {}", tmp.display()))?;
+
+        file.write_all(pod.as_buf().as_bytes())
+            .with_context(|| format!("Writing {}", tmp.display()))?;
+
+        // NB: The contents must be on disk before the rename, otherwise a
+        // crash could leave an empty or partial file under the final name.
+        file.sync_all()
+            .with_context(|| format!("Syncing {}", tmp.display()))?;
+
+        drop(file);
+
+        fs::rename(&tmp, path).with_context(|| format!("Renaming to {}", path.display()))?;
+
+        // NB: Syncing the directory persists the rename itself.
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        fs::File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .with_context(|| format!("Syncing {}", dir.display()))?;
+
+        Ok(())
+    }
+
+    /// Get the entry of a channel or bus.
+    pub fn get(&self, name: &str) -> Option<&SessionEntry> {
+        self.entries.get(name)
+    }
+
+    /// Get the entry of a channel or bus, inserting an empty one if it
+    /// doesn't exist.
+    pub fn entry(&mut self, name: &str) -> &mut SessionEntry {
+        self.entries.entry(String::from(name)).or_default()
+    }
+
+    /// Remove the entry of a channel or bus.
+    pub fn remove(&mut self, name: &str) -> Option<SessionEntry> {
+        self.entries.remove(name)
+    }
+
+    /// Iterate over all entries.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SessionEntry)> + '_ {
+        self.entries
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry))
+    }
+
+    /// Write the session.
+    pub fn write(&self, pod: Builder<impl Writer>) -> Result<(), Error> {
+        pod.write_struct(|st| {
+            st.field().write_sized(SESSION_VERSION)?;
+
+            st.field().write_struct(|entries| {
+                for (name, entry) in &self.entries {
+                    entries.field().write_struct(|st| {
+                        st.field().write_unsized(name.as_str())?;
+
+                        for node in [&entry.node, &entry.target] {
+                            match node {
+                                Some(node) => node.write(st)?,
+                                None => st.field().write_none()?,
+                            }
+                        }
+
+                        st.field().write_struct(|links| {
+                            for link in &entry.links {
+                                links.field().write_struct(|st| {
+                                    st.field().write(link.direction)?;
+                                    st.field().write_unsized(link.port.as_str())?;
+                                    link.peer.write(st)?;
+                                    st.field().write_unsized(link.peer_port.as_str())
+                                })?;
+                            }
+
+                            Ok(())
+                        })
+                    })?;
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Read a session written by [`Session::write`].
+    pub fn read(st: Struct<impl AsSlice>) -> Result<Self> {
+        let mut st = st.as_ref();
+
+        let version = st.field()?.read_sized::<i32>()?;
+        ensure!(
+            version == SESSION_VERSION,
+            "Unsupported session version {version}"
+        );
+
+        let mut entries = BTreeMap::new();
+        let mut list = st.field()?.read_struct()?;
+
+        while !list.is_empty() {
+            let mut st = list.field()?.read_struct()?;
+            let name = st.field()?.read_unsized::<str>()?;
+
+            let mut entry = SessionEntry::default();
+
+            for node in [&mut entry.node, &mut entry.target] {
+                if let Some(value) = st.field()?.read_option()? {
+                    *node = Some(NodeRef::read(value.read_struct()?)?);
+                }
+            }
+
+            let mut links = st.field()?.read_struct()?;
+
+            while !links.is_empty() {
+                let mut st = links.field()?.read_struct()?;
+                let direction = st.read::<Direction>()?;
+                let port = st.field()?.read_unsized::<str>()?;
+                let peer = NodeRef::read(st.field()?.read_struct()?)?;
+                let peer_port = st.field()?.read_unsized::<str>()?;
+                entry
+                    .links
+                    .push(SessionLink::new(direction, port, peer, peer_port));
+            }
+
+            if entries.insert(String::from(name), entry).is_some() {
+                bail!("Duplicate session entry `{name}`");
+            }
+        }
+
+        Ok(Self { entries })
+    }
+}
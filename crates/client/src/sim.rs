@@ -0,0 +1,292 @@
+//! Deterministic simulation of the processing cycle.
+//!
+//! This drives a [`ClientNode`] through a scripted timeline without a running
+//! PipeWire server. The activation areas of the node and its peers and the
+//! position of the graph clock live in memory owned by the [`Simulation`],
+//! so the scheduling logic of the node, like how it transitions its status and
+//! triggers its peers, can be tested in isolation.
+//!
+//! # Examples
+//!
+//! ```
+//! use client::sim::{Simulation, Step};
+//! use protocol::consts::Activation;
+//!
+//! let mut sim = Simulation::new(48_000, 1024)?;
+//! let peer = sim.add_peer(2)?;
+//!
+//! sim.replay([Step::Cycle, Step::Trigger, Step::Process])?;
+//! assert_eq!(sim.status(), Activation::FINISHED);
+//! assert_eq!(sim.peer_pending(peer), 1);
+//! assert_eq!(sim.peer_status(peer), Activation::NOT_TRIGGERED);
+//!
+//! // Once the other input of the peer finishes, it is triggered.
+//! sim.replay([Step::SignalPeer(peer)])?;
+//! assert_eq!(sim.peer_status(peer), Activation::TRIGGERED);
+//!
+//! // Processing without being triggered is an xrun, and peers are left
+//! // untouched.
+//! sim.replay([Step::Cycle, Step::Process])?;
+//! assert_eq!(sim.node().stats().not_self_triggered, 1);
+//! assert_eq!(sim.peer_pending(peer), 2);
+//! # Ok::<_, anyhow::Error>(())
+//! ```
+
+use core::mem;
+use core::ptr::NonNull;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use protocol::EventFd;
+use protocol::Properties;
+use protocol::consts::Activation;
+use protocol::ffi;
+use protocol::poll::Token;
+
+use crate::grace::Epoch;
+use crate::memory::Region;
+use crate::ptr::{atomic, volatile};
+use crate::{ClientNode, LocalId, PeerActivation, Ports};
+
+/// A step in a simulated timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Step {
+    /// Start a new cycle of the graph.
+    ///
+    /// This advances the graph clock by one quantum, and resets the node and
+    /// every peer to [`Activation::NOT_TRIGGERED`] with their pending counts
+    /// restored to the number of required signals, like the driver does.
+    Cycle,
+    /// Trigger the simulated node, like a node it depends on finishing.
+    Trigger,
+    /// Run a processing cycle of the simulated node, which is a call to
+    /// [`ClientNode::start_process`] followed by [`ClientNode::end_process`].
+    Process,
+    /// Signal a peer from some other node in the graph, which decrements its
+    /// pending count and triggers it if it reaches zero.
+    SignalPeer(usize),
+    /// Set the status of the simulated node.
+    SetStatus(Activation),
+}
+
+/// A simulation of the processing cycle of a single node.
+///
+/// See the [module level documentation](self).
+pub struct Simulation {
+    // NB: The node must be dropped before the storage it refers to.
+    node: ClientNode,
+    storage: Storage,
+    cycle: u64,
+    nsec: u64,
+    rate: u32,
+    quantum: u64,
+}
+
+impl Simulation {
+    /// Construct a new simulation of a graph running at the given sample
+    /// `rate` where each cycle processes `quantum` samples.
+    pub fn new(rate: u32, quantum: u64) -> Result<Self> {
+        let mut storage = Storage {
+            activation: alloc_zeroed(),
+            position: alloc_zeroed(),
+            peers: Vec::new(),
+        };
+
+        let epoch = Epoch::new();
+
+        let mut node = ClientNode::new(
+            LocalId::new(0),
+            Ports::new(),
+            Token::new(0),
+            Token::new(1),
+            epoch.reader(),
+            Properties::new(),
+        )?;
+
+        unsafe {
+            volatile!(storage.position, clock.rate).write(ffi::Fraction {
+                num: 1,
+                denom: rate,
+            });
+            volatile!(storage.position, clock.duration).write(quantum);
+        }
+
+        _ = node.replace_activation(region(storage.activation));
+        _ = node.replace_io_position(region(storage.position));
+
+        Ok(Self {
+            node,
+            storage,
+            cycle: 0,
+            nsec: 0,
+            rate,
+            quantum,
+        })
+    }
+
+    /// Add a peer which the simulated node triggers when it finishes
+    /// processing, where `required` is the number of nodes the peer waits for
+    /// including the simulated node.
+    ///
+    /// Returns the index of the peer.
+    pub fn add_peer(&mut self, required: u32) -> Result<usize> {
+        let activation = alloc_zeroed::<ffi::NodeActivation>();
+        self.storage.peers.push(activation);
+
+        unsafe {
+            volatile!(activation, server_version).write(1);
+            volatile!(activation, state[0].required).write(required);
+            volatile!(activation, state[0].pending).write(required);
+        }
+
+        let index = self.storage.peers.len() - 1;
+        let peer_id = u32::try_from(index)?;
+
+        let peer = unsafe { PeerActivation::new(peer_id, EventFd::new(0)?, region(activation)) };
+
+        self.node.peer_activations.push(peer);
+        Ok(index)
+    }
+
+    /// Access the simulated node.
+    #[inline]
+    pub fn node(&self) -> &ClientNode {
+        &self.node
+    }
+
+    /// Access the simulated node mutably.
+    #[inline]
+    pub fn node_mut(&mut self) -> &mut ClientNode {
+        &mut self.node
+    }
+
+    /// The current monotonic time of the simulation in nanoseconds.
+    #[inline]
+    pub fn nsec(&self) -> u64 {
+        self.nsec
+    }
+
+    /// The status of the simulated node.
+    pub fn status(&self) -> Activation {
+        unsafe { atomic!(self.storage.activation, status).load() }
+    }
+
+    /// The status of the given peer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the peer does not exist.
+    pub fn peer_status(&self, peer: usize) -> Activation {
+        unsafe { atomic!(self.storage.peers[peer], status).load() }
+    }
+
+    /// The number of signals the given peer is still waiting for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the peer does not exist.
+    pub fn peer_pending(&self, peer: usize) -> u32 {
+        unsafe { atomic!(self.storage.peers[peer], state[0].pending).load() }
+    }
+
+    /// Replay the given steps in order.
+    pub fn replay(&mut self, steps: impl IntoIterator<Item = Step>) -> Result<()> {
+        for step in steps {
+            self.step(step)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a single step.
+    pub fn step(&mut self, step: Step) -> Result<()> {
+        match step {
+            Step::Cycle => {
+                let position = self.quantum * self.cycle;
+                self.nsec = self.quantum_nsec() * self.cycle;
+                self.cycle += 1;
+
+                unsafe {
+                    volatile!(self.storage.position, clock.position).write(position);
+                    volatile!(self.storage.position, clock.nsec).write(self.nsec);
+                    atomic!(self.storage.activation, status).store(Activation::NOT_TRIGGERED);
+
+                    for &peer in &self.storage.peers {
+                        let required = volatile!(peer, state[0].required).read();
+                        atomic!(peer, state[0].pending).store(required);
+                        atomic!(peer, status).store(Activation::NOT_TRIGGERED);
+                    }
+                }
+            }
+            Step::Trigger => unsafe {
+                volatile!(self.storage.activation, signal_time).write(self.nsec);
+                atomic!(self.storage.activation, status).store(Activation::TRIGGERED);
+            },
+            Step::Process => {
+                self.node.start_process()?;
+                self.node.end_process()?;
+            }
+            Step::SignalPeer(peer) => {
+                let Some(peer) = self.node.peer_activations.get_mut(peer) else {
+                    anyhow::bail!("Missing peer {peer}");
+                };
+
+                unsafe {
+                    peer.trigger(self.nsec)?;
+                }
+            }
+            Step::SetStatus(status) => unsafe {
+                atomic!(self.storage.activation, status).store(status);
+            },
+        }
+
+        Ok(())
+    }
+
+    /// The duration of a single quantum in nanoseconds.
+    fn quantum_nsec(&self) -> u64 {
+        if self.rate == 0 {
+            return 0;
+        }
+
+        self.quantum * 1_000_000_000 / u64::from(self.rate)
+    }
+}
+
+/// Memory which is referenced by the simulated node.
+struct Storage {
+    activation: NonNull<ffi::NodeActivation>,
+    position: NonNull<ffi::IoPosition>,
+    peers: Vec<NonNull<ffi::NodeActivation>>,
+}
+
+impl Drop for Storage {
+    fn drop(&mut self) {
+        // SAFETY: Every pointer was allocated by `alloc_zeroed` and is no
+        // longer referenced.
+        unsafe {
+            drop(Box::from_raw(self.activation.as_ptr()));
+            drop(Box::from_raw(self.position.as_ptr()));
+
+            for peer in self.peers.drain(..) {
+                drop(Box::from_raw(peer.as_ptr()));
+            }
+        }
+    }
+}
+
+/// Allocate a zeroed value which is freed when [`Storage`] is dropped.
+fn alloc_zeroed<T>() -> NonNull<T> {
+    // SAFETY: This is only used for shared memory structures, for which all
+    // zeroes is a valid bit pattern.
+    let value = Box::new(unsafe { mem::zeroed::<T>() });
+    NonNull::from(Box::leak(value))
+}
+
+/// Construct a region referencing the given pointer.
+fn region<T>(ptr: NonNull<T>) -> Region<T> {
+    Region::new(0, mem::size_of::<T>(), ptr)
+}
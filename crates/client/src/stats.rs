@@ -1,8 +1,43 @@
 use core::mem;
 use core::time::Duration;
 
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use pod::{Readable, Writable};
+use protocol::id;
 use protocol::ids::IdSet;
 
+use crate::{Parameters, utils};
+
+/// The object type of a [`StatsSnapshot`].
+///
+/// This is in the range of object types reserved for other vendors.
+pub const STATS_OBJECT_TYPE: u32 = 0x7f00_0001;
+
+/// The parameter which a [`StatsSnapshot`] is published as, see
+/// [`Stats::publish`].
+pub const STATS_PARAM: u32 = 0x7f00_0001;
+
+/// The property keys of a [`StatsSnapshot`].
+mod key {
+    pub(super) const NO_OUTPUT_BUFFER: u32 = 1;
+    pub(super) const NO_INPUT_BUFFER: u32 = 2;
+    pub(super) const NON_READY: u32 = 3;
+    pub(super) const NOT_SELF_TRIGGERED: u32 = 4;
+    pub(super) const SIGNAL_ERROR: u32 = 5;
+    pub(super) const SIGNAL_OK: u32 = 6;
+    pub(super) const READY_OK: u32 = 7;
+    pub(super) const READY_ERROR: u32 = 8;
+    pub(super) const TIMING_SUM: u32 = 9;
+    pub(super) const TIMING_COUNT: u32 = 10;
+    pub(super) const CPU_SUM: u32 = 11;
+    pub(super) const CLIP_LATCHED: u32 = 12;
+    pub(super) const CLIP_NSEC: u32 = 13;
+    pub(super) const OVERLOAD_LATCHED: u32 = 14;
+    pub(super) const OVERLOAD_NSEC: u32 = 15;
+}
+
 /// Efficiently collected processing statistics.
 #[derive(Default)]
 pub struct Stats {
@@ -15,11 +50,197 @@ pub struct Stats {
     pub signal_error_set: IdSet,
     pub signal_ok: usize,
     pub signal_ok_set: IdSet,
+    /// Number of times the server was signalled that the node is ready.
+    pub ready_ok: usize,
+    /// Number of times signalling the server that the node is ready failed.
+    pub ready_error: usize,
     pub timing_sum: u64,
     pub timing_count: usize,
+    /// CPU time spent by the processing thread in nanoseconds.
+    pub cpu_sum: u64,
+    /// CPU time accounted to individually named processing blocks, see
+    /// [`Stats::register_block`].
+    blocks: Vec<(&'static str, BlockStats)>,
+    /// The thread CPU time when the previous block ended, see
+    /// [`Stats::lap`].
+    mark: u64,
+    /// Bitmask of channels with a latched clip indicator, see
+    /// [`ClipDetector`].
+    ///
+    /// [`ClipDetector`]: crate::ClipDetector
+    pub clip_latched: u64,
+    /// The time in nanoseconds of the earliest latched clip indicator, or
+    /// zero if none is latched.
+    pub clip_nsec: u64,
+    /// Bitmask of channels with a latched overload indicator.
+    pub overload_latched: u64,
+    /// The time in nanoseconds of the earliest latched overload indicator,
+    /// or zero if none is latched.
+    pub overload_nsec: u64,
+}
+
+/// A processing block registered with [`Stats::register_block`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockId(usize);
+
+/// CPU time accounted to a single processing block.
+///
+/// See [`Stats::time_block`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BlockStats {
+    /// CPU time spent in the block in nanoseconds.
+    pub cpu_sum: u64,
+    /// The largest CPU time spent in the block in a single call.
+    pub cpu_max: u64,
+    /// The number of times the block has been called.
+    pub count: usize,
+}
+
+/// Statistics of the control connection to the server.
+///
+/// Round trips are measured by [`Stream::ping`], and the round trip time is
+/// the time from sending a ping until the matching done event is received.
+///
+/// [`Stream::ping`]: crate::Stream::ping
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ControlStats {
+    /// The number of pings which have been sent.
+    pub pings: usize,
+    /// The number of pings which have been answered.
+    pub pongs: usize,
+    /// The last measured round trip time in nanoseconds.
+    pub rtt_last: u64,
+    /// The largest measured round trip time in nanoseconds.
+    pub rtt_max: u64,
+    /// The sum of measured round trip times in nanoseconds.
+    pub rtt_sum: u64,
+    /// The number of informational events which have been dropped because
+    /// the event queue was full, see [`Stream::set_event_capacity`].
+    ///
+    /// [`Stream::set_event_capacity`]: crate::Stream::set_event_capacity
+    pub dropped_events: usize,
+}
+
+impl ControlStats {
+    /// The average round trip time, if any pings have been answered.
+    pub fn rtt_avg(&self) -> Option<Duration> {
+        let pongs = u64::try_from(self.pongs).ok().filter(|&n| n > 0)?;
+        Some(Duration::from_nanos(self.rtt_sum / pongs))
+    }
+
+    /// Record a measured round trip time in nanoseconds.
+    pub(crate) fn record(&mut self, rtt: u64) {
+        self.pongs += 1;
+        self.rtt_last = rtt;
+        self.rtt_max = self.rtt_max.max(rtt);
+        self.rtt_sum = self.rtt_sum.saturating_add(rtt);
+    }
+}
+
+/// A snapshot of [`Stats`] which can be serialized as a pod object.
+///
+/// This is published as a node parameter through [`Stats::publish`], so that
+/// external monitoring clients can read it through the regular parameter
+/// mechanism.
+///
+/// # Examples
+///
+/// ```
+/// use client::{Stats, StatsSnapshot};
+///
+/// let mut stats = Stats::default();
+/// stats.no_input_buffer = 2;
+/// stats.timing_count = 10;
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write(stats.snapshot())?;
+///
+/// let snapshot = pod.as_ref().read::<StatsSnapshot>()?;
+/// assert_eq!(snapshot.no_input_buffer, 2);
+/// assert_eq!(snapshot.timing_count, 10);
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Readable, Writable)]
+#[pod(object(type = STATS_OBJECT_TYPE, id = STATS_PARAM))]
+#[non_exhaustive]
+pub struct StatsSnapshot {
+    #[pod(property(key = key::NO_OUTPUT_BUFFER))]
+    pub no_output_buffer: u64,
+    #[pod(property(key = key::NO_INPUT_BUFFER))]
+    pub no_input_buffer: u64,
+    #[pod(property(key = key::NON_READY))]
+    pub non_ready: u64,
+    #[pod(property(key = key::NOT_SELF_TRIGGERED))]
+    pub not_self_triggered: u64,
+    #[pod(property(key = key::SIGNAL_ERROR))]
+    pub signal_error: u64,
+    #[pod(property(key = key::SIGNAL_OK))]
+    pub signal_ok: u64,
+    #[pod(property(key = key::READY_OK))]
+    pub ready_ok: u64,
+    #[pod(property(key = key::READY_ERROR))]
+    pub ready_error: u64,
+    /// The sum of processing cycle timings in nanoseconds.
+    #[pod(property(key = key::TIMING_SUM))]
+    pub timing_sum: u64,
+    /// The number of timed processing cycles.
+    #[pod(property(key = key::TIMING_COUNT))]
+    pub timing_count: u64,
+    /// CPU time spent by the processing thread in nanoseconds.
+    #[pod(property(key = key::CPU_SUM))]
+    pub cpu_sum: u64,
+    /// Bitmask of channels with a latched clip indicator.
+    #[pod(property(key = key::CLIP_LATCHED))]
+    pub clip_latched: u64,
+    /// The time in nanoseconds of the earliest latched clip indicator.
+    #[pod(property(key = key::CLIP_NSEC))]
+    pub clip_nsec: u64,
+    /// Bitmask of channels with a latched overload indicator.
+    #[pod(property(key = key::OVERLOAD_LATCHED))]
+    pub overload_latched: u64,
+    /// The time in nanoseconds of the earliest latched overload indicator.
+    #[pod(property(key = key::OVERLOAD_NSEC))]
+    pub overload_nsec: u64,
 }
 
 impl Stats {
+    /// Take a snapshot of the counters of the statistics.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            no_output_buffer: self.no_output_buffer as u64,
+            no_input_buffer: self.no_input_buffer as u64,
+            non_ready: self.non_ready as u64,
+            not_self_triggered: self.not_self_triggered as u64,
+            signal_error: self.signal_error as u64,
+            signal_ok: self.signal_ok as u64,
+            ready_ok: self.ready_ok as u64,
+            ready_error: self.ready_error as u64,
+            timing_sum: self.timing_sum,
+            timing_count: self.timing_count as u64,
+            cpu_sum: self.cpu_sum,
+            clip_latched: self.clip_latched,
+            clip_nsec: self.clip_nsec,
+            overload_latched: self.overload_latched,
+            overload_nsec: self.overload_nsec,
+        }
+    }
+
+    /// Publish a snapshot of the statistics as the [`STATS_PARAM`] parameter,
+    /// replacing any previously published snapshot.
+    ///
+    /// This is typically done on the parameters of a node, which are sent to
+    /// the server the next time the node is updated.
+    pub fn publish(&self, params: &mut Parameters) -> Result<()> {
+        let param = id::Param::from_id(STATS_PARAM);
+
+        let mut pod = pod::dynamic();
+        let object = pod.as_mut().embed(self.snapshot())?;
+
+        params.remove(param);
+        params.push(object)?;
+        Ok(())
+    }
+
     /// Merge this statistics with another.
     pub fn merge(&mut self, other: &mut Self) {
         self.no_output_buffer += mem::take(&mut other.no_output_buffer);
@@ -31,8 +252,120 @@ impl Stats {
         self.signal_error_set |= mem::take(&mut other.signal_error_set);
         self.signal_ok += mem::take(&mut other.signal_ok);
         self.signal_ok_set |= mem::take(&mut other.signal_ok_set);
+        self.ready_ok += mem::take(&mut other.ready_ok);
+        self.ready_error += mem::take(&mut other.ready_error);
         self.timing_sum += mem::take(&mut other.timing_sum);
         self.timing_count += mem::take(&mut other.timing_count);
+        self.cpu_sum += mem::take(&mut other.cpu_sum);
+        // NB: Latched indicators are state rather than counters, so they are
+        // left in place until they are reset.
+        self.clip_latched |= other.clip_latched;
+        self.clip_nsec = earliest(self.clip_nsec, other.clip_nsec);
+        self.overload_latched |= other.overload_latched;
+        self.overload_nsec = earliest(self.overload_nsec, other.overload_nsec);
+
+        for (label, other) in &mut other.blocks {
+            let other = mem::take(other);
+            let id = self.register_block(label);
+            let block = &mut self.blocks[id.0].1;
+            block.cpu_sum += other.cpu_sum;
+            block.cpu_max = block.cpu_max.max(other.cpu_max);
+            block.count += other.count;
+        }
+    }
+
+    /// Register a processing block which CPU time can be accounted to,
+    /// returning the existing block if `label` is already registered.
+    ///
+    /// This allocates, so blocks should be registered before processing
+    /// starts. Accounting time to a registered block does not allocate.
+    pub fn register_block(&mut self, label: &'static str) -> BlockId {
+        if let Some(index) = self.blocks.iter().position(|&(l, _)| l == label) {
+            return BlockId(index);
+        }
+
+        self.blocks.push((label, BlockStats::default()));
+        BlockId(self.blocks.len() - 1)
+    }
+
+    /// Get the CPU time accounted to the block registered as `label`.
+    pub fn block(&self, label: &str) -> Option<&BlockStats> {
+        let (_, block) = self.blocks.iter().find(|&&(l, _)| l == label)?;
+        Some(block)
+    }
+
+    /// Iterate over registered blocks and the CPU time accounted to them.
+    pub fn blocks(&self) -> impl ExactSizeIterator<Item = (&'static str, &BlockStats)> + '_ {
+        self.blocks.iter().map(|(label, block)| (*label, block))
+    }
+
+    /// Run the given processing block and account the CPU time it consumes
+    /// to `block`.
+    ///
+    /// This is used to find which part of the processing callback is
+    /// consuming the cycle budget. It reads the thread CPU time twice, so
+    /// to time a sequence of blocks prefer [`Stats::lap`] which only reads it
+    /// once per block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::Stats;
+    ///
+    /// let mut stats = Stats::default();
+    /// let block = stats.register_block("sum");
+    ///
+    /// let sum = stats.time_block(block, || (0..1000u32).sum::<u32>());
+    ///
+    /// assert_eq!(sum, 499500);
+    /// assert_eq!(stats.block("sum").map(|b| b.count), Some(1));
+    /// ```
+    pub fn time_block<T>(&mut self, block: BlockId, f: impl FnOnce() -> T) -> T {
+        self.start_blocks();
+        let value = f();
+        self.lap(block);
+        value
+    }
+
+    /// Start timing a sequence of processing blocks, see [`Stats::lap`].
+    #[inline]
+    pub fn start_blocks(&mut self) {
+        self.mark = utils::get_thread_cpu_nsec().unwrap_or_default();
+    }
+
+    /// Account the CPU time consumed since the previous lap, or since
+    /// [`Stats::start_blocks`] was called, to `block`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::Stats;
+    ///
+    /// let mut stats = Stats::default();
+    /// let a = stats.register_block("a");
+    /// let b = stats.register_block("b");
+    ///
+    /// stats.start_blocks();
+    /// let sum = (0..1000u32).sum::<u32>();
+    /// stats.lap(a);
+    /// let product = (1..10u32).product::<u32>();
+    /// stats.lap(b);
+    ///
+    /// assert_eq!((sum, product), (499500, 362880));
+    /// assert_eq!(stats.block("a").map(|b| b.count), Some(1));
+    /// assert_eq!(stats.block("b").map(|b| b.count), Some(1));
+    /// ```
+    pub fn lap(&mut self, block: BlockId) {
+        let now = utils::get_thread_cpu_nsec().unwrap_or_default();
+        let elapsed = now.saturating_sub(mem::replace(&mut self.mark, now));
+
+        let Some((_, block)) = self.blocks.get_mut(block.0) else {
+            return;
+        };
+
+        block.cpu_sum += elapsed;
+        block.cpu_max = block.cpu_max.max(elapsed);
+        block.count += 1;
     }
 
     /// Report statistics to the tracing logger.
@@ -51,6 +384,12 @@ impl Stats {
             self.signal_ok_set.clear();
         }
 
+        if self.ready_error > 0 {
+            tracing::warn!(self.ready_error, self.ready_ok);
+            self.ready_error = 0;
+            self.ready_ok = 0;
+        }
+
         if self.no_input_buffer > 0 {
             tracing::warn!(self.no_input_buffer);
             self.no_input_buffer = 0;
@@ -69,9 +408,54 @@ impl Stats {
         if self.timing_count > 0 {
             let average_timing =
                 Duration::from_nanos((self.timing_sum as f64 / self.timing_count as f64) as u64);
-            tracing::warn!(self.timing_count, self.timing_sum, ?average_timing);
+            let average_cpu =
+                Duration::from_nanos((self.cpu_sum as f64 / self.timing_count as f64) as u64);
+            tracing::warn!(
+                self.timing_count,
+                self.timing_sum,
+                ?average_timing,
+                ?average_cpu
+            );
             self.timing_count = 0;
             self.timing_sum = 0;
+            self.cpu_sum = 0;
         }
+
+        if self.clip_latched != 0 || self.overload_latched != 0 {
+            tracing::warn!(
+                clip_latched = format_args!("{:#b}", self.clip_latched),
+                self.clip_nsec,
+                overload_latched = format_args!("{:#b}", self.overload_latched),
+                self.overload_nsec
+            );
+            self.clip_latched = 0;
+            self.clip_nsec = 0;
+            self.overload_latched = 0;
+            self.overload_nsec = 0;
+        }
+
+        for (label, block) in &mut self.blocks {
+            let label = *label;
+
+            if block.count == 0 {
+                continue;
+            }
+
+            let average_cpu =
+                Duration::from_nanos((block.cpu_sum as f64 / block.count as f64) as u64);
+            let max_cpu = Duration::from_nanos(block.cpu_max);
+            tracing::warn!(label, block.count, ?average_cpu, ?max_cpu);
+            *block = BlockStats::default();
+        }
+    }
+}
+
+/// The earliest of two timestamps, where zero means unset.
+#[inline]
+pub(crate) fn earliest(a: u64, b: u64) -> u64 {
+    match (a, b) {
+        (0, b) => b,
+        (a, 0) => a,
+        (a, b) => a.min(b),
     }
 }
@@ -5,21 +5,24 @@ use core::mem::MaybeUninit;
 use core::slice;
 
 use core::time::Duration;
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::ffi::CString;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
 use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixListener;
+use std::path::Path;
 use std::time::SystemTime;
 
 use alloc::borrow::ToOwned;
-use alloc::string::String;
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
 use anyhow::{Context, Result, anyhow, bail, ensure};
 use pod::AsSlice;
-use pod::{ChoiceType, DynamicBuf, Fd, Object, Pod, Slice, Struct, Type};
+use pod::{ChoiceType, DynamicBuf, Fd, Id, Object, Pod, Slice, Struct, Type};
 use protocol::EventFd;
 use protocol::Poll;
 use protocol::Prop;
@@ -29,30 +32,65 @@ use protocol::ffi;
 use protocol::flags;
 use protocol::id;
 use protocol::ids::IdSet;
-use protocol::op::{self, ClientEvent, ClientNodeEvent, CoreEvent, RegistryEvent};
+use protocol::object;
+use protocol::op::{
+    self, ClientEvent, ClientNodeEvent, CoreEvent, DeviceEvent, MetadataEvent, NodeEvent,
+    RegistryEvent,
+};
+use protocol::param;
 use protocol::poll::{ChangeInterest, Interest, PollEvent, Token};
+use protocol::symbol::Interner;
 use protocol::types::Header;
-use protocol::{Connection, Properties, prop};
+use protocol::{Connection, ConnectionOptions, FdOrigin, ManagedFd, Properties, prop};
 use slab::Slab;
 use tracing::Level;
 
 use crate::activation::PeerActivation;
 use crate::buffer::{self, Buffer};
+use crate::coordination::{self, Coordination};
+use crate::event_queue::EventQueue;
 use crate::events::{
-    ObjectKind, RemoveNodeParamEvent, RemovePortParamEvent, SetNodeParamEvent, SetPortParamEvent,
-    StreamEvent,
+    ClipEvent, CoordinationEvent, FormatChangedEvent, FormatRejectedEvent, MessageEvent,
+    MetadataPropertyEvent, ObjectKind, OverloadEvent, PresetRejectedEvent, ProxyParamEvent,
+    QuantumChangedEvent, RateChangedEvent, RemoveNodeParamEvent, RemovePortParamEvent,
+    RouteVolumeEvent, SetNodeParamEvent, SetPortParamEvent, StreamEvent, UnknownMessageEvent,
+    UseBuffersEvent,
 };
 use crate::ports::PortMix;
 use crate::ports::PortParam;
 use crate::ptr::{atomic, volatile};
+use crate::security_context;
+use crate::subscription::{self, EventClasses, Subscribers, Subscription};
 use crate::utils;
+use crate::warnings::Warnings;
 use crate::{
-    Buffers, Client, ClientNode, ClientNodeId, ClientNodes, GlobalId, LocalId, Memory, MixId,
-    PortId, Ports, Region,
+    Access, BluetoothCodecs, Buffers, Capabilities, Client, ClientNode, ClientNodeId, ClientNodes,
+    ControlStats, DeviceProxy, FormatSpec, GlobalId, GlobalObject, GlobalRef, IdleTransition,
+    LocalId, Memory, Metadata, MixId, NodeRef, OverloadAction, OverloadDecision, ParamRefresh,
+    PortId, Ports, PropertyLayer, Proxies, Proxy, ProxyId, ProxyKind, Region, Registry,
+    RegistryFilter, ResolvedProperties, RouteId, RouteVolume, SecurityContext, Session,
+    SessionLink,
 };
 
+#[cfg(test)]
+mod tests;
+
 const CREATE_CLIENT_NODE: i32 = 0x2000;
 const GET_REGISTRY_SYNC: i32 = 0x1000;
+const SHUTDOWN_SYNC: i32 = 0x3000;
+const PING_SYNC: i32 = 0x4000;
+const REFRESH_SYNC: i32 = 0x5000;
+const USER_SYNC: i32 = 0x6000;
+
+/// Log a warning which is rate limited and deduplicated by its site, node and
+/// kind, see [`Warnings`].
+macro_rules! warn_limited {
+    ($warnings:expr, $site:literal, $node:expr, $kind:expr, $($tt:tt)*) => {
+        if $warnings.check($site, $node, $kind) {
+            tracing::warn!($($tt)*);
+        }
+    };
+}
 
 macro_rules! tracing_error {
     ($error:expr, $($tt:tt)*) => {{
@@ -70,13 +108,22 @@ pub struct Stream {
     c: Client,
     connection_added: bool,
     connection_token: Token,
+    connection_state: ConnectionState,
+    reconnect: Option<ConnectionOptions>,
+    edge_triggered: bool,
     core: CoreState,
     client: ClientState,
-    registries: Slab<RegistryEntry>,
+    registry_id: Option<LocalId>,
+    security_context_id: Option<LocalId>,
+    registries: Slab<GlobalObject>,
+    symbols: Interner,
     id_to_registry: BTreeMap<GlobalId, usize>,
+    registry_filters: Vec<RegistryFilter>,
+    filtered: BTreeSet<GlobalId>,
     factories: BTreeMap<String, usize>,
     globals: GlobalMap,
     client_nodes: ClientNodes,
+    proxies: Proxies,
     local_id_to_kind: BTreeMap<LocalId, Kind>,
     has_header: bool,
     header: Header,
@@ -86,7 +133,30 @@ pub struct Stream {
     read_to_client: HashMap<Token, ClientNodeId>,
     write_to_client: HashMap<Token, ClientNodeId>,
     fds: VecDeque<Option<OwnedFd>>,
+    message_fds: Vec<Option<ManagedFd>>,
     ops: VecDeque<Op>,
+    events: EventQueue,
+    subscribers: Subscribers,
+    warnings: Warnings,
+    capture_unknown: bool,
+    capture_messages: bool,
+    pending_pings: BTreeMap<u32, u64>,
+    refresh: ParamRefresh,
+    /// Outstanding refreshes by the sequence of the sync which follows them.
+    pending_refreshes: BTreeMap<u32, i32>,
+    /// Identifiers of destroyed proxies which are reused once the server has
+    /// acknowledged their removal.
+    removed_ids: BTreeSet<LocalId>,
+    node_defaults: Properties,
+    port_defaults: Properties,
+    sidechains: Vec<Sidechain>,
+    coordination: Option<Coordination>,
+    /// Metadata objects subscribed to by name, and the proxy they are bound
+    /// through once announced.
+    metadata: BTreeMap<String, Option<ProxyId>>,
+    session_links: Vec<PendingSessionLink>,
+    format_spec: Option<FormatSpec>,
+    control_stats: ControlStats,
     memory: Memory,
     add_interest: VecDeque<(RawFd, Token, Interest)>,
     modify_interest: VecDeque<(RawFd, Token, Interest)>,
@@ -113,13 +183,22 @@ impl Stream {
             c: Client::new(connection),
             connection_added: false,
             connection_token,
+            connection_state: ConnectionState::Connected,
+            reconnect: None,
+            edge_triggered: false,
             core: CoreState::default(),
             client,
+            registry_id: None,
+            security_context_id: None,
             registries: Slab::new(),
+            symbols: Interner::new(),
             id_to_registry: BTreeMap::new(),
+            registry_filters: Vec::new(),
+            filtered: BTreeSet::new(),
             factories: BTreeMap::new(),
             globals: GlobalMap::new(),
             client_nodes: ClientNodes::new(),
+            proxies: Proxies::new(),
             local_id_to_kind: BTreeMap::new(),
             has_header: false,
             header: Header::default(),
@@ -129,7 +208,25 @@ impl Stream {
             read_to_client: HashMap::new(),
             write_to_client: HashMap::new(),
             fds: VecDeque::with_capacity(16),
+            message_fds: Vec::new(),
             ops: VecDeque::from([Op::CoreHello]),
+            events: EventQueue::new(),
+            subscribers: Subscribers::new(),
+            warnings: Warnings::new(),
+            capture_unknown: false,
+            capture_messages: false,
+            pending_pings: BTreeMap::new(),
+            refresh: ParamRefresh::new(),
+            pending_refreshes: BTreeMap::new(),
+            removed_ids: BTreeSet::new(),
+            node_defaults: Properties::new(),
+            port_defaults: Properties::new(),
+            sidechains: Vec::new(),
+            coordination: None,
+            metadata: BTreeMap::new(),
+            session_links: Vec::new(),
+            format_spec: None,
+            control_stats: ControlStats::default(),
             memory: Memory::new(),
             add_interest: VecDeque::new(),
             modify_interest: VecDeque::new(),
@@ -156,6 +253,323 @@ impl Stream {
         self.client_nodes.iter_mut()
     }
 
+    /// Get a bound proxy.
+    pub fn proxy(&self, proxy_id: ProxyId) -> Result<&Proxy> {
+        self.proxies.get(proxy_id)
+    }
+
+    /// Access all bound proxies.
+    pub fn proxies(&self) -> &Proxies {
+        &self.proxies
+    }
+
+    /// Register file descriptors as edge-triggered.
+    ///
+    /// When enabled, every interest returned by [`Stream::add_interest`] and
+    /// [`Stream::modify_interest`] includes [`Interest::EDGE`], and
+    /// [`Stream::drive`] fully drains the connection every time it is
+    /// reported as ready. This reduces the number of wakeups of the poll loop.
+    ///
+    /// This must be configured before any interest has been added.
+    pub fn set_edge_triggered(&mut self, edge_triggered: bool) {
+        self.edge_triggered = edge_triggered;
+    }
+
+    /// Reconnect to the server with the given options if the connection is
+    /// lost, such as when the server restarts, or `None` to disable it.
+    ///
+    /// Once the connection is lost, all state tied to it like memory regions,
+    /// bound proxies and the registry is torn down and
+    /// [`StreamEvent::Disconnected`] is emitted. Once reconnected, every client
+    /// node is created again with its ports and their parameters, after which
+    /// [`StreamEvent::Reconnected`] is emitted.
+    ///
+    /// If the server is not accepting connections when the connection is
+    /// lost, reconnecting has to be retried through [`Stream::reconnect`].
+    ///
+    /// Reconnecting is disabled by default, in which case the connection is
+    /// reported as hung up to the caller of [`Stream::drive`].
+    pub fn set_reconnect(&mut self, options: Option<ConnectionOptions>) {
+        self.reconnect = options;
+    }
+
+    /// Test if the stream is connected to the server.
+    ///
+    /// This is `false` from when the connection is lost until the stream has
+    /// reconnected, see [`Stream::set_reconnect`].
+    #[inline]
+    pub fn is_connected(&self) -> bool {
+        self.connection_state == ConnectionState::Connected
+    }
+
+    /// Try to reconnect to the server after the connection has been lost.
+    ///
+    /// Returns `false` if the server is not accepting connections yet, in
+    /// which case this should be retried later, like from a timer. Returns
+    /// `true` if a connection has been established or if the stream is not
+    /// disconnected.
+    ///
+    /// See [`Stream::set_reconnect`].
+    pub fn reconnect(&mut self) -> Result<bool> {
+        match self.connection_state {
+            ConnectionState::Disconnected => {}
+            ConnectionState::Lost => return Ok(false),
+            ConnectionState::Connected | ConnectionState::Resuming => return Ok(true),
+        }
+
+        let options = self.reconnect.unwrap_or_default();
+
+        let connection = match Connection::open_with(&options) {
+            Ok(connection) => connection,
+            Err(error) => {
+                tracing::debug!(%error, "Server is not accepting connections");
+                return Ok(false);
+            }
+        };
+
+        tracing::info!("Reconnected to server");
+        self.c = Client::new(connection);
+        self.connection_added = false;
+        self.connection_state = ConnectionState::Resuming;
+        self.ops.push_back(Op::CoreHello);
+        Ok(true)
+    }
+
+    /// Tear down all state which is tied to a lost connection.
+    fn disconnect(&mut self, poll: &mut Poll, recv: &mut RecvBuf) {
+        tracing::warn!("Connection to server lost");
+
+        if self.connection_added
+            && let Err(error) =
+                poll.delete(self.c.as_raw_fd(), self.connection_token, self.c.interest())
+        {
+            tracing::warn!(%error, "Failed to remove connection from poll");
+        }
+
+        self.connection_added = false;
+        self.connection_state = ConnectionState::Disconnected;
+
+        // NB: Revoking all memory frees every region held by client nodes.
+        self.memory.revoke_all();
+        self.free_revoked_regions();
+
+        for node in self.client_nodes.iter_mut() {
+            node.reset_connection();
+        }
+
+        for sidechain in &mut self.sidechains {
+            sidechain.port_global = None;
+            sidechain.link = None;
+        }
+
+        for proxy_id in self.metadata.values_mut() {
+            *proxy_id = None;
+        }
+
+        if let Some(c) = &mut self.coordination {
+            c.reset(GlobalId::INVALID);
+        }
+
+        let mut ids = IdSet::new();
+        ids.set(consts::CORE_ID);
+        ids.set(consts::CLIENT_ID);
+
+        self.ids = ids;
+        self.client.id = GlobalId::INVALID;
+        self.core = CoreState::default();
+        self.registry_id = None;
+        self.security_context_id = None;
+        self.id_to_registry.clear();
+        self.filtered.clear();
+        self.factories.clear();
+        self.globals = GlobalMap::new();
+        self.proxies = Proxies::new();
+        self.local_id_to_kind.clear();
+        self.has_header = false;
+        self.process_set.clear();
+        self.read_to_client.clear();
+        self.write_to_client.clear();
+        self.fds.clear();
+        self.message_fds.clear();
+        self.ops.clear();
+        self.pending_pings.clear();
+        self.refresh.clear();
+        self.pending_refreshes.clear();
+        self.removed_ids.clear();
+        // NB: Queued interests refer to file descriptors of client nodes
+        // which have been closed, and whose numbers might be reused.
+        self.add_interest.clear();
+        self.modify_interest.clear();
+        recv.clear();
+
+        // NB: Removals are implied by the disconnect, so they are not emitted
+        // one by one.
+        self.registries.clear();
+        self.symbols.collect();
+
+        self.events.push(StreamEvent::Disconnected);
+    }
+
+    /// Set the interval at which repeated protocol warnings are logged.
+    ///
+    /// Warnings about protocol anomalies, like unsupported events, are
+    /// deduplicated so that a misbehaving server does not flood the log. The
+    /// number of suppressed warnings is logged by [`Stream::flush_warnings`].
+    pub fn set_warning_interval(&mut self, interval: Duration) {
+        self.warnings.set_interval(interval);
+    }
+
+    /// Set the formats accepted by ports of nodes created through the stream,
+    /// or `None` to leave formats up to the caller.
+    ///
+    /// See [`StreamBuilder`].
+    ///
+    /// [`StreamBuilder`]: crate::StreamBuilder
+    pub fn set_format_spec(&mut self, spec: Option<FormatSpec>) {
+        self.format_spec = spec;
+    }
+
+    /// Get the formats accepted by ports, if set.
+    #[inline]
+    pub fn format_spec(&self) -> Option<&FormatSpec> {
+        self.format_spec.as_ref()
+    }
+
+    /// Set whether messages which are not understood by the stream, like
+    /// unsupported events or events for unknown receivers, should be captured
+    /// and emitted as [`StreamEvent::UnknownMessage`].
+    ///
+    /// This is useful to observe and report compatibility issues with newer
+    /// servers. It is disabled by default.
+    pub fn set_capture_unknown(&mut self, capture: bool) {
+        self.capture_unknown = capture;
+    }
+
+    /// Set whether every message received from the server should be captured
+    /// and emitted as [`StreamEvent::Message`], which is useful to record
+    /// traces of the protocol.
+    ///
+    /// It is disabled by default.
+    pub fn set_capture_messages(&mut self, capture: bool) {
+        self.capture_messages = capture;
+    }
+
+    /// Set a property of the client, which is sent to the server the next
+    /// time the stream is run.
+    ///
+    /// This can be used to update user-facing metadata like
+    /// [`prop::APPLICATION_NAME`] at runtime.
+    pub fn set_client_property(&mut self, key: impl AsRef<Prop>, value: impl AsRef<str>) {
+        if self.client.props.insert(key, value) {
+            self.ops.push_back(Op::ClientUpdateProperties);
+        }
+    }
+
+    /// Access the default properties of every node hosted by the client.
+    ///
+    /// Properties of a node override these defaults, and the result is
+    /// resolved each time the node is updated. Modifying the defaults updates
+    /// every node.
+    pub fn node_defaults_mut(&mut self) -> &mut Properties {
+        &mut self.node_defaults
+    }
+
+    /// Access the default properties of every port hosted by the client.
+    ///
+    /// These are overridden by [`ClientNode::port_props`] of the node the port
+    /// belongs to, which in turn are overridden by the properties of the port.
+    pub fn port_defaults_mut(&mut self) -> &mut Properties {
+        &mut self.port_defaults
+    }
+
+    /// Resolve the properties of a node as they are sent to the server.
+    pub fn resolve_node_props(&self, node_id: ClientNodeId) -> Result<ResolvedProperties> {
+        let node = self.client_nodes.get(node_id)?;
+
+        Ok(ResolvedProperties::resolve([
+            (PropertyLayer::Client, &self.node_defaults),
+            (PropertyLayer::Node, &node.props),
+        ]))
+    }
+
+    /// Resolve the properties of a port as they are sent to the server.
+    pub fn resolve_port_props(
+        &self,
+        node_id: ClientNodeId,
+        direction: Direction,
+        port_id: PortId,
+    ) -> Result<ResolvedProperties> {
+        let node = self.client_nodes.get(node_id)?;
+        let port = node.ports.get(direction, port_id)?;
+
+        Ok(ResolvedProperties::resolve([
+            (PropertyLayer::Client, &self.port_defaults),
+            (PropertyLayer::Node, &node.port_props),
+            (PropertyLayer::Port, &port.props),
+        ]))
+    }
+
+    /// Log the number of protocol warnings which have been suppressed since
+    /// the last flush.
+    ///
+    /// This is intended to be called periodically, such as from a timer.
+    pub fn flush_warnings(&mut self) {
+        self.warnings.flush();
+    }
+
+    /// Set the maximum number of events which are queued between calls to
+    /// [`Stream::run`].
+    ///
+    /// Once the queue is full the oldest informational event is dropped, like
+    /// a received proxy parameter or a clip indicator, which is counted in
+    /// [`ControlStats::dropped_events`]. Lifecycle and registry events, like
+    /// [`StreamEvent::GlobalAdded`] or [`StreamEvent::Disconnected`], are
+    /// never dropped.
+    pub fn set_event_capacity(&mut self, capacity: usize) {
+        self.events.set_capacity(capacity);
+        self.control_stats.dropped_events += self.events.take_dropped();
+    }
+
+    /// Subscribe to the given classes of events.
+    ///
+    /// Every event returned by [`Stream::run`] is also sent to each
+    /// subscription interested in it, which allows separate components of an
+    /// application to consume the events they care about independently.
+    ///
+    /// Subscriptions are bounded, so events are dropped for a subscriber which
+    /// falls behind, see [`Subscription::dropped`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use client::{EventClasses, Stream};
+    ///
+    /// # fn f(stream: &mut Stream) {
+    /// let ui = stream.subscribe(EventClasses::OBJECTS | EventClasses::STATE);
+    ///
+    /// std::thread::spawn(move || {
+    ///     while let Some(event) = ui.recv() {
+    ///         println!("{event:?}");
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn subscribe(&mut self, classes: EventClasses) -> Subscription {
+        self.subscribe_with_capacity(classes, subscription::DEFAULT_CAPACITY)
+    }
+
+    /// Subscribe to the given classes of events, where up to `capacity`
+    /// events can be queued before further events are dropped.
+    ///
+    /// See [`Stream::subscribe`].
+    pub fn subscribe_with_capacity(
+        &mut self,
+        classes: EventClasses,
+        capacity: usize,
+    ) -> Subscription {
+        self.subscribers.subscribe(classes, capacity)
+    }
+
     /// Allocate a unique token.
     #[inline]
     pub fn token(&mut self) -> Result<Token> {
@@ -166,29 +580,109 @@ impl Stream {
 
     #[inline]
     pub fn add_interest(&mut self) -> Option<(RawFd, Token, Interest)> {
-        if !self.connection_added {
+        if !self.connection_added && self.has_connection() {
             self.connection_added = true;
-            return Some((self.c.as_raw_fd(), self.connection_token, self.c.interest()));
+            let interest = self.with_edge(self.c.interest());
+            return Some((self.c.as_raw_fd(), self.connection_token, interest));
         }
 
-        self.add_interest.pop_front()
+        let (fd, token, interest) = self.add_interest.pop_front()?;
+        Some((fd, token, self.with_edge(interest)))
     }
 
     #[inline]
     pub fn modify_interest(&mut self) -> Option<(RawFd, Token, Interest)> {
-        if let ChangeInterest::Changed(interest) = self.c.modify_interest() {
+        if self.has_connection()
+            && let ChangeInterest::Changed(interest) = self.c.modify_interest()
+        {
+            let interest = self.with_edge(interest);
             return Some((self.c.as_raw_fd(), self.connection_token, interest));
         }
 
         if let Some((fd, token, interest)) = self.modify_interest.pop_front() {
-            return Some((fd, token, interest));
+            return Some((fd, token, self.with_edge(interest)));
         }
 
         None
     }
 
+    /// Test if there is a live connection to the server, which might still be
+    /// resuming.
+    #[inline]
+    fn has_connection(&self) -> bool {
+        matches!(
+            self.connection_state,
+            ConnectionState::Connected | ConnectionState::Resuming
+        )
+    }
+
+    #[inline]
+    fn with_edge(&self, interest: Interest) -> Interest {
+        if self.edge_triggered {
+            interest | Interest::EDGE
+        } else {
+            interest
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     fn process_operations(&mut self) -> Result<Option<StreamEvent>> {
+        let node_defaults = self.node_defaults.take_modified();
+        let port_defaults = self.port_defaults.take_modified();
+
+        if self.connection_state == ConnectionState::Connected {
+            if self.refresh.is_pending() {
+                self.issue_refreshes()?;
+            }
+
+            self.check_idle()?;
+        }
+
+        for (node_id, node) in self.client_nodes.iter_mut_with_id() {
+            while let Some((decision, dropped)) = node.take_overload_decision() {
+                self.ops.push_back(Op::NodeOverload {
+                    node_id,
+                    decision,
+                    dropped,
+                });
+            }
+
+            while let Some(latch) = node.take_clip_latch() {
+                self.ops.push_back(Op::Clip(ClipEvent { node_id, latch }));
+            }
+
+            if let Some((previous, rate)) = node.take_rate_change() {
+                self.ops.push_back(Op::RateChanged(RateChangedEvent {
+                    node_id,
+                    previous,
+                    rate,
+                }));
+            }
+
+            if let Some((previous, quantum)) = node.take_quantum_change() {
+                self.ops.push_back(Op::QuantumChanged(QuantumChangedEvent {
+                    node_id,
+                    previous,
+                    quantum,
+                }));
+            }
+
+            if node_defaults {
+                node.props.mark_modified();
+            }
+
+            if node.port_props.take_modified() || port_defaults {
+                node.ports.mark_props_modified();
+            }
+
+            if node.take_committed() || (!node.is_batching() && node.has_modified_props()) {
+                self.ops.push_back(Op::NodeUpdate {
+                    node_id,
+                    what: None,
+                });
+            }
+        }
+
         while let Some(op) = self.ops.pop_front() {
             tracing::trace!(?op);
 
@@ -197,16 +691,37 @@ impl Stream {
                     self.c.core_hello()?;
                     self.c.client_update_properties(&self.client.props)?;
                 }
+                Op::ClientUpdateProperties => {
+                    self.c.client_update_properties(&self.client.props)?;
+                }
                 Op::GetRegistry => {
                     let local_id =
                         LocalId::new(self.ids.alloc().context("ran out of identifiers")?);
                     self.c.core_get_registry(local_id)?;
+                    self.registry_id = Some(local_id);
                     self.local_id_to_kind.insert(local_id, Kind::Registry);
                     self.c.core_sync(GET_REGISTRY_SYNC)?;
                 }
                 Op::CoreStarted => {
                     return Ok(Some(StreamEvent::Started));
                 }
+                Op::Reconnected => {
+                    self.connection_state = ConnectionState::Connected;
+                    self.resume_nodes()?;
+
+                    if let Some(c) = &mut self.coordination {
+                        c.reset(self.client.id);
+                    }
+
+                    self.coordination_bind()?;
+                    return Ok(Some(StreamEvent::Reconnected));
+                }
+                Op::Shutdown => {
+                    return Ok(Some(StreamEvent::Shutdown));
+                }
+                Op::Synced(seq) => {
+                    return Ok(Some(StreamEvent::Synced(seq)));
+                }
                 Op::Pong { id, seq } => {
                     self.c.core_pong(id, seq)?;
                 }
@@ -216,74 +731,72 @@ impl Stream {
                 Op::NodeUpdate { node_id, what } => {
                     let node = self.client_nodes.get_mut(node_id)?;
 
+                    // NB: Changes are held back until the batch is committed.
+                    if node.is_batching() {
+                        if let Some(what) = what {
+                            return Ok(Some(what.into_event(node_id)));
+                        }
+
+                        continue;
+                    }
+
                     if node.take_modified() {
+                        let mut props = resolve_for_update(
+                            node.props.take_modified(),
+                            [
+                                (PropertyLayer::Client, &self.node_defaults),
+                                (PropertyLayer::Node, &node.props),
+                            ],
+                        );
+
+                        // NB: Removed properties which are still provided by
+                        // another layer are updated instead of cleared.
+                        let mut removed = mem::take(&mut node.removed_props);
+                        removed.retain(|key| props.get(key.as_str()).is_none());
+
                         self.c.client_node_update(
                             node.id,
                             node.max_input_ports,
                             node.max_output_ports,
-                            &mut node.props,
+                            &mut props,
+                            &removed,
                             &node.params,
                         )?;
                     }
 
-                    for port in node.ports.inputs_mut() {
-                        if !port.is_modified() {
-                            continue;
+                    for port in node.ports.iter_mut() {
+                        if let Some(spec) = &self.format_spec
+                            && port.params.get(id::Param::ENUM_FORMAT).is_empty()
+                        {
+                            let mut pod = pod::array();
+                            port.params.push(pod.as_mut().embed(spec.enum_format())?)?;
+                            port.params.set_writable(id::Param::FORMAT);
                         }
 
-                        self.c.client_node_port_update(
-                            node.id,
-                            Direction::INPUT,
-                            port.id,
-                            &mut port.props,
-                            &mut port.params,
-                        )?;
-                    }
-
-                    for port in node.ports.outputs_mut() {
                         if !port.is_modified() {
                             continue;
                         }
 
+                        let mut props = resolve_for_update(
+                            port.props.take_modified(),
+                            [
+                                (PropertyLayer::Client, &self.port_defaults),
+                                (PropertyLayer::Node, &node.port_props),
+                                (PropertyLayer::Port, &port.props),
+                            ],
+                        );
+
                         self.c.client_node_port_update(
                             node.id,
-                            Direction::OUTPUT,
+                            port.direction,
                             port.id,
-                            &mut port.props,
+                            &mut props,
                             &mut port.params,
                         )?;
                     }
 
                     if let Some(what) = what {
-                        let ev = match what {
-                            NodeUpdateWhat::SetNodeParam(param) => {
-                                StreamEvent::SetNodeParam(SetNodeParamEvent { node_id, param })
-                            }
-                            NodeUpdateWhat::RemoveNodeParam(param) => {
-                                StreamEvent::RemoveNodeParam(RemoveNodeParamEvent {
-                                    node_id,
-                                    param,
-                                })
-                            }
-                            NodeUpdateWhat::SetPortParam(direction, port_id, param) => {
-                                StreamEvent::SetPortParam(SetPortParamEvent {
-                                    node_id,
-                                    direction,
-                                    port_id,
-                                    param,
-                                })
-                            }
-                            NodeUpdateWhat::RemovePortParam(direction, port_id, param) => {
-                                StreamEvent::RemovePortParam(RemovePortParamEvent {
-                                    node_id,
-                                    direction,
-                                    port_id,
-                                    param,
-                                })
-                            }
-                        };
-
-                        return Ok(Some(ev));
+                        return Ok(Some(what.into_event(node_id)));
                     }
                 }
                 Op::NodeStart { node_id } => {
@@ -319,6 +832,100 @@ impl Stream {
                 Op::NodeReadInterest { node_id } => {
                     self.node_read_interest(node_id)?;
                 }
+                Op::ProxyParam { proxy_id, param } => {
+                    return Ok(Some(StreamEvent::ProxyParam(ProxyParamEvent {
+                        proxy_id,
+                        param,
+                    })));
+                }
+                Op::RouteVolume {
+                    proxy_id,
+                    route,
+                    applied,
+                } => {
+                    return Ok(Some(StreamEvent::RouteVolume(RouteVolumeEvent {
+                        proxy_id,
+                        route,
+                        applied,
+                    })));
+                }
+                Op::NodeSuspended { node_id } => {
+                    return Ok(Some(StreamEvent::NodeSuspended(node_id)));
+                }
+                Op::NodeResumed { node_id } => {
+                    return Ok(Some(StreamEvent::NodeResumed(node_id)));
+                }
+                Op::UseBuffers(event) => {
+                    return Ok(Some(StreamEvent::UseBuffers(event)));
+                }
+                Op::RateChanged(event) => {
+                    return Ok(Some(StreamEvent::RateChanged(event)));
+                }
+                Op::QuantumChanged(event) => {
+                    return Ok(Some(StreamEvent::QuantumChanged(event)));
+                }
+                Op::Coordination(event) => {
+                    return Ok(Some(StreamEvent::Coordination(event)));
+                }
+                Op::FormatChanged(event) => {
+                    return Ok(Some(StreamEvent::FormatChanged(event)));
+                }
+                Op::FormatRejected(event) => {
+                    return Ok(Some(StreamEvent::FormatRejected(event)));
+                }
+                Op::PresetRejected(event) => {
+                    return Ok(Some(StreamEvent::PresetRejected(event)));
+                }
+                Op::Clip(event) => {
+                    tracing::debug!(?event.node_id, ?event.latch, "Clip indicator latched");
+                    return Ok(Some(StreamEvent::Clip(event)));
+                }
+                Op::MetadataProperty(event) => {
+                    return Ok(Some(StreamEvent::MetadataProperty(event)));
+                }
+                Op::GlobalAdded(object) => {
+                    return Ok(Some(StreamEvent::GlobalAdded(object)));
+                }
+                Op::GlobalRemoved(object) => {
+                    return Ok(Some(StreamEvent::GlobalRemoved(object)));
+                }
+                Op::UnknownMessage(event) => {
+                    return Ok(Some(StreamEvent::UnknownMessage(event)));
+                }
+                Op::Message(event) => {
+                    return Ok(Some(StreamEvent::Message(event)));
+                }
+                Op::NodeOverload {
+                    node_id,
+                    decision,
+                    dropped,
+                } => {
+                    let node = self.client_nodes.get_mut(node_id)?;
+
+                    let modified = match decision {
+                        OverloadDecision::Engaged(OverloadAction::RequestQuantum(quantum)) => node
+                            .props
+                            .insert(prop::NODE_FORCE_QUANTUM, quantum.to_string()),
+                        OverloadDecision::Released(OverloadAction::RequestQuantum(..)) => {
+                            node.remove_prop(prop::NODE_FORCE_QUANTUM)
+                        }
+                        _ => false,
+                    };
+
+                    if modified {
+                        self.ops.push_back(Op::NodeUpdate {
+                            node_id,
+                            what: None,
+                        });
+                    }
+
+                    tracing::debug!(?node_id, ?decision, dropped, "Overload policy");
+                    return Ok(Some(StreamEvent::Overload(OverloadEvent {
+                        node_id,
+                        decision,
+                        dropped,
+                    })));
+                }
             }
         }
 
@@ -348,28 +955,43 @@ impl Stream {
 
         let st = pod.read_struct()?;
 
+        let n_fds = self.header.n_fds() as usize;
+
+        ensure!(
+            n_fds <= self.fds.len(),
+            "Header specifies more file descriptors ({n_fds}) than is stored ({})",
+            self.fds.len()
+        );
+
+        for (index, fd) in (0..).zip(self.fds.drain(..n_fds)) {
+            let fd = match fd {
+                Some(fd) => Some(ManagedFd::new(fd, FdOrigin::new(self.header.seq(), index))?),
+                None => None,
+            };
+
+            self.message_fds.push(fd);
+        }
+
+        if self.capture_messages {
+            self.ops.push_back(Op::Message(MessageEvent {
+                interface: self.receiver_interface(),
+                id: self.header.id(),
+                op: self.header.op(),
+                fds: self.header.n_fds(),
+                payload: st.as_buf().as_bytes().to_vec(),
+            }));
+        }
+
         let result = match self.header.id() {
             consts::CORE_ID => self.core(st),
             consts::CLIENT_ID => self.client(st),
             _ => self.dynamic(st),
         };
 
-        if self.header.n_fds() > 0 {
-            let n_fds = self.header.n_fds() as usize;
-
-            ensure!(
-                n_fds <= self.fds.len(),
-                "Header specifies more file descriptors ({n_fds}) than is stored ({})",
-                self.fds.len()
-            );
-
-            if n_fds > 0 {
-                for fd in self.fds.drain(..n_fds).flatten() {
-                    tracing::warn!("Closing unused file descriptor: {fd:?}");
-                }
-
-                tracing::trace!(n_fds, fds_after = ?self.fds, "Freed file descriptors");
-            }
+        // NB: Dropping file descriptors which were not used warns about them.
+        if !self.message_fds.is_empty() {
+            self.message_fds.clear();
+            tracing::trace!(fds_after = ?self.fds, "Freed file descriptors");
         }
 
         self.has_header = false;
@@ -380,9 +1002,21 @@ impl Stream {
     /// Process client.
     #[tracing::instrument(skip(self, poll, recv))]
     pub fn run(&mut self, poll: &mut Poll, recv: &mut RecvBuf) -> Result<Option<StreamEvent>> {
+        if self.connection_state == ConnectionState::Lost {
+            self.disconnect(poll, recv);
+            self.reconnect()?;
+        }
+
+        // NB: Memory is only unmapped once every node which might still be
+        // reading from it has passed through a quiescent point.
+        if self.memory.has_pending() {
+            let oldest = self.client_nodes.iter().map(|n| n.observed_epoch()).min();
+            self.memory.reclaim(oldest.unwrap_or(u64::MAX));
+        }
+
         loop {
-            if let Some(ev) = self.process_operations()? {
-                return Ok(Some(ev));
+            while let Some(ev) = self.process_operations()? {
+                self.events.push(ev);
             }
 
             if !self.process_messages(recv)? {
@@ -390,8 +1024,23 @@ impl Stream {
             }
         }
 
-        if let Some(raw_id) = self.process_set.take_next() {
-            return Ok(Some(StreamEvent::Process(ClientNodeId::new(raw_id))));
+        self.control_stats.dropped_events += self.events.take_dropped();
+
+        // NB: Processing is time-sensitive, so it takes priority over any
+        // informational events which are queued.
+        while let Some(index) = self.process_set.take_next() {
+            let Some(node_id) = self.client_nodes.id_at(index) else {
+                continue;
+            };
+
+            let ev = StreamEvent::Process(node_id);
+            self.subscribers.publish(&ev);
+            return Ok(Some(ev));
+        }
+
+        if let Some(ev) = self.events.pop() {
+            self.subscribers.publish(&ev);
+            return Ok(Some(ev));
         }
 
         while let Some((fd, token, interest)) = self.add_interest() {
@@ -423,29 +1072,49 @@ impl Stream {
         if e.token == self.connection_token {
             tracing::trace!(?e.interest, "connection");
 
+            // NB: The connection is torn down the next time the stream is run,
+            // since that requires access to the poll.
+            if self.reconnect.is_some() && (e.interest.is_hup() || e.interest.is_error()) {
+                self.connection_state = ConnectionState::Lost;
+                return Ok(());
+            }
+
             if e.interest.is_read() {
                 let mut fds = [0; 32];
 
-                let n_fds = self
-                    .c
-                    .recv_with_fds(recv, &mut fds[..])
-                    .context("receive error")?;
+                // NB: Receiving stops early whenever file descriptors are
+                // received, so keep going until the socket is drained since an
+                // edge-triggered connection is not reported as ready again.
+                loop {
+                    let n_fds = self
+                        .c
+                        .recv_with_fds(recv, &mut fds[..])
+                        .context("receive error")?;
+
+                    if n_fds == 0 {
+                        break;
+                    }
 
-                for (i, fd) in fds.into_iter().take(n_fds).enumerate() {
-                    let fd = if fd == -1 {
-                        tracing::error!("Received file descriptor #{i} is invalid -1");
-                        None
-                    } else {
-                        // SAFETY: We assume the received file descriptors are valid.
-                        Some(unsafe { OwnedFd::from_raw_fd(fd) })
-                    };
+                    for (i, fd) in fds.into_iter().take(n_fds).enumerate() {
+                        let fd = if fd == -1 {
+                            tracing::error!("Received file descriptor #{i} is invalid -1");
+                            None
+                        } else {
+                            // SAFETY: We assume the received file descriptors are valid.
+                            Some(unsafe { OwnedFd::from_raw_fd(fd) })
+                        };
 
-                    self.fds.push_back(fd);
+                        self.fds.push_back(fd);
+                    }
                 }
             }
 
             if e.interest.is_write() {
-                self.c.send()?;
+                if self.edge_triggered {
+                    self.c.send_all()?;
+                } else {
+                    self.c.send()?;
+                }
             }
 
             return Ok(());
@@ -463,7 +1132,14 @@ impl Stream {
     #[tracing::instrument(skip(self, token))]
     pub fn handle_read(&mut self, token: Token) -> Result<()> {
         let Some(node_id) = self.read_to_client.get(&token) else {
-            tracing::warn!(?token, "Got read for unknown token");
+            warn_limited!(
+                self.warnings,
+                "read",
+                None,
+                0,
+                ?token,
+                "Got read for unknown token"
+            );
             return Ok(());
         };
 
@@ -477,7 +1153,7 @@ impl Stream {
             return Ok(());
         };
 
-        self.process_set.set(node_id.into_u32());
+        self.process_set.set(node_id.index_u32());
         Ok(())
     }
 
@@ -498,10 +1174,10 @@ impl Stream {
             );
         }
 
-        let Some(fd) = self.fds.get_mut(index) else {
+        let Some(fd) = self.message_fds.get_mut(index) else {
             bail!(
                 "Received file descriptor not in stored range 0-{}: {fd:?}",
-                self.fds.len()
+                self.message_fds.len()
             );
         };
 
@@ -509,14 +1185,17 @@ impl Stream {
             bail!("Received file descriptor already used: {fd:?}");
         };
 
-        Ok(Some(fd))
+        Ok(Some(fd.into_owned()))
     }
 
     /// Set a client node as active.
     pub fn client_node_set_active(&mut self, node_id: ClientNodeId, active: bool) -> Result<()> {
-        let node = self.client_nodes.get(node_id)?;
+        let node = self.client_nodes.get_mut(node_id)?;
         self.c.client_node_set_active(node.id, active)?;
 
+        node.active = active;
+        node.idle.set_active(active, utils::get_monotonic_nsec()?);
+
         self.ops.push_back(Op::NodeUpdate {
             node_id,
             what: None,
@@ -525,54 +1204,1647 @@ impl Stream {
         Ok(())
     }
 
-    #[tracing::instrument(skip_all, ret(level = Level::TRACE))]
-    pub fn create_object(&mut self, kind: &str, props: &Properties) -> Result<()> {
-        let Some(entry) = self
-            .factories
-            .get(kind)
-            .and_then(|&id| self.registries.get(id))
-        else {
-            bail!("No factory for {kind}");
-        };
-
-        let Some(type_name) = entry.props.get("factory.type.name") else {
-            bail!("No factory type name for {kind}");
-        };
+    /// Gracefully shut down the stream.
+    ///
+    /// This deactivates and destroys all client nodes on the server, so that no
+    /// stale nodes are left behind. Once the server has processed the shutdown
+    /// [`StreamEvent::Shutdown`] is emitted, after which the stream can be
+    /// dropped.
+    ///
+    /// This is intended to be called when the application is asked to
+    /// terminate, such as when receiving `SIGINT` through a
+    /// [`protocol::SignalFd`].
+    pub fn shutdown(&mut self) -> Result<()> {
+        for node in self.client_nodes.iter_mut() {
+            if node.active {
+                self.c.client_node_set_active(node.id, false)?;
+                node.active = false;
+            }
 
-        let Some(version) = entry
-            .props
-            .get("factory.type.version")
-            .and_then(|version| str::parse::<u32>(version).ok())
-        else {
-            bail!("No factory type version for {kind}");
-        };
+            self.c.core_destroy(node.id)?;
+        }
 
-        let kind = match kind {
-            "client-node" => {
-                let new_id = LocalId::new(self.ids.alloc().context("ran out of identifiers")?);
+        self.c.core_sync(SHUTDOWN_SYNC)?;
+        Ok(())
+    }
 
-                self.c
-                    .core_create_object(kind, type_name, version, new_id, props)?;
+    /// Request a synchronization with the server, returning its sequence
+    /// number.
+    ///
+    /// Once the server has processed every request sent before this one, and
+    /// the events those requests caused have been handled,
+    /// [`StreamEvent::Synced`] is emitted with the returned sequence number.
+    /// This can be used to wait for parameters after subscribing to them.
+    pub fn sync(&mut self) -> Result<u32> {
+        self.c.core_sync(USER_SYNC)
+    }
 
-                let mut ports = Ports::new();
+    /// Ping the server to measure the round trip time of the connection.
+    ///
+    /// The round trip time is recorded in [`Stream::control_stats`] once the
+    /// server responds. This can also be used as a liveness probe when the
+    /// connection seems idle, see [`Stream::oldest_pending_ping`].
+    pub fn ping(&mut self) -> Result<()> {
+        let now = utils::get_monotonic_nsec()?;
+        let seq = self.c.core_sync(PING_SYNC)?;
+        self.pending_pings.insert(seq, now);
+        self.control_stats.pings += 1;
+        Ok(())
+    }
 
-                let write_token = Token::new(self.tokens.alloc().context("no more tokens")? as u64);
-                let read_token = Token::new(self.tokens.alloc().context("no more tokens")? as u64);
+    /// Statistics of the control connection to the server.
+    #[inline]
+    pub fn control_stats(&self) -> &ControlStats {
+        &self.control_stats
+    }
 
-                let node_id = self.client_nodes.insert(ClientNode::new(
-                    new_id,
-                    ports,
-                    write_token,
-                    read_token,
-                )?);
+    /// How long the oldest ping which has not been responded to has been
+    /// waiting for a response.
+    ///
+    /// Returns `None` if all pings have been responded to.
+    pub fn oldest_pending_ping(&self) -> Result<Option<Duration>> {
+        let Some(&sent) = self.pending_pings.values().min() else {
+            return Ok(None);
+        };
 
-                self.local_id_to_kind
-                    .insert(new_id, Kind::ClientNode(node_id));
+        let now = utils::get_monotonic_nsec()?;
+        Ok(Some(Duration::from_nanos(now.saturating_sub(sent))))
+    }
 
-                ObjectKind::Node(node_id)
+    /// Deactivate nodes which have been idle for longer than their configured
+    /// idle timeout, which emits [`StreamEvent::NodeSuspended`].
+    ///
+    /// A node is considered idle if it is active and has no links, see
+    /// [`IdlePolicy`]. This is called each time the stream is run, so it only
+    /// needs to be called manually to check idle nodes without running the
+    /// stream. Suspended nodes are automatically re-activated once a link to
+    /// them appears, which emits [`StreamEvent::NodeResumed`].
+    ///
+    /// [`IdlePolicy`]: crate::IdlePolicy
+    pub fn check_idle(&mut self) -> Result<()> {
+        let now = utils::get_monotonic_nsec()?;
+
+        for (node_id, node) in self.client_nodes.iter_mut_with_id() {
+            if node.idle.poll(now) != Some(IdleTransition::Suspend) {
+                continue;
             }
-            kind => {
-                bail!("Unsupported object kind: {kind}");
+
+            tracing::debug!(?node_id, "Suspending idle node");
+            self.c.client_node_set_active(node.id, false)?;
+            self.ops.push_back(Op::NodeSuspended { node_id });
+        }
+
+        Ok(())
+    }
+
+    /// Get the time until the next idle node is deactivated, if any are idle.
+    ///
+    /// Idle nodes are only deactivated when the stream is run, so this can be
+    /// used to arm a timer which makes sure that happens promptly.
+    pub fn idle_deadline(&self) -> Result<Option<Duration>> {
+        let Some(deadline) = self
+            .client_nodes
+            .iter()
+            .filter_map(|n| n.idle.deadline())
+            .min()
+        else {
+            return Ok(None);
+        };
+
+        let now = utils::get_monotonic_nsec()?;
+        Ok(Some(Duration::from_nanos(deadline.saturating_sub(now))))
+    }
+
+    /// Update link counts for client nodes referenced by a link.
+    fn update_links(&mut self, props: &Properties, added: bool) -> Result<()> {
+        for key in [prop::LINK_INPUT_NODE, prop::LINK_OUTPUT_NODE] {
+            let Some(global_id) = props.get(key).and_then(|id| str::parse::<u32>(id).ok()) else {
+                continue;
+            };
+
+            let Some(Kind::ClientNode(node_id)) = self
+                .globals
+                .by_global(GlobalId::new(global_id))
+                .and_then(|local_id| self.local_id_to_kind.get(&local_id))
+            else {
+                continue;
+            };
+
+            let node_id = *node_id;
+            let node = self.client_nodes.get_mut(node_id)?;
+
+            if added {
+                if node.idle.link_added() == Some(IdleTransition::Resume) {
+                    tracing::debug!(?node_id, "Resuming node");
+                    self.c.client_node_set_active(node.id, true)?;
+                    self.ops.push_back(Op::NodeResumed { node_id });
+                }
+            } else {
+                node.idle.link_removed(utils::get_monotonic_nsec()?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Describe which operations are permitted for this stream.
+    ///
+    /// The access of the client is reported by the server after connecting, so
+    /// this should be queried again once the stream has been established.
+    pub fn capabilities(&self) -> Capabilities {
+        let access = Access::from_props(&self.client.props);
+
+        let create_client_node = self
+            .factories
+            .get("client-node")
+            .and_then(|&index| self.registries.get(index))
+            .is_some_and(|entry| entry.permissions().contains(flags::Permission::X));
+
+        // NB: Binding requires read permissions on the bound global, see
+        // `Stream::bind`.
+        let bind = self.registry_id.is_some()
+            && self.registries.iter().any(|(_, entry)| {
+                ProxyKind::from_type(entry.ty()).is_some()
+                    && entry.permissions().contains(flags::Permission::R)
+            });
+
+        Capabilities {
+            observe: self.registry_id.is_some() && !access.is_restricted(),
+            app_id: self
+                .client
+                .props
+                .get(prop::ACCESS_PORTAL_APP_ID)
+                .map(String::from),
+            bind,
+            create_client_node,
+            access,
+        }
+    }
+
+    /// Install a filter for globals announced by the registry.
+    ///
+    /// Once any filter has been installed, globals which do not match at least
+    /// one filter are dropped as they are announced. This avoids tracking
+    /// globals which are not of interest, which on a typical desktop can be
+    /// several hundreds.
+    ///
+    /// Filters only apply to globals announced after they have been installed,
+    /// so they should be installed before the stream is driven. Globals which
+    /// the stream depends on internally, like factories, links and objects
+    /// owned by this client, are never filtered.
+    ///
+    /// Filtered globals are not visible to methods such as [`Stream::bind`]
+    /// and [`Stream::permissions`].
+    pub fn add_registry_filter(&mut self, filter: RegistryFilter) {
+        self.registry_filters.push(filter);
+    }
+
+    /// Remove all installed registry filters.
+    ///
+    /// Globals which have already been filtered are not restored.
+    pub fn clear_registry_filters(&mut self) {
+        self.registry_filters.clear();
+    }
+
+    /// Test if a global should be filtered.
+    fn is_filtered(&self, id: GlobalId, ty: &str, props: Struct<Slice<'_>>) -> Result<bool> {
+        if self.registry_filters.is_empty() {
+            return Ok(false);
+        }
+
+        if matches!(
+            ty,
+            consts::INTERFACE_FACTORY | consts::INTERFACE_LINK | consts::INTERFACE_SECURITY_CONTEXT
+        ) || self.globals.by_global(id).is_some()
+        {
+            return Ok(false);
+        }
+
+        for filter in &self.registry_filters {
+            if filter.matches(ty, props.as_ref())? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Access the global objects announced by the registry.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use client::Stream;
+    /// use protocol::{Connection, Properties};
+    ///
+    /// let stream = Stream::new(Connection::open()?, Properties::new())?;
+    ///
+    /// for node in stream.registry().nodes() {
+    ///     if node.media_class() == Some("Audio/Sink") {
+    ///         println!("{}: {:?}", node.id(), node.name());
+    ///     }
+    /// }
+    ///
+    /// for link in stream.registry().links() {
+    ///     println!("{:?} -> {:?}", link.output_node(), link.input_node());
+    /// }
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn registry(&self) -> Registry<'_> {
+        Registry::new(&self.registries, &self.id_to_registry)
+    }
+
+    /// Get the permissions the client has on a global object.
+    ///
+    /// Returns `None` if the global object is not visible to the client.
+    pub fn permissions(&self, global_id: GlobalId) -> Option<flags::Permission> {
+        let index = *self.id_to_registry.get(&global_id)?;
+        Some(self.registries.get(index)?.permissions())
+    }
+
+    /// Get a reference to a global object which includes its serial.
+    ///
+    /// Returns `None` if the global object is not visible to the client, or if
+    /// the server did not announce a serial for it.
+    pub fn global_ref(&self, global_id: GlobalId) -> Option<GlobalRef> {
+        let index = *self.id_to_registry.get(&global_id)?;
+        let serial = self.registries.get(index)?.serial?;
+        Some(GlobalRef::new(global_id, serial))
+    }
+
+    /// Resolve a reference to a global object into its identifier.
+    ///
+    /// This errors if the global object has been removed, or if its identifier
+    /// has since been reused by another object.
+    pub fn resolve(&self, global: GlobalRef) -> Result<GlobalId> {
+        let Some(entry) = self
+            .id_to_registry
+            .get(&global.id)
+            .and_then(|&index| self.registries.get(index))
+        else {
+            bail!("No global object with id {}", global.id);
+        };
+
+        ensure!(
+            entry.serial == Some(global.serial),
+            "Global object {global} has been replaced by another object",
+        );
+
+        Ok(global.id)
+    }
+
+    /// Bind to a global object by reference.
+    ///
+    /// This is like [`Stream::bind`], but errors instead of binding to another
+    /// object if the referenced one has been replaced.
+    pub fn bind_ref(&mut self, global: GlobalRef) -> Result<ProxyId> {
+        let global_id = self.resolve(global)?;
+        self.bind(global_id)
+    }
+
+    /// Ensure that the client has the given permissions on a global object.
+    fn ensure_permissions(&self, global_id: GlobalId, perm: flags::Permission) -> Result<()> {
+        let Some(permissions) = self.permissions(global_id) else {
+            bail!("No global object with id {global_id}");
+        };
+
+        ensure!(
+            permissions.contains(perm),
+            "Missing permissions {perm:?} on global object {global_id}"
+        );
+
+        Ok(())
+    }
+
+    /// Bind to a global object.
+    ///
+    /// Only nodes and devices can currently be bound. Once bound, the proxy can
+    /// be used to subscribe to parameters through [`Stream::subscribe_params`].
+    #[tracing::instrument(skip(self), ret(level = Level::TRACE))]
+    pub fn bind(&mut self, global_id: GlobalId) -> Result<ProxyId> {
+        let Some(registry_id) = self.registry_id else {
+            bail!("Registry is not available");
+        };
+
+        let Some(entry) = self
+            .id_to_registry
+            .get(&global_id)
+            .and_then(|&index| self.registries.get(index))
+        else {
+            bail!("No global object with id {global_id}");
+        };
+
+        let Some(kind) = ProxyKind::from_type(&entry.ty) else {
+            bail!("Unsupported global object type {}", entry.ty);
+        };
+
+        ensure!(
+            entry.permissions().contains(flags::Permission::R),
+            "Missing read permission on global object {global_id}"
+        );
+
+        let version = entry.version.min(kind.version());
+        let serial = entry.serial;
+        let local_id = LocalId::new(self.ids.alloc().context("ran out of identifiers")?);
+
+        self.c
+            .registry_bind(registry_id, global_id, kind.as_type(), version, local_id)?;
+
+        let proxy_id = self
+            .proxies
+            .insert(Proxy::new(local_id, global_id, serial, kind));
+        self.local_id_to_kind
+            .insert(local_id, Kind::Proxy(proxy_id));
+        Ok(proxy_id)
+    }
+
+    /// Unbind a proxy which was bound through [`Stream::bind`].
+    ///
+    /// The proxy is destroyed on the server and everything associated with it
+    /// is forgotten, like its cached parameters, pending route volume changes
+    /// and parameter refreshes. Metadata subscriptions bound through the proxy
+    /// are removed, and if the proxy is used for coordination the coordination
+    /// forgets its peers until the metadata object is bound again.
+    #[tracing::instrument(skip(self), ret(level = Level::TRACE))]
+    pub fn unbind(&mut self, proxy_id: ProxyId) -> Result<()> {
+        let proxy = self.proxies.get(proxy_id)?;
+        let (local_id, global_id) = (proxy.id, proxy.global_id);
+
+        self.c.core_destroy(local_id)?;
+
+        self.metadata.retain(|_, bound| *bound != Some(proxy_id));
+        self.remove_proxy(proxy_id);
+        self.local_id_to_kind.remove(&local_id);
+
+        if self.globals.by_global(global_id) == Some(local_id) {
+            self.globals.remove_by_global(global_id);
+        }
+
+        // NB: The identifier can't be reused until the server has acknowledged
+        // that it has been removed.
+        self.removed_ids.insert(local_id);
+        Ok(())
+    }
+
+    /// Forget all state associated with a proxy.
+    fn remove_proxy(&mut self, proxy_id: ProxyId) {
+        for bound in self.metadata.values_mut() {
+            if *bound == Some(proxy_id) {
+                *bound = None;
+            }
+        }
+
+        if let Some(c) = &mut self.coordination
+            && c.proxy == Some(proxy_id)
+        {
+            let mut events = Vec::new();
+            c.unbind(&mut events);
+            self.ops.extend(events.into_iter().map(Op::Coordination));
+        }
+
+        self.refresh.remove(proxy_id);
+
+        if self.proxies.remove(proxy_id).is_none() {
+            tracing::warn!(?proxy_id, "Tried to remove unknown proxy");
+        } else {
+            tracing::debug!(?proxy_id, "Removed proxy");
+        }
+    }
+
+    /// Create a restricted socket at `path` through the security context
+    /// extension.
+    ///
+    /// Clients connecting to the socket are assigned `props`, which should
+    /// include [`prop::SEC_ENGINE`] and typically [`prop::SEC_APP_ID`] so that
+    /// the session manager can apply its access policy to them. This is useful
+    /// to hand restricted graph access to helper processes.
+    #[tracing::instrument(skip(self, path, props), ret(level = Level::TRACE))]
+    pub fn create_security_context(
+        &mut self,
+        path: impl AsRef<Path>,
+        props: &Properties,
+    ) -> Result<SecurityContext> {
+        let id = match self.security_context_id {
+            Some(id) => id,
+            None => {
+                let Some(registry_id) = self.registry_id else {
+                    bail!("Registry is not available");
+                };
+
+                let Some(entry) = self
+                    .registries
+                    .iter()
+                    .map(|(_, entry)| entry)
+                    .find(|entry| entry.ty == consts::INTERFACE_SECURITY_CONTEXT)
+                else {
+                    bail!("Security context is not supported by the server");
+                };
+
+                ensure!(
+                    entry
+                        .permissions()
+                        .contains(flags::Permission::R | flags::Permission::X),
+                    "Missing permissions to create security contexts"
+                );
+
+                let version = entry.version.min(3);
+                let local_id = LocalId::new(self.ids.alloc().context("ran out of identifiers")?);
+
+                self.c.registry_bind(
+                    registry_id,
+                    entry.id,
+                    consts::INTERFACE_SECURITY_CONTEXT,
+                    version,
+                    local_id,
+                )?;
+
+                self.local_id_to_kind
+                    .insert(local_id, Kind::SecurityContext);
+                self.security_context_id = Some(local_id);
+                local_id
+            }
+        };
+
+        let path = path.as_ref();
+
+        let listener = UnixListener::bind(path)
+            .with_context(|| anyhow!("Binding security context socket {}", path.display()))?;
+
+        let (close_read, close_write) =
+            security_context::pipe().context("Creating security context close pipe")?;
+
+        self.c
+            .security_context_create(id, listener.into(), close_read, props)?;
+
+        Ok(SecurityContext::new(path.to_owned(), close_write))
+    }
+
+    /// Subscribe to changes of the given parameters on a bound proxy.
+    ///
+    /// The server responds by emitting the current value of each parameter,
+    /// and emits them again whenever they change. Received values are stored
+    /// in the [`ParamCache`] of the proxy and notified through
+    /// [`StreamEvent::ProxyParam`].
+    ///
+    /// Every change results in the server emitting the values again, so for
+    /// objects which change frequently [`Stream::watch_params`] should be
+    /// preferred.
+    ///
+    /// [`ParamCache`]: crate::ParamCache
+    pub fn subscribe_params(&mut self, proxy_id: ProxyId, ids: &[id::Param]) -> Result<()> {
+        let proxy = self.proxies.get_mut(proxy_id)?;
+
+        match proxy.kind {
+            ProxyKind::Node => self.c.node_subscribe_params(proxy.id, ids)?,
+            ProxyKind::Device => self.c.device_subscribe_params(proxy.id, ids)?,
+            kind => bail!("Proxy of kind {kind:?} does not have parameters"),
+        }
+
+        proxy.subscribed = ids.to_vec();
+        Ok(())
+    }
+
+    /// Enumerate the given parameters of a bound proxy.
+    ///
+    /// Unlike [`Stream::subscribe_params`] the enumeration is scheduled, so
+    /// that requests for the same proxy are coalesced and repeated requests
+    /// back off, see [`ParamRefresh`]. Received values are stored in the
+    /// [`ParamCache`] of the proxy and notified through
+    /// [`StreamEvent::ProxyParam`]. Parameters which no longer have any values
+    /// are removed from the cache once the enumeration has completed.
+    ///
+    /// Refreshes which are delayed are issued the next time the stream is
+    /// run, so callers which want them to be issued promptly should make sure
+    /// the stream is run by [`Stream::refresh_deadline`].
+    ///
+    /// [`ParamCache`]: crate::ParamCache
+    pub fn refresh_params(&mut self, proxy_id: ProxyId, ids: &[id::Param]) -> Result<()> {
+        let proxy = self.proxies.get(proxy_id)?;
+
+        if !matches!(proxy.kind, ProxyKind::Node | ProxyKind::Device) {
+            bail!("Proxy of kind {:?} does not have parameters", proxy.kind);
+        }
+
+        let now = utils::get_monotonic_nsec()?;
+        self.refresh.request(proxy_id, ids, now);
+        Ok(())
+    }
+
+    /// Watch the given parameters of a bound proxy.
+    ///
+    /// The parameters are enumerated through [`Stream::refresh_params`], and
+    /// enumerated again whenever the server reports them as changed in the
+    /// info of the proxy. This is preferable over [`Stream::subscribe_params`]
+    /// for objects which change frequently, since a flood of changes results
+    /// in a bounded number of enumerations.
+    pub fn watch_params(&mut self, proxy_id: ProxyId, ids: &[id::Param]) -> Result<()> {
+        self.refresh_params(proxy_id, ids)?;
+        self.proxies.get_mut(proxy_id)?.watched = ids.to_vec();
+        Ok(())
+    }
+
+    /// Set the number of parameter refreshes which can be outstanding at
+    /// once.
+    ///
+    /// See [`ParamRefresh::set_max_outstanding`].
+    pub fn set_max_outstanding_refreshes(&mut self, max: usize) {
+        self.refresh.set_max_outstanding(max);
+    }
+
+    /// Set the shortest and the longest delay between parameter refreshes of
+    /// the same proxy.
+    ///
+    /// See [`ParamRefresh::set_backoff`].
+    pub fn set_refresh_backoff(&mut self, min: Duration, max: Duration) {
+        self.refresh.set_backoff(min, max);
+    }
+
+    /// Get the time until the next delayed parameter refresh can be issued,
+    /// if any are waiting.
+    ///
+    /// See [`Stream::refresh_params`].
+    pub fn refresh_deadline(&self) -> Result<Option<Duration>> {
+        let Some(deadline) = self.refresh.deadline() else {
+            return Ok(None);
+        };
+
+        let now = utils::get_monotonic_nsec()?;
+        Ok(Some(Duration::from_nanos(deadline.saturating_sub(now))))
+    }
+
+    /// Issue parameter refreshes which are due, each followed by a sync which
+    /// marks its completion.
+    fn issue_refreshes(&mut self) -> Result<()> {
+        let now = utils::get_monotonic_nsec()?;
+
+        while let Some(refresh) = self.refresh.next(now) {
+            let proxy = self.proxies.get(refresh.proxy_id)?;
+
+            for &param in &refresh.params {
+                match proxy.kind {
+                    ProxyKind::Node => {
+                        self.c
+                            .node_enum_params(proxy.id, refresh.seq, param, 0, u32::MAX)?;
+                    }
+                    ProxyKind::Device => {
+                        self.c
+                            .device_enum_params(proxy.id, refresh.seq, param, 0, u32::MAX)?;
+                    }
+                    ProxyKind::Metadata => {}
+                }
+            }
+
+            let seq = self.c.core_sync(REFRESH_SYNC)?;
+            self.pending_refreshes.insert(seq, refresh.seq);
+        }
+
+        Ok(())
+    }
+
+    /// Access a bound device proxy, through which the state of the device can
+    /// be changed.
+    pub fn device(&mut self, proxy_id: ProxyId) -> Result<DeviceProxy<'_>> {
+        let global_id = self.proxies.get(proxy_id)?.global_id;
+        let permissions = self.permissions(global_id);
+
+        let proxy = self.proxies.get_mut(proxy_id)?;
+
+        ensure!(
+            proxy.kind == ProxyKind::Device,
+            "Proxy {proxy_id} is not a device"
+        );
+
+        Ok(DeviceProxy {
+            c: &mut self.c,
+            proxy,
+            permissions,
+        })
+    }
+
+    /// Set the volume and mute state of a route on a bound device.
+    ///
+    /// This is a shorthand for [`DeviceProxy::set_route_volume`] through
+    /// [`Stream::device`].
+    pub fn set_route_volume(
+        &mut self,
+        proxy_id: ProxyId,
+        route: RouteId,
+        channel_volumes: &[f32],
+        mute: bool,
+        save: bool,
+    ) -> Result<()> {
+        self.device(proxy_id)?
+            .set_route_volume(route, channel_volumes, mute, save)
+    }
+
+    /// Get the Bluetooth audio codecs advertised by a bound device.
+    ///
+    /// This requires that the device is subscribed to the
+    /// [`id::Param::PROP_INFO`] and [`id::Param::PROPS`] parameters, which
+    /// [`Stream::set_bluetooth_codec`] does automatically.
+    pub fn bluetooth_codecs(&self, proxy_id: ProxyId) -> Result<BluetoothCodecs> {
+        let proxy = self.proxies.get(proxy_id)?;
+        let mut codecs = BluetoothCodecs::new();
+
+        for param in proxy.params.get(id::Param::PROP_INFO) {
+            codecs.read_prop_info(param.as_ref())?;
+        }
+
+        for param in proxy.params.get(id::Param::PROPS) {
+            codecs.read_props(param.as_ref())?;
+        }
+
+        Ok(codecs)
+    }
+
+    /// Switch the Bluetooth audio codec of a bound device.
+    pub fn set_bluetooth_codec(
+        &mut self,
+        proxy_id: ProxyId,
+        codec: id::BluetoothAudioCodec,
+    ) -> Result<()> {
+        let global_id = self.proxies.get(proxy_id)?.global_id;
+        self.ensure_permissions(global_id, flags::Permission::W | flags::Permission::X)?;
+
+        let proxy = self.proxies.get_mut(proxy_id)?;
+
+        ensure!(
+            proxy.kind == ProxyKind::Device,
+            "Proxy {proxy_id} is not a device"
+        );
+
+        let mut pod = pod::dynamic();
+        BluetoothCodecs::write_select(pod.as_mut(), codec)?;
+
+        self.c
+            .device_set_param(proxy.id, id::Param::PROPS, 0, pod.as_ref().read_object()?)?;
+
+        let mut changed = false;
+
+        for param in [id::Param::PROP_INFO, id::Param::PROPS] {
+            if !proxy.subscribed.contains(&param) {
+                proxy.subscribed.push(param);
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.c
+                .device_subscribe_params(proxy.id, &proxy.subscribed)?;
+        }
+
+        Ok(())
+    }
+
+    /// Feed a sidechain from an arbitrary port in the graph into a node.
+    ///
+    /// This adds an input port to the node next to the `host` input port,
+    /// which is marked as part of the `sidechain` port group. The port
+    /// advertises the same formats as the host port but negotiates its own
+    /// format. Once the server has announced the port, the `source` port is
+    /// linked to it.
+    ///
+    /// Calling this again for the same host port relinks the existing
+    /// sidechain port to the new source.
+    ///
+    /// Returns the identifier of the sidechain input port. Use
+    /// [`Stream::sidechain_alignment`] to align it with the host port.
+    pub fn connect_sidechain(
+        &mut self,
+        node_id: ClientNodeId,
+        host: PortId,
+        source: GlobalId,
+    ) -> Result<PortId> {
+        if let Some(index) = self
+            .sidechains
+            .iter()
+            .position(|s| s.node_id == node_id && s.host == host)
+        {
+            self.unlink_sidechain(index)?;
+            self.sidechains[index].source = source;
+            self.link_sidechain(index)?;
+            return Ok(self.sidechains[index].port);
+        }
+
+        let node = self.client_nodes.get_mut(node_id)?;
+        let host_port = node.ports.get(Direction::INPUT, host)?;
+
+        let params = host_port.params.subset(&[
+            id::Param::ENUM_FORMAT,
+            id::Param::META,
+            id::Param::IO,
+            id::Param::BUFFERS,
+        ])?;
+
+        let name = match host_port.name() {
+            Some(name) => format!("sidechain_{name}"),
+            None => format!("sidechain_{host}"),
+        };
+
+        let port = node.ports.insert(Direction::INPUT)?;
+        port.params = params;
+        port.params.set_writable(id::Param::FORMAT);
+        port.props.insert(prop::PORT_NAME, name);
+        port.props.insert(prop::PORT_GROUP, "sidechain");
+        let port = port.id;
+
+        self.sidechains.push(Sidechain {
+            node_id,
+            host,
+            port,
+            source,
+            port_global: None,
+            link: None,
+        });
+
+        self.ops.push_back(Op::NodeUpdate {
+            node_id,
+            what: None,
+        });
+
+        Ok(port)
+    }
+
+    /// Disconnect the sidechain of the given host port.
+    ///
+    /// The link to the sidechain port is destroyed, but the port itself is
+    /// kept so that it can be relinked with [`Stream::connect_sidechain`].
+    pub fn disconnect_sidechain(&mut self, node_id: ClientNodeId, host: PortId) -> Result<()> {
+        let Some(index) = self
+            .sidechains
+            .iter()
+            .position(|s| s.node_id == node_id && s.host == host)
+        else {
+            bail!("No sidechain for port {host} on node {node_id}");
+        };
+
+        self.unlink_sidechain(index)
+    }
+
+    /// Get the sidechain port of the given host port, if one has been
+    /// connected.
+    pub fn sidechain(&self, node_id: ClientNodeId, host: PortId) -> Option<PortId> {
+        let sidechain = self
+            .sidechains
+            .iter()
+            .find(|s| s.node_id == node_id && s.host == host)?;
+
+        Some(sidechain.port)
+    }
+
+    /// Calculate how many samples the sidechain of the given host port should
+    /// be delayed to line up with the host port, based on the latency reported
+    /// for both ports.
+    ///
+    /// A negative value means that the host port has to be delayed instead,
+    /// see [`ChannelStrip::set_sidechain_alignment`].
+    ///
+    /// Returns `None` if the latency of either port or the current quantum is
+    /// not yet known.
+    ///
+    /// [`ChannelStrip::set_sidechain_alignment`]: crate::ChannelStrip::set_sidechain_alignment
+    pub fn sidechain_alignment(&self, node_id: ClientNodeId, host: PortId) -> Result<Option<i64>> {
+        let Some(port) = self.sidechain(node_id, host) else {
+            bail!("No sidechain for port {host} on node {node_id}");
+        };
+
+        let node = self.client_nodes.get(node_id)?;
+
+        let (Some(quantum), Some(clock)) = (node.duration(), node.clock_time()) else {
+            return Ok(None);
+        };
+
+        let latency = |port: PortId| -> Result<Option<u64>> {
+            let port = node.ports.get(Direction::INPUT, port)?;
+
+            for param in port.params.get(id::Param::LATENCY) {
+                let latency = param.value.as_ref().read::<param::Latency>()?;
+
+                if latency.direction() == Direction::INPUT {
+                    return Ok(Some(latency.max_samples(quantum, clock.rate())));
+                }
+            }
+
+            Ok(None)
+        };
+
+        let (Some(host), Some(sidechain)) = (latency(host)?, latency(port)?) else {
+            return Ok(None);
+        };
+
+        Ok(Some(host as i64 - sidechain as i64))
+    }
+
+    /// Link the source of a sidechain to its port, if the port has been
+    /// announced and it isn't already linked.
+    fn link_sidechain(&mut self, index: usize) -> Result<()> {
+        let sidechain = &self.sidechains[index];
+
+        let (Some(port_global), None) = (sidechain.port_global, sidechain.link) else {
+            return Ok(());
+        };
+
+        let Some(node_global) = self.client_nodes.get(sidechain.node_id)?.global_id else {
+            return Ok(());
+        };
+
+        let Some(source) = self
+            .id_to_registry
+            .get(&sidechain.source)
+            .and_then(|&index| self.registries.get(index))
+        else {
+            bail!("Sidechain source {} not found", sidechain.source);
+        };
+
+        ensure!(
+            source.ty == consts::INTERFACE_PORT,
+            "Sidechain source {} is not a port",
+            sidechain.source
+        );
+
+        let Some(source_node) = source.props.get(prop::NODE_ID) else {
+            bail!("Sidechain source {} has no node", sidechain.source);
+        };
+
+        let mut props = Properties::new();
+        props.insert(prop::LINK_OUTPUT_NODE, source_node);
+        props.insert(
+            prop::LINK_OUTPUT_PORT,
+            sidechain.source.into_u32().to_string(),
+        );
+        props.insert(prop::LINK_INPUT_NODE, node_global.into_u32().to_string());
+        props.insert(prop::LINK_INPUT_PORT, port_global.into_u32().to_string());
+        props.insert(prop::OBJECT_LINGER, "false");
+
+        let link = self.create_from_factory("link-factory", &props)?;
+        self.sidechains[index].link = Some(link);
+        Ok(())
+    }
+
+    /// Destroy the link of a sidechain, if any.
+    fn unlink_sidechain(&mut self, index: usize) -> Result<()> {
+        if let Some(link) = self.sidechains[index].link.take() {
+            self.c.core_destroy(link)?;
+        }
+
+        Ok(())
+    }
+
+    /// Track a port announced by the registry, linking it if it is the port of
+    /// a sidechain.
+    fn sidechain_port_added(&mut self, id: GlobalId, props: &Properties) -> Result<()> {
+        if props.get(prop::PORT_DIRECTION) != Some("in") {
+            return Ok(());
+        }
+
+        let parse = |key| props.get(key).and_then(|id| str::parse::<u32>(id).ok());
+
+        let (Some(node_global), Some(port)) = (parse(prop::NODE_ID), parse(prop::PORT_ID)) else {
+            return Ok(());
+        };
+
+        for index in 0..self.sidechains.len() {
+            let sidechain = &self.sidechains[index];
+
+            if sidechain.port != PortId::new(port)
+                || self.client_nodes.get(sidechain.node_id)?.global_id
+                    != Some(GlobalId::new(node_global))
+            {
+                continue;
+            }
+
+            self.sidechains[index].port_global = Some(id);
+
+            if let Err(error) = self.link_sidechain(index) {
+                tracing_error!(error, "Failed to link sidechain");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget about globals related to sidechains which have been removed.
+    fn sidechain_global_removed(&mut self, id: GlobalId, local_id: Option<LocalId>) {
+        for sidechain in &mut self.sidechains {
+            if sidechain.port_global == Some(id) {
+                sidechain.port_global = None;
+                sidechain.link = None;
+            }
+
+            if sidechain.source == id || (local_id.is_some() && sidechain.link == local_id) {
+                sidechain.link = None;
+            }
+        }
+    }
+
+    /// Coordinate with other instances connected to the same server, under the
+    /// given instance name.
+    ///
+    /// This binds the `default` metadata object once it is announced and
+    /// publishes the name, the claimed bus names and the driver priority of
+    /// this instance in it. Peer instances, bus owners and the elected driver
+    /// are reported through [`StreamEvent::Coordination`], and can be
+    /// inspected through [`Stream::coordination`].
+    ///
+    /// This should be called once the stream has started. Calling it again
+    /// renames the instance.
+    pub fn coordinate(&mut self, instance: &str) -> Result<()> {
+        ensure!(
+            self.registry_id.is_some(),
+            "Coordination requires the stream to be started"
+        );
+
+        match &mut self.coordination {
+            Some(c) => {
+                c.set_instance(instance);
+                self.coordination_publish_all()?;
+            }
+            None => {
+                self.coordination = Some(Coordination::new(instance, self.client.id));
+                self.coordination_bind()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the state of coordination with other instances, if it has been
+    /// set up through [`Stream::coordinate`].
+    pub fn coordination(&self) -> Option<&Coordination> {
+        self.coordination.as_ref()
+    }
+
+    /// Claim a bus name, like the name of a virtual sink, for this instance.
+    ///
+    /// Returns `true` if the bus name is currently owned by this instance.
+    /// Ownership can change as peers come and go, which is reported through
+    /// [`CoordinationEvent::BusOwner`]. Instances which do not own a bus name
+    /// should not create the objects it refers to.
+    ///
+    /// [`CoordinationEvent::BusOwner`]: crate::events::CoordinationEvent::BusOwner
+    pub fn claim_bus(&mut self, bus: &str) -> Result<bool> {
+        let Some(c) = &mut self.coordination else {
+            bail!("Coordination has not been set up");
+        };
+
+        let mut events = Vec::new();
+
+        if c.claim_bus(bus, &mut events) {
+            let key = format!("{}{bus}", coordination::BUS_PREFIX);
+            let instance = c.instance().to_owned();
+            self.coordination_publish(&key, Some(&instance))?;
+        }
+
+        self.ops.extend(events.into_iter().map(Op::Coordination));
+        Ok(self.coordination.as_ref().is_some_and(|c| c.owns_bus(bus)))
+    }
+
+    /// Release a bus name previously claimed through [`Stream::claim_bus`].
+    pub fn release_bus(&mut self, bus: &str) -> Result<()> {
+        let Some(c) = &mut self.coordination else {
+            bail!("Coordination has not been set up");
+        };
+
+        let mut events = Vec::new();
+
+        if c.release_bus(bus, &mut events) {
+            let key = format!("{}{bus}", coordination::BUS_PREFIX);
+            self.coordination_publish(&key, None)?;
+        }
+
+        self.ops.extend(events.into_iter().map(Op::Coordination));
+        Ok(())
+    }
+
+    /// Set the priority this instance is a driver candidate with, or `None`
+    /// to withdraw its candidacy.
+    ///
+    /// The candidate with the highest priority is elected, which is reported
+    /// through [`CoordinationEvent::Driver`]. Only the elected driver should
+    /// force the rate or quantum of the graph, like through
+    /// [`ClientNode::set_force_rate`].
+    ///
+    /// [`CoordinationEvent::Driver`]: crate::events::CoordinationEvent::Driver
+    pub fn set_driver_priority(&mut self, priority: Option<i32>) -> Result<()> {
+        let Some(c) = &mut self.coordination else {
+            bail!("Coordination has not been set up");
+        };
+
+        let mut events = Vec::new();
+        c.set_driver_priority(priority, &mut events);
+
+        let value = priority.map(|priority| priority.to_string());
+        self.coordination_publish(coordination::DRIVER_KEY, value.as_deref())?;
+        self.ops.extend(events.into_iter().map(Op::Coordination));
+        Ok(())
+    }
+
+    /// Bind the shared metadata object used for coordination, if it has been
+    /// announced and isn't already bound.
+    fn coordination_bind(&mut self) -> Result<()> {
+        let Some(c) = &self.coordination else {
+            return Ok(());
+        };
+
+        // NB: While resuming, the coordination is bound once the client id of
+        // the new connection is known.
+        if c.proxy.is_some() || self.connection_state == ConnectionState::Resuming {
+            return Ok(());
+        }
+
+        let Some(global_id) = self
+            .registries
+            .iter()
+            .map(|(_, entry)| entry)
+            .find(|entry| {
+                entry.ty == consts::INTERFACE_METADATA
+                    && entry.props.get(prop::METADATA_NAME) == Some("default")
+            })
+            .map(|entry| entry.id)
+        else {
+            return Ok(());
+        };
+
+        let proxy_id = self.bind(global_id)?;
+
+        if let Some(c) = &mut self.coordination {
+            c.proxy = Some(proxy_id);
+
+            let mut events = Vec::new();
+
+            for (_, entry) in &self.registries {
+                if entry.ty == consts::INTERFACE_CLIENT {
+                    c.set_serial(entry.id, entry.serial, &mut events);
+                }
+            }
+
+            self.ops.extend(events.into_iter().map(Op::Coordination));
+        }
+
+        tracing::debug!(?global_id, ?proxy_id, "Bound coordination metadata");
+        self.coordination_publish_all()
+    }
+
+    /// Publish all entries of this instance to the shared metadata.
+    fn coordination_publish_all(&mut self) -> Result<()> {
+        let Some(c) = &self.coordination else {
+            return Ok(());
+        };
+
+        let instance = c.instance().to_owned();
+
+        let mut entries = vec![(
+            String::from(coordination::INSTANCE_KEY),
+            Some(instance.clone()),
+        )];
+
+        for bus in c.buses() {
+            entries.push((
+                format!("{}{bus}", coordination::BUS_PREFIX),
+                Some(instance.clone()),
+            ));
+        }
+
+        if let Some(priority) = c.driver_priority() {
+            entries.push((
+                String::from(coordination::DRIVER_KEY),
+                Some(priority.to_string()),
+            ));
+        }
+
+        for (key, value) in entries {
+            self.coordination_publish(&key, value.as_deref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Publish an entry of this instance to the shared metadata, if it is
+    /// bound.
+    fn coordination_publish(&mut self, key: &str, value: Option<&str>) -> Result<()> {
+        let Some(c) = &self.coordination else {
+            return Ok(());
+        };
+
+        let Some(proxy_id) = c.proxy else {
+            return Ok(());
+        };
+
+        let subject = c.local();
+        let proxy = self.proxies.get(proxy_id)?;
+        let ty = value.map(|_| coordination::STRING_TYPE);
+
+        self.c
+            .metadata_set_property(proxy.id, subject, key, ty, value)?;
+        Ok(())
+    }
+
+    /// Handle a client or metadata global going away.
+    fn coordination_global_removed(&mut self, id: GlobalId) {
+        let Some(c) = &mut self.coordination else {
+            return;
+        };
+
+        let mut events = Vec::new();
+
+        let bound = c
+            .proxy
+            .and_then(|proxy_id| self.proxies.get(proxy_id).ok())
+            .is_some_and(|proxy| proxy.global_id == id);
+
+        if bound {
+            tracing::debug!(?id, "Coordination metadata removed");
+            c.unbind(&mut events);
+        } else {
+            c.remove_peer(id, &mut events);
+        }
+
+        self.ops.extend(events.into_iter().map(Op::Coordination));
+    }
+
+    /// Subscribe to the properties of the metadata object with the given
+    /// `metadata.name`, like `default`.
+    ///
+    /// The metadata object is bound once it is announced by the registry, and
+    /// is bound again if it is re-announced. Properties are reported through
+    /// [`StreamEvent::MetadataProperty`], and can be inspected through
+    /// [`Stream::metadata`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use client::{GlobalId, Stream};
+    /// use protocol::{Connection, Properties};
+    ///
+    /// let mut stream = Stream::new(Connection::open()?, Properties::new())?;
+    /// stream.subscribe_metadata("default")?;
+    ///
+    /// // Once the stream has been driven for a while.
+    /// if let Some(metadata) = stream.metadata("default") {
+    ///     let sink = metadata.value(GlobalId::new(0), "default.audio.sink");
+    ///     println!("Default sink: {sink:?}");
+    /// }
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    pub fn subscribe_metadata(&mut self, name: &str) -> Result<()> {
+        if !self.metadata.contains_key(name) {
+            self.metadata.insert(String::from(name), None);
+        }
+
+        self.metadata_bind()
+    }
+
+    /// Get the properties of a metadata object subscribed to through
+    /// [`Stream::subscribe_metadata`].
+    ///
+    /// Returns `None` if the metadata object has not been bound.
+    pub fn metadata(&self, name: &str) -> Option<&Metadata> {
+        let proxy_id = (*self.metadata.get(name)?)?;
+        Some(&self.proxies.get(proxy_id).ok()?.metadata)
+    }
+
+    /// Set a property on a subject of a metadata object subscribed to through
+    /// [`Stream::subscribe_metadata`], or remove it if `value` is `None`.
+    ///
+    /// Properties which apply to the server as a whole, like
+    /// `default.audio.sink`, are set on the subject `0`.
+    pub fn set_metadata(
+        &mut self,
+        name: &str,
+        subject: GlobalId,
+        key: &str,
+        ty: Option<&str>,
+        value: Option<&str>,
+    ) -> Result<()> {
+        let proxy = self.metadata_proxy(name)?;
+
+        self.c
+            .metadata_set_property(proxy.id, subject, key, ty, value)?;
+        Ok(())
+    }
+
+    /// Clear all properties of a metadata object subscribed to through
+    /// [`Stream::subscribe_metadata`].
+    pub fn clear_metadata(&mut self, name: &str) -> Result<()> {
+        let proxy = self.metadata_proxy(name)?;
+        self.c.metadata_clear(proxy.id)?;
+        Ok(())
+    }
+
+    /// Get the proxy of a bound metadata object.
+    fn metadata_proxy(&self, name: &str) -> Result<&Proxy> {
+        let Some(&proxy_id) = self.metadata.get(name) else {
+            bail!("Not subscribed to metadata {name}");
+        };
+
+        let Some(proxy_id) = proxy_id else {
+            bail!("Metadata {name} has not been bound");
+        };
+
+        self.proxies.get(proxy_id)
+    }
+
+    /// Bind subscribed metadata objects which have been announced and aren't
+    /// already bound.
+    fn metadata_bind(&mut self) -> Result<()> {
+        if self.registry_id.is_none() {
+            return Ok(());
+        }
+
+        let mut bind = Vec::new();
+
+        for (name, proxy_id) in &self.metadata {
+            if proxy_id.is_some() {
+                continue;
+            }
+
+            let global = self.registry().iter().find(|object| {
+                object.ty == consts::INTERFACE_METADATA
+                    && object.props.get(prop::METADATA_NAME) == Some(name.as_str())
+            });
+
+            if let Some(global) = global {
+                bind.push((name.clone(), global.id));
+            }
+        }
+
+        for (name, global_id) in bind {
+            let proxy_id = self.bind(global_id)?;
+            tracing::debug!(name, ?global_id, ?proxy_id, "Bound metadata");
+            self.metadata.insert(name, Some(proxy_id));
+        }
+
+        Ok(())
+    }
+
+    /// Get a reference to a node which can be resolved again after a restart
+    /// through [`Stream::resolve_node_ref`].
+    ///
+    /// Returns `None` if the global object is not a node, or if it doesn't
+    /// have a `node.name`.
+    pub fn node_ref(&self, global_id: GlobalId) -> Option<NodeRef> {
+        let entry = self.registry_entry(global_id)?;
+
+        if entry.ty != consts::INTERFACE_NODE {
+            return None;
+        }
+
+        let name = entry.props.get(prop::NODE_NAME)?;
+        Some(NodeRef::new(name, entry.serial))
+    }
+
+    /// Resolve a node reference to the global id of an equivalent node.
+    ///
+    /// A node with the same name and serial is preferred, which otherwise
+    /// falls back to the first node with the same name.
+    pub fn resolve_node_ref(&self, node: &NodeRef) -> Option<GlobalId> {
+        let mut found = None;
+
+        for (_, entry) in &self.registries {
+            if entry.ty != consts::INTERFACE_NODE
+                || entry.props.get(prop::NODE_NAME) != Some(node.name.as_str())
+            {
+                continue;
+            }
+
+            if node.serial.is_some() && entry.serial == node.serial {
+                return Some(entry.id);
+            }
+
+            found = found.or(Some(entry.id));
+        }
+
+        found
+    }
+
+    /// Load a preset file written with [`ClientNode::write_preset`] into a
+    /// client node, see [`ClientNode::read_preset`].
+    ///
+    /// If the file can't be read or the preset is invalid, the node is left
+    /// untouched and [`StreamEvent::PresetRejected`] is emitted. Returns
+    /// `true` if the preset was applied.
+    ///
+    /// Changes to preset files can be watched for with a [`PresetWatcher`].
+    ///
+    /// [`PresetWatcher`]: crate::PresetWatcher
+    pub fn load_preset(&mut self, node_id: ClientNodeId, path: impl AsRef<Path>) -> Result<bool> {
+        let path = path.as_ref();
+        let node = self.client_nodes.get_mut(node_id)?;
+
+        let result = fs::read(path)
+            .with_context(|| format!("Reading {}", path.display()))
+            .and_then(|bytes| {
+                let pod = Pod::new(DynamicBuf::from_slice(&bytes)?);
+                node.read_preset(pod.as_ref().read_struct()?)
+            });
+
+        let Err(error) = result else {
+            return Ok(true);
+        };
+
+        let error = format!("{error:#}");
+        tracing::warn!(?node_id, path = %path.display(), error, "Preset rejected");
+
+        self.ops.push_back(Op::PresetRejected(PresetRejectedEvent {
+            node_id,
+            path: path.to_path_buf(),
+            error,
+        }));
+
+        Ok(false)
+    }
+
+    /// Record the node, target and links of a client node in a session under
+    /// the given channel or bus name, replacing any existing entry.
+    ///
+    /// The node must have been announced by the server.
+    pub fn record_session(
+        &self,
+        session: &mut Session,
+        name: &str,
+        node_id: ClientNodeId,
+    ) -> Result<()> {
+        let node = self.client_nodes.get(node_id)?;
+
+        let Some(global_id) = node.global_id else {
+            bail!("Node {node_id} has not been announced");
+        };
+
+        let target = node.props.get(prop::TARGET_OBJECT).map(|target| {
+            self.registries
+                .iter()
+                .map(|(_, entry)| entry)
+                .find(|entry| {
+                    entry.ty == consts::INTERFACE_NODE
+                        && (entry.props.get(prop::NODE_NAME) == Some(target)
+                            || entry.props.get(prop::OBJECT_SERIAL) == Some(target))
+                })
+                .and_then(|entry| self.node_ref(entry.id))
+                .unwrap_or_else(|| NodeRef::new(target, None))
+        });
+
+        let mut links = Vec::new();
+        let global = global_id.into_u32().to_string();
+
+        for (_, entry) in &self.registries {
+            if entry.ty != consts::INTERFACE_LINK {
+                continue;
+            }
+
+            let (direction, port, peer, peer_port) =
+                if entry.props.get(prop::LINK_OUTPUT_NODE) == Some(global.as_str()) {
+                    (
+                        Direction::OUTPUT,
+                        prop::LINK_OUTPUT_PORT,
+                        prop::LINK_INPUT_NODE,
+                        prop::LINK_INPUT_PORT,
+                    )
+                } else if entry.props.get(prop::LINK_INPUT_NODE) == Some(global.as_str()) {
+                    (
+                        Direction::INPUT,
+                        prop::LINK_INPUT_PORT,
+                        prop::LINK_OUTPUT_NODE,
+                        prop::LINK_OUTPUT_PORT,
+                    )
+                } else {
+                    continue;
+                };
+
+            let lookup = |key| {
+                let id = entry.props.get(key)?.parse::<u32>().ok()?;
+                Some(GlobalId::new(id))
+            };
+
+            let port_name = |id| {
+                let entry = self.registry_entry(id)?;
+                entry.props.get(prop::PORT_NAME)
+            };
+
+            let (Some(port), Some(peer), Some(peer_port)) =
+                (lookup(port), lookup(peer), lookup(peer_port))
+            else {
+                continue;
+            };
+
+            let (Some(port), Some(peer), Some(peer_port)) =
+                (port_name(port), self.node_ref(peer), port_name(peer_port))
+            else {
+                continue;
+            };
+
+            links.push(SessionLink::new(direction, port, peer, peer_port));
+        }
+
+        let entry = session.entry(name);
+        entry.node = self.node_ref(global_id);
+        entry.target = target;
+        entry.links = links;
+        Ok(())
+    }
+
+    /// Restore the target and links of a channel or bus recorded in a session
+    /// onto a client node.
+    ///
+    /// The target is resolved to an equivalent node and assigned through the
+    /// `target.object` property. Links are created once the ports on both
+    /// ends have been announced, which might be after the peer node has been
+    /// recreated. Does nothing if the session has no entry with the given
+    /// name.
+    pub fn restore_session(
+        &mut self,
+        session: &Session,
+        name: &str,
+        node_id: ClientNodeId,
+    ) -> Result<()> {
+        let Some(entry) = session.get(name) else {
+            return Ok(());
+        };
+
+        if let Some(target) = &entry.target {
+            // NB: Prefer the serial of an equivalent node since it is
+            // unambiguous, otherwise let the session manager pick the target
+            // up by name once it appears.
+            let value = self
+                .resolve_node_ref(target)
+                .and_then(|id| self.registry_entry(id)?.serial)
+                .map(|serial| serial.to_string())
+                .unwrap_or_else(|| target.name.clone());
+
+            self.client_nodes
+                .get_mut(node_id)?
+                .props
+                .insert(prop::TARGET_OBJECT, value);
+        }
+
+        self.session_links
+            .retain(|pending| pending.node_id != node_id);
+
+        for link in &entry.links {
+            self.session_links.push(PendingSessionLink {
+                node_id,
+                link: link.clone(),
+            });
+        }
+
+        self.link_session();
+        Ok(())
+    }
+
+    /// Create pending session links whose ports have been announced.
+    fn link_session(&mut self) {
+        let mut index = 0;
+
+        while index < self.session_links.len() {
+            match self.try_link_session(&self.session_links[index]) {
+                Ok(Some(props)) => {
+                    let pending = self.session_links.swap_remove(index);
+
+                    if let Err(error) = self.create_from_factory("link-factory", &props) {
+                        tracing_error!(error, "Failed to restore session link");
+                        tracing::warn!(?pending.link, "Dropping session link");
+                    }
+                }
+                Ok(None) => {
+                    index += 1;
+                }
+                Err(error) => {
+                    let pending = self.session_links.swap_remove(index);
+                    tracing_error!(error, "Failed to restore session link");
+                    tracing::warn!(?pending.link, "Dropping session link");
+                }
+            }
+        }
+    }
+
+    /// Build the properties of a pending session link, if both of its ports
+    /// have been announced.
+    fn try_link_session(&self, pending: &PendingSessionLink) -> Result<Option<Properties>> {
+        let Some(node_global) = self.client_nodes.get(pending.node_id)?.global_id else {
+            return Ok(None);
+        };
+
+        let link = &pending.link;
+
+        let Some(peer_global) = self.resolve_node_ref(&link.peer) else {
+            return Ok(None);
+        };
+
+        let peer_direction = if link.direction == Direction::OUTPUT {
+            Direction::INPUT
+        } else {
+            Direction::OUTPUT
+        };
+
+        let (Some(port), Some(peer_port)) = (
+            self.find_port(node_global, link.direction, &link.port),
+            self.find_port(peer_global, peer_direction, &link.peer_port),
+        ) else {
+            return Ok(None);
+        };
+
+        let mut ends = [(node_global, port), (peer_global, peer_port)];
+
+        if link.direction == Direction::INPUT {
+            ends.reverse();
+        }
+
+        let [(output_node, output_port), (input_node, input_port)] = ends;
+
+        let mut props = Properties::new();
+        props.insert(prop::LINK_OUTPUT_NODE, output_node.into_u32().to_string());
+        props.insert(prop::LINK_OUTPUT_PORT, output_port.into_u32().to_string());
+        props.insert(prop::LINK_INPUT_NODE, input_node.into_u32().to_string());
+        props.insert(prop::LINK_INPUT_PORT, input_port.into_u32().to_string());
+        props.insert(prop::OBJECT_LINGER, "false");
+        Ok(Some(props))
+    }
+
+    /// Find the global id of a port of a node by its direction and name.
+    fn find_port(&self, node: GlobalId, direction: Direction, name: &str) -> Option<GlobalId> {
+        let node = node.into_u32().to_string();
+
+        let direction = if direction == Direction::OUTPUT {
+            "out"
+        } else {
+            "in"
+        };
+
+        self.registries
+            .iter()
+            .map(|(_, entry)| entry)
+            .find(|entry| {
+                entry.ty == consts::INTERFACE_PORT
+                    && entry.props.get(prop::NODE_ID) == Some(node.as_str())
+                    && entry.props.get(prop::PORT_DIRECTION) == Some(direction)
+                    && entry.props.get(prop::PORT_NAME) == Some(name)
+            })
+            .map(|entry| entry.id)
+    }
+
+    /// Look up the registry entry of a global object.
+    fn registry_entry(&self, global_id: GlobalId) -> Option<&GlobalObject> {
+        let index = *self.id_to_registry.get(&global_id)?;
+        self.registries.get(index)
+    }
+
+    /// Create an object through the given factory, returning the local
+    /// identifier of the new object.
+    fn create_from_factory(&mut self, factory: &str, props: &Properties) -> Result<LocalId> {
+        let Some(entry) = self
+            .factories
+            .get(factory)
+            .and_then(|&id| self.registries.get(id))
+        else {
+            bail!("No factory for {factory}");
+        };
+
+        let Some(type_name) = entry.props.get("factory.type.name") else {
+            bail!("No factory type name for {factory}");
+        };
+
+        let Some(version) = entry
+            .props
+            .get("factory.type.version")
+            .and_then(|version| str::parse::<u32>(version).ok())
+        else {
+            bail!("No factory type version for {factory}");
+        };
+
+        let new_id = LocalId::new(self.ids.alloc().context("ran out of identifiers")?);
+
+        self.c
+            .core_create_object(factory, type_name, version, new_id, props)?;
+
+        Ok(new_id)
+    }
+
+    #[tracing::instrument(skip_all, ret(level = Level::TRACE))]
+    pub fn create_object(&mut self, kind: &str, props: &Properties) -> Result<()> {
+        let kind = match kind {
+            "client-node" => {
+                let new_id = self.create_from_factory(kind, props)?;
+
+                let mut ports = Ports::new();
+
+                let write_token = Token::new(self.tokens.alloc().context("no more tokens")? as u64);
+                let read_token = Token::new(self.tokens.alloc().context("no more tokens")? as u64);
+
+                let node_id = self.client_nodes.insert(ClientNode::new(
+                    new_id,
+                    ports,
+                    write_token,
+                    read_token,
+                    self.memory.epoch().reader(),
+                    props.clone(),
+                )?)?;
+
+                self.local_id_to_kind
+                    .insert(new_id, Kind::ClientNode(node_id));
+
+                ObjectKind::Node(node_id)
+            }
+            kind => {
+                bail!("Unsupported object kind: {kind}");
             }
         };
 
@@ -580,6 +2852,40 @@ impl Stream {
         Ok(())
     }
 
+    /// Create every client node again after reconnecting, which sends its
+    /// ports and parameters in full with the next update.
+    fn resume_nodes(&mut self) -> Result<()> {
+        let node_ids = self
+            .client_nodes
+            .iter_mut_with_id()
+            .map(|(node_id, _)| node_id)
+            .collect::<Vec<_>>();
+
+        for node_id in node_ids {
+            let props = self.client_nodes.get(node_id)?.factory_props.clone();
+            let new_id = self.create_from_factory("client-node", &props)?;
+            self.local_id_to_kind
+                .insert(new_id, Kind::ClientNode(node_id));
+
+            let node = self.client_nodes.get_mut(node_id)?;
+            node.id = new_id;
+
+            if node.active {
+                self.c.client_node_set_active(node.id, true)?;
+                node.idle.set_active(true, utils::get_monotonic_nsec()?);
+            }
+
+            tracing::debug!(?node_id, ?new_id, "Re-created node");
+
+            self.ops.push_back(Op::NodeUpdate {
+                node_id,
+                what: None,
+            });
+        }
+
+        Ok(())
+    }
+
     fn node_read_interest(&mut self, node_id: ClientNodeId) -> Result<()> {
         let node = self.client_nodes.get(node_id)?;
 
@@ -621,17 +2927,27 @@ impl Stream {
             CoreEvent::ERROR => {
                 self.core_error_event(st).context(op)?;
             }
+            CoreEvent::REMOVE_ID_EVENT => {
+                self.core_remove_id_event(st).context(op)?;
+            }
             CoreEvent::BOUND_ID => {
                 self.core_bound_id_event(st).context(op)?;
             }
             CoreEvent::ADD_MEM => {
                 self.core_add_mem_event(st).context(op)?;
             }
-            CoreEvent::DESTROY => {
-                self.core_destroy(st).context(op)?;
+            CoreEvent::REMOVE_MEM => {
+                self.core_remove_mem_event(st).context(op)?;
             }
             op => {
-                tracing::warn!("Unsupported event: {op}");
+                warn_limited!(
+                    self.warnings,
+                    "core",
+                    None,
+                    u32::from(self.header.op()),
+                    "Unsupported event: {op}"
+                );
+                self.unknown_message("core", &st);
             }
         }
 
@@ -649,18 +2965,69 @@ impl Stream {
                 self.client_error(st).context(op)?;
             }
             op => {
-                tracing::warn!("Unsupported event: {op}");
+                warn_limited!(
+                    self.warnings,
+                    "client",
+                    None,
+                    u32::from(self.header.op()),
+                    "Unsupported event: {op}"
+                );
+                self.unknown_message("client", &st);
             }
         }
 
         Ok(())
     }
 
+    /// Capture a message which was not understood, if enabled.
+    fn unknown_message(&mut self, interface: &'static str, st: &Struct<Slice<'_>>) {
+        if !self.capture_unknown {
+            return;
+        }
+
+        self.ops.push_back(Op::UnknownMessage(UnknownMessageEvent {
+            interface,
+            id: self.header.id(),
+            op: self.header.op(),
+            payload: st.as_buf().as_bytes().to_vec(),
+        }));
+    }
+
+    /// Get the name of the interface the current message is addressed to.
+    fn receiver_interface(&self) -> &'static str {
+        match self.header.id() {
+            consts::CORE_ID => "core",
+            consts::CLIENT_ID => "client",
+            id => match self.local_id_to_kind.get(&LocalId::new(id)) {
+                Some(Kind::Registry) => "registry",
+                Some(Kind::ClientNode(..)) => "client-node",
+                Some(Kind::Proxy(proxy_id)) => match self.proxies.get(*proxy_id) {
+                    Ok(proxy) => match proxy.kind {
+                        ProxyKind::Node => "node",
+                        ProxyKind::Device => "device",
+                        ProxyKind::Metadata => "metadata",
+                    },
+                    Err(..) => "unknown",
+                },
+                Some(Kind::SecurityContext) => "security-context",
+                None => "unknown",
+            },
+        }
+    }
+
     fn dynamic(&mut self, st: Struct<Slice<'_>>) -> Result<()> {
         let id = LocalId::new(self.header.id());
 
         let Some(kind) = self.local_id_to_kind.get(&id) else {
-            tracing::warn!(?self.header, "Unknown receiver");
+            warn_limited!(
+                self.warnings,
+                "receiver",
+                None,
+                self.header.id(),
+                ?self.header,
+                "Unknown receiver"
+            );
+            self.unknown_message("unknown", &st);
             return Ok(());
         };
 
@@ -677,7 +3044,15 @@ impl Stream {
                         self.registry_global_remove(st).context(op)?;
                     }
                     op => {
-                        tracing::warn!(?op, "Registry unsupported op");
+                        warn_limited!(
+                            self.warnings,
+                            "registry",
+                            None,
+                            u32::from(self.header.op()),
+                            ?op,
+                            "Registry unsupported op"
+                        );
+                        self.unknown_message("registry", &st);
                     }
                 }
             }
@@ -714,9 +3089,117 @@ impl Stream {
                         self.client_node_set_mix_info(node_id, st).context(op)?;
                     }
                     op => {
-                        tracing::warn!("Unsupported event: {op}");
+                        warn_limited!(
+                            self.warnings,
+                            "client-node",
+                            Some(node_id),
+                            u32::from(self.header.op()),
+                            "Unsupported event: {op}"
+                        );
+                        self.unknown_message("client-node", &st);
+                    }
+                }
+            }
+            Kind::Proxy(proxy_id) => match self.proxies.get(proxy_id)?.kind {
+                ProxyKind::Node => {
+                    let op = NodeEvent::from_raw(self.header.op());
+                    tracing::trace!("Event: {op}");
+
+                    match op {
+                        NodeEvent::INFO => {
+                            // NB: A malformed info is not fatal, since it only
+                            // drives refreshes of watched parameters.
+                            if let Err(error) = self.node_info(proxy_id, st) {
+                                warn_limited!(
+                                    self.warnings,
+                                    "node-info",
+                                    None,
+                                    0,
+                                    ?proxy_id,
+                                    %error,
+                                    "Failed to decode node info"
+                                );
+                            }
+                        }
+                        NodeEvent::PARAM => {
+                            self.proxy_param(proxy_id, st).context(op)?;
+                        }
+                        op => {
+                            warn_limited!(
+                                self.warnings,
+                                "node",
+                                None,
+                                u32::from(self.header.op()),
+                                "Unsupported event: {op}"
+                            );
+                            self.unknown_message("node", &st);
+                        }
+                    }
+                }
+                ProxyKind::Device => {
+                    let op = DeviceEvent::from_raw(self.header.op());
+                    tracing::trace!("Event: {op}");
+
+                    match op {
+                        DeviceEvent::INFO => {
+                            if let Err(error) = self.device_info(proxy_id, st) {
+                                warn_limited!(
+                                    self.warnings,
+                                    "device-info",
+                                    None,
+                                    0,
+                                    ?proxy_id,
+                                    %error,
+                                    "Failed to decode device info"
+                                );
+                            }
+                        }
+                        DeviceEvent::PARAM => {
+                            self.proxy_param(proxy_id, st).context(op)?;
+                        }
+                        op => {
+                            warn_limited!(
+                                self.warnings,
+                                "device",
+                                None,
+                                u32::from(self.header.op()),
+                                "Unsupported event: {op}"
+                            );
+                            self.unknown_message("device", &st);
+                        }
+                    }
+                }
+                ProxyKind::Metadata => {
+                    let op = MetadataEvent::from_raw(self.header.op());
+                    tracing::trace!("Event: {op}");
+
+                    match op {
+                        MetadataEvent::PROPERTY => {
+                            self.metadata_property(proxy_id, st).context(op)?;
+                        }
+                        op => {
+                            warn_limited!(
+                                self.warnings,
+                                "metadata",
+                                None,
+                                u32::from(self.header.op()),
+                                "Unsupported event: {op}"
+                            );
+                            self.unknown_message("metadata", &st);
+                        }
                     }
                 }
+            },
+            Kind::SecurityContext => {
+                warn_limited!(
+                    self.warnings,
+                    "security-context",
+                    None,
+                    u32::from(self.header.op()),
+                    "Unsupported security context event: {}",
+                    self.header.op()
+                );
+                self.unknown_message("security-context", &st);
             }
         }
 
@@ -756,6 +3239,10 @@ impl Stream {
         let (id, seq) = st.read::<(i32, i32)>()?;
 
         match id {
+            GET_REGISTRY_SYNC if self.connection_state == ConnectionState::Resuming => {
+                self.ops.push_back(Op::Reconnected);
+                tracing::trace!(id, seq, "Registry sync after reconnecting done");
+            }
             GET_REGISTRY_SYNC => {
                 self.ops.push_back(Op::CoreStarted);
                 tracing::trace!(id, seq, "Intitial registry sync done");
@@ -763,6 +3250,52 @@ impl Stream {
             CREATE_CLIENT_NODE => {
                 tracing::trace!(id, seq, "Client node created");
             }
+            SHUTDOWN_SYNC => {
+                self.ops.push_back(Op::Shutdown);
+                tracing::trace!(id, seq, "Shutdown done");
+            }
+            REFRESH_SYNC => {
+                // NB: The sequence is sent as unsigned, but echoed as signed.
+                let Some(refresh) = self
+                    .pending_refreshes
+                    .remove(&(seq as u32))
+                    .and_then(|seq| self.refresh.complete(seq))
+                else {
+                    tracing::trace!(id, seq, "Refresh of removed proxy done");
+                    return Ok(());
+                };
+
+                let proxy = self.proxies.get_mut(refresh.proxy_id)?;
+
+                // NB: Parameters which were not received during the
+                // enumeration no longer have any values.
+                for param in refresh.params {
+                    if proxy.params.seq(param) != Some(refresh.seq) && proxy.params.remove(param) {
+                        self.ops.push_back(Op::ProxyParam {
+                            proxy_id: refresh.proxy_id,
+                            param,
+                        });
+                    }
+                }
+
+                tracing::trace!(id, seq, "Refresh done");
+            }
+            USER_SYNC => {
+                // NB: The sequence is sent as unsigned, but echoed as signed.
+                self.ops.push_back(Op::Synced(seq as u32));
+                tracing::trace!(id, seq, "Sync done");
+            }
+            PING_SYNC => {
+                // NB: The sequence is sent as unsigned, but echoed as signed.
+                let Some(sent) = self.pending_pings.remove(&(seq as u32)) else {
+                    tracing::warn!(id, seq, "Unknown ping sequence");
+                    return Ok(());
+                };
+
+                let rtt = utils::get_monotonic_nsec()?.saturating_sub(sent);
+                self.control_stats.record(rtt);
+                tracing::trace!(id, seq, rtt, "Ping done");
+            }
             id => {
                 tracing::warn!(id, seq, "Unknown core done event id");
             }
@@ -797,6 +3330,23 @@ impl Stream {
         let (local_id, global_id) = st.read::<(LocalId, GlobalId)>()?;
         self.globals.insert(local_id, global_id);
         tracing::debug!(?local_id, ?global_id);
+
+        if let Some(Kind::ClientNode(node_id)) = self.local_id_to_kind.get(&local_id) {
+            self.client_nodes.get_mut(*node_id)?.global_id = Some(global_id);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn core_remove_id_event(&mut self, mut st: Struct<Slice<'_>>) -> Result<()> {
+        let local_id = st.read::<LocalId>()?;
+        tracing::debug!(?local_id);
+
+        if self.removed_ids.remove(&local_id) {
+            self.ids.unset(local_id.into_u32());
+        }
+
         Ok(())
     }
 
@@ -816,13 +3366,103 @@ impl Stream {
     }
 
     #[tracing::instrument(skip_all)]
-    fn core_destroy(&mut self, mut st: Struct<Slice<'_>>) -> Result<()> {
+    fn core_remove_mem_event(&mut self, mut st: Struct<Slice<'_>>) -> Result<()> {
         let id = st.field()?.read_sized::<u32>()?;
 
         tracing::debug!(id);
+
+        if self.memory.revoke(id) {
+            self.free_revoked_regions();
+        }
+
         Ok(())
     }
 
+    /// Free any regions held by client nodes which refer to revoked memory.
+    fn free_revoked_regions(&mut self) {
+        let memory = &mut self.memory;
+
+        for node in self.client_nodes.iter_mut() {
+            if node
+                .activation
+                .as_ref()
+                .is_some_and(|r| memory.is_revoked(r))
+                && let Some(region) = node.take_activation()
+            {
+                memory.free(region);
+            }
+
+            if node.io_clock.as_ref().is_some_and(|r| memory.is_revoked(r))
+                && let Some(region) = node.io_clock.take()
+            {
+                memory.free(region);
+            }
+
+            if node
+                .io_control
+                .as_ref()
+                .is_some_and(|r| memory.is_revoked(r))
+                && let Some(region) = node.io_control.take()
+            {
+                memory.free(region);
+            }
+
+            if node
+                .io_position
+                .as_ref()
+                .is_some_and(|r| memory.is_revoked(r))
+                && let Some(region) = node.take_io_position()
+            {
+                memory.free(region);
+            }
+
+            let revoked = node
+                .peer_activations
+                .extract_if(.., |a| memory.is_revoked(&a.region))
+                .collect::<Vec<_>>();
+
+            for a in revoked {
+                memory.free(a.region);
+            }
+
+            for port in node.ports.iter_mut() {
+                if port.io_clock.as_ref().is_some_and(|r| memory.is_revoked(r))
+                    && let Some(region) = port.io_clock.take()
+                {
+                    memory.free(region);
+                }
+
+                if port
+                    .io_position
+                    .as_ref()
+                    .is_some_and(|r| memory.is_revoked(r))
+                    && let Some(region) = port.io_position.take()
+                {
+                    memory.free(region);
+                }
+
+                let revoked = port
+                    .mixes
+                    .buffers
+                    .extract_if(.., |m| memory.is_revoked(&m.region))
+                    .collect::<Vec<_>>();
+
+                for mix in revoked {
+                    port.port_buffers.free_all(mix.mix_id);
+                    memory.free(mix.region);
+                }
+
+                let revoked = port
+                    .extract_buffers_if(|b| buffers_revoked(memory, b))
+                    .collect::<Vec<_>>();
+
+                for buffers in revoked {
+                    free_buffers(memory, buffers);
+                }
+            }
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     fn client_info(&mut self, mut st: Struct<Slice<'_>>) -> Result<()> {
         let id = st.field()?.read::<GlobalId>()?;
@@ -855,25 +3495,39 @@ impl Stream {
     #[tracing::instrument(skip_all)]
     fn registry_global(&mut self, mut st: Struct<Slice<'_>>) -> Result<()> {
         let (id, permissions, ty, version, mut props) =
-            st.read::<(GlobalId, _, _, _, Struct<_>)>()?;
+            st.read::<(GlobalId, _, &str, _, Struct<_>)>()?;
 
         let n_items = props.read::<u32>()?;
 
+        if self.is_filtered(id, ty, props.as_ref())? {
+            tracing::trace!(?id, ty, "Filtered registry global");
+            self.filtered.insert(id);
+            return Ok(());
+        }
+
         let index = self.registries.vacant_key();
 
-        let mut registry = RegistryEntry {
+        let mut registry = GlobalObject {
             id,
+            serial: None,
             permissions,
-            ty,
+            ty: self.symbols.intern(ty),
             version,
             props: Properties::new(),
         };
 
         for _ in 0..n_items {
             let (key, value) = props.read::<(&str, &str)>()?;
-            registry.props.insert(key.to_owned(), value.to_owned());
+            registry
+                .props
+                .insert_symbol(self.symbols.intern(key), value);
         }
 
+        registry.serial = registry
+            .props
+            .get(prop::OBJECT_SERIAL)
+            .and_then(|serial| serial.parse().ok());
+
         if registry.ty == consts::INTERFACE_FACTORY
             && let Some(name) = registry.props.get("factory.name")
         {
@@ -884,13 +3538,23 @@ impl Stream {
 
         self.id_to_registry.insert(id, index);
 
+        if registry.ty == consts::INTERFACE_LINK {
+            self.update_links(&registry.props, true)?;
+        }
+
+        if registry.ty == consts::INTERFACE_PORT {
+            self.sidechain_port_added(id, &registry.props)?;
+        }
+
+        let is_port = registry.ty == consts::INTERFACE_PORT;
+
         if let Some(kind) = self
             .globals
             .by_global(id)
             .and_then(|local_id| self.local_id_to_kind.get_mut(&local_id))
         {
             match *kind {
-                Kind::Registry => {}
+                Kind::Registry | Kind::Proxy(..) | Kind::SecurityContext => {}
                 Kind::ClientNode(node_id) => {
                     if self
                         .client_nodes
@@ -907,7 +3571,27 @@ impl Stream {
             }
         }
 
+        if registry.ty == consts::INTERFACE_CLIENT
+            && let Some(c) = &mut self.coordination
+        {
+            let mut events = Vec::new();
+            c.set_serial(id, registry.serial, &mut events);
+            self.ops.extend(events.into_iter().map(Op::Coordination));
+        }
+
+        let is_metadata = registry.ty == consts::INTERFACE_METADATA;
+        self.ops.push_back(Op::GlobalAdded(registry.clone()));
         self.registries.insert(registry);
+
+        if is_metadata {
+            self.coordination_bind()?;
+            self.metadata_bind()?;
+        }
+
+        if is_port && !self.session_links.is_empty() {
+            self.link_session();
+        }
+
         Ok(())
     }
 
@@ -916,6 +3600,10 @@ impl Stream {
         let id = st.read::<GlobalId>()?;
 
         let Some(registry_index) = self.id_to_registry.remove(&id) else {
+            if self.filtered.remove(&id) {
+                return Ok(());
+            }
+
             tracing::warn!(?id, "Tried to remove unknown registry");
             return Ok(());
         };
@@ -927,23 +3615,198 @@ impl Stream {
 
         tracing::debug!(?registry, "Removed registry");
 
-        if let Some(local_id) = self.globals.remove_by_global(id) {
+        self.symbols.collect();
+
+        if registry.ty == consts::INTERFACE_LINK {
+            self.update_links(&registry.props, false)?;
+        }
+
+        if registry.ty == consts::INTERFACE_CLIENT || registry.ty == consts::INTERFACE_METADATA {
+            self.coordination_global_removed(id);
+        }
+
+        let local_id = self.globals.remove_by_global(id);
+        self.sidechain_global_removed(id, local_id);
+        self.ops.push_back(Op::GlobalRemoved(registry));
+
+        if let Some(local_id) = local_id {
             self.ids.unset(local_id.into_u32());
 
             if let Some(kind) = self.local_id_to_kind.remove(&local_id) {
                 match kind {
                     Kind::Registry => {}
+                    Kind::SecurityContext => {
+                        self.security_context_id = None;
+                    }
                     Kind::ClientNode(node_id) => {
+                        self.session_links
+                            .retain(|pending| pending.node_id != node_id);
+
                         if self.client_nodes.remove(node_id).is_none() {
                             tracing::warn!(?node_id, "Tried to remove unknown client node");
                         } else {
+                            // NB: The index might be reused by a later node.
+                            self.process_set.unset(node_id.index_u32());
                             tracing::info!(?node_id, "Removed client node");
                         }
                     }
+                    Kind::Proxy(proxy_id) => {
+                        self.remove_proxy(proxy_id);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, st))]
+    fn metadata_property(&mut self, proxy_id: ProxyId, mut st: Struct<Slice<'_>>) -> Result<()> {
+        let (subject, key, ty, value) =
+            st.read::<(GlobalId, Option<&str>, Option<&str>, Option<&str>)>()?;
+
+        tracing::trace!(?subject, key, ty, value);
+
+        self.proxies
+            .get_mut(proxy_id)?
+            .metadata
+            .apply(subject, key, ty, value);
+
+        self.ops
+            .push_back(Op::MetadataProperty(MetadataPropertyEvent {
+                proxy_id,
+                subject,
+                key: key.map(String::from),
+                ty: ty.map(String::from),
+                value: value.map(String::from),
+            }));
+
+        let Some(c) = &mut self.coordination else {
+            return Ok(());
+        };
+
+        if c.proxy != Some(proxy_id) {
+            return Ok(());
+        }
+
+        let mut events = Vec::new();
+        c.property(subject, key, value, &mut events);
+        self.ops.extend(events.into_iter().map(Op::Coordination));
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, st))]
+    fn node_info(&mut self, proxy_id: ProxyId, mut st: Struct<Slice<'_>>) -> Result<()> {
+        if let Some(params) = node_info_params(&mut st)? {
+            self.info_params(proxy_id, params)?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, st))]
+    fn device_info(&mut self, proxy_id: ProxyId, mut st: Struct<Slice<'_>>) -> Result<()> {
+        let _id = st.read::<u32>()?;
+        let change_mask = st.read::<flags::DeviceInfoChangeFlags>()?;
+
+        if change_mask & flags::DeviceInfoChangeFlags::PARAMS {
+            let _props = st.read::<Struct<_>>()?;
+            let params = st.read::<Struct<_>>()?;
+            self.info_params(proxy_id, params)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle the parameter infos of a node or device, refreshing watched
+    /// parameters whose flags have changed.
+    ///
+    /// The server toggles [`flags::ParamFlags::SERIAL`] whenever the value of
+    /// a parameter changes, so any change in flags is treated as a change.
+    fn info_params(&mut self, proxy_id: ProxyId, mut params: Struct<Slice<'_>>) -> Result<()> {
+        let proxy = self.proxies.get_mut(proxy_id)?;
+        let n_params = params.read::<u32>()?;
+
+        let mut changed = Vec::new();
+
+        for _ in 0..n_params {
+            let (param, param_flags) = params.read::<(id::Param, flags::ParamFlags)>()?;
+
+            let previous = match proxy.param_flags.iter_mut().find(|(id, _)| *id == param) {
+                Some((_, previous)) => Some(core::mem::replace(previous, param_flags)),
+                None => {
+                    proxy.param_flags.push((param, param_flags));
+                    None
+                }
+            };
+
+            if previous.is_some_and(|previous| previous != param_flags)
+                && param_flags & flags::ParamFlags::READ
+                && proxy.watched.contains(&param)
+            {
+                changed.push(param);
+            }
+        }
+
+        if !changed.is_empty() {
+            let now = utils::get_monotonic_nsec()?;
+            self.refresh.request(proxy_id, &changed, now);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, st))]
+    fn proxy_param(&mut self, proxy_id: ProxyId, mut st: Struct<Slice<'_>>) -> Result<()> {
+        let (seq, id, index, next) = st.read::<(i32, id::Param, u32, u32)>()?;
+
+        let value = match st.field()?.read_option()? {
+            Some(value) => Some(value.read_object()?.to_owned()?),
+            None => None,
+        };
+
+        tracing::trace!(seq, ?id, index, next);
+
+        let proxy = self.proxies.get_mut(proxy_id)?;
+
+        if id == id::Param::ROUTE
+            && !proxy.pending_routes.is_empty()
+            && let Some(value) = &value
+        {
+            // NB: A route which can't be decoded can't acknowledge a change,
+            // but is still cached like any other parameter.
+            match RouteVolume::read(value.as_ref()) {
+                Ok(reported) => {
+                    // Routes are identified by their index together with the
+                    // device they belong to.
+                    let pending = proxy.pending_routes.iter().position(|p| {
+                        p.route.index == reported.route.index
+                            && p.route.device == reported.route.device
+                    });
+
+                    if let Some(n) = pending {
+                        let pending = proxy.pending_routes.swap_remove(n);
+
+                        self.ops.push_back(Op::RouteVolume {
+                            proxy_id,
+                            route: reported.route,
+                            applied: pending.matches(&reported),
+                        });
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(?proxy_id, %error, "Failed to decode route");
                 }
             }
         }
 
+        proxy.params.insert(seq, id, index, value);
+
+        self.ops.push_back(Op::ProxyParam {
+            proxy_id,
+            param: id,
+        });
+
         Ok(())
     }
 
@@ -1084,7 +3947,14 @@ impl Stream {
                 }
             }
             _ => {
-                tracing::warn!(?id, "Unsupported IO type in set IO");
+                warn_limited!(
+                    self.warnings,
+                    "set-io",
+                    Some(node_id),
+                    id.into_id(),
+                    ?id,
+                    "Unsupported IO type in set IO"
+                );
                 return Ok(());
             }
         }
@@ -1114,6 +3984,12 @@ impl Stream {
             id::NodeCommand::PAUSE => {
                 self.ops.push_back(Op::NodePause { node_id });
             }
+            id::NodeCommand::PARAM_BEGIN => {
+                node.begin_params();
+            }
+            id::NodeCommand::PARAM_END => {
+                node.commit();
+            }
             _ => {
                 tracing::warn!(?object_id, "Unsupported command");
             }
@@ -1136,21 +4012,94 @@ impl Stream {
         let flags = st.field()?.read_sized::<u32>()?;
 
         let port = node.ports.get_mut(direction, port_id)?;
+        let previous = port.format.clone();
 
         let what = if let Some(value) = st.read::<Option<Object<Slice<'_>>>>()? {
             tracing::trace!(?id, flags, object = ?value, "set");
+
+            let format = if id == id::Param::FORMAT {
+                value.as_ref().read::<object::Format>().ok()
+            } else {
+                None
+            };
+
+            if id == id::Param::FORMAT && port.midi {
+                let is_control = format
+                    .as_ref()
+                    .is_some_and(|f| f.media_sub_type == id::MediaSubType::CONTROL);
+
+                if !is_control {
+                    tracing::warn!(?direction, ?port_id, ?format, "Rejecting non-MIDI format");
+                    return self.reject_format(node_id, direction, port_id, None);
+                }
+            } else if id == id::Param::FORMAT {
+                let audio = value.as_ref().read::<object::AudioFormat>().ok();
+
+                if let Some(spec) = &self.format_spec
+                    && !audio.as_ref().is_some_and(|audio| spec.accepts(audio))
+                {
+                    tracing::warn!(?direction, ?port_id, ?audio, "Rejecting unsupported format");
+                    return self.reject_format(node_id, direction, port_id, audio);
+                }
+
+                port.format = audio;
+            }
+
             port.params.set(id, [PortParam::with_flags(value, flags)])?;
-            NodeUpdateWhat::SetPortParam(direction, port_id, id)
+            NodeUpdateWhat::SetPortParam(direction, port_id, id, format)
         } else {
             tracing::trace!(?id, flags, "remove");
             _ = port.params.remove(id);
+
+            if id == id::Param::FORMAT {
+                port.format = None;
+            }
+
             NodeUpdateWhat::RemovePortParam(direction, port_id, id)
         };
 
+        let changed = (port.format != previous).then(|| port.format.clone());
+
         self.ops.push_back(Op::NodeUpdate {
             node_id,
             what: Some(what),
         });
+
+        if let Some(format) = changed {
+            self.ops.push_back(Op::FormatChanged(FormatChangedEvent {
+                node_id,
+                direction,
+                port_id,
+                format,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Report a format which is not accepted by a port to the server as an
+    /// error on the node, and emit it as [`StreamEvent::FormatRejected`].
+    fn reject_format(
+        &mut self,
+        node_id: ClientNodeId,
+        direction: Direction,
+        port_id: PortId,
+        format: Option<object::AudioFormat>,
+    ) -> Result<()> {
+        let node = self.client_nodes.get(node_id)?;
+
+        let message = format!("Format of {direction:?} port {port_id} is not supported");
+
+        self.c
+            .core_error(node.id, self.header.seq(), -libc::EINVAL, &message)?;
+
+        self.ops.push_back(Op::FormatRejected(FormatRejectedEvent {
+            node_id,
+            direction,
+            port_id,
+            format,
+        }));
+
         Ok(())
     }
 
@@ -1254,6 +4203,8 @@ impl Stream {
             "UseBuffers"
         );
 
+        let n_buffers = buffers.len();
+
         let buffers = Buffers {
             direction,
             port_id,
@@ -1265,18 +4216,15 @@ impl Stream {
 
         node.ports
             .get_mut(direction, port_id)?
-            .replace_buffers(buffers, |b| {
-                for buffer in b.buffers {
-                    for meta in buffer.metas {
-                        self.memory.free(meta.region);
-                    }
+            .replace_buffers(buffers, |b| free_buffers(&mut self.memory, b));
 
-                    for data in buffer.datas {
-                        self.memory.free(data.region);
-                        self.memory.free(data.chunk);
-                    }
-                }
-            });
+        self.ops.push_back(Op::UseBuffers(UseBuffersEvent {
+            node_id,
+            direction,
+            port_id,
+            mix_id,
+            n_buffers,
+        }));
 
         Ok(())
     }
@@ -1370,7 +4318,14 @@ impl Stream {
                 }
             }
             id => {
-                tracing::warn!(?id, "Unsupported IO type in port set IO");
+                warn_limited!(
+                    self.warnings,
+                    "port-set-io",
+                    Some(node_id),
+                    id.into_id(),
+                    ?id,
+                    "Unsupported IO type in port set IO"
+                );
                 return Ok(());
             }
         }
@@ -1461,6 +4416,23 @@ fn frame<'buf>(buf: &'buf mut RecvBuf, header: &Header) -> Result<Option<Pod<Sli
     Ok(Some(Pod::new(pod::buf::slice(bytes))))
 }
 
+/// Read a node info event up to its parameter infos, returning them if they
+/// have changed.
+fn node_info_params<'de>(st: &mut Struct<Slice<'de>>) -> Result<Option<Struct<Slice<'de>>>> {
+    let (_id, _max_input_ports, _max_output_ports) = st.read::<(u32, u32, u32)>()?;
+    let change_mask = st.read::<flags::NodeInfoChangeFlags>()?;
+
+    if !(change_mask & flags::NodeInfoChangeFlags::PARAMS) {
+        return Ok(None);
+    }
+
+    // NB: The remaining fields are only needed to reach the params.
+    let (_n_input_ports, _n_output_ports) = st.read::<(u32, u32)>()?;
+    let (_state, _error) = st.read::<(Id<u32>, Option<&str>)>()?;
+    let _props = st.read::<Struct<_>>()?;
+    Ok(Some(st.read::<Struct<_>>()?))
+}
+
 #[derive(Default, Debug)]
 struct CoreState {
     id: u32,
@@ -1478,34 +4450,99 @@ struct ClientState {
     props: Properties,
 }
 
-#[derive(Debug)]
-struct RegistryEntry {
-    id: GlobalId,
-    permissions: i32,
-    ty: String,
-    version: u32,
-    props: Properties,
+/// The state of the connection to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionState {
+    /// The stream is connected.
+    Connected,
+    /// The connection has hung up and is waiting to be torn down.
+    Lost,
+    /// The connection has been torn down and is waiting to be re-established.
+    Disconnected,
+    /// A new connection has been established, and is waiting for the initial
+    /// registry sync.
+    Resuming,
 }
 
 #[derive(Debug)]
 enum Kind {
     Registry,
     ClientNode(ClientNodeId),
+    Proxy(ProxyId),
+    SecurityContext,
+}
+
+/// A link restored from a session, which is created once both of its ports
+/// have been announced.
+#[derive(Debug)]
+struct PendingSessionLink {
+    node_id: ClientNodeId,
+    link: SessionLink,
+}
+
+/// A sidechain fed into a node from a port elsewhere in the graph.
+#[derive(Debug)]
+struct Sidechain {
+    node_id: ClientNodeId,
+    /// The input port the sidechain is aligned to.
+    host: PortId,
+    /// The input port the sidechain is fed into.
+    port: PortId,
+    /// The global port feeding the sidechain.
+    source: GlobalId,
+    /// The global id of the sidechain port, once announced.
+    port_global: Option<GlobalId>,
+    /// The link feeding the sidechain, once created.
+    link: Option<LocalId>,
 }
 
 #[derive(Debug)]
 enum NodeUpdateWhat {
     SetNodeParam(id::Param),
     RemoveNodeParam(id::Param),
-    SetPortParam(Direction, PortId, id::Param),
+    SetPortParam(Direction, PortId, id::Param, Option<object::Format>),
     RemovePortParam(Direction, PortId, id::Param),
 }
 
+impl NodeUpdateWhat {
+    fn into_event(self, node_id: ClientNodeId) -> StreamEvent {
+        match self {
+            NodeUpdateWhat::SetNodeParam(param) => {
+                StreamEvent::SetNodeParam(SetNodeParamEvent { node_id, param })
+            }
+            NodeUpdateWhat::RemoveNodeParam(param) => {
+                StreamEvent::RemoveNodeParam(RemoveNodeParamEvent { node_id, param })
+            }
+            NodeUpdateWhat::SetPortParam(direction, port_id, param, format) => {
+                StreamEvent::SetPortParam(SetPortParamEvent {
+                    node_id,
+                    direction,
+                    port_id,
+                    param,
+                    format,
+                })
+            }
+            NodeUpdateWhat::RemovePortParam(direction, port_id, param) => {
+                StreamEvent::RemovePortParam(RemovePortParamEvent {
+                    node_id,
+                    direction,
+                    port_id,
+                    param,
+                })
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 enum Op {
     CoreHello,
+    ClientUpdateProperties,
     GetRegistry,
     CoreStarted,
+    Reconnected,
+    Shutdown,
+    Synced(u32),
     Pong {
         id: u32,
         seq: u32,
@@ -1526,6 +4563,39 @@ enum Op {
     NodeReadInterest {
         node_id: ClientNodeId,
     },
+    ProxyParam {
+        proxy_id: ProxyId,
+        param: id::Param,
+    },
+    RouteVolume {
+        proxy_id: ProxyId,
+        route: RouteId,
+        applied: bool,
+    },
+    NodeSuspended {
+        node_id: ClientNodeId,
+    },
+    NodeResumed {
+        node_id: ClientNodeId,
+    },
+    NodeOverload {
+        node_id: ClientNodeId,
+        decision: OverloadDecision,
+        dropped: u32,
+    },
+    UseBuffers(UseBuffersEvent),
+    FormatChanged(FormatChangedEvent),
+    FormatRejected(FormatRejectedEvent),
+    PresetRejected(PresetRejectedEvent),
+    Clip(ClipEvent),
+    MetadataProperty(MetadataPropertyEvent),
+    GlobalAdded(GlobalObject),
+    GlobalRemoved(GlobalObject),
+    RateChanged(RateChangedEvent),
+    QuantumChanged(QuantumChangedEvent),
+    Coordination(CoordinationEvent),
+    UnknownMessage(UnknownMessageEvent),
+    Message(MessageEvent),
 }
 
 #[derive(Debug)]
@@ -1557,3 +4627,46 @@ impl GlobalMap {
         self.global_to_local.remove(&global_id)
     }
 }
+
+/// Test if any region in the set of buffers refers to revoked memory.
+fn buffers_revoked(memory: &Memory, buffers: &Buffers) -> bool {
+    buffers.buffers.iter().any(|buffer| {
+        let metas = buffer.metas.iter().any(|m| memory.is_revoked(&m.region));
+
+        let datas = buffer
+            .datas
+            .iter()
+            .any(|d| memory.is_revoked(&d.region) || memory.is_revoked(&d.chunk));
+
+        metas || datas
+    })
+}
+
+/// Free all regions used by a set of buffers.
+fn free_buffers(memory: &mut Memory, buffers: Buffers) {
+    for buffer in buffers.buffers {
+        for meta in buffer.metas {
+            memory.free(meta.region);
+        }
+
+        for data in buffer.datas {
+            memory.free(data.region);
+            memory.free(data.chunk);
+        }
+    }
+}
+
+/// Resolve layered properties to send with an update, which are only
+/// considered modified if the most specific layer was.
+fn resolve_for_update<'a>(
+    modified: bool,
+    layers: impl IntoIterator<Item = (PropertyLayer, &'a Properties)>,
+) -> Properties {
+    let mut props = ResolvedProperties::resolve(layers).to_properties();
+
+    if !modified {
+        props.take_modified();
+    }
+
+    props
+}
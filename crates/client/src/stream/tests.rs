@@ -0,0 +1,451 @@
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+
+use alloc::vec::Vec;
+
+use pod::Id;
+use pod::buf::ArrayVec;
+use protocol::buf::RecvBuf;
+use protocol::consts::Direction;
+use protocol::object::AudioFormat;
+use protocol::poll::{PollEvent, Token};
+use protocol::{Connection, Poll, Properties, consts, flags, id, prop};
+
+use super::{Op, node_info_params};
+use crate::events::StreamEvent;
+use crate::{
+    ChannelStrip, ClientNode, FormatSpec, GlobalId, LocalId, Ports, RegistryFilter, RouteId, Stream,
+};
+
+/// Construct a stream over a socket pair, returning the peer to keep it open.
+fn stream() -> anyhow::Result<(Stream, UnixStream)> {
+    let (a, b) = UnixStream::pair()?;
+    let mut c = Connection::from_socket(a);
+    c.set_nonblocking(true)?;
+    let stream = Stream::new(c, Properties::new())?;
+    Ok((stream, b))
+}
+
+/// Write a registry global the way the server marshals it.
+fn global(id: u32, ty: &str, name: &str) -> Result<pod::Builder<pod::DynamicBuf>, pod::Error> {
+    global_with(id, flags::Permission::R, ty, name)
+}
+
+/// Write a registry global with the given permissions.
+fn global_with(
+    id: u32,
+    permissions: flags::Permission,
+    ty: &str,
+    name: &str,
+) -> Result<pod::Builder<pod::DynamicBuf>, pod::Error> {
+    let mut pod = pod::dynamic();
+
+    pod.as_mut().write_struct(|st| {
+        st.field().write(id)?;
+        st.field().write(permissions)?;
+        st.field().write(ty)?;
+        st.field().write(3i32)?;
+
+        st.field().write_struct(|props| {
+            props.field().write(1i32)?;
+            props.field().write(prop::NODE_NAME.as_str())?;
+            props.field().write(name)?;
+            Ok(())
+        })
+    })?;
+
+    Ok(pod)
+}
+
+/// Write a registry global removal.
+fn global_remove(id: u32) -> Result<pod::Builder<pod::DynamicBuf>, pod::Error> {
+    let mut pod = pod::dynamic();
+    pod.as_mut().write_struct(|st| st.field().write(id))?;
+    Ok(pod)
+}
+
+#[test]
+fn filtered_large_ids() -> anyhow::Result<()> {
+    let (mut stream, _peer) = stream()?;
+    stream.add_registry_filter(RegistryFilter::new().interface(consts::INTERFACE_NODE));
+
+    // NB: Global ids are not bounded by the number of local ids.
+    for id in [5, 127, 128, 1000, 70000] {
+        stream.registry_global(
+            global(id, consts::INTERFACE_DEVICE, "device")?
+                .as_ref()
+                .read_struct()?,
+        )?;
+    }
+
+    stream.registry_global(
+        global(4000, consts::INTERFACE_NODE, "node")?
+            .as_ref()
+            .read_struct()?,
+    )?;
+
+    assert_eq!(stream.filtered.len(), 5);
+    assert!(stream.registry().get(GlobalId::new(1000)).is_none());
+    assert!(stream.registry().get(GlobalId::new(4000)).is_some());
+
+    for id in [5, 127, 128, 1000, 70000, 4000] {
+        stream.registry_global_remove(global_remove(id)?.as_ref().read_struct()?)?;
+    }
+
+    assert!(stream.filtered.is_empty());
+    assert!(stream.registry().get(GlobalId::new(4000)).is_none());
+    Ok(())
+}
+
+/// Write a node info the way the server marshals it.
+fn node_info(
+    change_mask: flags::NodeInfoChangeFlags,
+) -> Result<pod::Builder<pod::DynamicBuf>, pod::Error> {
+    let mut pod = pod::dynamic();
+
+    pod.as_mut().write_struct(|st| {
+        st.field().write(42i32)?;
+        st.field().write(1i32)?;
+        st.field().write(2i32)?;
+        st.field().write(change_mask)?;
+        st.field().write(1i32)?;
+        st.field().write(2i32)?;
+        // NB: The state is an enum which is sent as an id.
+        st.field().write(Id(3u32))?;
+        st.field().write(None::<&str>)?;
+
+        st.field().write_struct(|props| {
+            props.field().write(1i32)?;
+            props.field().write("node.name")?;
+            props.field().write("test")?;
+            Ok(())
+        })?;
+
+        st.field().write_struct(|params| {
+            params.field().write(2i32)?;
+            params.field().write(id::Param::PROPS)?;
+            params.field().write(flags::ParamFlags::READWRITE)?;
+            params.field().write(id::Param::FORMAT)?;
+            params.field().write(flags::ParamFlags::READ)?;
+            Ok(())
+        })?;
+
+        Ok(())
+    })?;
+
+    Ok(pod)
+}
+
+#[test]
+fn node_info_with_params() -> anyhow::Result<()> {
+    let pod = node_info(flags::NodeInfoChangeFlags::STATE | flags::NodeInfoChangeFlags::PARAMS)?;
+    let mut st = pod.as_ref().read_struct()?;
+
+    let mut params = node_info_params(&mut st)?.expect("params changed");
+    assert!(st.is_empty());
+
+    assert_eq!(params.read::<u32>()?, 2);
+
+    let (param, param_flags) = params.read::<(id::Param, flags::ParamFlags)>()?;
+    assert_eq!(param, id::Param::PROPS);
+    assert_eq!(param_flags, flags::ParamFlags::READWRITE);
+
+    let (param, param_flags) = params.read::<(id::Param, flags::ParamFlags)>()?;
+    assert_eq!(param, id::Param::FORMAT);
+    assert_eq!(param_flags, flags::ParamFlags::READ);
+    assert!(params.is_empty());
+    Ok(())
+}
+
+#[test]
+fn node_info_without_params() -> anyhow::Result<()> {
+    let pod = node_info(flags::NodeInfoChangeFlags::STATE)?;
+    let mut st = pod.as_ref().read_struct()?;
+    assert!(node_info_params(&mut st)?.is_none());
+    Ok(())
+}
+
+/// Encode a done event from the core the way the server does.
+fn core_done(id: i32, seq: u32) -> anyhow::Result<Vec<u8>> {
+    let mut pod = pod::dynamic();
+
+    pod.as_mut().write_struct(|st| {
+        st.field().write(id)?;
+        st.field().write(seq as i32)?;
+        Ok(())
+    })?;
+
+    let body = pod.as_buf().as_bytes();
+
+    let mut message = Vec::new();
+    message.extend_from_slice(&consts::CORE_ID.to_ne_bytes());
+    message.extend_from_slice(&((1u32 << 24) | body.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(body);
+    Ok(message)
+}
+
+#[test]
+fn capture_messages() -> anyhow::Result<()> {
+    let (mut stream, mut peer) = stream()?;
+    stream.set_capture_messages(true);
+
+    let seq = stream.sync()?;
+    let message = core_done(0x6000, seq)?;
+    peer.write_all(&message)?;
+
+    let mut poll = Poll::new()?;
+    let mut recv = RecvBuf::new();
+    let mut events = ArrayVec::<PollEvent, 4>::new();
+    let mut output = Vec::new();
+
+    loop {
+        while let Some(ev) = stream.run(&mut poll, &mut recv)? {
+            output.push(ev);
+        }
+
+        if !output.is_empty() {
+            break;
+        }
+
+        poll.poll(&mut events)?;
+
+        while let Some(e) = events.pop() {
+            stream.drive(&mut recv, e)?;
+        }
+    }
+
+    let [StreamEvent::Message(captured), StreamEvent::Synced(synced)] = &output[..] else {
+        panic!("unexpected events: {output:?}");
+    };
+
+    assert_eq!(captured.interface, "core");
+    assert_eq!(captured.id, consts::CORE_ID);
+    assert_eq!(captured.op, 1);
+    assert_eq!(captured.fds, 0);
+    // NB: The payload holds the fields of the message without the struct
+    // header.
+    assert_eq!(captured.payload, message[24..]);
+    assert_eq!(*synced, seq);
+    Ok(())
+}
+
+#[test]
+fn reject_unsupported_format() -> anyhow::Result<()> {
+    let (mut stream, _peer) = stream()?;
+    stream.set_format_spec(Some(FormatSpec::audio().with_rate(44100, 48000, 48000)));
+
+    let mut ports = Ports::new();
+    let port_id = ports.insert(Direction::INPUT)?.id;
+
+    let node = ClientNode::new(
+        LocalId::new(3),
+        ports,
+        Token::new(10),
+        Token::new(11),
+        stream.memory.epoch().reader(),
+        Properties::new(),
+    )?;
+
+    let node_id = stream.client_nodes.insert(node)?;
+
+    let format = AudioFormat {
+        media_type: id::MediaType::AUDIO,
+        media_sub_type: id::MediaSubType::RAW,
+        format: id::AudioFormat::F32P,
+        channels: 2,
+        rate: 96000,
+    };
+
+    let mut pod = pod::dynamic();
+
+    pod.as_mut().write_struct(|st| {
+        st.field().write(Direction::INPUT)?;
+        st.field().write(port_id)?;
+        st.field().write(id::Param::FORMAT)?;
+        st.field().write(0u32)?;
+        st.field().write(&format)?;
+        Ok(())
+    })?;
+
+    stream.ops.clear();
+    stream.client_node_port_set_param(node_id, pod.as_ref().read_struct()?)?;
+
+    let node = stream.client_nodes.get(node_id)?;
+    assert!(node.ports.get(Direction::INPUT, port_id)?.format.is_none());
+
+    let Some(Op::FormatRejected(event)) = stream.ops.pop_front() else {
+        panic!("expected the format to be rejected");
+    };
+
+    assert_eq!(event.node_id, node_id);
+    assert_eq!(event.port_id, port_id);
+    assert_eq!(event.format, Some(format));
+    assert!(stream.ops.is_empty());
+    Ok(())
+}
+
+#[test]
+fn client_nodes_keep_creation_order() -> anyhow::Result<()> {
+    let (mut stream, _peer) = stream()?;
+
+    let mut ids = Vec::new();
+
+    for n in 0..4 {
+        let node = ClientNode::new(
+            LocalId::new(n),
+            Ports::new(),
+            Token::new(10 + u64::from(n) * 2),
+            Token::new(11 + u64::from(n) * 2),
+            stream.memory.epoch().reader(),
+            Properties::new(),
+        )?;
+
+        ids.push(stream.client_nodes.insert(node)?);
+    }
+
+    assert!(stream.client_nodes.remove(ids[1]).is_some());
+    assert!(stream.client_nodes.remove(ids[1]).is_none());
+
+    let node = ClientNode::new(
+        LocalId::new(4),
+        Ports::new(),
+        Token::new(18),
+        Token::new(19),
+        stream.memory.epoch().reader(),
+        Properties::new(),
+    )?;
+
+    // The slot of the removed node is reused with a new generation.
+    let reused = stream.client_nodes.insert(node)?;
+    assert_eq!(reused.index(), ids[1].index());
+    assert!(stream.client_nodes.get(ids[1]).is_err());
+
+    let order = stream
+        .client_nodes
+        .iter_mut_with_id()
+        .map(|(id, node)| (id, node.id.into_u32()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(order, [(ids[0], 0), (ids[2], 2), (ids[3], 3), (reused, 4)]);
+
+    assert_eq!(stream.client_nodes.get(ids[3])?.id, LocalId::new(3));
+    assert_eq!(stream.client_nodes.id_at(ids[3].index_u32()), Some(ids[3]));
+    Ok(())
+}
+
+#[test]
+fn batched_route_volumes() -> anyhow::Result<()> {
+    let (mut stream, _peer) = stream()?;
+    stream.registry_id = Some(LocalId::new(2));
+
+    let perm = flags::Permission::R | flags::Permission::W | flags::Permission::X;
+
+    stream.registry_global(
+        global_with(40, perm, consts::INTERFACE_DEVICE, "device")?
+            .as_ref()
+            .read_struct()?,
+    )?;
+
+    let proxy_id = stream.bind(GlobalId::new(40))?;
+    let first = RouteId {
+        index: 1,
+        device: 0,
+    };
+    let second = RouteId {
+        index: 2,
+        device: 1,
+    };
+
+    let mut device = stream.device(proxy_id)?;
+    device.begin_params();
+    device.begin_params();
+    device.set_route_volume(first, &[0.25], false, false)?;
+    device.set_route_volume(second, &[0.5], false, false)?;
+    device.set_route_volume(first, &[0.75], true, false)?;
+    device.commit()?;
+
+    // Nothing is sent until the outermost batch is committed.
+    assert!(device.is_batching());
+    assert_eq!(device.pending_routes().count(), 0);
+
+    device.commit()?;
+    assert!(!device.is_batching());
+
+    let pending = device
+        .pending_routes()
+        .map(|p| (p.route, p.channel_volumes.clone(), p.mute))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        pending,
+        [
+            (second, Vec::from([0.5]), false),
+            (first, Vec::from([0.75]), true)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn load_preset_validates_before_applying() -> anyhow::Result<()> {
+    let (mut stream, _peer) = stream()?;
+
+    let node = ClientNode::new(
+        LocalId::new(3),
+        Ports::new(),
+        Token::new(10),
+        Token::new(11),
+        stream.memory.epoch().reader(),
+        Properties::new(),
+    )?;
+
+    let node_id = stream.client_nodes.insert(node)?;
+
+    let node = stream.client_nodes.get_mut(node_id)?;
+    let strip = node.add_strip(ChannelStrip::new(48000));
+    node.props.insert(prop::NODE_DESCRIPTION, "Saved");
+
+    let mut pod = pod::dynamic();
+    node.write_preset(pod.as_mut())?;
+
+    let dir = std::env::temp_dir().join(std::format!("livemix-load-preset-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+
+    let valid = dir.join("valid.preset");
+    std::fs::write(&valid, pod.as_buf().as_bytes())?;
+
+    // A preset which is cut short in the middle of the strip presets.
+    let truncated = dir.join("truncated.preset");
+    let bytes = pod.as_buf().as_bytes();
+    std::fs::write(&truncated, &bytes[..bytes.len() - 8])?;
+
+    node.props.insert(prop::NODE_DESCRIPTION, "Changed");
+    stream.ops.clear();
+
+    for path in [truncated, dir.join("missing.preset")] {
+        assert!(!stream.load_preset(node_id, &path)?);
+
+        let Some(Op::PresetRejected(event)) = stream.ops.pop_front() else {
+            panic!("expected the preset to be rejected");
+        };
+
+        assert_eq!(event.node_id, node_id);
+        assert_eq!(event.path, path);
+
+        let node = stream.client_nodes.get(node_id)?;
+        assert_eq!(node.props.get(prop::NODE_DESCRIPTION), Some("Changed"));
+    }
+
+    assert!(stream.load_preset(node_id, &valid)?);
+    assert!(stream.ops.is_empty());
+
+    let node = stream.client_nodes.get(node_id)?;
+    assert_eq!(node.props.get(prop::NODE_DESCRIPTION), Some("Saved"));
+    assert!(node.strip(strip).is_some());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
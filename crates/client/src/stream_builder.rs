@@ -0,0 +1,208 @@
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use protocol::param::{EnumFormatBuilder, IntChoice};
+use protocol::{Connection, ConnectionOptions, Properties, id, object};
+
+use crate::Stream;
+
+/// The audio formats accepted by the ports of a stream.
+///
+/// This is used to write the [`ENUM_FORMAT`] parameter of ports and to
+/// validate the [`FORMAT`] the server settles on, see [`StreamBuilder`].
+///
+/// [`ENUM_FORMAT`]: id::Param::ENUM_FORMAT
+/// [`FORMAT`]: id::Param::FORMAT
+///
+/// # Examples
+///
+/// ```
+/// use client::FormatSpec;
+/// use protocol::id;
+/// use protocol::object::AudioFormat;
+///
+/// let spec = FormatSpec::audio()
+///     .with_formats(&[id::AudioFormat::F32P, id::AudioFormat::S16])
+///     .with_channels(1, 2, 2)
+///     .with_rate(44100, 96000, 48000);
+///
+/// let format = AudioFormat {
+///     media_type: id::MediaType::AUDIO,
+///     media_sub_type: id::MediaSubType::RAW,
+///     format: id::AudioFormat::S16,
+///     channels: 2,
+///     rate: 44100,
+/// };
+///
+/// assert!(spec.accepts(&format));
+/// assert!(!spec.accepts(&AudioFormat { channels: 4, ..format.clone() }));
+/// assert!(!spec.accepts(&AudioFormat { rate: 22050, ..format }));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSpec {
+    media_sub_type: id::MediaSubType,
+    formats: Vec<id::AudioFormat>,
+    channels: (u32, u32, u32),
+    rate: (u32, u32, u32),
+}
+
+impl FormatSpec {
+    /// Accept raw audio.
+    ///
+    /// By default this accepts mono 32-bit planar floating point samples at
+    /// any rate from 8000 to 192000, preferring 48000.
+    pub fn audio() -> Self {
+        Self::new(id::MediaSubType::RAW)
+    }
+
+    /// Accept audio in the DSP format, which is mono non-interleaved 32-bit
+    /// floating point.
+    pub fn audio_dsp() -> Self {
+        Self::new(id::MediaSubType::DSP)
+    }
+
+    fn new(media_sub_type: id::MediaSubType) -> Self {
+        Self {
+            media_sub_type,
+            formats: Vec::from([id::AudioFormat::F32P]),
+            channels: (1, 1, 1),
+            rate: (8000, 192000, 48000),
+        }
+    }
+
+    /// Accept any of the given sample formats, where the first one is
+    /// preferred.
+    pub fn with_formats(mut self, formats: &[id::AudioFormat]) -> Self {
+        self.formats = Vec::from(formats);
+        self
+    }
+
+    /// Accept a number of channels in the range `min..=max`, preferring
+    /// `default`.
+    pub fn with_channels(self, min: u32, max: u32, default: u32) -> Self {
+        Self {
+            channels: (min, max, default.clamp(min, max)),
+            ..self
+        }
+    }
+
+    /// Accept a sample rate in the range `min..=max`, preferring `default`.
+    pub fn with_rate(self, min: u32, max: u32, default: u32) -> Self {
+        Self {
+            rate: (min, max, default.clamp(min, max)),
+            ..self
+        }
+    }
+
+    /// Get the [`ENUM_FORMAT`] parameter describing the spec.
+    ///
+    /// [`ENUM_FORMAT`]: id::Param::ENUM_FORMAT
+    pub fn enum_format(&self) -> EnumFormatBuilder<'_> {
+        EnumFormatBuilder::new(id::MediaType::AUDIO, self.media_sub_type)
+            .format_any(&self.formats)
+            .channels(choice(self.channels))
+            .rate(choice(self.rate))
+    }
+
+    /// Test if the given format is accepted by the spec.
+    pub fn accepts(&self, format: &object::AudioFormat) -> bool {
+        let (min_channels, max_channels, _) = self.channels;
+        let (min_rate, max_rate, _) = self.rate;
+
+        format.media_type == id::MediaType::AUDIO
+            && format.media_sub_type == self.media_sub_type
+            && self.formats.contains(&format.format)
+            && (min_channels..=max_channels).contains(&format.channels)
+            && (min_rate..=max_rate).contains(&format.rate)
+    }
+}
+
+fn choice((min, max, default): (u32, u32, u32)) -> IntChoice {
+    if min == max {
+        IntChoice::Fixed(min)
+    } else {
+        IntChoice::range(min, max, default)
+    }
+}
+
+/// A builder for a [`Stream`] which negotiates the format of its ports.
+///
+/// Every port of a node created through the stream which doesn't have an
+/// [`ENUM_FORMAT`] parameter when the node is updated has one written from
+/// the [`FormatSpec`]. Formats set by the server are validated against the
+/// spec, where accepted formats are stored in [`Port::format`] and surfaced
+/// through [`StreamEvent::FormatChanged`]. Other formats are rejected by
+/// reporting an error on the node to the server, and are surfaced through
+/// [`StreamEvent::FormatRejected`].
+///
+/// [`ENUM_FORMAT`]: id::Param::ENUM_FORMAT
+/// [`Port::format`]: crate::Port::format
+/// [`StreamEvent::FormatChanged`]: crate::events::StreamEvent::FormatChanged
+/// [`StreamEvent::FormatRejected`]: crate::events::StreamEvent::FormatRejected
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{FormatSpec, StreamBuilder};
+/// use protocol::{Connection, Properties, id};
+///
+/// let connection = Connection::open()?;
+///
+/// let stream = StreamBuilder::new(connection)
+///     .with_props(Properties::new())
+///     .with_formats(
+///         FormatSpec::audio()
+///             .with_formats(&[id::AudioFormat::F32P, id::AudioFormat::S16])
+///             .with_channels(1, 8, 2),
+///     )
+///     .build()?;
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+pub struct StreamBuilder {
+    connection: Connection,
+    props: Properties,
+    formats: Option<FormatSpec>,
+    reconnect: Option<ConnectionOptions>,
+}
+
+impl StreamBuilder {
+    /// Construct a new builder for a stream over the given connection.
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            props: Properties::new(),
+            formats: None,
+            reconnect: None,
+        }
+    }
+
+    /// Set the properties of the client.
+    pub fn with_props(self, props: Properties) -> Self {
+        Self { props, ..self }
+    }
+
+    /// Set the formats accepted by ports.
+    pub fn with_formats(self, formats: FormatSpec) -> Self {
+        Self {
+            formats: Some(formats),
+            ..self
+        }
+    }
+
+    /// Reconnect with the given options if the connection to the server is
+    /// lost, see [`Stream::set_reconnect`].
+    pub fn with_reconnect(self, options: ConnectionOptions) -> Self {
+        Self {
+            reconnect: Some(options),
+            ..self
+        }
+    }
+
+    /// Build the stream.
+    pub fn build(self) -> Result<Stream> {
+        let mut stream = Stream::new(self.connection, self.props)?;
+        stream.set_format_spec(self.formats);
+        stream.set_reconnect(self.reconnect);
+        Ok(stream)
+    }
+}
@@ -0,0 +1,227 @@
+use core::fmt;
+use core::ops::BitOr;
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use crate::events::StreamEvent;
+
+#[cfg(test)]
+mod tests;
+
+/// The default number of events which can be queued for a subscription.
+pub(crate) const DEFAULT_CAPACITY: usize = 256;
+
+/// A class of stream events which can be subscribed to.
+///
+/// Classes can be combined with `|`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct EventClasses(u32);
+
+impl EventClasses {
+    /// No events.
+    pub const NONE: Self = Self(0);
+    /// Processing events, see [`StreamEvent::Process`].
+    pub const PROCESS: Self = Self(1 << 0);
+    /// Objects which have been created, see [`StreamEvent::ObjectCreated`],
+    /// and global objects which have been added to or removed from the
+    /// registry.
+    pub const OBJECTS: Self = Self(1 << 1);
+    /// Parameters, buffers, route volumes and metadata properties which have
+    /// changed, and formats or presets which have been rejected.
+    pub const PARAMS: Self = Self(1 << 2);
+    /// Changes to the state of the stream or its nodes, like it being started,
+    /// nodes being suspended, overloaded or changing rate or quantum,
+    /// coordination with other instances, synchronizations completing, or
+    /// the stream shutting down.
+    pub const STATE: Self = Self(1 << 3);
+    /// Protocol messages which were not understood or have been captured, see
+    /// [`StreamEvent::UnknownMessage`] and [`StreamEvent::Message`].
+    pub const PROTOCOL: Self = Self(1 << 4);
+    /// All events.
+    pub const ALL: Self = Self(0b11111);
+
+    /// Get the class of the given event.
+    pub fn of(event: &StreamEvent) -> Self {
+        match event {
+            StreamEvent::Process(..) => Self::PROCESS,
+            StreamEvent::ObjectCreated(..)
+            | StreamEvent::GlobalAdded(..)
+            | StreamEvent::GlobalRemoved(..) => Self::OBJECTS,
+            StreamEvent::SetNodeParam(..)
+            | StreamEvent::RemoveNodeParam(..)
+            | StreamEvent::SetPortParam(..)
+            | StreamEvent::RemovePortParam(..)
+            | StreamEvent::FormatChanged(..)
+            | StreamEvent::FormatRejected(..)
+            | StreamEvent::PresetRejected(..)
+            | StreamEvent::UseBuffers(..)
+            | StreamEvent::ProxyParam(..)
+            | StreamEvent::RouteVolume(..)
+            | StreamEvent::MetadataProperty(..) => Self::PARAMS,
+            StreamEvent::Started
+            | StreamEvent::Disconnected
+            | StreamEvent::Reconnected
+            | StreamEvent::NodeSuspended(..)
+            | StreamEvent::NodeResumed(..)
+            | StreamEvent::Overload(..)
+            | StreamEvent::Clip(..)
+            | StreamEvent::RateChanged(..)
+            | StreamEvent::QuantumChanged(..)
+            | StreamEvent::Coordination(..)
+            | StreamEvent::Synced(..)
+            | StreamEvent::Shutdown => Self::STATE,
+            StreamEvent::UnknownMessage(..) | StreamEvent::Message(..) => Self::PROTOCOL,
+        }
+    }
+
+    /// Test if the set contains any of the given classes.
+    #[inline]
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for EventClasses {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Debug for EventClasses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(EventClasses, &str); 5] = [
+            (EventClasses::PROCESS, "PROCESS"),
+            (EventClasses::OBJECTS, "OBJECTS"),
+            (EventClasses::PARAMS, "PARAMS"),
+            (EventClasses::STATE, "STATE"),
+            (EventClasses::PROTOCOL, "PROTOCOL"),
+        ];
+
+        let mut f = f.debug_set();
+
+        for (class, name) in NAMES {
+            if self.intersects(class) {
+                f.entry(&format_args!("{name}"));
+            }
+        }
+
+        f.finish()
+    }
+}
+
+/// A subscription to a filtered set of events emitted by a [`Stream`].
+///
+/// Events are delivered over a bounded channel, so a subscription can be
+/// moved to and consumed by another thread. Dropping the subscription
+/// unsubscribes it.
+///
+/// Publishing never blocks the stream. If the subscriber falls behind and its
+/// channel is full, events are dropped and counted in
+/// [`Subscription::dropped`].
+///
+/// See [`Stream::subscribe`].
+///
+/// [`Stream`]: crate::Stream
+/// [`Stream::subscribe`]: crate::Stream::subscribe
+pub struct Subscription {
+    rx: mpsc::Receiver<StreamEvent>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl Subscription {
+    /// Receive the next event without blocking.
+    ///
+    /// Returns `None` if no event is available.
+    #[inline]
+    pub fn try_recv(&self) -> Option<StreamEvent> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Block until the next event is received.
+    ///
+    /// Returns `None` if the stream has been dropped.
+    #[inline]
+    pub fn recv(&self) -> Option<StreamEvent> {
+        self.rx.recv().ok()
+    }
+
+    /// Iterate over events which are available without blocking.
+    #[inline]
+    pub fn try_iter(&self) -> impl Iterator<Item = StreamEvent> + '_ {
+        self.rx.try_iter()
+    }
+
+    /// The number of events which have been dropped because the subscription
+    /// was full.
+    #[inline]
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+struct Subscriber {
+    classes: EventClasses,
+    tx: mpsc::SyncSender<StreamEvent>,
+    dropped: Arc<AtomicUsize>,
+}
+
+/// The subscribers of a stream.
+pub(crate) struct Subscribers {
+    entries: Vec<Subscriber>,
+}
+
+impl Subscribers {
+    /// Construct an empty set of subscribers.
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a subscriber to the given classes of events, which can have up to
+    /// `capacity` events queued.
+    pub(crate) fn subscribe(&mut self, classes: EventClasses, capacity: usize) -> Subscription {
+        let (tx, rx) = mpsc::sync_channel(capacity.max(1));
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        self.entries.push(Subscriber {
+            classes,
+            tx,
+            dropped: dropped.clone(),
+        });
+
+        Subscription { rx, dropped }
+    }
+
+    /// Publish an event to every interested subscriber, dropping subscribers
+    /// which have gone away.
+    pub(crate) fn publish(&mut self, event: &StreamEvent) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let class = EventClasses::of(event);
+
+        self.entries.retain(|s| {
+            if !s.classes.intersects(class) {
+                return true;
+            }
+
+            match s.tx.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(mpsc::TrySendError::Full(..)) => {
+                    s.dropped.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                Err(mpsc::TrySendError::Disconnected(..)) => false,
+            }
+        });
+    }
+}
@@ -0,0 +1,42 @@
+use crate::ClientNodeId;
+use crate::events::StreamEvent;
+
+use super::{EventClasses, Subscribers};
+
+#[test]
+fn bounded() {
+    let mut subscribers = Subscribers::new();
+    let slow = subscribers.subscribe(EventClasses::PROCESS, 2);
+    let fast = subscribers.subscribe(EventClasses::PROCESS, 8);
+
+    let node_id = ClientNodeId::new(0, 0);
+
+    for _ in 0..5 {
+        subscribers.publish(&StreamEvent::Process(node_id));
+    }
+
+    assert_eq!(slow.try_iter().count(), 2);
+    assert_eq!(slow.dropped(), 3);
+    assert_eq!(fast.try_iter().count(), 5);
+    assert_eq!(fast.dropped(), 0);
+
+    // NB: Once the subscriber has caught up it receives events again.
+    subscribers.publish(&StreamEvent::Process(node_id));
+    assert_eq!(slow.try_recv(), Some(StreamEvent::Process(node_id)));
+    assert_eq!(slow.dropped(), 3);
+}
+
+#[test]
+fn filtered_and_dropped() {
+    let mut subscribers = Subscribers::new();
+    let state = subscribers.subscribe(EventClasses::STATE, 1);
+    let gone = subscribers.subscribe(EventClasses::STATE, 1);
+    drop(gone);
+
+    subscribers.publish(&StreamEvent::Started);
+    subscribers.publish(&StreamEvent::Process(ClientNodeId::new(0, 0)));
+
+    assert_eq!(state.try_recv(), Some(StreamEvent::Started));
+    assert_eq!(state.try_recv(), None);
+    assert_eq!(subscribers.entries.len(), 1);
+}
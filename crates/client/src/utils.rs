@@ -1,12 +1,175 @@
 //! Various utility functions for working with pipewire clients.
 
+use core::fmt;
+use core::time::Duration;
+
 use std::io;
 use std::os::fd::RawFd;
+use std::time::SystemTime;
+
+const NSEC_PER_SEC: u64 = 1_000_000_000u64;
 
 /// Get the current monotonic time in nanoseconds.
 pub fn get_monotonic_nsec() -> io::Result<u64> {
-    const NSEC_PER_SEC: u64 = 1_000_000_000u64;
+    get_clock_nsec(libc::CLOCK_MONOTONIC)
+}
+
+/// Convert a monotonic timestamp in nanoseconds, like the ones used by the
+/// graph clock, into wall-clock time.
+///
+/// This samples both clocks, so the result is subject to any adjustments of
+/// the wall-clock which has happened since the timestamp was taken.
+pub fn monotonic_to_system(nsec: u64) -> io::Result<SystemTime> {
+    let now = get_monotonic_nsec()?;
+    let system = SystemTime::now();
+
+    Ok(if nsec <= now {
+        system - Duration::from_nanos(now - nsec)
+    } else {
+        system + Duration::from_nanos(nsec - now)
+    })
+}
+
+/// Get the CPU time consumed by the current thread in nanoseconds.
+///
+/// Unlike [`get_monotonic_nsec`] this does not advance while the thread is
+/// preempted, which makes it suitable for accounting the cost of processing.
+pub fn get_thread_cpu_nsec() -> io::Result<u64> {
+    get_clock_nsec(libc::CLOCK_THREAD_CPUTIME_ID)
+}
+
+/// Pin the current thread to the given set of CPU cores.
+///
+/// This is typically used to keep the thread processing audio away from cores
+/// which are busy with other work.
+pub fn set_thread_affinity(cpus: &[usize]) -> io::Result<()> {
+    // SAFETY: We're just using c-apis as intended.
+    unsafe {
+        let mut set = core::mem::zeroed::<libc::cpu_set_t>();
+        let max = core::mem::size_of::<libc::cpu_set_t>() * 8;
+
+        for &cpu in cpus {
+            if cpu >= max {
+                return Err(io::Error::from(io::ErrorKind::InvalidInput));
+            }
+
+            libc::CPU_SET(cpu, &mut set);
+        }
+
+        if libc::sched_setaffinity(0, core::mem::size_of::<libc::cpu_set_t>(), &set) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// Configure the floating point environment of the current thread to flush
+/// denormal numbers to zero.
+///
+/// Processing near-silent signals through recursive filters can otherwise
+/// produce long runs of denormal numbers which are very slow to compute on
+/// most CPUs. This should be called once on the thread which performs
+/// processing.
+///
+/// On x86 this sets the flush-to-zero (FTZ) and denormals-are-zero (DAZ) flags,
+/// and on aarch64 the flush-to-zero (FZ) flag. Returns `false` if the current
+/// architecture is not supported, in which case nothing is changed.
+///
+/// # Examples
+///
+/// ```
+/// use std::hint::black_box;
+///
+/// if client::utils::set_flush_denormals(true) {
+///     let value = black_box(f32::MIN_POSITIVE) * black_box(0.5);
+///     assert_eq!(value, 0.0);
+/// }
+///
+/// client::utils::set_flush_denormals(false);
+/// let value = black_box(f32::MIN_POSITIVE) * black_box(0.5);
+/// assert!(value > 0.0);
+/// ```
+pub fn set_flush_denormals(enabled: bool) -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        // Flush-to-zero (bit 15) and denormals-are-zero (bit 6).
+        const MASK: u32 = (1 << 15) | (1 << 6);
+
+        let mut csr = 0u32;
+
+        // SAFETY: Reading and writing the MXCSR register of the current
+        // thread only affects how floating point operations are performed.
+        unsafe {
+            core::arch::asm!("stmxcsr [{}]", in(reg) &mut csr, options(nostack));
+
+            if enabled {
+                csr |= MASK;
+            } else {
+                csr &= !MASK;
+            }
+
+            core::arch::asm!("ldmxcsr [{}]", in(reg) &csr, options(nostack));
+        }
+
+        true
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        // Flush-to-zero (bit 24).
+        const MASK: u64 = 1 << 24;
+
+        let mut fpcr: u64;
+
+        // SAFETY: Reading and writing the FPCR register of the current thread
+        // only affects how floating point operations are performed.
+        unsafe {
+            core::arch::asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack));
+
+            if enabled {
+                fpcr |= MASK;
+            } else {
+                fpcr &= !MASK;
+            }
+
+            core::arch::asm!("msr fpcr, {}", in(reg) fpcr, options(nomem, nostack));
+        }
+
+        true
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        _ = enabled;
+        false
+    }
+}
+
+/// Format a string as a quoted and escaped JSON string.
+pub(crate) struct JsonString<'a>(pub(crate) &'a str);
+
+impl fmt::Display for JsonString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{c}")?,
+            }
+        }
+
+        f.write_str("\"")
+    }
+}
 
+fn get_clock_nsec(clock: libc::clockid_t) -> io::Result<u64> {
     let mut time_spec = libc::timespec {
         tv_sec: 0,
         tv_nsec: 0,
@@ -14,7 +177,7 @@ pub fn get_monotonic_nsec() -> io::Result<u64> {
 
     // SAFETY: We're just using c-apis as intended.
     unsafe {
-        if libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time_spec) == -1 {
+        if libc::clock_gettime(clock, &mut time_spec) == -1 {
             return Err(io::Error::last_os_error());
         }
     }
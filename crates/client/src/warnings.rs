@@ -0,0 +1,106 @@
+use core::time::Duration;
+
+use std::collections::HashMap;
+
+use crate::{ClientNodeId, utils};
+
+/// The default interval at which a repeated warning is logged.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Rate limiting and deduplication of warnings.
+///
+/// Warnings are keyed by the site they are emitted from, the node they
+/// concern, and a site-specific kind like an opcode. The first warning for a
+/// key is logged, after which repeated warnings for the same key are counted
+/// and only logged again once the interval has passed. Counts of suppressed
+/// warnings are logged when the warnings are flushed.
+pub(crate) struct Warnings {
+    entries: HashMap<Key, Entry>,
+    interval: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    site: &'static str,
+    node: Option<ClientNodeId>,
+    kind: u32,
+}
+
+struct Entry {
+    logged_at: u64,
+    suppressed: usize,
+}
+
+impl Warnings {
+    /// Construct a new empty set of warnings.
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            interval: duration_nsec(DEFAULT_INTERVAL),
+        }
+    }
+
+    /// Set the interval at which repeated warnings are logged.
+    pub(crate) fn set_interval(&mut self, interval: Duration) {
+        self.interval = duration_nsec(interval);
+    }
+
+    /// Test if a warning should be logged, counting it as suppressed if not.
+    pub(crate) fn check(
+        &mut self,
+        site: &'static str,
+        node: Option<ClientNodeId>,
+        kind: u32,
+    ) -> bool {
+        let now = utils::get_monotonic_nsec().unwrap_or_default();
+        let key = Key { site, node, kind };
+
+        let Some(entry) = self.entries.get_mut(&key) else {
+            self.entries.insert(
+                key,
+                Entry {
+                    logged_at: now,
+                    suppressed: 0,
+                },
+            );
+
+            return true;
+        };
+
+        if now.saturating_sub(entry.logged_at) < self.interval {
+            entry.suppressed += 1;
+            return false;
+        }
+
+        entry.logged_at = now;
+        true
+    }
+
+    /// Log the number of suppressed warnings, and forget about warnings which
+    /// have not been repeated within the interval.
+    pub(crate) fn flush(&mut self) {
+        let now = utils::get_monotonic_nsec().unwrap_or_default();
+        let interval = self.interval;
+
+        self.entries.retain(|key, entry| {
+            if entry.suppressed > 0 {
+                tracing::warn!(
+                    site = key.site,
+                    node = ?key.node,
+                    kind = key.kind,
+                    suppressed = entry.suppressed,
+                    "Suppressed repeated warnings"
+                );
+
+                entry.suppressed = 0;
+                return true;
+            }
+
+            now.saturating_sub(entry.logged_at) < interval
+        });
+    }
+}
+
+fn duration_nsec(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,71 @@
+//! Tests for driving a stream through the tokio runtime.
+
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use client::events::StreamEvent;
+use client::{AsyncStream, Stream};
+use protocol::{Connection, Properties};
+
+/// The identifier the stream uses for syncs requested through
+/// [`Stream::sync`].
+const USER_SYNC: i32 = 0x6000;
+
+/// The opcode of the done event of the core.
+const CORE_DONE: u32 = 1;
+
+/// Encode a done event from the core the way the server does.
+fn core_done(id: i32, seq: u32) -> Result<Vec<u8>> {
+    let mut pod = pod::dynamic();
+
+    pod.as_mut().write_struct(|st| {
+        st.field().write(id)?;
+        st.field().write(seq as i32)?;
+        Ok(())
+    })?;
+
+    let body = pod.as_buf().as_bytes();
+
+    let mut message = Vec::new();
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(&((CORE_DONE << 24) | body.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(body);
+    Ok(message)
+}
+
+#[test]
+fn run_waits_for_events() -> Result<()> {
+    let (a, mut b) = UnixStream::pair()?;
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()?;
+
+    rt.block_on(async move {
+        let mut c = Connection::from_socket(a);
+        c.set_nonblocking(true)?;
+
+        let mut stream = AsyncStream::new(Stream::new(c, Properties::new())?)?;
+        let seq = stream.get_mut().sync()?;
+
+        let message = core_done(USER_SYNC, seq)?;
+
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            b.write_all(&message).unwrap();
+            // NB: Keep the peer open, since a closed peer is an error.
+            b
+        });
+
+        let event = stream.run().await?;
+        let _b = writer.join().unwrap();
+
+        assert_eq!(event, StreamEvent::Synced(seq));
+        Ok(())
+    })
+}
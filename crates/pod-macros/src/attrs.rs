@@ -8,7 +8,7 @@ use crate::Ctxt;
 
 pub(crate) struct Object {
     pub(crate) ty: syn::Expr,
-    pub(crate) id: syn::Expr,
+    pub(crate) id: Option<syn::Expr>,
 }
 
 #[derive(Default)]
@@ -89,13 +89,6 @@ pub(crate) fn container(cx: &Ctxt, inputs: &[syn::Attribute]) -> Result<Containe
                     )
                 })?;
 
-                let object_id = object_id.ok_or_else(|| {
-                    syn::Error::new(
-                        meta.path.span(),
-                        "#[pod(object(..))] Missing `id` attribute",
-                    )
-                })?;
-
                 attrs.container = Container::Object(Box::new(Object {
                     ty: object_type,
                     id: object_id,
@@ -118,9 +111,47 @@ pub(crate) fn container(cx: &Ctxt, inputs: &[syn::Attribute]) -> Result<Containe
     Ok(attrs)
 }
 
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    pub(crate) id: Option<syn::Expr>,
+}
+
+pub(crate) fn variant(cx: &Ctxt, inputs: &[syn::Attribute]) -> Result<VariantAttrs, ()> {
+    let mut attrs = VariantAttrs::default();
+
+    for a in inputs {
+        if !a.path().is_ident("pod") {
+            continue;
+        }
+
+        let result = a.parse_nested_meta(|meta| {
+            if meta.path.is_ident("id") {
+                meta.input.parse::<Token![=]>()?;
+                attrs.id = Some(meta.input.parse()?);
+                return Ok(());
+            }
+
+            Err(syn::Error::new(
+                meta.path.span(),
+                "#[pod(..)] Unsupported variant attribute",
+            ))
+        });
+
+        if let Err(e) = result {
+            cx.error(e);
+            continue;
+        }
+    }
+
+    Ok(attrs)
+}
+
 #[derive(Default)]
 pub(crate) struct FieldAttrs {
     pub(crate) key: Option<syn::Expr>,
+    /// Don't check that the key has the same type as the keys of other
+    /// fields.
+    pub(crate) unchecked: bool,
 }
 
 pub(crate) fn field(cx: &Ctxt, inputs: &[syn::Attribute]) -> Result<FieldAttrs, ()> {
@@ -150,7 +181,12 @@ pub(crate) fn field(cx: &Ctxt, inputs: &[syn::Attribute]) -> Result<FieldAttrs,
                         let ident = content.parse::<syn::Ident>()?;
 
                         if ident == "key" {
-                            break 'out &mut attrs.key;
+                            break 'out Some(&mut attrs.key);
+                        }
+
+                        if ident == "unchecked" {
+                            attrs.unchecked = true;
+                            break 'out None;
                         }
 
                         return Err(syn::Error::new(
@@ -159,8 +195,10 @@ pub(crate) fn field(cx: &Ctxt, inputs: &[syn::Attribute]) -> Result<FieldAttrs,
                         ));
                     };
 
-                    content.parse::<Token![=]>()?;
-                    *out = Some(content.parse()?);
+                    if let Some(out) = out {
+                        content.parse::<Token![=]>()?;
+                        *out = Some(content.parse()?);
+                    }
 
                     if content.is_empty() {
                         break;
@@ -62,58 +62,399 @@ struct Field<'field> {
     accessor: syn::Member,
     attrs: attrs::FieldAttrs,
     data: &'field syn::Field,
+    array: Option<ArrayField<'field>>,
 }
 
-fn fields<'field>(cx: &Ctxt, data: &'field syn::Data) -> Result<Vec<Field<'field>>, ()> {
+/// A field which is encoded as an array when used as an object property.
+enum ArrayField<'field> {
+    /// A field of type `[T; N]`.
+    Fixed {
+        elem: &'field syn::Type,
+        len: &'field syn::Expr,
+    },
+    /// A field of type `Vec<T>`.
+    Vec { elem: &'field syn::Type },
+}
+
+impl<'field> ArrayField<'field> {
+    /// Classify the type of a field.
+    ///
+    /// Arrays of `u8` are excluded, since they are encoded as bytes.
+    fn new(ty: &'field syn::Type) -> Option<Self> {
+        let this = match ty {
+            syn::Type::Array(array) => Self::Fixed {
+                elem: &array.elem,
+                len: &array.len,
+            },
+            syn::Type::Path(path) if path.qself.is_none() => {
+                let last = path.path.segments.last()?;
+
+                if last.ident != "Vec" {
+                    return None;
+                }
+
+                let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+                    return None;
+                };
+
+                let mut args = args.args.iter();
+
+                let (Some(syn::GenericArgument::Type(elem)), None) = (args.next(), args.next())
+                else {
+                    return None;
+                };
+
+                Self::Vec { elem }
+            }
+            _ => return None,
+        };
+
+        if let syn::Type::Path(path) = this.elem()
+            && path.path.is_ident("u8")
+        {
+            return None;
+        }
+
+        Some(this)
+    }
+
+    fn elem(&self) -> &'field syn::Type {
+        match *self {
+            Self::Fixed { elem, .. } | Self::Vec { elem } => elem,
+        }
+    }
+}
+
+struct Variant<'variant> {
+    ident: &'variant syn::Ident,
+    id: syn::Expr,
+    fields: Vec<Field<'variant>>,
+}
+
+enum Data<'data> {
+    Struct(Vec<Field<'data>>),
+    Enum(Vec<Variant<'data>>),
+}
+
+fn fields<'field>(cx: &Ctxt, input: &'field syn::Fields) -> Result<Vec<Field<'field>>, ()> {
+    let mut fields = Vec::new();
+
+    for (index, f) in input.iter().enumerate() {
+        let attrs = attrs::field(cx, &f.attrs)?;
+
+        let span;
+        let accessor;
+
+        match &f.ident {
+            Some(ident) => {
+                span = ident.span();
+                accessor = syn::Member::Named(ident.clone());
+            }
+            None => {
+                span = f.span();
+                accessor = syn::Member::Unnamed(syn::Index {
+                    index: index as u32,
+                    span: f.span(),
+                });
+            }
+        };
+
+        fields.push(Field {
+            span,
+            accessor,
+            attrs,
+            data: f,
+            array: ArrayField::new(&f.ty),
+        });
+    }
+
+    Ok(fields)
+}
+
+fn data<'data>(cx: &Ctxt, data: &'data syn::Data) -> Result<Data<'data>, ()> {
     match data {
-        syn::Data::Struct(s) => {
-            let mut fields = Vec::new();
+        syn::Data::Struct(s) => Ok(Data::Struct(fields(cx, &s.fields)?)),
+        syn::Data::Enum(e) => {
+            let mut variants = Vec::new();
 
-            for (index, f) in s.fields.iter().enumerate() {
-                let attrs = attrs::field(cx, &f.attrs)?;
+            for v in &e.variants {
+                let attrs = attrs::variant(cx, &v.attrs)?;
 
-                let span;
-                let accessor;
+                let Some(id) = attrs.id else {
+                    cx.error(syn::Error::new(
+                        v.ident.span(),
+                        "#[pod(id = ..)] Missing for variant",
+                    ));
 
-                match &f.ident {
-                    Some(ident) => {
-                        span = ident.span();
-                        accessor = syn::Member::Named(ident.clone());
-                    }
-                    None => {
-                        span = f.span();
-                        accessor = syn::Member::Unnamed(syn::Index {
-                            index: index as u32,
-                            span: f.span(),
-                        });
-                    }
+                    continue;
                 };
 
-                fields.push(Field {
-                    span,
-                    accessor,
-                    attrs,
-                    data: f,
+                variants.push(Variant {
+                    ident: &v.ident,
+                    id,
+                    fields: fields(cx, &v.fields)?,
                 });
             }
 
-            Ok(fields)
+            Ok(Data::Enum(variants))
         }
-        syn::Data::Enum(..) => {
+        syn::Data::Union(..) => {
             cx.error(syn::Error::new(
                 Span::call_site(),
-                "Enums are not supported",
+                "Unions are not supported",
             ));
             Err(())
         }
-        syn::Data::Union(..) => {
+    }
+}
+
+/// Collect the property keys of fields, reporting fields without one.
+fn keys<'a>(cx: &Ctxt, fields: &'a [Field<'_>]) -> Vec<&'a syn::Expr> {
+    let mut keys = Vec::new();
+
+    for f in fields {
+        let Some(key) = &f.attrs.key else {
             cx.error(syn::Error::new(
-                Span::call_site(),
-                "Unions are not supported",
+                f.span,
+                "#[pod(key = ..)] Missing for field",
             ));
-            Err(())
+
+            continue;
+        };
+
+        keys.push(key);
+    }
+
+    keys
+}
+
+/// Check that the keys of fields all have the same type, except for keys
+/// marked as `unchecked`.
+///
+/// This prevents keys of different kinds of objects from accidentally being
+/// mixed, since they are compared by their raw ids.
+fn check_keys(fields: &[Field<'_>]) -> TokenStream {
+    let keys = fields
+        .iter()
+        .filter(|f| !f.attrs.unchecked)
+        .filter_map(|f| f.attrs.key.as_ref())
+        .collect::<Vec<_>>();
+
+    if keys.len() < 2 {
+        return quote!();
+    }
+
+    quote! {
+        let _ = || [#(#keys),*];
+    }
+}
+
+/// Get the object id of a struct, which must be specified in the container.
+fn object_id<'a>(cx: &Ctxt, id: &'a Option<syn::Expr>) -> Result<&'a syn::Expr, ()> {
+    let Some(id) = id else {
+        cx.error(syn::Error::new(
+            Span::call_site(),
+            "#[pod(object(..))] Missing `id` attribute",
+        ));
+
+        return Err(());
+    };
+
+    Ok(id)
+}
+
+/// Check that an enum encoded as an object takes its object ids from its
+/// variants.
+fn no_object_id(cx: &Ctxt, id: &Option<syn::Expr>) -> Result<(), ()> {
+    if let Some(id) = id {
+        cx.error(syn::Error::new(
+            id.span(),
+            "#[pod(object(id = ..))] Is not supported for enums, use #[pod(id = ..)] on each variant",
+        ));
+
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/// Check that the variants of an enum which is not encoded as an object are
+/// all unit variants.
+fn unit_variants(cx: &Ctxt, variants: &[Variant<'_>]) -> Result<(), ()> {
+    let mut ok = true;
+
+    for v in variants {
+        if !v.fields.is_empty() {
+            cx.error(syn::Error::new(
+                v.ident.span(),
+                "Variants with fields require #[pod(object(type = ..))] on the enum",
+            ));
+
+            ok = false;
         }
     }
+
+    if ok { Ok(()) } else { Err(()) }
+}
+
+/// Read the properties of an object into the given fields, constructing the
+/// value with `path`.
+fn read_properties(
+    cx: &Ctxt,
+    toks: &Toks<'_>,
+    path: TokenStream,
+    fields: &[Field<'_>],
+) -> TokenStream {
+    let Toks {
+        result,
+        option,
+        object,
+        property,
+        raw_id_t,
+        default_t,
+        pod_item_t,
+        array,
+        value,
+        error,
+        ..
+    } = toks;
+
+    let keys = keys(cx, fields);
+
+    let mut vars = Vec::new();
+    let mut types = Vec::new();
+    let mut fallback = Vec::new();
+    let mut reads = Vec::new();
+
+    for (n, f) in fields.iter().enumerate() {
+        let ty = &f.data.ty;
+        let var = syn::Ident::new(&format!("field{n}"), f.span);
+
+        let read = match &f.array {
+            None => quote! {
+                #var = #option::Some(#pod_item_t::read(#property::value(prop))?);
+            },
+            Some(ArrayField::Fixed { len, .. }) => quote! {
+                let mut array = #value::read_array(#property::value(prop))?;
+                let len = #array::len(&array);
+
+                if len != #len {
+                    return #result::Err(#error::__property_array_length(key, #len, len));
+                }
+
+                #var = #option::Some(#array::read::<#ty>(&mut array)?);
+            },
+            Some(ArrayField::Vec { .. }) => quote! {
+                let mut array = #value::read_array(#property::value(prop))?;
+                let mut values = <#ty>::with_capacity(#array::len(&array));
+
+                while let #option::Some(value) = #array::next(&mut array)? {
+                    values.push(#pod_item_t::read(value)?);
+                }
+
+                #var = #option::Some(values);
+            },
+        };
+
+        vars.push(var);
+        types.push(ty);
+        fallback.push(quote!(<#ty as #default_t>::default()));
+        reads.push(read);
+    }
+
+    let match_fields = if !keys.is_empty() {
+        quote! {
+            let key = #property::key::<u32>(&prop);
+
+            #(if key == #raw_id_t::into_id(#keys) {
+                #reads
+            } else)* {}
+        }
+    } else {
+        quote!()
+    };
+
+    let check_keys = check_keys(fields);
+    let accessor = fields.iter().map(|f| &f.accessor);
+
+    quote! {
+        #check_keys
+
+        #(
+            let mut #vars = #option::<#types>::None;
+        )*
+
+        while !#object::is_empty(&obj) {
+            let prop = #object::property(&mut obj)?;
+            #match_fields
+        }
+
+        #result::Ok(#path {
+            #(#accessor: match #vars {
+                #option::Some(v) => v,
+                #option::None => #fallback,
+            },)*
+        })
+    }
+}
+
+/// Write the given fields as properties of an object, where each field is
+/// accessed through `access`.
+fn write_properties(
+    cx: &Ctxt,
+    toks: &Toks<'_>,
+    fields: &[Field<'_>],
+    access: &[TokenStream],
+) -> TokenStream {
+    let Toks {
+        result,
+        builder,
+        object_builder,
+        array_builder,
+        sized_writable_t,
+        ..
+    } = toks;
+
+    let keys = keys(cx, fields);
+
+    let writes = fields.iter().zip(access).map(|(f, access)| match &f.array {
+        None => quote!(#builder::write(prop, #access)?;),
+        Some(array) => {
+            let elem = array.elem();
+
+            quote! {
+                #builder::write_array(prop, <#elem as #sized_writable_t>::TYPE, |array| {
+                    #array_builder::write(array, &(#access)[..])
+                })?;
+            }
+        }
+    });
+
+    let check_keys = check_keys(fields);
+
+    quote! {
+        #check_keys
+
+        #(
+            let prop = #object_builder::property(obj, #keys);
+            #writes
+        )*
+
+        #result::Ok(())
+    }
+}
+
+/// Bindings for the fields of a variant in a `match *self` pattern.
+fn bindings<'a>(fields: &'a [Field<'_>]) -> (Vec<&'a syn::Member>, Vec<syn::Ident>) {
+    let accessor = fields.iter().map(|f| &f.accessor).collect();
+
+    let vars = fields
+        .iter()
+        .enumerate()
+        .map(|(n, f)| syn::Ident::new(&format!("field{n}"), f.span))
+        .collect();
+
+    (accessor, vars)
 }
 
 pub fn readable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
@@ -131,20 +472,18 @@ pub fn readable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
 
     let Toks {
         result,
-        option,
         readable_t,
         error,
+        id: id_t,
         pod_stream_t,
         struct_,
         object,
-        property,
         raw_id_t,
-        default_t,
         pod_item_t,
         ..
     } = &toks;
 
-    let fields = fields(cx, &input.data)?;
+    let data = data(cx, &input.data)?;
 
     let (add, lt) = 'lt: {
         if let Some(lt) = generics.lifetimes().next() {
@@ -178,8 +517,8 @@ pub fn readable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
 
     let inner;
 
-    match attrs.container {
-        attrs::Container::Struct => {
+    match (attrs.container, &data) {
+        (attrs::Container::Struct, Data::Struct(fields)) => {
             let accessor = fields.iter().map(|f| &f.accessor);
 
             inner = quote! {
@@ -190,46 +529,29 @@ pub fn readable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
                 })
             };
         }
-        attrs::Container::Object(o) => {
-            let attrs::Object { ty, id } = &*o;
+        (attrs::Container::Struct, Data::Enum(variants)) => {
+            unit_variants(cx, variants)?;
 
-            let mut keys = Vec::new();
-            let mut vars = Vec::new();
-            let mut types = Vec::new();
-            let mut fallback = Vec::new();
+            let idents = variants.iter().map(|v| v.ident);
+            let ids = variants.iter().map(|v| &v.id);
 
-            for (n, f) in fields.iter().enumerate() {
-                let Some(key) = &f.attrs.key else {
-                    cx.error(syn::Error::new(
-                        f.span,
-                        "#[pod(key = ..)] Missing for field",
-                    ));
-
-                    continue;
-                };
-
-                let ty = &f.data.ty;
-
-                keys.push(key);
-                vars.push(syn::Ident::new(&format!("field{n}"), f.span));
-                types.push(ty);
-                fallback.push(quote!(<#ty as #default_t>::default()));
-            }
+            inner = quote! {
+                let #id_t(id) = #pod_item_t::read::<#id_t<u32>>(#pod_stream_t::next(pod)?)?;
 
-            let match_fields = if !keys.is_empty() {
-                quote! {
-                    match #raw_id_t::from_id(#property::key(&prop)) {
-                        #(#keys => {
-                            #vars = #option::Some(#pod_item_t::read(#property::value(prop))?);
-                        },)*
-                        _ => {},
+                #(
+                    if id == #raw_id_t::into_id(#ids) {
+                        return #result::Ok(Self::#idents {});
                     }
-                }
-            } else {
-                quote!()
+                )*
+
+                #result::Err(#error::__unknown_variant_id(id))
             };
+        }
+        (attrs::Container::Object(o), Data::Struct(fields)) => {
+            let attrs::Object { ty, id } = &*o;
+            let id = object_id(cx, id)?;
 
-            let accessor = fields.iter().map(|f| &f.accessor);
+            let body = read_properties(cx, &toks, quote!(Self), fields);
 
             inner = quote! {
                 let mut obj = #pod_item_t::read_object(#pod_stream_t::next(pod)?)?;
@@ -242,21 +564,36 @@ pub fn readable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
                     return #result::Err(#error::__invalid_object_id(#id, obj.object_id::<u32>()));
                 }
 
-                #(
-                    let mut #vars = #option::<#types>::None;
-                )*
+                #body
+            };
+        }
+        (attrs::Container::Object(o), Data::Enum(variants)) => {
+            let attrs::Object { ty, id } = &*o;
+            no_object_id(cx, id)?;
+
+            let ids = variants.iter().map(|v| &v.id);
+
+            let bodies = variants.iter().map(|v| {
+                let ident = v.ident;
+                read_properties(cx, &toks, quote!(Self::#ident), &v.fields)
+            });
 
-                while !#object::is_empty(&obj) {
-                    let prop = #object::property(&mut obj)?;
-                    #match_fields
+            inner = quote! {
+                let mut obj = #pod_item_t::read_object(#pod_stream_t::next(pod)?)?;
+
+                if #ty != #object::object_type::<u32>(&obj) {
+                    return #result::Err(#error::__invalid_object_type(#ty, obj.object_type::<u32>()));
                 }
 
-                #result::Ok(Self {
-                    #(#accessor: match #vars {
-                        #option::Some(v) => v,
-                        #option::None => #fallback,
-                    },)*
-                })
+                let id = #object::object_id::<u32>(&obj);
+
+                #(
+                    if id == #raw_id_t::into_id(#ids) {
+                        return { #bodies };
+                    }
+                )*
+
+                #result::Err(#error::__unknown_variant_id(id))
             };
         }
     }
@@ -289,11 +626,12 @@ pub fn writable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
         result,
         writable_t,
         error,
+        id: id_t,
         pod_sink_t,
         builder,
         struct_builder,
-        object_builder,
         object,
+        raw_id_t,
         embeddable_t,
         writer_slice,
         writer_t,
@@ -301,14 +639,15 @@ pub fn writable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
         ..
     } = &toks;
 
-    let fields = fields(cx, &input.data)?;
-    let accessor = fields.iter().map(|f| &f.accessor).collect::<Vec<_>>();
+    let data = data(cx, &input.data)?;
 
     let inner;
-    let impl_embeddable;
+    let embed;
+
+    match (attrs.container, &data) {
+        (attrs::Container::Struct, Data::Struct(fields)) => {
+            let accessor = fields.iter().map(|f| &f.accessor);
 
-    match attrs.container {
-        attrs::Container::Struct => {
             inner = quote! {
                 #builder::write_struct(#pod_sink_t::next(pod)?, |pod| {
                     #(#struct_builder::write(pod, &self.#accessor)?;)*
@@ -318,61 +657,94 @@ pub fn writable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
                 #result::Ok(())
             };
 
-            impl_embeddable = None;
+            embed = None;
         }
-        attrs::Container::Object(o) => {
-            let attrs::Object { ty, id } = &*o;
+        (attrs::Container::Struct, Data::Enum(variants)) => {
+            unit_variants(cx, variants)?;
 
-            let mut keys = Vec::new();
+            let idents = variants.iter().map(|v| v.ident);
+            let ids = variants.iter().map(|v| &v.id);
 
-            for f in &fields {
-                let Some(key) = &f.attrs.key else {
-                    cx.error(syn::Error::new(
-                        f.span,
-                        "#[pod(key = ..)] Missing for field",
-                    ));
-
-                    continue;
+            inner = quote! {
+                let id = match *self {
+                    #(Self::#idents { .. } => #raw_id_t::into_id(#ids),)*
                 };
 
-                keys.push(key);
-            }
+                #builder::write(#pod_sink_t::next(pod)?, #id_t(id))
+            };
+
+            embed = None;
+        }
+        (attrs::Container::Object(o), Data::Struct(fields)) => {
+            let attrs::Object { ty, id } = &*o;
+            let id = object_id(cx, id)?;
+
+            let access = fields
+                .iter()
+                .map(|f| {
+                    let accessor = &f.accessor;
+                    quote!(&self.#accessor)
+                })
+                .collect::<Vec<_>>();
+
+            let body = write_properties(cx, &toks, fields, &access);
 
             inner = quote! {
                 #builder::write_object(#pod_sink_t::next(pod)?, #ty, #id, |obj| {
-                    #(
-                        let prop = #object_builder::property(obj, #keys);
-                        #builder::write(prop, &self.#accessor)?;
-                    )*
-
-                    #result::Ok(())
+                    #body
                 })?;
 
                 #result::Ok(())
             };
 
-            let (impl_generics, ty_generics, where_generics) = generics.split_for_impl();
-
-            impl_embeddable = Some(quote! {
-                #[automatically_derived]
-                impl #impl_generics #embeddable_t for #ident #ty_generics #where_generics {
-                    type Embed<W> = #object<#writer_slice<W, 16>> where W: #writer_t;
+            embed = Some(quote! {
+                #builder::embed_object(pod, #ty, #id, |obj| {
+                    #body
+                })
+            });
+        }
+        (attrs::Container::Object(o), Data::Enum(variants)) => {
+            let attrs::Object { ty, id } = &*o;
+            no_object_id(cx, id)?;
+
+            let mut arms = Vec::new();
+            let mut embed_arms = Vec::new();
+
+            for v in variants {
+                let ident = v.ident;
+                let id = &v.id;
+                let (accessor, vars) = bindings(&v.fields);
+                let access = vars.iter().map(|v| quote!(#v)).collect::<Vec<_>>();
+                let body = write_properties(cx, &toks, &v.fields, &access);
+
+                arms.push(quote! {
+                    Self::#ident { #(#accessor: ref #vars,)* } => {
+                        #builder::write_object(#pod_sink_t::next(pod)?, #ty, #id, |obj| {
+                            #body
+                        })?;
+                    }
+                });
 
-                    #[inline]
-                    fn embed_into<W, P>(&self, pod: #builder<W, P>) -> #result<Self::Embed<W>, #error>
-                    where
-                        W: #writer_t,
-                        P: #build_pod_t,
-                    {
+                embed_arms.push(quote! {
+                    Self::#ident { #(#accessor: ref #vars,)* } => {
                         #builder::embed_object(pod, #ty, #id, |obj| {
-                            #(
-                                let prop = #object_builder::property(obj, #keys);
-                                #builder::write(prop, &self.#accessor)?;
-                            )*
-
-                            #result::Ok(())
+                            #body
                         })
                     }
+                });
+            }
+
+            inner = quote! {
+                match *self {
+                    #(#arms)*
+                }
+
+                #result::Ok(())
+            };
+
+            embed = Some(quote! {
+                match *self {
+                    #(#embed_arms)*
                 }
             });
         }
@@ -380,6 +752,24 @@ pub fn writable(cx: &Ctxt, input: syn::DeriveInput) -> Result<TokenStream, ()> {
 
     let (impl_generics, ty_generics, where_generics) = generics.split_for_impl();
 
+    let impl_embeddable = embed.map(|embed| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #embeddable_t for #ident #ty_generics #where_generics {
+                type Embed<W> = #object<#writer_slice<W, 16>> where W: #writer_t;
+
+                #[inline]
+                fn embed_into<W, P>(&self, pod: #builder<W, P>) -> #result<Self::Embed<W>, #error>
+                where
+                    W: #writer_t,
+                    P: #build_pod_t,
+                {
+                    #embed
+                }
+            }
+        }
+    });
+
     Ok(quote! {
         #[automatically_derived]
         impl #impl_generics #writable_t for #ident #ty_generics #where_generics {
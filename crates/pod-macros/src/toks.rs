@@ -3,10 +3,13 @@ use quote::ToTokens;
 use syn::Token;
 
 pub(crate) struct Toks<'base> {
+    pub(crate) array: P<'base>,
+    pub(crate) array_builder: Nested<'base>,
     pub(crate) builder: P<'base>,
     pub(crate) default_t: Nested<'base>,
     pub(crate) embeddable_t: P<'base>,
     pub(crate) error: P<'base>,
+    pub(crate) id: P<'base>,
     pub(crate) object_builder: Nested<'base>,
     pub(crate) object: P<'base>,
     pub(crate) option: Nested<'base>,
@@ -17,8 +20,10 @@ pub(crate) struct Toks<'base> {
     pub(crate) raw_id_t: P<'base>,
     pub(crate) readable_t: P<'base>,
     pub(crate) result: Nested<'base>,
+    pub(crate) sized_writable_t: P<'base>,
     pub(crate) struct_: P<'base>,
     pub(crate) struct_builder: Nested<'base>,
+    pub(crate) value: P<'base>,
     pub(crate) writable_t: P<'base>,
     pub(crate) writer_slice: P<'base>,
     pub(crate) writer_t: P<'base>,
@@ -55,10 +60,13 @@ impl<'base> Toks<'base> {
         }
 
         Toks {
+            array: p!(Array),
+            array_builder: p!(builder::ArrayBuilder),
             builder: p!(Builder),
             default_t: core!(default::Default),
             embeddable_t: p!(Embeddable),
             error: p!(Error),
+            id: p!(Id),
             object_builder: p!(builder::ObjectBuilder),
             object: p!(Object),
             option: core!(option::Option),
@@ -69,8 +77,10 @@ impl<'base> Toks<'base> {
             raw_id_t: p!(RawId),
             readable_t: p!(Readable),
             result: core!(result::Result),
+            sized_writable_t: p!(SizedWritable),
             struct_: p!(Struct),
             struct_builder: p!(builder::StructBuilder),
+            value: p!(Value),
             writable_t: p!(Writable),
             writer_slice: p!(WriterSlice),
             writer_t: p!(Writer),
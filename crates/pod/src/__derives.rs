@@ -89,3 +89,149 @@
 //!
 //! Note that if a choice is encountered while decoding a pod, the value of the
 //! choice will only be extracted if it has the type `NONE`.
+//!
+//! #### `#[pod(property(key = <key>, unchecked))]`
+//!
+//! Keys can be any expression of a type implementing [`RawId`]. To prevent
+//! keys of different kinds of objects from accidentally being mixed, all keys
+//! of an object must have the same type.
+//!
+//! [`RawId`]: crate::RawId
+//!
+//! ```compile_fail
+//! use pod::{Readable, Writable};
+//! use protocol::id;
+//!
+//! #[derive(Readable, Writable)]
+//! #[pod(object(type = id::ObjectType::FORMAT, id = id::Param::FORMAT))]
+//! struct Mixed {
+//!     #[pod(property(key = id::Format::AUDIO_RATE))]
+//!     rate: u32,
+//!     #[pod(property(key = id::Prop::VOLUME))]
+//!     volume: f32,
+//! }
+//! ```
+//!
+//! A key which is intentionally of another type can be marked with
+//! `unchecked`.
+//!
+//! ```
+//! use pod::{Readable, Writable};
+//! use protocol::id;
+//!
+//! #[derive(Readable, Writable)]
+//! #[pod(object(type = id::ObjectType::FORMAT, id = id::Param::FORMAT))]
+//! struct Mixed {
+//!     #[pod(property(key = id::Format::AUDIO_RATE))]
+//!     rate: u32,
+//!     #[pod(property(key = id::Prop::VOLUME, unchecked))]
+//!     volume: f32,
+//! }
+//! ```
+//!
+//! #### Array properties
+//!
+//! Fields of type `[T; N]` and `Vec<T>` are encoded as array properties, like
+//! the volumes or the channel map of a node. Decoding a `[T; N]` field errors
+//! if the array does not have exactly `N` elements.
+//!
+//! Fields of type `[u8; N]` and `Vec<u8>` are encoded as bytes.
+//!
+//! ```
+//! use pod::{Id, Readable, Type, Writable};
+//! use protocol::id;
+//!
+//! #[derive(Debug, PartialEq, Readable, Writable)]
+//! #[pod(object(type = id::ObjectType::PROPS, id = id::Param::PROPS))]
+//! struct StereoProps {
+//!     #[pod(property(key = id::Prop::CHANNEL_VOLUMES))]
+//!     volumes: [f32; 2],
+//!     #[pod(property(key = id::Prop::CHANNEL_MAP))]
+//!     channel_map: Vec<Id<u32>>,
+//! }
+//!
+//! let mut pod = pod::array();
+//!
+//! pod.as_mut().write(StereoProps {
+//!     volumes: [1.0, 0.5],
+//!     channel_map: vec![Id(3), Id(4)],
+//! })?;
+//!
+//! let props = pod.as_ref().read::<StereoProps>()?;
+//! assert_eq!(props.volumes, [1.0, 0.5]);
+//! assert_eq!(props.channel_map, [Id(3), Id(4)]);
+//!
+//! let mut pod = pod::array();
+//!
+//! pod.as_mut().write_object(id::ObjectType::PROPS, id::Param::PROPS, |obj| {
+//!     obj.property(id::Prop::CHANNEL_VOLUMES)
+//!         .write_array(Type::FLOAT, |array| array.write((1.0f32, 0.5f32, 0.25f32)))
+//! })?;
+//!
+//! assert!(pod.as_ref().read::<StereoProps>().is_err());
+//! # Ok::<_, pod::Error>(())
+//! ```
+//!
+//! ## Enums
+//!
+//! Enums are supported by giving each variant an id with `#[pod(id = <id>)]`.
+//!
+//! Enums with only unit variants are encoded as an `Id` pod, which is useful
+//! for modelling the value of a choice.
+//!
+//! ```
+//! use pod::{Readable, Writable};
+//! use protocol::id;
+//!
+//! #[derive(Debug, PartialEq, Readable, Writable)]
+//! enum Media {
+//!     #[pod(id = id::MediaType::AUDIO)]
+//!     Audio,
+//!     #[pod(id = id::MediaType::VIDEO)]
+//!     Video,
+//! }
+//!
+//! let mut pod = pod::array();
+//! pod.as_mut().write(Media::Video)?;
+//! assert_eq!(pod.as_ref().read::<id::MediaType>()?, id::MediaType::VIDEO);
+//! assert_eq!(pod.as_ref().read::<Media>()?, Media::Video);
+//! # Ok::<_, pod::Error>(())
+//! ```
+//!
+//! #### `#[pod(object(type = <type>))]` and `#[pod(id = <id>)]`
+//!
+//! Enums with variants carrying fields are encoded as objects of the
+//! specified type, where the object id selects the variant. The fields of
+//! each variant are bound to properties like the fields of a struct.
+//!
+//! ```
+//! use pod::{Readable, Writable};
+//! use protocol::id;
+//!
+//! #[derive(Debug, PartialEq, Readable, Writable)]
+//! #[pod(object(type = id::ObjectType::FORMAT))]
+//! enum Param {
+//!     #[pod(id = id::Param::ENUM_FORMAT)]
+//!     EnumFormat {
+//!         #[pod(property(key = id::Format::MEDIA_TYPE))]
+//!         media_type: id::MediaType,
+//!     },
+//!     #[pod(id = id::Param::FORMAT)]
+//!     Format {
+//!         #[pod(property(key = id::Format::MEDIA_TYPE))]
+//!         media_type: id::MediaType,
+//!         #[pod(property(key = id::Format::AUDIO_RATE))]
+//!         rate: u32,
+//!     },
+//! }
+//!
+//! let param = Param::Format {
+//!     media_type: id::MediaType::AUDIO,
+//!     rate: 48000,
+//! };
+//!
+//! let mut pod = pod::array();
+//! pod.as_mut().write(&param)?;
+//! assert_eq!(pod.as_ref().read::<Param>()?, param);
+//! # Ok::<_, pod::Error>(())
+//! ```
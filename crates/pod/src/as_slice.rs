@@ -13,7 +13,7 @@ mod sealed {
 
     #[cfg(feature = "alloc")]
     use crate::DynamicBuf;
-    use crate::{ArrayBuf, AsSlice, Slice, Writer, WriterSlice};
+    use crate::{ArrayBuf, AsSlice, ConstPod, Slice, Writer, WriterSlice};
 
     pub trait Sealed {}
 
@@ -25,9 +25,10 @@ mod sealed {
     impl Sealed for Slice<'_> {}
     impl<const N: usize> Sealed for ArrayBuf<N> {}
     #[cfg(feature = "alloc")]
-    impl Sealed for DynamicBuf {}
+    impl<A> Sealed for DynamicBuf<A> where A: crate::buf::Allocator {}
     impl<R> Sealed for &mut R where R: ?Sized + AsSlice {}
     impl<R> Sealed for &R where R: ?Sized + AsSlice {}
+    impl<const N: usize> Sealed for ConstPod<N> {}
     impl<B, const N: usize> Sealed for WriterSlice<B, N> where B: Writer {}
 }
 
@@ -5,14 +5,22 @@ pub use self::array_buf::{ArrayBuf, ArrayBufPos};
 mod array_vec;
 pub use self::array_vec::ArrayVec;
 
+#[cfg(feature = "alloc")]
+mod allocator;
+#[cfg(feature = "alloc")]
+pub use self::allocator::{Allocator, Global};
+
 #[cfg(feature = "alloc")]
 mod dynamic_buf;
 #[cfg(feature = "alloc")]
-pub use self::dynamic_buf::{AllocError, DynamicBuf, DynamicBufPos};
+pub use self::dynamic_buf::{AllocError, DynamicBuf, DynamicBufPos, Growth};
 
 mod slice;
 pub use self::slice::Slice;
 
+mod chain;
+pub use self::chain::Chain;
+
 mod writer_slice;
 pub use self::writer_slice::WriterSlice;
 
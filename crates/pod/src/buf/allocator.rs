@@ -0,0 +1,163 @@
+use core::alloc::Layout;
+use core::ptr::{self, NonNull};
+
+use alloc::alloc;
+
+/// An allocator which backs a [`DynamicBuf`].
+///
+/// This mirrors the unstable `Allocator` trait in the standard library, so that
+/// users of the `alloc` feature can back buffers with something like an arena
+/// without relying on the global allocator.
+///
+/// Only buffers are generic over their allocator. Parameter caches and
+/// property maps in the client still use the global allocator.
+///
+/// [`DynamicBuf`]: crate::DynamicBuf
+///
+/// # Safety
+///
+/// Memory returned by [`Allocator::allocate`] and [`Allocator::reallocate`]
+/// must be valid for reads and writes of the size of the requested layout and
+/// be aligned to it, and must remain valid until it is passed to
+/// [`Allocator::deallocate`] or [`Allocator::reallocate`].
+///
+/// # Examples
+///
+/// ```
+/// use core::alloc::Layout;
+/// use core::cell::{Cell, UnsafeCell};
+/// use core::ptr::NonNull;
+///
+/// use pod::DynamicBuf;
+/// use pod::buf::Allocator;
+///
+/// struct Arena {
+///     data: UnsafeCell<[u64; 64]>,
+///     used: Cell<usize>,
+/// }
+///
+/// unsafe impl Allocator for Arena {
+///     fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+///         let words = layout.size().div_ceil(8);
+///         let start = self.used.get();
+///
+///         if layout.align() > 8 || start + words > 64 {
+///             return None;
+///         }
+///
+///         self.used.set(start + words);
+///         NonNull::new(self.data.get().cast::<u64>().wrapping_add(start).cast())
+///     }
+///
+///     unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+/// }
+///
+/// let arena = Arena {
+///     data: UnsafeCell::new([0; 64]),
+///     used: Cell::new(0),
+/// };
+///
+/// let mut buf = DynamicBuf::new_in(&arena);
+/// buf.extend_from_words(&[1u64, 2, 3])?;
+/// assert_eq!(buf.len(), 24);
+/// assert!(arena.used.get() > 0);
+/// # Ok::<_, pod::Error>(())
+/// ```
+pub unsafe trait Allocator {
+    /// Allocate memory fitting the given layout, returning `None` if the
+    /// allocation failed.
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// Deallocate memory previously allocated by this allocator.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `ptr` was allocated by this allocator with
+    /// the given `layout`.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Reallocate memory to fit the `new` layout, preserving its contents up
+    /// to the smallest of the two sizes.
+    ///
+    /// By default this allocates a new block, copies the contents and
+    /// deallocates the old block. On failure the old block is left untouched.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `ptr` was allocated by this allocator with
+    /// the `old` layout, and that `new` has the same alignment as `old`.
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old: Layout, new: Layout) -> Option<NonNull<u8>> {
+        let data = self.allocate(new)?;
+
+        // SAFETY: Both blocks are valid for the smallest of the two sizes and
+        // they are distinct allocations.
+        unsafe {
+            data.as_ptr()
+                .copy_from_nonoverlapping(ptr.as_ptr(), old.size().min(new.size()));
+            self.deallocate(ptr, old);
+        }
+
+        Some(data)
+    }
+}
+
+unsafe impl<A> Allocator for &A
+where
+    A: ?Sized + Allocator,
+{
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        (**self).allocate(layout)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+
+    #[inline]
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old: Layout, new: Layout) -> Option<NonNull<u8>> {
+        unsafe { (**self).reallocate(ptr, old, new) }
+    }
+}
+
+/// The global allocator, which is the default allocator of a [`DynamicBuf`].
+///
+/// [`DynamicBuf`]: crate::DynamicBuf
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Global;
+
+unsafe impl Allocator for Global {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        if layout.size() == 0 {
+            return NonNull::new(ptr::without_provenance_mut(layout.align()));
+        }
+
+        // SAFETY: The layout has a non-zero size.
+        NonNull::new(unsafe { alloc::alloc(layout) })
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() > 0 {
+            unsafe { alloc::dealloc(ptr.as_ptr(), layout) }
+        }
+    }
+
+    #[inline]
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old: Layout, new: Layout) -> Option<NonNull<u8>> {
+        if old.size() == 0 || new.size() == 0 {
+            let data = self.allocate(new)?;
+
+            // SAFETY: Any contents are at most zero bytes.
+            unsafe {
+                self.deallocate(ptr, old);
+            }
+
+            return Some(data);
+        }
+
+        NonNull::new(unsafe { alloc::realloc(ptr.as_ptr(), old, new.size()) })
+    }
+}
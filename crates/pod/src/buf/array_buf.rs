@@ -10,11 +10,24 @@ use super::CapacityError;
 
 const DEFAULT_SIZE: usize = 1024;
 
+/// The size of a word, which the capacity of the buffer must be a multiple of.
+const WORD_SIZE: usize = mem::size_of::<u64>();
+
 /// A fixed-size buffer with a flexible read and write position.
 ///
 /// The initialized slice of the buffer is defined by the region betweeen the
 /// `read` and `write` positions.
 ///
+/// The capacity `N` is specified in bytes and must be a multiple of the 8 byte
+/// word size used by pods, which is validated at compile time. The buffer is
+/// always aligned to a word.
+///
+/// ```compile_fail
+/// use pod::ArrayBuf;
+///
+/// let buf = ArrayBuf::<12>::new();
+/// ```
+///
 /// # Examples
 ///
 /// ```
@@ -44,11 +57,18 @@ impl<const N: usize> ArrayBuf<N> {
     /// ```
     /// use pod::ArrayBuf;
     ///
-    /// let buf = ArrayBuf::<3>::new();
-    /// assert_eq!(buf.capacity(), 3);
+    /// let buf = ArrayBuf::<16>::new();
+    /// assert_eq!(buf.capacity(), 16);
     /// ```
     #[inline]
     pub const fn new() -> Self {
+        const {
+            assert!(
+                N.is_multiple_of(WORD_SIZE),
+                "ArrayBuf capacity must be a multiple of the word size"
+            );
+        }
+
         // SAFETY: The buffer is a sequence of uninitialized elements.
         Self {
             data: unsafe { MaybeUninit::uninit().assume_init() },
@@ -190,6 +210,44 @@ impl<const N: usize> ArrayBuf<N> {
         N
     }
 
+    /// Returns the number of bytes which can still be written to the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ArrayBuf;
+    ///
+    /// let mut buf = ArrayBuf::<16>::new();
+    /// assert_eq!(buf.remaining(), 16);
+    /// buf.extend_from_words(&[1u32, 2, 3])?;
+    /// assert_eq!(buf.remaining(), 4);
+    /// assert_eq!(buf.remaining_words(), 0);
+    /// # Ok::<_, pod::buf::CapacityError>(())
+    /// ```
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns the number of whole words which can still be written to the
+    /// buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ArrayBuf;
+    ///
+    /// let mut buf = ArrayBuf::<32>::new();
+    /// assert_eq!(buf.remaining_words(), 4);
+    /// buf.extend_from_words(&[1u64])?;
+    /// assert_eq!(buf.remaining_words(), 3);
+    /// # Ok::<_, pod::buf::CapacityError>(())
+    /// ```
+    #[inline]
+    pub const fn remaining_words(&self) -> usize {
+        self.remaining() / WORD_SIZE
+    }
+
     /// Resets the buffer to an empty state.
     ///
     /// This clears the content of the buffer that can be read, treating any
@@ -262,6 +320,28 @@ impl<const N: usize> ArrayBuf<N> {
     }
 }
 
+/// Construct an [`ArrayBuf`] by copying a slice of bytes.
+///
+/// # Examples
+///
+/// ```
+/// use pod::ArrayBuf;
+///
+/// let buf = ArrayBuf::<8>::try_from(&[1u8, 2, 3][..])?;
+/// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+///
+/// assert!(ArrayBuf::<8>::try_from(&[0u8; 9][..]).is_err());
+/// # Ok::<_, pod::buf::CapacityError>(())
+/// ```
+impl<const N: usize> TryFrom<&[u8]> for ArrayBuf<N> {
+    type Error = CapacityError;
+
+    #[inline]
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_slice(bytes)
+    }
+}
+
 /// Debug implementation for `ArrayBuf`.
 ///
 /// # Examples
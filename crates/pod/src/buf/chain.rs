@@ -0,0 +1,300 @@
+use core::fmt;
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+use crate::DynamicBuf;
+use crate::error::BufferUnderflow;
+use crate::utils::{self, UninitAlign};
+use crate::{Error, Reader, Slice, Type};
+
+#[cfg(feature = "alloc")]
+use super::AllocError;
+
+/// A reader over two non-contiguous slices of bytes, like the two segments of
+/// a ring buffer.
+///
+/// Since [`Reader`] hands out contiguous slices this does not implement it.
+/// Instead words can be read across the segment boundary, and individual pods
+/// can be split off without copying through [`Chain::split_pod`]. Most pods
+/// are fully contained in one of the segments, in which case they can be
+/// accessed through [`Chain::as_contiguous`].
+///
+/// This is constructed through [`Slice::chain`].
+///
+/// # Examples
+///
+/// ```
+/// use pod::{AsSlice, Value};
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write(1i32)?;
+/// pod.as_mut().write(2i32)?;
+///
+/// let bytes = pod.as_buf().as_bytes();
+/// let (head, tail) = bytes.split_at(20);
+///
+/// let mut chain = pod::buf::slice(head).chain(pod::buf::slice(tail));
+///
+/// let first = chain.split_pod()?;
+/// let value = Value::from_reader(first.as_contiguous().unwrap())?.0;
+/// assert_eq!(value.read_sized::<i32>()?, 1);
+///
+/// let second = chain.split_pod()?;
+/// assert!(second.as_contiguous().is_none());
+///
+/// let owned = second.to_owned()?;
+/// let value = Value::from_reader(owned.as_slice())?.0;
+/// assert_eq!(value.read_sized::<i32>()?, 2);
+///
+/// assert!(chain.is_empty());
+/// # Ok::<_, pod::Error>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct Chain<'de> {
+    head: Slice<'de>,
+    tail: Slice<'de>,
+}
+
+impl<'de> Chain<'de> {
+    #[inline]
+    pub(super) fn new(head: Slice<'de>, tail: Slice<'de>) -> Self {
+        let mut this = Self { head, tail };
+        this.normalize();
+        this
+    }
+
+    /// The length of the chain in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.head.len() + self.tail.len()
+    }
+
+    /// Check if the chain is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_empty()
+    }
+
+    /// Get the two segments of the chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let chain = pod::buf::slice(&[1, 2]).chain(pod::buf::slice(&[3]));
+    /// let (head, tail) = chain.as_slices();
+    /// assert_eq!(head.as_bytes(), &[1, 2]);
+    /// assert_eq!(tail.as_bytes(), &[3]);
+    /// ```
+    #[inline]
+    pub fn as_slices(&self) -> (Slice<'de>, Slice<'de>) {
+        (self.head, self.tail)
+    }
+
+    /// Get the chain as a single contiguous slice, if it only spans one
+    /// segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let chain = pod::buf::slice(&[1, 2]).chain(pod::buf::slice(&[]));
+    /// assert_eq!(chain.as_contiguous().map(|s| s.as_bytes()), Some(&[1, 2][..]));
+    ///
+    /// let chain = pod::buf::slice(&[]).chain(pod::buf::slice(&[3]));
+    /// assert_eq!(chain.as_contiguous().map(|s| s.as_bytes()), Some(&[3][..]));
+    ///
+    /// let chain = pod::buf::slice(&[1, 2]).chain(pod::buf::slice(&[3]));
+    /// assert!(chain.as_contiguous().is_none());
+    /// ```
+    #[inline]
+    pub fn as_contiguous(&self) -> Option<Slice<'de>> {
+        if self.tail.is_empty() {
+            Some(self.head)
+        } else {
+            None
+        }
+    }
+
+    /// Copy the contents of the chain into a contiguous owned buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let chain = pod::buf::slice(&[1, 2]).chain(pod::buf::slice(&[3]));
+    /// let buf = chain.to_owned()?;
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_owned(&self) -> Result<DynamicBuf, AllocError> {
+        let mut buf = DynamicBuf::from_slice(self.head.as_bytes())?;
+        buf.extend_from_words(self.tail.as_bytes())?;
+        Ok(buf)
+    }
+
+    /// Skip the given number of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut chain = pod::buf::slice(&[1, 2]).chain(pod::buf::slice(&[3, 4]));
+    /// chain.skip(3)?;
+    /// assert_eq!(chain.as_contiguous().map(|s| s.as_bytes()), Some(&[4][..]));
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn skip(&mut self, size: usize) -> Result<(), BufferUnderflow> {
+        if size <= self.head.len() {
+            self.head.skip(size)?;
+        } else {
+            self.tail.skip(size - self.head.len())?;
+            self.head = self.head.end();
+        }
+
+        self.normalize();
+        Ok(())
+    }
+
+    /// Unpad the chain by advancing the position to align with the specified
+    /// `align`.
+    #[inline]
+    pub fn unpad(&mut self, align: usize) -> Result<(), BufferUnderflow> {
+        debug_assert!(
+            align <= u8::MAX as usize,
+            "Alignments larger than 256 bytes are not supported"
+        );
+
+        self.skip(self.head.padding(align))
+    }
+
+    /// Split off the head of the chain without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut chain = pod::buf::slice(&[1, 2]).chain(pod::buf::slice(&[3, 4]));
+    ///
+    /// let head = chain.split(3).unwrap();
+    /// assert_eq!(head.len(), 3);
+    /// assert_eq!(chain.len(), 1);
+    /// assert!(chain.split(2).is_none());
+    /// ```
+    pub fn split(&mut self, at: usize) -> Option<Chain<'de>> {
+        if at <= self.head.len() {
+            let (a, b) = self.head.split_at_checked(at)?;
+            self.head = b;
+            self.normalize();
+            return Some(Chain::new(a, a.end()));
+        }
+
+        let (a, b) = self.tail.split_at_checked(at - self.head.len())?;
+        let head = Chain::new(self.head, a);
+        self.head = b;
+        self.tail = b.end();
+        Some(head)
+    }
+
+    /// Split off the next pod in the chain including its header without
+    /// copying, and skip past any trailing padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write(1i32)?;
+    ///
+    /// let (head, tail) = pod.as_buf().as_bytes().split_at(4);
+    /// let mut chain = pod::buf::slice(head).chain(pod::buf::slice(tail));
+    ///
+    /// let value = chain.split_pod()?;
+    /// assert_eq!(value.len(), 12);
+    /// assert!(chain.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn split_pod(&mut self) -> Result<Chain<'de>, Error> {
+        let (size, _) = self.peek_header()?;
+        let size = size.checked_add(8).ok_or(BufferUnderflow)?;
+        let pod = self.split(size).ok_or(BufferUnderflow)?;
+
+        if !self.is_empty() {
+            self.unpad(8)?;
+        }
+
+        Ok(pod)
+    }
+
+    /// Peek type `T` from the chain without consuming it.
+    #[inline]
+    pub fn peek<T>(&self) -> Result<T, BufferUnderflow>
+    where
+        T: Copy,
+    {
+        let mut out = UninitAlign::<T>::uninit();
+        self.peek_words_uninit(out.as_mut_slice())?;
+        // SAFETY: The slice must have been initialized by the reader.
+        Ok(unsafe { out.assume_init() })
+    }
+
+    /// Read type `T` from the chain.
+    #[inline]
+    pub fn read<T>(&mut self) -> Result<T, BufferUnderflow>
+    where
+        T: Copy,
+    {
+        let mut out = UninitAlign::<T>::uninit();
+        self.peek_words_uninit(out.as_mut_slice())?;
+        self.skip(out.size())?;
+        // SAFETY: The slice must have been initialized by the reader.
+        Ok(unsafe { out.assume_init() })
+    }
+
+    /// Peek the size and type of the next pod without consuming the chain.
+    #[inline]
+    pub fn peek_header(&self) -> Result<(usize, Type), Error> {
+        let [size, ty] = self.peek::<[u32; 2]>()?;
+        let ty = Type::new(ty);
+        let size = utils::to_size(size)?;
+        Ok((size, ty))
+    }
+
+    /// Read the size and type of the next pod.
+    #[inline]
+    pub fn header(&mut self) -> Result<(usize, Type), Error> {
+        let [size, ty] = self.read::<[u32; 2]>()?;
+        let ty = Type::new(ty);
+        let size = utils::to_size(size)?;
+        Ok((size, ty))
+    }
+
+    fn peek_words_uninit(&self, out: &mut [MaybeUninit<u8>]) -> Result<(), BufferUnderflow> {
+        if out.len() <= self.head.len() {
+            return self.head.peek_words_uninit(out);
+        }
+
+        if out.len() > self.len() {
+            return Err(BufferUnderflow);
+        }
+
+        let (a, b) = out.split_at_mut(self.head.len());
+        self.head.peek_words_uninit(a)?;
+        self.tail.peek_words_uninit(b)?;
+        Ok(())
+    }
+
+    /// Ensure that the head is only empty if the whole chain is empty.
+    #[inline]
+    fn normalize(&mut self) {
+        if self.head.is_empty() {
+            self.head = self.tail;
+            self.tail = self.tail.end();
+        }
+    }
+}
+
+impl fmt::Debug for Chain<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.head.as_bytes())
+            .entries(self.tail.as_bytes())
+            .finish()
+    }
+}
@@ -5,8 +5,11 @@ use core::mem;
 use core::ptr;
 use core::slice;
 
+use ::alloc::boxed::Box;
 use alloc::alloc;
 
+use super::allocator::{Allocator, Global};
+
 use crate::Slice;
 use crate::SplitReader;
 use crate::utils::BytesInhabited;
@@ -29,11 +32,42 @@ impl fmt::Display for AllocError {
     }
 }
 
+/// How a [`DynamicBuf`] grows when a write exceeds its capacity.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Growth {
+    /// Grow to the next power of two, but to no less than 16 bytes.
+    ///
+    /// This is the default, and avoids frequent reallocations.
+    #[default]
+    Double,
+    /// Grow to exactly the size needed, rounded up to the nearest word.
+    Exact,
+    /// Never grow, so writes which exceed the capacity fail with a
+    /// [`CapacityError`].
+    ///
+    /// Space can still be reserved explicitly, like through
+    /// [`DynamicBuf::try_reserve`], which allows a buffer to be allocated once
+    /// up front and guarantees that writing to it never allocates.
+    ///
+    /// [`CapacityError`]: crate::buf::CapacityError
+    Fixed,
+}
+
 /// A buffer which can be used in combination with a channel.
-pub struct DynamicBuf {
+///
+/// The buffer is backed by an [`Allocator`], which by default is the
+/// [`Global`] allocator. How the buffer grows is controlled by its [`Growth`]
+/// strategy.
+pub struct DynamicBuf<A = Global>
+where
+    A: Allocator,
+{
     data: ptr::NonNull<u8>,
     cap: usize,
     len: usize,
+    growth: Growth,
+    alloc: A,
 }
 
 impl DynamicBuf {
@@ -52,32 +86,333 @@ impl DynamicBuf {
     /// ```
     #[inline]
     pub const fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Construct a new empty buffer with space for at least `capacity` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let buf = DynamicBuf::with_capacity(10)?;
+    /// assert!(buf.is_empty());
+    /// assert_eq!(buf.capacity(), 16);
+    ///
+    /// let buf = DynamicBuf::with_capacity(0)?;
+    /// assert_eq!(buf.capacity(), 0);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    /// Construct a and initialize a new dynamic buffer with the contents of the
+    /// given slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let buf = DynamicBuf::from_slice(&[1, 2, 3])?;
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    ///
+    /// let buf = DynamicBuf::from_slice(&[])?;
+    /// assert!(buf.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn from_slice(data: &[u8]) -> Result<Self, AllocError> {
+        Self::from_slice_in(data, Global)
+    }
+}
+
+impl<A> DynamicBuf<A>
+where
+    A: Allocator,
+{
+    /// Construct a new empty buffer backed by the given allocator.
+    ///
+    /// Nothing is allocated until the buffer is written to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    /// use pod::buf::Global;
+    ///
+    /// let mut buf = DynamicBuf::new_in(Global);
+    /// assert!(buf.is_empty());
+    /// buf.extend_from_words(&[42u64])?;
+    /// assert_eq!(buf.len(), 8);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub const fn new_in(alloc: A) -> Self {
         DynamicBuf {
             data: ptr::NonNull::<u64>::dangling().cast(),
             cap: 0,
             len: 0,
+            growth: Growth::Double,
+            alloc,
         }
     }
 
-    /// Construct a and initialize a new dynamic buffer with the contents of the
-    /// given slice.
-    pub fn from_slice(data: &[u8]) -> Result<Self, AllocError> {
+    /// Construct a new empty buffer backed by the given allocator with space
+    /// for at least `capacity` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    /// use pod::buf::Global;
+    ///
+    /// let buf = DynamicBuf::with_capacity_in(10, Global)?;
+    /// assert_eq!(buf.capacity(), 16);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, AllocError> {
+        let mut buf = Self::new_in(alloc);
+        buf.realloc(capacity)?;
+        Ok(buf)
+    }
+
+    /// Construct a and initialize a new dynamic buffer backed by the given
+    /// allocator with the contents of the given slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    /// use pod::buf::Global;
+    ///
+    /// let buf = DynamicBuf::from_slice_in(&[1, 2, 3], Global)?;
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn from_slice_in(data: &[u8], alloc: A) -> Result<Self, AllocError> {
+        let mut buf = Self::with_capacity_in(data.len(), alloc)?;
+
+        // SAFETY: The buffer has been allocated to fit the data.
+        unsafe {
+            buf.data
+                .as_ptr()
+                .copy_from_nonoverlapping(data.as_ptr(), data.len());
+            buf.advance_written(data.len());
+        }
+
+        Ok(buf)
+    }
+
+    /// Access the allocator backing the buffer.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Get the number of bytes the buffer can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let mut buf = DynamicBuf::new();
+    /// assert_eq!(buf.capacity(), 0);
+    /// buf.extend_from_words(&[42u64])?;
+    /// assert!(buf.capacity() >= 8);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Get the growth strategy of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    /// use pod::buf::Growth;
+    ///
+    /// let buf = DynamicBuf::new();
+    /// assert_eq!(buf.growth(), Growth::Double);
+    /// ```
+    #[inline]
+    pub const fn growth(&self) -> Growth {
+        self.growth
+    }
+
+    /// Set the growth strategy of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    /// use pod::buf::Growth;
+    ///
+    /// let mut buf = DynamicBuf::new();
+    /// buf.set_growth(Growth::Exact);
+    /// buf.extend_from_words(&[1u8, 2, 3])?;
+    /// assert_eq!(buf.capacity(), 8);
+    ///
+    /// buf.set_growth(Growth::Fixed);
+    /// assert!(buf.extend_from_words(&[1u64]).is_err());
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn set_growth(&mut self, growth: Growth) {
+        self.growth = growth;
+    }
+
+    /// Reserve space for at least `additional` more bytes.
+    ///
+    /// Like when the buffer is written to, this might reserve more space than
+    /// requested according to its [`Growth`] strategy. A buffer with
+    /// [`Growth::Fixed`] reserves exactly the space requested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let mut buf = DynamicBuf::from_slice(&[1, 2, 3])?;
+    /// buf.try_reserve(100)?;
+    /// assert!(buf.capacity() >= 103);
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let needed = self.len.checked_add(additional).ok_or(AllocError)?;
+
+        if needed <= self.cap {
+            return Ok(());
+        }
+
+        let cap = self.grown_capacity(needed)?.unwrap_or(needed);
+        self.realloc(cap)
+    }
+
+    /// Reserve space for at least `additional` more bytes without
+    /// over-allocating.
+    ///
+    /// The capacity is only rounded up to the nearest word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let mut buf = DynamicBuf::from_slice(&[1, 2, 3])?;
+    /// buf.try_reserve_exact(100)?;
+    /// assert_eq!(buf.capacity(), 104);
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        let needed = self.len.checked_add(additional).ok_or(AllocError)?;
+
+        if needed <= self.cap {
+            return Ok(());
+        }
+
+        self.realloc(needed)
+    }
+
+    /// Shrink the capacity of the buffer as much as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let mut buf = DynamicBuf::with_capacity(1024)?;
+    /// buf.extend_from_words(&[1u8, 2, 3])?;
+    /// buf.shrink_to_fit()?;
+    /// assert_eq!(buf.capacity(), 8);
+    /// assert_eq!(buf.as_bytes(), &[1, 2, 3]);
+    ///
+    /// buf.clear();
+    /// buf.shrink_to_fit()?;
+    /// assert_eq!(buf.capacity(), 0);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn shrink_to_fit(&mut self) -> Result<(), AllocError> {
+        self.shrink_to(0)
+    }
+
+    /// Shrink the capacity of the buffer to hold at least `min_capacity`
+    /// bytes.
+    ///
+    /// The capacity never shrinks below the length of the buffer, and is never
+    /// grown by this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let mut buf = DynamicBuf::with_capacity(1024)?;
+    /// buf.extend_from_words(&[1u8, 2, 3])?;
+    /// buf.shrink_to(64)?;
+    /// assert_eq!(buf.capacity(), 64);
+    /// buf.shrink_to(0)?;
+    /// assert_eq!(buf.capacity(), 8);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) -> Result<(), AllocError> {
+        let cap = min_capacity
+            .max(self.len)
+            .next_multiple_of(mem::size_of::<u64>());
+
+        if cap >= self.cap {
+            return Ok(());
+        }
+
+        self.realloc(cap)
+    }
+
+    /// Convert the buffer into a boxed slice holding exactly its contents.
+    ///
+    /// Since the buffer is allocated with word alignment the contents are
+    /// copied into a new allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::DynamicBuf;
+    ///
+    /// let mut buf = DynamicBuf::with_capacity(1024)?;
+    /// buf.extend_from_words(&[1u8, 2, 3])?;
+    ///
+    /// let boxed = buf.into_boxed_slice()?;
+    /// assert_eq!(&boxed[..], &[1, 2, 3]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn into_boxed_slice(self) -> Result<Box<[u8]>, AllocError> {
+        if self.len == 0 {
+            return Ok(Box::default());
+        }
+
+        // SAFETY: The layout has a non-zero size and the allocation is
+        // initialized from the contents of the buffer before being boxed.
         unsafe {
-            let layout = Layout::from_size_align(data.len(), mem::align_of::<u64>())
-                .map_err(|_| AllocError)?;
-            let ptr = alloc::alloc(layout);
+            let layout = Layout::array::<u8>(self.len).map_err(|_| AllocError)?;
+            let data = alloc::alloc(layout);
 
-            if ptr.is_null() {
+            if data.is_null() {
                 return Err(AllocError);
             }
 
-            ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
-
-            Ok(DynamicBuf {
-                data: ptr::NonNull::new_unchecked(ptr).cast(),
-                cap: data.len(),
-                len: data.len(),
-            })
+            data.copy_from_nonoverlapping(self.data.as_ptr(), self.len);
+            Ok(Box::from_raw(ptr::slice_from_raw_parts_mut(data, self.len)))
         }
     }
 
@@ -181,8 +516,53 @@ impl DynamicBuf {
         unsafe { slice::from_raw_parts_mut(self.data.as_ptr(), self.len) }
     }
 
+    /// Write to the buffer without allocating.
+    ///
+    /// The closure is called with the buffer behaving as if its growth
+    /// strategy was [`Growth::Fixed`], so writes which exceed the capacity of
+    /// the buffer fail with a [`CapacityError`] instead of allocating. If the
+    /// closure fails, anything it wrote is discarded.
+    ///
+    /// [`CapacityError`]: crate::buf::CapacityError
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{Builder, DynamicBuf};
+    ///
+    /// let mut buf = DynamicBuf::with_capacity(16)?;
+    ///
+    /// buf.try_write(|buf| Builder::new(buf).write_sized(42i32))?;
+    /// assert_eq!(buf.len(), 16);
+    ///
+    /// assert!(buf.try_write(|buf| Builder::new(buf).write_sized(42i32)).is_err());
+    /// assert_eq!(buf.len(), 16);
+    /// assert_eq!(buf.capacity(), 16);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn try_write<O>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<O, Error>,
+    ) -> Result<O, Error> {
+        let growth = mem::replace(&mut self.growth, Growth::Fixed);
+        let len = self.len;
+
+        let result = f(self);
+
+        self.growth = growth;
+
+        if result.is_err() {
+            self.len = len;
+        }
+
+        result
+    }
+
     /// Extend the buffer with a slice of words.
     ///
+    /// This fails if the buffer needs to grow, but its growth strategy is
+    /// [`Growth::Fixed`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -198,6 +578,16 @@ impl DynamicBuf {
     /// ```
     #[inline]
     pub fn extend_from_words<T>(&mut self, words: &[T]) -> Result<(), AllocError>
+    where
+        T: BytesInhabited,
+    {
+        self.extend(words).map_err(|_| AllocError)
+    }
+
+    /// Extend the buffer with a slice of words, growing it according to its
+    /// growth strategy.
+    #[inline]
+    fn extend<T>(&mut self, words: &[T]) -> Result<(), Error>
     where
         T: BytesInhabited,
     {
@@ -238,46 +628,63 @@ impl DynamicBuf {
         self.len = len;
     }
 
-    /// Ensure up to the given length is reserved.
-    fn reserve(&mut self, needed: usize) -> Result<(), AllocError> {
+    /// Ensure up to the given length is reserved, growing the buffer
+    /// according to its growth strategy.
+    fn reserve(&mut self, needed: usize) -> Result<(), Error> {
         if needed <= self.cap {
             return Ok(());
         }
 
-        let new_cap = needed
-            .next_power_of_two()
-            .max(16)
-            .div_ceil(mem::size_of::<u64>());
+        let Some(cap) = self.grown_capacity(needed)? else {
+            return Err(Error::new(ErrorKind::CapacityError(CapacityError)));
+        };
 
-        let (data, cap) = match self.cap {
-            0 => unsafe {
-                let layout = Layout::array::<u64>(new_cap).map_err(|_| AllocError)?;
+        self.realloc(cap)?;
+        Ok(())
+    }
 
-                let data = alloc::alloc(layout);
+    /// Get the capacity the buffer grows to in order to hold `needed` bytes,
+    /// or `None` if it should not grow.
+    fn grown_capacity(&self, needed: usize) -> Result<Option<usize>, AllocError> {
+        match self.growth {
+            Growth::Double => {
+                let cap = needed.checked_next_power_of_two().ok_or(AllocError)?;
+                Ok(Some(cap.max(16)))
+            }
+            Growth::Exact => Ok(Some(needed)),
+            Growth::Fixed => Ok(None),
+        }
+    }
 
-                if data.is_null() {
-                    return Err(AllocError);
-                }
+    /// Reallocate the buffer to hold `cap` bytes rounded up to the nearest
+    /// word, which must not be smaller than the length of the buffer.
+    fn realloc(&mut self, cap: usize) -> Result<(), AllocError> {
+        debug_assert!(cap >= self.len, "Capacity {cap} is less than length");
 
-                (ptr::NonNull::new_unchecked(data), layout.size())
-            },
+        if cap == 0 {
+            self.free();
+            return Ok(());
+        }
+
+        let new_layout =
+            Layout::array::<u64>(cap.div_ceil(mem::size_of::<u64>())).map_err(|_| AllocError)?;
+
+        let data = match self.cap {
+            0 => self.alloc.allocate(new_layout),
+            // SAFETY: The buffer has been allocated with the old layout.
             _ => unsafe {
                 let old_layout =
                     Layout::from_size_align_unchecked(self.cap, mem::align_of::<u64>());
-                let new_layout = Layout::array::<u64>(new_cap).map_err(|_| AllocError)?;
-
-                let data = alloc::realloc(self.data.as_ptr().cast(), old_layout, new_layout.size());
-
-                if data.is_null() {
-                    return Err(AllocError);
-                }
-
-                (ptr::NonNull::new_unchecked(data), new_layout.size())
+                self.alloc.reallocate(self.data, old_layout, new_layout)
             },
         };
 
-        self.data = data.cast();
-        self.cap = cap;
+        let Some(data) = data else {
+            return Err(AllocError);
+        };
+
+        self.data = data;
+        self.cap = new_layout.size();
         Ok(())
     }
 
@@ -286,7 +693,7 @@ impl DynamicBuf {
             // SAFETY: The buffer is guaranteed to be allocated with the same alignment as `A`.
             unsafe {
                 let layout = Layout::from_size_align_unchecked(self.cap, mem::align_of::<u64>());
-                alloc::dealloc(self.data.as_ptr().cast(), layout);
+                self.alloc.deallocate(self.data, layout);
             }
 
             self.data = ptr::NonNull::<u64>::dangling().cast();
@@ -296,7 +703,10 @@ impl DynamicBuf {
     }
 }
 
-impl Drop for DynamicBuf {
+impl<A> Drop for DynamicBuf<A>
+where
+    A: Allocator,
+{
     #[inline]
     fn drop(&mut self) {
         self.clear();
@@ -304,15 +714,24 @@ impl Drop for DynamicBuf {
     }
 }
 
-impl AsSlice for DynamicBuf {
+impl<A> AsSlice for DynamicBuf<A>
+where
+    A: Allocator,
+{
     #[inline]
     fn as_slice(&self) -> Slice<'_> {
         Slice::new(self.as_bytes())
     }
 }
 
-impl SplitReader for DynamicBuf {
-    type TakeReader<'this> = Slice<'this>;
+impl<A> SplitReader for DynamicBuf<A>
+where
+    A: Allocator,
+{
+    type TakeReader<'this>
+        = Slice<'this>
+    where
+        Self: 'this;
 
     #[inline]
     fn take_reader(&mut self) -> Self::TakeReader<'_> {
@@ -340,9 +759,12 @@ impl Pos for DynamicBufPos {
     }
 }
 
-impl Writer for DynamicBuf {
+impl<A> Writer for DynamicBuf<A>
+where
+    A: Allocator,
+{
     type Mut<'this>
-        = &'this mut DynamicBuf
+        = &'this mut DynamicBuf<A>
     where
         Self: 'this;
 
@@ -390,7 +812,7 @@ impl Writer for DynamicBuf {
     where
         T: BytesInhabited,
     {
-        self.extend_from_words(words)?;
+        self.extend(words)?;
         Ok(())
     }
 
@@ -516,7 +938,10 @@ impl Default for DynamicBuf {
     }
 }
 
-impl fmt::Debug for DynamicBuf {
+impl<A> fmt::Debug for DynamicBuf<A>
+where
+    A: Allocator,
+{
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.as_bytes()).finish()
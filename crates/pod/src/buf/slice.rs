@@ -4,10 +4,14 @@ use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 use core::slice;
 
+#[cfg(feature = "alloc")]
+use crate::DynamicBuf;
 use crate::error::BufferUnderflow;
-use crate::{AsSlice, DynamicBuf, Error, Reader, SplitReader, Visitor};
+use crate::{AsSlice, Error, Reader, SplitReader, Visitor};
 
+#[cfg(feature = "alloc")]
 use super::AllocError;
+use super::Chain;
 
 /// A buffer that represents a slice of bytes.
 #[derive(Clone, Copy)]
@@ -56,6 +60,7 @@ impl<'de> Slice<'de> {
     /// let buf = slice.to_owned()?;
     /// # Ok::<_, pod::Error>(())
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn to_owned(&self) -> Result<DynamicBuf, AllocError> {
         DynamicBuf::from_slice(self.as_bytes())
     }
@@ -147,6 +152,54 @@ impl<'de> Slice<'de> {
         Some((a, b))
     }
 
+    /// Chain this slice with a `tail` slice, constructing a reader which spans
+    /// both of them without copying.
+    ///
+    /// The tail is treated as if it was directly following this slice, which
+    /// is the case when reading from the two segments of a ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let head = pod::buf::slice(&[1, 0, 0, 0, 2, 0]);
+    /// let tail = pod::buf::slice(&[0, 0]);
+    ///
+    /// let mut chain = head.chain(tail);
+    /// assert_eq!(chain.len(), 8);
+    /// assert_eq!(chain.read::<[u32; 2]>()?, [1, 2]);
+    /// assert!(chain.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn chain(self, tail: Slice<'de>) -> Chain<'de> {
+        let tail = Slice {
+            off: (self.off as usize).wrapping_add(self.len) as u8,
+            ..tail
+        };
+
+        Chain::new(self, tail)
+    }
+
+    /// Get an empty slice positioned at the end of this slice.
+    #[inline]
+    pub(super) fn end(&self) -> Slice<'de> {
+        Slice {
+            ptr: unsafe { wrapping_add(self.ptr, self.len) },
+            len: 0,
+            off: (self.off as usize).wrapping_add(self.len) as u8,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the number of padding bytes needed to align the slice to `align`.
+    #[inline]
+    pub(super) fn padding(&self, align: usize) -> usize {
+        match (self.off as usize) % align {
+            0 => 0,
+            remaining => align - remaining,
+        }
+    }
+
     #[inline]
     fn offset(&mut self, size: usize) {
         self.off = (self.off as usize).wrapping_add(size) as u8;
@@ -179,6 +232,8 @@ impl<'de> Reader<'de> for Slice<'de> {
 
     type Pos = Pos<'de>;
 
+    type Checkpoint = Slice<'de>;
+
     #[inline]
     fn borrow_mut(&mut self) -> Self::Mut<'_> {
         self
@@ -230,6 +285,32 @@ impl<'de> Reader<'de> for Slice<'de> {
         self.ptr.addr().get().wrapping_sub(pos.ptr.addr().get())
     }
 
+    /// Save the current state of the reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::Reader;
+    ///
+    /// let mut buf = pod::buf::slice(&[1, 0, 0, 0, 2, 0, 0, 0]);
+    ///
+    /// let checkpoint = buf.checkpoint();
+    /// assert_eq!(buf.read::<u32>()?, 1);
+    /// buf.rollback(checkpoint);
+    /// assert_eq!(buf.read::<u32>()?, 1);
+    /// assert_eq!(buf.read::<u32>()?, 2);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        *self
+    }
+
+    #[inline]
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        *self = checkpoint;
+    }
+
     /// Skip the given number of bytes in the reader.
     ///
     /// # Examples
@@ -481,6 +481,7 @@ where
     #[inline]
     pub fn write_none(mut self) -> Result<(), Error> {
         self.kind.check(Type::NONE, 0)?;
+        self.kind.header(self.buf.borrow_mut())?;
         self.buf.write(&[0, Type::NONE.into_u32()])?;
         Ok(())
     }
@@ -824,6 +825,110 @@ where
         Ok(())
     }
 
+    /// Write a [`ChoiceType::RANGE`] choice of values between `min` and `max`,
+    /// where `default` is the preferred value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ChoiceType;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_range(1i32, 2, 32)?;
+    ///
+    /// let choice = pod.as_ref().read_choice()?;
+    /// assert_eq!(choice.choice_type(), ChoiceType::RANGE);
+    ///
+    /// let range = choice.as_range::<i32>()?;
+    /// assert_eq!((range.min, range.default, range.max), (1, 2, 32));
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn write_range<T>(self, min: T, default: T, max: T) -> Result<(), Error>
+    where
+        T: SizedWritable,
+    {
+        self.write_choice(ChoiceType::RANGE, T::TYPE, |choice| {
+            choice.child().write_sized(default)?;
+            choice.child().write_sized(min)?;
+            choice.child().write_sized(max)?;
+            Ok(())
+        })
+    }
+
+    /// Write a [`ChoiceType::STEP`] choice of values between `min` and `max`
+    /// in increments of `step`, where `default` is the preferred value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ChoiceType;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_step(0i32, 64, 1024, 64)?;
+    ///
+    /// let choice = pod.as_ref().read_choice()?;
+    /// assert_eq!(choice.choice_type(), ChoiceType::STEP);
+    ///
+    /// let step = choice.as_step::<i32>()?;
+    /// assert_eq!((step.min, step.default, step.max, step.step), (0, 64, 1024, 64));
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn write_step<T>(self, min: T, default: T, max: T, step: T) -> Result<(), Error>
+    where
+        T: SizedWritable,
+    {
+        self.write_choice(ChoiceType::STEP, T::TYPE, |choice| {
+            choice.child().write_sized(default)?;
+            choice.child().write_sized(min)?;
+            choice.child().write_sized(max)?;
+            choice.child().write_sized(step)?;
+            Ok(())
+        })
+    }
+
+    /// Write a [`ChoiceType::ENUM`] choice of the given alternatives, where
+    /// `default` is the preferred value.
+    ///
+    /// By convention the default value is also one of the alternatives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoiceType, Id};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_enum(Id(2u32), [Id(1u32), Id(2), Id(3)])?;
+    ///
+    /// let choice = pod.as_ref().read_choice()?;
+    /// assert_eq!(choice.choice_type(), ChoiceType::ENUM);
+    ///
+    /// let alternatives = choice.as_enum::<Id<u32>>()?;
+    /// assert_eq!(alternatives.default, Id(2));
+    /// assert_eq!(alternatives.alternatives, [Id(1), Id(2), Id(3)]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn write_enum<T>(
+        self,
+        default: T,
+        alternatives: impl IntoIterator<Item = T>,
+    ) -> Result<(), Error>
+    where
+        T: SizedWritable,
+    {
+        self.write_choice(ChoiceType::ENUM, T::TYPE, |choice| {
+            choice.child().write_sized(default)?;
+
+            for value in alternatives {
+                choice.child().write_sized(value)?;
+            }
+
+            Ok(())
+        })
+    }
+
     /// Write a nested pod.
     ///
     /// # Examples
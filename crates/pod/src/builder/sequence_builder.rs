@@ -1,6 +1,9 @@
 use core::mem;
+use core::time::Duration;
 
-use crate::{BuildPod, Builder, ControlPod, Error, Type, Writer};
+use crate::{BuildPod, Builder, ControlPod, Error, ErrorKind, Type, Writer};
+
+const NSEC_PER_SEC: u128 = 1_000_000_000;
 
 /// An encoder for a sequence.
 #[must_use = "Sequence encoders must be closed to ensure all elements are initialized"]
@@ -13,6 +16,7 @@ where
     header: W::Pos,
     unit: u32,
     pad: u32,
+    rate: Option<u32>,
 }
 
 impl<W, P> SequenceBuilder<W, P>
@@ -36,6 +40,7 @@ where
             header,
             unit: 0,
             pad: 0,
+            rate: None,
         })
     }
 
@@ -58,6 +63,82 @@ where
         Builder::new_with(self.writer.borrow_mut(), ControlPod::new())
     }
 
+    /// Set the sample rate used to convert times into frame offsets by
+    /// [`SequenceBuilder::control_at_time`].
+    ///
+    /// This should be the rate negotiated for the port the sequence is
+    /// written to.
+    #[inline]
+    pub fn set_rate(&mut self, rate: u32) {
+        self.rate = Some(rate);
+    }
+
+    /// Write a control at the given offset in frames from the start of the
+    /// cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_sequence(|seq| {
+    ///     seq.control_at(0).midi(&[0x90, 60, 127])?;
+    ///     seq.control_at(256).midi(&[0x80, 60, 0])?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let mut seq = pod.as_ref().read_sequence()?;
+    /// assert_eq!(seq.control()?.offset(), 0);
+    /// assert_eq!(seq.control()?.offset(), 256);
+    /// assert!(seq.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn control_at(&mut self, frames: u32) -> Builder<W::Mut<'_>, ControlPod> {
+        self.control().offset(frames)
+    }
+
+    /// Write a control at the given time from the start of the cycle.
+    ///
+    /// The time is converted into a frame offset using the rate set through
+    /// [`SequenceBuilder::set_rate`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if no rate has been set, or if the offset in frames doesn't fit
+    /// in 32 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::time::Duration;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_sequence(|seq| {
+    ///     seq.set_rate(48_000);
+    ///     seq.control_at_time(Duration::from_millis(10))?.midi(&[0x90, 60, 127])
+    /// })?;
+    ///
+    /// let mut seq = pod.as_ref().read_sequence()?;
+    /// assert_eq!(seq.control()?.offset(), 480);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn control_at_time(
+        &mut self,
+        time: Duration,
+    ) -> Result<Builder<W::Mut<'_>, ControlPod>, Error> {
+        let Some(rate) = self.rate else {
+            return Err(Error::new(ErrorKind::MissingSequenceRate));
+        };
+
+        let frames = time.as_nanos() * u128::from(rate) / NSEC_PER_SEC;
+
+        let Ok(frames) = u32::try_from(frames) else {
+            return Err(Error::new(ErrorKind::ControlOffsetOverflow));
+        };
+
+        Ok(self.control_at(frames))
+    }
+
     #[inline]
     pub(crate) fn close(mut self) -> Result<(), Error> {
         let size = self
@@ -0,0 +1,569 @@
+//! A C API for building and reading pods.
+//!
+//! This is enabled through the `capi` feature, and the corresponding header can
+//! be generated with [cbindgen] using the `cbindgen.toml` configuration which
+//! is shipped with this crate:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --crate pod --output pod.h
+//! ```
+//!
+//! All functions which can fail return `0` on success and `-1` on failure.
+//! Readers are plain views into memory owned by the caller, which are advanced
+//! past every value which has been successfully read. A value which fails to
+//! be read leaves the reader unmodified.
+//!
+//! [cbindgen]: https://github.com/mozilla/cbindgen
+
+use core::ffi::{CStr, c_char, c_int};
+use core::ptr;
+use core::slice;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::item::Item;
+use crate::{Builder, DynamicBuf, Error, Id, Reader, Slice, Value};
+
+const OK: c_int = 0;
+const ERROR: c_int = -1;
+
+/// A builder of pods.
+///
+/// Values are added to the builder in order, and nested structs are opened
+/// with [`pod_builder_begin_struct`] and closed with [`pod_builder_end_struct`].
+/// The encoded pods are produced by [`pod_builder_finish`].
+pub struct PodBuilder {
+    stack: Vec<Vec<Item>>,
+    pod: Builder<DynamicBuf>,
+}
+
+impl PodBuilder {
+    fn push(&mut self, item: Item) -> c_int {
+        let Some(items) = self.stack.last_mut() else {
+            return ERROR;
+        };
+
+        items.push(item);
+        OK
+    }
+}
+
+/// Construct a new pod builder.
+///
+/// The builder must be freed with [`pod_builder_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn pod_builder_new() -> *mut PodBuilder {
+    let builder = PodBuilder {
+        stack: Vec::from([Vec::new()]),
+        pod: Builder::dynamic(),
+    };
+
+    Box::into_raw(Box::new(builder))
+}
+
+/// Free a pod builder.
+///
+/// # Safety
+///
+/// The builder must have been constructed with [`pod_builder_new`] and not
+/// already been freed. Passing a null pointer does nothing.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_free(builder: *mut PodBuilder) {
+    if !builder.is_null() {
+        // SAFETY: The caller guarantees that the builder is valid.
+        drop(unsafe { Box::from_raw(builder) });
+    }
+}
+
+/// Clear a pod builder so that it can be re-used.
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_clear(builder: *mut PodBuilder) {
+    // SAFETY: The caller guarantees that the builder is valid.
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return;
+    };
+
+    builder.stack.clear();
+    builder.stack.push(Vec::new());
+    builder.pod.clear();
+}
+
+macro_rules! write_sized {
+    ($(#[$meta:meta])* $name:ident, $ty:ty, $variant:ident) => {
+        $(#[$meta])*
+        ///
+        /// # Safety
+        ///
+        /// The builder must be a valid pointer constructed with
+        /// [`pod_builder_new`].
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn $name(builder: *mut PodBuilder, value: $ty) -> c_int {
+            // SAFETY: The caller guarantees that the builder is valid.
+            match unsafe { builder.as_mut() } {
+                Some(builder) => builder.push(Item::$variant(value)),
+                None => ERROR,
+            }
+        }
+    };
+}
+
+write_sized!(
+    /// Add a boolean.
+    pod_builder_write_bool, bool, Bool
+);
+write_sized!(
+    /// Add an identifier.
+    pod_builder_write_id, u32, Id
+);
+write_sized!(
+    /// Add a 32-bit signed integer.
+    pod_builder_write_int, i32, Int
+);
+write_sized!(
+    /// Add a 64-bit signed integer.
+    pod_builder_write_long, i64, Long
+);
+write_sized!(
+    /// Add a 32-bit float.
+    pod_builder_write_float, f32, Float
+);
+write_sized!(
+    /// Add a 64-bit float.
+    pod_builder_write_double, f64, Double
+);
+
+/// Add a none value.
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_write_none(builder: *mut PodBuilder) -> c_int {
+    // SAFETY: The caller guarantees that the builder is valid.
+    match unsafe { builder.as_mut() } {
+        Some(builder) => builder.push(Item::None),
+        None => ERROR,
+    }
+}
+
+/// Add a null-terminated string.
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`],
+/// and `value` must point to a null-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_write_string(
+    builder: *mut PodBuilder,
+    value: *const c_char,
+) -> c_int {
+    // SAFETY: The caller guarantees that the builder is valid.
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return ERROR;
+    };
+
+    if value.is_null() {
+        return ERROR;
+    }
+
+    // SAFETY: The caller guarantees that the string is null-terminated.
+    let value = unsafe { CStr::from_ptr(value) };
+    builder.push(Item::String(value.into()))
+}
+
+/// Add a byte array of the given length.
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`],
+/// and `data` must point to `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_write_bytes(
+    builder: *mut PodBuilder,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    // SAFETY: The caller guarantees that the builder is valid.
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the data is valid.
+    let Some(data) = (unsafe { bytes(data, len) }) else {
+        return ERROR;
+    };
+
+    builder.push(Item::Bytes(data.into()))
+}
+
+/// Begin a struct, which all subsequently added values are fields of until
+/// [`pod_builder_end_struct`] is called.
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_begin_struct(builder: *mut PodBuilder) -> c_int {
+    // SAFETY: The caller guarantees that the builder is valid.
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return ERROR;
+    };
+
+    builder.stack.push(Vec::new());
+    OK
+}
+
+/// End the struct most recently opened with [`pod_builder_begin_struct`].
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_end_struct(builder: *mut PodBuilder) -> c_int {
+    // SAFETY: The caller guarantees that the builder is valid.
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return ERROR;
+    };
+
+    if builder.stack.len() < 2 {
+        return ERROR;
+    }
+
+    let Some(items) = builder.stack.pop() else {
+        return ERROR;
+    };
+
+    builder.push(Item::Struct(items))
+}
+
+/// Encode every added value and store a pointer to and the length of the
+/// encoded pods in `data` and `len`.
+///
+/// The encoded data is owned by the builder, and is valid until the builder is
+/// modified or freed. Fails if a struct has not been ended.
+///
+/// # Safety
+///
+/// The builder must be a valid pointer constructed with [`pod_builder_new`],
+/// and `data` and `len` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_builder_finish(
+    builder: *mut PodBuilder,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> c_int {
+    // SAFETY: The caller guarantees that the builder is valid.
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return ERROR;
+    };
+
+    let [items] = builder.stack.as_slice() else {
+        return ERROR;
+    };
+
+    if data.is_null() || len.is_null() {
+        return ERROR;
+    }
+
+    builder.pod.clear();
+
+    if builder.pod.as_mut().write(items.as_slice()).is_err() {
+        return ERROR;
+    }
+
+    let bytes = builder.pod.as_buf().as_bytes();
+
+    // SAFETY: The caller guarantees that the outputs are valid.
+    unsafe {
+        data.write(bytes.as_ptr());
+        len.write(bytes.len());
+    }
+
+    OK
+}
+
+/// A reader of pods, which is a view into memory owned by the caller.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PodReader {
+    /// The data which is left to read.
+    pub data: *const u8,
+    /// The number of bytes left to read.
+    pub len: usize,
+}
+
+impl PodReader {
+    /// Read the next value, only advancing the reader if `f` succeeds.
+    ///
+    /// # Safety
+    ///
+    /// The reader must point to `len` readable bytes.
+    unsafe fn read<'de, T>(
+        &mut self,
+        f: impl FnOnce(Value<Slice<'de>>) -> Result<T, Error>,
+    ) -> Option<T> {
+        // SAFETY: The caller guarantees that the reader is valid.
+        let data = unsafe { bytes(self.data, self.len)? };
+        let (value, mut rest) = Value::from_reader(Slice::new(data)).ok()?;
+        rest.unpad(8).ok()?;
+        let output = f(value).ok()?;
+        *self = PodReader::from_slice(rest);
+        Some(output)
+    }
+
+    fn from_slice(slice: Slice<'_>) -> Self {
+        let bytes = slice.as_bytes();
+
+        Self {
+            data: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+}
+
+/// Construct a reader over `len` bytes of encoded pods starting at `data`.
+#[unsafe(no_mangle)]
+pub extern "C" fn pod_reader_init(data: *const u8, len: usize) -> PodReader {
+    PodReader { data, len }
+}
+
+/// Test if the reader has no more values to read.
+///
+/// # Safety
+///
+/// The reader must be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_reader_is_empty(reader: *const PodReader) -> bool {
+    // SAFETY: The caller guarantees that the reader is valid.
+    match unsafe { reader.as_ref() } {
+        Some(reader) => reader.len == 0,
+        None => true,
+    }
+}
+
+/// Store the type of the next value in `ty` without advancing the reader.
+///
+/// # Safety
+///
+/// The reader must be a valid pointer to a reader over readable memory, and
+/// `ty` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_reader_peek_type(reader: *const PodReader, ty: *mut u32) -> c_int {
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(mut reader) = (unsafe { reader.as_ref().copied() }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(value) = (unsafe { reader.read(|value| Ok(value.ty())) }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the output is valid.
+    unsafe { write_out(ty, value.into_u32()) }
+}
+
+macro_rules! read_sized {
+    ($(#[$meta:meta])* $name:ident, $ty:ty $(, $map:expr)?) => {
+        $(#[$meta])*
+        ///
+        /// # Safety
+        ///
+        /// The reader must be a valid pointer to a reader over readable
+        /// memory, and `out` must be valid for writes.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn $name(reader: *mut PodReader, out: *mut $ty) -> c_int {
+            // SAFETY: The caller guarantees that the reader is valid.
+            let Some(reader) = (unsafe { reader.as_mut() }) else {
+                return ERROR;
+            };
+
+            // SAFETY: The caller guarantees that the reader is valid.
+            let value = unsafe { reader.read(|value| value.read_sized()) };
+
+            let Some(value) = value else {
+                return ERROR;
+            };
+
+            $(let value = $map(value);)?
+
+            // SAFETY: The caller guarantees that the output is valid.
+            unsafe { write_out(out, value) }
+        }
+    };
+}
+
+read_sized!(
+    /// Read a boolean.
+    pod_reader_read_bool, bool
+);
+read_sized!(
+    /// Read an identifier.
+    pod_reader_read_id, u32, |Id(value): Id<u32>| value
+);
+read_sized!(
+    /// Read a 32-bit signed integer.
+    pod_reader_read_int, i32
+);
+read_sized!(
+    /// Read a 64-bit signed integer.
+    pod_reader_read_long, i64
+);
+read_sized!(
+    /// Read a 32-bit float.
+    pod_reader_read_float, f32
+);
+read_sized!(
+    /// Read a 64-bit float.
+    pod_reader_read_double, f64
+);
+
+/// Read a string, storing a pointer to it in `out`.
+///
+/// The string is null-terminated and borrowed from the memory of the reader.
+///
+/// # Safety
+///
+/// The reader must be a valid pointer to a reader over readable memory, and
+/// `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_reader_read_string(
+    reader: *mut PodReader,
+    out: *mut *const c_char,
+) -> c_int {
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(reader) = (unsafe { reader.as_mut() }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(value) = (unsafe { reader.read(|value| value.read_unsized::<CStr>()) }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the output is valid.
+    unsafe { write_out(out, value.as_ptr()) }
+}
+
+/// Read a byte array, storing a pointer to it in `data` and its length in
+/// `len`.
+///
+/// The bytes are borrowed from the memory of the reader.
+///
+/// # Safety
+///
+/// The reader must be a valid pointer to a reader over readable memory, and
+/// `data` and `len` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_reader_read_bytes(
+    reader: *mut PodReader,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> c_int {
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(reader) = (unsafe { reader.as_mut() }) else {
+        return ERROR;
+    };
+
+    if data.is_null() || len.is_null() {
+        return ERROR;
+    }
+
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(value) = (unsafe { reader.read(|value| value.read_unsized::<[u8]>()) }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the outputs are valid.
+    unsafe {
+        data.write(value.as_ptr());
+        len.write(value.len());
+    }
+
+    OK
+}
+
+/// Read a struct, storing a reader over its fields in `fields`.
+///
+/// # Safety
+///
+/// The reader must be a valid pointer to a reader over readable memory, and
+/// `fields` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_reader_read_struct(
+    reader: *mut PodReader,
+    fields: *mut PodReader,
+) -> c_int {
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(reader) = (unsafe { reader.as_mut() }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the reader is valid.
+    let value = unsafe { reader.read(|value| Ok(value.read_struct()?.as_buf().as_bytes())) };
+
+    let Some(value) = value else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the output is valid.
+    unsafe { write_out(fields, pod_reader_init(value.as_ptr(), value.len())) }
+}
+
+/// Skip the next value.
+///
+/// # Safety
+///
+/// The reader must be a valid pointer to a reader over readable memory.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pod_reader_skip(reader: *mut PodReader) -> c_int {
+    // SAFETY: The caller guarantees that the reader is valid.
+    let Some(reader) = (unsafe { reader.as_mut() }) else {
+        return ERROR;
+    };
+
+    // SAFETY: The caller guarantees that the reader is valid.
+    match unsafe { reader.read(|value| value.skip()) } {
+        Some(..) => OK,
+        None => ERROR,
+    }
+}
+
+/// Construct a byte slice from a pointer and a length, where a null pointer is
+/// only permitted for an empty slice.
+///
+/// # Safety
+///
+/// The pointer must point to `len` readable bytes.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        return Some(&[]);
+    }
+
+    if data.is_null() {
+        return None;
+    }
+
+    // SAFETY: The caller guarantees that the data is valid.
+    Some(unsafe { slice::from_raw_parts(data, len) })
+}
+
+/// Write a value to an output pointer, failing if it is null.
+///
+/// # Safety
+///
+/// The pointer must be valid for writes if it is non-null.
+unsafe fn write_out<T>(out: *mut T, value: T) -> c_int {
+    if out.is_null() {
+        return ERROR;
+    }
+
+    // SAFETY: The caller guarantees that the output is valid.
+    unsafe { ptr::write(out, value) };
+    OK
+}
@@ -1,5 +1,8 @@
 use core::fmt;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 /// The type of a choice.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -25,7 +28,7 @@ impl ChoiceType {
 impl ChoiceType {
     /// Convert the choice into a `u32`.
     #[inline]
-    pub(crate) fn into_u32(self) -> u32 {
+    pub(crate) const fn into_u32(self) -> u32 {
         self.0
     }
 
@@ -49,3 +52,67 @@ impl fmt::Debug for ChoiceType {
         }
     }
 }
+
+/// How to handle a choice when a plain value is expected.
+///
+/// Choices of type [`ChoiceType::NONE`] are always unwrapped, since they only
+/// have one valid option.
+///
+/// See [`Value::read_sized_with`].
+///
+/// [`Value::read_sized_with`]: crate::Value::read_sized_with
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChoicePolicy {
+    /// Error if the choice is of any type other than [`ChoiceType::NONE`].
+    #[default]
+    Strict,
+    /// Take the default value of the choice, which is its first child.
+    Default,
+}
+
+/// The options of a [`ChoiceType::RANGE`] choice.
+///
+/// See [`Choice::as_range`].
+///
+/// [`Choice::as_range`]: crate::Choice::as_range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChoiceRange<T> {
+    /// The preferred value.
+    pub default: T,
+    /// The smallest value.
+    pub min: T,
+    /// The largest value.
+    pub max: T,
+}
+
+/// The options of a [`ChoiceType::STEP`] choice.
+///
+/// See [`Choice::as_step`].
+///
+/// [`Choice::as_step`]: crate::Choice::as_step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChoiceStep<T> {
+    /// The preferred value.
+    pub default: T,
+    /// The smallest value.
+    pub min: T,
+    /// The largest value.
+    pub max: T,
+    /// The increment between values.
+    pub step: T,
+}
+
+/// The options of a [`ChoiceType::ENUM`] choice.
+///
+/// See [`Choice::as_enum`].
+///
+/// [`Choice::as_enum`]: crate::Choice::as_enum
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChoiceEnum<T> {
+    /// The preferred value.
+    pub default: T,
+    /// The alternatives, where preferred values come first.
+    pub alternatives: Vec<T>,
+}
@@ -0,0 +1,131 @@
+use core::fmt;
+use core::slice;
+
+use crate::{AsSlice, ChoiceType, Pod, Slice, Type};
+
+/// A pod which has been assembled at compile time.
+///
+/// This is constructed through the [`const_pod!`] macro, see it for
+/// documentation.
+///
+/// [`const_pod!`]: crate::macros::const_pod
+#[repr(C, align(8))]
+pub struct ConstPod<const N: usize> {
+    words: [u32; N],
+}
+
+impl<const N: usize> ConstPod<N> {
+    /// Get the bytes of the pod.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        // SAFETY: The words are plain data and the length is in bytes.
+        unsafe { slice::from_raw_parts(self.words.as_ptr().cast(), N * 4) }
+    }
+
+    /// Access the pod for reading.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// pod::macros::const_pod! {
+    ///     static POD = Int(42);
+    /// }
+    ///
+    /// assert_eq!(POD.as_pod().read_sized::<i32>()?, 42);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn as_pod(&self) -> Pod<Slice<'_>> {
+        Pod::new(self.as_slice())
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub const fn __writer() -> ConstWriter<N> {
+        ConstWriter {
+            words: [0; N],
+            at: 0,
+        }
+    }
+}
+
+impl<const N: usize> AsSlice for ConstPod<N> {
+    #[inline]
+    fn as_slice(&self) -> Slice<'_> {
+        Slice::new(self.as_bytes())
+    }
+}
+
+impl<const N: usize> fmt::Debug for ConstPod<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_pod().fmt(f)
+    }
+}
+
+/// Writer used by [`const_pod!`] to assemble a [`ConstPod`].
+///
+/// [`const_pod!`]: crate::macros::const_pod
+#[doc(hidden)]
+pub struct ConstWriter<const N: usize> {
+    words: [u32; N],
+    at: usize,
+}
+
+#[doc(hidden)]
+impl<const N: usize> ConstWriter<N> {
+    pub const fn __u32(mut self, value: u32) -> Self {
+        assert!(self.at < N, "Pod exceeds its computed size");
+        self.words[self.at] = value;
+        self.at += 1;
+        self
+    }
+
+    pub const fn __i32(self, value: i32) -> Self {
+        self.__u32(value as u32)
+    }
+
+    pub const fn __bool(self, value: bool) -> Self {
+        self.__u32(value as u32)
+    }
+
+    pub const fn __f32(self, value: f32) -> Self {
+        self.__u32(value.to_bits())
+    }
+
+    pub const fn __i64(self, value: i64) -> Self {
+        let [a, b, c, d, e, f, g, h] = value.to_ne_bytes();
+        self.__u32(u32::from_ne_bytes([a, b, c, d]))
+            .__u32(u32::from_ne_bytes([e, f, g, h]))
+    }
+
+    pub const fn __f64(self, value: f64) -> Self {
+        self.__i64(value.to_bits() as i64)
+    }
+
+    pub const fn __header(self, size: usize, ty: Type) -> Self {
+        self.__u32(size as u32).__u32(ty.into_u32())
+    }
+
+    pub const fn __type(self, ty: Type) -> Self {
+        self.__u32(ty.into_u32())
+    }
+
+    pub const fn __choice(self, ty: ChoiceType) -> Self {
+        self.__u32(ty.into_u32())
+    }
+
+    /// Pad to the nearest word.
+    pub const fn __pad(self) -> Self {
+        if self.at.is_multiple_of(2) {
+            return self;
+        }
+
+        self.__u32(0)
+    }
+
+    pub const fn __finish(self) -> ConstPod<N> {
+        assert!(self.at == N, "Pod is smaller than its computed size");
+        ConstPod { words: self.words }
+    }
+}
@@ -2,6 +2,50 @@ use core::fmt;
 
 use crate::{AsSlice, Value};
 
+/// The type of a control inside of a sequence.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ControlType(u32);
+
+impl ControlType {
+    /// An invalid control.
+    pub const INVALID: Self = Self(0);
+    /// The control contains an object with properties.
+    pub const PROPERTIES: Self = Self(1);
+    /// The control contains raw MIDI bytes.
+    pub const MIDI: Self = Self(2);
+    /// The control contains an OSC packet.
+    pub const OSC: Self = Self(3);
+    /// The control contains a UMP (Universal MIDI Packet).
+    pub const UMP: Self = Self(4);
+
+    /// Convert the control type into a `u32`.
+    #[inline]
+    pub const fn into_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Convert a `u32` into a control type.
+    #[inline]
+    pub const fn from_u32(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Debug for ControlType {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "Invalid"),
+            1 => write!(f, "Properties"),
+            2 => write!(f, "Midi"),
+            3 => write!(f, "Osc"),
+            4 => write!(f, "Ump"),
+            _ => write!(f, "Unknown({})", self.0),
+        }
+    }
+}
+
 /// A control item inside of a sequence.
 ///
 /// # Examples
@@ -75,6 +119,27 @@ impl<B> Control<B> {
         self.ty
     }
 
+    /// Get the type of the control as a [`ControlType`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ControlType;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_sequence(|seq| seq.control().midi(&[0x90, 60, 127]))?;
+    ///
+    /// let mut seq = pod.as_ref().read_sequence()?;
+    /// let c = seq.control()?;
+    /// assert_eq!(c.control_type(), ControlType::MIDI);
+    /// assert_eq!(c.value().read_unsized::<[u8]>()?, &[0x90, 60, 127]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub fn control_type(&self) -> ControlType {
+        ControlType::from_u32(self.ty)
+    }
+
     /// Access the value of the control.
     ///
     /// # Examples
@@ -56,6 +56,22 @@ impl Error {
         })
     }
 
+    #[doc(hidden)]
+    pub fn __unknown_variant_id(actual: impl RawId) -> Self {
+        Self::new(ErrorKind::UnknownVariantId {
+            actual: actual.into_id(),
+        })
+    }
+
+    #[doc(hidden)]
+    pub fn __property_array_length(key: impl RawId, expected: usize, actual: usize) -> Self {
+        Self::new(ErrorKind::PropertyArrayLength {
+            key: key.into_id(),
+            expected,
+            actual,
+        })
+    }
+
     #[doc(hidden)]
     pub fn __missing_object_field(name: &'static str) -> Self {
         Self::new(ErrorKind::MissingObjectField { name })
@@ -118,6 +134,15 @@ pub(crate) enum ErrorKind {
     NotUtf8,
     NotSupportedRef,
     InvalidArrayLength,
+    MissingSequenceRate,
+    ControlOffsetOverflow,
+    #[cfg(feature = "alloc")]
+    InvalidContainerMagic,
+    #[cfg(feature = "alloc")]
+    UnsupportedContainerVersion {
+        major: u16,
+        minor: u16,
+    },
     UnsizedTypeInArray {
         ty: Type,
     },
@@ -139,6 +164,7 @@ pub(crate) enum ErrorKind {
         expected: usize,
         actual: usize,
     },
+    #[cfg(feature = "alloc")]
     ReservedOverflow {
         write: usize,
         len: usize,
@@ -182,6 +208,14 @@ pub(crate) enum ErrorKind {
     MissingObjectIndex {
         index: usize,
     },
+    UnknownVariantId {
+        actual: u32,
+    },
+    PropertyArrayLength {
+        key: u32,
+        expected: usize,
+        actual: usize,
+    },
     InvalidChoiceType {
         ty: Type,
         expected: ChoiceType,
@@ -274,6 +308,17 @@ impl fmt::Display for Error {
             ErrorKind::NotUtf8 => write!(f, "String does not contain valid UTF-8"),
             ErrorKind::NotSupportedRef => write!(f, "Decoding into reference is not supported"),
             ErrorKind::InvalidArrayLength => write!(f, "Invalid array length"),
+            ErrorKind::MissingSequenceRate => write!(
+                f,
+                "Sequence has no rate to convert control times into frames"
+            ),
+            ErrorKind::ControlOffsetOverflow => write!(f, "Control offset overflows 32 bits"),
+            #[cfg(feature = "alloc")]
+            ErrorKind::InvalidContainerMagic => write!(f, "Invalid pod container magic"),
+            #[cfg(feature = "alloc")]
+            ErrorKind::UnsupportedContainerVersion { major, minor } => {
+                write!(f, "Unsupported pod container version {major}.{minor}")
+            }
             ErrorKind::UnsizedTypeInArray { ty } => write!(
                 f,
                 "Unsized type {ty:?} in array, use write_unsized_array instead"
@@ -310,6 +355,7 @@ impl fmt::Display for Error {
                     "Expected reserved to write {expected} bytes, but found {actual}"
                 )
             }
+            #[cfg(feature = "alloc")]
             ErrorKind::ReservedOverflow {
                 write,
                 len,
@@ -353,6 +399,19 @@ impl fmt::Display for Error {
             ErrorKind::MissingObjectIndex { index } => {
                 write!(f, "Missing object index {index}")
             }
+            ErrorKind::UnknownVariantId { actual } => {
+                write!(f, "No variant matches id {actual}")
+            }
+            ErrorKind::PropertyArrayLength {
+                key,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Expected an array of length {expected} for property {key}, but found {actual}"
+                )
+            }
             ErrorKind::InvalidChoiceType {
                 ty,
                 expected,
@@ -41,6 +41,6 @@ impl RawId for u32 {
 /// assert_eq!(pod.as_ref().read_sized::<Id<u32>>()?, Id(142u32));
 /// # Ok::<_, pod::Error>(())
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct Id<T>(pub T);
@@ -0,0 +1,38 @@
+use alloc::ffi::CString;
+use alloc::vec::Vec;
+
+use crate::{Error, Id, PodSink, Writable};
+
+/// A value which has been added to a builder of the C API or the WebAssembly
+/// bindings.
+pub(crate) enum Item {
+    None,
+    Bool(bool),
+    Id(u32),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(CString),
+    Bytes(Vec<u8>),
+    Struct(Vec<Item>),
+}
+
+impl Writable for Item {
+    fn write_into(&self, pod: &mut impl PodSink) -> Result<(), Error> {
+        let pod = pod.next()?;
+
+        match self {
+            Item::None => pod.write_none(),
+            Item::Bool(value) => pod.write_sized(*value),
+            Item::Id(value) => pod.write_sized(Id(*value)),
+            Item::Int(value) => pod.write_sized(*value),
+            Item::Long(value) => pod.write_sized(*value),
+            Item::Float(value) => pod.write_sized(*value),
+            Item::Double(value) => pod.write_sized(*value),
+            Item::String(value) => pod.write_unsized(value.as_c_str()),
+            Item::Bytes(value) => pod.write_unsized(value.as_slice()),
+            Item::Struct(items) => pod.write_struct(|st| st.write(items.as_slice())),
+        }
+    }
+}
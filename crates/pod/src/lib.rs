@@ -26,6 +26,11 @@ mod pod;
 #[doc(inline)]
 pub use self::pod::Pod;
 
+mod const_pod;
+pub use self::const_pod::ConstPod;
+#[doc(hidden)]
+pub use self::const_pod::ConstWriter;
+
 mod value;
 pub use self::value::Value;
 
@@ -73,7 +78,7 @@ pub(crate) mod sized_readable;
 pub use self::sized_readable::SizedReadable;
 
 mod read;
-pub use self::read::{Array, Choice, Object, Sequence, Struct};
+pub use self::read::{Array, Choice, MaybeChoice, Object, Sequence, Struct};
 
 pub mod buf;
 #[cfg(feature = "alloc")]
@@ -83,7 +88,7 @@ pub use self::buf::DynamicBuf;
 pub use self::buf::{ArrayBuf, Slice, WriterSlice};
 
 mod writer;
-pub use self::writer::Writer;
+pub use self::writer::{Reserved, Writer};
 
 mod as_slice;
 pub use self::as_slice::AsSlice;
@@ -116,7 +121,7 @@ mod property;
 pub use self::property::Property;
 
 mod control;
-pub use self::control::Control;
+pub use self::control::{Control, ControlType};
 
 mod pointer;
 pub use self::pointer::Pointer;
@@ -125,7 +130,9 @@ mod fd;
 pub use self::fd::Fd;
 
 mod choice;
-pub use self::choice::ChoiceType;
+#[cfg(feature = "alloc")]
+pub use self::choice::ChoiceEnum;
+pub use self::choice::{ChoicePolicy, ChoiceRange, ChoiceStep, ChoiceType};
 
 pub mod builder;
 #[doc(inline)]
@@ -139,6 +146,15 @@ mod pod_sink;
 #[doc(inline)]
 pub use self::pod_sink::PodSink;
 
+#[cfg(any(feature = "capi", feature = "wasm-bindgen"))]
+mod item;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+
 /// Construct a new [`Pod`] with a 128 word-sized array buffer.
 ///
 /// # Examples
@@ -121,7 +121,7 @@ macro_rules! __id {
 
                 /// Get the identifier value.
                 #[inline]
-                pub fn into_id(self) -> u32 {
+                pub const fn into_id(self) -> u32 {
                     self.0
                 }
 
@@ -133,6 +133,67 @@ macro_rules! __id {
                         _ => Self(value),
                     }
                 }
+
+                /// All known identifiers, together with their names and the
+                /// names of their equivalent SPA constants.
+                pub const NAMES: &'static [(Self, &'static str, Option<&'static str>)] = &[
+                    $((
+                        Self::$field,
+                        stringify!($field),
+                        $crate::macros::one_of!(None, $(Some(stringify!($field_constant)))*),
+                    ),)*
+                ];
+
+                /// Get the name of the identifier, or `None` if it is unknown.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                #[doc = concat!(" use ", stringify!($module), "::", stringify!($ty), ";")]
+                ///
+                #[doc = concat!(" assert!(", stringify!($ty), "::", stringify!($example), ".name().is_some());")]
+                #[doc = concat!(" assert_eq!(", stringify!($ty), "::from_id(u32::MAX / 2).name(), None);")]
+                /// ```
+                pub fn name(&self) -> Option<&'static str> {
+                    match self.0 {
+                        $($field_value => Some(stringify!($field)),)*
+                        _ => None,
+                    }
+                }
+
+                /// Get the name of the equivalent SPA constant of the
+                /// identifier, like `SPA_FORMAT_AUDIO_rate`.
+                pub fn spa_name(&self) -> Option<&'static str> {
+                    Self::NAMES
+                        .iter()
+                        .find(|(id, _, _)| id == self)
+                        .and_then(|&(_, _, spa)| spa)
+                }
+
+                /// Look up an identifier by name.
+                ///
+                /// Names are matched case-insensitively, where `.`, `-` and `_`
+                /// are considered equal, so `audio.rate` matches `AUDIO_RATE`.
+                /// The name of the equivalent SPA constant like
+                /// `SPA_FORMAT_AUDIO_rate` and its short form like `rate` also
+                /// match, where the first identifier matching a short form is
+                /// returned.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                #[doc = concat!(" use ", stringify!($module), "::", stringify!($ty), ";")]
+                ///
+                #[doc = concat!(" let id = ", stringify!($ty), "::", stringify!($example), ";")]
+                #[doc = concat!(" assert_eq!(", stringify!($ty), "::from_name(id.name().unwrap()), Some(id));")]
+                #[doc = concat!(" assert_eq!(", stringify!($ty), "::from_name(\"not.a.name\"), None);")]
+                /// ```
+                pub fn from_name(name: &str) -> Option<Self> {
+                    Self::NAMES
+                        .iter()
+                        .find(|&&(_, field, spa)| $crate::macros::__name_matches(name, field, spa))
+                        .map(|&(id, _, _)| id)
+                }
             }
 
             impl core::default::Default for $ty {
@@ -193,6 +254,39 @@ macro_rules! __id {
 
 pub use __id as id;
 
+/// Test if a name matches the name of an identifier, see the `from_name`
+/// function generated by [`id!`].
+#[doc(hidden)]
+pub fn __name_matches(name: &str, field: &str, spa: Option<&str>) -> bool {
+    fn is_separator(b: u8) -> bool {
+        matches!(b, b'.' | b'-' | b'_')
+    }
+
+    let matches_field = name.len() == field.len()
+        && name
+            .bytes()
+            .zip(field.bytes())
+            .all(|(a, b)| a.eq_ignore_ascii_case(&b) || (is_separator(a) && is_separator(b)));
+
+    if matches_field {
+        return true;
+    }
+
+    let Some(spa) = spa else {
+        return false;
+    };
+
+    if name == spa {
+        return true;
+    }
+
+    // NB: Only suffixes with lowercase characters are short forms, since
+    // suffixes like `LE` in `SPA_AUDIO_FORMAT_S16_LE` are not.
+    spa.rsplit('_')
+        .next()
+        .is_some_and(|short| short.bytes().any(|b| b.is_ascii_lowercase()) && short == name)
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __one_of {
@@ -564,7 +658,7 @@ macro_rules! __flags {
             #[doc = concat!(" use ", stringify!($module), "::", stringify!($ty), ";")]
             ///
             /// let mut pod = pod::array();
-            #[doc = concat!(" pod.as_mut().write(1 | (1 as ", stringify!($repr), ").rotate_right(2))?;")]
+            #[doc = concat!(" pod.as_mut().write(", stringify!($ty), "::", stringify!($example0), ".into_raw() | (1 as ", stringify!($repr), ").rotate_right(2))?;")]
             ///
             #[doc = concat!(" let flags = pod.as_ref().read_sized::<", stringify!($ty), ">()?;")]
             #[doc = concat!(" assert_eq!(flags.unknown_bits(), (1 as ", stringify!($repr), ").rotate_right(2));")]
@@ -740,6 +834,185 @@ macro_rules! __flags {
 
 pub use __flags as flags;
 
+/// Assemble pods at compile time into static byte arrays.
+///
+/// This supports a subset of pods which can be constructed in a constant
+/// context, which is intended for fixed parameters that are sent frequently.
+///
+/// The following values are supported:
+/// * `Bool(bool)`, `Id(u32)`, `Int(i32)`, `Long(i64)`, `Float(f32)` and
+///   `Double(f64)`.
+/// * `Fraction(num, denom)` and `Rectangle(width, height)`.
+/// * `Struct { value, .. }`.
+/// * `Object(object_type, object_id) { key => value, .. }`.
+/// * `Choice(choice_type, child) { value, .. }` where `choice_type` is a
+///   [`ChoiceType`] constant and `child` is one of `Id`, `Int`, `Long`,
+///   `Float` or `Double`.
+///
+/// Identifiers, object types and keys are `u32` expressions. Identifiers
+/// declared through [`id!`] can be converted with their `into_id` method.
+///
+/// [`ChoiceType`]: crate::ChoiceType
+///
+/// # Examples
+///
+/// ```
+/// use pod::ChoiceType;
+///
+/// pod::macros::const_pod! {
+///     /// A fixed format parameter.
+///     static FORMAT = Object(10, 20) {
+///         1 => Id(2),
+///         2 => Choice(ENUM, Int) { 48000, 44100, 48000 },
+///         3 => Struct { Int(1), Long(2), Fraction(1, 30) },
+///     };
+/// }
+///
+/// let mut obj = FORMAT.as_pod().read_object()?;
+/// assert_eq!(obj.object_type::<u32>(), 10);
+/// assert_eq!(obj.object_id::<u32>(), 20);
+///
+/// let p = obj.property()?;
+/// assert_eq!(p.key::<u32>(), 1);
+/// assert_eq!(p.value().read_sized::<pod::Id<u32>>()?, pod::Id(2));
+///
+/// let p = obj.property()?;
+/// let mut choice = p.value().read_choice()?;
+/// assert_eq!(choice.choice_type(), ChoiceType::ENUM);
+/// assert_eq!(choice.next().unwrap().read_sized::<i32>()?, 48000);
+/// assert_eq!(choice.next().unwrap().read_sized::<i32>()?, 44100);
+///
+/// let p = obj.property()?;
+/// let mut st = p.value().read_struct()?;
+/// assert_eq!(st.field()?.read_sized::<i32>()?, 1);
+/// assert_eq!(st.field()?.read_sized::<i64>()?, 2);
+/// # Ok::<_, pod::Error>(())
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __const_pod {
+    (
+        $(
+            $(#[$meta:meta])*
+            $vis:vis static $name:ident = $kind:ident $(($($args:tt)*))? $({$($body:tt)*})?;
+        )*
+    ) => {
+        $(
+            $(#[$meta])*
+            $vis static $name: $crate::ConstPod<{
+                $crate::macros::const_pod!(@size $kind $(($($args)*))? $({$($body)*})?)
+            }> = {
+                let w = $crate::ConstPod::__writer();
+                $crate::macros::const_pod!(@write w, $kind $(($($args)*))? $({$($body)*})?);
+                w.__finish()
+            };
+        )*
+    };
+
+    // The size of a value in words of 32 bits, including its header and any
+    // trailing padding.
+    (@size Bool($e:expr)) => { 4 };
+    (@size Id($e:expr)) => { 4 };
+    (@size Int($e:expr)) => { 4 };
+    (@size Long($e:expr)) => { 4 };
+    (@size Float($e:expr)) => { 4 };
+    (@size Double($e:expr)) => { 4 };
+    (@size Fraction($a:expr, $b:expr)) => { 4 };
+    (@size Rectangle($a:expr, $b:expr)) => { 4 };
+    (@size Struct { $($kind:ident $(($($args:tt)*))? $({$($body:tt)*})?),* $(,)? }) => {
+        2 $(+ $crate::macros::const_pod!(@size $kind $(($($args)*))? $({$($body)*})?))*
+    };
+    (@size Object($ty:expr, $id:expr) { $($key:expr => $kind:ident $(($($args:tt)*))? $({$($body:tt)*})?),* $(,)? }) => {
+        4 $(+ 2 + $crate::macros::const_pod!(@size $kind $(($($args)*))? $({$($body)*})?))*
+    };
+    (@size Choice($choice:ident, $child:ident) { $($value:expr),* $(,)? }) => {
+        2 + usize::next_multiple_of($crate::macros::const_pod!(@choice_size $child { $($value),* }), 2)
+    };
+
+    // The unpadded size of the body of a choice in words.
+    (@choice_size $child:ident { $($value:expr),* }) => {
+        4 $(+ $crate::macros::const_pod!(@child_size $child, $value))*
+    };
+
+    (@child_size Id, $e:expr) => { 1 };
+    (@child_size Int, $e:expr) => { 1 };
+    (@child_size Float, $e:expr) => { 1 };
+    (@child_size Long, $e:expr) => { 2 };
+    (@child_size Double, $e:expr) => { 2 };
+
+    (@child_type Id) => { $crate::Type::ID };
+    (@child_type Int) => { $crate::Type::INT };
+    (@child_type Float) => { $crate::Type::FLOAT };
+    (@child_type Long) => { $crate::Type::LONG };
+    (@child_type Double) => { $crate::Type::DOUBLE };
+
+    (@child $w:ident, Id, $e:expr) => { $w.__u32($e) };
+    (@child $w:ident, Int, $e:expr) => { $w.__i32($e) };
+    (@child $w:ident, Float, $e:expr) => { $w.__f32($e) };
+    (@child $w:ident, Long, $e:expr) => { $w.__i64($e) };
+    (@child $w:ident, Double, $e:expr) => { $w.__f64($e) };
+
+    (@write $w:ident, Bool($e:expr)) => {
+        let $w = $w.__header(4, $crate::Type::BOOL).__bool($e).__pad();
+    };
+    (@write $w:ident, Id($e:expr)) => {
+        let $w = $w.__header(4, $crate::Type::ID).__u32($e).__pad();
+    };
+    (@write $w:ident, Int($e:expr)) => {
+        let $w = $w.__header(4, $crate::Type::INT).__i32($e).__pad();
+    };
+    (@write $w:ident, Long($e:expr)) => {
+        let $w = $w.__header(8, $crate::Type::LONG).__i64($e);
+    };
+    (@write $w:ident, Float($e:expr)) => {
+        let $w = $w.__header(4, $crate::Type::FLOAT).__f32($e).__pad();
+    };
+    (@write $w:ident, Double($e:expr)) => {
+        let $w = $w.__header(8, $crate::Type::DOUBLE).__f64($e);
+    };
+    (@write $w:ident, Fraction($a:expr, $b:expr)) => {
+        let $w = $w.__header(8, $crate::Type::FRACTION).__u32($a).__u32($b);
+    };
+    (@write $w:ident, Rectangle($a:expr, $b:expr)) => {
+        let $w = $w.__header(8, $crate::Type::RECTANGLE).__u32($a).__u32($b);
+    };
+    (@write $w:ident, Struct { $($kind:ident $(($($args:tt)*))? $({$($body:tt)*})?),* $(,)? }) => {
+        let $w = $w.__header(
+            ($crate::macros::const_pod!(@size Struct { $($kind $(($($args)*))? $({$($body)*})?),* }) - 2) * 4,
+            $crate::Type::STRUCT,
+        );
+
+        $($crate::macros::const_pod!(@write $w, $kind $(($($args)*))? $({$($body)*})?);)*
+    };
+    (@write $w:ident, Object($ty:expr, $id:expr) { $($key:expr => $kind:ident $(($($args:tt)*))? $({$($body:tt)*})?),* $(,)? }) => {
+        let $w = $w.__header(
+            ($crate::macros::const_pod!(@size Object($ty, $id) { $($key => $kind $(($($args)*))? $({$($body)*})?),* }) - 2) * 4,
+            $crate::Type::OBJECT,
+        );
+
+        let $w = $w.__u32($ty).__u32($id);
+
+        $(
+            let $w = $w.__u32($key).__u32(0);
+            $crate::macros::const_pod!(@write $w, $kind $(($($args)*))? $({$($body)*})?);
+        )*
+    };
+    (@write $w:ident, Choice($choice:ident, $child:ident) { $($value:expr),* $(,)? }) => {
+        let $w = $w.__header(
+            $crate::macros::const_pod!(@choice_size $child { $($value),* }) * 4,
+            $crate::Type::CHOICE,
+        );
+
+        let $w = $w.__choice($crate::ChoiceType::$choice).__u32(0);
+        let $w = $w.__u32(($crate::macros::const_pod!(@child_size $child, 0)) * 4);
+        let $w = $w.__type($crate::macros::const_pod!(@child_type $child));
+        $(let $w = $crate::macros::const_pod!(@child $w, $child, $value);)*
+        let $w = $w.__pad();
+    };
+}
+
+pub use __const_pod as const_pod;
+
 macro_rules! __encode_into_sized {
     (impl [$($tt:tt)*] $ty:ty $(where $($where:tt)*)?) => {
         impl<$($tt)*> $crate::Writable for $ty
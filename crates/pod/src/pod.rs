@@ -1,14 +1,14 @@
 use core::fmt;
 
+#[cfg(feature = "alloc")]
+use crate::DynamicBuf;
 #[cfg(feature = "alloc")]
 use crate::buf::AllocError;
 use crate::{
-    Array, ArrayBuf, AsSlice, BufferUnderflow, Choice, Error, Object, PackedPod, PodStream,
-    ReadPod, Readable, Reader, Sequence, SizedReadable, Slice, Struct, Type, UnsizedReadable,
-    UnsizedWritable, Value, Visitor, Writer,
+    Array, ArrayBuf, AsSlice, BufferUnderflow, Choice, Error, Object, PackedPod, PaddedPod,
+    PodStream, ReadPod, Readable, Reader, Sequence, SizedReadable, Slice, Struct, Type,
+    UnsizedReadable, UnsizedWritable, Value, Visitor, Writer,
 };
-#[cfg(feature = "alloc")]
-use crate::{DynamicBuf, PaddedPod};
 
 /// A POD (Plain Old Data) handler.
 ///
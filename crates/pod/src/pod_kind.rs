@@ -2,8 +2,8 @@ use core::mem;
 
 use crate::utils;
 use crate::{
-    BufferUnderflow, Error, ErrorKind, PADDING, RawId, Reader, SizedWritable, Type,
-    UnsizedWritable, Writer,
+    BufferUnderflow, ControlType, Error, ErrorKind, PADDING, RawId, Reader, SizedWritable, Type,
+    UnsizedWritable, Writable, Writer,
 };
 
 use super::Builder;
@@ -236,6 +236,84 @@ impl<B> Builder<B, ControlPod> {
         self.as_kind_mut().ty = ty;
         self
     }
+
+    /// Modify the type of a control using a [`ControlType`].
+    pub fn control_type(self, ty: ControlType) -> Self {
+        self.ty(ty.into_u32())
+    }
+}
+
+/// The object type of properties, which is `SPA_TYPE_OBJECT_Props`.
+const OBJECT_TYPE_PROPS: u32 = 0x40002;
+/// The object id of properties, which is `SPA_PARAM_Props`.
+const OBJECT_ID_PROPS: u32 = 2;
+
+impl<B> Builder<B, ControlPod>
+where
+    B: Writer,
+{
+    /// Write raw MIDI bytes as the control.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ControlType;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_sequence(|seq| {
+    ///     seq.control_at(64).midi(&[0x90, 60, 127])
+    /// })?;
+    ///
+    /// let mut seq = pod.as_ref().read_sequence()?;
+    /// let c = seq.control()?;
+    /// assert_eq!(c.offset(), 64);
+    /// assert_eq!(c.control_type(), ControlType::MIDI);
+    /// assert_eq!(c.value().read_unsized::<[u8]>()?, &[0x90, 60, 127]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn midi(self, bytes: &[u8]) -> Result<(), Error> {
+        self.control_type(ControlType::MIDI).write_unsized(bytes)
+    }
+
+    /// Write a change of a single property as the control.
+    ///
+    /// This is encoded as a properties object with one property, which is how
+    /// parameter changes are sent in a control sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::ControlType;
+    ///
+    /// // The key of the volume property.
+    /// const VOLUME: u32 = 0x10003;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_sequence(|seq| {
+    ///     seq.control_at(0).prop_change(VOLUME, 0.5f32)
+    /// })?;
+    ///
+    /// let mut seq = pod.as_ref().read_sequence()?;
+    /// let c = seq.control()?;
+    /// assert_eq!(c.control_type(), ControlType::PROPERTIES);
+    ///
+    /// let mut obj = c.value().read_object()?;
+    /// let p = obj.property()?;
+    /// assert_eq!(p.key::<u32>(), VOLUME);
+    /// assert_eq!(p.value().read_sized::<f32>()?, 0.5);
+    /// assert!(obj.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn prop_change<K>(self, key: K, value: impl Writable) -> Result<(), Error>
+    where
+        K: RawId,
+    {
+        self.control_type(ControlType::PROPERTIES).write_object(
+            OBJECT_TYPE_PROPS,
+            OBJECT_ID_PROPS,
+            |obj| obj.property(key).write(value),
+        )
+    }
 }
 
 impl BuildPod for ControlPod {
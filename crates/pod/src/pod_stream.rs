@@ -1,4 +1,4 @@
-use crate::{Error, Object, Readable, SizedReadable, Slice, Struct, UnsizedReadable};
+use crate::{Error, MaybeChoice, Object, Readable, SizedReadable, Slice, Struct, UnsizedReadable};
 
 /// The protocol for an item from a pod stream.
 pub trait PodItem<'de>
@@ -15,6 +15,11 @@ where
     where
         T: SizedReadable<'de>;
 
+    /// The the next sized the item, or the choice it is offered as.
+    fn read_sized_or_choice<T>(self) -> Result<MaybeChoice<Slice<'de>, T>, Error>
+    where
+        T: SizedReadable<'de>;
+
     /// The the next unsized the item.
     fn read_unsized<T>(self) -> Result<&'de T, Error>
     where
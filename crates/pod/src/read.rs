@@ -12,3 +12,6 @@ pub use self::sequence::Sequence;
 
 mod choice;
 pub use self::choice::Choice;
+
+mod maybe_choice;
+pub use self::maybe_choice::MaybeChoice;
@@ -1,10 +1,12 @@
 use core::fmt;
-use core::mem;
+use core::mem::{self, MaybeUninit};
+use core::slice;
 
 #[cfg(feature = "alloc")]
 use crate::DynamicBuf;
 use crate::PodStream;
 use crate::Readable;
+use crate::SizedReadable;
 #[cfg(feature = "alloc")]
 use crate::buf::AllocError;
 use crate::utils;
@@ -224,6 +226,105 @@ where
         Ok(Some(pod))
     }
 
+    /// Read elements from the array into an uninitialized buffer without
+    /// allocating.
+    ///
+    /// This reads up to `out.len()` elements and returns the initialized
+    /// prefix of the buffer. Any remaining elements are left in the array. If
+    /// decoding an element fails, elements which have already been decoded
+    /// are not dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    ///
+    /// use pod::Type;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_array(Type::INT, |array| {
+    ///     array.child().write(1i32)?;
+    ///     array.child().write(2i32)?;
+    ///     array.child().write(3i32)?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let mut array = pod.as_ref().read_array()?;
+    ///
+    /// let mut out = [MaybeUninit::<i32>::uninit(); 2];
+    /// assert_eq!(array.read_into(&mut out)?, &[1, 2]);
+    /// assert_eq!(array.len(), 1);
+    ///
+    /// let mut out = [MaybeUninit::<i32>::uninit(); 8];
+    /// assert_eq!(array.read_into(&mut out)?, &[3]);
+    /// assert!(array.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn read_into<'out, T>(
+        &mut self,
+        out: &'out mut [MaybeUninit<T>],
+    ) -> Result<&'out mut [T], Error>
+    where
+        T: SizedReadable<'de>,
+    {
+        let mut len = 0;
+
+        for slot in out.iter_mut() {
+            let Some(value) = self.next()? else {
+                break;
+            };
+
+            slot.write(value.read_sized::<T>()?);
+            len += 1;
+        }
+
+        // SAFETY: The first `len` elements have been initialized above.
+        Ok(unsafe { slice::from_raw_parts_mut(out.as_mut_ptr().cast::<T>(), len) })
+    }
+
+    /// Read elements from the array into an existing buffer without
+    /// allocating, returning the number of elements read.
+    ///
+    /// This reads up to `out.len()` elements. Any remaining elements are left
+    /// in the array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::Type;
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_array(Type::INT, |array| {
+    ///     array.child().write(1i32)?;
+    ///     array.child().write(2i32)?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let mut array = pod.as_ref().read_array()?;
+    ///
+    /// let mut out = [0i32; 4];
+    /// assert_eq!(array.read_into_slice(&mut out)?, 2);
+    /// assert_eq!(out, [1, 2, 0, 0]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn read_into_slice<T>(&mut self, out: &mut [T]) -> Result<usize, Error>
+    where
+        T: SizedReadable<'de>,
+    {
+        let mut len = 0;
+
+        for slot in out.iter_mut() {
+            let Some(value) = self.next()? else {
+                break;
+            };
+
+            *slot = value.read_sized::<T>()?;
+            len += 1;
+        }
+
+        Ok(len)
+    }
+
     /// Coerce into an owned [`Array`].
     ///
     /// # Examples
@@ -2,15 +2,18 @@ use core::fmt;
 use core::mem;
 
 #[cfg(feature = "alloc")]
-use crate::DynamicBuf;
+use alloc::vec::Vec;
+
 use crate::PodStream;
 #[cfg(feature = "alloc")]
 use crate::buf::AllocError;
 use crate::utils;
 use crate::{
-    AsSlice, BufferUnderflow, ChoiceType, Error, Readable, Reader, Slice, Type, UnsizedWritable,
-    Value, Writer,
+    AsSlice, BufferUnderflow, ChoiceRange, ChoiceStep, ChoiceType, Error, ErrorKind, Readable,
+    Reader, Slice, Type, UnsizedWritable, Value, Writer,
 };
+#[cfg(feature = "alloc")]
+use crate::{ChoiceEnum, DynamicBuf};
 
 /// A decoder for a choice.
 ///
@@ -395,6 +398,144 @@ where
     }
 }
 
+impl<B> Choice<B>
+where
+    B: AsSlice,
+{
+    /// Read the options of a [`ChoiceType::RANGE`] choice.
+    ///
+    /// Reading does not affect the choice.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the choice is of another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoiceRange, ChoiceType, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+    ///     choice.write((10i32, 0i32, 30i32))
+    /// })?;
+    ///
+    /// let choice = pod.as_ref().read_choice()?;
+    ///
+    /// assert_eq!(
+    ///     choice.as_range::<i32>()?,
+    ///     ChoiceRange { default: 10, min: 0, max: 30 }
+    /// );
+    ///
+    /// assert!(choice.as_step::<i32>().is_err());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn as_range<'de, T>(&'de self) -> Result<ChoiceRange<T>, Error>
+    where
+        T: Readable<'de>,
+    {
+        let (default, min, max) = self.expect(ChoiceType::RANGE)?.read()?;
+        Ok(ChoiceRange { default, min, max })
+    }
+
+    /// Read the options of a [`ChoiceType::STEP`] choice.
+    ///
+    /// Reading does not affect the choice.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the choice is of another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoiceStep, ChoiceType, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_choice(ChoiceType::STEP, Type::INT, |choice| {
+    ///     choice.write((16i32, 0i32, 64i32, 8i32))
+    /// })?;
+    ///
+    /// let choice = pod.as_ref().read_choice()?;
+    ///
+    /// assert_eq!(
+    ///     choice.as_step::<i32>()?,
+    ///     ChoiceStep { default: 16, min: 0, max: 64, step: 8 }
+    /// );
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn as_step<'de, T>(&'de self) -> Result<ChoiceStep<T>, Error>
+    where
+        T: Readable<'de>,
+    {
+        let (default, min, max, step) = self.expect(ChoiceType::STEP)?.read()?;
+        Ok(ChoiceStep {
+            default,
+            min,
+            max,
+            step,
+        })
+    }
+
+    /// Read the options of a [`ChoiceType::ENUM`] choice.
+    ///
+    /// Reading does not affect the choice.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the choice is of another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoiceType, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_choice(ChoiceType::ENUM, Type::INT, |choice| {
+    ///     choice.write((48000i32, 44100i32, 48000i32))
+    /// })?;
+    ///
+    /// let choice = pod.as_ref().read_choice()?;
+    /// let rates = choice.as_enum::<i32>()?;
+    ///
+    /// assert_eq!(rates.default, 48000);
+    /// assert_eq!(rates.alternatives, [44100, 48000]);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn as_enum<'de, T>(&'de self) -> Result<ChoiceEnum<T>, Error>
+    where
+        T: Readable<'de>,
+    {
+        let mut choice = self.expect(ChoiceType::ENUM)?;
+        let default = choice.read()?;
+
+        let mut alternatives = Vec::with_capacity(choice.len());
+
+        while !choice.is_empty() {
+            alternatives.push(choice.read()?);
+        }
+
+        Ok(ChoiceEnum {
+            default,
+            alternatives,
+        })
+    }
+
+    /// Borrow the choice if it is of the expected type.
+    fn expect(&self, expected: ChoiceType) -> Result<Choice<Slice<'_>>, Error> {
+        if self.choice_type != expected {
+            return Err(Error::new(ErrorKind::InvalidChoiceType {
+                ty: self.child_type,
+                expected,
+                actual: self.choice_type,
+            }));
+        }
+
+        Ok(self.as_ref())
+    }
+}
+
 impl<'de, B> PodStream<'de> for Choice<B>
 where
     B: Reader<'de>,
@@ -0,0 +1,128 @@
+use core::fmt;
+
+use crate::error::ErrorKind;
+use crate::{AsSlice, Readable, Reader, SizedReadable, Slice};
+use crate::{BufferUnderflow, Choice, ChoicePolicy, ChoiceType, Error, PodItem, PodStream};
+
+/// A value which was either read directly, or which was offered as a choice.
+///
+/// Choices of type [`ChoiceType::NONE`] only have one valid option, so they
+/// are unwrapped into [`MaybeChoice::Value`].
+///
+/// This implements [`Readable`], so it can be used for fields of derived types
+/// which might be offered as a choice, like the properties of an `EnumFormat`
+/// parameter.
+///
+/// [`ChoiceType::NONE`]: crate::ChoiceType::NONE
+///
+/// # Examples
+///
+/// ```
+/// use pod::{ChoicePolicy, ChoiceType, MaybeChoice, Type};
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write_struct(|st| {
+///     st.field().write(2u32)?;
+///
+///     st.field().write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+///         choice.child().write(48000i32)?;
+///         choice.child().write(8000i32)?;
+///         choice.child().write(192000i32)?;
+///         Ok(())
+///     })
+/// })?;
+///
+/// let mut st = pod.as_ref().read_struct()?;
+/// let (channels, rate) = st.read::<(MaybeChoice<_, u32>, MaybeChoice<_, i32>)>()?;
+///
+/// assert!(matches!(channels, MaybeChoice::Value(2)));
+/// assert!(rate.is_choice());
+/// assert_eq!(rate.resolve(ChoicePolicy::Default)?, 48000);
+/// # Ok::<_, pod::Error>(())
+/// ```
+pub enum MaybeChoice<B, T> {
+    /// A plain value.
+    Value(T),
+    /// A choice.
+    Choice(Choice<B>),
+}
+
+impl<B, T> MaybeChoice<B, T> {
+    /// Test if this is a choice.
+    #[inline]
+    pub fn is_choice(&self) -> bool {
+        matches!(self, MaybeChoice::Choice(..))
+    }
+}
+
+impl<'de, B, T> MaybeChoice<B, T>
+where
+    B: Reader<'de>,
+    T: SizedReadable<'de>,
+{
+    /// Resolve into a plain value using the given [`ChoicePolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoicePolicy, ChoiceType, MaybeChoice, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_choice(ChoiceType::ENUM, Type::INT, |choice| {
+    ///     choice.child().write(2i32)?;
+    ///     choice.child().write(1i32)?;
+    ///     choice.child().write(2i32)?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let value = pod.as_ref().into_value()?;
+    ///
+    /// let choice = value.as_ref().read_sized_or_choice::<i32>()?;
+    /// assert!(choice.resolve(ChoicePolicy::Strict).is_err());
+    ///
+    /// let choice = value.as_ref().read_sized_or_choice::<i32>()?;
+    /// assert_eq!(choice.resolve(ChoicePolicy::Default)?, 2);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn resolve(self, policy: ChoicePolicy) -> Result<T, Error> {
+        match self {
+            MaybeChoice::Value(value) => Ok(value),
+            MaybeChoice::Choice(mut choice) => {
+                if policy == ChoicePolicy::Strict {
+                    return Err(Error::new(ErrorKind::InvalidChoiceType {
+                        ty: choice.child_type(),
+                        expected: ChoiceType::NONE,
+                        actual: choice.choice_type(),
+                    }));
+                }
+
+                let value = choice.next().ok_or(BufferUnderflow)?;
+                value.read_sized()
+            }
+        }
+    }
+}
+
+impl<'de, T> Readable<'de> for MaybeChoice<Slice<'de>, T>
+where
+    T: SizedReadable<'de>,
+{
+    #[inline]
+    fn read_from(pod: &mut impl PodStream<'de>) -> Result<Self, Error> {
+        pod.next()?.read_sized_or_choice()
+    }
+}
+
+impl<B, T> fmt::Debug for MaybeChoice<B, T>
+where
+    B: AsSlice,
+    T: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaybeChoice::Value(value) => f.debug_tuple("Value").field(value).finish(),
+            MaybeChoice::Choice(choice) => f.debug_tuple("Choice").field(choice).finish(),
+        }
+    }
+}
@@ -5,8 +5,8 @@ use crate::RawId;
 #[cfg(feature = "alloc")]
 use crate::buf::{AllocError, DynamicBuf};
 use crate::{
-    AsSlice, BufferUnderflow, Error, ErrorKind, PADDING, PodItem, PodStream, Property, Readable,
-    Reader, SizedReadable, Slice, Type, UnsizedReadable, UnsizedWritable, Value, Writer,
+    AsSlice, BufferUnderflow, Error, ErrorKind, MaybeChoice, PADDING, PodItem, PodStream, Property,
+    Readable, Reader, SizedReadable, Slice, Type, UnsizedReadable, UnsizedWritable, Value, Writer,
 };
 
 use super::Struct;
@@ -370,6 +370,16 @@ impl<'de> PodItem<'de> for Object<Slice<'de>> {
         }))
     }
 
+    #[inline]
+    fn read_sized_or_choice<T>(self) -> Result<MaybeChoice<Slice<'de>, T>, Error>
+    where
+        T: SizedReadable<'de>,
+    {
+        Err(Error::new(ErrorKind::ReadSizedNotSupported {
+            ty: Type::OBJECT,
+        }))
+    }
+
     #[inline]
     fn read_unsized<T>(self) -> Result<&'de T, Error>
     where
@@ -26,6 +26,10 @@ where
     /// The position type used by the reader.
     type Pos: 'de;
 
+    /// A saved state of the reader which can be restored through
+    /// [`Reader::rollback`].
+    type Checkpoint;
+
     /// Borrow the current reader mutably.
     fn borrow_mut(&mut self) -> Self::Mut<'_>;
 
@@ -35,6 +39,13 @@ where
     /// Get the position of the reader relative to the queried position.
     fn distance_from(&self, pos: &Self::Pos) -> usize;
 
+    /// Save the current state of the reader.
+    fn checkpoint(&self) -> Self::Checkpoint;
+
+    /// Restore the reader to a state previously saved through
+    /// [`Reader::checkpoint`].
+    fn rollback(&mut self, checkpoint: Self::Checkpoint);
+
     /// Skip the given number of bytes.
     fn skip(&mut self, size: usize) -> Result<(), BufferUnderflow>;
 
@@ -155,6 +166,70 @@ where
         let size = utils::to_size(size)?;
         Ok((size, ty))
     }
+
+    /// Peek the size and type of the next pod without consuming the reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{AsSlice, Reader, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write(42i32)?;
+    ///
+    /// let buf = pod.as_buf().as_slice();
+    /// assert_eq!(buf.peek_header()?, (4, Type::INT));
+    /// assert_eq!(buf.len(), 16);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    fn peek_header(&self) -> Result<(usize, Type), Error> {
+        let [size, ty] = self.peek::<[u32; 2]>()?;
+        let ty = Type::new(ty);
+        let size = utils::to_size(size)?;
+        Ok((size, ty))
+    }
+
+    /// Speculatively parse from the reader, restoring its state if parsing
+    /// fails.
+    ///
+    /// This is useful when the layout of data is not known up front, like
+    /// when messages differ slightly across protocol versions and one shape
+    /// should be attempted before falling back to another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{AsSlice, Reader, Value};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write(42i32)?;
+    ///
+    /// let mut buf = pod.as_buf().as_slice();
+    ///
+    /// let result = buf.speculate(|buf| Value::from_reader(&mut *buf)?.0.read_sized::<f32>());
+    /// assert!(result.is_err());
+    /// assert_eq!(buf.len(), 16);
+    ///
+    /// let value = buf.speculate(|buf| Value::from_reader(&mut *buf)?.0.read_sized::<i32>())?;
+    /// assert_eq!(value, 42);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    fn speculate<T, E>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, E>) -> Result<T, E>
+    where
+        Self: Sized,
+    {
+        let checkpoint = self.checkpoint();
+
+        match f(self) {
+            Ok(value) => Ok(value),
+            Err(e) => {
+                self.rollback(checkpoint);
+                Err(e)
+            }
+        }
+    }
 }
 
 impl<'de, R> Reader<'de> for &mut R
@@ -168,6 +243,8 @@ where
 
     type Pos = R::Pos;
 
+    type Checkpoint = R::Checkpoint;
+
     #[inline]
     fn borrow_mut(&mut self) -> Self::Mut<'_> {
         (**self).borrow_mut()
@@ -183,6 +260,16 @@ where
         (**self).distance_from(pos)
     }
 
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (**self).checkpoint()
+    }
+
+    #[inline]
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        (**self).rollback(checkpoint)
+    }
+
     #[inline]
     fn skip(&mut self, size: usize) -> Result<(), BufferUnderflow> {
         (**self).skip(size)
@@ -1,5 +1,4 @@
 use core::any;
-#[cfg(feature = "alloc")]
 use core::ffi::CStr;
 
 #[cfg(feature = "alloc")]
@@ -11,11 +10,14 @@ use alloc::string::String;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+#[cfg(feature = "alloc")]
+use crate::OwnedBitmap;
 use crate::buf::ArrayVec;
 use crate::utils::WordBytes;
-#[cfg(feature = "alloc")]
-use crate::{Bitmap, OwnedBitmap, UnsizedReadable};
-use crate::{Error, ErrorKind, Fd, Fraction, Id, Pointer, RawId, Reader, Rectangle, Type};
+use crate::{
+    Bitmap, Error, ErrorKind, Fd, Fraction, Id, Pointer, RawId, Reader, Rectangle, Type,
+    UnsizedReadable,
+};
 
 /// A trait for types that can be decoded.
 pub trait SizedReadable<'de>
@@ -240,7 +240,7 @@ impl SizedWritable for f32 {
 
     #[inline]
     fn write_sized(&self, mut writer: impl Writer) -> Result<(), Error> {
-        writer.write(&[self.to_bits(), 0])
+        writer.write(&[self.to_bits()])
     }
 }
 
@@ -19,7 +19,9 @@ mod sealed {
     pub trait Sealed {}
 
     impl<const N: usize> Sealed for ArrayBuf<N> {}
-    impl Sealed for DynamicBuf {}
+    #[cfg(feature = "alloc")]
+    impl<A> Sealed for DynamicBuf<A> where A: crate::buf::Allocator {}
+    #[cfg(feature = "alloc")]
     impl Sealed for Vec<u8> {}
     impl Sealed for Slice<'_> {}
     impl<R> Sealed for &mut R where R: ?Sized + SplitReader {}
@@ -1,6 +1,14 @@
+#[cfg(feature = "capi")]
+mod capi;
 mod choice;
+mod const_pod;
+mod endian;
 mod object;
+mod roundtrip;
 mod struct_;
+mod utils;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm;
 
 use core::ffi::CStr;
 
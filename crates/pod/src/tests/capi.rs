@@ -0,0 +1,66 @@
+use core::ffi::CStr;
+use core::ptr;
+
+use crate::Type;
+use crate::capi::*;
+
+#[test]
+fn capi_roundtrip() {
+    unsafe {
+        let builder = pod_builder_new();
+
+        assert_eq!(pod_builder_write_int(builder, 42), 0);
+        assert_eq!(pod_builder_begin_struct(builder), 0);
+        assert_eq!(pod_builder_write_string(builder, c"hello".as_ptr()), 0);
+        assert_eq!(pod_builder_write_double(builder, 2.5), 0);
+        assert_eq!(pod_builder_write_bytes(builder, [1, 2, 3].as_ptr(), 3), 0);
+
+        let mut data = ptr::null();
+        let mut len = 0;
+        assert_eq!(pod_builder_finish(builder, &mut data, &mut len), -1);
+
+        assert_eq!(pod_builder_end_struct(builder), 0);
+        assert_eq!(pod_builder_end_struct(builder), -1);
+        assert_eq!(pod_builder_write_id(builder, 7), 0);
+        assert_eq!(pod_builder_finish(builder, &mut data, &mut len), 0);
+
+        let mut reader = pod_reader_init(data, len);
+
+        let mut ty = 0;
+        assert_eq!(pod_reader_peek_type(&reader, &mut ty), 0);
+        assert_eq!(ty, Type::INT.into_u32());
+
+        let mut string = ptr::null();
+        assert_eq!(pod_reader_read_string(&mut reader, &mut string), -1);
+
+        let mut int = 0i32;
+        assert_eq!(pod_reader_read_int(&mut reader, &mut int), 0);
+        assert_eq!(int, 42);
+
+        let mut fields = pod_reader_init(ptr::null(), 0);
+        assert_eq!(pod_reader_read_struct(&mut reader, &mut fields), 0);
+
+        assert_eq!(pod_reader_read_string(&mut fields, &mut string), 0);
+        assert_eq!(CStr::from_ptr(string), c"hello");
+
+        let mut double = 0.0f64;
+        assert_eq!(pod_reader_read_double(&mut fields, &mut double), 0);
+        assert_eq!(double, 2.5);
+
+        let mut bytes = ptr::null();
+        let mut bytes_len = 0;
+        assert_eq!(
+            pod_reader_read_bytes(&mut fields, &mut bytes, &mut bytes_len),
+            0
+        );
+        assert_eq!(core::slice::from_raw_parts(bytes, bytes_len), &[1, 2, 3]);
+        assert!(pod_reader_is_empty(&fields));
+
+        let mut id = 0u32;
+        assert_eq!(pod_reader_read_id(&mut reader, &mut id), 0);
+        assert_eq!(id, 7);
+        assert!(pod_reader_is_empty(&reader));
+
+        pod_builder_free(builder);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{ChoiceType, Type};
+use crate::error::ErrorKind;
+use crate::{ChoicePolicy, ChoiceRange, ChoiceStep, ChoiceType, MaybeChoice, Type};
 
 #[test]
 fn choice_read() -> Result<(), crate::Error> {
@@ -24,3 +25,152 @@ fn choice_read() -> Result<(), crate::Error> {
     // assert_eq!(c, 30);
     Ok(())
 }
+
+#[test]
+fn choice_policy() -> Result<(), crate::Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut()
+        .write_choice(ChoiceType::ENUM, Type::ID, |choice| {
+            choice.child().write(crate::Id(3u32))?;
+            choice.child().write(crate::Id(3u32))?;
+            choice.child().write(crate::Id(4u32))?;
+            Ok(())
+        })?;
+
+    let value = pod.as_ref().into_value()?;
+
+    assert_eq!(
+        value
+            .as_ref()
+            .read_sized::<crate::Id<u32>>()
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidChoiceType {
+            ty: Type::ID,
+            expected: ChoiceType::NONE,
+            actual: ChoiceType::ENUM,
+        }
+    );
+
+    let id = value
+        .as_ref()
+        .read_sized_with::<crate::Id<u32>>(ChoicePolicy::Default)?;
+    assert_eq!(id, crate::Id(3));
+
+    let MaybeChoice::Choice(mut choice) =
+        value.as_ref().read_sized_or_choice::<crate::Id<u32>>()?
+    else {
+        panic!("expected a choice");
+    };
+
+    assert_eq!(choice.choice_type(), ChoiceType::ENUM);
+    assert_eq!(choice.len(), 3);
+    assert_eq!(
+        choice.read::<[crate::Id<u32>; 3]>()?,
+        [crate::Id(3), crate::Id(3), crate::Id(4)]
+    );
+    Ok(())
+}
+
+#[test]
+fn choice_none_unwrapped() -> Result<(), crate::Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut()
+        .write_choice(ChoiceType::NONE, Type::INT, |choice| {
+            choice.child().write(42i32)?;
+            Ok(())
+        })?;
+
+    let value = pod.as_ref().into_value()?;
+
+    assert_eq!(value.as_ref().read_sized::<i32>()?, 42);
+
+    assert!(matches!(
+        value.as_ref().read_sized_or_choice::<i32>()?,
+        MaybeChoice::Value(42)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn choice_object_properties() -> Result<(), crate::Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10, 20, |obj| {
+        obj.property(1).write(2u32)?;
+
+        obj.property(2)
+            .write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+                choice.child().write(48000i32)?;
+                choice.child().write(8000i32)?;
+                choice.child().write(192000i32)?;
+                Ok(())
+            })
+    })?;
+
+    let mut obj = pod.as_ref().read_object()?;
+
+    let channels = obj.property()?.value().read::<MaybeChoice<_, u32>>()?;
+    assert!(matches!(channels, MaybeChoice::Value(2)));
+
+    let rate = obj.property()?.value().read::<MaybeChoice<_, i32>>()?;
+    assert!(rate.is_choice());
+    assert_eq!(rate.resolve(ChoicePolicy::Default)?, 48000);
+    Ok(())
+}
+
+#[test]
+fn choice_helpers() -> Result<(), crate::Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10, 20, |obj| {
+        obj.property(1).write_range(8000i32, 48000, 192000)?;
+        obj.property(2).write_step(0i64, 256, 1024, 128)?;
+        obj.property(3)
+            .write_enum(crate::Id(2u32), [crate::Id(1u32)])?;
+        Ok(())
+    })?;
+
+    let mut obj = pod.as_ref().read_object()?;
+
+    let rate = obj.property()?.value().read_choice()?;
+    assert_eq!(rate.child_type(), Type::INT);
+    assert_eq!(
+        rate.as_range::<i32>()?,
+        ChoiceRange {
+            default: 48000,
+            min: 8000,
+            max: 192000,
+        }
+    );
+
+    assert_eq!(
+        rate.as_enum::<i32>().unwrap_err().kind(),
+        ErrorKind::InvalidChoiceType {
+            ty: Type::INT,
+            expected: ChoiceType::ENUM,
+            actual: ChoiceType::RANGE,
+        }
+    );
+
+    let quantum = obj.property()?.value().read_choice()?;
+    assert_eq!(quantum.child_type(), Type::LONG);
+    assert_eq!(
+        quantum.as_step::<i64>()?,
+        ChoiceStep {
+            default: 256,
+            min: 0,
+            max: 1024,
+            step: 128,
+        }
+    );
+
+    let id = obj.property()?.value().read_choice()?;
+    let id = id.as_enum::<crate::Id<u32>>()?;
+    assert_eq!(id.default, crate::Id(2));
+    assert_eq!(id.alternatives, [crate::Id(1)]);
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::{ChoiceType, Error, Fraction, Rectangle, Type};
+
+crate::macros::const_pod! {
+    static OBJECT = Object(10, 20) {
+        1 => Bool(true),
+        2 => Id(7),
+        3 => Choice(RANGE, Int) { 10, 0, 30 },
+        4 => Struct {
+            Long(-1),
+            Float(1.5),
+            Double(2.5),
+            Fraction(1, 30),
+            Rectangle(800, 600),
+        },
+        5 => Choice(ENUM, Long) { 1, 2 },
+    };
+}
+
+#[test]
+fn const_pod_matches_builder() -> Result<(), Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10, 20, |obj| {
+        obj.property(1).write_sized(true)?;
+        obj.property(2).write_sized(crate::Id(7u32))?;
+        obj.property(3)
+            .write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+                choice.child().write_sized(10i32)?;
+                choice.child().write_sized(0i32)?;
+                choice.child().write_sized(30i32)?;
+                Ok(())
+            })?;
+        obj.property(4).write_struct(|st| {
+            st.field().write_sized(-1i64)?;
+            st.field().write_sized(1.5f32)?;
+            st.field().write_sized(2.5f64)?;
+            st.field().write_sized(Fraction::new(1, 30))?;
+            st.field().write_sized(Rectangle::new(800, 600))?;
+            Ok(())
+        })?;
+        obj.property(5)
+            .write_choice(ChoiceType::ENUM, Type::LONG, |choice| {
+                choice.child().write_sized(1i64)?;
+                choice.child().write_sized(2i64)?;
+                Ok(())
+            })?;
+        Ok(())
+    })?;
+
+    assert_eq!(OBJECT.as_bytes(), pod.as_ref().as_buf().as_bytes());
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+
+use crate::utils::{Endian, decode_endian, encode_endian};
+use crate::{ChoiceType, Error, Type};
+
+const FOREIGN: Endian = match Endian::NATIVE {
+    Endian::Little => Endian::Big,
+    Endian::Big => Endian::Little,
+};
+
+#[test]
+fn endian_roundtrip() -> Result<(), Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10, 20, |obj| {
+        obj.property(1).write_sized(true)?;
+        obj.property(2).write_unsized("hello")?;
+        obj.property(3)
+            .write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+                choice.child().write_sized(10i32)?;
+                choice.child().write_sized(0i32)?;
+                choice.child().write_sized(30i32)?;
+                Ok(())
+            })?;
+        obj.property(4).write_array(Type::LONG, |array| {
+            array.child().write_sized(1i64)?;
+            array.child().write_sized(-2i64)?;
+            Ok(())
+        })?;
+        obj.property(5).write_struct(|st| {
+            st.field().write_sized(2.5f64)?;
+            st.field().write_sized(crate::Fraction::new(1, 30))?;
+            st.field().write_none()?;
+            Ok(())
+        })?;
+        Ok(())
+    })?;
+
+    let expected = pod.as_buf().as_bytes();
+    let mut bytes = Vec::from(expected);
+
+    encode_endian(&mut bytes, Endian::NATIVE)?;
+    assert_eq!(bytes, expected);
+
+    encode_endian(&mut bytes, FOREIGN)?;
+    assert_ne!(bytes, expected);
+
+    let size = u32::try_from(expected.len() - 8).unwrap();
+
+    let header = match FOREIGN {
+        Endian::Little => size.to_le_bytes(),
+        Endian::Big => size.to_be_bytes(),
+    };
+
+    assert_eq!(&bytes[..4], &header);
+    // Strings are left as-is.
+    assert!(bytes.windows(5).any(|w| w == b"hello"));
+
+    decode_endian(&mut bytes, FOREIGN)?;
+    assert_eq!(bytes, expected);
+    Ok(())
+}
+
+#[test]
+fn endian_truncated() {
+    let mut pod = crate::array();
+    pod.as_mut().write_sized(42i32).unwrap();
+
+    let mut bytes = Vec::from(pod.as_buf().as_bytes());
+    bytes.truncate(10);
+
+    assert!(encode_endian(&mut bytes, FOREIGN).is_err());
+}
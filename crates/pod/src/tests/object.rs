@@ -1,4 +1,6 @@
-use crate::{ChoiceType, Error, Id, Readable, Type};
+use alloc::vec::Vec;
+
+use crate::{ChoiceType, Error, ErrorKind, Id, Readable, Type, Writable};
 
 #[test]
 fn embed_object() -> Result<(), Error> {
@@ -59,3 +61,231 @@ fn contents_decode() -> Result<(), Error> {
     assert_eq!(c.value, 200);
     Ok(())
 }
+
+#[test]
+fn none_property() -> Result<(), Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10, 20, |obj| {
+        obj.property(1).flags(0b10).write_none()?;
+        obj.property(2).write(2i32)?;
+        Ok(())
+    })?;
+
+    let mut obj = pod.as_ref().read_object()?;
+
+    let p = obj.property()?;
+    assert_eq!(p.key::<u32>(), 1);
+    assert_eq!(p.flags(), 0b10);
+    assert_eq!(p.value().ty(), Type::NONE);
+
+    let p = obj.property()?;
+    assert_eq!(p.key::<u32>(), 2);
+    assert_eq!(p.value().read_sized::<i32>()?, 2);
+
+    assert!(obj.is_empty());
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Readable, Writable)]
+#[pod(crate, object(type = 10u32))]
+enum Message {
+    #[pod(id = 1u32)]
+    Volume {
+        #[pod(property = 100u32)]
+        channel: u32,
+        #[pod(property = 101u32)]
+        volume: f32,
+    },
+    #[pod(id = 2u32)]
+    Mute(#[pod(property = 100u32)] u32),
+    #[pod(id = 3u32)]
+    Reset,
+}
+
+#[test]
+fn enum_object_roundtrip() -> Result<(), Error> {
+    let messages = [
+        Message::Volume {
+            channel: 2,
+            volume: 0.5,
+        },
+        Message::Mute(4),
+        Message::Reset,
+    ];
+
+    for message in messages {
+        let mut pod = crate::array();
+        pod.as_mut().write(&message)?;
+        assert_eq!(pod.as_ref().read::<Message>()?, message);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn enum_object_decode() -> Result<(), Error> {
+    let mut pod = crate::array();
+    let obj = pod
+        .as_mut()
+        .embed_object(10u32, 2u32, |obj| obj.property(100u32).write(7u32))?;
+
+    assert_eq!(obj.as_ref().read::<Message>()?, Message::Mute(7));
+
+    let mut pod = crate::array();
+    pod.as_mut().write_object(10u32, 4u32, |_| Ok(()))?;
+
+    assert_eq!(
+        pod.as_ref().read::<Message>().unwrap_err().kind(),
+        ErrorKind::UnknownVariantId { actual: 4 }
+    );
+
+    let mut pod = crate::array();
+    pod.as_mut().write_object(11u32, 1u32, |_| Ok(()))?;
+
+    assert_eq!(
+        pod.as_ref().read::<Message>().unwrap_err().kind(),
+        ErrorKind::InvalidObjectType {
+            expected: 10,
+            actual: 11
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn enum_id() -> Result<(), Error> {
+    #[derive(Debug, Clone, Copy, PartialEq, Readable, Writable)]
+    #[pod(crate)]
+    enum Mode {
+        #[pod(id = 1u32)]
+        Stereo,
+        #[pod(id = 2u32)]
+        Mono,
+    }
+
+    let mut pod = crate::array();
+    pod.as_mut().write(Mode::Mono)?;
+
+    assert_eq!(pod.as_ref().read_sized::<Id<u32>>()?, Id(2));
+    assert_eq!(pod.as_ref().read::<Mode>()?, Mode::Mono);
+
+    let mut pod = crate::array();
+
+    pod.as_mut()
+        .write_choice(ChoiceType::NONE, Type::ID, |choice| {
+            choice.write((Id(1u32), Id(1u32), Id(2u32)))
+        })?;
+
+    assert_eq!(pod.as_ref().read::<Mode>()?, Mode::Stereo);
+
+    let mut pod = crate::array();
+    pod.as_mut().write_sized(Id(3u32))?;
+
+    assert_eq!(
+        pod.as_ref().read::<Mode>().unwrap_err().kind(),
+        ErrorKind::UnknownVariantId { actual: 3 }
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Readable, Writable)]
+#[pod(crate, object(type = 10u32, id = 20u32))]
+struct Volumes {
+    #[pod(property = 1u32)]
+    volumes: [f32; 2],
+    #[pod(property = 2u32)]
+    map: Vec<Id<u32>>,
+    #[pod(property = 3u32)]
+    mask: [u8; 4],
+}
+
+#[test]
+fn array_properties() -> Result<(), Error> {
+    let volumes = Volumes {
+        volumes: [0.5, 1.0],
+        map: Vec::from([Id(3), Id(4), Id(5)]),
+        mask: [1, 2, 3, 4],
+    };
+
+    let mut pod = crate::array();
+    pod.as_mut().write(&volumes)?;
+
+    let mut obj = pod.as_ref().read_object()?;
+
+    let p = obj.property()?;
+    assert_eq!(p.key::<u32>(), 1);
+    let mut array = p.value().read_array()?;
+    assert_eq!(array.child_type(), Type::FLOAT);
+    assert_eq!(array.read::<(f32, f32)>()?, (0.5, 1.0));
+
+    let p = obj.property()?;
+    assert_eq!(p.key::<u32>(), 2);
+    let array = p.value().read_array()?;
+    assert_eq!(array.child_type(), Type::ID);
+    assert_eq!(array.len(), 3);
+
+    let p = obj.property()?;
+    assert_eq!(p.key::<u32>(), 3);
+    assert_eq!(p.value().ty(), Type::BYTES);
+
+    assert_eq!(pod.as_ref().read::<Volumes>()?, volumes);
+    Ok(())
+}
+
+#[test]
+fn array_property_length() -> Result<(), Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10u32, 20u32, |obj| {
+        obj.property(1u32)
+            .write_array(Type::FLOAT, |array| array.write((0.5f32, 1.0f32, 0.25f32)))
+    })?;
+
+    let error = pod.as_ref().read::<Volumes>().unwrap_err();
+
+    assert_eq!(
+        error.kind(),
+        ErrorKind::PropertyArrayLength {
+            key: 1,
+            expected: 2,
+            actual: 3
+        }
+    );
+
+    let mut pod = crate::array();
+
+    pod.as_mut().write_object(10u32, 20u32, |obj| {
+        obj.property(2u32).write_array(Type::ID, |_| Ok(()))
+    })?;
+
+    let volumes = pod.as_ref().read::<Volumes>()?;
+    assert!(volumes.map.is_empty());
+    Ok(())
+}
+
+#[test]
+fn computed_keys() -> Result<(), Error> {
+    const BASE: u32 = 100;
+
+    #[derive(Debug, PartialEq, Readable, Writable)]
+    #[pod(crate, object(type = 10u32, id = 20u32))]
+    struct Computed {
+        #[pod(property = BASE + 1)]
+        a: u32,
+        #[pod(property = BASE + 2)]
+        b: u32,
+    }
+
+    let mut pod = crate::array();
+    pod.as_mut().write(Computed { a: 1, b: 2 })?;
+
+    let mut obj = pod.as_ref().read_object()?;
+    assert_eq!(obj.property()?.key::<u32>(), 101);
+    assert_eq!(obj.property()?.key::<u32>(), 102);
+
+    assert_eq!(pod.as_ref().read::<Computed>()?, Computed { a: 1, b: 2 });
+    Ok(())
+}
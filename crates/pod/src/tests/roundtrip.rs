@@ -0,0 +1,244 @@
+//! Randomized roundtrip tests which generate trees of pod values, write them
+//! with a builder and assert that reading them back produces the same tree.
+
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::vec::Vec;
+use core::ffi::CStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::buf::ArrayBuf;
+use crate::{Builder, Error, Fraction, Id, PodSink, Rectangle, Type, Value, Writable};
+use crate::{DynamicBuf, Slice};
+
+/// The number of trees to generate per test.
+const ITERATIONS: usize = 500;
+/// The maximum depth of generated trees.
+const MAX_DEPTH: usize = 4;
+/// The maximum number of children of a container.
+const MAX_CHILDREN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tree {
+    None,
+    Bool(bool),
+    Id(u32),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(CString),
+    Bytes(Vec<u8>),
+    Rectangle(Rectangle),
+    Fraction(Fraction),
+    Array(Vec<i32>),
+    Struct(Vec<Tree>),
+    Object(u32, u32, Vec<(u32, u32, Tree)>),
+    Pod(Box<Tree>),
+}
+
+impl Tree {
+    fn generate(rng: &mut StdRng, depth: usize) -> Self {
+        let leaves = 12;
+        let containers = if depth < MAX_DEPTH { 3 } else { 0 };
+
+        match rng.random_range(0..leaves + containers) {
+            0 => Tree::None,
+            1 => Tree::Bool(rng.random()),
+            2 => Tree::Id(rng.random()),
+            3 => Tree::Int(rng.random()),
+            4 => Tree::Long(rng.random()),
+            5 => Tree::Float(rng.random()),
+            6 => Tree::Double(rng.random()),
+            7 => {
+                let len = rng.random_range(0..16);
+                let bytes = (0..len)
+                    .map(|_| rng.random_range(1..=u8::MAX))
+                    .collect::<Vec<_>>();
+                Tree::String(CString::new(bytes).expect("no interior nulls"))
+            }
+            8 => {
+                let len = rng.random_range(0..16);
+                Tree::Bytes((0..len).map(|_| rng.random()).collect())
+            }
+            9 => Tree::Rectangle(Rectangle::new(rng.random(), rng.random())),
+            10 => Tree::Fraction(Fraction::new(rng.random(), rng.random())),
+            11 => {
+                let len = rng.random_range(0..8);
+                Tree::Array((0..len).map(|_| rng.random()).collect())
+            }
+            12 => Tree::Struct(Self::children(rng, depth)),
+            13 => {
+                let len = rng.random_range(0..=MAX_CHILDREN);
+
+                let properties = (0..len)
+                    .map(|_| (rng.random(), rng.random(), Tree::generate(rng, depth + 1)))
+                    .collect();
+
+                Tree::Object(rng.random(), rng.random(), properties)
+            }
+            _ => Tree::Pod(Box::new(Tree::generate(rng, depth + 1))),
+        }
+    }
+
+    fn children(rng: &mut StdRng, depth: usize) -> Vec<Tree> {
+        let len = rng.random_range(0..=MAX_CHILDREN);
+        (0..len).map(|_| Tree::generate(rng, depth + 1)).collect()
+    }
+
+    fn read(value: Value<Slice<'_>>) -> Result<Self, Error> {
+        let tree = match value.ty() {
+            Type::NONE => Tree::None,
+            Type::BOOL => Tree::Bool(value.read_sized()?),
+            Type::ID => Tree::Id(value.read_sized::<Id<u32>>()?.0),
+            Type::INT => Tree::Int(value.read_sized()?),
+            Type::LONG => Tree::Long(value.read_sized()?),
+            Type::FLOAT => Tree::Float(value.read_sized()?),
+            Type::DOUBLE => Tree::Double(value.read_sized()?),
+            Type::STRING => Tree::String(value.read_unsized::<CStr>()?.into()),
+            Type::BYTES => Tree::Bytes(value.read_unsized::<[u8]>()?.into()),
+            Type::RECTANGLE => Tree::Rectangle(value.read_sized()?),
+            Type::FRACTION => Tree::Fraction(value.read_sized()?),
+            Type::ARRAY => {
+                let mut array = value.read_array()?;
+                let mut items = Vec::new();
+
+                while let Some(item) = array.next()? {
+                    items.push(item.read_sized()?);
+                }
+
+                Tree::Array(items)
+            }
+            Type::STRUCT => {
+                let mut st = value.read_struct()?;
+                let mut fields = Vec::new();
+
+                while !st.is_empty() {
+                    fields.push(Tree::read(st.field()?)?);
+                }
+
+                Tree::Struct(fields)
+            }
+            Type::OBJECT => {
+                let mut obj = value.read_object()?;
+                let mut properties = Vec::new();
+
+                while !obj.is_empty() {
+                    let p = obj.property()?;
+                    properties.push((p.key(), p.flags(), Tree::read(p.value())?));
+                }
+
+                Tree::Object(obj.object_type(), obj.object_id(), properties)
+            }
+            Type::POD => Tree::Pod(Box::new(Tree::read(value.read_pod()?.into_value()?)?)),
+            ty => panic!("Unexpected type {ty:?}"),
+        };
+
+        Ok(tree)
+    }
+}
+
+impl Writable for Tree {
+    fn write_into(&self, pod: &mut impl PodSink) -> Result<(), Error> {
+        let pod = pod.next()?;
+
+        match self {
+            Tree::None => pod.write_none(),
+            Tree::Bool(value) => pod.write_sized(*value),
+            Tree::Id(value) => pod.write_sized(Id(*value)),
+            Tree::Int(value) => pod.write_sized(*value),
+            Tree::Long(value) => pod.write_sized(*value),
+            Tree::Float(value) => pod.write_sized(*value),
+            Tree::Double(value) => pod.write_sized(*value),
+            Tree::String(value) => pod.write_unsized(value.as_c_str()),
+            Tree::Bytes(value) => pod.write_unsized(value.as_slice()),
+            Tree::Rectangle(value) => pod.write_sized(*value),
+            Tree::Fraction(value) => pod.write_sized(*value),
+            Tree::Array(items) => pod.write_array(Type::INT, |array| {
+                for item in items {
+                    array.child().write(*item)?;
+                }
+
+                Ok(())
+            }),
+            Tree::Struct(fields) => pod.write_struct(|st| st.write(fields.as_slice())),
+            Tree::Object(object_type, object_id, properties) => {
+                pod.write_object(*object_type, *object_id, |obj| {
+                    for (key, flags, value) in properties {
+                        obj.property(*key).flags(*flags).write(value)?;
+                    }
+
+                    Ok(())
+                })
+            }
+            Tree::Pod(inner) => pod.write_pod(|pod| pod.as_mut().write(&**inner)),
+        }
+    }
+}
+
+#[test]
+fn roundtrip_dynamic_buf() -> Result<(), Error> {
+    let mut rng = StdRng::seed_from_u64(0x7e57);
+
+    for _ in 0..ITERATIONS {
+        let tree = Tree::generate(&mut rng, 0);
+
+        let mut pod = Builder::new(DynamicBuf::new());
+        pod.as_mut().write(&tree)?;
+
+        let read = Tree::read(pod.as_ref().into_value()?)?;
+        assert_eq!(read, tree);
+
+        let owned = pod.as_ref().to_owned()?;
+        let read = Tree::read(owned.as_ref().into_value()?)?;
+        assert_eq!(read, tree);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn roundtrip_array_buf() -> Result<(), Error> {
+    let mut rng = StdRng::seed_from_u64(0xa77a);
+
+    for _ in 0..ITERATIONS {
+        let tree = Tree::generate(&mut rng, 0);
+
+        let mut pod = Builder::new(ArrayBuf::<8192>::new());
+        pod.as_mut().write(&tree)?;
+
+        let read = Tree::read(pod.as_ref().into_value()?)?;
+        assert_eq!(read, tree);
+
+        let mut dynamic = Builder::new(DynamicBuf::new());
+        dynamic.as_mut().write(&tree)?;
+        assert_eq!(pod.as_buf().as_bytes(), dynamic.as_buf().as_bytes());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn roundtrip_struct_fields() -> Result<(), Error> {
+    let mut rng = StdRng::seed_from_u64(0x57c7);
+
+    for _ in 0..ITERATIONS {
+        let fields = Tree::children(&mut rng, 0);
+
+        let mut pod = Builder::new(DynamicBuf::new());
+        pod.as_mut().write_struct(|st| {
+            for field in &fields {
+                st.field().write(field)?;
+            }
+
+            Ok(())
+        })?;
+
+        let read = Tree::read(pod.as_ref().into_value()?)?;
+        assert_eq!(read, Tree::Struct(fields));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::utils::{self, ChangeKind, Container, ContainerWriter};
+use crate::{ChoiceType, Error, Type};
+
+#[test]
+fn hexdump_choice() -> Result<(), Error> {
+    let mut pod = crate::array();
+
+    pod.as_mut()
+        .write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+            choice.child().write_sized(10i32)?;
+            choice.child().write_sized(0i32)?;
+            choice.child().write_sized(30i32)?;
+            Ok(())
+        })?;
+
+    let dump = utils::hexdump(pod.as_ref().as_buf().as_bytes()).to_string();
+
+    let expected = [
+        "0000: 1c 00 00 00 13 00 00 00  Choice size=28",
+        "0008: 01 00 00 00 00 00 00 00    choice Range flags=0",
+        "0010: 04 00 00 00 04 00 00 00    child Int size=4",
+        "0018: 0a 00 00 00                10",
+        "001c: 00 00 00 00                0",
+        "0020: 1e 00 00 00                30",
+    ];
+
+    assert_eq!(dump.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn hexdump_truncated() -> Result<(), Error> {
+    let mut pod = crate::array();
+    pod.as_mut().write(42i64)?;
+
+    let bytes = pod.as_ref().as_buf().as_bytes();
+    let dump = utils::hexdump(&bytes[..12]).to_string();
+
+    let expected = [
+        "0000: 08 00 00 00 05 00 00 00  Long size=8",
+        "0008: 2a 00 00 00                <truncated>",
+    ];
+
+    assert_eq!(dump.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn diff_struct() -> Result<(), Error> {
+    let mut a = crate::array();
+    a.as_mut().write_struct(|st| {
+        st.field().write(1i32)?;
+        st.field().write_struct(|st| {
+            st.field().write("a")?;
+            Ok(())
+        })?;
+        st.field().write(3i32)?;
+        Ok(())
+    })?;
+
+    let mut b = crate::array();
+    b.as_mut().write_struct(|st| {
+        st.field().write(1i32)?;
+        st.field().write_struct(|st| {
+            st.field().write("b")?;
+            Ok(())
+        })?;
+        Ok(())
+    })?;
+
+    let diff = utils::diff(a.as_ref().into_value()?, b.as_ref().into_value()?)?;
+
+    let changes = diff.changes();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].path, "[1][0]");
+    assert_eq!(changes[0].kind, ChangeKind::Changed);
+    assert_eq!(changes[1].path, "[2]");
+    assert_eq!(changes[1].kind, ChangeKind::Removed);
+
+    let diff = utils::diff(a.as_ref().into_value()?, a.as_ref().into_value()?)?;
+    assert!(diff.is_empty());
+    Ok(())
+}
+
+#[test]
+fn container_forward_compat() -> Result<(), Error> {
+    let mut writer = ContainerWriter::new();
+    let trace = writer.register("trace")?;
+
+    let mut pod = crate::array();
+    pod.as_mut().write_sized(42i32)?;
+    writer.push(trace, pod.as_ref())?;
+    writer.push(7, pod.as_ref())?;
+
+    let bytes = writer.finish()?;
+
+    // Simulate a later minor version which extends the header.
+    let mut extended = Vec::new();
+    extended.extend_from_slice(&bytes[..6]);
+    extended.extend_from_slice(&1u16.to_le_bytes());
+    extended.extend_from_slice(&24u32.to_le_bytes());
+    extended.extend_from_slice(&bytes[12..16]);
+    extended.extend_from_slice(&[0xff; 8]);
+    extended.extend_from_slice(&bytes[16..]);
+
+    let container = Container::parse(&extended)?;
+    assert_eq!(container.version(), (1, 1));
+
+    let entries = container.entries().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(container.type_name(entries[0].kind()), Some("trace"));
+    assert_eq!(entries[0].as_pod().read_sized::<i32>()?, 42);
+    assert_eq!(container.type_name(entries[1].kind()), None);
+    assert_eq!(entries[1].as_pod().read_sized::<i32>()?, 42);
+
+    let mut major = bytes.clone();
+    major[4..6].copy_from_slice(&2u16.to_le_bytes());
+    assert!(Container::parse(&major).is_err());
+
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert!(Container::parse(&magic).is_err());
+    Ok(())
+}
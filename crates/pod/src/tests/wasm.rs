@@ -0,0 +1,55 @@
+use alloc::string::String;
+
+use crate::Type;
+use crate::wasm::{PodBuilder, PodReader};
+
+// NB: Errors are JavaScript exceptions which can't be constructed outside of
+// WebAssembly, so only successful operations are tested here.
+#[test]
+fn wasm_roundtrip() {
+    let mut builder = PodBuilder::new();
+
+    builder.write_int(42);
+    builder.begin_struct();
+    builder.write_string("hello").unwrap();
+    builder.write_double(2.5);
+    builder.write_bytes(&[1, 2, 3]);
+    builder.end_struct().unwrap();
+    builder.write_id(7);
+
+    let data = builder.finish().unwrap();
+    let mut reader = PodReader::new(&data);
+
+    assert_eq!(reader.peek_type().unwrap(), Type::INT.into_u32());
+    assert_eq!(reader.read_int().unwrap(), 42);
+
+    let mut fields = reader.read_struct().unwrap();
+    assert_eq!(fields.read_string().unwrap(), "hello");
+    assert_eq!(fields.read_double().unwrap(), 2.5);
+    assert_eq!(fields.read_bytes().unwrap(), [1, 2, 3]);
+    assert!(fields.is_empty());
+
+    assert_eq!(reader.read_id().unwrap(), 7);
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn wasm_inspect() {
+    let mut builder = PodBuilder::new();
+    builder.begin_struct();
+    builder.write_int(1);
+    builder.write_string("hello").unwrap();
+    builder.end_struct().unwrap();
+    builder.write_none();
+
+    let data = builder.finish().unwrap();
+    let mut reader = PodReader::new(&data);
+
+    assert_eq!(
+        reader.inspect().unwrap(),
+        String::from("Struct { fields: [1, \"hello\"] }")
+    );
+
+    reader.skip().unwrap();
+    assert!(reader.is_empty());
+}
@@ -155,6 +155,7 @@ impl UnsizedWritable for String {
     }
 }
 
+#[cfg(feature = "alloc")]
 crate::macros::encode_into_unsized!(String);
 
 /// [`UnsizedWritable`] implementation for an unsized [`Bitmap`].
@@ -6,6 +6,25 @@ use core::slice;
 
 use crate::{Error, ErrorKind, SizeOverflow, WordOverflow};
 
+mod hexdump;
+pub use self::hexdump::{HexDump, hexdump};
+
+mod endian;
+pub use self::endian::{Endian, decode_endian, encode_endian};
+
+#[cfg(feature = "alloc")]
+mod container;
+#[cfg(feature = "alloc")]
+pub use self::container::{
+    CONTAINER_MAGIC, CONTAINER_MAJOR, CONTAINER_MINOR, Container, ContainerEntries, ContainerEntry,
+    ContainerWriter,
+};
+
+#[cfg(feature = "alloc")]
+mod diff;
+#[cfg(feature = "alloc")]
+pub use self::diff::{Change, ChangeKind, Diff, diff};
+
 /// Indicates a type which has all bit patterns inhabited.
 ///
 /// # Safety
@@ -0,0 +1,326 @@
+use core::str;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::BufferUnderflow;
+use crate::utils::{Endian, decode_endian, encode_endian, to_word};
+use crate::{AsSlice, DynamicBuf, Error, ErrorKind, Pod, Slice};
+
+/// The magic bytes which every container starts with.
+pub const CONTAINER_MAGIC: [u8; 4] = *b"PODC";
+
+/// The major version of the container format written by [`ContainerWriter`].
+///
+/// Readers reject containers with a different major version.
+pub const CONTAINER_MAJOR: u16 = 1;
+
+/// The minor version of the container format written by [`ContainerWriter`].
+///
+/// Minor versions only add information which older readers can skip.
+pub const CONTAINER_MINOR: u16 = 0;
+
+/// The size of the fixed header written by this version of the format.
+const HEADER_SIZE: u32 = 16;
+
+/// A writer for the on-disk pod container format.
+///
+/// A container is a small file format used to persist pods, like presets,
+/// captured parameters or traces. All integers and pods are stored in
+/// little-endian byte order regardless of the host, and it is laid out as
+/// follows:
+///
+/// * The header, consisting of the [`CONTAINER_MAGIC`] bytes, the major and
+///   minor version as `u16`, the size of the header as a `u32` and a reserved
+///   `u32` of flags.
+/// * The type table, consisting of a `u32` count followed by entries of a
+///   `u32` kind, the `u32` length of the name and the name padded to 4 bytes.
+/// * Pod entries until the end of the container, each consisting of a `u32`
+///   kind, the `u32` size of the pod and the pod padded to 8 bytes.
+///
+/// Forward compatibility is achieved through the following rules:
+///
+/// * Readers reject containers with a different major version.
+/// * Readers skip any header bytes beyond the header size they know about.
+/// * Readers preserve entries whose kind is missing from the type table.
+///
+/// # Examples
+///
+/// ```
+/// use pod::utils::{Container, ContainerWriter};
+///
+/// let mut writer = ContainerWriter::new();
+/// let preset = writer.register("preset")?;
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write_struct(|st| {
+///     st.field().write(0.5f64)?;
+///     st.field().write("gain")?;
+///     Ok(())
+/// })?;
+///
+/// writer.push(preset, pod.as_ref())?;
+/// let bytes = writer.finish()?;
+///
+/// let container = Container::parse(&bytes)?;
+/// assert_eq!(container.version(), (1, 0));
+///
+/// let entries = container.entries().collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(entries.len(), 1);
+/// assert_eq!(entries[0].kind(), preset);
+/// assert_eq!(container.type_name(entries[0].kind()), Some("preset"));
+///
+/// let mut st = entries[0].as_pod().read_struct()?;
+/// assert_eq!(st.field()?.read_sized::<f64>()?, 0.5);
+/// assert_eq!(st.field()?.read_unsized::<str>()?, "gain");
+/// # Ok::<_, pod::Error>(())
+/// ```
+#[derive(Default)]
+pub struct ContainerWriter {
+    types: Vec<String>,
+    entries: Vec<u8>,
+}
+
+impl ContainerWriter {
+    /// Construct a new empty container writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a named kind of entry in the type table, returning the kind
+    /// to use with [`ContainerWriter::push`].
+    ///
+    /// Registering the same name multiple times returns the same kind.
+    pub fn register(&mut self, name: &str) -> Result<u32, Error> {
+        if let Some(index) = self.types.iter().position(|n| n == name) {
+            return Ok(index as u32);
+        }
+
+        let kind = to_word(self.types.len())?;
+        self.types.push(String::from(name));
+        Ok(kind)
+    }
+
+    /// Push a pod entry of the given kind into the container.
+    pub fn push(&mut self, kind: u32, pod: Pod<impl AsSlice>) -> Result<(), Error> {
+        let mut data = DynamicBuf::from_slice(pod.as_buf().as_slice().as_bytes())?;
+        encode_endian(data.as_bytes_mut(), Endian::Little)?;
+
+        let size = to_word(data.len())?;
+        self.entries.extend_from_slice(&kind.to_le_bytes());
+        self.entries.extend_from_slice(&size.to_le_bytes());
+        self.entries.extend_from_slice(data.as_bytes());
+        pad(&mut self.entries, 8);
+        Ok(())
+    }
+
+    /// Finish writing the container, returning its bytes.
+    pub fn finish(self) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&CONTAINER_MAGIC);
+        out.extend_from_slice(&CONTAINER_MAJOR.to_le_bytes());
+        out.extend_from_slice(&CONTAINER_MINOR.to_le_bytes());
+        out.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+
+        out.extend_from_slice(&to_word(self.types.len())?.to_le_bytes());
+
+        for (kind, name) in self.types.iter().enumerate() {
+            out.extend_from_slice(&to_word(kind)?.to_le_bytes());
+            out.extend_from_slice(&to_word(name.len())?.to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+            pad(&mut out, 4);
+        }
+
+        out.extend_from_slice(&self.entries);
+        Ok(out)
+    }
+}
+
+/// A parsed pod container.
+///
+/// See [`ContainerWriter`] for a description of the format.
+pub struct Container<'de> {
+    major: u16,
+    minor: u16,
+    types: Vec<(u32, &'de str)>,
+    entries: Slice<'de>,
+}
+
+impl<'de> Container<'de> {
+    /// Parse the header and type table of a container.
+    pub fn parse(bytes: &'de [u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader { bytes };
+
+        if reader.take(4)? != CONTAINER_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidContainerMagic));
+        }
+
+        let major = u16::from_le_bytes(reader.array()?);
+        let minor = u16::from_le_bytes(reader.array()?);
+
+        if major != CONTAINER_MAJOR {
+            return Err(Error::new(ErrorKind::UnsupportedContainerVersion {
+                major,
+                minor,
+            }));
+        }
+
+        let header_size = reader.u32()?;
+        let _flags = reader.u32()?;
+
+        // NB: Skip over header fields added by later minor versions.
+        reader.take((header_size as usize).saturating_sub(HEADER_SIZE as usize))?;
+
+        let count = reader.u32()?;
+        let mut types = Vec::new();
+
+        for _ in 0..count {
+            let kind = reader.u32()?;
+            let len = reader.u32()? as usize;
+            let name = reader.take(len)?;
+            reader.take(len.next_multiple_of(4) - len)?;
+
+            let Ok(name) = str::from_utf8(name) else {
+                return Err(Error::new(ErrorKind::NotUtf8));
+            };
+
+            types.push((kind, name));
+        }
+
+        Ok(Self {
+            major,
+            minor,
+            types,
+            entries: Slice::new(reader.bytes),
+        })
+    }
+
+    /// Get the major and minor version of the container.
+    #[inline]
+    pub fn version(&self) -> (u16, u16) {
+        (self.major, self.minor)
+    }
+
+    /// Iterate over the type table of the container.
+    pub fn types(&self) -> impl Iterator<Item = (u32, &'de str)> + '_ {
+        self.types.iter().copied()
+    }
+
+    /// Look up the name of a kind in the type table.
+    pub fn type_name(&self, kind: u32) -> Option<&'de str> {
+        self.types.iter().find(|(k, _)| *k == kind).map(|(_, n)| *n)
+    }
+
+    /// Iterate over the entries of the container.
+    ///
+    /// Each entry is decoded into host byte order.
+    pub fn entries(&self) -> ContainerEntries<'de> {
+        ContainerEntries {
+            reader: ByteReader {
+                bytes: self.entries.as_bytes(),
+            },
+        }
+    }
+}
+
+/// An iterator over the entries of a [`Container`].
+///
+/// See [`Container::entries`].
+pub struct ContainerEntries<'de> {
+    reader: ByteReader<'de>,
+}
+
+impl ContainerEntries<'_> {
+    fn next_entry(&mut self) -> Result<ContainerEntry, Error> {
+        let kind = self.reader.u32()?;
+        let size = self.reader.u32()? as usize;
+        let data = self.reader.take(size)?;
+
+        let padding = (size.next_multiple_of(8) - size).min(self.reader.bytes.len());
+        self.reader.take(padding)?;
+
+        let mut data = DynamicBuf::from_slice(data)?;
+        decode_endian(data.as_bytes_mut(), Endian::Little)?;
+        Ok(ContainerEntry { kind, data })
+    }
+}
+
+impl Iterator for ContainerEntries<'_> {
+    type Item = Result<ContainerEntry, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.bytes.is_empty() {
+            return None;
+        }
+
+        let result = self.next_entry();
+
+        if result.is_err() {
+            self.reader.bytes = &[];
+        }
+
+        Some(result)
+    }
+}
+
+/// An entry in a [`Container`].
+pub struct ContainerEntry {
+    kind: u32,
+    data: DynamicBuf,
+}
+
+impl ContainerEntry {
+    /// Get the kind of the entry.
+    ///
+    /// This can be resolved to a name through [`Container::type_name`].
+    #[inline]
+    pub fn kind(&self) -> u32 {
+        self.kind
+    }
+
+    /// Access the pod of the entry.
+    #[inline]
+    pub fn as_pod(&self) -> Pod<Slice<'_>> {
+        Pod::new(self.data.as_slice())
+    }
+
+    /// Coerce the entry into its underlying buffer.
+    #[inline]
+    pub fn into_buf(self) -> DynamicBuf {
+        self.data
+    }
+}
+
+struct ByteReader<'de> {
+    bytes: &'de [u8],
+}
+
+impl<'de> ByteReader<'de> {
+    #[inline]
+    fn take(&mut self, n: usize) -> Result<&'de [u8], BufferUnderflow> {
+        let Some((head, tail)) = self.bytes.split_at_checked(n) else {
+            return Err(BufferUnderflow);
+        };
+
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    #[inline]
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], BufferUnderflow> {
+        let bytes = self.take(N)?;
+        <[u8; N]>::try_from(bytes).map_err(|_| BufferUnderflow)
+    }
+
+    #[inline]
+    fn u32(&mut self) -> Result<u32, BufferUnderflow> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+}
+
+#[inline]
+fn pad(out: &mut Vec<u8>, align: usize) {
+    out.resize(out.len().next_multiple_of(align), 0);
+}
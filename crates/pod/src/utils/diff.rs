@@ -0,0 +1,284 @@
+use core::fmt;
+use core::fmt::Write;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{AsSlice, Error, Object, Slice, Struct, Type, Value};
+
+/// The kind of a [`Change`] in a [`Diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// The value is only present in the new pod.
+    Added,
+    /// The value is only present in the old pod.
+    Removed,
+    /// The value differs between the two pods.
+    Changed,
+}
+
+/// A single change in a [`Diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Change {
+    /// The path to the changed value.
+    ///
+    /// Struct fields are identified by their index like `[1]` and object
+    /// properties by their key like `.3`.
+    pub path: String,
+    /// The kind of change.
+    pub kind: ChangeKind,
+    /// The debug representation of the old value, if any.
+    pub old: Option<String>,
+    /// The debug representation of the new value, if any.
+    pub new: Option<String>,
+}
+
+/// A structural diff between two pods.
+///
+/// See [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Test if the two pods are structurally equal.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Get the changes in the diff.
+    #[inline]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    fn push(&mut self, path: &str, kind: ChangeKind, old: Option<String>, new: Option<String>) {
+        let path = if path.is_empty() {
+            String::from(".")
+        } else {
+            String::from(path)
+        };
+
+        self.changes.push(Change {
+            path,
+            kind,
+            old,
+            new,
+        });
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            match (&change.old, &change.new) {
+                (Some(old), Some(new)) => writeln!(f, "~ {}: {old} -> {new}", change.path)?,
+                (Some(old), None) => writeln!(f, "- {}: {old}", change.path)?,
+                (None, Some(new)) => writeln!(f, "+ {}: {new}", change.path)?,
+                (None, None) => writeln!(f, "~ {}", change.path)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compute a structural diff between two pod values.
+///
+/// Structs are compared field by field and objects property by property,
+/// matched by their key. Any other values are compared by their encoded
+/// representation.
+///
+/// # Examples
+///
+/// ```
+/// use pod::utils::ChangeKind;
+///
+/// let mut a = pod::array();
+/// a.as_mut().write_object(10u32, 20u32, |obj| {
+///     obj.property(1u32).write(1i32)?;
+///     obj.property(2u32).write("left")?;
+///     Ok(())
+/// })?;
+///
+/// let mut b = pod::array();
+/// b.as_mut().write_object(10u32, 20u32, |obj| {
+///     obj.property(1u32).write(1i32)?;
+///     obj.property(2u32).write("right")?;
+///     obj.property(3u32).write(true)?;
+///     Ok(())
+/// })?;
+///
+/// let diff = pod::utils::diff(a.as_ref().into_value()?, b.as_ref().into_value()?)?;
+///
+/// assert_eq!(diff.changes().len(), 2);
+/// assert_eq!(diff.changes()[0].path, ".2");
+/// assert_eq!(diff.changes()[0].kind, ChangeKind::Changed);
+/// assert_eq!(diff.changes()[1].path, ".3");
+/// assert_eq!(diff.changes()[1].kind, ChangeKind::Added);
+///
+/// assert_eq!(diff.to_string(), "~ .2: \"left\" -> \"right\"\n+ .3: true\n");
+/// # Ok::<_, pod::Error>(())
+/// ```
+pub fn diff(old: Value<impl AsSlice>, new: Value<impl AsSlice>) -> Result<Diff, Error> {
+    let mut diff = Diff::default();
+    let mut path = String::new();
+    diff_value(&mut diff, &mut path, old.as_ref(), new.as_ref())?;
+    Ok(diff)
+}
+
+fn diff_value(
+    diff: &mut Diff,
+    path: &mut String,
+    old: Value<Slice<'_>>,
+    new: Value<Slice<'_>>,
+) -> Result<(), Error> {
+    if old.ty() != new.ty() {
+        diff.push(
+            path,
+            ChangeKind::Changed,
+            Some(format!("{old:?}")),
+            Some(format!("{new:?}")),
+        );
+        return Ok(());
+    }
+
+    match old.ty() {
+        Type::STRUCT => diff_struct(diff, path, old.read_struct()?, new.read_struct()?),
+        Type::OBJECT => diff_object(diff, path, old.read_object()?, new.read_object()?),
+        _ => {
+            if bytes(&old) != bytes(&new) {
+                diff.push(
+                    path,
+                    ChangeKind::Changed,
+                    Some(format!("{old:?}")),
+                    Some(format!("{new:?}")),
+                );
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn diff_struct(
+    diff: &mut Diff,
+    path: &mut String,
+    mut old: Struct<Slice<'_>>,
+    mut new: Struct<Slice<'_>>,
+) -> Result<(), Error> {
+    let mut index = 0usize;
+
+    loop {
+        let len = path.len();
+        _ = write!(path, "[{index}]");
+
+        match (old.is_empty(), new.is_empty()) {
+            (true, true) => {
+                path.truncate(len);
+                break;
+            }
+            (false, false) => {
+                diff_value(diff, path, old.field()?, new.field()?)?;
+            }
+            (false, true) => {
+                let old = old.field()?;
+                diff.push(path, ChangeKind::Removed, Some(format!("{old:?}")), None);
+            }
+            (true, false) => {
+                let new = new.field()?;
+                diff.push(path, ChangeKind::Added, None, Some(format!("{new:?}")));
+            }
+        }
+
+        path.truncate(len);
+        index += 1;
+    }
+
+    Ok(())
+}
+
+fn diff_object(
+    diff: &mut Diff,
+    path: &mut String,
+    mut old: Object<Slice<'_>>,
+    new: Object<Slice<'_>>,
+) -> Result<(), Error> {
+    if old.object_type::<u32>() != new.object_type::<u32>()
+        || old.object_id::<u32>() != new.object_id::<u32>()
+    {
+        diff.push(
+            path,
+            ChangeKind::Changed,
+            Some(format!("{old:?}")),
+            Some(format!("{new:?}")),
+        );
+        return Ok(());
+    }
+
+    let mut seen = Vec::new();
+
+    while !old.is_empty() {
+        let p = old.property()?;
+        let key = p.key::<u32>();
+        seen.push(key);
+
+        let len = path.len();
+        _ = write!(path, ".{key}");
+
+        match find(new.as_ref(), key)? {
+            Some(value) => {
+                diff_value(diff, path, p.value(), value)?;
+            }
+            None => {
+                let old = p.value();
+                diff.push(path, ChangeKind::Removed, Some(format!("{old:?}")), None);
+            }
+        }
+
+        path.truncate(len);
+    }
+
+    let mut new = new;
+
+    while !new.is_empty() {
+        let p = new.property()?;
+        let key = p.key::<u32>();
+
+        if seen.contains(&key) {
+            continue;
+        }
+
+        let len = path.len();
+        _ = write!(path, ".{key}");
+        let new = p.value();
+        diff.push(path, ChangeKind::Added, None, Some(format!("{new:?}")));
+        path.truncate(len);
+    }
+
+    Ok(())
+}
+
+/// Find the value of a property by key.
+fn find(mut obj: Object<Slice<'_>>, key: u32) -> Result<Option<Value<Slice<'_>>>, Error> {
+    while !obj.is_empty() {
+        let p = obj.property()?;
+
+        if p.key::<u32>() == key {
+            return Ok(Some(p.value()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the encoded bytes of a value.
+fn bytes<'de>(value: &Value<Slice<'de>>) -> &'de [u8] {
+    let bytes = value.as_buf().as_bytes();
+    bytes.get(..value.size()).unwrap_or(bytes)
+}
@@ -0,0 +1,225 @@
+use crate::error::BufferUnderflow;
+use crate::{Error, ErrorKind, Type};
+
+/// The byte order used when encoding pods for persistence or transfer.
+///
+/// See [`encode_endian`] and [`decode_endian`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Little-endian byte order.
+    Little,
+    /// Big-endian byte order.
+    Big,
+}
+
+impl Endian {
+    /// The byte order of the current host.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Self = Self::Little;
+
+    /// The byte order of the current host.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Self = Self::Big;
+}
+
+/// Convert an encoded pod in host byte order into the specified byte order in
+/// place.
+///
+/// Pods are encoded in host byte order by default, which is what pipewire
+/// expects. This is used when a pod is persisted to disk or transferred to a
+/// host which might have a different byte order. Strings, bytes and bitmaps
+/// are left as-is, while every other value is converted recursively.
+///
+/// # Examples
+///
+/// ```
+/// use pod::utils::Endian;
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write_struct(|st| {
+///     st.field().write(0x01020304i32)?;
+///     st.field().write("hello")?;
+///     Ok(())
+/// })?;
+///
+/// let mut bytes = pod.as_buf().as_bytes().to_vec();
+/// pod::utils::encode_endian(&mut bytes, Endian::Big)?;
+///
+/// // The size of the struct in big-endian.
+/// assert_eq!(&bytes[..4], &[0, 0, 0, 32]);
+///
+/// pod::utils::decode_endian(&mut bytes, Endian::Big)?;
+/// assert_eq!(bytes, pod.as_buf().as_bytes());
+/// # Ok::<_, pod::Error>(())
+/// ```
+pub fn encode_endian(bytes: &mut [u8], endian: Endian) -> Result<(), Error> {
+    if endian == Endian::NATIVE {
+        return Ok(());
+    }
+
+    swap_pods(bytes, Swap::FromNative)
+}
+
+/// Convert an encoded pod in the specified byte order into host byte order in
+/// place.
+///
+/// This is the inverse of [`encode_endian`], see it for details.
+pub fn decode_endian(bytes: &mut [u8], endian: Endian) -> Result<(), Error> {
+    if endian == Endian::NATIVE {
+        return Ok(());
+    }
+
+    swap_pods(bytes, Swap::ToNative)
+}
+
+#[derive(Clone, Copy)]
+enum Swap {
+    /// The input is in native byte order, so values have to be read before
+    /// they are swapped.
+    FromNative,
+    /// The input is in foreign byte order, so values have to be swapped
+    /// before they are read.
+    ToNative,
+}
+
+/// Swap a sequence of pods, each padded to 8 bytes.
+fn swap_pods(mut bytes: &mut [u8], swap: Swap) -> Result<(), Error> {
+    while !bytes.is_empty() {
+        let size = swap_pod(bytes, swap)?;
+        let size = size.next_multiple_of(8).min(bytes.len());
+        bytes = &mut bytes[size..];
+    }
+
+    Ok(())
+}
+
+/// Swap a single pod including its header, returning the unpadded size of
+/// the pod.
+fn swap_pod(bytes: &mut [u8], swap: Swap) -> Result<usize, Error> {
+    let size = swap32(bytes, 0, swap)? as usize;
+    let ty = Type::new(swap32(bytes, 4, swap)?);
+    let body = bytes.get_mut(8..8 + size).ok_or(BufferUnderflow)?;
+    swap_body(body, ty, swap)?;
+    Ok(8 + size)
+}
+
+/// Swap the body of a pod with the given type.
+fn swap_body(bytes: &mut [u8], ty: Type, swap: Swap) -> Result<(), Error> {
+    match ty {
+        Type::NONE | Type::STRING | Type::BYTES | Type::BITMAP => {}
+        Type::BOOL | Type::ID | Type::INT | Type::FLOAT => {
+            expect_size(bytes, ty, 4)?;
+            swap32(bytes, 0, swap)?;
+        }
+        Type::LONG | Type::DOUBLE | Type::FD => {
+            expect_size(bytes, ty, 8)?;
+            swap64(bytes, 0)?;
+        }
+        Type::RECTANGLE | Type::FRACTION => {
+            expect_size(bytes, ty, 8)?;
+            swap32(bytes, 0, swap)?;
+            swap32(bytes, 4, swap)?;
+        }
+        Type::POINTER => {
+            expect_size(bytes, ty, 16)?;
+            swap32(bytes, 0, swap)?;
+            swap64(bytes, 8)?;
+        }
+        Type::ARRAY => {
+            let child_size = swap32(bytes, 0, swap)? as usize;
+            let child_type = Type::new(swap32(bytes, 4, swap)?);
+            swap_children(&mut bytes[8..], child_size, child_type, swap)?;
+        }
+        Type::CHOICE => {
+            swap32(bytes, 0, swap)?;
+            swap32(bytes, 4, swap)?;
+            let child_size = swap32(bytes, 8, swap)? as usize;
+            let child_type = Type::new(swap32(bytes, 12, swap)?);
+            swap_children(&mut bytes[16..], child_size, child_type, swap)?;
+        }
+        Type::STRUCT | Type::POD => {
+            swap_pods(bytes, swap)?;
+        }
+        // NB: Objects and sequences share the same layout, a pair of words
+        // followed by entries which are a pair of words and a pod.
+        Type::OBJECT | Type::SEQUENCE => {
+            swap32(bytes, 0, swap)?;
+            swap32(bytes, 4, swap)?;
+
+            let mut bytes = &mut bytes[8..];
+
+            while !bytes.is_empty() {
+                swap32(bytes, 0, swap)?;
+                swap32(bytes, 4, swap)?;
+                let size = swap_pod(&mut bytes[8..], swap)?;
+                let size = (8 + size).next_multiple_of(8).min(bytes.len());
+                bytes = &mut bytes[size..];
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Swap the children of an array or a choice, which are stored without
+/// headers.
+fn swap_children(bytes: &mut [u8], size: usize, ty: Type, swap: Swap) -> Result<(), Error> {
+    if size == 0 {
+        return Ok(());
+    }
+
+    if !bytes.len().is_multiple_of(size) {
+        return Err(Error::new(ErrorKind::InvalidArrayLength));
+    }
+
+    for child in bytes.chunks_exact_mut(size) {
+        swap_body(child, ty, swap)?;
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn expect_size(bytes: &[u8], ty: Type, expected: usize) -> Result<(), Error> {
+    if bytes.len() != expected {
+        return Err(Error::new(ErrorKind::ExpectedSize {
+            ty,
+            expected,
+            actual: bytes.len(),
+        }));
+    }
+
+    Ok(())
+}
+
+/// Swap the 32-bit word at the given offset, returning its value in host byte
+/// order.
+#[inline]
+fn swap32(bytes: &mut [u8], at: usize, swap: Swap) -> Result<u32, BufferUnderflow> {
+    let word = bytes
+        .get_mut(at..at + 4)
+        .and_then(|word| <&mut [u8; 4]>::try_from(word).ok())
+        .ok_or(BufferUnderflow)?;
+
+    let value = u32::from_ne_bytes(*word);
+    let swapped = value.swap_bytes();
+    *word = swapped.to_ne_bytes();
+
+    Ok(match swap {
+        Swap::FromNative => value,
+        Swap::ToNative => swapped,
+    })
+}
+
+/// Swap the 64-bit word at the given offset.
+#[inline]
+fn swap64(bytes: &mut [u8], at: usize) -> Result<(), BufferUnderflow> {
+    let word = bytes
+        .get_mut(at..at + 8)
+        .and_then(|word| <&mut [u8; 8]>::try_from(word).ok())
+        .ok_or(BufferUnderflow)?;
+
+    word.reverse();
+    Ok(())
+}
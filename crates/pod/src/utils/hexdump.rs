@@ -0,0 +1,404 @@
+use core::fmt;
+
+use crate::{ChoiceType, Slice, Type, Value};
+
+/// The size of a word in a pod.
+const WORD: usize = 8;
+
+/// Construct an annotated hexdump of serialized pods.
+///
+/// Each line of the dump holds one word of data prefixed by its offset. Pod
+/// headers are decoded inline with their type and size, and containers are
+/// walked recursively with their children indented.
+///
+/// # Examples
+///
+/// ```
+/// let mut pod = pod::array();
+/// pod.as_mut().write_struct(|st| {
+///     st.field().write(10i32)?;
+///     st.field().write("hi")?;
+///     Ok(())
+/// })?;
+///
+/// let dump = pod::utils::hexdump(pod.as_ref().as_buf().as_bytes()).to_string();
+///
+/// let expected = [
+///     "0000: 20 00 00 00 0e 00 00 00  Struct size=32",
+///     "0008: 04 00 00 00 04 00 00 00    Int size=4",
+///     "0010: 0a 00 00 00 00 00 00 00      10",
+///     "0018: 03 00 00 00 08 00 00 00    String size=3",
+///     "0020: 68 69 00 00 00 00 00 00      \"hi\"",
+/// ];
+///
+/// assert_eq!(dump.lines().collect::<Vec<_>>(), expected);
+/// # Ok::<_, pod::Error>(())
+/// ```
+#[inline]
+pub fn hexdump(bytes: &[u8]) -> HexDump<'_> {
+    HexDump { bytes }
+}
+
+/// An annotated hexdump of serialized pods.
+///
+/// See [`hexdump`].
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+}
+
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut offset = 0;
+
+        while offset < self.bytes.len() {
+            let Some(next) = self.pod(f, offset, self.bytes.len(), 0)? else {
+                break;
+            };
+
+            offset = next;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for HexDump<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl HexDump<'_> {
+    /// Dump the pod at `offset`, returning the offset of the next pod or `None`
+    /// if the pod is truncated.
+    fn pod(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> Result<Option<usize>, fmt::Error> {
+        let Some((size, ty)) = self.pair(offset, end) else {
+            self.truncated(f, offset, end, depth)?;
+            return Ok(None);
+        };
+
+        let ty = Type::new(ty);
+        let size = size as usize;
+
+        self.line(
+            f,
+            offset,
+            end,
+            depth,
+            Some(format_args!("{ty:?} size={size}")),
+        )?;
+
+        let start = offset + WORD;
+
+        let Some(body_end) = start.checked_add(size).filter(|&n| n <= end) else {
+            self.truncated(f, start, end, depth + 1)?;
+            return Ok(None);
+        };
+
+        self.content(f, ty, start, body_end, depth + 1)?;
+        Ok(Some(body_end.next_multiple_of(WORD).min(end)))
+    }
+
+    /// Dump the content of a pod of the given type.
+    fn content(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        ty: Type,
+        mut offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        match ty {
+            Type::STRUCT => {
+                self.children(f, offset, end, depth)?;
+            }
+            Type::POD => {
+                self.children(f, offset, end, depth)?;
+            }
+            Type::OBJECT => {
+                let Some((object_type, object_id)) = self.pair(offset, end) else {
+                    return self.truncated(f, offset, end, depth);
+                };
+
+                self.line(
+                    f,
+                    offset,
+                    end,
+                    depth,
+                    Some(format_args!("object type={object_type} id={object_id}")),
+                )?;
+
+                offset += WORD;
+
+                while offset < end {
+                    let Some((key, flags)) = self.pair(offset, end) else {
+                        return self.truncated(f, offset, end, depth);
+                    };
+
+                    self.line(
+                        f,
+                        offset,
+                        end,
+                        depth,
+                        Some(format_args!("property key={key} flags={flags}")),
+                    )?;
+
+                    let Some(next) = self.pod(f, offset + WORD, end, depth + 1)? else {
+                        return Ok(());
+                    };
+
+                    offset = next;
+                }
+            }
+            Type::SEQUENCE => {
+                let Some((unit, _)) = self.pair(offset, end) else {
+                    return self.truncated(f, offset, end, depth);
+                };
+
+                self.line(
+                    f,
+                    offset,
+                    end,
+                    depth,
+                    Some(format_args!("sequence unit={unit}")),
+                )?;
+                offset += WORD;
+
+                while offset < end {
+                    let Some((control_offset, control_type)) = self.pair(offset, end) else {
+                        return self.truncated(f, offset, end, depth);
+                    };
+
+                    self.line(
+                        f,
+                        offset,
+                        end,
+                        depth,
+                        Some(format_args!(
+                            "control offset={control_offset} type={control_type}"
+                        )),
+                    )?;
+
+                    let Some(next) = self.pod(f, offset + WORD, end, depth + 1)? else {
+                        return Ok(());
+                    };
+
+                    offset = next;
+                }
+            }
+            Type::ARRAY => {
+                let Some((child_size, child_type)) = self.pair(offset, end) else {
+                    return self.truncated(f, offset, end, depth);
+                };
+
+                self.line(
+                    f,
+                    offset,
+                    end,
+                    depth,
+                    Some(format_args!(
+                        "child {:?} size={child_size}",
+                        Type::new(child_type)
+                    )),
+                )?;
+
+                self.elements(
+                    f,
+                    Type::new(child_type),
+                    child_size as usize,
+                    offset + WORD,
+                    end,
+                    depth,
+                )?;
+            }
+            Type::CHOICE => {
+                let Some((choice_type, flags)) = self.pair(offset, end) else {
+                    return self.truncated(f, offset, end, depth);
+                };
+
+                self.line(
+                    f,
+                    offset,
+                    end,
+                    depth,
+                    Some(format_args!(
+                        "choice {:?} flags={flags}",
+                        ChoiceType::from_u32(choice_type)
+                    )),
+                )?;
+
+                offset += WORD;
+
+                let Some((child_size, child_type)) = self.pair(offset, end) else {
+                    return self.truncated(f, offset, end, depth);
+                };
+
+                self.line(
+                    f,
+                    offset,
+                    end,
+                    depth,
+                    Some(format_args!(
+                        "child {:?} size={child_size}",
+                        Type::new(child_type)
+                    )),
+                )?;
+
+                self.elements(
+                    f,
+                    Type::new(child_type),
+                    child_size as usize,
+                    offset + WORD,
+                    end,
+                    depth,
+                )?;
+            }
+            ty => {
+                // Include the padding of the pod in the dump.
+                let padded = end.next_multiple_of(WORD).min(self.bytes.len());
+                self.value(f, ty, offset, end, padded, depth)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Dump a sequence of child pods.
+    fn children(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        while offset < end {
+            let Some(next) = self.pod(f, offset, end, depth)? else {
+                break;
+            };
+
+            offset = next;
+        }
+
+        Ok(())
+    }
+
+    /// Dump the packed elements of an array or choice.
+    fn elements(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        ty: Type,
+        size: usize,
+        mut offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        if size == 0 {
+            return self.raw(f, offset, end, depth);
+        }
+
+        while offset + size <= end {
+            self.value(f, ty, offset, offset + size, offset + size, depth)?;
+            offset += size;
+        }
+
+        self.raw(f, offset, end, depth)
+    }
+
+    /// Dump a value with the decoded value annotated on its first line.
+    ///
+    /// The bytes up until `padded` are included in the dump.
+    fn value(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        ty: Type,
+        offset: usize,
+        end: usize,
+        padded: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        let value = Value::new(Slice::new(&self.bytes[offset..end]), end - offset, ty);
+        self.line(f, offset, padded, depth, Some(format_args!("{value:?}")))?;
+
+        if offset + WORD < padded {
+            self.raw(f, offset + WORD, padded, depth)?;
+        }
+
+        Ok(())
+    }
+
+    /// Dump raw words without annotations.
+    fn raw(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        while offset < end {
+            self.line(f, offset, end, depth, None)?;
+            offset += WORD;
+        }
+
+        Ok(())
+    }
+
+    fn truncated(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        self.line(f, offset, end, depth, Some(format_args!("<truncated>")))
+    }
+
+    /// Read a pair of native endian `u32` values at the given offset.
+    fn pair(&self, offset: usize, end: usize) -> Option<(u32, u32)> {
+        if offset.checked_add(WORD)? > end {
+            return None;
+        }
+
+        let a = self.bytes.get(offset..offset + 4)?;
+        let b = self.bytes.get(offset + 4..offset + WORD)?;
+        Some((
+            u32::from_ne_bytes(a.try_into().ok()?),
+            u32::from_ne_bytes(b.try_into().ok()?),
+        ))
+    }
+
+    /// Write a single line holding up to one word of data.
+    fn line(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        offset: usize,
+        end: usize,
+        depth: usize,
+        note: Option<fmt::Arguments<'_>>,
+    ) -> fmt::Result {
+        let end = end.min(self.bytes.len()).min(offset + WORD);
+        let bytes = self.bytes.get(offset..end).unwrap_or_default();
+
+        write!(f, "{offset:04x}:")?;
+
+        for b in bytes {
+            write!(f, " {b:02x}")?;
+        }
+
+        if let Some(note) = note {
+            for _ in bytes.len()..WORD {
+                f.write_str("   ")?;
+            }
+
+            write!(f, "  {:indent$}{note}", "", indent = depth * 2)?;
+        }
+
+        writeln!(f)
+    }
+}
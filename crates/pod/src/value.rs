@@ -2,7 +2,6 @@ use core::ffi::CStr;
 use core::fmt;
 use core::mem;
 
-use crate::ChoiceType;
 #[cfg(feature = "alloc")]
 use crate::DynamicBuf;
 use crate::PodStream;
@@ -17,6 +16,7 @@ use crate::{
     Pointer, Reader, Rectangle, SizedReadable, Slice, Type, UnsizedReadable, UnsizedWritable,
     Visitor, Writer,
 };
+use crate::{ChoicePolicy, ChoiceType, MaybeChoice};
 
 /// A value inside of a [`Pod`].
 ///
@@ -37,6 +37,13 @@ impl<B> Value<B> {
 }
 
 impl<B> Value<B> {
+    /// Get a reference to the underlying buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn as_buf(&self) -> &B {
+        &self.buf
+    }
+
     /// Get the type of the pod.
     ///
     /// # Examples
@@ -197,6 +204,36 @@ where
     /// ```
     #[inline]
     pub fn read_sized<T>(self) -> Result<T, Error>
+    where
+        T: SizedReadable<'de>,
+    {
+        self.read_sized_with(ChoicePolicy::Strict)
+    }
+
+    /// Read a sized value, using the given [`ChoicePolicy`] to determine how
+    /// to handle a choice which is encountered in place of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoicePolicy, ChoiceType, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_choice(ChoiceType::RANGE, Type::INT, |choice| {
+    ///     choice.child().write(48000i32)?;
+    ///     choice.child().write(8000i32)?;
+    ///     choice.child().write(192000i32)?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let value = pod.as_ref().into_value()?;
+    ///
+    /// assert!(value.as_ref().read_sized_with::<i32>(ChoicePolicy::Strict).is_err());
+    ///
+    /// assert_eq!(value.read_sized_with::<i32>(ChoicePolicy::Default)?, 48000);
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn read_sized_with<T>(self, policy: ChoicePolicy) -> Result<T, Error>
     where
         T: SizedReadable<'de>,
     {
@@ -204,9 +241,9 @@ where
             Type::CHOICE => {
                 let mut choice = self.read_choice()?;
 
-                if choice.choice_type() != ChoiceType::NONE {
+                if choice.choice_type() != ChoiceType::NONE && policy == ChoicePolicy::Strict {
                     return Err(Error::new(ErrorKind::InvalidChoiceType {
-                        ty: Type::INT,
+                        ty: choice.child_type(),
                         expected: ChoiceType::NONE,
                         actual: choice.choice_type(),
                     }));
@@ -221,6 +258,56 @@ where
         Ok(value)
     }
 
+    /// Read a sized value, or the full choice if a choice of any type other
+    /// than [`ChoiceType::NONE`] is encountered in place of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoiceType, MaybeChoice, Type};
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write_choice(ChoiceType::ENUM, Type::INT, |choice| {
+    ///     choice.child().write(2i32)?;
+    ///     choice.child().write(1i32)?;
+    ///     choice.child().write(2i32)?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let MaybeChoice::Choice(mut choice) = pod.as_ref().into_value()?.read_sized_or_choice::<i32>()? else {
+    ///     panic!("expected a choice");
+    /// };
+    ///
+    /// assert_eq!(choice.choice_type(), ChoiceType::ENUM);
+    /// assert_eq!(choice.read::<(i32, i32, i32)>()?, (2, 1, 2));
+    ///
+    /// let mut pod = pod::array();
+    /// pod.as_mut().write(42i32)?;
+    ///
+    /// let value = pod.as_ref().into_value()?.read_sized_or_choice::<i32>()?;
+    /// assert!(matches!(value, MaybeChoice::Value(42)));
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    pub fn read_sized_or_choice<T>(self) -> Result<MaybeChoice<Slice<'de>, T>, Error>
+    where
+        T: SizedReadable<'de>,
+    {
+        if self.ty != Type::CHOICE {
+            return Ok(MaybeChoice::Value(T::read_content(
+                self.buf, self.ty, self.size,
+            )?));
+        }
+
+        let mut choice = self.read_choice()?;
+
+        if choice.choice_type() != ChoiceType::NONE {
+            return Ok(MaybeChoice::Choice(choice));
+        }
+
+        let value = choice.next().ok_or(BufferUnderflow)?;
+        Ok(MaybeChoice::Value(value.read_sized()?))
+    }
+
     /// Read the next unsized value.
     ///
     /// # Examples
@@ -586,6 +673,14 @@ impl<'de> PodItem<'de> for Value<Slice<'de>> {
         Value::read_sized(self)
     }
 
+    #[inline]
+    fn read_sized_or_choice<T>(self) -> Result<MaybeChoice<Slice<'de>, T>, Error>
+    where
+        T: SizedReadable<'de>,
+    {
+        Value::read_sized_or_choice(self)
+    }
+
     #[inline]
     fn read_unsized<T>(self) -> Result<&'de T, Error>
     where
@@ -0,0 +1,264 @@
+//! WebAssembly bindings for building and inspecting pods.
+//!
+//! This is enabled through the `wasm-bindgen` feature, and is intended for web
+//! tooling like a browser-based viewer of recorded traces. The bindings can be
+//! generated with [wasm-bindgen]:
+//!
+//! ```text
+//! cargo build -p pod --no-default-features --features wasm-bindgen --target wasm32-unknown-unknown --release
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/pod.wasm
+//! ```
+//!
+//! Values which fail to be read leave the reader unmodified, and errors are
+//! raised as JavaScript exceptions.
+//!
+//! [wasm-bindgen]: https://github.com/wasm-bindgen/wasm-bindgen
+
+use core::ffi::CStr;
+
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::item::Item;
+use crate::{Builder, Error, Id, Reader, Slice, Value};
+
+/// A builder of pods.
+///
+/// Values are added to the builder in order, and nested structs are opened
+/// with [`PodBuilder::begin_struct`] and closed with
+/// [`PodBuilder::end_struct`]. The encoded pods are produced by
+/// [`PodBuilder::finish`].
+#[wasm_bindgen]
+pub struct PodBuilder {
+    stack: Vec<Vec<Item>>,
+}
+
+#[wasm_bindgen]
+impl PodBuilder {
+    /// Construct a new pod builder.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::from([Vec::new()]),
+        }
+    }
+
+    /// Clear the builder so that it can be re-used.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.stack.push(Vec::new());
+    }
+
+    /// Add a boolean.
+    pub fn write_bool(&mut self, value: bool) {
+        self.push(Item::Bool(value));
+    }
+
+    /// Add an identifier.
+    pub fn write_id(&mut self, value: u32) {
+        self.push(Item::Id(value));
+    }
+
+    /// Add a 32-bit signed integer.
+    pub fn write_int(&mut self, value: i32) {
+        self.push(Item::Int(value));
+    }
+
+    /// Add a 64-bit signed integer.
+    pub fn write_long(&mut self, value: i64) {
+        self.push(Item::Long(value));
+    }
+
+    /// Add a 32-bit float.
+    pub fn write_float(&mut self, value: f32) {
+        self.push(Item::Float(value));
+    }
+
+    /// Add a 64-bit float.
+    pub fn write_double(&mut self, value: f64) {
+        self.push(Item::Double(value));
+    }
+
+    /// Add a none value.
+    pub fn write_none(&mut self) {
+        self.push(Item::None);
+    }
+
+    /// Add a string, which must not contain any null bytes.
+    pub fn write_string(&mut self, value: &str) -> Result<(), JsError> {
+        let value = CString::new(value).map_err(|e| JsError::new(&e.to_string()))?;
+        self.push(Item::String(value));
+        Ok(())
+    }
+
+    /// Add a byte array.
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.push(Item::Bytes(value.into()));
+    }
+
+    /// Begin a struct, which all subsequently added values are fields of
+    /// until [`PodBuilder::end_struct`] is called.
+    pub fn begin_struct(&mut self) {
+        self.stack.push(Vec::new());
+    }
+
+    /// End the struct most recently opened with [`PodBuilder::begin_struct`].
+    pub fn end_struct(&mut self) -> Result<(), JsError> {
+        if self.stack.len() < 2 {
+            return Err(JsError::new("No struct to end"));
+        }
+
+        if let Some(items) = self.stack.pop() {
+            self.push(Item::Struct(items));
+        }
+
+        Ok(())
+    }
+
+    /// Encode every added value.
+    ///
+    /// Fails if a struct has not been ended.
+    pub fn finish(&self) -> Result<Vec<u8>, JsError> {
+        let [items] = self.stack.as_slice() else {
+            return Err(JsError::new("Struct has not been ended"));
+        };
+
+        let mut pod = Builder::dynamic();
+        pod.as_mut().write(items.as_slice()).map_err(js_error)?;
+        Ok(pod.as_buf().as_bytes().to_vec())
+    }
+}
+
+impl PodBuilder {
+    fn push(&mut self, item: Item) {
+        if let Some(items) = self.stack.last_mut() {
+            items.push(item);
+        }
+    }
+}
+
+impl Default for PodBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reader of encoded pods.
+#[wasm_bindgen]
+pub struct PodReader {
+    data: Vec<u8>,
+    at: usize,
+}
+
+#[wasm_bindgen]
+impl PodReader {
+    /// Construct a reader over a copy of encoded pods.
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Self {
+        Self {
+            data: data.to_vec(),
+            at: 0,
+        }
+    }
+
+    /// Test if the reader has no more values to read.
+    pub fn is_empty(&self) -> bool {
+        self.at >= self.data.len()
+    }
+
+    /// Get the type of the next value without advancing the reader.
+    pub fn peek_type(&self) -> Result<u32, JsError> {
+        let (value, _) = self.value().map_err(js_error)?;
+        Ok(value.ty().into_u32())
+    }
+
+    /// Read a boolean.
+    pub fn read_bool(&mut self) -> Result<bool, JsError> {
+        self.read(|value| value.read_sized())
+    }
+
+    /// Read an identifier.
+    pub fn read_id(&mut self) -> Result<u32, JsError> {
+        self.read(|value| Ok(value.read_sized::<Id<u32>>()?.0))
+    }
+
+    /// Read a 32-bit signed integer.
+    pub fn read_int(&mut self) -> Result<i32, JsError> {
+        self.read(|value| value.read_sized())
+    }
+
+    /// Read a 64-bit signed integer.
+    pub fn read_long(&mut self) -> Result<i64, JsError> {
+        self.read(|value| value.read_sized())
+    }
+
+    /// Read a 32-bit float.
+    pub fn read_float(&mut self) -> Result<f32, JsError> {
+        self.read(|value| value.read_sized())
+    }
+
+    /// Read a 64-bit float.
+    pub fn read_double(&mut self) -> Result<f64, JsError> {
+        self.read(|value| value.read_sized())
+    }
+
+    /// Read a string.
+    pub fn read_string(&mut self) -> Result<String, JsError> {
+        self.read(|value| {
+            let value = value.read_unsized::<CStr>()?;
+            Ok(String::from(value.to_string_lossy()))
+        })
+    }
+
+    /// Read a byte array.
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, JsError> {
+        self.read(|value| Ok(value.read_unsized::<[u8]>()?.to_vec()))
+    }
+
+    /// Read a struct, returning a reader over its fields.
+    pub fn read_struct(&mut self) -> Result<PodReader, JsError> {
+        self.read(|value| Ok(PodReader::new(value.read_struct()?.as_buf().as_bytes())))
+    }
+
+    /// Skip the next value.
+    pub fn skip(&mut self) -> Result<(), JsError> {
+        self.read(|value| value.skip().map(|_| ()))
+    }
+
+    /// Describe the next value in a human readable form, like
+    /// `Struct { fields: [1, "hello"] }`.
+    pub fn inspect(&mut self) -> Result<String, JsError> {
+        self.read(|value| Ok(format!("{value:?}")))
+    }
+}
+
+impl PodReader {
+    /// Decode the next value, returning it and the offset of the value after
+    /// it.
+    fn value(&self) -> Result<(Value<Slice<'_>>, usize), Error> {
+        let data = self.data.get(self.at..).unwrap_or_default();
+        let (value, mut rest) = Value::from_reader(Slice::new(data))?;
+        rest.unpad(8)?;
+        Ok((value, self.data.len() - rest.as_bytes().len()))
+    }
+
+    /// Read the next value, only advancing the reader if `f` succeeds.
+    fn read<T>(
+        &mut self,
+        f: impl FnOnce(Value<Slice<'_>>) -> Result<T, Error>,
+    ) -> Result<T, JsError> {
+        let (value, at) = self.value().map_err(js_error)?;
+        let output = f(value).map_err(js_error)?;
+        self.at = at;
+        Ok(output)
+    }
+}
+
+fn js_error(error: Error) -> JsError {
+    JsError::new(&error.to_string())
+}
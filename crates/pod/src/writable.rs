@@ -37,6 +37,34 @@ where
     }
 }
 
+/// Implementation of [`Writable`] for an optional value, which is encoded as a
+/// `None` pod if it is not set.
+///
+/// # Examples
+///
+/// ```
+/// use pod::Type;
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write_struct(|st| st.write((Some(42u32), None::<u32>)))?;
+///
+/// let mut st = pod.as_ref().read_struct()?;
+/// assert_eq!(st.read::<(Option<u32>, Option<u32>)>()?, (Some(42), None));
+/// # Ok::<_, pod::Error>(())
+/// ```
+impl<T> Writable for Option<T>
+where
+    T: Writable,
+{
+    #[inline]
+    fn write_into(&self, pod: &mut impl PodSink) -> Result<(), Error> {
+        match self {
+            Some(value) => value.write_into(pod),
+            None => pod.next()?.write_none(),
+        }
+    }
+}
+
 /// Implementation of [`Writable`] for an array.
 ///
 /// # Examples
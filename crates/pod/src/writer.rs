@@ -1,3 +1,8 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::slice;
+
 use crate::utils::BytesInhabited;
 use crate::{Error, Slice};
 
@@ -9,7 +14,7 @@ mod sealed {
     pub trait Sealed {}
     impl<const N: usize> Sealed for ArrayBuf<N> {}
     #[cfg(feature = "alloc")]
-    impl Sealed for DynamicBuf {}
+    impl<A> Sealed for DynamicBuf<A> where A: crate::buf::Allocator {}
     impl<W> Sealed for &mut W where W: ?Sized + Writer {}
 }
 
@@ -33,6 +38,35 @@ where
     fn saturating_add(self, other: usize) -> Self;
 }
 
+/// A typed handle to a region reserved through [`Writer::reserve_value`].
+///
+/// The region can be patched with a new value of the same type through
+/// [`Writer::patch`], which is useful for headers whose content is only known
+/// once the data following them has been written.
+pub struct Reserved<P, T> {
+    pos: P,
+    _marker: PhantomData<T>,
+}
+
+impl<P, T> Clone for Reserved<P, T>
+where
+    P: Copy,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P, T> Copy for Reserved<P, T> where P: Copy {}
+
+impl<P, T> fmt::Debug for Reserved<P, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reserved").finish_non_exhaustive()
+    }
+}
+
 /// A type that can have PODs written to it.
 pub trait Writer
 where
@@ -82,6 +116,63 @@ where
 
     /// Get a slice from the writer starting at the given position.
     fn slice_from(&self, pos: Self::Pos) -> Slice<'_>;
+
+    /// Reserve a region holding `value` which can later be patched through
+    /// [`Writer::patch`].
+    ///
+    /// # Examples
+    ///
+    /// Writing a custom struct-like container where the size in the header
+    /// is patched once the content has been written:
+    ///
+    /// ```
+    /// use pod::{AsSlice, DynamicBuf, Pod, Writer};
+    ///
+    /// const STRUCT: u32 = 14;
+    /// const INT: u32 = 4;
+    ///
+    /// let mut buf = DynamicBuf::new();
+    ///
+    /// let header = buf.reserve_value([0u32, STRUCT])?;
+    /// buf.write(&[4u32, INT, 42, 0])?;
+    ///
+    /// let size = buf.written_since(&header) as u32;
+    /// assert_eq!(size, 16);
+    /// buf.patch(header, [size, STRUCT])?;
+    ///
+    /// let mut st = Pod::new(buf.as_slice()).read_struct()?;
+    /// assert_eq!(st.field()?.read_sized::<i32>()?, 42);
+    /// assert!(st.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    fn reserve_value<T>(&mut self, value: T) -> Result<Reserved<Self::Pos, T>, Error>
+    where
+        T: BytesInhabited,
+    {
+        let pos = self.reserve(slice::from_ref(&value))?;
+
+        Ok(Reserved {
+            pos,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Patch a region previously reserved through [`Writer::reserve_value`].
+    #[inline]
+    fn patch<T>(&mut self, reserved: Reserved<Self::Pos, T>, value: T) -> Result<(), Error>
+    where
+        T: BytesInhabited,
+    {
+        self.write_at(reserved.pos, slice::from_ref(&value))
+    }
+
+    /// Get the number of bytes written after the given reserved region.
+    #[inline]
+    fn written_since<T>(&self, reserved: &Reserved<Self::Pos, T>) -> usize {
+        self.distance_from(&reserved.pos)
+            .saturating_sub(mem::size_of::<T>())
+    }
 }
 
 impl<W> Writer for &mut W
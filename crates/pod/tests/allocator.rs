@@ -0,0 +1,174 @@
+//! Tests that buffers backed by a custom allocator never touch the global
+//! allocator.
+
+#![cfg(feature = "std")]
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::{Cell, UnsafeCell};
+use core::ptr::NonNull;
+
+use std::alloc::System;
+
+use pod::buf::{Allocator, Growth};
+use pod::{AsSlice, Builder, DynamicBuf, Error, Pod, Type};
+
+/// The global allocator of the test binary, which counts allocations made by
+/// the current thread.
+///
+/// This is an integration test so that the allocator only applies to this
+/// binary.
+struct CountingGlobal;
+
+std::thread_local! {
+    static GLOBAL_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    _ = GLOBAL_ALLOCATIONS.try_with(|c| c.set(c.get() + 1));
+}
+
+fn global_allocations() -> usize {
+    GLOBAL_ALLOCATIONS.with(Cell::get)
+}
+
+unsafe impl GlobalAlloc for CountingGlobal {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingGlobal = CountingGlobal;
+
+const WORDS: usize = 1024;
+
+/// A bump allocator over a fixed region of memory.
+struct Arena {
+    data: UnsafeCell<[u64; WORDS]>,
+    used: Cell<usize>,
+    allocations: Cell<usize>,
+}
+
+impl Arena {
+    fn new() -> Self {
+        Self {
+            data: UnsafeCell::new([0; WORDS]),
+            used: Cell::new(0),
+            allocations: Cell::new(0),
+        }
+    }
+}
+
+unsafe impl Allocator for Arena {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let words = layout.size().div_ceil(8);
+        let start = self.used.get();
+
+        if layout.align() > 8 || start + words > WORDS {
+            return None;
+        }
+
+        self.used.set(start + words);
+        self.allocations.set(self.allocations.get() + 1);
+        NonNull::new(self.data.get().cast::<u64>().wrapping_add(start).cast())
+    }
+
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
+
+#[test]
+fn no_global_allocations() -> Result<(), Error> {
+    let arena = Arena::new();
+    let before = global_allocations();
+
+    let mut pod = Builder::new(DynamicBuf::new_in(&arena));
+
+    pod.as_mut().write_struct(|st| {
+        st.field().write_sized(42i32)?;
+        st.field().write_unsized("hello world")?;
+
+        st.field().write_array(Type::LONG, |array| {
+            for n in 0..64i64 {
+                array.child().write_sized(n)?;
+            }
+
+            Ok(())
+        })
+    })?;
+
+    let mut st = pod.as_ref().read_struct()?;
+    assert_eq!(st.field()?.read_sized::<i32>()?, 42);
+    assert_eq!(st.field()?.read_unsized::<str>()?, "hello world");
+    assert_eq!(st.field()?.read_array()?.len(), 64);
+
+    drop(pod);
+
+    assert_eq!(global_allocations(), before);
+    assert!(arena.allocations.get() > 1);
+    Ok(())
+}
+
+#[test]
+fn arena_exhausted() {
+    let arena = Arena::new();
+    let mut buf = DynamicBuf::new_in(&arena);
+
+    assert!(buf.try_reserve(WORDS * 8).is_ok());
+    assert!(buf.try_reserve(WORDS * 8 + 1).is_err());
+}
+
+#[test]
+fn global_is_counted() {
+    let before = global_allocations();
+    let mut buf = DynamicBuf::new();
+    assert!(buf.try_reserve(64).is_ok());
+    assert!(global_allocations() > before);
+}
+
+#[test]
+fn preallocated_writes_never_allocate() -> Result<(), Error> {
+    let mut buf = DynamicBuf::new();
+    buf.try_reserve(256)?;
+    buf.set_growth(Growth::Fixed);
+
+    let before = global_allocations();
+
+    for _ in 0..4 {
+        buf.clear();
+
+        Builder::new(&mut buf).write_struct(|st| {
+            st.field().write_sized(42i32)?;
+            st.field().write_unsized("hello world")?;
+            Ok(())
+        })?;
+    }
+
+    let result = buf.try_write(|buf| {
+        Builder::new(buf).write_array(Type::LONG, |array| {
+            for n in 0..64i64 {
+                array.child().write_sized(n)?;
+            }
+
+            Ok(())
+        })
+    });
+
+    assert!(result.is_err());
+    assert_eq!(global_allocations(), before);
+    assert_eq!(buf.capacity(), 256);
+
+    let mut st = Pod::new(buf.as_slice()).read_struct()?;
+    assert_eq!(st.field()?.read_sized::<i32>()?, 42);
+    assert_eq!(st.field()?.read_unsized::<str>()?, "hello world");
+    Ok(())
+}
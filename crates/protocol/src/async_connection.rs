@@ -0,0 +1,131 @@
+use std::os::fd::RawFd;
+
+use tokio::io::Interest;
+use tokio::io::unix::AsyncFd;
+
+use crate::buf::{RecvBuf, SendBuf};
+use crate::{Connection, Error, ErrorKind};
+
+/// A [`Connection`] which is driven by the tokio runtime.
+///
+/// This lets a connection be embedded in an existing async runtime instead of
+/// being driven by a [`Poll`] loop. Messages are still encoded and decoded
+/// through the [`Connection`], which can be accessed through
+/// [`AsyncConnection::get_mut`], and the [`RecvBuf`] and [`SendBuf`] buffers.
+///
+/// This requires the `tokio` feature and that the connection is created
+/// inside of a runtime with IO enabled.
+///
+/// [`Poll`]: crate::Poll
+///
+/// # Examples
+///
+/// ```no_run
+/// use protocol::buf::{RecvBuf, SendBuf};
+/// use protocol::{AsyncConnection, Connection};
+///
+/// # async fn run() -> Result<(), protocol::Error> {
+/// let mut c = AsyncConnection::new(Connection::open()?)?;
+///
+/// let mut outgoing = SendBuf::new();
+/// let mut recv = RecvBuf::new();
+/// let mut fds = [0; 16];
+///
+/// c.send_all(&mut outgoing).await?;
+///
+/// loop {
+///     let n_fds = c.recv_with_fds(&mut recv, &mut fds).await?;
+///     // Decode the messages in `recv` and the received file descriptors.
+/// }
+/// # }
+/// ```
+pub struct AsyncConnection {
+    inner: AsyncFd<Connection>,
+}
+
+impl AsyncConnection {
+    /// Register a connection with the current runtime, setting it to
+    /// non-blocking mode.
+    ///
+    /// # Panics
+    ///
+    /// This panics if called outside of a tokio runtime.
+    pub fn new(mut connection: Connection) -> Result<Self, Error> {
+        connection.set_nonblocking(true)?;
+
+        let inner = AsyncFd::with_interest(connection, Interest::READABLE | Interest::WRITABLE)
+            .map_err(ErrorKind::RegisterFailed)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Access the underlying connection.
+    #[inline]
+    pub fn get_ref(&self) -> &Connection {
+        self.inner.get_ref()
+    }
+
+    /// Access the underlying connection mutably, like to write requests.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Connection {
+        self.inner.get_mut()
+    }
+
+    /// Deregister the connection from the runtime and return it.
+    #[inline]
+    pub fn into_inner(self) -> Connection {
+        self.inner.into_inner()
+    }
+
+    /// Receive data and file descriptors from the server into `recv` and
+    /// `fds`, waiting until some have been received.
+    ///
+    /// Returns the number of received file descriptors, see
+    /// [`Connection::recv_with_fds`].
+    pub async fn recv_with_fds(
+        &mut self,
+        recv: &mut RecvBuf,
+        fds: &mut [RawFd],
+    ) -> Result<usize, Error> {
+        loop {
+            let mut guard = self
+                .inner
+                .readable_mut()
+                .await
+                .map_err(ErrorKind::ReceiveFailed)?;
+
+            let before = recv.remaining_bytes();
+            let n_fds = guard.get_inner_mut().recv_with_fds(recv, fds)?;
+
+            // NB: Receiving stops early when file descriptors are received,
+            // otherwise it stops once the socket would block.
+            if n_fds == 0 {
+                guard.clear_ready();
+            }
+
+            if n_fds > 0 || recv.remaining_bytes() != before {
+                return Ok(n_fds);
+            }
+        }
+    }
+
+    /// Send all outgoing data to the server, waiting for the socket to become
+    /// writable as needed.
+    pub async fn send_all(&mut self, outgoing: &mut SendBuf) -> Result<(), Error> {
+        while !outgoing.is_empty() {
+            let mut guard = self
+                .inner
+                .writable_mut()
+                .await
+                .map_err(ErrorKind::SendFailed)?;
+
+            guard.get_inner_mut().send_all(outgoing)?;
+
+            if !outgoing.is_empty() {
+                guard.clear_ready();
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -2,28 +2,35 @@ use core::fmt;
 use core::mem::{self, MaybeUninit};
 use core::ptr;
 
+use alloc::vec::Vec;
+
+use std::collections::VecDeque;
 use std::env;
 use std::ffi::OsStr;
 use std::io;
 use std::io::Write;
-use std::os::fd::{AsRawFd, RawFd};
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
 use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
 
 use pod::IntoRaw;
-use pod::{AsSlice, Pod};
+use pod::{AsSlice, Pod, Slice};
 use tracing::Level;
 
 use crate::buf::{RecvBuf, SendBuf};
 use crate::poll::{ChangeInterest, Interest};
 use crate::types::Header;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, FdOrigin, ManagedFd};
 
 const ENVIRONS: &[&str] = &["PIPEWIRE_RUNTIME_DIR", "XDG_RUNTIME_DIR", "USERPROFILE"];
 const DEFAULT_PIPEWIRE_REMOTE: &str = "pipewire-0";
 
 const MAX_SEND_SIZE: usize = 4096;
 
+/// The maximum number of file descriptors sent with a single message, same as
+/// `MAX_FDS_MSG` in the native protocol.
+const MAX_SEND_FDS: usize = 28;
+
 impl AsRawFd for Connection {
     #[inline]
     fn as_raw_fd(&self) -> i32 {
@@ -31,6 +38,103 @@ impl AsRawFd for Connection {
     }
 }
 
+/// Options applied to the socket when opening a [`Connection`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use protocol::{Connection, ConnectionOptions};
+///
+/// let options = ConnectionOptions::new()
+///     .recv_buffer_size(1 << 20)
+///     .send_buffer_size(1 << 20)
+///     .nonblocking(true);
+///
+/// let c = Connection::open_with(&options)?;
+/// let credentials = c.peer_credentials()?;
+/// println!("Connected to pid {}", credentials.pid());
+/// # Ok::<_, protocol::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConnectionOptions {
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+    busy_poll: Option<u32>,
+    nonblocking: bool,
+}
+
+impl ConnectionOptions {
+    /// Construct the default options, which leaves the socket as it is
+    /// configured by the system.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            recv_buffer_size: None,
+            send_buffer_size: None,
+            busy_poll: None,
+            nonblocking: false,
+        }
+    }
+
+    /// Set the size of the receive buffer of the socket through `SO_RCVBUF`.
+    #[inline]
+    pub const fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the send buffer of the socket through `SO_SNDBUF`.
+    #[inline]
+    pub const fn send_buffer_size(mut self, size: usize) -> Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the number of microseconds to busy poll when receiving through
+    /// `SO_BUSY_POLL`.
+    #[inline]
+    pub const fn busy_poll(mut self, usec: u32) -> Self {
+        self.busy_poll = Some(usec);
+        self
+    }
+
+    /// Set the socket to non-blocking mode once connected.
+    #[inline]
+    pub const fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+}
+
+/// The credentials of the process on the other end of a [`Connection`], as
+/// reported by `SO_PEERCRED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerCredentials {
+    pid: i32,
+    uid: u32,
+    gid: u32,
+}
+
+impl PeerCredentials {
+    /// The process id of the peer.
+    #[inline]
+    pub fn pid(&self) -> i32 {
+        self.pid
+    }
+
+    /// The user id of the peer.
+    #[inline]
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The group id of the peer.
+    #[inline]
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+}
+
 /// A connection to a local pipewire server.
 #[derive(Debug)]
 pub struct Connection {
@@ -38,12 +142,20 @@ pub struct Connection {
     message_sequence: u32,
     interest: Interest,
     modified: ChangeInterest,
+    fds: VecDeque<ManagedFd>,
 }
 
 impl Connection {
     /// Open a connection to a local pipewire server.
-    #[tracing::instrument]
+    #[inline]
     pub fn open() -> Result<Self, Error> {
+        Self::open_with(&ConnectionOptions::new())
+    }
+
+    /// Open a connection to a local pipewire server, applying the given
+    /// options to the socket.
+    #[tracing::instrument]
+    pub fn open_with(options: &ConnectionOptions) -> Result<Self, Error> {
         let socket = 'socket: {
             let owned;
 
@@ -78,14 +190,131 @@ impl Connection {
             return Err(Error::new(ErrorKind::NoSocket));
         };
 
-        Ok(Self {
+        let mut this = Self::from_socket(socket);
+
+        if let Some(size) = options.recv_buffer_size {
+            this.set_recv_buffer_size(size)?;
+        }
+
+        if let Some(size) = options.send_buffer_size {
+            this.set_send_buffer_size(size)?;
+        }
+
+        if let Some(usec) = options.busy_poll {
+            this.set_busy_poll(usec)?;
+        }
+
+        if options.nonblocking {
+            this.set_nonblocking(true)?;
+        }
+
+        Ok(this)
+    }
+
+    /// Construct a connection over a socket which is already connected, like
+    /// one end of a socket pair.
+    pub fn from_socket(socket: UnixStream) -> Self {
+        Self {
             socket,
             message_sequence: 0,
             interest: Interest::READ | Interest::HUP | Interest::ERROR,
             modified: ChangeInterest::Unchanged,
+            fds: VecDeque::new(),
+        }
+    }
+
+    /// Set the size of the receive buffer of the socket through `SO_RCVBUF`.
+    ///
+    /// Note that the kernel doubles the requested size to leave room for
+    /// bookkeeping, and caps it to the system wide maximum.
+    pub fn set_recv_buffer_size(&mut self, size: usize) -> Result<(), Error> {
+        let size = libc::c_int::try_from(size).map_err(|_| ErrorKind::SizeOverflow)?;
+        self.set_option(libc::SO_RCVBUF, size)
+    }
+
+    /// Get the size of the receive buffer of the socket.
+    pub fn recv_buffer_size(&self) -> Result<usize, Error> {
+        Ok(self.get_option::<libc::c_int>(libc::SO_RCVBUF)? as usize)
+    }
+
+    /// Set the size of the send buffer of the socket through `SO_SNDBUF`.
+    ///
+    /// Note that the kernel doubles the requested size to leave room for
+    /// bookkeeping, and caps it to the system wide maximum.
+    pub fn set_send_buffer_size(&mut self, size: usize) -> Result<(), Error> {
+        let size = libc::c_int::try_from(size).map_err(|_| ErrorKind::SizeOverflow)?;
+        self.set_option(libc::SO_SNDBUF, size)
+    }
+
+    /// Get the size of the send buffer of the socket.
+    pub fn send_buffer_size(&self) -> Result<usize, Error> {
+        Ok(self.get_option::<libc::c_int>(libc::SO_SNDBUF)? as usize)
+    }
+
+    /// Set the number of microseconds to busy poll when receiving through
+    /// `SO_BUSY_POLL`.
+    pub fn set_busy_poll(&mut self, usec: u32) -> Result<(), Error> {
+        let usec = libc::c_int::try_from(usec).map_err(|_| ErrorKind::SizeOverflow)?;
+        self.set_option(libc::SO_BUSY_POLL, usec)
+    }
+
+    /// Get the credentials of the process on the other end of the connection.
+    pub fn peer_credentials(&self) -> Result<PeerCredentials, Error> {
+        let cred = self.get_option::<libc::ucred>(libc::SO_PEERCRED)?;
+
+        Ok(PeerCredentials {
+            pid: cred.pid,
+            uid: cred.uid,
+            gid: cred.gid,
         })
     }
 
+    fn set_option(&self, name: libc::c_int, value: libc::c_int) -> Result<(), Error> {
+        // SAFETY: We're just using c-apis as intended.
+        let result = unsafe {
+            libc::setsockopt(
+                self.socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                name,
+                (&value as *const libc::c_int).cast(),
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+
+        if result == -1 {
+            return Err(Error::new(ErrorKind::SocketOptionFailed(
+                io::Error::last_os_error(),
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn get_option<T>(&self, name: libc::c_int) -> Result<T, Error> {
+        let mut value = MaybeUninit::<T>::zeroed();
+        let mut len = mem::size_of::<T>() as libc::socklen_t;
+
+        // SAFETY: We're just using c-apis as intended, and the option is only
+        // read once the kernel has filled it in.
+        unsafe {
+            let result = libc::getsockopt(
+                self.socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                name,
+                value.as_mut_ptr().cast(),
+                &mut len,
+            );
+
+            if result == -1 {
+                return Err(Error::new(ErrorKind::SocketOptionFailed(
+                    io::Error::last_os_error(),
+                )));
+            }
+
+            Ok(value.assume_init())
+        }
+    }
+
     /// Set the connection to non-blocking mode.
     #[inline]
     pub fn set_nonblocking(&mut self, nonblocking: bool) -> Result<(), Error> {
@@ -114,8 +343,21 @@ impl Connection {
     pub fn send(&mut self, outgoing: &mut SendBuf) -> Result<(), Error> {
         // Keep track of how much we've sent to limit the amount of time we
         // spend sending.
-        let mut sent = MAX_SEND_SIZE;
+        self.send_limited(outgoing, MAX_SEND_SIZE)
+    }
+
+    /// Send data to the server until the outgoing buffer is empty or the
+    /// socket would block.
+    ///
+    /// Unlike [`Connection::send`] this does not limit the amount of data sent
+    /// in one call, which is required when the connection is registered as
+    /// edge-triggered since no further write readiness is reported until the
+    /// socket has been filled.
+    pub fn send_all(&mut self, outgoing: &mut SendBuf) -> Result<(), Error> {
+        self.send_limited(outgoing, usize::MAX)
+    }
 
+    fn send_limited(&mut self, outgoing: &mut SendBuf, mut sent: usize) -> Result<(), Error> {
         loop {
             if outgoing.is_empty() {
                 self.modified |= self.interest.unset(Interest::WRITE);
@@ -126,7 +368,13 @@ impl Connection {
             let bytes = bytes.get(..bytes.len().min(sent)).unwrap_or_default();
             let remaining_before = bytes.len();
 
-            match self.socket.write(bytes) {
+            let result = if self.fds.is_empty() {
+                self.socket.write(bytes)
+            } else {
+                self.send_with_fds(bytes)
+            };
+
+            match result {
                 Ok(0) => {
                     return Err(Error::new(ErrorKind::RemoteClosed));
                 }
@@ -162,7 +410,68 @@ impl Connection {
         }
     }
 
+    /// Write bytes to the socket together with pending file descriptors.
+    ///
+    /// The file descriptors are sent in the same order as they were queued,
+    /// and are closed on our end once they have been sent.
+    fn send_with_fds(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        const {
+            assert!(mem::align_of::<MaybeUninit<[u64; 32]>>() >= mem::align_of::<libc::cmsghdr>());
+        }
+
+        let n_fds = self.fds.len().min(MAX_SEND_FDS);
+        let fd_size = n_fds * mem::size_of::<RawFd>();
+        let size = unsafe { libc::CMSG_SPACE(fd_size as u32) as usize };
+
+        let mut buf = MaybeUninit::<[u64; 32]>::zeroed();
+        assert!(mem::size_of_val(&buf) >= size);
+
+        let mut iov = libc::iovec {
+            iov_base: bytes.as_ptr().cast_mut().cast(),
+            iov_len: bytes.len(),
+        };
+
+        unsafe {
+            let mut msghdr = mem::zeroed::<libc::msghdr>();
+            msghdr.msg_iov = &mut iov;
+            msghdr.msg_iovlen = 1;
+            msghdr.msg_control = &mut buf as *mut _ as *mut libc::c_void;
+            msghdr.msg_controllen = size;
+
+            let c = libc::CMSG_FIRSTHDR(&msghdr as *const _);
+            (*c).cmsg_level = libc::SOL_SOCKET;
+            (*c).cmsg_type = libc::SCM_RIGHTS;
+            (*c).cmsg_len = libc::CMSG_LEN(fd_size as u32) as usize;
+
+            let fd_ptr = libc::CMSG_DATA(c).cast::<RawFd>();
+
+            for (i, fd) in self.fds.iter().take(n_fds).enumerate() {
+                ptr::write_unaligned(fd_ptr.add(i), fd.as_raw_fd());
+            }
+
+            let n = libc::sendmsg(
+                self.socket.as_raw_fd(),
+                &msghdr as *const _,
+                libc::MSG_NOSIGNAL,
+            );
+
+            if n < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            tracing::trace!(n_fds, "sent file descriptors");
+
+            for mut fd in self.fds.drain(..n_fds) {
+                fd.mark_used();
+            }
+
+            Ok(n as usize)
+        }
+    }
+
     /// Receive file descriptors from the server.
+    ///
+    /// Received file descriptors have the close-on-exec flag set.
     pub fn recv_with_fds(&mut self, recv: &mut RecvBuf, fds: &mut [RawFd]) -> Result<usize, Error> {
         const {
             assert!(mem::align_of::<MaybeUninit<[u64; 64]>>() >= mem::align_of::<libc::cmsghdr>());
@@ -198,7 +507,11 @@ impl Connection {
                 msghdr.msg_control = &mut buf as *mut _ as *mut libc::c_void;
                 msghdr.msg_controllen = size;
 
-                let n = libc::recvmsg(self.socket.as_raw_fd(), &mut msghdr as *mut _, 0);
+                let n = libc::recvmsg(
+                    self.socket.as_raw_fd(),
+                    &mut msghdr as *mut _,
+                    libc::MSG_CMSG_CLOEXEC,
+                );
 
                 if n < 0 {
                     match io::Error::last_os_error() {
@@ -281,8 +594,53 @@ impl Connection {
         pod: Pod<impl AsSlice>,
     ) -> Result<(), Error> {
         tracing::trace!("Request");
+        self.write_request(outgoing, id, op.into_raw(), pod.as_ref(), 0)
+    }
 
-        let pod = pod.as_ref();
+    /// Send an outgoing request which passes file descriptors.
+    ///
+    /// The file descriptors are referenced in the pod through
+    /// [`pod::Fd`] values holding their index in `fds`. Ownership of the file
+    /// descriptors is held by the connection until they have been sent.
+    #[tracing::instrument(skip(self, pod, fds), fields(remaining = outgoing.len(), n_fds = fds.len()), ret(level = Level::TRACE))]
+    pub fn request_with_fds(
+        &mut self,
+        outgoing: &mut SendBuf,
+        id: u32,
+        op: impl IntoRaw<u8> + fmt::Display + fmt::Debug,
+        pod: Pod<impl AsSlice>,
+        fds: Vec<OwnedFd>,
+    ) -> Result<(), Error> {
+        let Ok(n_fds) = u32::try_from(fds.len()) else {
+            return Err(Error::new(ErrorKind::SizeOverflow));
+        };
+
+        let seq = self.message_sequence;
+
+        // NB: Every file descriptor is wrapped before the request is written,
+        // since a queued header which counts file descriptors that are never
+        // sent would misalign every file descriptor sent after it.
+        let mut managed = Vec::with_capacity(fds.len());
+
+        for (index, fd) in (0..).zip(fds) {
+            let fd = ManagedFd::new(fd, FdOrigin::new(seq, index))
+                .map_err(|e| Error::new(ErrorKind::ManageFdFailed(e)))?;
+            managed.push(fd);
+        }
+
+        self.write_request(outgoing, id, op.into_raw(), pod.as_ref(), n_fds)?;
+        self.fds.extend(managed);
+        Ok(())
+    }
+
+    fn write_request(
+        &mut self,
+        outgoing: &mut SendBuf,
+        id: u32,
+        op: u8,
+        pod: Pod<Slice<'_>>,
+        n_fds: u32,
+    ) -> Result<(), Error> {
         let buf = pod.as_buf();
 
         let Ok(size) = u32::try_from(buf.len()) else {
@@ -292,7 +650,7 @@ impl Connection {
         let message_sequence = self.message_sequence;
         self.message_sequence = self.message_sequence.wrapping_add(1);
 
-        let Some(header) = Header::new(id, op.into_raw(), size, message_sequence, 0) else {
+        let Some(header) = Header::new(id, op, size, message_sequence, n_fds) else {
             return Err(Error::new(ErrorKind::HeaderSizeOverflow { size }));
         };
 
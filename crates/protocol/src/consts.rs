@@ -21,12 +21,21 @@ pub const INTERFACE_CLIENT: &str = "PipeWire:Interface:Client";
 /// The type of interface node.
 pub const INTERFACE_NODE: &str = "PipeWire:Interface:Node";
 
+/// The type of interface device.
+pub const INTERFACE_DEVICE: &str = "PipeWire:Interface:Device";
+
 /// The type of interface port.
 pub const INTERFACE_PORT: &str = "PipeWire:Interface:Port";
 
 /// The type of interface link.
 pub const INTERFACE_LINK: &str = "PipeWire:Interface:Link";
 
+/// The type of interface security context.
+pub const INTERFACE_SECURITY_CONTEXT: &str = "PipeWire:Interface:SecurityContext";
+
+/// The type of interface metadata.
+pub const INTERFACE_METADATA: &str = "PipeWire:Interface:Metadata";
+
 pod::macros::consts! {
     /// The direction of a port.
     #[example = OUTPUT]
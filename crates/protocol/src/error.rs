@@ -33,6 +33,12 @@ impl error::Error for Error {
             ErrorKind::SendFailed(e) => Some(e),
             #[cfg(feature = "std")]
             ErrorKind::ReceiveFailed(e) => Some(e),
+            #[cfg(feature = "std")]
+            ErrorKind::ManageFdFailed(e) => Some(e),
+            #[cfg(feature = "std")]
+            ErrorKind::SocketOptionFailed(e) => Some(e),
+            #[cfg(feature = "tokio")]
+            ErrorKind::RegisterFailed(e) => Some(e),
             _ => None,
         }
     }
@@ -71,6 +77,12 @@ pub(crate) enum ErrorKind {
     SendFailed(io::Error),
     #[cfg(feature = "std")]
     ReceiveFailed(io::Error),
+    #[cfg(feature = "std")]
+    ManageFdFailed(io::Error),
+    #[cfg(feature = "std")]
+    SocketOptionFailed(io::Error),
+    #[cfg(feature = "tokio")]
+    RegisterFailed(io::Error),
     RemoteClosed,
     NoSocket,
     SizeOverflow,
@@ -103,6 +115,14 @@ impl fmt::Display for Error {
             ErrorKind::SendFailed(..) => write!(f, "Send failed"),
             #[cfg(feature = "std")]
             ErrorKind::ReceiveFailed(..) => write!(f, "Receive failed"),
+            #[cfg(feature = "std")]
+            ErrorKind::ManageFdFailed(..) => write!(f, "Managing file descriptor failed"),
+            #[cfg(feature = "std")]
+            ErrorKind::SocketOptionFailed(..) => write!(f, "Socket option failed"),
+            #[cfg(feature = "tokio")]
+            ErrorKind::RegisterFailed(..) => {
+                write!(f, "Registering the socket with the runtime failed")
+            }
             ErrorKind::RemoteClosed => write!(f, "Remote server closed the connection"),
             ErrorKind::NoSocket => write!(f, "No socket to connect to found"),
             ErrorKind::SizeOverflow => write!(f, "Size overflow"),
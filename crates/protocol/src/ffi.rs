@@ -45,6 +45,16 @@ pod::macros::flags! {
         NO_POSITION = 1 << 1;
     }
 
+    /// Describes `SPA_IO_RATE_MATCH_FLAG_*`
+    #[examples = [ACTIVE]]
+    #[not_set = []]
+    #[module = protocol::ffi]
+    pub struct IoRateMatchFlags(u32) {
+        NONE;
+        /// Rate matching is active.
+        ACTIVE = 1 << 0;
+    }
+
     /// Describes `SPA_IO_SEGMENT_VIDEO_FLAG_*`
     #[examples = [VALID]]
     #[not_set = [DROP_FRAME]]
@@ -311,6 +321,11 @@ fn test_layout() {
 
     assert_eq!(mem::size_of::<NodeActivation>(), 2312);
     assert_eq!(mem::offset_of!(NodeActivation, client_version), 540);
+    assert_eq!(mem::size_of::<IoRateMatch>(), 48);
+    assert_eq!(mem::size_of::<IoAsyncBuffers>(), 16);
+    assert_eq!(mem::size_of::<IoSegmentBar>(), 64);
+    assert_eq!(mem::size_of::<IoSegmentVideo>(), 80);
+    assert_eq!(mem::size_of::<IoSegment>(), 184);
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -428,6 +443,40 @@ pub struct IoBuffers {
     pub buffer_id: i32,
 }
 
+/// Rate matching information shared between a driver and a follower which
+/// needs to resample.
+///
+/// This is the equivalent of `struct spa_io_rate_match`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct IoRateMatch {
+    /// extra delay in samples for resampler
+    pub delay: u32,
+    /// requested input size for resampler
+    pub size: u32,
+    /// rate for resampler (set by node)
+    pub rate: f64,
+    /// extra flags (set by node)
+    pub flags: IoRateMatchFlags,
+    /// resampler delay fractional part
+    pub delay_frac: i32,
+    _pad: Pad<[u32; 6]>,
+}
+
+/// Async area to exchange buffers.
+///
+/// Nodes which are scheduled asynchronously read from the buffers of the
+/// previous cycle and write to the buffers of the next one, selected by the
+/// cycle number of the driver.
+///
+/// This is the equivalent of `struct spa_io_async_buffers`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IoAsyncBuffers {
+    /// the buffers for the current and the next cycle.
+    pub buffers: [IoBuffers; 2],
+}
+
 /// Describes essential buffer header metadata such as flags and timestamps.
 ///
 /// This is the equivalent of `struct spa_meta_header`.
@@ -480,4 +529,28 @@ fn test_sizes() {
         mem::align_of::<IoClock>(),
         mem::align_of::<libspa_sys::spa_io_clock>()
     );
+    assert_eq!(
+        mem::size_of::<IoRateMatch>(),
+        mem::size_of::<libspa_sys::spa_io_rate_match>()
+    );
+    assert_eq!(
+        mem::offset_of!(IoRateMatch, flags),
+        mem::offset_of!(libspa_sys::spa_io_rate_match, flags)
+    );
+    assert_eq!(
+        mem::size_of::<IoSegment>(),
+        mem::size_of::<libspa_sys::spa_io_segment>()
+    );
+    assert_eq!(
+        mem::offset_of!(IoSegment, video),
+        mem::offset_of!(libspa_sys::spa_io_segment, video)
+    );
+    assert_eq!(
+        mem::size_of::<IoSegmentBar>(),
+        mem::size_of::<libspa_sys::spa_io_segment_bar>()
+    );
+    assert_eq!(
+        mem::size_of::<IoSegmentVideo>(),
+        mem::size_of::<libspa_sys::spa_io_segment_video>()
+    );
 }
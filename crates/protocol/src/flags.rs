@@ -63,6 +63,30 @@ pod::macros::flags! {
         RT_TRIGGER_DONE = 1 << 12;
     }
 
+    /// Permissions a client has on a global object.
+    #[examples = [R, X]]
+    #[not_set = [W]]
+    #[module = protocol::flags]
+    pub struct Permission(u32) {
+        NONE;
+        /// Object can be seen and events can be received.
+        #[constant = pipewire_sys::PW_PERM_R]
+        R = 0o400;
+        /// Methods can be called that modify the object.
+        #[constant = pipewire_sys::PW_PERM_W]
+        W = 0o200;
+        /// Methods can be called on the object. The W flag must be present in
+        /// order to call methods that modify the object.
+        #[constant = pipewire_sys::PW_PERM_X]
+        X = 0o100;
+        /// Metadata can be set on object.
+        #[constant = pipewire_sys::PW_PERM_M]
+        M = 0o010;
+        /// A link can be made between a node that doesn't have permission to
+        /// see the other node.
+        L = 0o020;
+    }
+
     #[examples = [PARAMS]]
     #[not_set = [INFO]]
     #[module = protocol::flags]
@@ -369,6 +393,43 @@ pod::macros::flags! {
         PROPS = 1 << 0;
     }
 
+    /// Describes `PW_NODE_CHANGE_MASK_*`.
+    #[examples = [STATE, PARAMS]]
+    #[not_set = [PROPS]]
+    #[module = protocol::flags]
+    pub struct NodeInfoChangeFlags(u64) {
+        NONE;
+        /// The number of input ports changed.
+        #[constant = pipewire_sys::PW_NODE_CHANGE_MASK_INPUT_PORTS]
+        INPUT_PORTS = 1 << 0;
+        /// The number of output ports changed.
+        #[constant = pipewire_sys::PW_NODE_CHANGE_MASK_OUTPUT_PORTS]
+        OUTPUT_PORTS = 1 << 1;
+        /// The state of the node changed.
+        #[constant = pipewire_sys::PW_NODE_CHANGE_MASK_STATE]
+        STATE = 1 << 2;
+        /// The properties of the node changed.
+        #[constant = pipewire_sys::PW_NODE_CHANGE_MASK_PROPS]
+        PROPS = 1 << 3;
+        /// The parameters of the node changed.
+        #[constant = pipewire_sys::PW_NODE_CHANGE_MASK_PARAMS]
+        PARAMS = 1 << 4;
+    }
+
+    /// Describes `PW_DEVICE_CHANGE_MASK_*`.
+    #[examples = [PARAMS]]
+    #[not_set = [PROPS]]
+    #[module = protocol::flags]
+    pub struct DeviceInfoChangeFlags(u64) {
+        NONE;
+        /// The properties of the device changed.
+        #[constant = pipewire_sys::PW_DEVICE_CHANGE_MASK_PROPS]
+        PROPS = 1 << 0;
+        /// The parameters of the device changed.
+        #[constant = pipewire_sys::PW_DEVICE_CHANGE_MASK_PARAMS]
+        PARAMS = 1 << 1;
+    }
+
     /// Describes `PW_NODE_ACTIVATION_FLAG_*`.
     #[examples = [PROFILER]]
     #[not_set = [ASYNC]]
@@ -302,6 +302,64 @@ pod::macros::id! {
         PARAMS = 0x80001,
     }
 
+    /// Properties for SPA_TYPE_OBJECT_PropInfo.
+    ///
+    /// Equivalent to `enum spa_prop_info`.
+    #[example = ID]
+    #[module = protocol::id]
+    pub struct PropInfo {
+        UNKNOWN,
+        /// Associated id of the property (Id enum spa_prop).
+        #[constant = libspa_sys::SPA_PROP_INFO_id]
+        ID = 1,
+        /// Name of the property (String).
+        #[constant = libspa_sys::SPA_PROP_INFO_name]
+        NAME = 2,
+        /// Type and range/enums of property (Choice).
+        #[constant = libspa_sys::SPA_PROP_INFO_type]
+        TYPE = 3,
+        /// Labels of property if any, this is a struct with pairs of values,
+        /// the first one is of the type of the property, the second one is a
+        /// string with a user readable label for the value.
+        #[constant = libspa_sys::SPA_PROP_INFO_labels]
+        LABELS = 4,
+        /// Type of container if any (Id).
+        #[constant = libspa_sys::SPA_PROP_INFO_container]
+        CONTAINER = 5,
+        /// Is part of params property (Bool).
+        #[constant = libspa_sys::SPA_PROP_INFO_params]
+        PARAMS = 6,
+        /// User readable description (String).
+        #[constant = libspa_sys::SPA_PROP_INFO_description]
+        DESCRIPTION = 7,
+    }
+
+    /// Bluetooth audio codecs.
+    ///
+    /// Equivalent to `enum spa_bluetooth_audio_codec`.
+    #[example = AAC]
+    #[module = protocol::id]
+    pub struct BluetoothAudioCodec {
+        UNKNOWN,
+        SBC = 1,
+        SBC_XQ = 2,
+        MPEG = 3,
+        AAC = 4,
+        AAC_ELD = 5,
+        APTX = 6,
+        APTX_HD = 7,
+        LDAC = 8,
+        APTX_LL = 9,
+        APTX_LL_DUPLEX = 10,
+        FASTSTREAM = 11,
+        FASTSTREAM_DUPLEX = 12,
+        LC3PLUS_HR = 13,
+        OPUS_05 = 14,
+        CVSD = 0x100,
+        MSBC = 0x101,
+        LC3 = 0x200,
+    }
+
     /// Different IO area types.
     ///
     /// Represents `enum spa_io_type`.
@@ -698,6 +756,54 @@ pod::macros::id! {
         SIZE = 2,
     }
 
+    /// properties for SPA_TYPE_OBJECT_ParamRoute.
+    ///
+    /// Equivalent to `enum spa_param_route`.
+    #[example = INDEX]
+    #[module = protocol::id]
+    pub struct ParamRoute {
+        UNKNOWN,
+        /// Index of the routing destination (Int).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_index]
+        INDEX = 1,
+        /// Direction, input/output (Id enum spa_direction).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_direction]
+        DIRECTION = 2,
+        /// Device id (Int).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_device]
+        DEVICE = 3,
+        /// Name of the routing destination (String).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_name]
+        NAME = 4,
+        /// Description of the destination (String).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_description]
+        DESCRIPTION = 5,
+        /// Priority of the destination (Int).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_priority]
+        PRIORITY = 6,
+        /// Availability of the destination (Id enum spa_param_availability).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_available]
+        AVAILABLE = 7,
+        /// Info (Struct(Int : n_items, (String : key, String : value)*)).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_info]
+        INFO = 8,
+        /// Associated profile indexes (Array of Int).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_profiles]
+        PROFILES = 9,
+        /// Properties (Object SPA_TYPE_OBJECT_Props).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_props]
+        PROPS = 10,
+        /// Associated device indexes (Array of Int).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_devices]
+        DEVICES = 11,
+        /// Profile id (Int).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_profile]
+        PROFILE = 12,
+        /// If route should be saved (Bool).
+        #[constant = libspa_sys::SPA_PARAM_ROUTE_save]
+        SAVE = 13,
+    }
+
     /// properties for SPA_TYPE_OBJECT_ParamIO
     ///
     /// This corresponds to `enum spa_param_io`.
@@ -712,6 +818,36 @@ pod::macros::id! {
         #[constant = libspa_sys::SPA_PARAM_IO_size]
         SIZE = 2,
     }
+
+    /// properties for SPA_TYPE_OBJECT_ParamLatency
+    ///
+    /// This corresponds to `enum spa_param_latency`.
+    #[example = MIN_QUANTUM]
+    #[module = protocol::id]
+    pub struct ParamLatency {
+        UNKNOWN,
+        /// Direction, input/output (Id enum spa_direction).
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_direction]
+        DIRECTION = 1,
+        /// Min latency relative to quantum (Float).
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_minQuantum]
+        MIN_QUANTUM = 2,
+        /// Max latency relative to quantum (Float).
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_maxQuantum]
+        MAX_QUANTUM = 3,
+        /// Min latency (Int) relative to rate.
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_minRate]
+        MIN_RATE = 4,
+        /// Max latency (Int) relative to rate.
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_maxRate]
+        MAX_RATE = 5,
+        /// Min latency (Long) in nanoseconds.
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_minNs]
+        MIN_NS = 6,
+        /// Max latency (Long) in nanoseconds.
+        #[constant = libspa_sys::SPA_PARAM_LATENCY_maxNs]
+        MAX_NS = 7,
+    }
 }
 
 impl AudioFormat {
@@ -747,4 +883,20 @@ impl AudioFormat {
     pub const DSP_S32: Self = Self::S24_32P;
     pub const DSP_F32: Self = Self::F32P;
     pub const DSP_F64: Self = Self::F64P;
+
+    /// Test if the format is planar, where each channel is stored in a
+    /// separate data block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protocol::id;
+    ///
+    /// assert!(id::AudioFormat::F32P.is_planar());
+    /// assert!(!id::AudioFormat::F32.is_planar());
+    /// ```
+    #[inline]
+    pub fn is_planar(&self) -> bool {
+        (0x200..0x400).contains(&self.into_id())
+    }
 }
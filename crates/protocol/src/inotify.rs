@@ -0,0 +1,271 @@
+//! Watching files and directories for changes through inotify.
+
+use core::fmt;
+use core::mem;
+use core::ops::BitOr;
+use core::ptr;
+use std::ffi::{CString, OsStr};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::path::Path;
+
+/// Inotify file descriptor, used to watch files and directories for changes
+/// through the poll loop.
+///
+/// Editors and tools tend to generate bursts of events when saving a file, so
+/// changes should be debounced before acting on them. This is conveniently done
+/// by re-arming a [`TimerFd`] with [`TimerFd::set_timeout`] for every batch of
+/// events received, and only reloading once the timer expires.
+///
+/// [`TimerFd`]: crate::TimerFd
+/// [`TimerFd::set_timeout`]: crate::TimerFd::set_timeout
+///
+/// # Examples
+///
+/// ```no_run
+/// use protocol::inotify::{InotifyFd, WatchMask};
+///
+/// let inotify = InotifyFd::new()?;
+/// inotify.set_nonblocking(true)?;
+/// inotify.add_watch("presets", WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)?;
+///
+/// let mut buf = [0u8; 4096];
+///
+/// while let Some(events) = inotify.read(&mut buf)? {
+///     for event in events {
+///         println!("{:?} changed", event.name());
+///     }
+/// }
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub struct InotifyFd {
+    fd: OwnedFd,
+}
+
+impl InotifyFd {
+    /// Construct a new inotify fd.
+    pub fn new() -> io::Result<Self> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let fd = libc::inotify_init1(libc::IN_CLOEXEC);
+
+            if fd == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self {
+                fd: OwnedFd::from_raw_fd(fd),
+            })
+        }
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let mut flags = libc::fcntl(self.fd.as_raw_fd(), libc::F_GETFL);
+
+            if flags == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if nonblocking {
+                flags |= libc::O_NONBLOCK;
+            } else {
+                flags &= !libc::O_NONBLOCK;
+            }
+
+            if libc::fcntl(self.fd.as_raw_fd(), libc::F_SETFL, flags) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Watch the given path for the specified events.
+    ///
+    /// If the path is a directory, events are reported for the files inside of
+    /// it.
+    pub fn add_watch(&self, path: impl AsRef<Path>, mask: WatchMask) -> io::Result<WatchId> {
+        let path = CString::new(path.as_ref().as_os_str().as_bytes())?;
+
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let wd = libc::inotify_add_watch(self.fd.as_raw_fd(), path.as_ptr(), mask.0);
+
+            if wd == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(WatchId(wd))
+        }
+    }
+
+    /// Stop watching the path associated with the given watch.
+    pub fn remove_watch(&self, id: WatchId) -> io::Result<()> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            if libc::inotify_rm_watch(self.fd.as_raw_fd(), id.0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Read a batch of events into the given buffer.
+    ///
+    /// The buffer should be large enough to hold at least one event with a
+    /// file name, at which point 4096 bytes is a reasonable size.
+    ///
+    /// Returns `None` if the operation would block.
+    pub fn read<'buf>(&self, buf: &'buf mut [u8]) -> io::Result<Option<InotifyEvents<'buf>>> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let n = libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len());
+
+            if n == -1 {
+                match io::Error::last_os_error() {
+                    e if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                    e => return Err(e),
+                }
+            }
+
+            Ok(Some(InotifyEvents {
+                buf: buf.get(..n as usize).unwrap_or_default(),
+            }))
+        }
+    }
+}
+
+impl AsRawFd for InotifyFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+/// The identifier of a watch added through [`InotifyFd::add_watch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct WatchId(i32);
+
+/// The events to watch for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct WatchMask(u32);
+
+impl WatchMask {
+    /// A file was created in a watched directory.
+    pub const CREATE: Self = Self(libc::IN_CREATE);
+    /// A file was modified.
+    pub const MODIFY: Self = Self(libc::IN_MODIFY);
+    /// A file opened for writing was closed.
+    pub const CLOSE_WRITE: Self = Self(libc::IN_CLOSE_WRITE);
+    /// A file was deleted from a watched directory.
+    pub const DELETE: Self = Self(libc::IN_DELETE);
+    /// A file was moved out of a watched directory.
+    pub const MOVED_FROM: Self = Self(libc::IN_MOVED_FROM);
+    /// A file was moved into a watched directory.
+    pub const MOVED_TO: Self = Self(libc::IN_MOVED_TO);
+
+    /// Test if the mask contains the given events.
+    #[inline]
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for WatchMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Debug for WatchMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(WatchMask, &str); 6] = [
+            (WatchMask::CREATE, "CREATE"),
+            (WatchMask::MODIFY, "MODIFY"),
+            (WatchMask::CLOSE_WRITE, "CLOSE_WRITE"),
+            (WatchMask::DELETE, "DELETE"),
+            (WatchMask::MOVED_FROM, "MOVED_FROM"),
+            (WatchMask::MOVED_TO, "MOVED_TO"),
+        ];
+
+        let mut f = f.debug_set();
+
+        for (mask, name) in NAMES {
+            if self.contains(mask) {
+                f.entry(&format_args!("{name}"));
+            }
+        }
+
+        f.finish()
+    }
+}
+
+/// An iterator over events read through [`InotifyFd::read`].
+pub struct InotifyEvents<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> Iterator for InotifyEvents<'buf> {
+    type Item = InotifyEvent<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        const HEADER: usize = mem::size_of::<libc::inotify_event>();
+
+        let header = self.buf.get(..HEADER)?;
+
+        // SAFETY: The header is fully initialized by the kernel, but might not
+        // be aligned in the buffer.
+        let event = unsafe { ptr::read_unaligned(header.as_ptr().cast::<libc::inotify_event>()) };
+
+        let len = event.len as usize;
+        let name = self.buf.get(HEADER..HEADER + len)?;
+        self.buf = self.buf.get(HEADER + len..).unwrap_or_default();
+
+        // NB: The name is padded with NUL bytes.
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+
+        Some(InotifyEvent {
+            wd: WatchId(event.wd),
+            mask: WatchMask(event.mask),
+            name: OsStr::from_bytes(&name[..end]),
+        })
+    }
+}
+
+/// A single event read through [`InotifyFd::read`].
+#[derive(Debug, Clone, Copy)]
+pub struct InotifyEvent<'buf> {
+    wd: WatchId,
+    mask: WatchMask,
+    name: &'buf OsStr,
+}
+
+impl<'buf> InotifyEvent<'buf> {
+    /// The watch the event is associated with.
+    #[inline]
+    pub fn watch(&self) -> WatchId {
+        self.wd
+    }
+
+    /// The events which occurred.
+    #[inline]
+    pub fn mask(&self) -> WatchMask {
+        self.mask
+    }
+
+    /// The name of the file inside of a watched directory, or an empty name if
+    /// the event is for the watched path itself.
+    #[inline]
+    pub fn name(&self) -> &'buf OsStr {
+        self.name
+    }
+}
@@ -15,7 +15,17 @@ use self::error::ErrorKind;
 #[cfg(feature = "std")]
 mod connection;
 #[cfg(feature = "std")]
-pub use self::connection::Connection;
+pub use self::connection::{Connection, ConnectionOptions, PeerCredentials};
+
+#[cfg(feature = "tokio")]
+mod async_connection;
+#[cfg(feature = "tokio")]
+pub use self::async_connection::AsyncConnection;
+
+#[cfg(feature = "std")]
+mod managed_fd;
+#[cfg(feature = "std")]
+pub use self::managed_fd::{FdOrigin, ManagedFd};
 
 pub mod types;
 
@@ -30,7 +40,14 @@ pub use self::event_fd::EventFd;
 mod timer_fd;
 pub use self::timer_fd::TimerFd;
 
+mod signal_fd;
+pub use self::signal_fd::SignalFd;
+
+pub mod inotify;
+pub use self::inotify::InotifyFd;
+
 pub mod consts;
+pub mod message;
 pub mod op;
 
 #[cfg(feature = "alloc")]
@@ -47,7 +64,14 @@ pub mod buf;
 pub mod prop;
 pub use self::prop::Prop;
 
+pub mod type_info;
+
 mod properties;
 pub use self::properties::Properties;
 
+#[cfg(feature = "alloc")]
+pub mod symbol;
+#[cfg(feature = "alloc")]
+pub use self::symbol::Symbol;
+
 pub mod ffi;
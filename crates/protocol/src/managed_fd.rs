@@ -0,0 +1,150 @@
+use core::fmt;
+
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
+
+/// Where a [`ManagedFd`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdOrigin {
+    seq: u32,
+    index: u32,
+}
+
+impl FdOrigin {
+    /// Construct a new origin from the sequence number of a message and the
+    /// index of the file descriptor in it.
+    #[inline]
+    pub const fn new(seq: u32, index: u32) -> Self {
+        Self { seq, index }
+    }
+
+    /// The sequence number of the message the file descriptor was passed
+    /// with.
+    #[inline]
+    pub const fn seq(&self) -> u32 {
+        self.seq
+    }
+
+    /// The index of the file descriptor in the message.
+    #[inline]
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+impl fmt::Display for FdOrigin {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message {} fd #{}", self.seq, self.index)
+    }
+}
+
+/// A file descriptor which is passed over a connection.
+///
+/// The file descriptor is always close-on-exec, and remembers which message it
+/// was passed with. If it is dropped without being used, a warning is logged
+/// with its origin.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::fd::OwnedFd;
+///
+/// use protocol::{FdOrigin, ManagedFd};
+///
+/// let fd = OwnedFd::from(std::fs::File::open("/dev/null")?);
+/// let mut fd = ManagedFd::new(fd, FdOrigin::new(0, 1))?;
+/// assert_eq!(fd.origin().index(), 1);
+///
+/// // Hand out an independent copy, the original is still owned.
+/// let copy = fd.try_clone_owned()?;
+/// let fd = fd.into_owned();
+/// # Ok::<_, std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ManagedFd {
+    fd: Option<OwnedFd>,
+    origin: FdOrigin,
+    used: bool,
+}
+
+impl ManagedFd {
+    /// Take ownership of a file descriptor, setting the close-on-exec flag if
+    /// it is not set.
+    pub fn new(fd: OwnedFd, origin: FdOrigin) -> io::Result<Self> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let flags = libc::fcntl(fd.as_raw_fd(), libc::F_GETFD);
+
+            if flags == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if flags & libc::FD_CLOEXEC == 0
+                && libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags | libc::FD_CLOEXEC) == -1
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(Self {
+            fd: Some(fd),
+            origin,
+            used: false,
+        })
+    }
+
+    /// The origin of the file descriptor.
+    #[inline]
+    pub fn origin(&self) -> FdOrigin {
+        self.origin
+    }
+
+    /// Mark the file descriptor as used, so that dropping it doesn't log a
+    /// warning.
+    #[inline]
+    pub fn mark_used(&mut self) {
+        self.used = true;
+    }
+
+    /// Duplicate the file descriptor, handing out a close-on-exec copy which
+    /// is independent of this one.
+    ///
+    /// This marks the file descriptor as used.
+    pub fn try_clone_owned(&mut self) -> io::Result<OwnedFd> {
+        let fd = self.fd.as_ref().expect("file descriptor taken");
+        let fd = fd.try_clone()?;
+        self.used = true;
+        Ok(fd)
+    }
+
+    /// Take the underlying file descriptor.
+    #[inline]
+    pub fn into_owned(mut self) -> OwnedFd {
+        self.fd.take().expect("file descriptor taken")
+    }
+}
+
+impl AsFd for ManagedFd {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_ref().expect("file descriptor taken").as_fd()
+    }
+}
+
+impl AsRawFd for ManagedFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+impl Drop for ManagedFd {
+    fn drop(&mut self) {
+        if let Some(fd) = &self.fd
+            && !self.used
+        {
+            tracing::warn!(?fd, origin = %self.origin, "Unused file descriptor dropped");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Typed bodies of protocol messages.
+//!
+//! Every message in here can both be written and read, so that both sides of
+//! a connection can be interpreted. Client methods can be decoded like a server
+//! would, and server events can be encoded like a server would.
+//!
+//! Messages are sent as a struct and the [`Message`] trait associates each body
+//! with its opcode.
+//!
+//! # Examples
+//!
+//! ```
+//! use protocol::message::{CoreDone, CoreSync, Message};
+//! use protocol::op;
+//!
+//! let mut pod = pod::array();
+//! pod.as_mut().write(CoreSync { id: 0, seq: 42 })?;
+//!
+//! let sync = pod.as_ref().read::<CoreSync>()?;
+//! assert_eq!(CoreSync::OP, op::Core::SYNC);
+//! assert_eq!(sync, CoreSync { id: 0, seq: 42 });
+//!
+//! let mut pod = pod::array();
+//! pod.as_mut().write(CoreDone { id: sync.id, seq: sync.seq })?;
+//! assert_eq!(pod.as_ref().read::<CoreDone>()?, CoreDone { id: 0, seq: 42 });
+//! # Ok::<_, pod::Error>(())
+//! ```
+
+use pod::{Fd, IntoRaw, Readable, Writable};
+
+use crate::{flags, id, op};
+
+/// A message body associated with an opcode.
+pub trait Message {
+    /// The type of the opcode.
+    type Op: IntoRaw<u8>;
+
+    /// The opcode of the message.
+    const OP: Self::Op;
+}
+
+macro_rules! message {
+    ($($ty:ident $(<$lt:lifetime>)? => $op_ty:ident::$op:ident),* $(,)?) => {
+        $(
+            impl $(<$lt>)* Message for $ty $(<$lt>)* {
+                type Op = op::$op_ty;
+                const OP: Self::Op = op::$op_ty::$op;
+            }
+        )*
+    };
+}
+
+/// The first message sent by a client, see [`op::Core::HELLO`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreHello {
+    /// The version of the protocol spoken by the client.
+    pub version: u32,
+}
+
+/// Request a [`CoreDone`] event, see [`op::Core::SYNC`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreSync {
+    /// The id which is echoed back in the done event.
+    pub id: i32,
+    /// The sequence number which is echoed back in the done event.
+    pub seq: u32,
+}
+
+/// The response to a [`CorePing`] event, see [`op::Core::PONG`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CorePong {
+    /// The id copied from the ping event.
+    pub id: u32,
+    /// The sequence number copied from the ping event.
+    pub seq: u32,
+}
+
+/// Bind the registry to a new proxy, see [`op::Core::GET_REGISTRY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreGetRegistry {
+    /// The version of the registry interface.
+    pub version: i32,
+    /// The local id of the new registry proxy.
+    pub new_id: u32,
+}
+
+/// Destroy a resource, see [`op::Core::DESTROY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreDestroy {
+    /// The local id of the resource to destroy.
+    pub id: u32,
+}
+
+message! {
+    CoreHello => Core::HELLO,
+    CoreSync => Core::SYNC,
+    CorePong => Core::PONG,
+    CoreGetRegistry => Core::GET_REGISTRY,
+    CoreDestroy => Core::DESTROY,
+}
+
+/// Emitted in response to a [`CoreSync`], see [`op::CoreEvent::DONE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreDone {
+    /// The id of the sync method.
+    pub id: i32,
+    /// The sequence number of the sync method.
+    pub seq: u32,
+}
+
+/// A ping which should be responded to with a [`CorePong`], see
+/// [`op::CoreEvent::PING`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CorePing {
+    /// The id to copy into the pong.
+    pub id: u32,
+    /// The sequence number to copy into the pong.
+    pub seq: u32,
+}
+
+/// A fatal error, see [`op::CoreEvent::ERROR`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreError<'de> {
+    /// The id of the proxy where the error occurred.
+    pub id: i32,
+    /// The sequence number of the request which caused the error.
+    pub seq: i32,
+    /// A negative errno describing the error.
+    pub res: i32,
+    /// A description of the error.
+    pub message: &'de str,
+}
+
+/// Acknowledge the removal of a local id, see
+/// [`op::CoreEvent::REMOVE_ID_EVENT`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreRemoveId {
+    /// The local id which can be reused.
+    pub id: u32,
+}
+
+/// A local id has been bound to a global, see [`op::CoreEvent::BOUND_ID`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreBoundId {
+    /// The local id of the proxy.
+    pub id: u32,
+    /// The global id the proxy was bound to.
+    pub global_id: u32,
+}
+
+/// Memory has been added, see [`op::CoreEvent::ADD_MEM`].
+///
+/// The file descriptor is referenced through its index among the file
+/// descriptors passed with the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreAddMem {
+    /// The unique identifier of the memory.
+    pub id: u32,
+    /// The type of the memory.
+    pub ty: id::DataType,
+    /// The index of the file descriptor of the memory.
+    pub fd: Fd,
+    /// Flags of the memory block.
+    pub flags: flags::MemBlock,
+}
+
+/// Memory has been removed, see [`op::CoreEvent::REMOVE_MEM`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct CoreRemoveMem {
+    /// The unique identifier of the memory.
+    pub id: u32,
+}
+
+message! {
+    CoreDone => CoreEvent::DONE,
+    CorePing => CoreEvent::PING,
+    CoreError<'de> => CoreEvent::ERROR,
+    CoreRemoveId => CoreEvent::REMOVE_ID_EVENT,
+    CoreBoundId => CoreEvent::BOUND_ID,
+    CoreAddMem => CoreEvent::ADD_MEM,
+    CoreRemoveMem => CoreEvent::REMOVE_MEM,
+}
+
+/// Bind to a global object, see [`op::Registry::BIND`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct RegistryBind<'de> {
+    /// The global id to bind to.
+    pub id: u32,
+    /// The type of the interface to bind.
+    pub ty: &'de str,
+    /// The version of the interface to bind.
+    pub version: u32,
+    /// The local id of the new proxy.
+    pub new_id: u32,
+}
+
+/// Destroy a global object, see [`op::Registry::DESTROY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct RegistryDestroy {
+    /// The global id to destroy.
+    pub id: u32,
+}
+
+/// A global object has been removed, see [`op::RegistryEvent::GLOBAL_REMOVE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct RegistryGlobalRemove {
+    /// The global id which was removed.
+    pub id: u32,
+}
+
+message! {
+    RegistryBind<'de> => Registry::BIND,
+    RegistryDestroy => Registry::DESTROY,
+    RegistryGlobalRemove => RegistryEvent::GLOBAL_REMOVE,
+}
+
+/// Set a property on a subject of a metadata object, or remove it if the value
+/// is `None`, see [`op::Metadata::SET_PROPERTY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct MetadataSetProperty<'de> {
+    /// The global id of the subject of the property.
+    pub subject: u32,
+    /// The key of the property.
+    pub key: &'de str,
+    /// The type of the value, like `Spa:String:JSON`.
+    pub ty: Option<&'de str>,
+    /// The value of the property.
+    pub value: Option<&'de str>,
+}
+
+/// Clear all properties of a metadata object, see [`op::Metadata::CLEAR`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct MetadataClear {}
+
+/// A property of a subject of a metadata object has been set or removed, see
+/// [`op::MetadataEvent::PROPERTY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Readable, Writable)]
+pub struct MetadataProperty<'de> {
+    /// The global id of the subject of the property.
+    pub subject: u32,
+    /// The key of the property, or `None` if all properties of the subject
+    /// have been removed.
+    pub key: Option<&'de str>,
+    /// The type of the value.
+    pub ty: Option<&'de str>,
+    /// The value of the property, or `None` if it has been removed.
+    pub value: Option<&'de str>,
+}
+
+message! {
+    MetadataSetProperty<'de> => Metadata::SET_PROPERTY,
+    MetadataClear => Metadata::CLEAR,
+    MetadataProperty<'de> => MetadataEvent::PROPERTY,
+}
@@ -20,6 +20,10 @@ pod::macros::consts! {
         /// event. The id and seq should be copied from the Ping event.
         #[display = "Core::Pong"]
         PONG = 3;
+        /// Report an error which occurred on the object with the given id, most
+        /// often in response to an event the server sent to it.
+        #[display = "Core::Error"]
+        ERROR = 4;
         /// A client requests to bind to the registry object and list the
         /// available objects on the server.
         #[display = "Core::GetRegistry"]
@@ -27,6 +31,10 @@ pod::macros::consts! {
         /// Create a new object from a factory of a certain type.
         #[display = "Core::CreateObject"]
         CREATE_OBJECT = 6;
+        /// Destroy a resource, like an object created through
+        /// `Core::CreateObject`.
+        #[display = "Core::Destroy"]
+        DESTROY = 7;
     }
 
     #[example = GLOBAL]
@@ -69,9 +77,10 @@ pod::macros::consts! {
         /// identifier id.
         #[display = "Core::AddMem"]
         ADD_MEM = 6;
-        /// Destroy an object.
-        #[display = "Core::Destroy"]
-        DESTROY = 7;
+        /// Memory with the given identifier will be removed. The client should
+        /// not use the memory identifier anymore.
+        #[display = "Core::RemoveMem"]
+        REMOVE_MEM = 7;
     }
 
     #[example = UPDATE_PROPERTIES]
@@ -96,6 +105,20 @@ pod::macros::consts! {
         ERROR = 1;
     }
 
+    #[example = BIND]
+    #[module = protocol::consts]
+    pub struct Registry(u8) {
+        UNKNOWN;
+        /// Bind to the global object with id and use the client proxy with
+        /// new_id as the proxy. After this call, methods can be sent to the
+        /// remote global object and events can be received.
+        #[display = "Registry::Bind"]
+        BIND = 1;
+        /// Attempt to destroy the global object with the given id.
+        #[display = "Registry::Destroy"]
+        DESTROY = 2;
+    }
+
     #[example = GLOBAL]
     #[module = protocol::consts]
     pub struct RegistryEvent(u8) {
@@ -108,6 +131,67 @@ pod::macros::consts! {
         GLOBAL_REMOVE = 1;
     }
 
+    #[example = SUBSCRIBE_PARAMS]
+    #[module = protocol::consts]
+    pub struct Node(u8) {
+        UNKNOWN;
+        /// Automatically emit Param events for the given ids when they are
+        /// changed.
+        #[display = "Node::SubscribeParams"]
+        SUBSCRIBE_PARAMS = 1;
+        /// Enumerate the values of a param.
+        #[display = "Node::EnumParams"]
+        ENUM_PARAMS = 2;
+        /// Set a param on the node.
+        #[display = "Node::SetParam"]
+        SET_PARAM = 3;
+        /// Send a command to the node.
+        #[display = "Node::SendCommand"]
+        SEND_COMMAND = 4;
+    }
+
+    #[example = PARAM]
+    #[module = protocol::consts]
+    pub struct NodeEvent(u8) {
+        UNKNOWN;
+        /// Notify node info.
+        #[display = "Node::Info"]
+        INFO = 0;
+        /// Emitted as a result of EnumParams or when a subscribed param
+        /// changed.
+        #[display = "Node::Param"]
+        PARAM = 1;
+    }
+
+    #[example = SUBSCRIBE_PARAMS]
+    #[module = protocol::consts]
+    pub struct Device(u8) {
+        UNKNOWN;
+        /// Automatically emit Param events for the given ids when they are
+        /// changed.
+        #[display = "Device::SubscribeParams"]
+        SUBSCRIBE_PARAMS = 1;
+        /// Enumerate the values of a param.
+        #[display = "Device::EnumParams"]
+        ENUM_PARAMS = 2;
+        /// Set a param on the device.
+        #[display = "Device::SetParam"]
+        SET_PARAM = 3;
+    }
+
+    #[example = PARAM]
+    #[module = protocol::consts]
+    pub struct DeviceEvent(u8) {
+        UNKNOWN;
+        /// Notify device info.
+        #[display = "Device::Info"]
+        INFO = 0;
+        /// Emitted as a result of EnumParams or when a subscribed param
+        /// changed.
+        #[display = "Device::Param"]
+        PARAM = 1;
+    }
+
     #[example = UPDATE]
     #[module = protocol::consts]
     pub struct ClientNode(u8) {
@@ -162,4 +246,41 @@ pod::macros::consts! {
         #[display = "ClientNode::PortSetMixInfo"]
         PORT_SET_MIX_INFO = 11;
     }
+
+    #[example = CREATE]
+    #[module = protocol::consts]
+    pub struct SecurityContext(u8) {
+        UNKNOWN;
+        /// Create a new security context with the given listening socket and
+        /// close file descriptor. Clients connecting to the socket are assigned
+        /// the given properties, which can be used to restrict their access.
+        /// The security context is removed when the close file descriptor is
+        /// closed by the peer.
+        #[display = "SecurityContext::Create"]
+        CREATE = 1;
+    }
+
+    #[example = SET_PROPERTY]
+    #[module = protocol::consts]
+    pub struct Metadata(u8) {
+        UNKNOWN;
+        /// Set a property with a key, type and value on a subject, or remove
+        /// it if the value is empty.
+        #[display = "Metadata::SetProperty"]
+        SET_PROPERTY = 1;
+        /// Clear all properties.
+        #[display = "Metadata::Clear"]
+        CLEAR = 2;
+    }
+
+    #[example = PROPERTY]
+    #[module = protocol::consts]
+    pub struct MetadataEvent(u8) {
+        UNKNOWN;
+        /// A property of a subject has been set, or removed if the value is
+        /// empty. If the key is empty, all properties of the subject have
+        /// been removed.
+        #[display = "Metadata::Property"]
+        PROPERTY = 0;
+    }
 }
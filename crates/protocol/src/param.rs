@@ -1,7 +1,14 @@
 //! Helper types for interacting with parameter objects.
 
-use pod::{Readable, Writable};
+use core::ops::RangeInclusive;
 
+use pod::builder::ObjectBuilder;
+use pod::{
+    BuildPod, Builder, ChoiceType, Embeddable, Error, Id, Object, PodSink, Readable, Type,
+    Writable, Writer, WriterSlice,
+};
+
+use crate::consts::Direction;
 use crate::id;
 
 /// A [`PARAM_IO`] object type.
@@ -27,3 +34,387 @@ pub struct Meta {
     #[pod(property(key = id::ParamMeta::SIZE))]
     pub size: usize,
 }
+
+/// A [`PARAM_LATENCY`] object type.
+///
+/// Latency is described relative to the quantum, relative to the sample rate
+/// and in absolute nanoseconds, where the total latency is the sum of all
+/// three.
+///
+/// [`PARAM_LATENCY`]: id::ObjectType::PARAM_LATENCY
+///
+/// # Examples
+///
+/// ```
+/// use pod::Id;
+/// use protocol::consts::Direction;
+/// use protocol::param::Latency;
+///
+/// let latency = Latency {
+///     direction: Id(Direction::INPUT.into_raw()),
+///     min_quantum: 1.0,
+///     max_quantum: 1.0,
+///     min_rate: 64,
+///     max_rate: 64,
+///     min_ns: 0,
+///     max_ns: 1_000_000,
+/// };
+///
+/// let mut pod = pod::array();
+/// pod.as_mut().write(&latency)?;
+///
+/// let latency = pod.as_ref().read::<Latency>()?;
+/// assert_eq!(latency.direction(), Direction::INPUT);
+/// assert_eq!(latency.max_samples(1024, 48000), 1024 + 64 + 48);
+/// # Ok::<_, pod::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Readable, Writable)]
+#[pod(object(type = id::ObjectType::PARAM_LATENCY, id = id::Param::LATENCY))]
+pub struct Latency {
+    #[pod(property(key = id::ParamLatency::DIRECTION))]
+    pub direction: Id<u32>,
+    #[pod(property(key = id::ParamLatency::MIN_QUANTUM))]
+    pub min_quantum: f32,
+    #[pod(property(key = id::ParamLatency::MAX_QUANTUM))]
+    pub max_quantum: f32,
+    #[pod(property(key = id::ParamLatency::MIN_RATE))]
+    pub min_rate: i32,
+    #[pod(property(key = id::ParamLatency::MAX_RATE))]
+    pub max_rate: i32,
+    #[pod(property(key = id::ParamLatency::MIN_NS))]
+    pub min_ns: i64,
+    #[pod(property(key = id::ParamLatency::MAX_NS))]
+    pub max_ns: i64,
+}
+
+impl Latency {
+    /// The direction the latency applies to.
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        Direction::from_raw(self.direction.0)
+    }
+
+    /// The minimum latency in samples for the given quantum and sample rate.
+    pub fn min_samples(&self, quantum: u64, rate: u32) -> u64 {
+        to_samples(self.min_quantum, self.min_rate, self.min_ns, quantum, rate)
+    }
+
+    /// The maximum latency in samples for the given quantum and sample rate.
+    pub fn max_samples(&self, quantum: u64, rate: u32) -> u64 {
+        to_samples(self.max_quantum, self.max_rate, self.max_ns, quantum, rate)
+    }
+}
+
+fn to_samples(quantum_part: f32, rate_part: i32, ns: i64, quantum: u64, rate: u32) -> u64 {
+    let quantum_part = (quantum_part.max(0.0) as f64 * quantum as f64) as u64;
+    let rate_part = rate_part.max(0) as u64;
+    let ns_part = (ns.max(0) as u128 * rate as u128 / 1_000_000_000) as u64;
+    quantum_part + rate_part + ns_part
+}
+
+/// An integer property of a format, which is either fixed or a range of
+/// acceptable values.
+///
+/// This can be constructed from a `u32` for a fixed value, or from an
+/// inclusive range in which case the start of the range is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntChoice {
+    /// A fixed value.
+    Fixed(u32),
+    /// A range of acceptable values.
+    Range {
+        /// The preferred value.
+        default: u32,
+        /// The smallest acceptable value.
+        min: u32,
+        /// The largest acceptable value.
+        max: u32,
+    },
+}
+
+impl IntChoice {
+    /// Construct a range of acceptable values with the given default.
+    #[inline]
+    pub const fn range(min: u32, max: u32, default: u32) -> Self {
+        Self::Range { default, min, max }
+    }
+}
+
+impl From<u32> for IntChoice {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self::Fixed(value)
+    }
+}
+
+impl From<RangeInclusive<u32>> for IntChoice {
+    #[inline]
+    fn from(range: RangeInclusive<u32>) -> Self {
+        let (min, max) = range.into_inner();
+        Self::range(min, max, min)
+    }
+}
+
+impl Writable for IntChoice {
+    #[inline]
+    fn write_into(&self, pod: &mut impl PodSink) -> Result<(), Error> {
+        let pod = pod.next()?;
+
+        match *self {
+            IntChoice::Fixed(value) => pod.write(value),
+            IntChoice::Range { default, min, max } => pod.write_range(min, default, max),
+        }
+    }
+}
+
+/// A builder for an [`ENUM_FORMAT`] parameter, which describes the formats a
+/// port can be configured with.
+///
+/// Properties which accept more than one value are written as choices, where
+/// the first value is the preferred one.
+///
+/// [`ENUM_FORMAT`]: id::Param::ENUM_FORMAT
+///
+/// # Examples
+///
+/// ```
+/// use pod::{ChoiceType, Type};
+/// use protocol::id::{self, AudioFormat};
+/// use protocol::param::EnumFormatBuilder;
+///
+/// let format = EnumFormatBuilder::audio()
+///     .format_any(&[AudioFormat::F32P, AudioFormat::S16])
+///     .rate_range(8000, 192000, 48000)
+///     .channels(1..=8);
+///
+/// let mut pod = pod::array();
+/// let obj = pod.as_mut().embed(&format)?;
+///
+/// assert_eq!(obj.object_type::<id::ObjectType>(), id::ObjectType::FORMAT);
+/// assert_eq!(obj.object_id::<id::Param>(), id::Param::ENUM_FORMAT);
+///
+/// let mut obj = obj.as_ref();
+///
+/// let p = obj.property()?;
+/// assert_eq!(p.key::<id::Format>(), id::Format::MEDIA_TYPE);
+/// assert_eq!(p.value().read::<id::MediaType>()?, id::MediaType::AUDIO);
+///
+/// let p = obj.property()?;
+/// assert_eq!(p.key::<id::Format>(), id::Format::MEDIA_SUB_TYPE);
+/// assert_eq!(p.value().read::<id::MediaSubType>()?, id::MediaSubType::RAW);
+///
+/// let p = obj.property()?;
+/// assert_eq!(p.key::<id::Format>(), id::Format::AUDIO_FORMAT);
+/// let mut choice = p.value().read_choice()?;
+/// assert_eq!(choice.choice_type(), ChoiceType::ENUM);
+/// assert_eq!(choice.child_type(), Type::ID);
+/// assert_eq!(
+///     choice.read::<[AudioFormat; 3]>()?,
+///     [AudioFormat::F32P, AudioFormat::F32P, AudioFormat::S16]
+/// );
+///
+/// let p = obj.property()?;
+/// assert_eq!(p.key::<id::Format>(), id::Format::AUDIO_RATE);
+/// let mut choice = p.value().read_choice()?;
+/// assert_eq!(choice.choice_type(), ChoiceType::RANGE);
+/// assert_eq!(choice.read::<(u32, u32, u32)>()?, (48000, 8000, 192000));
+///
+/// let p = obj.property()?;
+/// assert_eq!(p.key::<id::Format>(), id::Format::AUDIO_CHANNELS);
+/// let mut choice = p.value().read_choice()?;
+/// assert_eq!(choice.choice_type(), ChoiceType::RANGE);
+/// assert_eq!(choice.read::<(u32, u32, u32)>()?, (1, 1, 8));
+///
+/// assert!(obj.is_empty());
+/// # Ok::<_, pod::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnumFormatBuilder<'a> {
+    media_type: id::MediaType,
+    media_sub_type: id::MediaSubType,
+    formats: &'a [id::AudioFormat],
+    rate: Option<IntChoice>,
+    channels: Option<IntChoice>,
+    control_types: Option<u32>,
+}
+
+impl<'a> EnumFormatBuilder<'a> {
+    /// Construct a builder for the given media type and sub type.
+    #[inline]
+    pub const fn new(media_type: id::MediaType, media_sub_type: id::MediaSubType) -> Self {
+        Self {
+            media_type,
+            media_sub_type,
+            formats: &[],
+            rate: None,
+            channels: None,
+            control_types: None,
+        }
+    }
+
+    /// Construct a builder for raw audio.
+    #[inline]
+    pub const fn audio() -> Self {
+        Self::new(id::MediaType::AUDIO, id::MediaSubType::RAW)
+    }
+
+    /// Construct a builder for audio in the DSP format, which is
+    /// non-interleaved 32-bit floating point.
+    #[inline]
+    pub const fn audio_dsp() -> Self {
+        Self::new(id::MediaType::AUDIO, id::MediaSubType::DSP)
+    }
+
+    /// Construct a builder for a control sequence, which is used to carry
+    /// MIDI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pod::{ChoiceType, ControlType, Type};
+    /// use protocol::id;
+    /// use protocol::param::EnumFormatBuilder;
+    ///
+    /// let format = EnumFormatBuilder::control().control_types(1 << ControlType::MIDI.into_u32());
+    ///
+    /// let mut pod = pod::array();
+    /// let obj = pod.as_mut().embed(&format)?;
+    /// let mut obj = obj.as_ref();
+    ///
+    /// let p = obj.property()?;
+    /// assert_eq!(p.value().read::<id::MediaType>()?, id::MediaType::APPLICATION);
+    ///
+    /// let p = obj.property()?;
+    /// assert_eq!(p.value().read::<id::MediaSubType>()?, id::MediaSubType::CONTROL);
+    ///
+    /// let p = obj.property()?;
+    /// assert_eq!(p.key::<id::Format>(), id::Format::CONTROL_TYPES);
+    /// let mut choice = p.value().read_choice()?;
+    /// assert_eq!(choice.choice_type(), ChoiceType::FLAGS);
+    /// assert_eq!(choice.child_type(), Type::INT);
+    /// assert_eq!(choice.read::<u32>()?, 1 << ControlType::MIDI.into_u32());
+    ///
+    /// assert!(obj.is_empty());
+    /// # Ok::<_, pod::Error>(())
+    /// ```
+    #[inline]
+    pub const fn control() -> Self {
+        Self::new(id::MediaType::APPLICATION, id::MediaSubType::CONTROL)
+    }
+
+    /// Accept any of the given audio formats, where the first one is
+    /// preferred.
+    ///
+    /// If only one format is specified, it is written as a fixed value.
+    #[inline]
+    pub const fn format_any(mut self, formats: &'a [id::AudioFormat]) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Set the sample rate.
+    #[inline]
+    pub fn rate(mut self, rate: impl Into<IntChoice>) -> Self {
+        self.rate = Some(rate.into());
+        self
+    }
+
+    /// Accept a sample rate in the range `min..=max`, preferring `default`.
+    #[inline]
+    pub fn rate_range(self, min: u32, max: u32, default: u32) -> Self {
+        self.rate(IntChoice::range(min, max, default))
+    }
+
+    /// Set the number of channels.
+    #[inline]
+    pub fn channels(mut self, channels: impl Into<IntChoice>) -> Self {
+        self.channels = Some(channels.into());
+        self
+    }
+
+    /// Accept a number of channels in the range `min..=max`, preferring
+    /// `default`.
+    #[inline]
+    pub fn channels_range(self, min: u32, max: u32, default: u32) -> Self {
+        self.channels(IntChoice::range(min, max, default))
+    }
+
+    /// Set the mask of control types accepted in a control sequence, where
+    /// each bit is a [`ControlType`].
+    ///
+    /// [`ControlType`]: pod::ControlType
+    #[inline]
+    pub fn control_types(mut self, types: u32) -> Self {
+        self.control_types = Some(types);
+        self
+    }
+
+    fn write_properties<W, P>(&self, obj: &mut ObjectBuilder<W, P>) -> Result<(), Error>
+    where
+        W: Writer,
+        P: BuildPod,
+    {
+        obj.property(id::Format::MEDIA_TYPE)
+            .write(self.media_type)?;
+        obj.property(id::Format::MEDIA_SUB_TYPE)
+            .write(self.media_sub_type)?;
+
+        match self.formats {
+            [] => {}
+            [format] => {
+                obj.property(id::Format::AUDIO_FORMAT).write(*format)?;
+            }
+            [default, ..] => {
+                obj.property(id::Format::AUDIO_FORMAT)
+                    .write_enum(*default, self.formats.iter().copied())?;
+            }
+        }
+
+        if let Some(rate) = &self.rate {
+            obj.property(id::Format::AUDIO_RATE).write(rate)?;
+        }
+
+        if let Some(channels) = &self.channels {
+            obj.property(id::Format::AUDIO_CHANNELS).write(channels)?;
+        }
+
+        if let Some(types) = self.control_types {
+            obj.property(id::Format::CONTROL_TYPES).write_choice(
+                ChoiceType::FLAGS,
+                Type::INT,
+                |choice| choice.write(types),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Writable for EnumFormatBuilder<'_> {
+    #[inline]
+    fn write_into(&self, pod: &mut impl PodSink) -> Result<(), Error> {
+        pod.next()?
+            .write_object(id::ObjectType::FORMAT, id::Param::ENUM_FORMAT, |obj| {
+                self.write_properties(obj)
+            })
+    }
+}
+
+impl Embeddable for EnumFormatBuilder<'_> {
+    type Embed<W>
+        = Object<WriterSlice<W, 16>>
+    where
+        W: Writer;
+
+    #[inline]
+    fn embed_into<W, P>(&self, pod: Builder<W, P>) -> Result<Self::Embed<W>, Error>
+    where
+        W: Writer,
+        P: BuildPod,
+    {
+        pod.embed_object(id::ObjectType::FORMAT, id::Param::ENUM_FORMAT, |obj| {
+            self.write_properties(obj)
+        })
+    }
+}
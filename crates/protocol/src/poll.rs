@@ -5,7 +5,7 @@ use core::ops::BitOrAssign;
 use core::{mem, ops::BitOr};
 use std::fmt;
 
-use libc::{POLLERR, POLLHUP, POLLIN, POLLOUT};
+use libc::{EPOLLET, POLLERR, POLLHUP, POLLIN, POLLOUT};
 
 /// The token returned by a poller.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -69,6 +69,12 @@ impl Interest {
     pub const HUP: Self = Self::new().hup();
     /// Error interest.
     pub const ERROR: Self = Self::new().error();
+    /// Edge-triggered registration.
+    ///
+    /// When combined with other interests, readiness is only reported when it
+    /// changes, so the file descriptor must be fully drained every time it is
+    /// reported as ready.
+    pub const EDGE: Self = Self::new().edge();
 
     /// Construct a new ready set.
     const fn new() -> Self {
@@ -125,6 +131,12 @@ impl Interest {
         Self(self.0 | POLLERR as u32)
     }
 
+    /// Make a ready set which is edge-triggered.
+    #[inline]
+    const fn edge(self) -> Self {
+        Self(self.0 | EPOLLET as u32)
+    }
+
     /// If events are read ready.
     #[inline]
     pub const fn is_read(&self) -> bool {
@@ -149,6 +161,12 @@ impl Interest {
         self.0 & (POLLERR as u32) != 0
     }
 
+    /// If the interest is edge-triggered.
+    #[inline]
+    pub const fn is_edge(&self) -> bool {
+        self.0 & (EPOLLET as u32) != 0
+    }
+
     /// As raw underlying u32.
     ///
     /// Note that since this is all based on constrained constant values we know
@@ -189,6 +207,10 @@ impl fmt::Debug for Interest {
             f.field(&DebugString::new("POLLERR"));
         }
 
+        if self.0 & EPOLLET as u32 != 0 {
+            f.field(&DebugString::new("EPOLLET"));
+        }
+
         return f.finish();
 
         #[repr(transparent)]
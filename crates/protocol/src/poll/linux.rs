@@ -87,11 +87,28 @@ impl Poll {
 
     /// Poll for the next events.
     pub fn poll(&mut self, out: &mut impl Events<PollEvent>) -> io::Result<()> {
+        self.wait(out, -1)
+    }
+
+    /// Poll for events which are ready without blocking.
+    ///
+    /// This is used when the poll itself is registered with another event
+    /// loop, which reports when any of its events are ready.
+    pub fn try_poll(&mut self, out: &mut impl Events<PollEvent>) -> io::Result<()> {
+        self.wait(out, 0)
+    }
+
+    fn wait(&mut self, out: &mut impl Events<PollEvent>, timeout: i32) -> io::Result<()> {
         // SAFETY: We're ensuring safety through type invariants.
         unsafe {
             let mut events = [mem::zeroed(); 4];
             let len = events.len().min(out.remaining_mut());
-            let ready = epoll_wait(self.fd.as_raw_fd(), events.as_mut_ptr(), len as i32, -1);
+            let ready = epoll_wait(
+                self.fd.as_raw_fd(),
+                events.as_mut_ptr(),
+                len as i32,
+                timeout,
+            );
 
             if ready == -1 {
                 return Err(io::Error::last_os_error());
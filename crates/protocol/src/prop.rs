@@ -25,14 +25,40 @@ macro_rules! properties {
 
 properties! {
     APPLICATION_NAME = "application.name";
+    OBJECT_SERIAL = "object.serial";
+    NODE_ID = "node.id";
     NODE_NAME = "node.name";
     NODE_DESCRIPTION = "node.description";
+    NODE_NICK = "node.nick";
+    NODE_FORCE_QUANTUM = "node.force-quantum";
+    NODE_FORCE_RATE = "node.force-rate";
+    NODE_LOCK_QUANTUM = "node.lock-quantum";
+    DEVICE_NAME = "device.name";
+    DEVICE_DESCRIPTION = "device.description";
     MEDIA_CLASS = "media.class";
     MEDIA_TYPE = "media.type";
     MEDIA_CATEGORY = "media.category";
     MEDIA_ROLE = "media.role";
+    MEDIA_ICON_NAME = "media.icon-name";
+    PORT_ID = "port.id";
     PORT_NAME = "port.name";
+    PORT_DIRECTION = "port.direction";
+    PORT_GROUP = "port.group";
+    PORT_MONITOR = "port.monitor";
     FORMAT_DSP = "format.dsp";
+    LINK_INPUT_NODE = "link.input.node";
+    LINK_OUTPUT_NODE = "link.output.node";
+    LINK_INPUT_PORT = "link.input.port";
+    LINK_OUTPUT_PORT = "link.output.port";
+    OBJECT_LINGER = "object.linger";
+    METADATA_NAME = "metadata.name";
+    TARGET_OBJECT = "target.object";
+    ACCESS = "pipewire.access";
+    CLIENT_ACCESS = "pipewire.client.access";
+    ACCESS_PORTAL_APP_ID = "pipewire.access.portal.app_id";
+    SEC_ENGINE = "pipewire.sec.engine";
+    SEC_APP_ID = "pipewire.sec.app-id";
+    SEC_INSTANCE_ID = "pipewire.sec.instance-id";
 }
 
 /// The key of a property.
@@ -48,7 +74,7 @@ impl Prop {
     }
 
     /// Get the string of the property.
-    pub(crate) const fn as_str(&self) -> &str {
+    pub const fn as_str(&self) -> &str {
         &self.0
     }
 }
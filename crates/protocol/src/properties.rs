@@ -8,12 +8,18 @@ use alloc::string::String;
 
 use std::collections::BTreeMap;
 
-use crate::Prop;
+use crate::{Prop, Symbol};
 
 /// Collection of properties.
-#[derive(Default)]
+///
+/// Property keys are stored as [`Symbol`]s, since the same keys are used
+/// across a large number of objects. Use [`Properties::insert_symbol`] with
+/// keys from an [`Interner`] to share their storage.
+///
+/// [`Interner`]: crate::symbol::Interner
+#[derive(Default, Clone)]
 pub struct Properties {
-    data: BTreeMap<String, String>,
+    data: BTreeMap<Symbol, String>,
     modified: bool,
 }
 
@@ -36,6 +42,12 @@ impl Properties {
         mem::take(&mut self.modified)
     }
 
+    /// Mark the properties as modified, such as when something they are
+    /// combined with has changed.
+    pub fn mark_modified(&mut self) {
+        self.modified = true;
+    }
+
     /// Get the number of properties in the collection.
     pub fn len(&self) -> usize {
         self.data.len()
@@ -48,25 +60,77 @@ impl Properties {
 
     /// Iterate over the properties in the collection.
     pub fn iter(&self) -> Iter<'_> {
-        self.data
-            .iter()
-            .map(|(k, v)| (Prop::new(k.as_str()), v.as_str()))
+        self.data.iter().map(|(k, v)| (k.as_prop(), v.as_str()))
     }
 
     /// Insert a property into the collection.
+    ///
+    /// Returns `true` if the property was added or modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protocol::Properties;
+    ///
+    /// let mut props = Properties::new();
+    /// assert!(props.insert("node.name", "a"));
+    /// assert!(!props.insert("node.name", "a"));
+    /// assert!(props.insert("node.name", "b"));
+    /// assert_eq!(props.get("node.name"), Some("b"));
+    /// ```
     pub fn insert(&mut self, key: impl AsRef<Prop>, value: impl AsRef<str>) -> bool {
         let key = key.as_ref().as_str();
         let value = value.as_ref();
 
-        let old = self.data.insert(String::from(key), String::from(value));
+        if let Some(old) = self.data.get_mut(key) {
+            if old == value {
+                return false;
+            }
 
-        let Some(old) = old else {
-            self.modified = true;
-            return true;
-        };
+            old.clear();
+            old.push_str(value);
+        } else {
+            self.data.insert(Symbol::new(key), String::from(value));
+        }
 
-        if old == value {
-            return false;
+        self.modified = true;
+        true
+    }
+
+    /// Insert a property with a key which has already been constructed, like
+    /// one from an [`Interner`].
+    ///
+    /// Returns `true` if the property was added or modified.
+    ///
+    /// [`Interner`]: crate::symbol::Interner
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protocol::Properties;
+    /// use protocol::symbol::Interner;
+    ///
+    /// let mut interner = Interner::new();
+    /// let mut a = Properties::new();
+    /// let mut b = Properties::new();
+    ///
+    /// assert!(a.insert_symbol(interner.intern("custom.key"), "a"));
+    /// assert!(b.insert_symbol(interner.intern("custom.key"), "b"));
+    /// assert_eq!(interner.len(), 1);
+    /// assert_eq!(a.get("custom.key"), Some("a"));
+    /// ```
+    pub fn insert_symbol(&mut self, key: Symbol, value: impl AsRef<str>) -> bool {
+        let value = value.as_ref();
+
+        if let Some(old) = self.data.get_mut(key.as_str()) {
+            if old == value {
+                return false;
+            }
+
+            old.clear();
+            old.push_str(value);
+        } else {
+            self.data.insert(key, String::from(value));
         }
 
         self.modified = true;
@@ -77,7 +141,7 @@ impl Properties {
     pub fn remove<K>(&mut self, key: &K) -> Option<String>
     where
         K: ?Sized + Ord,
-        String: Borrow<K>,
+        Symbol: Borrow<K>,
     {
         let value = self.data.remove(key);
         self.modified |= value.is_some();
@@ -88,11 +152,16 @@ impl Properties {
     pub fn get<K>(&self, key: &K) -> Option<&str>
     where
         K: ?Sized + Ord,
-        String: Borrow<K>,
+        Symbol: Borrow<K>,
     {
         self.data.get(key).map(|s| s.as_str())
     }
 
+    /// Iterate over the keys of the properties as interned symbols.
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.data.keys().cloned()
+    }
+
     /// Extend this collection of properties with another.
     ///
     /// Returns `true` if any properties were added or modified.
@@ -138,6 +207,15 @@ impl Properties {
     }
 }
 
+impl PartialEq for Properties {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl Eq for Properties {}
+
 impl fmt::Debug for Properties {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -147,7 +225,7 @@ impl fmt::Debug for Properties {
 
 /// The iterator produced by iterating over a borrowed [`Properties`].
 pub type Iter<'a> =
-    Map<btree_map::Iter<'a, String, String>, fn((&'a String, &'a String)) -> (&'a Prop, &'a str)>;
+    Map<btree_map::Iter<'a, Symbol, String>, fn((&'a Symbol, &'a String)) -> (&'a Prop, &'a str)>;
 
 impl<'a> IntoIterator for &'a Properties {
     type Item = (&'a Prop, &'a str);
@@ -155,8 +233,6 @@ impl<'a> IntoIterator for &'a Properties {
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
-        self.data
-            .iter()
-            .map(|(k, v)| (Prop::new(k.as_str()), v.as_str()))
+        self.data.iter().map(|(k, v)| (k.as_prop(), v.as_str()))
     }
 }
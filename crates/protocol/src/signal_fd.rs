@@ -0,0 +1,124 @@
+use core::ptr;
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+
+/// Signal file descriptor.
+///
+/// This allows signals like `SIGINT` and `SIGTERM` to be handled in the same
+/// poll loop as everything else, so that an application can shut down
+/// gracefully instead of being terminated in the middle of a cycle.
+///
+/// # Examples
+///
+/// ```no_run
+/// use protocol::SignalFd;
+///
+/// let signals = SignalFd::termination()?;
+/// signals.set_nonblocking(true)?;
+///
+/// if let Some(signal) = signals.read()? {
+///     println!("Received signal {signal}");
+/// }
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub struct SignalFd {
+    fd: OwnedFd,
+}
+
+impl SignalFd {
+    /// Construct a new signal fd receiving the given signals.
+    ///
+    /// The signals are blocked for the calling thread, so that they are only
+    /// delivered through the file descriptor. This should be called before any
+    /// other threads are spawned since they inherit the signal mask.
+    pub fn new(signals: &[i32]) -> io::Result<Self> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let mut mask: libc::sigset_t = mem::zeroed();
+            libc::sigemptyset(&mut mask);
+
+            for &signal in signals {
+                if libc::sigaddset(&mut mask, signal) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            let n = libc::pthread_sigmask(libc::SIG_BLOCK, &mask, ptr::null_mut());
+
+            if n != 0 {
+                return Err(io::Error::from_raw_os_error(n));
+            }
+
+            let fd = libc::signalfd(-1, &mask, libc::SFD_CLOEXEC);
+
+            if fd == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self {
+                fd: OwnedFd::from_raw_fd(fd),
+            })
+        }
+    }
+
+    /// Construct a new signal fd receiving the signals which ask a process to
+    /// terminate, which are `SIGINT` and `SIGTERM`.
+    pub fn termination() -> io::Result<Self> {
+        Self::new(&[libc::SIGINT, libc::SIGTERM])
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        // SAFETY: We're just using c-apis as intended.
+        unsafe {
+            let mut flags = libc::fcntl(self.fd.as_raw_fd(), libc::F_GETFL);
+
+            if flags == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if nonblocking {
+                flags |= libc::O_NONBLOCK;
+            } else {
+                flags &= !libc::O_NONBLOCK;
+            }
+
+            if libc::fcntl(self.fd.as_raw_fd(), libc::F_SETFL, flags) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Read the next received signal.
+    ///
+    /// Returns `None` if the operation would block.
+    pub fn read(&self) -> io::Result<Option<i32>> {
+        unsafe {
+            let mut info = mem::MaybeUninit::<libc::signalfd_siginfo>::uninit();
+            let size = mem::size_of::<libc::signalfd_siginfo>();
+            let n = libc::read(self.fd.as_raw_fd(), info.as_mut_ptr().cast(), size);
+
+            if n == -1 {
+                match io::Error::last_os_error() {
+                    e if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                    e => return Err(e),
+                }
+            }
+
+            if n as usize != size {
+                return Err(io::Error::other("expected a full signal info"));
+            }
+
+            Ok(Some(info.assume_init().ssi_signo as i32))
+        }
+    }
+}
+
+impl AsRawFd for SignalFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
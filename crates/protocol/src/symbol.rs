@@ -0,0 +1,294 @@
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::ptr;
+
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+
+use crate::Prop;
+
+#[derive(Clone)]
+enum Repr {
+    /// A well-known property key from [`crate::prop`].
+    Static(&'static str),
+    /// A reference counted string.
+    Shared(Arc<str>),
+}
+
+/// A property key or interface type.
+///
+/// Well-known property keys from [`prop`] are represented without allocating,
+/// and other strings are reference counted. Symbols interned through the same
+/// [`Interner`] share storage, so comparing equal symbols for equality is a
+/// pointer comparison. Ordering and hashing is performed over the content of
+/// the string, making it consistent with [`str`].
+///
+/// [`prop`]: crate::prop
+///
+/// # Examples
+///
+/// ```
+/// use protocol::Symbol;
+///
+/// let a = Symbol::new("node.name");
+/// let b = Symbol::new(&String::from("node.name"));
+///
+/// assert_eq!(a, b);
+/// assert_eq!(a, "node.name");
+/// assert_eq!(a.as_str(), "node.name");
+/// assert!(std::ptr::eq(a.as_str(), b.as_str()));
+///
+/// let c = Symbol::new("custom.key");
+/// let d = Symbol::new("custom.key");
+/// assert_eq!(c, d);
+/// assert!(!std::ptr::eq(c.as_str(), d.as_str()));
+/// ```
+#[derive(Clone)]
+pub struct Symbol(Repr);
+
+impl Symbol {
+    /// Construct a symbol from a string.
+    ///
+    /// Well-known property keys are looked up without allocating, while other
+    /// strings are allocated. Use an [`Interner`] to share the storage of
+    /// symbols which are constructed repeatedly.
+    pub fn new(string: &str) -> Self {
+        match Self::well_known(string) {
+            Some(symbol) => symbol,
+            None => Self(Repr::Shared(Arc::from(string))),
+        }
+    }
+
+    /// Get the symbol of a well-known property key from [`prop`].
+    ///
+    /// [`prop`]: crate::prop
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protocol::{Symbol, prop};
+    ///
+    /// assert!(Symbol::well_known("symbol.example").is_none());
+    /// let symbol = Symbol::well_known("node.name").unwrap();
+    /// assert_eq!(symbol.as_prop(), prop::NODE_NAME);
+    /// ```
+    pub fn well_known(string: &str) -> Option<Self> {
+        Some(Self(Repr::Static(Prop::get(string)?.as_str())))
+    }
+
+    /// Get the string of the symbol.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Static(string) => string,
+            Repr::Shared(string) => string,
+        }
+    }
+
+    /// Get the symbol as a property key.
+    #[inline]
+    pub fn as_prop(&self) -> &Prop {
+        Prop::new(self.as_str())
+    }
+}
+
+impl PartialEq for Symbol {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            // NB: Well-known keys only have one representation.
+            (Repr::Static(a), Repr::Static(b)) => ptr::eq(*a, *b),
+            (Repr::Shared(a), Repr::Shared(b)) if Arc::ptr_eq(a, b) => true,
+            _ => self.as_str() == other.as_str(),
+        }
+    }
+}
+
+impl Eq for Symbol {}
+
+impl PartialEq<str> for Symbol {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for Symbol {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            return Ordering::Equal;
+        }
+
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for Symbol {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.as_str().hash(state);
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for Symbol {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<Prop> for Symbol {
+    #[inline]
+    fn borrow(&self) -> &Prop {
+        self.as_prop()
+    }
+}
+
+impl AsRef<str> for Symbol {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<Prop> for Symbol {
+    #[inline]
+    fn as_ref(&self) -> &Prop {
+        self.as_prop()
+    }
+}
+
+impl fmt::Display for Symbol {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// A table of interned symbols.
+///
+/// Interning the same string repeatedly returns symbols which share storage.
+/// The interner is scoped to whatever owns it, like the registry of a stream,
+/// and symbols which are no longer referenced outside of the interner are
+/// freed by [`Interner::collect`].
+///
+/// # Examples
+///
+/// ```
+/// use protocol::symbol::Interner;
+///
+/// let mut interner = Interner::new();
+///
+/// let a = interner.intern("custom.key");
+/// let b = interner.intern("custom.key");
+/// assert!(std::ptr::eq(a.as_str(), b.as_str()));
+/// assert_eq!(interner.len(), 1);
+///
+/// // Well-known keys are never stored in the interner.
+/// interner.intern("node.name");
+/// assert_eq!(interner.len(), 1);
+///
+/// drop((a, b));
+/// interner.collect();
+/// assert!(interner.is_empty());
+/// ```
+#[derive(Default)]
+pub struct Interner {
+    symbols: BTreeSet<Symbol>,
+}
+
+impl Interner {
+    /// Construct a new empty interner.
+    pub const fn new() -> Self {
+        Self {
+            symbols: BTreeSet::new(),
+        }
+    }
+
+    /// Get the number of symbols stored in the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Test if the interner is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Get a symbol if it is well-known or has already been interned.
+    pub fn get(&self, string: &str) -> Option<Symbol> {
+        if let Some(symbol) = Symbol::well_known(string) {
+            return Some(symbol);
+        }
+
+        self.symbols.get(string).cloned()
+    }
+
+    /// Intern a string, returning the existing symbol if it has already been
+    /// interned.
+    ///
+    /// Well-known property keys are returned without allocating.
+    pub fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(symbol) = self.get(string) {
+            return symbol;
+        }
+
+        let symbol = Symbol(Repr::Shared(Arc::from(string)));
+        self.symbols.insert(symbol.clone());
+        symbol
+    }
+
+    /// Free symbols which are only referenced by the interner.
+    pub fn collect(&mut self) {
+        self.symbols.retain(|symbol| match &symbol.0 {
+            Repr::Static(..) => false,
+            Repr::Shared(string) => Arc::strong_count(string) > 1,
+        });
+    }
+}
+
+impl fmt::Debug for Interner {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.symbols).finish()
+    }
+}
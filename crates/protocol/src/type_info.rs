@@ -0,0 +1,596 @@
+//! Resolution of SPA type-info names like `Spa:Pod:Object:Param:Format`.
+//!
+//! These mirror the type tables of libspa, which are used by tools like
+//! `pw-cli` and `pw-dump` to give canonical names to the numeric identifiers
+//! of objects and their properties.
+//!
+//! # Examples
+//!
+//! ```
+//! use protocol::id;
+//! use protocol::type_info;
+//!
+//! let name = type_info::FORMAT.name(id::Format::AUDIO_RATE.into_id()).unwrap();
+//! assert_eq!(name.to_string(), "Spa:Pod:Object:Param:Format:Audio:rate");
+//! assert_eq!(name.short(), "Audio:rate");
+//!
+//! let (table, id) = type_info::resolve("Spa:Pod:Object:Param:Format:Audio:rate").unwrap();
+//! assert_eq!(table, &type_info::FORMAT);
+//! assert_eq!(id, id::Format::AUDIO_RATE.into_id());
+//!
+//! let keys = type_info::keys(id::ObjectType::FORMAT).unwrap();
+//! assert_eq!(keys.id("Audio:rate"), Some(id::Format::AUDIO_RATE.into_id()));
+//! ```
+
+use core::fmt;
+
+use crate::id;
+
+macro_rules! type_info {
+    ($(
+        $(#[doc = $doc:literal])*
+        pub static $name:ident: $ty:ident = $base:literal {
+            $($field:ident => $value:literal,)*
+        }
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            pub static $name: TypeInfo = TypeInfo {
+                base: $base,
+                values: &[$((id::$ty::$field.into_id(), $value),)*],
+            };
+        )*
+
+        static TABLES: &[&TypeInfo] = &[$(&$name,)*];
+    };
+}
+
+/// A table of SPA type-info names, equivalent to an array of
+/// `struct spa_type_info`.
+///
+/// Every name in the table is prefixed with a common base, so the full name
+/// of [`id::Param::ENUM_FORMAT`] is `Spa:Enum:ParamId:EnumFormat` where the
+/// base is `Spa:Enum:ParamId` and its short name is `EnumFormat`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeInfo {
+    base: &'static str,
+    values: &'static [(u32, &'static str)],
+}
+
+impl TypeInfo {
+    /// The base of names in the table, like `Spa:Enum:ParamId`.
+    #[inline]
+    pub fn base(&self) -> &'static str {
+        self.base
+    }
+
+    /// Get the full name of an identifier.
+    pub fn name(&self, id: u32) -> Option<TypeName> {
+        let short = self.short_name(id)?;
+
+        Some(TypeName {
+            base: self.base,
+            short,
+        })
+    }
+
+    /// Get the short name of an identifier, like `EnumFormat`.
+    pub fn short_name(&self, id: u32) -> Option<&'static str> {
+        self.values
+            .iter()
+            .find(|&&(value, _)| value == id)
+            .map(|&(_, short)| short)
+    }
+
+    /// Look up the identifier of a full name, like
+    /// `Spa:Enum:ParamId:EnumFormat`, or of a short name, like `EnumFormat`.
+    pub fn id(&self, name: &str) -> Option<u32> {
+        let short = self.strip_base(name).unwrap_or(name);
+
+        self.values
+            .iter()
+            .find(|&&(_, value)| value == short)
+            .map(|&(id, _)| id)
+    }
+
+    /// Iterate over the identifiers and full names in the table.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, TypeName)> {
+        let base = self.base;
+
+        self.values
+            .iter()
+            .map(move |&(id, short)| (id, TypeName { base, short }))
+    }
+
+    fn strip_base<'a>(&self, name: &'a str) -> Option<&'a str> {
+        name.strip_prefix(self.base)?.strip_prefix(':')
+    }
+}
+
+/// The full SPA type-info name of an identifier, like
+/// `Spa:Pod:Object:Param:Format:Audio:rate`.
+///
+/// The full name is produced through its [`Display`] implementation.
+///
+/// [`Display`]: core::fmt::Display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeName {
+    base: &'static str,
+    short: &'static str,
+}
+
+impl TypeName {
+    /// The base of the name, like `Spa:Pod:Object:Param:Format`.
+    #[inline]
+    pub fn base(&self) -> &'static str {
+        self.base
+    }
+
+    /// The short name, like `Audio:rate`.
+    #[inline]
+    pub fn short(&self) -> &'static str {
+        self.short
+    }
+}
+
+impl fmt::Display for TypeName {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.base, self.short)
+    }
+}
+
+impl PartialEq<str> for TypeName {
+    fn eq(&self, other: &str) -> bool {
+        other
+            .strip_prefix(self.base)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(|rest| rest == self.short)
+    }
+}
+
+impl PartialEq<&str> for TypeName {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// Resolve a full SPA type-info name to the table it belongs to and its
+/// identifier.
+///
+/// # Examples
+///
+/// ```
+/// use protocol::id;
+/// use protocol::type_info;
+///
+/// let (table, id) = type_info::resolve("Spa:Enum:ParamId:EnumFormat").unwrap();
+/// assert_eq!(table, &type_info::PARAM);
+/// assert_eq!(id, id::Param::ENUM_FORMAT.into_id());
+///
+/// assert!(type_info::resolve("EnumFormat").is_none());
+/// assert!(type_info::resolve("Spa:Enum:ParamId:Unknown").is_none());
+/// ```
+pub fn resolve(name: &str) -> Option<(&'static TypeInfo, u32)> {
+    TABLES.iter().find_map(|&table| {
+        let short = table.strip_base(name)?;
+        let &(id, _) = table.values.iter().find(|&&(_, value)| value == short)?;
+        Some((table, id))
+    })
+}
+
+/// Get the table of property keys of an object type.
+///
+/// # Examples
+///
+/// ```
+/// use protocol::id;
+/// use protocol::type_info;
+///
+/// let keys = type_info::keys(id::ObjectType::PROPS).unwrap();
+/// let name = keys.name(id::Prop::CHANNEL_VOLUMES.into_id()).unwrap();
+/// assert_eq!(name, "Spa:Pod:Object:Param:Props:channelVolumes");
+/// ```
+pub fn keys(object_type: id::ObjectType) -> Option<&'static TypeInfo> {
+    let table = match object_type {
+        id::ObjectType::PROP_INFO => &PROP_INFO,
+        id::ObjectType::PROPS => &PROPS,
+        id::ObjectType::FORMAT => &FORMAT,
+        id::ObjectType::PARAM_BUFFERS => &PARAM_BUFFERS,
+        id::ObjectType::PARAM_META => &PARAM_META,
+        id::ObjectType::PARAM_IO => &PARAM_IO,
+        id::ObjectType::PARAM_ROUTE => &PARAM_ROUTE,
+        id::ObjectType::PARAM_LATENCY => &PARAM_LATENCY,
+        _ => return None,
+    };
+
+    Some(table)
+}
+
+/// Get the table of values of a property key which holds an identifier, like
+/// the [`id::AudioFormat`] of [`id::Format::AUDIO_FORMAT`].
+///
+/// # Examples
+///
+/// ```
+/// use protocol::id;
+/// use protocol::type_info;
+///
+/// let values = type_info::values(id::ObjectType::FORMAT, id::Format::MEDIA_TYPE.into_id()).unwrap();
+/// assert_eq!(values.short_name(id::MediaType::AUDIO.into_id()), Some("audio"));
+///
+/// assert!(type_info::values(id::ObjectType::FORMAT, id::Format::AUDIO_RATE.into_id()).is_none());
+/// ```
+pub fn values(object_type: id::ObjectType, key: u32) -> Option<&'static TypeInfo> {
+    let table = match object_type {
+        id::ObjectType::FORMAT => match id::Format::from_id(key) {
+            id::Format::MEDIA_TYPE => &MEDIA_TYPE,
+            id::Format::MEDIA_SUB_TYPE => &MEDIA_SUB_TYPE,
+            id::Format::AUDIO_FORMAT => &AUDIO_FORMAT,
+            _ => return None,
+        },
+        id::ObjectType::PARAM_META => match id::ParamMeta::from_id(key) {
+            id::ParamMeta::TYPE => &META,
+            _ => return None,
+        },
+        id::ObjectType::PARAM_IO => match id::ParamIo::from_id(key) {
+            id::ParamIo::ID => &IO_TYPE,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(table)
+}
+
+type_info! {
+    /// Object types, like `Spa:Pod:Object:Param:Format`.
+    pub static OBJECT_TYPE: ObjectType = "Spa:Pod:Object" {
+        PROP_INFO => "Param:PropInfo",
+        PROPS => "Param:Props",
+        FORMAT => "Param:Format",
+        PARAM_BUFFERS => "Param:Buffers",
+        PARAM_META => "Param:Meta",
+        PARAM_IO => "Param:IO",
+        PARAM_PROFILE => "Param:Profile",
+        PARAM_PORT_CONFIG => "Param:PortConfig",
+        PARAM_ROUTE => "Param:Route",
+        PROFILER => "Profiler",
+        PARAM_LATENCY => "Param:Latency",
+        PARAM_PROCESS_LATENCY => "Param:ProcessLatency",
+        PARAM_TAG => "Param:Tag",
+    }
+
+    /// Command types, like `Spa:Pod:Object:Command:Node`.
+    pub static COMMAND_TYPE: CommandType = "Spa:Pod:Object" {
+        DEVICE => "Command:Device",
+        NODE => "Command:Node",
+    }
+
+    /// Parameter identifiers, like `Spa:Enum:ParamId:EnumFormat`.
+    pub static PARAM: Param = "Spa:Enum:ParamId" {
+        PROP_INFO => "PropInfo",
+        PROPS => "Props",
+        ENUM_FORMAT => "EnumFormat",
+        FORMAT => "Format",
+        BUFFERS => "Buffers",
+        META => "Meta",
+        IO => "IO",
+        ENUM_PROFILE => "EnumProfile",
+        PROFILE => "Profile",
+        ENUM_PORT_CONFIG => "EnumPortConfig",
+        PORT_CONFIG => "PortConfig",
+        ENUM_ROUTE => "EnumRoute",
+        ROUTE => "Route",
+        CONTROL => "Control",
+        LATENCY => "Latency",
+        PROCESS_LATENCY => "ProcessLatency",
+        TAG => "Tag",
+    }
+
+    /// Media types, like `Spa:Enum:MediaType:audio`.
+    pub static MEDIA_TYPE: MediaType = "Spa:Enum:MediaType" {
+        AUDIO => "audio",
+        VIDEO => "video",
+        IMAGE => "image",
+        BINARY => "binary",
+        STREAM => "stream",
+        APPLICATION => "application",
+    }
+
+    /// Media sub types, like `Spa:Enum:MediaSubtype:raw`.
+    pub static MEDIA_SUB_TYPE: MediaSubType = "Spa:Enum:MediaSubtype" {
+        RAW => "raw",
+        DSP => "dsp",
+        IEC958 => "iec958",
+        DSD => "dsd",
+        MP3 => "mp3",
+        AAC => "aac",
+        VORBIS => "vorbis",
+        WMA => "wma",
+        RA => "ra",
+        SBC => "sbc",
+        ADPCM => "adpcm",
+        G723 => "g723",
+        G726 => "g726",
+        G729 => "g729",
+        AMR => "amr",
+        GSM => "gsm",
+        ALAC => "alac",
+        FLAC => "flac",
+        APE => "ape",
+        OPUS => "opus",
+        H264 => "h264",
+        MJPG => "mjpg",
+        DV => "dv",
+        MPEGTS => "mpegts",
+        H263 => "h263",
+        MPEG1 => "mpeg1",
+        MPEG2 => "mpeg2",
+        MPEG4 => "mpeg4",
+        XVID => "xvid",
+        VC1 => "vc1",
+        VP8 => "vp8",
+        VP9 => "vp9",
+        BAYER => "bayer",
+        JPEG => "jpeg",
+        MIDI => "midi",
+        CONTROL => "control",
+    }
+
+    /// Audio sample formats, like `Spa:Enum:AudioFormat:F32LE`.
+    pub static AUDIO_FORMAT: AudioFormat = "Spa:Enum:AudioFormat" {
+        ENCODED => "ENCODED",
+        S8 => "S8",
+        U8 => "U8",
+        S16_LE => "S16LE",
+        S16_BE => "S16BE",
+        U16_LE => "U16LE",
+        U16_BE => "U16BE",
+        S24_32_LE => "S24_32LE",
+        S24_32_BE => "S24_32BE",
+        U24_32_LE => "U24_32LE",
+        U24_32_BE => "U24_32BE",
+        S32_LE => "S32LE",
+        S32_BE => "S32BE",
+        U32_LE => "U32LE",
+        U32_BE => "U32BE",
+        S24_LE => "S24LE",
+        S24_BE => "S24BE",
+        U24_LE => "U24LE",
+        U24_BE => "U24BE",
+        S20_LE => "S20LE",
+        S20_BE => "S20BE",
+        U20_LE => "U20LE",
+        U20_BE => "U20BE",
+        S18_LE => "S18LE",
+        S18_BE => "S18BE",
+        U18_LE => "U18LE",
+        U18_BE => "U18BE",
+        F32_LE => "F32LE",
+        F32_BE => "F32BE",
+        F64_LE => "F64LE",
+        F64_BE => "F64BE",
+        ULAW => "ULAW",
+        ALAW => "ALAW",
+        U8P => "U8P",
+        S16P => "S16P",
+        S24_32P => "S24_32P",
+        S32P => "S32P",
+        S24P => "S24P",
+        F32P => "F32P",
+        F64P => "F64P",
+        S8P => "S8P",
+    }
+
+    /// Data types of buffers, like `Spa:Enum:DataType:Fd:MemFd`.
+    pub static DATA_TYPE: DataType = "Spa:Enum:DataType" {
+        MEM_PTR => "MemPtr",
+        MEM_FD => "Fd:MemFd",
+        DMA_BUF => "Fd:DmaBuf",
+        MEM_ID => "MemId",
+        SYNC_OBJ => "Fd:SyncObj",
+    }
+
+    /// Metadata types of buffers, like `Spa:Pointer:Meta:Header`.
+    pub static META: Meta = "Spa:Pointer:Meta" {
+        HEADER => "Header",
+        VIDEO_CROP => "Region:VideoCrop",
+        VIDEO_DAMAGE => "Array:Region:VideoDamage",
+        BITMAP => "Bitmap",
+        CURSOR => "Cursor",
+        CONTROL => "Control",
+        BUSY => "Busy",
+        VIDEO_TRANSFORM => "VideoTransform",
+        SYNC_TIMELINE => "SyncTimeline",
+    }
+
+    /// IO areas, like `Spa:Enum:IO:Position`.
+    pub static IO_TYPE: IoType = "Spa:Enum:IO" {
+        BUFFERS => "Buffers",
+        RANGE => "Range",
+        CLOCK => "Clock",
+        LATENCY => "Latency",
+        CONTROL => "Control",
+        NOTIFY => "Notify",
+        POSITION => "Position",
+        RATE_MATCH => "RateMatch",
+        MEMORY => "Memory",
+        ASYNC_BUFFERS => "AsyncBuffers",
+    }
+
+    /// Node commands, like `Spa:Pod:Object:Command:Node:Suspend`.
+    pub static NODE_COMMAND: NodeCommand = "Spa:Pod:Object:Command:Node" {
+        SUSPEND => "Suspend",
+        PAUSE => "Pause",
+        START => "Start",
+        ENABLE => "Enable",
+        DISABLE => "Disable",
+        FLUSH => "Flush",
+        DRAIN => "Drain",
+        MARKER => "Marker",
+        PARAM_BEGIN => "ParamBegin",
+        PARAM_END => "ParamEnd",
+        REQUEST_PROCESS => "RequestProcess",
+    }
+
+    /// Keys of [`id::ObjectType::PROP_INFO`] objects, like
+    /// `Spa:Pod:Object:Param:PropInfo:name`.
+    pub static PROP_INFO: PropInfo = "Spa:Pod:Object:Param:PropInfo" {
+        ID => "id",
+        NAME => "name",
+        TYPE => "type",
+        LABELS => "labels",
+        CONTAINER => "container",
+        PARAMS => "params",
+        DESCRIPTION => "description",
+    }
+
+    /// Keys of [`id::ObjectType::PROPS`] objects, like
+    /// `Spa:Pod:Object:Param:Props:volume`.
+    pub static PROPS: Prop = "Spa:Pod:Object:Param:Props" {
+        DEVICE => "device",
+        DEVICE_NAME => "deviceName",
+        DEVICE_FD => "deviceFd",
+        CARD => "card",
+        CARD_NAME => "cardName",
+        MIN_LATENCY => "minLatency",
+        MAX_LATENCY => "maxLatency",
+        PERIODS => "periods",
+        PERIOD_SIZE => "periodSize",
+        PERIOD_EVENT => "periodEvent",
+        LIVE => "live",
+        RATE => "rate",
+        QUALITY => "quality",
+        BLUETOOTH_AUDIO_CODEC => "bluetoothAudioCodec",
+        BLUETOOTH_OFFLOAD_ACTIVE => "bluetoothOffloadActive",
+        WAVE_TYPE => "waveType",
+        FREQUENCY => "frequency",
+        VOLUME => "volume",
+        MUTE => "mute",
+        PATTERN_TYPE => "patternType",
+        DITHER_TYPE => "ditherType",
+        TRUNCATE => "truncate",
+        CHANNEL_VOLUMES => "channelVolumes",
+        VOLUME_BASE => "volumeBase",
+        VOLUME_STEP => "volumeStep",
+        CHANNEL_MAP => "channelMap",
+        MONITOR_MUTE => "monitorMute",
+        MONITOR_VOLUMES => "monitorVolumes",
+        LATENCY_OFFSET_NSEC => "latencyOffsetNsec",
+        SOFT_MUTE => "softMute",
+        SOFT_VOLUMES => "softVolumes",
+        IEC958_CODECS => "iec958Codecs",
+        VOLUME_RAMP_SAMPLES => "volumeRampSamples",
+        VOLUME_RAMP_STEP_SAMPLES => "volumeRampStepSamples",
+        VOLUME_RAMP_TIME => "volumeRampTime",
+        VOLUME_RAMP_STEP_TIME => "volumeRampStepTime",
+        VOLUME_RAMP_SCALE => "volumeRampScale",
+        BRIGHTNESS => "brightness",
+        CONTRAST => "contrast",
+        SATURATION => "saturation",
+        HUE => "hue",
+        GAMMA => "gamma",
+        EXPOSURE => "exposure",
+        GAIN => "gain",
+        SHARPNESS => "sharpness",
+        PARAMS => "params",
+    }
+
+    /// Keys of [`id::ObjectType::FORMAT`] objects, like
+    /// `Spa:Pod:Object:Param:Format:Audio:rate`.
+    pub static FORMAT: Format = "Spa:Pod:Object:Param:Format" {
+        MEDIA_TYPE => "mediaType",
+        MEDIA_SUB_TYPE => "mediaSubtype",
+        AUDIO_FORMAT => "Audio:format",
+        AUDIO_FLAGS => "Audio:flags",
+        AUDIO_RATE => "Audio:rate",
+        AUDIO_CHANNELS => "Audio:channels",
+        AUDIO_POSITION => "Audio:position",
+        AUDIO_IEC958_CODEC => "Audio:iec958Codec",
+        AUDIO_BITORDER => "Audio:bitorder",
+        AUDIO_INTERLEAVE => "Audio:interleave",
+        AUDIO_BITRATE => "Audio:bitrate",
+        AUDIO_BLOCK_ALIGN => "Audio:blockAlign",
+        AUDIO_AAC_STREAM_FORMAT => "Audio:AAC:streamFormat",
+        AUDIO_WMA_PROFILE => "Audio:WMA:profile",
+        AUDIO_AMR_BAND_MODE => "Audio:AMR:bandMode",
+        VIDEO_FORMAT => "Video:format",
+        VIDEO_MODIFIER => "Video:modifier",
+        VIDEO_SIZE => "Video:size",
+        VIDEO_FRAMERATE => "Video:framerate",
+        VIDEO_MAX_FRAMERATE => "Video:maxFramerate",
+        VIDEO_VIEWS => "Video:views",
+        VIDEO_INTERLACE_MODE => "Video:interlaceMode",
+        VIDEO_PIXEL_ASPECT_RATIO => "Video:pixelAspectRatio",
+        VIDEO_MULTIVIEW_MODE => "Video:multiviewMode",
+        VIDEO_MULTIVIEW_FLAGS => "Video:multiviewFlags",
+        VIDEO_CHROMA_SITE => "Video:chromaSite",
+        VIDEO_COLOR_RANGE => "Video:colorRange",
+        VIDEO_COLOR_MATRIX => "Video:colorMatrix",
+        VIDEO_TRANSFER_FUNCTION => "Video:transferFunction",
+        VIDEO_COLOR_PRIMARIES => "Video:colorPrimaries",
+        VIDEO_PROFILE => "Video:profile",
+        VIDEO_LEVEL => "Video:level",
+        VIDEO_H264_STREAM_FORMAT => "Video:H264:streamFormat",
+        VIDEO_H264_ALIGNMENT => "Video:H264:alignment",
+        CONTROL_TYPES => "Control:types",
+    }
+
+    /// Keys of [`id::ObjectType::PARAM_BUFFERS`] objects, like
+    /// `Spa:Pod:Object:Param:Buffers:size`.
+    pub static PARAM_BUFFERS: ParamBuffers = "Spa:Pod:Object:Param:Buffers" {
+        BUFFERS => "buffers",
+        BLOCKS => "blocks",
+        SIZE => "size",
+        STRIDE => "stride",
+        ALIGN => "align",
+        DATA_TYPE => "dataType",
+        META_TYPE => "metaType",
+    }
+
+    /// Keys of [`id::ObjectType::PARAM_META`] objects, like
+    /// `Spa:Pod:Object:Param:Meta:type`.
+    pub static PARAM_META: ParamMeta = "Spa:Pod:Object:Param:Meta" {
+        TYPE => "type",
+        SIZE => "size",
+    }
+
+    /// Keys of [`id::ObjectType::PARAM_IO`] objects, like
+    /// `Spa:Pod:Object:Param:IO:id`.
+    pub static PARAM_IO: ParamIo = "Spa:Pod:Object:Param:IO" {
+        ID => "id",
+        SIZE => "size",
+    }
+
+    /// Keys of [`id::ObjectType::PARAM_ROUTE`] objects, like
+    /// `Spa:Pod:Object:Param:Route:index`.
+    pub static PARAM_ROUTE: ParamRoute = "Spa:Pod:Object:Param:Route" {
+        INDEX => "index",
+        DIRECTION => "direction",
+        DEVICE => "device",
+        NAME => "name",
+        DESCRIPTION => "description",
+        PRIORITY => "priority",
+        AVAILABLE => "available",
+        INFO => "info",
+        PROFILES => "profiles",
+        PROPS => "props",
+        DEVICES => "devices",
+        PROFILE => "profile",
+        SAVE => "save",
+    }
+
+    /// Keys of [`id::ObjectType::PARAM_LATENCY`] objects, like
+    /// `Spa:Pod:Object:Param:Latency:minQuantum`.
+    pub static PARAM_LATENCY: ParamLatency = "Spa:Pod:Object:Param:Latency" {
+        DIRECTION => "direction",
+        MIN_QUANTUM => "minQuantum",
+        MAX_QUANTUM => "maxQuantum",
+        MIN_RATE => "minRate",
+        MAX_RATE => "maxRate",
+        MIN_NS => "minNs",
+        MAX_NS => "maxNs",
+    }
+}
@@ -52,6 +52,12 @@ impl Header {
         self.size_with_op & 0xffffff
     }
 
+    /// Get the sequence number of the message.
+    #[inline]
+    pub fn seq(&self) -> u32 {
+        self.seq
+    }
+
     /// Get the number of file descriptors.
     #[inline]
     pub fn n_fds(&self) -> u32 {
@@ -0,0 +1,67 @@
+//! Tests for driving a connection through the tokio runtime.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::Duration;
+
+use protocol::buf::{RecvBuf, SendBuf};
+use protocol::{AsyncConnection, Connection, Error};
+
+fn block_on<F>(f: F) -> F::Output
+where
+    F: Future,
+{
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+
+    rt.block_on(f)
+}
+
+#[test]
+fn recv_waits_for_data() -> Result<(), Error> {
+    let (a, mut b) = UnixStream::pair().unwrap();
+
+    block_on(async move {
+        let mut c = AsyncConnection::new(Connection::from_socket(a))?;
+
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            b.write_all(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+            // NB: Keep the peer open, since a closed peer is an error.
+            b
+        });
+
+        let mut recv = RecvBuf::new();
+        let mut fds = [0; 4];
+
+        let n_fds = c.recv_with_fds(&mut recv, &mut fds).await?;
+        let _b = writer.join().unwrap();
+
+        assert_eq!(n_fds, 0);
+        assert_eq!(recv.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        Ok(())
+    })
+}
+
+#[test]
+fn send_all_delivers() -> Result<(), Error> {
+    let (a, mut b) = UnixStream::pair().unwrap();
+
+    block_on(async move {
+        let mut c = AsyncConnection::new(Connection::from_socket(a))?;
+
+        let mut outgoing = SendBuf::new();
+        outgoing.extend_from_words(&[1u64, 2]).unwrap();
+        c.send_all(&mut outgoing).await?;
+        assert!(outgoing.is_empty());
+
+        let mut buf = [0u8; 16];
+        b.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf[..8], 1u64.to_ne_bytes());
+        assert_eq!(&buf[8..], 2u64.to_ne_bytes());
+        Ok(())
+    })
+}
@@ -11,13 +11,12 @@ use anyhow::{Context, Result, bail};
 use client::events::{ObjectKind, RemovePortParamEvent, SetPortParamEvent, StreamEvent};
 use client::{ClientNode, MixId, Port, PortId, Stats, Stream};
 use pod::buf::ArrayVec;
-use pod::{ChoiceType, Type};
 use protocol::buf::RecvBuf;
 use protocol::consts::Direction;
 use protocol::flags::ChunkFlags;
 use protocol::poll::{Interest, PollEvent};
 use protocol::prop;
-use protocol::{Connection, Poll, TimerFd, ffi, object, param};
+use protocol::{Connection, ConnectionOptions, Poll, SignalFd, TimerFd, ffi, object, param};
 use protocol::{Properties, id};
 
 const BUFFER_SAMPLES: u32 = 128;
@@ -36,6 +35,9 @@ struct ExampleApplication {
     formats: HashMap<(Direction, PortId), object::AudioFormat>,
     accumulators: HashMap<PortId, f32>,
     inputs: HashMap<(PortId, MixId), InputBuffer>,
+    /// The mixed audio of each input port in the current cycle, which is
+    /// copied into monitor ports.
+    monitors: HashMap<PortId, Vec<f32>>,
     stats: Stats,
 }
 
@@ -50,6 +52,9 @@ impl ExampleApplication {
         };
 
         for port in node.ports.inputs_mut() {
+            let monitor = self.monitors.entry(port.id).or_default();
+            monitor.clear();
+
             let Some(format) = self.formats.get(&(port.direction, port.id)) else {
                 continue;
             };
@@ -81,26 +86,18 @@ impl ExampleApplication {
                 };
 
                 let buffer = ib.buffer_mut();
-                let _ = &buffer.metas[0];
-                let data = &buffer.datas[0];
-
-                unsafe {
-                    let Some(region) = data.valid_region() else {
-                        bail!("No valid memory region");
-                    };
-
-                    let region = region.cast_array::<f32>()?;
+                let samples = buffer.audio(format).as_planar_f32(0)?;
 
-                    b.buf.reserve(region.len());
-
-                    b.buf
-                        .as_mut_ptr()
-                        .add(b.buf.len())
-                        .copy_from_nonoverlapping(region.as_ptr(), region.len());
+                if monitor.len() < samples.len() {
+                    monitor.resize(samples.len(), 0.0);
+                }
 
-                    b.buf.set_len(b.buf.len() + region.len());
+                for (m, s) in monitor.iter_mut().zip(samples) {
+                    *m += *s;
                 }
 
+                b.buf.extend_from_slice(samples);
+
                 ib.need_data()?;
             }
         }
@@ -130,12 +127,21 @@ impl ExampleApplication {
             let mut region = data.uninit_region().cast_array::<MaybeUninit<f32>>()?;
             let samples = region.len().min(duration as usize);
 
-            for d in region.as_slice_mut().iter_mut().take(samples) {
-                d.write(accumulator.sin() * DEFAULT_VOLUME);
-                *accumulator += M_PI_M2 * TONE / format.rate as f32;
+            if let Some(input) = port.monitor {
+                let monitor = self.monitors.get(&input).map(Vec::as_slice);
+                let monitor = monitor.unwrap_or_default();
 
-                if *accumulator >= M_PI_M2 {
-                    *accumulator -= M_PI_M2;
+                for (n, d) in region.as_slice_mut().iter_mut().take(samples).enumerate() {
+                    d.write(monitor.get(n).copied().unwrap_or_default());
+                }
+            } else {
+                for d in region.as_slice_mut().iter_mut().take(samples) {
+                    d.write(accumulator.sin() * DEFAULT_VOLUME);
+                    *accumulator += M_PI_M2 * TONE / format.rate as f32;
+
+                    if *accumulator >= M_PI_M2 {
+                        *accumulator -= M_PI_M2;
+                    }
                 }
             }
 
@@ -161,6 +167,10 @@ impl ExampleApplication {
             self.stats.merge(this.stats_mut());
         }
 
+        for this in stream.nodes_mut() {
+            self.stats.publish(&mut this.params)?;
+        }
+
         for (&(port_id, mix_id), b) in &mut self.inputs {
             if b.format.format != id::AudioFormat::F32P {
                 b.buf.clear();
@@ -219,8 +229,11 @@ fn main() -> Result<()> {
 
     let mut poll = Poll::new()?;
 
-    let mut c = Connection::open()?;
-    c.set_nonblocking(true)?;
+    let options = ConnectionOptions::new().nonblocking(true);
+    let c = Connection::open_with(&options)?;
+
+    let signals = SignalFd::termination()?;
+    signals.set_nonblocking(true)?;
 
     let timer = TimerFd::new()?;
     timer.set_nonblocking(true)?;
@@ -230,10 +243,14 @@ fn main() -> Result<()> {
     properties.insert(prop::APPLICATION_NAME, "livemix");
 
     let mut stream = client::Stream::new(c, properties)?;
+    stream.set_reconnect(Some(options));
 
     let timer_token = stream.token()?;
     poll.add(timer.as_raw_fd(), timer_token, Interest::READ)?;
 
+    let signals_token = stream.token()?;
+    poll.add(signals.as_raw_fd(), signals_token, Interest::READ)?;
+
     let mut events = ArrayVec::<PollEvent, 4>::new();
     let mut recv = RecvBuf::new();
 
@@ -244,6 +261,7 @@ fn main() -> Result<()> {
         formats: HashMap::new(),
         accumulators: HashMap::new(),
         inputs: HashMap::new(),
+        monitors: HashMap::new(),
         stats,
     };
 
@@ -255,6 +273,8 @@ fn main() -> Result<()> {
 
                     properties.insert(prop::NODE_NAME, "livemix");
                     properties.insert(prop::NODE_DESCRIPTION, "Livemix I/O node");
+                    properties.insert(prop::NODE_NICK, "Livemix");
+                    properties.insert(prop::MEDIA_ICON_NAME, "audio-card");
                     properties.insert(prop::MEDIA_CLASS, "Audio/Duplex");
                     properties.insert(prop::MEDIA_TYPE, "Audio");
                     properties.insert(prop::MEDIA_CATEGORY, "Duplex");
@@ -284,6 +304,14 @@ fn main() -> Result<()> {
 
                         add_port_params(port)?;
 
+                        let input = port.id;
+                        let port = node.ports.insert_monitor(input)?;
+
+                        port.props
+                            .insert(prop::FORMAT_DSP, "32 bit float mono audio");
+
+                        add_port_params(port)?;
+
                         let port = node.ports.insert(Direction::OUTPUT)?;
 
                         port.props.insert(prop::PORT_NAME, "output");
@@ -307,26 +335,23 @@ fn main() -> Result<()> {
                     direction,
                     port_id,
                     param: id::Param::FORMAT,
+                    format: Some(format),
                     ..
-                }) => {
-                    let node = stream.node(node_id)?;
-                    let port = node.ports.get(direction, port_id)?;
-
-                    if let [param] = port.params.get(id::Param::FORMAT) {
-                        let format = param.value.as_ref().read::<object::Format>()?;
-
-                        match format.media_type {
-                            id::MediaType::AUDIO => {
-                                let audio_format =
-                                    param.value.as_ref().read::<object::AudioFormat>()?;
-                                app.formats.insert((direction, port_id), audio_format);
-                            }
-                            other => {
-                                tracing::error!(?other, "Unsupported media type on port");
-                            }
+                }) => match format.media_type {
+                    id::MediaType::AUDIO => {
+                        let node = stream.node(node_id)?;
+                        let port = node.ports.get(direction, port_id)?;
+
+                        if let [param] = port.params.get(id::Param::FORMAT) {
+                            let audio_format =
+                                param.value.as_ref().read::<object::AudioFormat>()?;
+                            app.formats.insert((direction, port_id), audio_format);
                         }
                     }
-                }
+                    other => {
+                        tracing::error!(?other, "Unsupported media type on port");
+                    }
+                },
                 StreamEvent::RemovePortParam(RemovePortParamEvent {
                     direction,
                     port_id,
@@ -336,6 +361,17 @@ fn main() -> Result<()> {
                     tracing::info!("Removed format parameter from port {direction}/{port_id}");
                     app.formats.remove(&(direction, port_id));
                 }
+                StreamEvent::Disconnected => {
+                    tracing::warn!("Disconnected, retrying every timer tick");
+                    app.formats.clear();
+                }
+                StreamEvent::Reconnected => {
+                    tracing::info!("Reconnected and re-created nodes");
+                }
+                StreamEvent::Shutdown => {
+                    tracing::info!("Shutdown complete");
+                    return Ok(());
+                }
                 _ => {
                     // Other events, ignore.
                 }
@@ -345,7 +381,11 @@ fn main() -> Result<()> {
         poll.poll(&mut events)?;
 
         while let Some(e) = events.pop() {
-            if e.interest.is_error() || e.interest.is_hup() {
+            // NB: The stream handles its own connection hanging up by
+            // reconnecting.
+            if (e.token == signals_token || e.token == timer_token)
+                && (e.interest.is_error() || e.interest.is_hup())
+            {
                 bail!(
                     "File descriptor with token {:?} and interest {:?} unexpectedly errored or huped",
                     e.token,
@@ -353,9 +393,34 @@ fn main() -> Result<()> {
                 );
             }
 
+            if e.token == signals_token {
+                if e.interest.is_read()
+                    && let Some(signal) = signals.read().context("reading signals")?
+                {
+                    tracing::info!(signal, "Shutting down");
+                    stream.shutdown()?;
+                }
+
+                continue;
+            }
+
             if e.token == timer_token {
                 if e.interest.is_read() {
                     timer.read().context("reading the timer")?;
+
+                    if !stream.is_connected() {
+                        stream.reconnect()?;
+                        continue;
+                    }
+
+                    stream.flush_warnings();
+
+                    if let Some(age) = stream.oldest_pending_ping()? {
+                        tracing::warn!(?age, "Server has not responded to ping");
+                    }
+
+                    stream.ping()?;
+                    tracing::debug!(control = ?stream.control_stats());
                     app.tick(&mut stream)?;
                 }
 
@@ -370,34 +435,18 @@ fn main() -> Result<()> {
 fn add_port_params(port: &mut Port) -> Result<()> {
     let mut pod = pod::array();
 
-    port.params.push(pod.clear_mut().embed_object(
-        id::ObjectType::FORMAT,
-        id::Param::ENUM_FORMAT,
-        |obj| {
-            obj.property(id::Format::MEDIA_TYPE)
-                .write(id::MediaType::AUDIO)?;
-            obj.property(id::Format::MEDIA_SUB_TYPE)
-                .write(id::MediaSubType::DSP)?;
-            obj.property(id::Format::AUDIO_FORMAT).write_choice(
-                ChoiceType::ENUM,
-                Type::ID,
-                |choice| {
-                    choice.write((
-                        id::AudioFormat::S16,
-                        id::AudioFormat::F32,
-                        id::AudioFormat::F32P,
-                    ))
-                },
-            )?;
-            obj.property(id::Format::AUDIO_CHANNELS).write(1)?;
-            obj.property(id::Format::AUDIO_RATE).write_choice(
-                ChoiceType::RANGE,
-                Type::INT,
-                |c| c.write((DEFAULT_RATE, 44100, 48000)),
-            )?;
-            Ok(())
-        },
-    )?)?;
+    port.params.push(
+        pod.clear_mut().embed(
+            param::EnumFormatBuilder::audio_dsp()
+                .format_any(&[
+                    id::AudioFormat::S16,
+                    id::AudioFormat::F32,
+                    id::AudioFormat::F32P,
+                ])
+                .channels(1)
+                .rate_range(44100, 48000, DEFAULT_RATE),
+        )?,
+    )?;
 
     port.params.push(pod.clear_mut().embed(param::Meta {
         ty: id::Meta::HEADER,
@@ -423,20 +472,15 @@ fn add_port_params(port: &mut Port) -> Result<()> {
         id::ObjectType::PARAM_BUFFERS,
         id::Param::BUFFERS,
         |obj| {
-            obj.property(id::ParamBuffers::BUFFERS).write_choice(
-                ChoiceType::RANGE,
-                Type::INT,
-                |choice| choice.write((1, 1, 32)),
-            )?;
+            obj.property(id::ParamBuffers::BUFFERS)
+                .write_range(1i32, 1, 32)?;
 
             obj.property(id::ParamBuffers::BLOCKS).write(1i32)?;
 
-            obj.property(id::ParamBuffers::SIZE).write_choice(
-                ChoiceType::RANGE,
-                Type::INT,
-                |choice| {
-                    choice.write((BUFFER_SAMPLES * mem::size_of::<f32>() as u32, 32, i32::MAX))
-                },
+            obj.property(id::ParamBuffers::SIZE).write_range(
+                32,
+                (BUFFER_SAMPLES * mem::size_of::<f32>() as u32) as i32,
+                i32::MAX,
             )?;
 
             obj.property(id::ParamBuffers::STRIDE)
@@ -0,0 +1,128 @@
+//! Mix a set of WAV files down to a stereo WAV file without PipeWire.
+//!
+//! Every channel of every input is passed through its own channel strip.
+//! Mono inputs are mixed into both output channels, while the channels of
+//! other inputs alternate between left and right.
+//!
+//! ```sh
+//! cargo run --example offline_mixdown -- out.wav a.wav b.wav
+//! ```
+
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use client::ChannelStrip;
+use client::offline::{MemorySource, Offline};
+
+const QUANTUM: usize = 1024;
+
+/// Read a WAV file into one buffer per channel.
+fn read_channels(path: &PathBuf) -> Result<(u32, Vec<Vec<f32>>)> {
+    let mut reader =
+        hound::WavReader::open(path).with_context(|| format!("Opening {}", path.display()))?;
+
+    let spec = reader.spec();
+    let channels = usize::from(spec.channels);
+
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 / scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    let mut out = vec![Vec::with_capacity(samples.len() / channels); channels];
+
+    for frame in samples.chunks_exact(channels) {
+        for (channel, sample) in out.iter_mut().zip(frame) {
+            channel.push(*sample);
+        }
+    }
+
+    Ok((spec.sample_rate, out))
+}
+
+fn main() -> Result<()> {
+    let mut args = env::args_os().skip(1).map(PathBuf::from);
+
+    let Some(output) = args.next() else {
+        bail!("Usage: offline_mixdown <output> <input>...");
+    };
+
+    let mut rate = None;
+    let mut offline = None;
+    let mut channels = Vec::new();
+
+    for path in args {
+        let (file_rate, file_channels) = read_channels(&path)?;
+        let rate = *rate.get_or_insert(file_rate);
+        let offline = match &mut offline {
+            Some(offline) => offline,
+            None => offline.insert(Offline::new(rate, QUANTUM)?),
+        };
+
+        let mono = file_channels.len() == 1;
+
+        for (index, samples) in file_channels.into_iter().enumerate() {
+            let input = offline
+                .add_input(MemorySource::new(file_rate, samples))
+                .with_context(|| format!("Adding {}", path.display()))?;
+
+            let pan = match (mono, index % 2) {
+                (true, _) => [0.5, 0.5],
+                (false, 0) => [1.0, 0.0],
+                (false, _) => [0.0, 1.0],
+            };
+
+            channels.push((input, ChannelStrip::new(rate), pan));
+        }
+    }
+
+    let (Some(rate), Some(mut offline)) = (rate, offline) else {
+        bail!("No inputs");
+    };
+
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+
+    let mut writer = hound::WavWriter::create(&output, spec)
+        .with_context(|| format!("Creating {}", output.display()))?;
+
+    let mut buf = vec![0.0; QUANTUM];
+    let mut mix = vec![[0.0f32; 2]; QUANTUM];
+
+    let rendered = offline.run(|cycle| {
+        mix.fill([0.0; 2]);
+
+        for (input, strip, pan) in &mut channels {
+            buf.copy_from_slice(cycle.input(*input));
+            strip.process(&mut buf);
+
+            for (frame, sample) in mix.iter_mut().zip(&buf) {
+                frame[0] += sample * pan[0];
+                frame[1] += sample * pan[1];
+            }
+        }
+
+        for frame in &mix[..cycle.len()] {
+            writer.write_sample(frame[0])?;
+            writer.write_sample(frame[1])?;
+        }
+
+        Ok(())
+    })?;
+
+    writer.finalize()?;
+    println!("Rendered {rendered} samples to {}", output.display());
+    Ok(())
+}
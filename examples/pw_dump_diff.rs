@@ -0,0 +1,115 @@
+//! Compare the registry and the parameters of nodes with a concurrent run of
+//! `pw-dump`, reporting any divergences.
+//!
+//! ```sh
+//! cargo run --example pw_dump_diff
+//! ```
+
+use std::os::fd::AsRawFd;
+use std::process::{Command, ExitCode};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use client::Stream;
+use client::dump::{self, Dump};
+use client::events::StreamEvent;
+use pod::buf::ArrayVec;
+use protocol::buf::RecvBuf;
+use protocol::poll::{Interest, PollEvent};
+use protocol::{Connection, ConnectionOptions, Poll, Properties, TimerFd, id, prop};
+
+/// The parameters of nodes which are compared.
+const PARAMS: &[id::Param] = &[id::Param::ENUM_FORMAT, id::Param::FORMAT, id::Param::PROPS];
+
+fn main() -> Result<ExitCode> {
+    tracing_subscriber::fmt::try_init().map_err(anyhow::Error::msg)?;
+
+    let mut poll = Poll::new()?;
+
+    let c = Connection::open_with(&ConnectionOptions::new().nonblocking(true))?;
+
+    let mut properties = Properties::new();
+    properties.insert(prop::APPLICATION_NAME, "livemix-pw-dump-diff");
+
+    let mut stream = Stream::new(c, properties)?;
+
+    // NB: The timer gives parameter subscriptions time to settle before the
+    // comparison is made.
+    let timer = TimerFd::new()?;
+    timer.set_nonblocking(true)?;
+
+    let timer_token = stream.token()?;
+    poll.add(timer.as_raw_fd(), timer_token, Interest::READ)?;
+
+    let mut events = ArrayVec::<PollEvent, 4>::new();
+    let mut recv = RecvBuf::new();
+
+    loop {
+        while let Some(ev) = stream.run(&mut poll, &mut recv)? {
+            if let StreamEvent::Started = ev {
+                let nodes = stream
+                    .registry()
+                    .nodes()
+                    .map(|node| node.id())
+                    .collect::<Vec<_>>();
+
+                for node in nodes {
+                    // NB: The parameters of nodes which can't be bound are not
+                    // compared.
+                    let Ok(proxy_id) = stream.bind(node) else {
+                        continue;
+                    };
+
+                    stream.subscribe_params(proxy_id, PARAMS)?;
+                }
+
+                timer.set_interval(Duration::from_secs(1))?;
+            }
+        }
+
+        poll.poll(&mut events)?;
+
+        while let Some(e) = events.pop() {
+            if e.interest.is_error() || e.interest.is_hup() {
+                bail!("File descriptor with token {:?} errored", e.token);
+            }
+
+            if e.token == timer_token {
+                timer.read().context("reading the timer")?;
+                return compare(&stream);
+            }
+
+            stream.drive(&mut recv, e)?;
+        }
+    }
+}
+
+fn compare(stream: &Stream) -> Result<ExitCode> {
+    let output = Command::new("pw-dump")
+        .output()
+        .context("running pw-dump")?;
+
+    if !output.status.success() {
+        bail!("pw-dump failed with {}", output.status);
+    }
+
+    let dump = Dump::parse(&String::from_utf8(output.stdout)?).context("parsing pw-dump")?;
+    let divergences = dump::diff(stream.registry(), stream.proxies(), &dump);
+
+    for divergence in &divergences {
+        println!("{divergence}");
+    }
+
+    println!(
+        "{} objects in the registry, {} in the dump, {} divergences",
+        stream.registry().len(),
+        dump.len(),
+        divergences.len()
+    );
+
+    if divergences.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
@@ -0,0 +1,262 @@
+//! The `livemix` command line tool.
+//!
+//! ```sh
+//! livemix dump > dump.json
+//! livemix verify livemix-verify
+//! livemix trace record trace.podc
+//! livemix trace view trace.podc
+//! ```
+
+mod trace;
+mod verify;
+
+use std::env;
+use std::error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use client::Stream;
+use client::dump;
+use client::events::StreamEvent;
+use pod::buf::ArrayVec;
+use protocol::buf::RecvBuf;
+use protocol::poll::PollEvent;
+use protocol::{Connection, ConnectionOptions, Poll, Properties, id, prop};
+
+const USAGE: &str = "Usage: livemix <command>
+
+Commands:
+  dump                  Print all registry globals with their properties and
+                        params as JSON
+  verify <sink> [--tolerance <error>]
+                        Play test vectors through a sink which passes audio
+                        through, like a null sink, and compare the capture of
+                        its monitor. The capture must be bit-exact unless a
+                        tolerance is given
+  trace record <file> [--seconds <n>]
+                        Record received protocol messages and registry globals
+                        to a trace file, for 10 seconds by default
+  trace view <file> [--interface <name>] [--id <id>]
+                        Print a recorded trace on a timeline";
+
+/// The parameters which are dumped for nodes.
+const NODE_PARAMS: &[id::Param] = &[
+    id::Param::PROP_INFO,
+    id::Param::PROPS,
+    id::Param::ENUM_FORMAT,
+    id::Param::FORMAT,
+    id::Param::ENUM_PORT_CONFIG,
+    id::Param::PORT_CONFIG,
+    id::Param::LATENCY,
+    id::Param::PROCESS_LATENCY,
+    id::Param::TAG,
+];
+
+/// The parameters which are dumped for devices.
+const DEVICE_PARAMS: &[id::Param] = &[
+    id::Param::PROP_INFO,
+    id::Param::PROPS,
+    id::Param::ENUM_PROFILE,
+    id::Param::PROFILE,
+    id::Param::ENUM_ROUTE,
+    id::Param::ROUTE,
+];
+
+fn main() -> Result<ExitCode> {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(anyhow::Error::msg)?;
+
+    let mut args = env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("dump") => {
+            dump()?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some("verify") => {
+            let Some(sink) = args.next() else {
+                eprintln!("Missing sink\n\n{USAGE}");
+                return Ok(ExitCode::FAILURE);
+            };
+
+            let mut options = verify::Options {
+                sink,
+                tolerance: 0.0,
+            };
+
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--tolerance" => {
+                        options.tolerance = value(&mut args, "--tolerance")?;
+                    }
+                    arg => {
+                        eprintln!("Unknown argument `{arg}`\n\n{USAGE}");
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            }
+
+            verify::verify(options)
+        }
+        Some("trace") => match args.next().as_deref() {
+            Some("record") => {
+                let Some(path) = args.next() else {
+                    eprintln!("Missing file\n\n{USAGE}");
+                    return Ok(ExitCode::FAILURE);
+                };
+
+                let mut options = trace::RecordOptions {
+                    path: PathBuf::from(path),
+                    duration: Duration::from_secs(10),
+                };
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--seconds" => {
+                            let seconds = value(&mut args, "--seconds")?;
+                            options.duration = Duration::from_secs(seconds);
+                        }
+                        arg => {
+                            eprintln!("Unknown argument `{arg}`\n\n{USAGE}");
+                            return Ok(ExitCode::FAILURE);
+                        }
+                    }
+                }
+
+                trace::record(options)?;
+                Ok(ExitCode::SUCCESS)
+            }
+            Some("view") => {
+                let Some(path) = args.next() else {
+                    eprintln!("Missing file\n\n{USAGE}");
+                    return Ok(ExitCode::FAILURE);
+                };
+
+                let mut options = trace::ViewOptions {
+                    path: PathBuf::from(path),
+                    interface: None,
+                    id: None,
+                };
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--interface" => {
+                            options.interface = Some(value(&mut args, "--interface")?);
+                        }
+                        "--id" => {
+                            options.id = Some(value(&mut args, "--id")?);
+                        }
+                        arg => {
+                            eprintln!("Unknown argument `{arg}`\n\n{USAGE}");
+                            return Ok(ExitCode::FAILURE);
+                        }
+                    }
+                }
+
+                trace::view(options)?;
+                Ok(ExitCode::SUCCESS)
+            }
+            _ => {
+                eprintln!("{USAGE}");
+                Ok(ExitCode::FAILURE)
+            }
+        },
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(command) => {
+            eprintln!("Unknown command `{command}`\n\n{USAGE}");
+            Ok(ExitCode::FAILURE)
+        }
+        None => {
+            eprintln!("{USAGE}");
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Parse the value of a command line option.
+fn value<T>(args: &mut impl Iterator<Item = String>, option: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: error::Error + Send + Sync + 'static,
+{
+    let Some(value) = args.next() else {
+        bail!("Missing value for `{option}`");
+    };
+
+    value
+        .parse()
+        .with_context(|| format!("Bad value `{value}` for `{option}`"))
+}
+
+/// Connect to the server, bind every node and device which can be bound and
+/// print the registry as JSON once their parameters have been received.
+fn dump() -> Result<()> {
+    let mut poll = Poll::new()?;
+
+    let c = Connection::open_with(&ConnectionOptions::new().nonblocking(true))?;
+
+    let mut properties = Properties::new();
+    properties.insert(prop::APPLICATION_NAME, "livemix-dump");
+
+    let mut stream = Stream::new(c, properties)?;
+
+    let mut events = ArrayVec::<PollEvent, 4>::new();
+    let mut recv = RecvBuf::new();
+
+    // NB: The dump is printed once the server has answered a sync issued
+    // after every subscription, at which point the initial parameters of
+    // every subscription have been received.
+    let mut sync = None;
+
+    loop {
+        while let Some(ev) = stream.run(&mut poll, &mut recv)? {
+            match ev {
+                StreamEvent::Started => {
+                    let registry = stream.registry();
+
+                    let nodes = registry.nodes().map(|node| (node.id(), NODE_PARAMS));
+                    let devices = registry
+                        .devices()
+                        .map(|device| (device.id(), DEVICE_PARAMS));
+
+                    let globals = nodes.chain(devices).collect::<Vec<_>>();
+
+                    for (global_id, params) in globals {
+                        // NB: Globals which can't be bound are dumped without
+                        // parameters.
+                        let Ok(proxy_id) = stream.bind(global_id) else {
+                            continue;
+                        };
+
+                        stream.subscribe_params(proxy_id, params)?;
+                    }
+
+                    sync = Some(stream.sync()?);
+                }
+                StreamEvent::Synced(seq) if sync == Some(seq) => {
+                    println!("{}", dump::to_json(stream.registry(), stream.proxies()));
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        poll.poll(&mut events)?;
+
+        while let Some(e) = events.pop() {
+            if e.interest.is_error() || e.interest.is_hup() {
+                bail!("File descriptor with token {:?} errored", e.token);
+            }
+
+            stream.drive(&mut recv, e)?;
+        }
+    }
+}
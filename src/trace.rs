@@ -0,0 +1,488 @@
+//! Recording and viewing protocol traces.
+//!
+//! Traces are stored in the pod container format from [`pod::utils`], where
+//! each entry is a struct starting with the monotonic time in nanoseconds
+//! since recording started:
+//!
+//! * `message`: the time, the interface as a string, the receiver id, opcode
+//!   and the number of file descriptors passed with the message as ints, and
+//!   the fields of the message as bytes. Every message received from the
+//!   server is captured through [`Stream::set_capture_messages`].
+//! * `unknown-message`: like `message` but without the number of file
+//!   descriptors, which is only read from traces recorded by older versions.
+//! * `global-added`: the time, the global id, the type and the name of the
+//!   global as strings, which lets the viewer tell what objects were around.
+//! * `global-removed`: the time and the global id.
+//!
+//! The viewer decodes the fields of messages, resolving the names of param
+//! objects and their properties. File descriptors passed with messages and
+//! the lifecycle of memory mapped through `Core::AddMem` and
+//! `Core::RemoveMem` are shown on the timeline. Only messages received from
+//! the server are recorded, so messages and file descriptors sent by the
+//! client are not part of a trace.
+//!
+//! ```sh
+//! livemix trace record trace.podc --seconds 30
+//! livemix trace view trace.podc --interface client-node
+//! ```
+
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::fmt::Write as _;
+use std::fs;
+use std::os::fd::AsRawFd;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use client::Stream;
+use client::events::StreamEvent;
+use pod::buf::ArrayVec;
+use pod::utils::{Container, ContainerWriter};
+use pod::{Fd, Fraction, Id, Pod, Rectangle, Slice, Type, Value};
+use protocol::buf::RecvBuf;
+use protocol::poll::{Interest, PollEvent};
+use protocol::type_info::{self, TypeInfo};
+use protocol::{Connection, ConnectionOptions, Poll, Properties, TimerFd, id, op, prop};
+
+const MESSAGE: &str = "message";
+const UNKNOWN_MESSAGE: &str = "unknown-message";
+const GLOBAL_ADDED: &str = "global-added";
+const GLOBAL_REMOVED: &str = "global-removed";
+
+/// The options for recording a trace.
+pub(crate) struct RecordOptions {
+    /// The file to write the trace to.
+    pub(crate) path: PathBuf,
+    /// How long to record for.
+    pub(crate) duration: Duration,
+}
+
+/// The options for viewing a trace.
+pub(crate) struct ViewOptions {
+    /// The file to read the trace from.
+    pub(crate) path: PathBuf,
+    /// Only show messages addressed to the given interface.
+    pub(crate) interface: Option<String>,
+    /// Only show messages addressed to, and globals with, the given id.
+    pub(crate) id: Option<u32>,
+}
+
+/// Record a trace until the duration has elapsed, then write it to disk.
+pub(crate) fn record(options: RecordOptions) -> Result<()> {
+    let mut poll = Poll::new()?;
+
+    let c = Connection::open_with(&ConnectionOptions::new().nonblocking(true))?;
+
+    let timer = TimerFd::new()?;
+    timer.set_nonblocking(true)?;
+    timer.set_timeout(options.duration)?;
+
+    let mut properties = Properties::new();
+    properties.insert(prop::APPLICATION_NAME, "livemix-trace");
+
+    let mut stream = Stream::new(c, properties)?;
+    stream.set_capture_messages(true);
+
+    let timer_token = stream.token()?;
+    poll.add(timer.as_raw_fd(), timer_token, Interest::READ)?;
+
+    let mut events = ArrayVec::<PollEvent, 4>::new();
+    let mut recv = RecvBuf::new();
+
+    let mut writer = ContainerWriter::new();
+    let message = writer.register(MESSAGE)?;
+    let global_added = writer.register(GLOBAL_ADDED)?;
+    let global_removed = writer.register(GLOBAL_REMOVED)?;
+
+    let start = client::utils::get_monotonic_nsec()?;
+    let mut pod = pod::dynamic();
+    let mut count = 0usize;
+
+    loop {
+        while let Some(ev) = stream.run(&mut poll, &mut recv)? {
+            let now = client::utils::get_monotonic_nsec()?.saturating_sub(start);
+
+            let kind = match &ev {
+                StreamEvent::Message(m) => {
+                    pod.clear_mut().write_struct(|st| {
+                        st.field().write(now)?;
+                        st.field().write(m.interface)?;
+                        st.field().write(m.id)?;
+                        st.field().write(u32::from(m.op))?;
+                        st.field().write(m.fds)?;
+                        st.field().write_unsized(&m.payload[..])?;
+                        Ok(())
+                    })?;
+
+                    message
+                }
+                StreamEvent::GlobalAdded(global) => {
+                    let props = global.props();
+
+                    let name = props
+                        .get(prop::NODE_NAME)
+                        .or_else(|| props.get(prop::DEVICE_NAME))
+                        .or_else(|| props.get(prop::METADATA_NAME))
+                        .unwrap_or_default();
+
+                    pod.clear_mut().write_struct(|st| {
+                        st.field().write(now)?;
+                        st.field().write(global.id())?;
+                        st.field().write(global.ty())?;
+                        st.field().write(name)?;
+                        Ok(())
+                    })?;
+
+                    global_added
+                }
+                StreamEvent::GlobalRemoved(global) => {
+                    pod.clear_mut().write_struct(|st| {
+                        st.field().write(now)?;
+                        st.field().write(global.id())?;
+                        Ok(())
+                    })?;
+
+                    global_removed
+                }
+                _ => continue,
+            };
+
+            writer.push(kind, pod.as_ref())?;
+            count += 1;
+        }
+
+        poll.poll(&mut events)?;
+
+        while let Some(e) = events.pop() {
+            if e.token == timer_token {
+                let bytes = writer.finish()?;
+
+                fs::write(&options.path, bytes)
+                    .with_context(|| format!("Writing {}", options.path.display()))?;
+
+                eprintln!("Recorded {count} entries to {}", options.path.display());
+                return Ok(());
+            }
+
+            if e.interest.is_error() || e.interest.is_hup() {
+                bail!("File descriptor with token {:?} errored", e.token);
+            }
+
+            stream.drive(&mut recv, e)?;
+        }
+    }
+}
+
+/// Print the entries of a trace on a timeline.
+pub(crate) fn view(options: ViewOptions) -> Result<()> {
+    let bytes =
+        fs::read(&options.path).with_context(|| format!("Reading {}", options.path.display()))?;
+
+    let container = Container::parse(&bytes)?;
+
+    // Memory mapped by the server, by memory id with the time it was added.
+    let mut memory = BTreeMap::<u32, f64>::new();
+
+    for entry in container.entries() {
+        let entry = entry?;
+
+        let mut st = entry.as_pod().read_struct()?;
+        let now = st.field()?.read_sized::<u64>()?;
+        let time = now as f64 / 1e9;
+
+        match container.type_name(entry.kind()) {
+            Some(kind @ (MESSAGE | UNKNOWN_MESSAGE)) => {
+                let interface = st.field()?.read_unsized::<str>()?;
+                let id = st.field()?.read_sized::<u32>()?;
+                let code = st.field()?.read_sized::<u32>()?;
+
+                let fds = match kind {
+                    MESSAGE => st.field()?.read_sized::<u32>()?,
+                    _ => 0,
+                };
+
+                let payload = st.field()?.read_unsized::<[u8]>()?;
+
+                let lifecycle = match interface {
+                    "core" => mem_lifecycle(&mut memory, time, code, payload),
+                    _ => None,
+                };
+
+                if options.interface.as_deref().is_some_and(|i| i != interface)
+                    || options.id.is_some_and(|i| i != id)
+                {
+                    continue;
+                }
+
+                let unknown = if kind == UNKNOWN_MESSAGE {
+                    " unknown"
+                } else {
+                    ""
+                };
+
+                println!(
+                    "{time:12.6} {interface}#{id} {}{unknown} ({} bytes)",
+                    op_name(interface, code),
+                    payload.len()
+                );
+
+                if fds > 0 {
+                    let fds = (0..fds).map(|n| format!("#{n}")).collect::<Vec<_>>();
+                    println!("{:12}   <- fd {}", "", fds.join(", "));
+                }
+
+                match fields(payload) {
+                    Ok(fields) => {
+                        for field in fields {
+                            println!("{:12}   {field}", "");
+                        }
+                    }
+                    Err(..) => {
+                        for line in pod::utils::hexdump(payload).to_string().lines() {
+                            println!("{:12}   {line}", "");
+                        }
+                    }
+                }
+
+                if let Some(lifecycle) = lifecycle {
+                    println!("{:12}   {lifecycle}", "");
+                }
+            }
+            Some(GLOBAL_ADDED) => {
+                let id = st.field()?.read_sized::<u32>()?;
+                let ty = st.field()?.read_unsized::<str>()?;
+                let name = st.field()?.read_unsized::<str>()?;
+
+                if options.interface.is_some() || options.id.is_some_and(|i| i != id) {
+                    continue;
+                }
+
+                println!("{time:12.6} + global {id} {ty} {name:?}");
+            }
+            Some(GLOBAL_REMOVED) => {
+                let id = st.field()?.read_sized::<u32>()?;
+
+                if options.interface.is_some() || options.id.is_some_and(|i| i != id) {
+                    continue;
+                }
+
+                println!("{time:12.6} - global {id}");
+            }
+            // NB: Entries from newer versions of the trace format are skipped.
+            _ => {}
+        }
+    }
+
+    if options.interface.as_deref().is_none_or(|i| i == "core") && options.id.is_none() {
+        for (mem, added) in memory {
+            println!("{:12} memory {mem} still mapped since {added:.6}", "");
+        }
+    }
+
+    Ok(())
+}
+
+/// Track memory which is added or removed by a core message, returning a
+/// description of the change.
+fn mem_lifecycle(
+    memory: &mut BTreeMap<u32, f64>,
+    time: f64,
+    code: u32,
+    payload: &[u8],
+) -> Option<String> {
+    let code = u8::try_from(code).ok()?;
+    let mut pod = Pod::new(Slice::new(payload));
+
+    match op::CoreEvent::from_raw(code) {
+        op::CoreEvent::ADD_MEM => {
+            let mem = pod.as_mut().into_value().ok()?.read_sized::<u32>().ok()?;
+            let ty = pod
+                .as_mut()
+                .into_value()
+                .ok()?
+                .read_sized::<id::DataType>()
+                .ok()?;
+            let fd = pod.as_mut().into_value().ok()?.read_sized::<Fd>().ok()?;
+
+            // NB: Memory added without a file descriptor is removed.
+            if fd.fd() < 0 {
+                let added = memory.remove(&mem)?;
+                return Some(format!("memory {mem} unmapped after {:.6}s", time - added));
+            }
+
+            let ty = match type_info::DATA_TYPE.short_name(ty.into_id()) {
+                Some(name) => name.to_string(),
+                None => ty.into_id().to_string(),
+            };
+
+            memory.insert(mem, time);
+            Some(format!("memory {mem} mapped from {ty} fd #{}", fd.fd()))
+        }
+        op::CoreEvent::REMOVE_MEM => {
+            let mem = pod.as_mut().into_value().ok()?.read_sized::<u32>().ok()?;
+            let added = memory.remove(&mem)?;
+            Some(format!("memory {mem} unmapped after {:.6}s", time - added))
+        }
+        _ => None,
+    }
+}
+
+/// Describe the fields of a message.
+fn fields(payload: &[u8]) -> Result<Vec<String>> {
+    let mut pod = Pod::new(Slice::new(payload));
+    let mut fields = Vec::new();
+
+    while !pod.is_empty() {
+        let mut out = String::new();
+        describe(&mut out, pod.as_mut().into_value()?, None)?;
+        fields.push(out);
+    }
+
+    Ok(fields)
+}
+
+/// Describe a value, resolving identifiers through the given table of names
+/// and the names of param objects and their properties.
+fn describe(out: &mut String, value: Value<Slice<'_>>, names: Option<&TypeInfo>) -> Result<()> {
+    match value.ty() {
+        Type::NONE => out.push_str("none"),
+        Type::BOOL => write!(out, "{}", value.read_sized::<bool>()?)?,
+        Type::ID => {
+            let Id(id) = value.read_sized::<Id<u32>>()?;
+
+            match names.and_then(|names| names.short_name(id)) {
+                Some(name) => out.push_str(name),
+                None => write!(out, "id {id}")?,
+            }
+        }
+        Type::INT => write!(out, "{}", value.read_sized::<i32>()?)?,
+        Type::LONG => write!(out, "{}", value.read_sized::<i64>()?)?,
+        Type::FLOAT => write!(out, "{}", value.read_sized::<f32>()?)?,
+        Type::DOUBLE => write!(out, "{}", value.read_sized::<f64>()?)?,
+        Type::STRING => write!(out, "{:?}", value.read_unsized::<CStr>()?)?,
+        Type::BYTES => write!(out, "{} bytes", value.read_unsized::<[u8]>()?.len())?,
+        Type::RECTANGLE => {
+            let rect = value.read_sized::<Rectangle>()?;
+            write!(out, "{}x{}", rect.width, rect.height)?;
+        }
+        Type::FRACTION => {
+            let fraction = value.read_sized::<Fraction>()?;
+            write!(out, "{}/{}", fraction.num, fraction.denom)?;
+        }
+        Type::FD => match value.read_sized::<Fd>()?.fd() {
+            fd if fd < 0 => out.push_str("no fd"),
+            fd => write!(out, "fd #{fd}")?,
+        },
+        Type::ARRAY => {
+            let mut array = value.read_array()?;
+            out.push('[');
+
+            let mut first = true;
+
+            while let Some(value) = array.next()? {
+                if !first {
+                    out.push_str(", ");
+                }
+
+                describe(out, value, names)?;
+                first = false;
+            }
+
+            out.push(']');
+        }
+        Type::STRUCT => {
+            let mut st = value.read_struct()?;
+            out.push('(');
+
+            let mut first = true;
+
+            while !st.is_empty() {
+                if !first {
+                    out.push_str(", ");
+                }
+
+                describe(out, st.field()?, None)?;
+                first = false;
+            }
+
+            out.push(')');
+        }
+        Type::OBJECT => {
+            let mut object = value.read_object()?;
+            let object_type = object.object_type::<id::ObjectType>();
+            let object_id = object.object_id::<u32>();
+
+            match type_info::OBJECT_TYPE.short_name(object_type.into_id()) {
+                Some(name) => out.push_str(name),
+                None => write!(out, "object {}", object_type.into_id())?,
+            }
+
+            match type_info::PARAM.short_name(object_id) {
+                Some(name) => write!(out, "({name})")?,
+                None => write!(out, "({object_id})")?,
+            }
+
+            let keys = type_info::keys(object_type);
+            out.push_str(" {");
+
+            let mut first = true;
+
+            while !object.is_empty() {
+                let prop = object.property()?;
+                let key = prop.key::<u32>();
+
+                out.push_str(if first { " " } else { ", " });
+
+                match keys.and_then(|keys| keys.short_name(key)) {
+                    Some(name) => write!(out, "{name}: ")?,
+                    None => write!(out, "{key}: ")?,
+                }
+
+                describe(out, prop.value(), type_info::values(object_type, key))?;
+                first = false;
+            }
+
+            out.push_str(" }");
+        }
+        Type::CHOICE => {
+            let mut choice = value.read_choice()?;
+            write!(out, "{:?}", choice.choice_type())?;
+            out.push('[');
+
+            let mut first = true;
+
+            while let Some(value) = choice.next() {
+                if !first {
+                    out.push_str(", ");
+                }
+
+                describe(out, value, names)?;
+                first = false;
+            }
+
+            out.push(']');
+        }
+        _ => write!(out, "{value:?}")?,
+    }
+
+    Ok(())
+}
+
+/// Resolve the name of an event opcode sent to the given interface.
+fn op_name(interface: &str, code: u32) -> String {
+    let Ok(code) = u8::try_from(code) else {
+        return format!("op {code}");
+    };
+
+    match interface {
+        "core" => op::CoreEvent::from_raw(code).to_string(),
+        "client" => op::ClientEvent::from_raw(code).to_string(),
+        "registry" => op::RegistryEvent::from_raw(code).to_string(),
+        "client-node" => op::ClientNodeEvent::from_raw(code).to_string(),
+        "node" => op::NodeEvent::from_raw(code).to_string(),
+        "device" => op::DeviceEvent::from_raw(code).to_string(),
+        "metadata" => op::MetadataEvent::from_raw(code).to_string(),
+        _ => format!("op {code}"),
+    }
+}
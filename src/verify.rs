@@ -0,0 +1,482 @@
+//! End-to-end verification of buffer handling through a loopback.
+//!
+//! A playback node plays a known test vector into a sink, while a capture node
+//! records the monitor of the same sink. Once enough has been captured the
+//! capture is aligned against the test vector and compared sample by sample.
+//!
+//! The sink is expected to pass audio through unchanged, like a null sink:
+//!
+//! ```sh
+//! pw-cli create-node adapter '{ factory.name=support.null-audio-sink node.name=livemix-verify media.class=Audio/Sink audio.position=[MONO] }'
+//! livemix verify livemix-verify
+//! ```
+
+use std::collections::HashMap;
+use std::mem::{self, MaybeUninit};
+use std::os::fd::AsRawFd;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use client::events::{ObjectKind, RemovePortParamEvent, SetPortParamEvent, StreamEvent};
+use client::{ClientNode, ClientNodeId, Port, PortId, Stream};
+use pod::buf::ArrayVec;
+use protocol::buf::RecvBuf;
+use protocol::consts::Direction;
+use protocol::flags::ChunkFlags;
+use protocol::poll::{Interest, PollEvent};
+use protocol::{Connection, ConnectionOptions, Poll, Properties, TimerFd, ffi, id, object};
+use protocol::{param, prop};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const BUFFER_SAMPLES: u32 = 128;
+const DEFAULT_RATE: u32 = 48000;
+/// The amplitude of the marker which the capture is aligned by.
+const MARKER: f32 = 0.5;
+/// How long the capture is allowed to lag behind playback.
+const MAX_LATENCY: Duration = Duration::from_secs(1);
+/// How long to wait for the capture to complete.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The options of the verification.
+pub(crate) struct Options {
+    /// The name of the sink to play through.
+    pub(crate) sink: String,
+    /// The largest error allowed per sample, where zero requires the capture
+    /// to be bit-exact.
+    pub(crate) tolerance: f32,
+}
+
+/// The result of comparing a capture against the test vector.
+#[derive(Debug)]
+struct Report {
+    /// The number of samples the capture lags behind playback.
+    latency: usize,
+    /// The largest error of any sample.
+    max_error: f32,
+    /// The index in the test vector of the largest error.
+    max_error_at: usize,
+    /// The number of samples whose error exceeds the tolerance.
+    failed: usize,
+}
+
+/// Generate the test vector played at the given rate.
+///
+/// The vector starts with a single marker sample followed by silence, after
+/// which a sine, seeded noise, a ramp and an impulse train are played for a
+/// quarter of a second each.
+fn test_vector(rate: u32) -> Vec<f32> {
+    let rate = rate.max(1) as usize;
+    let section = rate / 4;
+
+    let mut out = Vec::with_capacity(64 + section * 4);
+
+    out.push(MARKER);
+    out.resize(64, 0.0);
+
+    let step = std::f32::consts::TAU * 997.0 / rate as f32;
+    out.extend((0..section).map(|n| (n as f32 * step).sin() * 0.25));
+
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    out.extend((0..section).map(|_| rng.random_range(-0.25..0.25)));
+
+    out.extend((0..section).map(|n| n as f32 / section as f32 * 0.5 - 0.25));
+
+    out.extend((0..section).map(|n| if n % 480 == 0 { 0.375 } else { 0.0 }));
+    out
+}
+
+/// Align a capture against the test vector by its marker and compare it.
+fn compare(vector: &[f32], captured: &[f32], tolerance: f32) -> Result<Report> {
+    let Some(latency) = captured.iter().position(|s| s.abs() >= MARKER / 2.0) else {
+        bail!(
+            "The marker was not found in {} captured samples",
+            captured.len()
+        );
+    };
+
+    let Some(captured) = captured.get(latency..latency + vector.len()) else {
+        bail!(
+            "Capture is truncated, expected {} samples after a latency of {latency}",
+            vector.len()
+        );
+    };
+
+    let mut report = Report {
+        latency,
+        max_error: 0.0,
+        max_error_at: 0,
+        failed: 0,
+    };
+
+    for (n, (expected, actual)) in vector.iter().zip(captured).enumerate() {
+        let error = (expected - actual).abs();
+
+        if error > report.max_error {
+            report.max_error = error;
+            report.max_error_at = n;
+        }
+
+        if error > tolerance {
+            report.failed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+struct Verify {
+    tolerance: f32,
+    formats: HashMap<(Direction, PortId), object::AudioFormat>,
+    output: Option<ClientNodeId>,
+    input: Option<ClientNodeId>,
+    vector: Option<Vec<f32>>,
+    /// The position of playback in the test vector, which is set once the
+    /// capture is running so that the marker isn't missed.
+    played: Option<usize>,
+    captured: Vec<f32>,
+}
+
+impl Verify {
+    fn format(&self, direction: Direction, port_id: PortId) -> Option<&object::AudioFormat> {
+        let format = self.formats.get(&(direction, port_id))?;
+
+        if format.channels != 1 || format.format != id::AudioFormat::F32P || format.rate == 0 {
+            tracing::warn!(?format, "Unsupported format on port");
+            return None;
+        }
+
+        Some(format)
+    }
+
+    /// Test if enough has been captured to compare against the test vector.
+    fn is_complete(&self) -> bool {
+        let Some(vector) = &self.vector else {
+            return false;
+        };
+
+        let Some(format) = self.formats.values().next() else {
+            return false;
+        };
+
+        let slack = (format.rate as u128 * MAX_LATENCY.as_millis() / 1000) as usize;
+        self.captured.len() >= vector.len() + slack
+    }
+
+    fn process_output(&mut self, node: &mut ClientNode) -> Result<()> {
+        node.start_process()?;
+
+        let Some(duration) = node.duration() else {
+            bail!("Clock duration is not configured on node")
+        };
+
+        for port in node.ports.outputs_mut() {
+            let Some(format) = self.format(port.direction, port.id) else {
+                continue;
+            };
+
+            let rate = format.rate;
+            let vector = self.vector.get_or_insert_with(|| test_vector(rate));
+
+            let Some(mut ob) = port.port_buffers.next_output(&mut port.mixes) else {
+                continue;
+            };
+
+            let data = &mut ob.buffer_mut().datas[0];
+
+            let mut region = data.uninit_region().cast_array::<MaybeUninit<f32>>()?;
+            let samples = region.len().min(duration as usize);
+
+            for d in region.as_slice_mut().iter_mut().take(samples) {
+                let sample = match &mut self.played {
+                    Some(n) => {
+                        let sample = vector.get(*n).copied().unwrap_or_default();
+                        *n += 1;
+                        sample
+                    }
+                    None => 0.0,
+                };
+
+                d.write(sample);
+            }
+
+            data.write_chunk(ffi::Chunk {
+                size: u32::try_from(samples.saturating_mul(mem::size_of::<f32>()))
+                    .unwrap_or(u32::MAX),
+                offset: 0,
+                stride: 4,
+                flags: ChunkFlags::NONE,
+            });
+
+            ob.have_data()?;
+        }
+
+        node.end_process()?;
+        Ok(())
+    }
+
+    fn process_input(&mut self, node: &mut ClientNode) -> Result<()> {
+        node.start_process()?;
+
+        for port in node.ports.inputs_mut() {
+            let Some(format) = self.format(port.direction, port.id).cloned() else {
+                continue;
+            };
+
+            for mix in port.mixes.iter_mut() {
+                let Some(mut ib) = port.port_buffers.next_input(mix) else {
+                    continue;
+                };
+
+                let samples = ib.buffer_mut().audio(&format).as_planar_f32(0)?;
+
+                // NB: Playback starts once the capture is running, so anything
+                // captured before then is silence.
+                if self.played.is_some() {
+                    self.captured.extend_from_slice(samples);
+                } else {
+                    self.played = Some(0);
+                }
+
+                ib.need_data()?;
+            }
+        }
+
+        node.end_process()?;
+        Ok(())
+    }
+}
+
+/// Play the test vector through the sink and compare the capture of its
+/// monitor against it.
+pub(crate) fn verify(options: Options) -> Result<ExitCode> {
+    let mut poll = Poll::new()?;
+
+    let c = Connection::open_with(&ConnectionOptions::new().nonblocking(true))?;
+
+    let timer = TimerFd::new()?;
+    timer.set_nonblocking(true)?;
+    timer.set_timeout(TIMEOUT)?;
+
+    let mut properties = Properties::new();
+    properties.insert(prop::APPLICATION_NAME, "livemix-verify");
+
+    let mut stream = Stream::new(c, properties)?;
+
+    let timer_token = stream.token()?;
+    poll.add(timer.as_raw_fd(), timer_token, Interest::READ)?;
+
+    let mut events = ArrayVec::<PollEvent, 4>::new();
+    let mut recv = RecvBuf::new();
+
+    let mut app = Verify {
+        tolerance: options.tolerance,
+        formats: HashMap::new(),
+        output: None,
+        input: None,
+        vector: None,
+        played: None,
+        captured: Vec::new(),
+    };
+
+    loop {
+        while let Some(ev) = stream.run(&mut poll, &mut recv)? {
+            match ev {
+                StreamEvent::Started => {
+                    let mut properties = Properties::new();
+                    properties.insert(prop::NODE_NAME, "livemix-verify-playback");
+                    properties.insert(prop::MEDIA_CLASS, "Stream/Output/Audio");
+                    properties.insert(prop::MEDIA_TYPE, "Audio");
+                    properties.insert(prop::TARGET_OBJECT, &options.sink);
+                    properties.insert("node.autoconnect", "true");
+                    stream.create_object("client-node", &properties)?;
+                }
+                StreamEvent::ObjectCreated(ObjectKind::Node(node_id)) => {
+                    let node = stream.node_mut(node_id)?;
+
+                    node.params.set_writable(id::Param::ENUM_FORMAT);
+                    node.params.set_writable(id::Param::FORMAT);
+
+                    // NB: The capture node is created once the playback node
+                    // has been created, which tells them apart.
+                    let direction = if app.output.is_none() {
+                        app.output = Some(node_id);
+                        Direction::OUTPUT
+                    } else {
+                        app.input = Some(node_id);
+                        Direction::INPUT
+                    };
+
+                    let port = node.ports.insert(direction)?;
+                    port.props.insert(prop::PORT_NAME, "mono");
+                    port.props
+                        .insert(prop::FORMAT_DSP, "32 bit float mono audio");
+                    add_port_params(port)?;
+
+                    stream.client_node_set_active(node_id, true)?;
+
+                    if direction == Direction::OUTPUT {
+                        let mut properties = Properties::new();
+                        properties.insert(prop::NODE_NAME, "livemix-verify-capture");
+                        properties.insert(prop::MEDIA_CLASS, "Stream/Input/Audio");
+                        properties.insert(prop::MEDIA_TYPE, "Audio");
+                        properties.insert(prop::TARGET_OBJECT, &options.sink);
+                        properties.insert("node.autoconnect", "true");
+                        properties.insert("stream.capture.sink", "true");
+                        stream.create_object("client-node", &properties)?;
+                    }
+                }
+                StreamEvent::Process(node_id) => {
+                    let node = stream.node_mut(node_id)?;
+
+                    if app.output == Some(node_id) {
+                        app.process_output(node).context("Processing playback")?;
+                    } else if app.input == Some(node_id) {
+                        app.process_input(node).context("Processing capture")?;
+                    }
+
+                    if app.is_complete() {
+                        return Ok(report(&app));
+                    }
+                }
+                StreamEvent::SetPortParam(SetPortParamEvent {
+                    node_id,
+                    direction,
+                    port_id,
+                    param: id::Param::FORMAT,
+                    format: Some(_),
+                    ..
+                }) => {
+                    let node = stream.node(node_id)?;
+                    let port = node.ports.get(direction, port_id)?;
+
+                    if let [param] = port.params.get(id::Param::FORMAT) {
+                        let format = param.value.as_ref().read::<object::AudioFormat>()?;
+                        app.formats.insert((direction, port_id), format);
+                    }
+                }
+                StreamEvent::RemovePortParam(RemovePortParamEvent {
+                    direction,
+                    port_id,
+                    param: id::Param::FORMAT,
+                    ..
+                }) => {
+                    app.formats.remove(&(direction, port_id));
+                }
+                StreamEvent::Disconnected => {
+                    bail!("Disconnected from the server");
+                }
+                _ => {}
+            }
+        }
+
+        poll.poll(&mut events)?;
+
+        while let Some(e) = events.pop() {
+            if e.token == timer_token {
+                bail!(
+                    "Timed out after capturing {} samples, is `{}` a sink which passes audio through?",
+                    app.captured.len(),
+                    options.sink
+                );
+            }
+
+            if e.interest.is_error() || e.interest.is_hup() {
+                bail!("File descriptor with token {:?} errored", e.token);
+            }
+
+            stream.drive(&mut recv, e)?;
+        }
+    }
+}
+
+/// Compare the capture and print the report.
+fn report(app: &Verify) -> ExitCode {
+    let vector = app.vector.as_deref().unwrap_or_default();
+
+    let report = match compare(vector, &app.captured, app.tolerance) {
+        Ok(report) => report,
+        Err(error) => {
+            println!("FAILED: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("latency: {} samples", report.latency);
+    println!(
+        "max error: {} at sample {}",
+        report.max_error, report.max_error_at
+    );
+    println!(
+        "samples over tolerance {}: {} of {}",
+        app.tolerance,
+        report.failed,
+        vector.len()
+    );
+
+    if report.failed > 0 {
+        println!("FAILED");
+        return ExitCode::FAILURE;
+    }
+
+    println!("OK");
+    ExitCode::SUCCESS
+}
+
+fn add_port_params(port: &mut Port) -> Result<()> {
+    let mut pod = pod::array();
+
+    port.params.push(
+        pod.clear_mut().embed(
+            param::EnumFormatBuilder::audio_dsp()
+                .format_any(&[id::AudioFormat::F32P])
+                .channels(1)
+                .rate_range(44100, 48000, DEFAULT_RATE),
+        )?,
+    )?;
+
+    port.params.push(pod.clear_mut().embed(param::Meta {
+        ty: id::Meta::HEADER,
+        size: mem::size_of::<ffi::MetaHeader>(),
+    })?)?;
+
+    port.params.push(pod.clear_mut().embed(param::Io {
+        ty: id::IoType::BUFFERS,
+        size: mem::size_of::<ffi::IoBuffers>(),
+    })?)?;
+
+    port.params.push(pod.clear_mut().embed(param::Io {
+        ty: id::IoType::CLOCK,
+        size: mem::size_of::<ffi::IoClock>(),
+    })?)?;
+
+    port.params.push(pod.clear_mut().embed(param::Io {
+        ty: id::IoType::POSITION,
+        size: mem::size_of::<ffi::IoPosition>(),
+    })?)?;
+
+    port.params.push(pod.clear_mut().embed_object(
+        id::ObjectType::PARAM_BUFFERS,
+        id::Param::BUFFERS,
+        |obj| {
+            obj.property(id::ParamBuffers::BUFFERS)
+                .write_range(1i32, 1, 32)?;
+
+            obj.property(id::ParamBuffers::BLOCKS).write(1i32)?;
+
+            obj.property(id::ParamBuffers::SIZE).write_range(
+                32,
+                (BUFFER_SAMPLES * mem::size_of::<f32>() as u32) as i32,
+                i32::MAX,
+            )?;
+
+            obj.property(id::ParamBuffers::STRIDE)
+                .write(mem::size_of::<f32>())?;
+            Ok(())
+        },
+    )?)?;
+
+    port.params.set_writable(id::Param::FORMAT);
+    Ok(())
+}